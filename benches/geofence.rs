@@ -0,0 +1,52 @@
+//! Benchmarks for the geofence the transit tracker runs against every station
+//! on every update.
+//!
+//! Run with `cargo bench --bench geofence`. `haversine` is the distance check
+//! alone, `prefiltered` is [`is_near_station`] which rules out most stations
+//! before computing the distance.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use geoutils::{Distance, Location};
+use rustic_pixel_examples::stations::{is_near_station, STATION_LOCATIONS};
+
+/// Where the person is, next to a station and between stations
+const PERSON_LOCATIONS: [(&str, f64, f64); 2] = [
+    ("at_station", 39.9541, -75.1679),
+    ("between_stations", 40.0500, -75.4000),
+];
+
+fn geofence(c: &mut Criterion) {
+    let radius = Distance::from_meters(200.0);
+    let stations = STATION_LOCATIONS.values().collect::<Vec<_>>();
+
+    for (name, lat, lon) in PERSON_LOCATIONS {
+        let person = Location::new(lat, lon);
+
+        c.bench_function(&format!("geofence/haversine/{name}"), |b| {
+            b.iter(|| {
+                stations
+                    .iter()
+                    .filter(|station| {
+                        black_box(&person)
+                            .is_in_circle(station, radius)
+                            .unwrap_or(false)
+                    })
+                    .count()
+            })
+        });
+
+        c.bench_function(&format!("geofence/prefiltered/{name}"), |b| {
+            b.iter(|| {
+                stations
+                    .iter()
+                    .filter(|station| {
+                        is_near_station(black_box(&person), station, &radius).unwrap_or(false)
+                    })
+                    .count()
+            })
+        });
+    }
+}
+
+criterion_group!(benches, geofence);
+criterion_main!(benches);
@@ -0,0 +1,71 @@
+//! Benchmarks for drawing the arrivals board with a growing number of trains.
+//!
+//! Run with `cargo bench --bench upcoming_arrivals`. The board is drawn onto a
+//! 128x256 [`BufferCanvas`], tall enough for every train to get a row, so the
+//! time grows with the number of rows laid out.
+//!
+//! Record a baseline before changing the board with
+//! `cargo bench --bench upcoming_arrivals -- --save-baseline before` and compare
+//! against it with `--baseline before`.
+
+use chrono::{Duration, Local};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use embedded_graphics::prelude::Size;
+use rustic_pixel_display::render::{BufferCanvas, Render};
+use rustic_pixel_examples::renders::upcoming_arrivals::{
+    UpcomingArrivals, UpcomingArrivalsConfig, UpcomingTrain, UpcomingTrainStatus,
+};
+use serde_json::json;
+
+const CANVAS_SIZE: Size = Size::new(128, 256);
+
+/// Numbers of trains on the board
+const TRAIN_COUNTS: [usize; 4] = [1, 4, 16, 32];
+
+/// A board showing `count` trains, a few minutes apart
+fn board(count: usize) -> UpcomingArrivals {
+    let config: UpcomingArrivalsConfig = serde_json::from_value(json!({
+        "amtrak_station": "PHL",
+        "results": count,
+    }))
+    .unwrap();
+
+    let now = Local::now().fixed_offset();
+    let trains = (0..count)
+        .map(|index| {
+            let status = match index % 3 {
+                0 => UpcomingTrainStatus::OnTime,
+                1 => UpcomingTrainStatus::Late(index as u32),
+                _ => UpcomingTrainStatus::Unknown,
+            };
+
+            UpcomingTrain::new(
+                format!("{}", 600 + index),
+                "Harrisburg",
+                now + Duration::minutes(5 * index as i64),
+                status,
+            )
+            .with_track(format!("{}", index % 6))
+        })
+        .collect();
+
+    UpcomingArrivals::with_arrivals(config, Vec::new(), trains).unwrap()
+}
+
+fn upcoming_arrivals_render(c: &mut Criterion) {
+    let mut group = c.benchmark_group("upcoming_arrivals_render");
+    let mut canvas = BufferCanvas::new(CANVAS_SIZE);
+
+    for count in TRAIN_COUNTS {
+        let board = board(count);
+
+        group.bench_with_input(BenchmarkId::from_parameter(count), &board, |b, board| {
+            b.iter(|| board.render(black_box(&mut canvas)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, upcoming_arrivals_render);
+criterion_main!(benches);
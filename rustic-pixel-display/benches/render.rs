@@ -0,0 +1,269 @@
+//! Benchmarks for the layout and canvas hot paths.
+//!
+//! Run with `cargo bench`. Every benchmark draws onto a 128x64 [`BufferCanvas`],
+//! the size of two chained 64x64 panels.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, Point, RgbColor, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+use rustic_pixel_display::{
+    layout_manager::{CommonLayout, LayoutManager, StatusBarPosition},
+    render::{BufferCanvas, Render, SubCanvas, SyncRender},
+};
+use std::convert::Infallible;
+
+const CANVAS_SIZE: Size = Size::new(128, 64);
+
+/// Sets every pixel of the canvas one at a time, the worst case for the
+/// canvases since nothing can be batched.
+struct PixelRender;
+
+impl<D> Render<D> for PixelRender
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let size = canvas.bounding_box().size;
+
+        canvas.draw_iter(
+            (0..size.height as i32)
+                .flat_map(|y| (0..size.width as i32).map(move |x| Point::new(x, y)))
+                .map(|point| Pixel(point, Rgb888::GREEN)),
+        )
+    }
+}
+
+fn pixel_render() -> Option<Box<dyn for<'a> SyncRender<SubCanvas<'a, BufferCanvas>>>> {
+    Some(Box::new(PixelRender))
+}
+
+fn layouts() -> Vec<(&'static str, CommonLayout<BufferCanvas>)> {
+    vec![
+        ("single", CommonLayout::Single(pixel_render())),
+        (
+            "split_width",
+            CommonLayout::SplitWidth {
+                left: pixel_render(),
+                right: pixel_render(),
+            },
+        ),
+        (
+            "split_height",
+            CommonLayout::SplitHeight {
+                top: pixel_render(),
+                bottom: pixel_render(),
+            },
+        ),
+        (
+            "split_4",
+            CommonLayout::Split4 {
+                top_left: pixel_render(),
+                top_right: pixel_render(),
+                bottom_left: pixel_render(),
+                bottom_right: pixel_render(),
+            },
+        ),
+        (
+            "with_status_bar",
+            CommonLayout::WithStatusBar {
+                bar_height: 10,
+                position: StatusBarPosition::Top,
+                bar: pixel_render(),
+                body: pixel_render(),
+            },
+        ),
+    ]
+}
+
+fn layout_manager_render(c: &mut Criterion) {
+    let mut group = c.benchmark_group("layout_manager_render");
+
+    for (name, common_layout) in layouts() {
+        let layout_manager = LayoutManager::from_common_layout(common_layout, CANVAS_SIZE);
+        let mut canvas = BufferCanvas::new(CANVAS_SIZE);
+
+        group.bench_function(name, |b| {
+            b.iter(|| layout_manager.render(black_box(&mut canvas)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn sub_canvas_forwarding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sub_canvas_forwarding");
+    let mut canvas = BufferCanvas::new(CANVAS_SIZE);
+
+    group.bench_function("parent", |b| {
+        b.iter(|| PixelRender.render(black_box(&mut canvas)).unwrap())
+    });
+
+    group.bench_function("full_sub_canvas", |b| {
+        b.iter(|| {
+            let mut sub_canvas = SubCanvas::new(Point::zero(), canvas.size(), &mut canvas);
+            PixelRender.render(black_box(&mut sub_canvas)).unwrap()
+        })
+    });
+
+    group.bench_function("offset_sub_canvas", |b| {
+        b.iter(|| {
+            let mut sub_canvas = SubCanvas::new(Point::new(64, 0), Size::new(64, 64), &mut canvas);
+            PixelRender.render(black_box(&mut sub_canvas)).unwrap()
+        })
+    });
+
+    group.bench_function("sub_canvas_clear", |b| {
+        b.iter(|| {
+            let mut sub_canvas = SubCanvas::new(Point::zero(), canvas.size(), &mut canvas);
+            sub_canvas.clear(black_box(Rgb888::BLUE)).unwrap()
+        })
+    });
+
+    group.bench_function("sub_canvas_fill_solid", |b| {
+        b.iter(|| {
+            let mut sub_canvas = SubCanvas::new(Point::zero(), canvas.size(), &mut canvas);
+            sub_canvas
+                .fill_solid(
+                    &Rectangle::new(Point::new(8, 8), Size::new(32, 32)),
+                    black_box(Rgb888::RED),
+                )
+                .unwrap()
+        })
+    });
+
+    group.bench_function("sub_canvas_fill_solid_full", |b| {
+        b.iter(|| {
+            let mut sub_canvas = SubCanvas::new(Point::new(64, 0), Size::new(64, 64), &mut canvas);
+            sub_canvas
+                .fill_solid(
+                    &Rectangle::new(Point::zero(), Size::new(64, 64)),
+                    black_box(Rgb888::RED),
+                )
+                .unwrap()
+        })
+    });
+
+    group.bench_function("sub_canvas_fill_contiguous", |b| {
+        b.iter(|| {
+            let mut sub_canvas = SubCanvas::new(Point::new(64, 0), Size::new(64, 64), &mut canvas);
+            sub_canvas
+                .fill_contiguous(
+                    &Rectangle::new(Point::zero(), Size::new(64, 64)),
+                    std::iter::repeat(black_box(Rgb888::GREEN)),
+                )
+                .unwrap()
+        })
+    });
+
+    group.bench_function("sub_canvas_fill_contiguous_clipped", |b| {
+        b.iter(|| {
+            let mut sub_canvas = SubCanvas::new(Point::new(64, 0), Size::new(64, 64), &mut canvas);
+            sub_canvas
+                .fill_contiguous(
+                    &Rectangle::new(Point::new(-16, -16), Size::new(96, 96)),
+                    std::iter::repeat(black_box(Rgb888::GREEN)),
+                )
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+/// A sub canvas spanning its whole parent forwards draws and clears untouched,
+/// so each pair of benchmarks here should be on par.
+fn full_canvas_fast_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("full_canvas_fast_path");
+    let mut canvas = BufferCanvas::new(CANVAS_SIZE);
+
+    group.bench_function("draw/parent", |b| {
+        b.iter(|| PixelRender.render(black_box(&mut canvas)).unwrap())
+    });
+
+    group.bench_function("draw/sub_canvas", |b| {
+        b.iter(|| {
+            let mut sub_canvas = SubCanvas::new(Point::zero(), CANVAS_SIZE, &mut canvas);
+            PixelRender.render(black_box(&mut sub_canvas)).unwrap()
+        })
+    });
+
+    group.bench_function("clear/parent", |b| {
+        b.iter(|| canvas.clear(black_box(Rgb888::BLUE)).unwrap())
+    });
+
+    group.bench_function("clear/sub_canvas", |b| {
+        b.iter(|| {
+            let mut sub_canvas = SubCanvas::new(Point::zero(), CANVAS_SIZE, &mut canvas);
+            sub_canvas.clear(black_box(Rgb888::BLUE)).unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+/// Fills a small region in the corner of the canvas, such as a clock ticking
+/// over, and reports either that region or the whole canvas as dirty.
+struct CornerRender {
+    dirty_region: Option<Rectangle>,
+}
+
+/// The part of the canvas [`CornerRender`] draws
+const CORNER: Rectangle = Rectangle::new(Point::new(96, 0), Size::new(32, 8));
+
+impl<D> Render<D> for CornerRender
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        canvas.fill_solid(&CORNER, Rgb888::WHITE)
+    }
+
+    fn dirty_region(&self) -> Option<Rectangle> {
+        self.dirty_region
+    }
+}
+
+/// Draws a frame the way the driver does, clearing only the dirty region
+/// reported by `render` before drawing it.
+fn draw_frame(canvas: &mut BufferCanvas, render: &CornerRender) {
+    match render.dirty_region() {
+        Some(region) => canvas.fill_solid(&region, Rgb888::BLACK).unwrap(),
+        None => canvas.clear(Rgb888::BLACK).unwrap(),
+    }
+
+    render.render(canvas).unwrap();
+}
+
+/// A render reporting the small region it changed against the same render
+/// reporting that all of the canvas changed.
+fn dirty_region_redraw(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dirty_region_redraw");
+    let mut canvas = BufferCanvas::new(CANVAS_SIZE);
+
+    let dirty = CornerRender {
+        dirty_region: Some(CORNER),
+    };
+    group.bench_function("dirty_region", |b| {
+        b.iter(|| draw_frame(black_box(&mut canvas), &dirty))
+    });
+
+    let full = CornerRender { dirty_region: None };
+    group.bench_function("full_redraw", |b| {
+        b.iter(|| draw_frame(black_box(&mut canvas), &full))
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    layout_manager_render,
+    sub_canvas_forwarding,
+    full_canvas_fast_path,
+    dirty_region_redraw
+);
+criterion_main!(benches);
@@ -12,11 +12,22 @@ pub fn derive_configurable(input: TokenStream) -> TokenStream {
 
     let name = &ast.ident;
 
-    let (name_variants, description_variants, load_variants, factory_defaults) = match &ast.data {
+    let (
+        name_variants,
+        description_variants,
+        colors_variants,
+        example_variants,
+        load_variants,
+        preview_variants,
+        factory_defaults,
+    ) = match &ast.data {
         Data::Enum(enum_data) => {
             let mut enum_name = Vec::new();
             let mut enum_description = Vec::new();
+            let mut enum_supported_colors = Vec::new();
+            let mut enum_example_config = Vec::new();
             let mut enum_load_from_config = Vec::new();
+            let mut enum_load_preview = Vec::new();
             let mut enum_factory_default = Vec::new();
 
             enum_data.variants.iter().for_each(|variant| {
@@ -47,19 +58,40 @@ pub fn derive_configurable(input: TokenStream) -> TokenStream {
                             }
                         };
 
+                        let render_supported_colors = quote! {
+                            Self::#variant_name(__self) => {
+                                __self.supported_colors()
+                            }
+                        };
+
+                        let render_example_config = quote! {
+                            Self::#variant_name(__self) => {
+                                __self.example_config()
+                            }
+                        };
+
                         let render_load_from_config = quote! {
                             Self::#variant_name(__self) => {
                                 __self.load_from_config(reader)
                             }
                         };
 
+                        let render_load_preview = quote! {
+                            Self::#variant_name(__self) => {
+                                __self.load_preview(reader)
+                            }
+                        };
+
                         let render_factory_default = quote! {
                             Self::#variant_name(#factory_type::default())
                         };
 
                         enum_name.push(render_name);
                         enum_description.push(render_description);
+                        enum_supported_colors.push(render_supported_colors);
+                        enum_example_config.push(render_example_config);
                         enum_load_from_config.push(render_load_from_config);
+                        enum_load_preview.push(render_load_preview);
                         enum_factory_default.push(render_factory_default);
                     }
                     Fields::Named(_) | Fields::Unit => {
@@ -71,7 +103,10 @@ pub fn derive_configurable(input: TokenStream) -> TokenStream {
             (
                 enum_name,
                 enum_description,
+                enum_supported_colors,
+                enum_example_config,
                 enum_load_from_config,
+                enum_load_preview,
                 enum_factory_default,
             )
         }
@@ -117,18 +152,42 @@ pub fn derive_configurable(input: TokenStream) -> TokenStream {
                 }
             }
 
-            fn load_from_config<R: std::io::Read>(&self, reader: R) -> anyhow::Result<Box<dyn Render<D>>> {
+            fn supported_colors(&self) -> &'static [rustic_pixel_display::render::ColorFormat] {
+                match self {
+                    #(#colors_variants)*
+                }
+            }
+
+            fn example_config(&self) -> Option<rustic_pixel_display::render::ExampleConfig> {
+                match self {
+                    #(#example_variants)*
+                }
+            }
+
+            fn load_from_config<R: std::io::Read>(&self, reader: R) -> std::result::Result<Box<dyn rustic_pixel_display::render::SyncRender<D>>, rustic_pixel_display::render::RenderError> {
                 match self {
                     #(#load_variants)*
                 }
             }
+
+            fn load_preview<R: std::io::Read>(&self, reader: R) -> std::result::Result<Box<dyn rustic_pixel_display::render::SyncRender<rustic_pixel_display::render::BufferCanvas>>, rustic_pixel_display::render::RenderError> {
+                match self {
+                    #(#preview_variants)*
+                }
+            }
         }
 
-        impl #impl_generics #name #type_generics #where_clause {
+        impl #impl_generics rustic_pixel_display::render::RenderFactories<#draw_target_ident> for #name #type_generics #where_clause {
             fn factories() -> Vec<Self> {
                 vec![#(#factory_defaults,)*]
             }
         }
+
+        impl #impl_generics #name #type_generics #where_clause {
+            fn factories() -> Vec<Self> {
+                <Self as rustic_pixel_display::render::RenderFactories<#draw_target_ident>>::factories()
+            }
+        }
     };
 
     TokenStream::from(expanded)
@@ -0,0 +1,83 @@
+use chrono::{DateTime, FixedOffset, Local};
+use parking_lot::Mutex;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Where renders that show or depend on the time read it from.
+///
+/// Renders read the [`SystemClock`] unless they are given another one, i.e. a
+/// [`FixedClock`] so tests can draw a clock or countdown at a known time and
+/// compare the frame against a golden buffer.
+pub trait Clock: Send + Sync {
+    /// The local wall-clock time, with the offset of the local timezone
+    fn now(&self) -> DateTime<FixedOffset>;
+
+    /// The monotonic instant, for elapsed times and animations
+    fn instant(&self) -> Instant;
+}
+
+/// A clock shared between the renders that read it
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The clock of the system, the time renders read by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// The system clock as a [`SharedClock`]
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<FixedOffset> {
+        Local::now().fixed_offset()
+    }
+
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that stands still at the time it is set to, for deterministic
+/// tests.
+///
+/// Both the wall-clock time and the instant only move when the clock is
+/// [`advance`](FixedClock::advance)d, and they move together.
+#[derive(Debug)]
+pub struct FixedClock {
+    time: Mutex<(DateTime<FixedOffset>, Instant)>,
+}
+
+impl FixedClock {
+    pub fn new(now: DateTime<FixedOffset>) -> Self {
+        Self {
+            time: Mutex::new((now, Instant::now())),
+        }
+    }
+
+    /// Moves the clock forward by `duration`
+    pub fn advance(&self, duration: Duration) {
+        let mut time = self.time.lock();
+        time.0 = time.0 + chrono::Duration::from_std(duration).expect("Advanced by too much");
+        time.1 += duration;
+    }
+
+    /// Sets the wall-clock time, the instant keeps counting from where it is
+    pub fn set(&self, now: DateTime<FixedOffset>) {
+        self.time.lock().0 = now;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<FixedOffset> {
+        self.time.lock().0
+    }
+
+    fn instant(&self) -> Instant {
+        self.time.lock().1
+    }
+}
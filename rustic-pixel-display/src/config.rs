@@ -1,3 +1,6 @@
+use crate::{driver::MAX_FPS_LIMIT, render::PanelMap};
+use anyhow::{anyhow, Result};
+use embedded_graphics::prelude::Size;
 use serde::{Deserialize, Serialize};
 use strum_macros::{AsRefStr, EnumString};
 
@@ -71,6 +74,35 @@ pub enum LedSequence {
     Bgr,
 }
 
+/// Which LED matrix library drives the panel when the driver is selected at
+/// runtime
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, EnumString, AsRefStr)]
+#[strum(ascii_case_insensitive)]
+pub enum DriverBackend {
+    /// The C++ rpi-rgb-led-matrix library
+    Cpp,
+    /// The pure Rust rpi_led_panel library
+    #[default]
+    Rust,
+}
+
+/// A physical panel of a chain of panels of different sizes, see
+/// [`PanelMap`] for how they are addressed.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct PhysicalPanel {
+    /// Left edge of the panel on the logical canvas
+    pub x: i32,
+
+    /// Top edge of the panel on the logical canvas
+    pub y: i32,
+
+    /// Width of the panel in pixels
+    pub w: u32,
+
+    /// Height of the panel in pixels
+    pub h: u32,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct HardwareConfig {
     pub hardware_mapping: HardwareMapping,
@@ -89,4 +121,135 @@ pub struct HardwareConfig {
     pub multiplexing: Option<MultiplexMapperType>,
     pub row_setter: RowAddressSetterType,
     pub led_sequence: LedSequence,
+
+    /// Path to the spidev device of panels connected over SPI, only used by the
+    /// SPI driver
+    #[serde(default)]
+    pub spi_bus: Option<String>,
+
+    /// Clock speed of the SPI bus, only used by the SPI driver
+    #[serde(default)]
+    pub spi_speed_hz: Option<u32>,
+
+    /// Directory frames are written to, only used by the file driver
+    #[serde(default)]
+    pub file_output_dir: Option<String>,
+
+    /// Minimum time between frames written by the file driver
+    #[serde(default)]
+    pub file_write_interval_ms: Option<u64>,
+
+    /// Address of the display agent frames are streamed to, only used by the
+    /// TCP driver
+    #[serde(default)]
+    pub tcp_address: Option<String>,
+
+    /// Caps how many frames are rendered per second, renders as fast as the
+    /// panel accepts frames if not set
+    #[serde(default)]
+    pub max_fps: Option<u32>,
+
+    /// Library used by the backend selectable driver
+    #[serde(default)]
+    pub driver_backend: DriverBackend,
+
+    /// Pixel mappers applied to the panels for non trivial arrangements, i.e.
+    /// "U-mapper;Rotate:90". Only supported by the C++ driver
+    #[serde(default)]
+    pub pixel_mapper: Option<String>,
+
+    /// Dithers frames down to this many bits per color channel before they are
+    /// displayed, hiding banding in gradients on panels run with few PWM bits.
+    /// Only supported by the backend selectable driver
+    #[serde(default)]
+    pub software_dither: Option<u8>,
+
+    /// How many screen pixels wide every panel pixel is drawn, only used by the
+    /// simulator driver
+    #[serde(default)]
+    pub simulator_scale: Option<u32>,
+
+    /// The panels of a chain of panels of different sizes, in the order they
+    /// are chained, composed into one logical canvas. The chain is treated as
+    /// uniform panels of `rows` by `cols` if empty. Only supported by the
+    /// backend selectable driver
+    #[serde(default)]
+    pub panels: Vec<PhysicalPanel>,
+}
+
+impl HardwareConfig {
+    /// The size of the canvas the driver addresses the chains of panels as
+    pub fn chain_size(&self) -> Size {
+        Size::new(
+            (self.cols * self.chain_length) as u32,
+            (self.rows * self.parallel) as u32,
+        )
+    }
+
+    /// Checks the settings that can be told to be wrong without a panel, so a
+    /// bad configuration is reported before the driver starts.
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in [
+            ("rows", self.rows),
+            ("cols", self.cols),
+            ("chain_length", self.chain_length),
+            ("parallel", self.parallel),
+        ] {
+            if value == 0 {
+                return Err(anyhow!("{name} must be at least 1"));
+            }
+        }
+
+        if let Some(max_fps) = self.max_fps {
+            if !(1..=MAX_FPS_LIMIT).contains(&max_fps) {
+                return Err(anyhow!(
+                    "max_fps of {max_fps} must be between 1 and {MAX_FPS_LIMIT}"
+                ));
+            }
+        }
+
+        if let Some(bits) = self.software_dither {
+            if !(1..=8).contains(&bits) {
+                return Err(anyhow!("software_dither of {bits} must be between 1 and 8"));
+            }
+        }
+
+        if let Some(pixel_mapper) = &self.pixel_mapper {
+            validate_pixel_mapper(pixel_mapper)?;
+        }
+
+        if self.simulator_scale == Some(0) {
+            return Err(anyhow!("simulator_scale must be at least 1"));
+        }
+
+        if !self.panels.is_empty() {
+            PanelMap::new(&self.panels, self.chain_size())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that every mapper of a `;` separated pixel mapper config is one the
+/// C++ driver knows, with the parameters it expects.
+pub fn validate_pixel_mapper(pixel_mapper: &str) -> Result<()> {
+    for mapper in pixel_mapper.split(';').map(str::trim) {
+        let (name, parameter) = match mapper.split_once(':') {
+            Some((name, parameter)) => (name, Some(parameter)),
+            None => (mapper, None),
+        };
+
+        match (name, parameter) {
+            ("U-mapper", None) | ("V-mapper", None) | ("V-mapper", Some("Z")) => {}
+            ("Rotate", Some(angle)) => match angle.parse::<i32>() {
+                Ok(angle) if angle % 90 == 0 => {}
+                _ => return Err(anyhow!("Rotate angle \"{angle}\" must be a multiple of 90")),
+            },
+            ("Mirror", Some("H")) | ("Mirror", Some("V")) => {}
+            ("Remap", Some(_)) => {}
+            _ => return Err(anyhow!("Unknown pixel mapper \"{mapper}\"")),
+        }
+    }
+
+    Ok(())
 }
@@ -0,0 +1,188 @@
+use super::{cpp_driver::CombinedConfig, CppHardwareDriver, HardwareDriver, RustHardwareDriver};
+use crate::{
+    config::{DriverBackend, HardwareConfig},
+    render::{BayerMatrix, BufferCanvas, DitherCanvas, PanelMap},
+};
+use anyhow::Result;
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Size},
+};
+use log::error;
+use rpi_led_matrix::LedCanvas;
+use rpi_led_panel::{Canvas, RGBMatrixConfig};
+use std::convert::Infallible;
+
+/// Drives the panel with either the C++ or the Rust library, picked by
+/// [`HardwareConfig::driver_backend`] when the driver is created.
+///
+/// The two libraries use different canvas types, so renders draw onto a
+/// [`BufferCanvas`] that is copied onto the canvas of the backend each time it
+/// is displayed. This costs one extra copy per frame compared to using
+/// [`CppHardwareDriver`] or [`RustHardwareDriver`] directly.
+///
+/// The same copy composes chains of panels of different sizes, configured with
+/// [`HardwareConfig::panels`], into one logical canvas, see [`PanelMap`].
+pub struct BackendHardwareDriver {
+    backend: Backend,
+    size: Size,
+
+    /// Bits per channel frames are dithered down to while being copied
+    software_dither: Option<u8>,
+
+    /// Where the pixels of the logical canvas are on the chain, if it is made
+    /// up of panels of different sizes
+    panel_map: Option<PanelMap>,
+}
+
+enum Backend {
+    Cpp {
+        driver: CppHardwareDriver,
+        canvas: Option<Box<LedCanvas>>,
+    },
+    Rust {
+        driver: RustHardwareDriver,
+        canvas: Option<Box<Canvas>>,
+    },
+}
+
+pub enum BackendConfig {
+    Cpp(CombinedConfig),
+    Rust(RGBMatrixConfig),
+}
+
+pub struct BackendHardwareConfig {
+    pub backend: BackendConfig,
+    pub size: Size,
+    pub software_dither: Option<u8>,
+    pub panel_map: Option<PanelMap>,
+}
+
+impl HardwareDriver for BackendHardwareDriver {
+    type Config = BackendHardwareConfig;
+    type Canvas = BufferCanvas;
+
+    fn new(config: Self::Config) -> Result<Self> {
+        let backend = match config.backend {
+            BackendConfig::Cpp(config) => {
+                let mut driver = CppHardwareDriver::new(config)?;
+                let canvas = Some(driver.create_canvas());
+                Backend::Cpp { driver, canvas }
+            }
+            BackendConfig::Rust(config) => {
+                let mut driver = RustHardwareDriver::new(config)?;
+                let canvas = Some(driver.create_canvas());
+                Backend::Rust { driver, canvas }
+            }
+        };
+
+        Ok(Self {
+            backend,
+            size: config.size,
+            software_dither: config.software_dither,
+            panel_map: config.panel_map,
+        })
+    }
+
+    fn create_canvas(&mut self) -> Box<Self::Canvas> {
+        Box::new(BufferCanvas::new(self.size))
+    }
+
+    fn display_canvas(&mut self, canvas: Box<Self::Canvas>) -> Box<Self::Canvas> {
+        let panel_map = self.panel_map.as_ref();
+
+        match &mut self.backend {
+            Backend::Cpp {
+                driver,
+                canvas: backend_canvas,
+            } => {
+                let Some(mut next) = backend_canvas.take() else {
+                    error!("No canvas to display the frame on, dropping the frame");
+                    return canvas;
+                };
+                copy_frame(&canvas, next.as_mut(), self.software_dither, panel_map);
+                *backend_canvas = Some(driver.display_canvas(next));
+            }
+            Backend::Rust {
+                driver,
+                canvas: backend_canvas,
+            } => {
+                let Some(mut next) = backend_canvas.take() else {
+                    error!("No canvas to display the frame on, dropping the frame");
+                    return canvas;
+                };
+                copy_frame(&canvas, next.as_mut(), self.software_dither, panel_map);
+                *backend_canvas = Some(driver.display_canvas(next));
+            }
+        }
+
+        // The frame has been copied out, so the same canvas can be drawn on again
+        canvas
+    }
+}
+
+/// Copies `frame` onto the canvas of the backend, dithering it down to
+/// `software_dither` bits per channel if set, and routing it to the panels of
+/// `panel_map` if set.
+fn copy_frame<D>(
+    frame: &BufferCanvas,
+    target: &mut D,
+    software_dither: Option<u8>,
+    panel_map: Option<&PanelMap>,
+) where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    match panel_map {
+        Some(panel_map) => copy_dithered(frame, &mut panel_map.canvas(target), software_dither),
+        None => copy_dithered(frame, target, software_dither),
+    }
+}
+
+fn copy_dithered<D>(frame: &BufferCanvas, target: &mut D, software_dither: Option<u8>)
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    match software_dither {
+        Some(bits) => frame.draw_to(
+            &mut DitherCanvas::new(target, bits, BayerMatrix::default()),
+            Point::zero(),
+        ),
+        None => frame.draw_to(target, Point::zero()),
+    }
+    .unwrap_or_else(|e| match e {});
+}
+
+impl TryFrom<HardwareConfig> for BackendHardwareConfig {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(config: HardwareConfig) -> Result<Self, Self::Error> {
+        let chain_size = config.chain_size();
+        let panel_map = if config.panels.is_empty() {
+            None
+        } else {
+            Some(PanelMap::new(&config.panels, chain_size)?)
+        };
+        let size = panel_map
+            .as_ref()
+            .map_or(chain_size, |panel_map| panel_map.size());
+
+        if let Some(bits) = config.software_dither {
+            if !(1..=8).contains(&bits) {
+                return Err(format!("software_dither of {bits} must be between 1 and 8").into());
+            }
+        }
+
+        let software_dither = config.software_dither;
+        let backend = match config.driver_backend {
+            DriverBackend::Cpp => BackendConfig::Cpp(config.try_into()?),
+            DriverBackend::Rust => BackendConfig::Rust(config.try_into()?),
+        };
+
+        Ok(Self {
+            backend,
+            size,
+            software_dither,
+            panel_map,
+        })
+    }
+}
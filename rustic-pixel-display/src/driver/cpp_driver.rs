@@ -1,7 +1,7 @@
 use super::HardwareDriver;
 use crate::config::{
-    HardwareConfig, HardwareMapping, LedSequence, MultiplexMapperType, PanelType,
-    RowAddressSetterType,
+    validate_pixel_mapper, HardwareConfig, HardwareMapping, LedSequence, MultiplexMapperType,
+    PanelType, RowAddressSetterType,
 };
 use anyhow::{anyhow, Result};
 use rpi_led_matrix::{LedCanvas, LedMatrix, LedMatrixOptions, LedRuntimeOptions};
@@ -75,7 +75,13 @@ impl TryFrom<HardwareConfig> for CombinedConfig {
             LedSequence::Brg => "BRG",
             LedSequence::Bgr => "BGR",
         });
-        matrix_options.set_pixel_mapper_config("");
+        matrix_options.set_pixel_mapper_config(match value.pixel_mapper.as_deref() {
+            Some(pixel_mapper) => {
+                validate_pixel_mapper(pixel_mapper)?;
+                pixel_mapper
+            }
+            None => "",
+        });
         matrix_options.set_hardware_pulsing(false);
         matrix_options.set_refresh_rate(false);
         matrix_options.set_inverse_colors(false);
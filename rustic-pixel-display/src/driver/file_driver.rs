@@ -0,0 +1,122 @@
+use super::HardwareDriver;
+use crate::{config::HardwareConfig, render::BufferCanvas};
+use anyhow::{Context, Result};
+use embedded_graphics::prelude::{RgbColor, Size};
+use log::error;
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// How often a frame is written if no interval is configured
+const DEFAULT_WRITE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Name of the image that holds the latest frame
+const FRAME_FILE_NAME: &str = "frame.png";
+
+/// Driver that writes frames to disk as a PNG instead of displaying them on a
+/// panel.
+///
+/// Allows the full driver, registry and HTTP stack to be run on a machine
+/// without any LED hardware. The latest frame is always written to the same
+/// file, at most once every write interval, so the driver can be left running
+/// without filling up the disk.
+pub struct FileHardwareDriver {
+    frame_path: PathBuf,
+    temp_path: PathBuf,
+    size: Size,
+    write_interval: Duration,
+    last_write: Option<Instant>,
+}
+
+pub struct FileConfig {
+    /// Directory the frames are written to
+    pub output_dir: PathBuf,
+    pub write_interval: Duration,
+    pub size: Size,
+}
+
+impl FileHardwareDriver {
+    fn write_frame(&self, canvas: &BufferCanvas) -> Result<()> {
+        let file = File::create(&self.temp_path)
+            .with_context(|| format!("Could not create {}", self.temp_path.display()))?;
+
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(file), self.size.width, self.size.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let data = canvas
+            .pixels()
+            .iter()
+            .flat_map(|pixel| [pixel.r(), pixel.g(), pixel.b()])
+            .collect::<Vec<_>>();
+
+        encoder.write_header()?.write_image_data(&data)?;
+
+        // Replace the previous frame in one step so readers never see a partial
+        // image
+        fs::rename(&self.temp_path, &self.frame_path)
+            .with_context(|| format!("Could not replace {}", self.frame_path.display()))?;
+
+        Ok(())
+    }
+}
+
+impl HardwareDriver for FileHardwareDriver {
+    type Config = FileConfig;
+    type Canvas = BufferCanvas;
+
+    fn new(config: Self::Config) -> Result<Self> {
+        fs::create_dir_all(&config.output_dir).with_context(|| {
+            format!("Could not create directory {}", config.output_dir.display())
+        })?;
+
+        Ok(Self {
+            frame_path: config.output_dir.join(FRAME_FILE_NAME),
+            temp_path: config.output_dir.join(format!("{FRAME_FILE_NAME}.tmp")),
+            size: config.size,
+            write_interval: config.write_interval,
+            last_write: None,
+        })
+    }
+
+    fn create_canvas(&mut self) -> Box<Self::Canvas> {
+        Box::new(BufferCanvas::new(self.size))
+    }
+
+    fn display_canvas(&mut self, canvas: Box<Self::Canvas>) -> Box<Self::Canvas> {
+        let written_recently = self
+            .last_write
+            .is_some_and(|last_write| last_write.elapsed() < self.write_interval);
+
+        if !written_recently {
+            self.last_write = Some(Instant::now());
+
+            if let Err(e) = self.write_frame(&canvas) {
+                error!("Could not write frame: {e}");
+            }
+        }
+
+        canvas
+    }
+}
+
+impl TryFrom<HardwareConfig> for FileConfig {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(config: HardwareConfig) -> Result<Self, Self::Error> {
+        Ok(FileConfig {
+            output_dir: config
+                .file_output_dir
+                .ok_or("An output directory must be configured to use the file driver")?
+                .into(),
+            write_interval: config
+                .file_write_interval_ms
+                .map_or(DEFAULT_WRITE_INTERVAL, Duration::from_millis),
+            size: config.chain_size(),
+        })
+    }
+}
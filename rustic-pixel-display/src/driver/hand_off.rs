@@ -0,0 +1,23 @@
+use anyhow::{anyhow, Result};
+use log::error;
+use std::sync::mpsc::{SyncSender, TrySendError};
+
+/// The canvas is passed back and forth between the render and driver threads,
+/// so at most one canvas is ever waiting in either channel.
+pub const CANVASES_IN_FLIGHT: usize = 1;
+
+/// Hands `item` over to the `to` thread.
+///
+/// A full channel means a second canvas was put in flight, which breaks the
+/// ping-pong between the threads. The extra canvas is dropped instead of
+/// blocking so that neither thread stalls.
+pub fn hand_off<T>(sender: &SyncSender<T>, item: T, to: &str) -> Result<()> {
+    match sender.try_send(item) {
+        Ok(()) => Ok(()),
+        Err(TrySendError::Full(_)) => {
+            error!("More than one canvas in flight to the {to} thread, dropping the extra canvas");
+            Ok(())
+        }
+        Err(TrySendError::Disconnected(_)) => Err(anyhow!("The {to} thread has exited")),
+    }
+}
@@ -1,33 +1,71 @@
-use crate::{config::HardwareConfig, render::Render};
+use crate::{
+    config::HardwareConfig,
+    render::{Render, Splash, SyncRender},
+};
 use anyhow::{anyhow, Result};
 use embedded_graphics::{
     pixelcolor::Rgb888,
-    prelude::{DrawTarget, RgbColor},
+    prelude::{DrawTarget, OriginDimensions, RgbColor},
+    primitives::Rectangle,
 };
-use log::{debug, warn};
+use log::{debug, error, warn};
+use parking_lot::Mutex;
 use std::{
+    any::Any,
+    collections::VecDeque,
     convert::Infallible,
+    panic::{self, AssertUnwindSafe},
     sync::{
-        atomic::{AtomicBool, Ordering},
-        mpsc::RecvTimeoutError,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, RecvTimeoutError},
         Arc,
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 #[cfg(feature = "http_server")]
 use crate::{http_server::build_api_server, registry::Registry, render::RenderFactory};
 
+mod backend_driver;
 mod cpp_driver;
+#[cfg(feature = "file_driver")]
+mod file_driver;
+mod hand_off;
 mod rust_driver;
-
-pub use cpp_driver::CppHardwareDriver;
+#[cfg(feature = "simulator")]
+mod simulator_driver;
+#[cfg(feature = "spi")]
+mod spi_driver;
+pub mod tcp_driver;
+
+pub use backend_driver::{BackendConfig, BackendHardwareConfig, BackendHardwareDriver};
+pub use cpp_driver::{CombinedConfig, CppHardwareDriver};
+#[cfg(feature = "file_driver")]
+pub use file_driver::{FileConfig, FileHardwareDriver};
+#[cfg(not(feature = "test-util"))]
+use hand_off::{hand_off, CANVASES_IN_FLIGHT};
+#[cfg(feature = "test-util")]
+pub use hand_off::{hand_off, CANVASES_IN_FLIGHT};
 pub use rust_driver::RustHardwareDriver;
+#[cfg(feature = "simulator")]
+pub use simulator_driver::{SimulatorConfig, SimulatorHardwareDriver, SimulatorWindow};
+#[cfg(feature = "spi")]
+pub use spi_driver::{SpiConfig, SpiHardwareDriver};
+pub use tcp_driver::{TcpConfig, TcpHardwareDriver};
 
 pub trait HardwareDriver: Sized {
     type Config: TryFrom<HardwareConfig>;
-    type Canvas: DrawTarget<Color = Rgb888, Error = Infallible> + Send + Sync + 'static;
+
+    /// The canvas renders draw on, reporting the logical size of the panels
+    /// through [`OriginDimensions`] so renders can lay themselves out without
+    /// being told it. For chains of panels that is `cols * chain_length` by
+    /// `rows * parallel`.
+    type Canvas: DrawTarget<Color = Rgb888, Error = Infallible>
+        + OriginDimensions
+        + Send
+        + Sync
+        + 'static;
 
     fn new(config: Self::Config) -> Result<Self>;
 
@@ -36,6 +74,315 @@ pub trait HardwareDriver: Sized {
     fn display_canvas(&mut self, canvas: Box<Self::Canvas>) -> Box<Self::Canvas>;
 }
 
+/// How long dropping the [`MatrixDriver`] waits for each thread to exit
+const THREAD_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Joins the thread if it exits within `timeout`.
+///
+/// Returns `None` if the thread is still running after `timeout`, in which case
+/// the thread is detached so that a wedged thread can't block shutdown.
+fn join_with_timeout<T>(
+    handle: thread::JoinHandle<T>,
+    timeout: Duration,
+) -> Option<thread::Result<T>> {
+    let deadline = Instant::now() + timeout;
+
+    while !handle.is_finished() {
+        if Instant::now() >= deadline {
+            return None;
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    Some(handle.join())
+}
+
+/// How long the render thread waits before retrying a render that panicked
+const RENDER_PANIC_RETRY_DELAY: Duration = Duration::from_millis(30);
+
+/// Runs `render_fn`, catching any panic so that one bad render doesn't take
+/// down the render thread.
+///
+/// Returns `None` if the render panicked, in which case the canvas may only be
+/// partially drawn and should not be displayed.
+fn catch_render_panic<F, T>(render_fn: F) -> Result<Option<T>>
+where
+    F: FnOnce() -> Result<T, Infallible>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(render_fn)) {
+        Ok(result) => Ok(Some(result?)),
+        Err(payload) => {
+            error!("Render panicked: {}", panic_message(payload.as_ref()));
+            Ok(None)
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+/// The highest frame rate that can be configured with `max_fps`
+pub const MAX_FPS_LIMIT: u32 = 240;
+
+/// The shortest time the driver thread waits for a frame before warning that
+/// the render thread has fallen behind
+const MIN_FRAME_TIMEOUT: Duration = Duration::from_millis(30);
+
+/// Caps how often the render thread draws a frame.
+///
+/// Combined with renders that report an empty dirty region when nothing
+/// changed, this lets the render thread idle instead of drawing as fast as the
+/// panel accepts frames.
+struct FrameLimiter {
+    interval: Option<Duration>,
+    next_frame: Instant,
+}
+
+impl FrameLimiter {
+    fn new(max_fps: Option<u32>) -> Result<Self> {
+        let interval = match max_fps {
+            None => None,
+            Some(max_fps) if (1..=MAX_FPS_LIMIT).contains(&max_fps) => {
+                Some(Duration::from_secs(1) / max_fps)
+            }
+            Some(max_fps) => {
+                return Err(anyhow!(
+                    "max_fps of {max_fps} must be between 1 and {MAX_FPS_LIMIT}"
+                ))
+            }
+        };
+
+        Ok(Self {
+            interval,
+            next_frame: Instant::now(),
+        })
+    }
+
+    /// Sleeps until the next frame is due.
+    fn wait(&mut self) {
+        if let Some(interval) = self.interval {
+            let now = Instant::now();
+
+            if self.next_frame > now {
+                thread::sleep(self.next_frame - now);
+            }
+
+            // Frames that were missed are not caught up on
+            self.next_frame = self.next_frame.max(Instant::now()) + interval;
+        }
+    }
+
+    /// How long a frame may take to render without holding up the next one.
+    fn frame_budget(&self) -> Duration {
+        self.interval.unwrap_or(MIN_FRAME_TIMEOUT)
+    }
+
+    /// How long the driver thread can wait for a frame before the render thread
+    /// is considered to have fallen behind.
+    fn frame_timeout(&self) -> Duration {
+        self.interval.map_or(MIN_FRAME_TIMEOUT, |interval| {
+            (interval * 2).max(MIN_FRAME_TIMEOUT)
+        })
+    }
+}
+
+/// Tracks what has to be redrawn on the canvas handed to the render thread.
+///
+/// The render and driver threads swap between two canvases, so the canvas
+/// being drawn holds the frame from two frames ago. Both the dirty region of
+/// the previous frame and that of the current frame need to be redrawn.
+#[derive(Default)]
+struct FrameDamage {
+    previous: Option<Rectangle>,
+}
+
+impl FrameDamage {
+    /// Returns the region of the canvas that has to be redrawn given the
+    /// `dirty` region reported by the render, `None` meaning all of it.
+    fn redraw_region(&mut self, dirty: Option<Rectangle>) -> Option<Rectangle> {
+        let region = match (dirty, self.previous) {
+            (Some(current), Some(previous)) => Some(bounding_union(current, previous)),
+            _ => None,
+        };
+
+        self.previous = dirty;
+        region
+    }
+
+    /// Forces the next frame to be redrawn in full.
+    fn invalidate(&mut self) {
+        self.previous = None;
+    }
+}
+
+/// Returns the smallest rectangle that contains both `a` and `b`.
+fn bounding_union(a: Rectangle, b: Rectangle) -> Rectangle {
+    match (a.bottom_right(), b.bottom_right()) {
+        (Some(a_bottom_right), Some(b_bottom_right)) => Rectangle::with_corners(
+            a.top_left.component_min(b.top_left),
+            a_bottom_right.component_max(b_bottom_right),
+        ),
+        (Some(_), None) => a,
+        (None, _) => b,
+    }
+}
+
+/// Draws a frame of `render` onto `canvas`, only clearing the region that
+/// changed and skipping the render altogether if nothing did.
+///
+/// Returns `false` if the render was skipped, in which case the canvas is
+/// identical to the frame being displayed.
+fn draw_frame<C, R>(
+    canvas: &mut C,
+    render: &R,
+    damage: &mut FrameDamage,
+) -> Result<bool, Infallible>
+where
+    C: DrawTarget<Color = Rgb888, Error = Infallible>,
+    R: Render<C> + ?Sized,
+{
+    let background_color = render.background_color().unwrap_or(Rgb888::BLACK);
+
+    match damage.redraw_region(render.dirty_region()) {
+        Some(region) if region.is_zero_sized() => return Ok(false),
+        Some(region) => canvas.fill_solid(&region, background_color)?,
+        None => canvas.clear(background_color)?,
+    }
+
+    render.render_at(canvas, Instant::now())?;
+    Ok(true)
+}
+
+/// Runs `render_fn`, returning how long it took, and warns if it took longer
+/// than `budget`.
+///
+/// Built with the `tracing` feature, the frame is rendered in a `render_frame`
+/// span recording the `frame` number and its duration.
+#[cfg(feature = "tracing")]
+fn time_frame<F, T>(frame: u64, budget: Duration, render_fn: F) -> (T, Duration)
+where
+    F: FnOnce() -> T,
+{
+    let span = tracing::info_span!("render_frame", frame, duration_ms = tracing::field::Empty);
+    let _entered = span.enter();
+
+    let start = Instant::now();
+    let result = render_fn();
+    let duration = start.elapsed();
+
+    span.record("duration_ms", duration.as_secs_f64() * 1000.0);
+
+    if duration > budget {
+        tracing::warn!(
+            frame,
+            ?duration,
+            ?budget,
+            "Frame took longer than its budget"
+        );
+    }
+
+    (result, duration)
+}
+
+/// Runs `render_fn`, returning how long it took, and warns if it took longer
+/// than `budget`.
+#[cfg(not(feature = "tracing"))]
+fn time_frame<F, T>(frame: u64, budget: Duration, render_fn: F) -> (T, Duration)
+where
+    F: FnOnce() -> T,
+{
+    let start = Instant::now();
+    let result = render_fn();
+    let duration = start.elapsed();
+
+    if duration > budget {
+        warn!("Frame {frame} took {duration:?}, longer than its budget of {budget:?}");
+    }
+
+    (result, duration)
+}
+
+/// How many of the most recent frames the average render time is taken over
+const RENDER_TIME_WINDOW: usize = 100;
+
+/// How long the most recent frames took to render
+#[derive(Debug, Default)]
+struct RenderTimes {
+    recent: VecDeque<Duration>,
+
+    /// Sum of `recent`
+    total: Duration,
+}
+
+impl RenderTimes {
+    fn record(&mut self, duration: Duration) {
+        self.recent.push_back(duration);
+        self.total += duration;
+
+        if self.recent.len() > RENDER_TIME_WINDOW {
+            if let Some(oldest) = self.recent.pop_front() {
+                self.total -= oldest;
+            }
+        }
+    }
+
+    fn average(&self) -> Option<Duration> {
+        if self.recent.is_empty() {
+            None
+        } else {
+            Some(self.total / self.recent.len() as u32)
+        }
+    }
+}
+
+/// Statistics about the frames of a [`MatrixDriver`], updated by its threads.
+///
+/// Cheap to read, so it can be polled for metrics or health checks.
+#[derive(Debug, Default)]
+pub struct DriverHealth {
+    /// Number of frames that were not displayed because they were identical to
+    /// the frame already being displayed
+    skipped_frames: AtomicU64,
+
+    render_times: Mutex<RenderTimes>,
+
+    /// If the render thread is holding a canvas back to retry a render that
+    /// panicked, in which case the driver thread expects to time out waiting
+    /// for a frame
+    retrying_render: AtomicBool,
+}
+
+impl DriverHealth {
+    /// Returns the number of frames that were not sent to the panel because
+    /// they were identical to the frame already being displayed.
+    pub fn skipped_frames(&self) -> u64 {
+        self.skipped_frames.load(Ordering::Relaxed)
+    }
+
+    /// Returns how long the most recent frames took to render on average,
+    /// `None` before the first frame.
+    pub fn average_render_time(&self) -> Option<Duration> {
+        self.render_times.lock().average()
+    }
+}
+
+/// A canvas sent from the render thread to the driver thread
+struct Frame<C> {
+    canvas: Box<C>,
+
+    /// If the canvas differs from the frame being displayed. Unchanged canvases
+    /// are handed back to the render thread without being displayed.
+    changed: bool,
+}
+
 pub struct MatrixDriver {
     /// Flag used to gracefully terminate the render and driver threads
     alive: Arc<AtomicBool>,
@@ -48,13 +395,25 @@ pub struct MatrixDriver {
 
     /// Handle to the HTTP thread (if any)
     http_thread_handle: Option<thread::JoinHandle<Result<()>>>,
+
+    /// Statistics about the frames, shared with the render and driver threads
+    health: Arc<DriverHealth>,
 }
 
 impl MatrixDriver {
-    pub fn with_single_render<H, R>(render: R, config: HardwareConfig) -> Result<Self>
+    /// Drives the panel with `render`.
+    ///
+    /// `splash` is drawn from the moment the panel is initialized until
+    /// `render` [has content](Render::has_content), the built-in [`Splash`] if
+    /// `None`.
+    pub fn with_single_render<H, R>(
+        render: R,
+        splash: Option<Box<dyn SyncRender<H::Canvas>>>,
+        config: HardwareConfig,
+    ) -> Result<Self>
     where
         H: HardwareDriver,
-        R: Render<H::Canvas> + Sync + Send + 'static,
+        R: SyncRender<H::Canvas> + 'static,
     {
         let alive = Arc::new(AtomicBool::new(true));
 
@@ -62,25 +421,73 @@ impl MatrixDriver {
         let alive_render = alive.clone();
         let alive_driver = alive.clone();
 
+        let health = Arc::new(DriverHealth::default());
+        let render_health = health.clone();
+        let driver_health = health.clone();
+
+        let mut frame_limiter = FrameLimiter::new(config.max_fps)?;
+        let frame_timeout = frame_limiter.frame_timeout();
+        let frame_budget = frame_limiter.frame_budget();
+
         // Channels used to send the canvas between the render and driver threads
         let (driver_to_render_sender, driver_to_render_receiver) =
-            std::sync::mpsc::channel::<Box<H::Canvas>>();
+            mpsc::sync_channel::<Box<H::Canvas>>(CANVASES_IN_FLIGHT);
         let (render_to_driver_sender, render_to_driver_receiver) =
-            std::sync::mpsc::channel::<Box<H::Canvas>>();
+            mpsc::sync_channel::<Frame<H::Canvas>>(CANVASES_IN_FLIGHT);
 
         // Create the render thread
         let render_thread_handle = thread::spawn(move || -> Result<()> {
             debug!("Started render thread");
+
+            // Canvas held back after a render panicked. The driver keeps displaying
+            // the previous frame until the render succeeds with this canvas.
+            let mut retry_canvas = None;
+            let mut damage = FrameDamage::default();
+            let mut frame = 0;
+
+            // Dropped once the render has something to show
+            let mut splash = Some(splash.unwrap_or_else(|| Box::new(Splash)));
+
             while alive_render.load(Ordering::SeqCst) {
-                match driver_to_render_receiver.recv() {
-                    Ok(mut canvas) => {
-                        canvas.clear(Rgb888::BLACK)?;
-                        render.render(canvas.as_mut())?;
-                        render_to_driver_sender.send(canvas)?;
-                    }
-                    Err(_) => {
-                        break;
-                    }
+                let mut canvas = match retry_canvas.take() {
+                    Some(canvas) => canvas,
+                    None => match driver_to_render_receiver.recv() {
+                        Ok(canvas) => canvas,
+                        Err(_) => break,
+                    },
+                };
+
+                frame_limiter.wait();
+                frame += 1;
+
+                if splash.is_some() && render.has_content() {
+                    splash = None;
+                    damage.invalidate();
+                }
+
+                let (changed, render_time) = time_frame(frame, frame_budget, || {
+                    catch_render_panic(|| match &splash {
+                        Some(splash) => draw_frame(canvas.as_mut(), splash.as_ref(), &mut damage),
+                        None => draw_frame(canvas.as_mut(), &render, &mut damage),
+                    })
+                });
+                render_health.render_times.lock().record(render_time);
+                let changed = changed?;
+
+                if let Some(changed) = changed {
+                    render_health
+                        .retrying_render
+                        .store(false, Ordering::Relaxed);
+                    hand_off(
+                        &render_to_driver_sender,
+                        Frame { canvas, changed },
+                        "driver",
+                    )?;
+                } else {
+                    damage.invalidate();
+                    render_health.retrying_render.store(true, Ordering::Relaxed);
+                    retry_canvas = Some(canvas);
+                    thread::sleep(RENDER_PANIC_RETRY_DELAY);
                 }
             }
 
@@ -98,22 +505,27 @@ impl MatrixDriver {
 
             let mut hardware_driver = H::new(hardware_config)?;
             let canvas = hardware_driver.create_canvas();
-            driver_to_render_sender.send(canvas)?;
+            hand_off(&driver_to_render_sender, canvas, "render")?;
 
             while alive_driver.load(Ordering::SeqCst) {
-                //let timeout = Duration::from_millis((1000.0 / framerate as f64) as u64);
-                let timeout = Duration::from_millis(30);
-
-                match render_to_driver_receiver.recv_timeout(timeout) {
-                    Ok(canvas) => {
-                        let canvas_new = hardware_driver.display_canvas(canvas);
-                        driver_to_render_sender.send(canvas_new)?;
+                match render_to_driver_receiver.recv_timeout(frame_timeout) {
+                    Ok(Frame { canvas, changed }) => {
+                        let canvas_new = if changed {
+                            hardware_driver.display_canvas(canvas)
+                        } else {
+                            driver_health.skipped_frames.fetch_add(1, Ordering::Relaxed);
+                            canvas
+                        };
+                        hand_off(&driver_to_render_sender, canvas_new, "render")?;
                     }
                     Err(RecvTimeoutError::Disconnected) => {
                         break;
                     }
                     Err(RecvTimeoutError::Timeout) => {
-                        warn!("Timeout waiting for frame from render");
+                        // The render already logged why it is being retried
+                        if !driver_health.retrying_render.load(Ordering::Relaxed) {
+                            warn!("Timeout waiting for frame from render");
+                        }
                         continue;
                     }
                 }
@@ -127,13 +539,21 @@ impl MatrixDriver {
             render_thread_handle: Some(render_thread_handle),
             driver_thread_handle: Some(driver_thread_handle),
             http_thread_handle: None,
+            health,
         })
     }
 
+    /// Drives the panel with the render selected in `registry`, which is
+    /// managed through an HTTP API served on `http_addr`.
+    ///
+    /// `splash` is drawn from the moment the panel is initialized until the
+    /// selected render [has content](Render::has_content), the built-in
+    /// [`Splash`] if `None`.
     #[cfg(feature = "http_server")]
     pub fn with_register<H, A, F>(
         http_addr: A,
         registry: Arc<parking_lot::Mutex<Registry<F, H::Canvas>>>,
+        splash: Option<Box<dyn SyncRender<H::Canvas>>>,
         config: HardwareConfig,
     ) -> Result<Self>
     where
@@ -152,25 +572,73 @@ impl MatrixDriver {
         let render_registry = registry.clone();
         let http_registry = registry;
 
+        let health = Arc::new(DriverHealth::default());
+        let render_health = health.clone();
+        let driver_health = health.clone();
+
+        let mut frame_limiter = FrameLimiter::new(config.max_fps)?;
+        let frame_timeout = frame_limiter.frame_timeout();
+        let frame_budget = frame_limiter.frame_budget();
+
         // Channels used to send the canvas between the render and driver threads
         let (driver_to_render_sender, driver_to_render_receiver) =
-            std::sync::mpsc::channel::<Box<H::Canvas>>();
+            mpsc::sync_channel::<Box<H::Canvas>>(CANVASES_IN_FLIGHT);
         let (render_to_driver_sender, render_to_driver_receiver) =
-            std::sync::mpsc::channel::<Box<H::Canvas>>();
+            mpsc::sync_channel::<Frame<H::Canvas>>(CANVASES_IN_FLIGHT);
 
         // Create the render thread
         let render_thread_handle = thread::spawn(move || -> Result<()> {
             debug!("Started render thread");
+
+            // Canvas held back after a render panicked. The driver keeps displaying
+            // the previous frame until the render succeeds with this canvas.
+            let mut retry_canvas = None;
+            let mut damage = FrameDamage::default();
+            let mut frame = 0;
+
+            // Dropped once the render has something to show
+            let mut splash = Some(splash.unwrap_or_else(|| Box::new(Splash)));
+
             while alive_render.load(Ordering::SeqCst) {
-                match driver_to_render_receiver.recv() {
-                    Ok(mut canvas) => {
-                        canvas.clear(Rgb888::BLACK)?;
-                        render_registry.lock().render(canvas.as_mut())?;
-                        render_to_driver_sender.send(canvas)?;
-                    }
-                    Err(_) => {
-                        break;
-                    }
+                let mut canvas = match retry_canvas.take() {
+                    Some(canvas) => canvas,
+                    None => match driver_to_render_receiver.recv() {
+                        Ok(canvas) => canvas,
+                        Err(_) => break,
+                    },
+                };
+
+                frame_limiter.wait();
+                frame += 1;
+
+                if splash.is_some() && render_registry.lock().has_content() {
+                    splash = None;
+                    damage.invalidate();
+                }
+
+                let (changed, render_time) = time_frame(frame, frame_budget, || {
+                    catch_render_panic(|| match &splash {
+                        Some(splash) => draw_frame(canvas.as_mut(), splash.as_ref(), &mut damage),
+                        None => draw_frame(canvas.as_mut(), &*render_registry.lock(), &mut damage),
+                    })
+                });
+                render_health.render_times.lock().record(render_time);
+                let changed = changed?;
+
+                if let Some(changed) = changed {
+                    render_health
+                        .retrying_render
+                        .store(false, Ordering::Relaxed);
+                    hand_off(
+                        &render_to_driver_sender,
+                        Frame { canvas, changed },
+                        "driver",
+                    )?;
+                } else {
+                    damage.invalidate();
+                    render_health.retrying_render.store(true, Ordering::Relaxed);
+                    retry_canvas = Some(canvas);
+                    thread::sleep(RENDER_PANIC_RETRY_DELAY);
                 }
             }
 
@@ -188,22 +656,27 @@ impl MatrixDriver {
 
             let mut hardware_driver = H::new(hardware_config)?;
             let canvas = hardware_driver.create_canvas();
-            driver_to_render_sender.send(canvas)?;
+            hand_off(&driver_to_render_sender, canvas, "render")?;
 
             while alive_driver.load(Ordering::SeqCst) {
-                //let timeout = Duration::from_millis((1000.0 / framerate as f64) as u64);
-                let timeout = Duration::from_millis(30);
-
-                match render_to_driver_receiver.recv_timeout(timeout) {
-                    Ok(canvas) => {
-                        let canvas_new = hardware_driver.display_canvas(canvas);
-                        driver_to_render_sender.send(canvas_new)?;
+                match render_to_driver_receiver.recv_timeout(frame_timeout) {
+                    Ok(Frame { canvas, changed }) => {
+                        let canvas_new = if changed {
+                            hardware_driver.display_canvas(canvas)
+                        } else {
+                            driver_health.skipped_frames.fetch_add(1, Ordering::Relaxed);
+                            canvas
+                        };
+                        hand_off(&driver_to_render_sender, canvas_new, "render")?;
                     }
                     Err(RecvTimeoutError::Disconnected) => {
                         break;
                     }
                     Err(RecvTimeoutError::Timeout) => {
-                        warn!("Timeout waiting for frame from render");
+                        // The render already logged why it is being retried
+                        if !driver_health.retrying_render.load(Ordering::Relaxed) {
+                            warn!("Timeout waiting for frame from render");
+                        }
                         continue;
                     }
                 }
@@ -230,8 +703,27 @@ impl MatrixDriver {
             render_thread_handle: Some(render_thread_handle),
             driver_thread_handle: Some(driver_thread_handle),
             http_thread_handle: Some(http_thread_handle),
+            health,
         })
     }
+
+    /// Returns the number of frames that were not sent to the panel because
+    /// they were identical to the frame already being displayed.
+    pub fn skipped_frames(&self) -> u64 {
+        self.health.skipped_frames()
+    }
+
+    /// Returns how long the most recent frames took to render on average,
+    /// `None` before the first frame.
+    pub fn average_render_time(&self) -> Option<Duration> {
+        self.health.average_render_time()
+    }
+
+    /// Returns the frame statistics, which keep being updated while the driver
+    /// is running.
+    pub fn health(&self) -> Arc<DriverHealth> {
+        self.health.clone()
+    }
 }
 
 impl Drop for MatrixDriver {
@@ -247,25 +739,22 @@ impl Drop for MatrixDriver {
         // Stop the threads
         alive.store(false, Ordering::SeqCst);
 
-        if let Some(render_handle) = render_thread_handle.take() {
-            render_handle
-                .join()
-                .expect("Failed to join the render thread")
-                .expect("Render thread encountered an error");
-        }
-
-        if let Some(driver_handle) = driver_thread_handle.take() {
-            driver_handle
-                .join()
-                .expect("Failed to join the driver thread")
-                .expect("Driver thread encountered an error");
-        }
-
-        if let Some(http_handle) = http_thread_handle.take() {
-            http_handle
-                .join()
-                .expect("Failed to join the http thread")
-                .expect("HTTP thread encountered an error");
+        for (name, handle) in [
+            ("render", render_thread_handle.take()),
+            ("driver", driver_thread_handle.take()),
+            ("HTTP", http_thread_handle.take()),
+        ] {
+            if let Some(handle) = handle {
+                match join_with_timeout(handle, THREAD_SHUTDOWN_TIMEOUT) {
+                    Some(result) => result
+                        .unwrap_or_else(|_| panic!("Failed to join the {name} thread"))
+                        .unwrap_or_else(|e| panic!("{name} thread encountered an error: {e}")),
+                    None => error!(
+                        "The {name} thread did not exit within {:?}, detaching it",
+                        THREAD_SHUTDOWN_TIMEOUT
+                    ),
+                }
+            }
         }
     }
 }
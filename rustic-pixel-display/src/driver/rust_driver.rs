@@ -35,6 +35,10 @@ impl TryFrom<HardwareConfig> for RGBMatrixConfig {
     type Error = Box<dyn std::error::Error>;
 
     fn try_from(config: HardwareConfig) -> Result<Self, Self::Error> {
+        if config.pixel_mapper.is_some() {
+            return Err("pixel_mapper is not supported by the Rust driver".into());
+        }
+
         Ok(RGBMatrixConfig {
             hardware_mapping: rpi_led_panel::HardwareMapping::from_str(
                 config.hardware_mapping.as_ref(),
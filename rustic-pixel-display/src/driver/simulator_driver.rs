@@ -0,0 +1,127 @@
+use super::HardwareDriver;
+use crate::config::HardwareConfig;
+use anyhow::Result;
+use embedded_graphics::{pixelcolor::Rgb888, prelude::Size};
+use embedded_graphics_simulator::{
+    OutputSettingsBuilder, SimulatorDisplay, SimulatorEvent, Window,
+};
+use log::info;
+use parking_lot::Mutex;
+use std::{thread, time::Duration};
+
+/// How many screen pixels wide every panel pixel is drawn if no scale is
+/// configured
+const DEFAULT_SCALE: u32 = 4;
+
+/// How long the window waits for the first frame before checking again
+const FIRST_FRAME_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The frame most recently displayed by a [`SimulatorHardwareDriver`], waiting
+/// to be shown by the [`SimulatorWindow`]
+static LATEST_FRAME: Mutex<Option<SimulatorDisplay<Rgb888>>> = Mutex::new(None);
+
+/// Driver that hands frames to a desktop window instead of a panel.
+///
+/// Lets the same registry, HTTP API and driver threads that run on the Pi be
+/// exercised on a desktop, so what is seen in the simulator matches what the
+/// panel shows.
+///
+/// Like every driver it runs on the driver thread of the
+/// [`MatrixDriver`](super::MatrixDriver), but SDL windows have to be updated
+/// from the main thread on some platforms. So the driver only copies each frame
+/// it is given into a shared slot, and a [`SimulatorWindow`] run on the main
+/// thread shows the latest frame and pumps the window events.
+pub struct SimulatorHardwareDriver {
+    size: Size,
+}
+
+pub struct SimulatorConfig {
+    pub size: Size,
+}
+
+impl HardwareDriver for SimulatorHardwareDriver {
+    type Config = SimulatorConfig;
+    type Canvas = SimulatorDisplay<Rgb888>;
+
+    fn new(config: Self::Config) -> Result<Self> {
+        Ok(Self { size: config.size })
+    }
+
+    fn create_canvas(&mut self) -> Box<Self::Canvas> {
+        Box::new(SimulatorDisplay::new(self.size))
+    }
+
+    fn display_canvas(&mut self, canvas: Box<Self::Canvas>) -> Box<Self::Canvas> {
+        // The render thread only redraws what changed, so the canvas is copied
+        // rather than handed over to keep its contents
+        *LATEST_FRAME.lock() = Some((*canvas).clone());
+        canvas
+    }
+}
+
+impl TryFrom<HardwareConfig> for SimulatorConfig {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(config: HardwareConfig) -> Result<Self, Self::Error> {
+        Ok(SimulatorConfig {
+            size: config.chain_size(),
+        })
+    }
+}
+
+/// The desktop window that shows the frames of a [`SimulatorHardwareDriver`].
+///
+/// Has to be created and run on the main thread, see
+/// [`SimulatorWindow::run`].
+pub struct SimulatorWindow {
+    window: Window,
+}
+
+impl SimulatorWindow {
+    /// Creates the window, scaled by the `simulator_scale` of `config`.
+    pub fn new(config: &HardwareConfig) -> Self {
+        let output_settings = OutputSettingsBuilder::new()
+            .scale(config.simulator_scale.unwrap_or(DEFAULT_SCALE))
+            .build();
+
+        Self {
+            window: Window::new("Rustic Pixel Display", &output_settings),
+        }
+    }
+
+    /// Shows the latest frame of the driver and pumps the window events until
+    /// the window is closed or `running` returns `false`.
+    ///
+    /// Blocks the calling thread, which should be the main thread, so the
+    /// tokio runtime the renders and the HTTP API depend on has to be running
+    /// on other threads.
+    pub fn run<F>(mut self, running: F)
+    where
+        F: Fn() -> bool,
+    {
+        while running() {
+            // Events can only be read once the window has been updated, so
+            // nothing happens until the first frame
+            let latest_frame = LATEST_FRAME.lock().clone();
+            let frame = match latest_frame {
+                Some(frame) => frame,
+                None => {
+                    thread::sleep(FIRST_FRAME_POLL_INTERVAL);
+                    continue;
+                }
+            };
+
+            // Paced by the frame rate of the window
+            self.window.update(&frame);
+
+            if self
+                .window
+                .events()
+                .any(|event| event == SimulatorEvent::Quit)
+            {
+                info!("Simulator window closed");
+                break;
+            }
+        }
+    }
+}
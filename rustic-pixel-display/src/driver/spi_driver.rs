@@ -0,0 +1,96 @@
+use super::HardwareDriver;
+use crate::{config::HardwareConfig, render::BufferCanvas};
+use anyhow::{Context, Result};
+use embedded_graphics::prelude::{RgbColor, Size};
+use log::error;
+use spidev::{SpiModeFlags, Spidev, SpidevOptions};
+use std::io::Write;
+
+/// Clock speed of the SPI bus if none is configured
+const DEFAULT_SPI_SPEED_HZ: u32 = 8_000_000;
+
+/// The largest transfer the spidev kernel driver accepts by default
+const SPI_CHUNK_SIZE: usize = 4096;
+
+/// Drives a LED matrix whose controller is connected over SPI instead of HUB75.
+///
+/// Renders draw onto an in-memory canvas that is written to the controller as
+/// raw RGB bytes, three per pixel in row-major order, each time it is
+/// displayed.
+pub struct SpiHardwareDriver {
+    spi: Spidev,
+    size: Size,
+
+    /// Reused between frames to avoid allocating a buffer for every frame
+    frame: Vec<u8>,
+}
+
+pub struct SpiConfig {
+    /// Path to the spidev device, i.e. "/dev/spidev0.0"
+    pub bus: String,
+    pub speed_hz: u32,
+    pub size: Size,
+}
+
+impl HardwareDriver for SpiHardwareDriver {
+    type Config = SpiConfig;
+    type Canvas = BufferCanvas;
+
+    fn new(config: Self::Config) -> Result<Self> {
+        let mut spi = Spidev::open(&config.bus)
+            .with_context(|| format!("Could not open SPI bus {}", config.bus))?;
+
+        spi.configure(
+            &SpidevOptions::new()
+                .bits_per_word(8)
+                .max_speed_hz(config.speed_hz)
+                .mode(SpiModeFlags::SPI_MODE_0)
+                .build(),
+        )
+        .context("Could not configure SPI bus")?;
+
+        Ok(Self {
+            spi,
+            size: config.size,
+            frame: Vec::with_capacity((config.size.width * config.size.height * 3) as usize),
+        })
+    }
+
+    fn create_canvas(&mut self) -> Box<Self::Canvas> {
+        Box::new(BufferCanvas::new(self.size))
+    }
+
+    fn display_canvas(&mut self, canvas: Box<Self::Canvas>) -> Box<Self::Canvas> {
+        self.frame.clear();
+        self.frame.extend(
+            canvas
+                .pixels()
+                .iter()
+                .flat_map(|pixel| [pixel.r(), pixel.g(), pixel.b()]),
+        );
+
+        for chunk in self.frame.chunks(SPI_CHUNK_SIZE) {
+            if let Err(e) = self.spi.write_all(chunk) {
+                error!("Could not write frame to SPI bus: {e}");
+                break;
+            }
+        }
+
+        // The frame has been copied out, so the same canvas can be drawn on again
+        canvas
+    }
+}
+
+impl TryFrom<HardwareConfig> for SpiConfig {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(config: HardwareConfig) -> Result<Self, Self::Error> {
+        Ok(SpiConfig {
+            bus: config
+                .spi_bus
+                .ok_or("An SPI bus must be configured to use the SPI driver")?,
+            speed_hz: config.spi_speed_hz.unwrap_or(DEFAULT_SPI_SPEED_HZ),
+            size: config.chain_size(),
+        })
+    }
+}
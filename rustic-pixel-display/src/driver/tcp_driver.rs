@@ -0,0 +1,225 @@
+//! Streams frames to a display agent on another machine.
+//!
+//! # Wire format
+//!
+//! All integers are big endian.
+//!
+//! 1. The driver connects and sends the 4 byte magic `RPXL` followed by the
+//!    protocol version as a `u8`.
+//! 2. The agent replies with the size of its panel as a `u16` width followed by
+//!    a `u16` height. The driver closes the connection if the size does not
+//!    match the size it was configured with.
+//! 3. The driver then sends frames for as long as the connection is open. Each
+//!    frame is a `u32` payload length followed by the payload, three bytes of
+//!    RGB per pixel in row-major order.
+//!
+//! If the connection is lost the driver keeps rendering and reconnects, at most
+//! once every [`RECONNECT_INTERVAL`], dropping the frames in between.
+
+use super::HardwareDriver;
+use crate::{config::HardwareConfig, render::BufferCanvas};
+use anyhow::{anyhow, Context, Result};
+use embedded_graphics::prelude::{RgbColor, Size};
+use log::{error, info};
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    time::{Duration, Instant},
+};
+
+/// Sent by the driver at the start of the handshake
+pub const PROTOCOL_MAGIC: &[u8; 4] = b"RPXL";
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// Minimum time between attempts to connect to the agent
+pub const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long connecting or writing a frame can take before the connection is
+/// considered lost
+const IO_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The size of the payload of a frame for a panel of `size`
+fn frame_len(size: Size) -> usize {
+    (size.width * size.height * 3) as usize
+}
+
+/// Performs the agent side of the handshake, reporting that its panel is
+/// `size`.
+pub fn accept_handshake(stream: &mut TcpStream, size: Size) -> Result<()> {
+    let mut hello = [0u8; 5];
+    stream.read_exact(&mut hello)?;
+
+    if &hello[..4] != PROTOCOL_MAGIC {
+        return Err(anyhow!("Client did not send the protocol magic"));
+    }
+
+    if hello[4] != PROTOCOL_VERSION {
+        return Err(anyhow!("Unsupported protocol version {}", hello[4]));
+    }
+
+    let width = u16::try_from(size.width).context("Panel is too wide")?;
+    let height = u16::try_from(size.height).context("Panel is too tall")?;
+
+    stream.write_all(&width.to_be_bytes())?;
+    stream.write_all(&height.to_be_bytes())?;
+
+    Ok(())
+}
+
+/// Reads the next frame sent to an agent with a panel of `size` into `frame`.
+pub fn read_frame(stream: &mut TcpStream, size: Size, frame: &mut Vec<u8>) -> Result<()> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+
+    let len = u32::from_be_bytes(len) as usize;
+    if len != frame_len(size) {
+        return Err(anyhow!(
+            "Frame of {len} bytes does not match the panel size {}x{}",
+            size.width,
+            size.height
+        ));
+    }
+
+    frame.resize(len, 0);
+    stream.read_exact(frame)?;
+
+    Ok(())
+}
+
+/// Performs the driver side of the handshake, checking that the agent's panel
+/// is `size`.
+fn connect(address: &SocketAddr, size: Size) -> Result<TcpStream> {
+    let mut stream = TcpStream::connect_timeout(address, IO_TIMEOUT)?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_nodelay(true)?;
+
+    stream.write_all(PROTOCOL_MAGIC)?;
+    stream.write_all(&[PROTOCOL_VERSION])?;
+
+    let mut agent_size = [0u8; 4];
+    stream.read_exact(&mut agent_size)?;
+
+    let agent_size = Size::new(
+        u16::from_be_bytes([agent_size[0], agent_size[1]]) as u32,
+        u16::from_be_bytes([agent_size[2], agent_size[3]]) as u32,
+    );
+
+    if agent_size != size {
+        return Err(anyhow!(
+            "Agent panel is {}x{} but the driver is configured for {}x{}",
+            agent_size.width,
+            agent_size.height,
+            size.width,
+            size.height
+        ));
+    }
+
+    Ok(stream)
+}
+
+/// Driver that forwards frames to a display agent over TCP, allowing renders to
+/// run on a different machine than the one connected to the panel.
+pub struct TcpHardwareDriver {
+    address: SocketAddr,
+    size: Size,
+    stream: Option<TcpStream>,
+    last_connect_attempt: Option<Instant>,
+
+    /// Reused between frames to avoid allocating a buffer for every frame
+    frame: Vec<u8>,
+}
+
+pub struct TcpConfig {
+    pub address: SocketAddr,
+    pub size: Size,
+}
+
+impl TcpHardwareDriver {
+    /// Returns the connection to the agent, reconnecting if the connection was
+    /// lost and enough time has passed since the last attempt.
+    fn stream(&mut self) -> Option<&mut TcpStream> {
+        let attempted_recently = self
+            .last_connect_attempt
+            .is_some_and(|last_attempt| last_attempt.elapsed() < RECONNECT_INTERVAL);
+
+        if self.stream.is_none() && !attempted_recently {
+            self.last_connect_attempt = Some(Instant::now());
+
+            match connect(&self.address, self.size) {
+                Ok(stream) => {
+                    info!("Connected to display agent {}", self.address);
+                    self.stream = Some(stream);
+                }
+                Err(e) => error!("Could not connect to display agent {}: {e}", self.address),
+            }
+        }
+
+        self.stream.as_mut()
+    }
+}
+
+impl HardwareDriver for TcpHardwareDriver {
+    type Config = TcpConfig;
+    type Canvas = BufferCanvas;
+
+    fn new(config: Self::Config) -> Result<Self> {
+        let mut driver = Self {
+            address: config.address,
+            size: config.size,
+            stream: None,
+            last_connect_attempt: None,
+            frame: Vec::with_capacity(frame_len(config.size) + 4),
+        };
+
+        // Connect eagerly so a misconfigured agent is reported on startup
+        driver.stream();
+
+        Ok(driver)
+    }
+
+    fn create_canvas(&mut self) -> Box<Self::Canvas> {
+        Box::new(BufferCanvas::new(self.size))
+    }
+
+    fn display_canvas(&mut self, canvas: Box<Self::Canvas>) -> Box<Self::Canvas> {
+        let mut frame = std::mem::take(&mut self.frame);
+        frame.clear();
+        frame.extend_from_slice(&(frame_len(self.size) as u32).to_be_bytes());
+        frame.extend(
+            canvas
+                .pixels()
+                .iter()
+                .flat_map(|pixel| [pixel.r(), pixel.g(), pixel.b()]),
+        );
+
+        if let Some(stream) = self.stream() {
+            if let Err(e) = stream.write_all(&frame) {
+                error!("Lost connection to display agent {}: {e}", self.address);
+                self.stream = None;
+            }
+        }
+
+        self.frame = frame;
+
+        canvas
+    }
+}
+
+impl TryFrom<HardwareConfig> for TcpConfig {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(config: HardwareConfig) -> Result<Self, Self::Error> {
+        let address = config
+            .tcp_address
+            .ok_or("An agent address must be configured to use the TCP driver")?
+            .to_socket_addrs()?
+            .next()
+            .ok_or("The agent address did not resolve to any socket address")?;
+
+        Ok(TcpConfig {
+            address,
+            size: config.chain_size(),
+        })
+    }
+}
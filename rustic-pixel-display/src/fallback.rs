@@ -0,0 +1,221 @@
+use crate::{
+    layout_manager::LayoutRegionConfig,
+    render::{Render, RenderError, RenderFactories, RenderFactory, SyncRender},
+};
+use anyhow::Result;
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, RgbColor},
+    primitives::Rectangle,
+};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::{convert::Infallible, io::Read, marker::PhantomData, time::Instant};
+
+/// Configuration for the [`FallbackFactory`].
+///
+/// The first render that has content is displayed, i.e. the current weather
+/// while the forecast is available and a label otherwise:
+///
+/// ```json
+/// {
+///   "renders": [
+///     { "factory": "Weather", "config": { ... } },
+///     { "factory": "Label", "config": { ... } }
+///   ]
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct FallbackConfig {
+    pub renders: Vec<LayoutRegionConfig>,
+}
+
+/// Delegates to the first of an ordered list of renders that reports it
+/// [has content](Render::has_content).
+///
+/// When none of the renders have content the last one is drawn, so the panel
+/// shows its loading or error state rather than going blank.
+pub struct Fallback<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    renders: Vec<Box<dyn SyncRender<D>>>,
+
+    /// Index of the render that was last drawn. `None` while the fallback is
+    /// not displayed.
+    active: Mutex<Option<usize>>,
+}
+
+impl<D> Fallback<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    pub fn new(renders: Vec<Box<dyn SyncRender<D>>>) -> Self {
+        Self {
+            renders,
+            active: Mutex::new(None),
+        }
+    }
+
+    /// Returns the index of the render that should currently be displayed.
+    fn current_index(&self) -> Option<usize> {
+        self.renders
+            .iter()
+            .position(|render| render.has_content())
+            .or_else(|| self.renders.len().checked_sub(1))
+    }
+
+    fn render_for(&self, index: Option<usize>) -> Option<&dyn SyncRender<D>> {
+        index
+            .and_then(|index| self.renders.get(index))
+            .map(|render| render.as_ref())
+    }
+}
+
+impl<D> Render<D> for Fallback<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let index = self.current_index();
+        let mut active = self.active.lock();
+
+        // Hand the selection over when falling through to another render, or back
+        if *active != index {
+            if let Some(previous) = self.render_for(*active) {
+                previous.on_deselect();
+            }
+
+            if let Some(render) = self.render_for(index) {
+                render.on_select();
+            }
+
+            *active = index;
+        }
+
+        match self.render_for(index) {
+            Some(render) => render.render_at(canvas, now),
+            None => canvas.clear(Rgb888::BLACK),
+        }
+    }
+
+    fn on_select(&self) {
+        // Every render is selected so the ones that are not displayed keep
+        // fetching and can take over once they have content again
+        for render in &self.renders {
+            render.on_select();
+        }
+
+        *self.active.lock() = self.current_index();
+    }
+
+    fn on_deselect(&self) {
+        for render in &self.renders {
+            render.on_deselect();
+        }
+
+        *self.active.lock() = None;
+    }
+
+    fn background_color(&self) -> Option<Rgb888> {
+        self.render_for(self.current_index())
+            .and_then(|render| render.background_color())
+    }
+
+    fn dirty_region(&self) -> Option<Rectangle> {
+        let index = self.current_index();
+
+        // The whole canvas changes when switching to another render
+        if *self.active.lock() != index {
+            return None;
+        }
+
+        self.render_for(index)
+            .and_then(|render| render.dirty_region())
+    }
+
+    fn describe(&self) -> String {
+        match self.render_for(self.current_index()) {
+            Some(render) => render.describe(),
+            None => "No renders".to_owned(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        self.renders.iter().any(|render| render.has_content())
+    }
+
+    fn reset(&self) {
+        for render in &self.renders {
+            render.reset();
+        }
+    }
+}
+
+/// Constructs a [`Fallback`] whose renders are constructed by the factories in
+/// `F`, typically the same `#[derive(RenderFactories)]` enum that the registry
+/// was created with.
+pub struct FallbackFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<(D, F)>,
+}
+
+impl<D, F> Default for FallbackFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D, F> RenderFactory<D> for FallbackFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible> + 'static,
+    F: RenderFactories<D>,
+{
+    fn render_name(&self) -> &'static str {
+        "Fallback"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Displays the first of a list of renders that has something to show"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: FallbackConfig = serde_json::from_reader(reader)?;
+        let factories = F::factories();
+
+        if config.renders.is_empty() {
+            return Err(RenderError::Config(
+                "Need to provide at least one render".to_owned(),
+            ));
+        }
+
+        let renders = config
+            .renders
+            .into_iter()
+            .map(|render| {
+                let factory = factories
+                    .iter()
+                    .find(|factory| factory.render_name() == render.factory)
+                    .ok_or_else(|| {
+                        RenderError::Config(format!("Factory \"{}\" was not found", render.factory))
+                    })?;
+
+                let config = serde_json::to_vec(&render.config)?;
+                factory.load_from_config(config.as_slice())
+            })
+            .collect::<Result<Vec<_>, RenderError>>()?;
+
+        Ok(Box::new(Fallback::new(renders)))
+    }
+}
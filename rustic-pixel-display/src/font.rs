@@ -0,0 +1,144 @@
+use embedded_graphics::mono_font::{self, MonoFont};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use strum_macros::{AsRefStr, EnumIter, EnumString};
+
+/// Drawn in place of characters that have no glyph in the fonts
+pub const FALLBACK_GLYPH: char = '?';
+
+/// The monospaced fonts renders can be configured with, named after the size
+/// of their glyphs, i.e. `"6x10"` or `"7x13 Bold"`.
+///
+/// Every font covers ISO 8859-1, so accented characters and symbols like `°`
+/// can be drawn. The glyphs of the ASCII range are identical to the ASCII
+/// variants of the fonts.
+#[derive(
+    Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, EnumString, AsRefStr, EnumIter,
+)]
+pub enum Font {
+    #[serde(rename = "4x6")]
+    #[strum(serialize = "4x6")]
+    FourBySix,
+    #[serde(rename = "5x7")]
+    #[strum(serialize = "5x7")]
+    FiveBySeven,
+    #[serde(rename = "5x8")]
+    #[strum(serialize = "5x8")]
+    FiveByEight,
+    #[serde(rename = "6x9")]
+    #[strum(serialize = "6x9")]
+    SixByNine,
+    #[serde(rename = "6x10")]
+    #[strum(serialize = "6x10")]
+    SixByTen,
+    #[serde(rename = "6x12")]
+    #[strum(serialize = "6x12")]
+    SixByTwelve,
+    #[serde(rename = "6x13")]
+    #[strum(serialize = "6x13")]
+    SixByThirteen,
+    #[serde(rename = "6x13 Bold")]
+    #[strum(serialize = "6x13 Bold")]
+    SixByThirteenBold,
+    #[serde(rename = "6x13 Italic")]
+    #[strum(serialize = "6x13 Italic")]
+    SixByThirteenItalic,
+    #[serde(rename = "7x13")]
+    #[strum(serialize = "7x13")]
+    SevenByThirteen,
+    #[serde(rename = "7x13 Bold")]
+    #[strum(serialize = "7x13 Bold")]
+    SevenByThirteenBold,
+    #[serde(rename = "7x13 Italic")]
+    #[strum(serialize = "7x13 Italic")]
+    SevenByThirteenItalic,
+    #[serde(rename = "7x14")]
+    #[strum(serialize = "7x14")]
+    SevenByFourteen,
+    #[serde(rename = "7x14 Bold")]
+    #[strum(serialize = "7x14 Bold")]
+    SevenByFourteenBold,
+    #[serde(rename = "8x13")]
+    #[strum(serialize = "8x13")]
+    EightByThirteen,
+    #[serde(rename = "8x13 Bold")]
+    #[strum(serialize = "8x13 Bold")]
+    EightByThirteenBold,
+    #[serde(rename = "8x13 Italic")]
+    #[strum(serialize = "8x13 Italic")]
+    EightByThirteenItalic,
+    #[serde(rename = "9x15")]
+    #[strum(serialize = "9x15")]
+    NineByFifteen,
+    #[serde(rename = "9x15 Bold")]
+    #[strum(serialize = "9x15 Bold")]
+    NineByFifteenBold,
+    #[serde(rename = "9x18")]
+    #[strum(serialize = "9x18")]
+    NineByEighteen,
+    #[serde(rename = "9x18 Bold")]
+    #[strum(serialize = "9x18 Bold")]
+    NineByEighteenBold,
+    #[serde(rename = "10x20")]
+    #[strum(serialize = "10x20")]
+    TenByTwenty,
+}
+
+impl Font {
+    pub fn mono_font(&self) -> &'static MonoFont<'static> {
+        match self {
+            Font::FourBySix => &mono_font::iso_8859_1::FONT_4X6,
+            Font::FiveBySeven => &mono_font::iso_8859_1::FONT_5X7,
+            Font::FiveByEight => &mono_font::iso_8859_1::FONT_5X8,
+            Font::SixByNine => &mono_font::iso_8859_1::FONT_6X9,
+            Font::SixByTen => &mono_font::iso_8859_1::FONT_6X10,
+            Font::SixByTwelve => &mono_font::iso_8859_1::FONT_6X12,
+            Font::SixByThirteen => &mono_font::iso_8859_1::FONT_6X13,
+            Font::SixByThirteenBold => &mono_font::iso_8859_1::FONT_6X13_BOLD,
+            Font::SixByThirteenItalic => &mono_font::iso_8859_1::FONT_6X13_ITALIC,
+            Font::SevenByThirteen => &mono_font::iso_8859_1::FONT_7X13,
+            Font::SevenByThirteenBold => &mono_font::iso_8859_1::FONT_7X13_BOLD,
+            Font::SevenByThirteenItalic => &mono_font::iso_8859_1::FONT_7X13_ITALIC,
+            Font::SevenByFourteen => &mono_font::iso_8859_1::FONT_7X14,
+            Font::SevenByFourteenBold => &mono_font::iso_8859_1::FONT_7X14_BOLD,
+            Font::EightByThirteen => &mono_font::iso_8859_1::FONT_8X13,
+            Font::EightByThirteenBold => &mono_font::iso_8859_1::FONT_8X13_BOLD,
+            Font::EightByThirteenItalic => &mono_font::iso_8859_1::FONT_8X13_ITALIC,
+            Font::NineByFifteen => &mono_font::iso_8859_1::FONT_9X15,
+            Font::NineByFifteenBold => &mono_font::iso_8859_1::FONT_9X15_BOLD,
+            Font::NineByEighteen => &mono_font::iso_8859_1::FONT_9X18,
+            Font::NineByEighteenBold => &mono_font::iso_8859_1::FONT_9X18_BOLD,
+            Font::TenByTwenty => &mono_font::iso_8859_1::FONT_10X20,
+        }
+    }
+}
+
+/// Replaces the characters of `text` outside of ISO 8859-1, which the fonts
+/// have no glyph for, with a close equivalent when there is one and
+/// [`FALLBACK_GLYPH`] otherwise.
+///
+/// Text from APIs, like station and destination names, can contain typographic
+/// quotes and dashes that would otherwise be drawn as placeholders. Text that
+/// can already be drawn is returned as is.
+pub fn latin1_lossy(text: &str) -> Cow<'_, str> {
+    if text.chars().all(is_latin1) {
+        return Cow::Borrowed(text);
+    }
+
+    Cow::Owned(
+        text.chars()
+            .map(|c| match c {
+                c if is_latin1(c) => c,
+                '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{2032}' => '\'',
+                '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{2033}' => '"',
+                '\u{2010}'..='\u{2015}' | '\u{2212}' => '-',
+                '\u{2022}' | '\u{2219}' => '\u{B7}',
+                _ => FALLBACK_GLYPH,
+            })
+            .collect(),
+    )
+}
+
+fn is_latin1(c: char) -> bool {
+    u32::from(c) <= 0xFF
+}
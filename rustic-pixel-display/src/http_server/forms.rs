@@ -1,7 +1,6 @@
 use crate::config::{self};
 use anyhow::Context;
-use embedded_graphics::mono_font;
-use rocket::{FromForm, FromFormField};
+use rocket::FromForm;
 use serde::Serialize;
 use std::str::FromStr;
 
@@ -142,87 +141,13 @@ impl<'a> TryFrom<&HardwareConfigForm<'a>> for config::HardwareConfig {
                 "The value \"{}\" for \"led_sequence\" was not a recognized value",
                 form.led_sequence
             ))?,
+            driver_backend: config::DriverBackend::default(),
+            simulator_scale: None,
+            panels: Vec::new(),
         })
     }
 }
 
-#[derive(Debug, PartialEq, FromFormField, Clone, Copy)]
-pub(crate) enum Font {
-    #[field(value = "4x6")]
-    FourBySix,
-    #[field(value = "5x7")]
-    FiveBySeven,
-    #[field(value = "5x8")]
-    FiveByEight,
-    #[field(value = "6x9")]
-    SixByNine,
-    #[field(value = "6x10")]
-    SixByTen,
-    #[field(value = "6x12")]
-    SixByTwelve,
-    #[field(value = "6x13")]
-    SixByThirteen,
-    #[field(value = "6x13 Bold")]
-    SixByThirteenBold,
-    #[field(value = "6x13 Italic")]
-    SixByThirteenItalic,
-    #[field(value = "7x13")]
-    SevenByThirteen,
-    #[field(value = "7x13 Bold")]
-    SevenByThirteenBold,
-    #[field(value = "7x13 Italic")]
-    SevenByThirteenItalic,
-    #[field(value = "7x14")]
-    SevenByFourteen,
-    #[field(value = "7x14 Bold")]
-    SevenByFourteenBold,
-    #[field(value = "8x13")]
-    EightByThirteen,
-    #[field(value = "8x13 Bold")]
-    EightByThirteenBold,
-    #[field(value = "8x13 Italic")]
-    EightByThirteenItalic,
-    #[field(value = "9x15")]
-    NineByFifteen,
-    #[field(value = "9x15 Bold")]
-    NineByFifteenBold,
-    #[field(value = "9x18")]
-    NineByEighteen,
-    #[field(value = "9x18 Bold")]
-    NineByEighteenBold,
-    #[field(value = "10x20")]
-    TenByTwenty,
-}
-
-impl From<Font> for mono_font::MonoFont<'_> {
-    fn from(value: Font) -> Self {
-        match value {
-            Font::FourBySix => mono_font::ascii::FONT_4X6,
-            Font::FiveBySeven => mono_font::ascii::FONT_5X7,
-            Font::FiveByEight => mono_font::ascii::FONT_5X8,
-            Font::SixByNine => mono_font::ascii::FONT_6X9,
-            Font::SixByTen => mono_font::ascii::FONT_6X10,
-            Font::SixByTwelve => mono_font::ascii::FONT_6X12,
-            Font::SixByThirteen => mono_font::ascii::FONT_6X13,
-            Font::SixByThirteenBold => mono_font::ascii::FONT_6X13_BOLD,
-            Font::SixByThirteenItalic => mono_font::ascii::FONT_6X13_ITALIC,
-            Font::SevenByThirteen => mono_font::ascii::FONT_7X13,
-            Font::SevenByThirteenBold => mono_font::ascii::FONT_7X13_BOLD,
-            Font::SevenByThirteenItalic => mono_font::ascii::FONT_7X13_ITALIC,
-            Font::SevenByFourteen => mono_font::ascii::FONT_7X14,
-            Font::SevenByFourteenBold => mono_font::ascii::FONT_7X14_BOLD,
-            Font::EightByThirteen => mono_font::ascii::FONT_8X13,
-            Font::EightByThirteenBold => mono_font::ascii::FONT_8X13_BOLD,
-            Font::EightByThirteenItalic => mono_font::ascii::FONT_8X13_ITALIC,
-            Font::NineByFifteen => mono_font::ascii::FONT_9X15,
-            Font::NineByFifteenBold => mono_font::ascii::FONT_9X15_BOLD,
-            Font::NineByEighteen => mono_font::ascii::FONT_9X18,
-            Font::NineByEighteenBold => mono_font::ascii::FONT_9X18_BOLD,
-            Font::TenByTwenty => mono_font::ascii::FONT_10X20,
-        }
-    }
-}
-
 #[derive(Debug, FromForm, Serialize)]
 #[allow(dead_code)]
 pub(crate) struct TransitConfigForm<'a> {
@@ -1,18 +1,38 @@
-use std::{convert::Infallible, io::Read, net::ToSocketAddrs, sync::Arc};
+use std::{convert::Infallible, error::Error, io::Read, net::ToSocketAddrs, str, sync::Arc};
 
-use embedded_graphics::{pixelcolor::Rgb888, prelude::DrawTarget};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, RgbColor, Size},
+};
 use parking_lot::Mutex;
 use rouille::{input::json::JsonError, router, try_or_400, try_or_404, Request, Response, Server};
 use serde::Serialize;
+use serde_json::error::Category;
+use strum::IntoEnumIterator;
 use tokio::runtime::Handle;
 use try_or_400::ErrJson;
 use uuid::Uuid;
 
 use crate::{
+    font::Font,
+    push_routes,
     registry::{Registry, RegistryError},
-    render::RenderFactory,
+    render::{BufferCanvas, FactoryInfo, RenderError, RenderFactory},
+    theme::Theme,
 };
 
+pub mod openapi;
+
+/// Size of the canvas previews are drawn on if the request does not specify one
+const DEFAULT_PREVIEW_SIZE: Size = Size::new(128, 64);
+
+/// The largest width or height a preview can be drawn at
+const MAX_PREVIEW_DIMENSION: u32 = 1024;
+
+/// The largest JSON body, in bytes, that is accepted if no limit is given to
+/// [`build_api_server_with_limit`]
+pub const DEFAULT_MAX_CONFIG_BYTES: usize = 64 * 1024;
+
 fn json_input_to_reader(request: &Request) -> Result<impl Read + '_, JsonError> {
     if let Some(header) = request.header("Content-Type") {
         if !header.starts_with("application/json") {
@@ -29,16 +49,179 @@ fn json_input_to_reader(request: &Request) -> Result<impl Read + '_, JsonError>
     }
 }
 
+/// Reads the JSON body of the request, at most `max_bytes` of it.
+///
+/// Returns the response to send instead if the body isn't JSON, or if it is
+/// larger than `max_bytes`, so an oversized body is rejected without being
+/// buffered in full.
+fn read_json_body(request: &Request, max_bytes: usize) -> Result<Vec<u8>, Response> {
+    let bad_request = |e: &dyn Error| Response::json(&ErrJson::from_err(e)).with_status_code(400);
+    let too_large = || {
+        Response::text(format!("The body is larger than {max_bytes} bytes")).with_status_code(413)
+    };
+
+    let json_reader = json_input_to_reader(request).map_err(|e| bad_request(&e))?;
+
+    // Reject bodies that announce their size up front before reading them
+    let content_length = request
+        .header("Content-Length")
+        .and_then(|length| length.parse::<usize>().ok());
+    if content_length.is_some_and(|length| length > max_bytes) {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+    json_reader
+        .take(max_bytes as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| bad_request(&e))?;
+
+    if body.len() > max_bytes {
+        return Err(too_large());
+    }
+
+    Ok(body)
+}
+
+/// Error body of a request whose render config was rejected, with the location
+/// of the problem when it is known so clients can point at it.
 #[derive(Serialize)]
-struct FactoryEntry<'a> {
-    name: &'a str,
-    description: &'a str,
+struct ConfigError {
+    /// What is wrong with the config: `encoding`, `syntax`, `type` or `config`
+    kind: &'static str,
+    description: String,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+impl ConfigError {
+    fn new(kind: &'static str, description: String) -> Self {
+        Self {
+            kind,
+            description,
+            line: None,
+            column: None,
+        }
+    }
+
+    fn from_json(e: &serde_json::Error) -> Self {
+        let kind = match e.classify() {
+            Category::Syntax | Category::Eof | Category::Io => "syntax",
+            Category::Data => "type",
+        };
+
+        Self {
+            kind,
+            description: e.to_string(),
+            line: Some(e.line()),
+            column: Some(e.column()),
+        }
+    }
+
+    fn into_response(self) -> Response {
+        Response::json(&self).with_status_code(400)
+    }
+}
+
+/// Checks that `body` is a UTF-8 encoded JSON object before it is handed to a
+/// factory, so the problem can be reported more precisely than the factory
+/// would.
+fn check_config(body: &[u8]) -> Result<(), ConfigError> {
+    let text = str::from_utf8(body).map_err(|e| {
+        let valid = str::from_utf8(&body[..e.valid_up_to()]).unwrap_or_default();
+        let line = valid.rsplit('\n').next().unwrap_or_default();
+
+        ConfigError {
+            line: Some(valid.matches('\n').count() + 1),
+            column: Some(line.chars().count() + 1),
+            ..ConfigError::new("encoding", format!("The config is not valid UTF-8: {e}"))
+        }
+    })?;
+
+    let config: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| ConfigError::from_json(&e))?;
+
+    if !config.is_object() {
+        return Err(ConfigError::new(
+            "type",
+            "The config must be a JSON object".to_owned(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Converts the error of loading or reconfiguring a render into a response,
+/// describing config errors as a [`ConfigError`].
+fn load_error_response(e: RegistryError) -> Response {
+    match e {
+        RegistryError::FactoryNotFound(_) | RegistryError::RenderNotFound(_) => {
+            Response::empty_404()
+        }
+        RegistryError::LoadFailed(RenderError::ConfigParse(e))
+        | RegistryError::ReconfigureFailed(RenderError::ConfigParse(e)) => {
+            ConfigError::from_json(&e).into_response()
+        }
+        RegistryError::LoadFailed(e @ RenderError::Config(_))
+        | RegistryError::ReconfigureFailed(e @ RenderError::Config(_)) => {
+            ConfigError::new("config", e.to_string()).into_response()
+        }
+        // The render has to be unloaded and loaded again instead
+        e @ RegistryError::ReconfigureFailed(RenderError::Unsupported(_)) => {
+            Response::json(&ErrJson::from_err(&e)).with_status_code(501)
+        }
+        e => Response::json(&ErrJson::from_err(&e)).with_status_code(400),
+    }
+}
+
+/// Encodes the canvas as an RGB PNG.
+fn encode_png(canvas: &BufferCanvas) -> Result<Vec<u8>, png::EncodingError> {
+    let size = canvas.size();
+    let mut png_data = Vec::new();
+
+    let mut encoder = png::Encoder::new(&mut png_data, size.width, size.height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let data = canvas
+        .pixels()
+        .iter()
+        .flat_map(|pixel| [pixel.r(), pixel.g(), pixel.b()])
+        .collect::<Vec<_>>();
+
+    encoder.write_header()?.write_image_data(&data)?;
+
+    Ok(png_data)
+}
+
+/// Reads the size of a preview from the `width` and `height` query parameters.
+fn preview_size(request: &Request) -> Option<Size> {
+    let dimension = |name: &str, default: u32| match request.get_param(name) {
+        Some(value) => value
+            .parse::<u32>()
+            .ok()
+            .filter(|value| (1..=MAX_PREVIEW_DIMENSION).contains(value)),
+        None => Some(default),
+    };
+
+    Some(Size::new(
+        dimension("width", DEFAULT_PREVIEW_SIZE.width)?,
+        dimension("height", DEFAULT_PREVIEW_SIZE.height)?,
+    ))
 }
 
 #[derive(Serialize)]
 struct RenderEntry<'a> {
     id: String,
     factory_name: &'a str,
+    description: String,
+}
+
+#[derive(Serialize)]
+struct FactoryStatusEntry<'a> {
+    name: &'a str,
+    loaded: bool,
+    selected: bool,
 }
 
 #[derive(Serialize)]
@@ -68,11 +251,31 @@ where
     factory_registry: Registry<F, D>,
 }
 
+/// Serves the HTTP API for `factory_registry` on `addr`, accepting JSON bodies
+/// of up to [`DEFAULT_MAX_CONFIG_BYTES`].
 pub fn build_api_server<A, D, F>(
     addr: A,
     runtime: Handle,
     factory_registry: Arc<Mutex<Registry<F, D>>>,
 ) -> Server<impl Send + Sync + 'static + Fn(&Request) -> Response>
+where
+    A: ToSocketAddrs,
+    D: DrawTarget<Color = Rgb888, Error = Infallible> + 'static,
+    F: RenderFactory<D> + 'static,
+{
+    build_api_server_with_limit(addr, runtime, factory_registry, DEFAULT_MAX_CONFIG_BYTES)
+}
+
+/// Serves the HTTP API for `factory_registry` on `addr`.
+///
+/// Requests with a JSON body larger than `max_config_bytes`, i.e. a render
+/// config or a theme, are rejected with a 413.
+pub fn build_api_server_with_limit<A, D, F>(
+    addr: A,
+    runtime: Handle,
+    factory_registry: Arc<Mutex<Registry<F, D>>>,
+    max_config_bytes: usize,
+) -> Server<impl Send + Sync + 'static + Fn(&Request) -> Response>
 where
     A: ToSocketAddrs,
     D: DrawTarget<Color = Rgb888, Error = Infallible> + 'static,
@@ -87,6 +290,9 @@ where
         let _guard = runtime.enter();
 
         router!(request,
+            (GET) (/openapi.json) => {
+                Response::json(&openapi::document())
+            },
             (GET) (/render/active) => {
                 Response::json(
                     &registry_unlock
@@ -94,10 +300,42 @@ where
                         .map(|(uuid, render)| RenderEntry {
                             id: uuid.to_string(),
                             factory_name: &render.factory_name,
+                            description: render.render.describe(),
                         })
                         .collect::<Vec<_>>(),
                 )
             },
+            (GET) (/render/{uuid: Uuid}/config) => {
+                match registry_unlock.render_config(uuid) {
+                    Ok(config) => Response::from_data("application/json", config.to_vec()),
+                    Err(RegistryError::RenderNotFound(_)) => Response::empty_404(),
+                    Err(e) => {
+                        let json_error = ErrJson::from_err(&e);
+                        Response::json(&json_error).with_status_code(413)
+                    }
+                }
+            },
+            (POST) (/render/{uuid: Uuid}/config) => {
+                // Attempt to read the JSON input from the request body
+                let body = match read_json_body(request, max_config_bytes) {
+                    Ok(body) => body,
+                    Err(response) => return response,
+                };
+
+                if let Err(e) = check_config(&body) {
+                    return e.into_response();
+                }
+
+                // Apply the config without unloading the render
+                match registry_unlock.reconfigure(uuid, body.as_slice()) {
+                    Ok(()) => Response::empty_204(),
+                    Err(e) => load_error_response(e),
+                }
+            },
+            (POST) (/render/{uuid: Uuid}/reset) => {
+                try_or_404!(registry_unlock.reset(uuid));
+                Response::empty_204()
+            },
             (DELETE) (/render/{uuid: Uuid}) => {
                 try_or_404!(registry_unlock.unload(uuid));
                 Response::empty_204()
@@ -106,9 +344,24 @@ where
                 Response::json(
                     &registry_unlock
                         .factory_iter()
-                        .map(|(_, factory)| FactoryEntry {
-                            name: factory.render_name(),
-                            description: factory.render_description(),
+                        .map(|(_, factory)| FactoryInfo::new(factory))
+                        .collect::<Vec<_>>(),
+                )
+            },
+            (GET) (/fonts) => {
+                // Lists the values the font fields of render configs accept
+                Response::json(&Font::iter().collect::<Vec<_>>())
+            },
+            (GET) (/factory/status) => {
+                let selected_name = registry_unlock.selected_name();
+
+                Response::json(
+                    &registry_unlock
+                        .loaded_iter()
+                        .map(|(name, loaded)| FactoryStatusEntry {
+                            name,
+                            loaded,
+                            selected: selected_name == Some(name),
                         })
                         .collect::<Vec<_>>(),
                 )
@@ -119,30 +372,118 @@ where
             },
             (POST) (/factory/load/{render_name: String}) => {
                 // Attempt to read the JSON input from the request body
-                let json_reader = try_or_400!(json_input_to_reader(request));
+                let body = match read_json_body(request, max_config_bytes) {
+                    Ok(body) => body,
+                    Err(response) => return response,
+                };
+
+                if let Err(e) = check_config(&body) {
+                    return e.into_response();
+                }
 
                 // Attempt to load the render into the registry
-                let uuid = match registry_unlock.load(&render_name, json_reader) {
+                let uuid = match registry_unlock.load(&render_name, body.as_slice()) {
                     Ok(uuid) => uuid,
-                    Err(e) => match e {
-                        RegistryError::FactoryNotFound(_) => return Response::empty_404(),
-                        _ => {
-                            let json_error = ErrJson::from_err(&e);
-                            return Response::json(&json_error).with_status_code(400);
-                        }
+                    Err(e) => return load_error_response(e),
+                };
+
+                Response::json(&LoadResponse {
+                    id: uuid.to_string()
+                })
+            },
+            (POST) (/factory/preview/{render_name: String}) => {
+                let size = match preview_size(request) {
+                    Some(size) => size,
+                    None => return Response::empty_400(),
+                };
+
+                // Attempt to read the JSON input from the request body
+                let body = match read_json_body(request, max_config_bytes) {
+                    Ok(body) => body,
+                    Err(response) => return response,
+                };
+
+                if let Err(e) = check_config(&body) {
+                    return e.into_response();
+                }
+
+                // Draw a single frame without adding the render to the registry
+                let canvas = match registry_unlock.preview(&render_name, body.as_slice(), size) {
+                    Ok(canvas) => canvas,
+                    Err(e) => return load_error_response(e),
+                };
+
+                match encode_png(&canvas) {
+                    Ok(png_data) => Response::from_data("image/png", png_data),
+                    Err(e) => {
+                        let json_error = ErrJson::from_err(&e);
+                        Response::json(&json_error).with_status_code(500)
                     }
+                }
+            },
+            (POST) (/factory/reload/{render_name: String}) => {
+                // Load the render again using the config it was last loaded with
+                let uuid = match registry_unlock.reload(&render_name) {
+                    Ok(uuid) => uuid,
+                    Err(e) => return load_error_response(e),
                 };
 
                 Response::json(&LoadResponse {
                     id: uuid.to_string()
                 })
             },
+            (GET) (/theme) => {
+                Response::json(&*registry_unlock.theme().read())
+            },
+            (POST) (/theme) => {
+                // Attempt to read the JSON input from the request body
+                let body = match read_json_body(request, max_config_bytes) {
+                    Ok(body) => body,
+                    Err(response) => return response,
+                };
+
+                // Colors are validated while parsing, so a bad theme is never applied
+                let theme: Theme = match serde_json::from_slice(&body) {
+                    Ok(theme) => theme,
+                    Err(e) => {
+                        let json_error = ErrJson::from_err(&e);
+                        return Response::json(&json_error).with_status_code(400);
+                    }
+                };
+
+                registry_unlock.set_theme(theme);
+                Response::empty_204()
+            },
+            (POST) (/push/{render_id: String}) => {
+                // Attempt to read the JSON input from the request body
+                let body = match read_json_body(request, max_config_bytes) {
+                    Ok(body) => body,
+                    Err(response) => return response,
+                };
+
+                // Handed to whichever render registered the route, see push_routes
+                match push_routes::push(&render_id, &body) {
+                    Some(Ok(())) => Response::empty_204(),
+                    Some(Err(e)) => Response::text(e).with_status_code(400),
+                    None => Response::empty_404(),
+                }
+            },
             (POST) (/layout_manager/select/{uuid: Uuid}) => {
                 try_or_404!(registry_unlock.select(uuid));
                 Response::empty_204()
             },
-            // If none of the other blocks matches the request, return a 404 response.
-            _ => Response::empty_404()
+            // If none of the other blocks matches the request, look for a route
+            // registered at runtime before returning a 404 response.
+            _ => {
+                if request.method() != "GET" {
+                    return Response::empty_404();
+                }
+
+                match push_routes::get(&request.url(), &|name| request.get_param(name)) {
+                    Some(value) => Response::json(&value),
+                    None => Response::empty_404(),
+                }
+            }
         )
     })
     .unwrap()
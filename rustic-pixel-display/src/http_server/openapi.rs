@@ -0,0 +1,382 @@
+//! The OpenAPI 3 description of the HTTP API, served at `GET /openapi.json` so
+//! clients can be generated from it.
+//!
+//! The document is maintained by hand, a route added to or changed in
+//! [`build_api_server_with_limit`](super::build_api_server_with_limit) has to be
+//! described here as well.
+
+use serde_json::{json, Value};
+use strum::IntoEnumIterator;
+
+use crate::font::Font;
+
+/// The version of the API described by the document
+const API_VERSION: &str = "0.1.0";
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+fn json_content(schema: Value) -> Value {
+    json!({ "application/json": { "schema": schema } })
+}
+
+/// A response with a JSON body of `schema`
+fn json_response(description: &str, schema: Value) -> Value {
+    json!({ "description": description, "content": json_content(schema) })
+}
+
+/// A response without a body
+fn empty_response(description: &str) -> Value {
+    json!({ "description": description })
+}
+
+/// A render config, its fields depend on the factory
+fn config_body() -> Value {
+    json!({
+        "required": true,
+        "description": "The config of the render, at most the configured maximum number of bytes",
+        "content": json_content(json!({ "type": "object" })),
+    })
+}
+
+fn uuid_parameter() -> Value {
+    json!({
+        "name": "uuid",
+        "in": "path",
+        "required": true,
+        "description": "The id of a loaded render",
+        "schema": { "type": "string", "format": "uuid" },
+    })
+}
+
+fn render_name_parameter() -> Value {
+    json!({
+        "name": "render_name",
+        "in": "path",
+        "required": true,
+        "description": "The name of a factory, as listed by the discovery endpoint",
+        "schema": { "type": "string" },
+    })
+}
+
+/// The responses of a request whose render config can be rejected
+fn config_responses(success_code: &str, success: Value) -> Value {
+    json!({
+        success_code: success,
+        "400": json_response(
+            "The config is not a JSON object or was rejected by the render",
+            schema_ref("ConfigError"),
+        ),
+        "404": empty_response("The factory or render does not exist"),
+        "413": empty_response("The body is larger than the configured maximum"),
+    })
+}
+
+/// Adds the response for `code` to `responses`
+fn with_response(mut responses: Value, code: &str, response: Value) -> Value {
+    responses[code] = response;
+    responses
+}
+
+fn paths() -> Value {
+    json!({
+        "/openapi.json": {
+            "get": {
+                "summary": "This document",
+                "responses": {
+                    "200": json_response("The OpenAPI description of the API", json!({ "type": "object" })),
+                },
+            },
+        },
+        "/render/active": {
+            "get": {
+                "summary": "Lists the loaded renders",
+                "responses": {
+                    "200": json_response(
+                        "The loaded renders",
+                        json!({ "type": "array", "items": schema_ref("RenderEntry") }),
+                    ),
+                },
+            },
+        },
+        "/render/{uuid}/config": {
+            "parameters": [uuid_parameter()],
+            "get": {
+                "summary": "The config the render was last loaded or reconfigured with",
+                "responses": {
+                    "200": json_response("The config of the render", json!({ "type": "object" })),
+                    "404": empty_response("The render does not exist"),
+                },
+            },
+            "post": {
+                "summary": "Applies a config to the render without unloading it",
+                "requestBody": config_body(),
+                "responses": with_response(
+                    config_responses("204", empty_response("The config was applied")),
+                    "501",
+                    json_response(
+                        "The render can't be reconfigured, it has to be unloaded and loaded again",
+                        schema_ref("ErrJson"),
+                    ),
+                ),
+            },
+        },
+        "/render/{uuid}/reset": {
+            "parameters": [uuid_parameter()],
+            "post": {
+                "summary": "Resets the state of the render",
+                "responses": {
+                    "204": empty_response("The render was reset"),
+                    "404": empty_response("The render does not exist"),
+                },
+            },
+        },
+        "/render/{uuid}": {
+            "parameters": [uuid_parameter()],
+            "delete": {
+                "summary": "Unloads the render",
+                "responses": {
+                    "204": empty_response("The render was unloaded"),
+                    "404": empty_response("The render does not exist"),
+                },
+            },
+        },
+        "/factory/discovery": {
+            "get": {
+                "summary": "Lists the factories renders can be loaded from",
+                "responses": {
+                    "200": json_response(
+                        "The factories",
+                        json!({ "type": "array", "items": schema_ref("FactoryInfo") }),
+                    ),
+                },
+            },
+        },
+        "/factory/status": {
+            "get": {
+                "summary": "Lists whether a render of each factory is loaded and selected",
+                "responses": {
+                    "200": json_response(
+                        "The status of the factories",
+                        json!({ "type": "array", "items": schema_ref("FactoryStatusEntry") }),
+                    ),
+                },
+            },
+        },
+        "/factory/load/{render_name}": {
+            "parameters": [render_name_parameter()],
+            "post": {
+                "summary": "Loads a render from the factory",
+                "requestBody": config_body(),
+                "responses": config_responses(
+                    "200",
+                    json_response("The render was loaded", schema_ref("LoadResponse")),
+                ),
+            },
+        },
+        "/factory/preview/{render_name}": {
+            "parameters": [
+                render_name_parameter(),
+                {
+                    "name": "width",
+                    "in": "query",
+                    "description": "The width of the preview",
+                    "schema": { "type": "integer", "minimum": 1, "maximum": super::MAX_PREVIEW_DIMENSION, "default": super::DEFAULT_PREVIEW_SIZE.width },
+                },
+                {
+                    "name": "height",
+                    "in": "query",
+                    "description": "The height of the preview",
+                    "schema": { "type": "integer", "minimum": 1, "maximum": super::MAX_PREVIEW_DIMENSION, "default": super::DEFAULT_PREVIEW_SIZE.height },
+                },
+            ],
+            "post": {
+                "summary": "Draws a single frame of a render without loading it",
+                "requestBody": config_body(),
+                "responses": with_response(
+                    config_responses(
+                        "200",
+                        json!({
+                            "description": "The frame",
+                            "content": { "image/png": { "schema": { "type": "string", "format": "binary" } } },
+                        }),
+                    ),
+                    "500",
+                    json_response("The frame could not be encoded", schema_ref("ErrJson")),
+                ),
+            },
+        },
+        "/factory/reload/{render_name}": {
+            "parameters": [render_name_parameter()],
+            "post": {
+                "summary": "Loads the render of the factory again with the config it was last loaded with",
+                "responses": {
+                    "200": json_response("The render was loaded", schema_ref("LoadResponse")),
+                    "400": json_response("The render could not be loaded", schema_ref("ConfigError")),
+                    "404": empty_response("The factory does not exist or was never loaded"),
+                },
+            },
+        },
+        "/fonts": {
+            "get": {
+                "summary": "Lists the values the font fields of render configs accept",
+                "responses": {
+                    "200": json_response(
+                        "The fonts",
+                        json!({ "type": "array", "items": schema_ref("Font") }),
+                    ),
+                },
+            },
+        },
+        "/theme": {
+            "get": {
+                "summary": "The colors the display is drawn in",
+                "responses": {
+                    "200": json_response("The theme", schema_ref("Theme")),
+                },
+            },
+            "post": {
+                "summary": "Changes the colors the display is drawn in",
+                "requestBody": {
+                    "required": true,
+                    "content": json_content(schema_ref("Theme")),
+                },
+                "responses": {
+                    "204": empty_response("The theme was applied"),
+                    "400": json_response("The theme is not valid", schema_ref("ErrJson")),
+                    "413": empty_response("The body is larger than the configured maximum"),
+                },
+            },
+        },
+        "/push/{render_id}": {
+            "parameters": [{
+                "name": "render_id",
+                "in": "path",
+                "required": true,
+                "description": "The id a render registered its push route with",
+                "schema": { "type": "string" },
+            }],
+            "post": {
+                "summary": "Pushes a JSON body to a render",
+                "requestBody": {
+                    "required": true,
+                    "content": json_content(json!({})),
+                },
+                "responses": {
+                    "204": empty_response("The render accepted the body"),
+                    "400": {
+                        "description": "The render rejected the body",
+                        "content": { "text/plain": { "schema": { "type": "string" } } },
+                    },
+                    "404": empty_response("No render registered the route"),
+                    "413": empty_response("The body is larger than the configured maximum"),
+                },
+            },
+        },
+        "/layout_manager/select/{uuid}": {
+            "parameters": [uuid_parameter()],
+            "post": {
+                "summary": "Shows the render on the display",
+                "responses": {
+                    "204": empty_response("The render was selected"),
+                    "404": empty_response("The render does not exist"),
+                },
+            },
+        },
+    })
+}
+
+fn hex_color() -> Value {
+    json!({ "type": "string", "pattern": "^#[0-9A-Fa-f]{6}$" })
+}
+
+fn schemas() -> Value {
+    let fonts = serde_json::to_value(Font::iter().collect::<Vec<_>>()).unwrap_or_default();
+
+    json!({
+        "RenderEntry": {
+            "type": "object",
+            "required": ["id", "factory_name", "description"],
+            "properties": {
+                "id": { "type": "string", "format": "uuid" },
+                "factory_name": { "type": "string" },
+                "description": { "type": "string" },
+            },
+        },
+        "FactoryInfo": {
+            "type": "object",
+            "required": ["name", "description", "supported_colors", "example_config"],
+            "properties": {
+                "name": { "type": "string" },
+                "description": { "type": "string" },
+                "supported_colors": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": ["Rgb888", "Rgb565", "Rgb555"] },
+                },
+                "example_config": { "type": "object", "nullable": true },
+            },
+        },
+        "FactoryStatusEntry": {
+            "type": "object",
+            "required": ["name", "loaded", "selected"],
+            "properties": {
+                "name": { "type": "string" },
+                "loaded": { "type": "boolean" },
+                "selected": { "type": "boolean" },
+            },
+        },
+        "LoadResponse": {
+            "type": "object",
+            "required": ["id"],
+            "properties": {
+                "id": { "type": "string", "format": "uuid" },
+            },
+        },
+        "ConfigError": {
+            "type": "object",
+            "required": ["kind", "description"],
+            "properties": {
+                "kind": { "type": "string", "enum": ["encoding", "syntax", "type", "config"] },
+                "description": { "type": "string" },
+                "line": { "type": "integer", "nullable": true },
+                "column": { "type": "integer", "nullable": true },
+            },
+        },
+        "ErrJson": {
+            "type": "object",
+            "required": ["description"],
+            "properties": {
+                "description": { "type": "string" },
+                "cause": { "allOf": [schema_ref("ErrJson")], "nullable": true },
+            },
+        },
+        "Font": {
+            "type": "string",
+            "enum": fonts,
+        },
+        "Theme": {
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "background": hex_color(),
+            },
+        },
+    })
+}
+
+/// The OpenAPI 3 document describing every route of the HTTP API
+pub fn document() -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "rustic-pixel-display",
+            "description": "Loads, configures and selects the renders shown on the display",
+            "version": API_VERSION,
+        },
+        "paths": paths(),
+        "components": {
+            "schemas": schemas(),
+        },
+    })
+}
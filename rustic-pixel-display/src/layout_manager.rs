@@ -1,12 +1,26 @@
-use crate::render::{Render, SubCanvas};
+use crate::render::{
+    BufferCanvas, Render, RenderError, RenderFactories, RenderFactory, SubCanvas, SyncRender,
+};
+use anyhow::Result;
 use embedded_graphics::{
     pixelcolor::Rgb888,
-    prelude::{DrawTarget, Point, RgbColor, Size},
+    prelude::{DrawTarget, OriginDimensions, Point, RgbColor, Size},
 };
-use serde::Serialize;
-use std::convert::Infallible;
+use log::warn;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, io::Read, marker::PhantomData, time::Instant};
+
+type SubRender<D> = Box<dyn for<'a> SyncRender<SubCanvas<'a, D>>>;
 
-type SubRender<D> = Box<dyn for<'a> Render<SubCanvas<'a, D>>>;
+/// Which edge of the canvas the status bar of a
+/// [`CommonLayout::WithStatusBar`] is placed along.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusBarPosition {
+    #[default]
+    Top,
+    Bottom,
+}
 
 pub enum CommonLayout<D>
 where
@@ -27,6 +41,14 @@ where
         bottom_left: Option<SubRender<D>>,
         bottom_right: Option<SubRender<D>>,
     },
+    /// A bar `bar_height` pixels tall spanning the width of the canvas, with
+    /// the body taking up the rest of the canvas.
+    WithStatusBar {
+        bar_height: u32,
+        position: StatusBarPosition,
+        bar: Option<SubRender<D>>,
+        body: Option<SubRender<D>>,
+    },
 }
 
 #[derive(Clone, Copy, Serialize)]
@@ -35,6 +57,10 @@ pub enum LayoutType {
     SplitWidth,
     SplitHeight,
     Split4,
+    WithStatusBar {
+        bar_height: u32,
+        position: StatusBarPosition,
+    },
 }
 
 impl<D> From<&CommonLayout<D>> for LayoutType
@@ -47,6 +73,158 @@ where
             CommonLayout::SplitWidth { .. } => Self::SplitWidth,
             CommonLayout::SplitHeight { .. } => Self::SplitHeight,
             CommonLayout::Split4 { .. } => Self::Split4,
+            CommonLayout::WithStatusBar {
+                bar_height,
+                position,
+                ..
+            } => Self::WithStatusBar {
+                bar_height: *bar_height,
+                position: *position,
+            },
+        }
+    }
+}
+
+impl LayoutType {
+    /// Computes the offset and size of each region of the layout for a canvas
+    /// of `canvas_size`. The regions are in the same order as the renders are
+    /// declared in [`CommonLayout`].
+    ///
+    /// When a side can't be split evenly the second half gets the extra pixel,
+    /// so regions of a tiny canvas may be zero sized but never underflow.
+    fn regions(&self, canvas_size: Size) -> Vec<(Point, Size)> {
+        match self {
+            LayoutType::Single => vec![(Point::zero(), canvas_size)],
+            LayoutType::SplitWidth => {
+                let left_width = canvas_size.width / 2;
+
+                vec![
+                    (
+                        Point::zero(),
+                        Size {
+                            width: left_width,
+                            ..canvas_size
+                        },
+                    ),
+                    (
+                        Point {
+                            x: left_width as i32,
+                            y: 0,
+                        },
+                        Size {
+                            width: canvas_size.width - left_width,
+                            ..canvas_size
+                        },
+                    ),
+                ]
+            }
+            LayoutType::SplitHeight => {
+                let top_height = canvas_size.height / 2;
+
+                vec![
+                    (
+                        Point::zero(),
+                        Size {
+                            height: top_height,
+                            ..canvas_size
+                        },
+                    ),
+                    (
+                        Point {
+                            x: 0,
+                            y: top_height as i32,
+                        },
+                        Size {
+                            height: canvas_size.height - top_height,
+                            ..canvas_size
+                        },
+                    ),
+                ]
+            }
+            LayoutType::Split4 => {
+                let left_width = canvas_size.width / 2;
+                let right_width = canvas_size.width - left_width;
+                let top_height = canvas_size.height / 2;
+                let bottom_height = canvas_size.height - top_height;
+
+                vec![
+                    (Point::zero(), Size::new(left_width, top_height)),
+                    (
+                        Point {
+                            x: left_width as i32,
+                            y: 0,
+                        },
+                        Size::new(right_width, top_height),
+                    ),
+                    (
+                        Point {
+                            x: 0,
+                            y: top_height as i32,
+                        },
+                        Size::new(left_width, bottom_height),
+                    ),
+                    (
+                        Point {
+                            x: left_width as i32,
+                            y: top_height as i32,
+                        },
+                        Size::new(right_width, bottom_height),
+                    ),
+                ]
+            }
+            LayoutType::WithStatusBar {
+                bar_height,
+                position,
+            } => {
+                let bar_height = (*bar_height).min(canvas_size.height);
+                let bar_size = Size {
+                    height: bar_height,
+                    ..canvas_size
+                };
+                let body_size = Size {
+                    height: canvas_size.height - bar_height,
+                    ..canvas_size
+                };
+
+                match position {
+                    StatusBarPosition::Top => vec![
+                        (Point::zero(), bar_size),
+                        (
+                            Point {
+                                x: 0,
+                                y: bar_height as i32,
+                            },
+                            body_size,
+                        ),
+                    ],
+                    StatusBarPosition::Bottom => vec![
+                        (
+                            Point {
+                                x: 0,
+                                y: body_size.height as i32,
+                            },
+                            bar_size,
+                        ),
+                        (Point::zero(), body_size),
+                    ],
+                }
+            }
+        }
+    }
+}
+
+/// Logs each region that is zero sized, since those are skipped when rendering.
+///
+/// Layouts created before the size of the canvas is known are zero sized, so
+/// nothing is logged for them.
+fn warn_zero_sized(canvas_size: Size, regions: &[(Point, Size)]) {
+    if canvas_size.width == 0 || canvas_size.height == 0 {
+        return;
+    }
+
+    for (index, (offset, size)) in regions.iter().enumerate() {
+        if size.width == 0 || size.height == 0 {
+            warn!("Layout region {index} at {offset:?} is {size:?} and will not be drawn");
         }
     }
 }
@@ -58,6 +236,14 @@ where
     size: Size,
     offset: Point,
     render: Option<SubRender<D>>,
+
+    /// Regions are drawn in ascending order of `z`, so a region with a higher
+    /// `z` is drawn on top of the regions it overlaps
+    z: i32,
+
+    /// Whether the region is one of the common layout, whose offset and size
+    /// are recomputed when the layout is resized
+    common: bool,
 }
 
 pub struct LayoutManager<D>
@@ -66,6 +252,7 @@ where
 {
     layouts: Vec<Layout<D>>,
     layout_type: LayoutType,
+    canvas_size: Size,
 }
 
 impl<D> LayoutManager<D>
@@ -76,126 +263,95 @@ where
         common_layout: CommonLayout<D>,
         canvas_size: Size,
     ) -> LayoutManager<D> {
-        let layout_type = (&common_layout).into();
-        let layouts = match common_layout {
-            CommonLayout::Single(render) => {
-                vec![Layout {
-                    size: canvas_size,
-                    offset: Point::zero(),
-                    render,
-                }]
-            }
-            CommonLayout::SplitWidth { left, right } => {
-                let split_width = canvas_size.width / 2;
-
-                vec![
-                    Layout {
-                        size: Size {
-                            width: split_width,
-                            ..canvas_size
-                        },
-                        offset: Point::zero(),
-                        render: left,
-                    },
-                    Layout {
-                        size: Size {
-                            width: split_width,
-                            ..canvas_size
-                        },
-                        offset: Point {
-                            x: split_width as i32,
-                            y: 0,
-                        },
-                        render: right,
-                    },
-                ]
-            }
-            CommonLayout::SplitHeight { top, bottom } => {
-                let split_height = canvas_size.height / 2;
-
-                vec![
-                    Layout {
-                        size: Size {
-                            height: split_height,
-                            ..canvas_size
-                        },
-                        offset: Point::zero(),
-                        render: top,
-                    },
-                    Layout {
-                        size: Size {
-                            height: split_height,
-                            ..canvas_size
-                        },
-                        offset: Point {
-                            x: 0,
-                            y: split_height as i32,
-                        },
-                        render: bottom,
-                    },
-                ]
-            }
+        let layout_type: LayoutType = (&common_layout).into();
+        let renders = match common_layout {
+            CommonLayout::Single(render) => vec![render],
+            CommonLayout::SplitWidth { left, right } => vec![left, right],
+            CommonLayout::SplitHeight { top, bottom } => vec![top, bottom],
             CommonLayout::Split4 {
                 top_left,
                 top_right,
                 bottom_left,
                 bottom_right,
-            } => {
-                let split_width = canvas_size.width / 2;
-                let split_height = canvas_size.height / 2;
-
-                vec![
-                    Layout {
-                        size: Size {
-                            width: split_width,
-                            height: split_height,
-                        },
-                        offset: Point::zero(),
-                        render: top_left,
-                    },
-                    Layout {
-                        size: Size {
-                            width: split_width,
-                            height: split_height,
-                        },
-                        offset: Point {
-                            x: 0,
-                            y: split_width as i32,
-                        },
-                        render: top_right,
-                    },
-                    Layout {
-                        size: Size {
-                            width: split_width,
-                            height: split_height,
-                        },
-                        offset: Point {
-                            x: split_height as i32,
-                            y: 0,
-                        },
-                        render: bottom_left,
-                    },
-                    Layout {
-                        size: Size {
-                            width: split_width,
-                            height: split_height,
-                        },
-                        offset: Point {
-                            x: split_height as i32,
-                            y: split_width as i32,
-                        },
-                        render: bottom_right,
-                    },
-                ]
-            }
+            } => vec![top_left, top_right, bottom_left, bottom_right],
+            CommonLayout::WithStatusBar { bar, body, .. } => vec![bar, body],
         };
 
+        let regions = layout_type.regions(canvas_size);
+        warn_zero_sized(canvas_size, &regions);
+
+        let layouts = regions
+            .into_iter()
+            .zip(renders)
+            .map(|((offset, size), render)| Layout {
+                size,
+                offset,
+                render,
+                z: 0,
+                common: true,
+            })
+            .collect();
+
         Self {
             layouts,
             layout_type,
+            canvas_size,
         }
     }
 
+    /// Adds a region of `size` at `offset` that is drawn by `render`, on top of
+    /// the regions of the common layout. See [`add_render_z`](Self::add_render_z).
+    pub fn add_render(&mut self, size: Size, offset: Point, render: SubRender<D>) {
+        self.add_render_z(size, offset, 0, render);
+    }
+
+    /// Adds a region of `size` at `offset` that is drawn by `render`.
+    ///
+    /// The region may overlap other regions, in which case the region with the
+    /// highest `z` is drawn last and ends up on top. Regions with the same `z`
+    /// are drawn in the order they were added, after the regions of the common
+    /// layout, which have a `z` of zero. Added regions keep their offset and
+    /// size when the layout is [resized](Self::resize).
+    pub fn add_render_z(&mut self, size: Size, offset: Point, z: i32, render: SubRender<D>) {
+        // Kept sorted by z so the regions can be drawn in order, after every
+        // region with the same z
+        let index = self.layouts.partition_point(|layout| layout.z <= z);
+
+        self.layouts.insert(
+            index,
+            Layout {
+                size,
+                offset,
+                render: Some(render),
+                z,
+                common: false,
+            },
+        );
+    }
+
+    /// Recomputes the regions of the layout for a canvas of `canvas_size`.
+    pub fn resize(&mut self, canvas_size: Size) {
+        let Self {
+            layouts,
+            layout_type,
+            ..
+        } = self;
+
+        let regions = layout_type.regions(canvas_size);
+        warn_zero_sized(canvas_size, &regions);
+
+        // Added regions may be drawn before the common ones, so only the
+        // common ones are matched up with the regions
+        let common_layouts = layouts.iter_mut().filter(|layout| layout.common);
+
+        for (layout, (offset, size)) in common_layouts.zip(regions) {
+            layout.offset = offset;
+            layout.size = size;
+        }
+
+        self.canvas_size = canvas_size;
+    }
+
     pub fn len(&self) -> usize {
         self.layouts.len()
     }
@@ -207,6 +363,10 @@ where
     pub fn layout(&self) -> LayoutType {
         self.layout_type
     }
+
+    pub fn canvas_size(&self) -> Size {
+        self.canvas_size
+    }
 }
 
 impl<D> Render<D> for LayoutManager<D>
@@ -214,17 +374,28 @@ where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
 {
     fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
-        for layout in self.layouts.iter() {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        // Regions are kept sorted by z as they are added
+        for layout in &self.layouts {
             let Layout {
                 size,
                 offset,
                 render,
+                ..
             } = layout;
 
+            // Nothing can be drawn in a region that is too small to split into
+            if size.width == 0 || size.height == 0 {
+                continue;
+            }
+
             let mut sub_canvas = SubCanvas::new(*offset, *size, canvas);
 
             if let Some(render) = render {
-                render.render(&mut sub_canvas)?;
+                render.render_at(&mut sub_canvas, now)?;
             } else {
                 sub_canvas.clear(Rgb888::BLACK)?;
             }
@@ -232,4 +403,296 @@ where
 
         Ok(())
     }
+
+    fn on_select(&self) {
+        for render in self
+            .layouts
+            .iter()
+            .filter_map(|layout| layout.render.as_ref())
+        {
+            render.on_select();
+        }
+    }
+
+    fn on_deselect(&self) {
+        for render in self
+            .layouts
+            .iter()
+            .filter_map(|layout| layout.render.as_ref())
+        {
+            render.on_deselect();
+        }
+    }
+
+    fn reset(&self) {
+        for render in self
+            .layouts
+            .iter()
+            .filter_map(|layout| layout.render.as_ref())
+        {
+            render.reset();
+        }
+    }
+}
+
+/// The render and configuration used to fill a single region of a layout.
+#[derive(Clone, Debug, Deserialize)]
+pub struct LayoutRegionConfig {
+    /// The name of the [`RenderFactory`] used to construct the render
+    pub factory: String,
+
+    /// The configuration provided to the [`RenderFactory`]
+    pub config: serde_json::Value,
+}
+
+/// Configuration for the [`LayoutFactory`].
+///
+/// Regions that are omitted (or `null`) are left blank. For example:
+///
+/// ```json
+/// {
+///   "layout": "SplitHeight",
+///   "top": { "factory": "Weather", "config": { ... } },
+///   "bottom": { "factory": "UpcomingArrivals", "config": { ... } }
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "layout")]
+pub enum LayoutConfig {
+    Single {
+        render: Option<LayoutRegionConfig>,
+    },
+    SplitWidth {
+        left: Option<LayoutRegionConfig>,
+        right: Option<LayoutRegionConfig>,
+    },
+    SplitHeight {
+        top: Option<LayoutRegionConfig>,
+        bottom: Option<LayoutRegionConfig>,
+    },
+    Split4 {
+        top_left: Option<LayoutRegionConfig>,
+        top_right: Option<LayoutRegionConfig>,
+        bottom_left: Option<LayoutRegionConfig>,
+        bottom_right: Option<LayoutRegionConfig>,
+    },
+    WithStatusBar {
+        bar_height: u32,
+        #[serde(default)]
+        position: StatusBarPosition,
+        bar: Option<LayoutRegionConfig>,
+        body: Option<LayoutRegionConfig>,
+    },
+}
+
+/// Draws a render constructed for a [`BufferCanvas`] onto a layout region.
+///
+/// Factories construct renders for a single canvas type, while the regions of
+/// a [`LayoutManager`] need renders that can draw onto any [`SubCanvas`]. The
+/// render is drawn into an intermediate buffer which is then copied onto the
+/// region.
+struct BufferedSubRender {
+    render: Box<dyn SyncRender<BufferCanvas>>,
+    buffer: Mutex<BufferCanvas>,
+}
+
+impl<'a, D> Render<SubCanvas<'a, D>> for BufferedSubRender
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut SubCanvas<'a, D>) -> Result<(), Infallible> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut SubCanvas<'a, D>, now: Instant) -> Result<(), Infallible> {
+        let mut buffer = self.buffer.lock();
+
+        if buffer.size() != canvas.size() {
+            *buffer = BufferCanvas::new(canvas.size());
+        }
+
+        buffer.clear(Rgb888::BLACK)?;
+        self.render.render_at(&mut *buffer, now)?;
+        buffer.draw_to(canvas, Point::zero())
+    }
+
+    fn on_select(&self) {
+        self.render.on_select();
+    }
+
+    fn on_deselect(&self) {
+        self.render.on_deselect();
+    }
+
+    fn describe(&self) -> String {
+        self.render.describe()
+    }
+
+    fn has_content(&self) -> bool {
+        self.render.has_content()
+    }
+
+    fn reset(&self) {
+        self.render.reset();
+    }
+}
+
+/// A [`LayoutManager`] that adapts its regions to the canvas it is drawn on.
+///
+/// Renders loaded through a [`RenderFactory`] don't know the size of the
+/// canvas until they are first drawn, so the regions are recomputed whenever
+/// the size of the canvas changes.
+struct CanvasSizedLayout<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    layout_manager: Mutex<LayoutManager<D>>,
+}
+
+impl<D> Render<D> for CanvasSizedLayout<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let mut layout_manager = self.layout_manager.lock();
+        let canvas_size = canvas.bounding_box().size;
+
+        if layout_manager.canvas_size() != canvas_size {
+            layout_manager.resize(canvas_size);
+        }
+
+        layout_manager.render_at(canvas, now)
+    }
+
+    fn on_select(&self) {
+        self.layout_manager.lock().on_select();
+    }
+
+    fn on_deselect(&self) {
+        self.layout_manager.lock().on_deselect();
+    }
+
+    fn reset(&self) {
+        self.layout_manager.lock().reset();
+    }
+}
+
+/// Constructs a [`LayoutManager`] whose regions are filled by other renders.
+///
+/// The renders of each region are constructed by the factories in `F`, which
+/// is typically the same `#[derive(RenderFactories)]` enum that the registry
+/// was created with, instantiated for a [`BufferCanvas`].
+pub struct LayoutFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<(D, F)>,
+}
+
+impl<D, F> Default for LayoutFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D, F> LayoutFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible> + 'static,
+    F: RenderFactories<BufferCanvas>,
+{
+    fn load_region(
+        factories: &[F],
+        region: Option<LayoutRegionConfig>,
+    ) -> Result<Option<SubRender<D>>, RenderError> {
+        let region = match region {
+            Some(region) => region,
+            None => return Ok(None),
+        };
+
+        let factory = factories
+            .iter()
+            .find(|factory| factory.render_name() == region.factory)
+            .ok_or_else(|| {
+                RenderError::Config(format!("Factory \"{}\" was not found", region.factory))
+            })?;
+
+        let config = serde_json::to_vec(&region.config)?;
+        let render = factory.load_from_config(config.as_slice())?;
+
+        Ok(Some(Box::new(BufferedSubRender {
+            render,
+            buffer: Mutex::new(BufferCanvas::new(Size::zero())),
+        })))
+    }
+}
+
+impl<D, F> RenderFactory<D> for LayoutFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible> + 'static,
+    F: RenderFactories<BufferCanvas>,
+{
+    fn render_name(&self) -> &'static str {
+        "Layout"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Splits the display into regions that are each drawn by another render"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: LayoutConfig = serde_json::from_reader(reader)?;
+        let factories = F::factories();
+
+        let common_layout = match config {
+            LayoutConfig::Single { render } => {
+                CommonLayout::Single(Self::load_region(&factories, render)?)
+            }
+            LayoutConfig::SplitWidth { left, right } => CommonLayout::SplitWidth {
+                left: Self::load_region(&factories, left)?,
+                right: Self::load_region(&factories, right)?,
+            },
+            LayoutConfig::SplitHeight { top, bottom } => CommonLayout::SplitHeight {
+                top: Self::load_region(&factories, top)?,
+                bottom: Self::load_region(&factories, bottom)?,
+            },
+            LayoutConfig::Split4 {
+                top_left,
+                top_right,
+                bottom_left,
+                bottom_right,
+            } => CommonLayout::Split4 {
+                top_left: Self::load_region(&factories, top_left)?,
+                top_right: Self::load_region(&factories, top_right)?,
+                bottom_left: Self::load_region(&factories, bottom_left)?,
+                bottom_right: Self::load_region(&factories, bottom_right)?,
+            },
+            LayoutConfig::WithStatusBar {
+                bar_height,
+                position,
+                bar,
+                body,
+            } => CommonLayout::WithStatusBar {
+                bar_height,
+                position,
+                bar: Self::load_region(&factories, bar)?,
+                body: Self::load_region(&factories, body)?,
+            },
+        };
+
+        Ok(Box::new(CanvasSizedLayout {
+            layout_manager: Mutex::new(LayoutManager::from_common_layout(
+                common_layout,
+                Size::zero(),
+            )),
+        }))
+    }
 }
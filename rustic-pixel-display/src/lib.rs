@@ -1,10 +1,18 @@
 // TODO: Remove when more mature
 #![allow(dead_code)]
 
+pub mod clock;
 pub mod config;
 pub mod driver;
+pub mod fallback;
+pub mod font;
 #[cfg(feature = "http_server")]
 pub mod http_server;
 pub mod layout_manager;
+pub mod push_routes;
+pub mod quiet_hours;
 pub mod registry;
 pub mod render;
+pub mod schedule;
+pub mod scrolling_feed;
+pub mod theme;
@@ -0,0 +1,153 @@
+use anyhow::{anyhow, Result};
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Handles the body of a push, returning why it was rejected
+pub type PushHandler = Arc<dyn Fn(&[u8]) -> Result<(), String> + Send + Sync>;
+
+/// Answers a `GET` with a JSON body, given a lookup of the query parameters of
+/// the request
+pub type GetHandler = Arc<dyn Fn(&dyn Fn(&str) -> Option<String>) -> Value + Send + Sync>;
+
+struct Route<H> {
+    id: String,
+
+    /// Tells registrations of the same id apart, so dropping a stale
+    /// [`PushRoute`] or [`GetRoute`] can't remove the route that replaced it
+    token: u64,
+
+    handler: H,
+}
+
+/// The routes renders are pushed to through `POST /push/{id}` of the HTTP API.
+///
+/// Factories are constructed with [`Default`] and have no handle to the
+/// server, so renders register their routes here and the server looks them up
+/// for every push. Without the HTTP server nothing pushes to them.
+static ROUTES: Mutex<Vec<Route<PushHandler>>> = Mutex::new(Vec::new());
+
+/// The paths served to `GET` requests that none of the routes of the HTTP API
+/// match, registered the same way as [`ROUTES`].
+static GET_ROUTES: Mutex<Vec<Route<GetHandler>>> = Mutex::new(Vec::new());
+
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(0);
+
+/// A route registered with [`register_push_route`], removed when dropped.
+pub struct PushRoute {
+    id: String,
+    token: u64,
+}
+
+impl PushRoute {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Drop for PushRoute {
+    fn drop(&mut self) {
+        ROUTES.lock().retain(|route| route.token != self.token);
+    }
+}
+
+/// Calls `handler` with the body of every push to `id` until the returned
+/// [`PushRoute`] is dropped.
+///
+/// Returns an error if another route is already registered as `id`.
+pub fn register_push_route(id: &str, handler: PushHandler) -> Result<PushRoute> {
+    let mut routes = ROUTES.lock();
+
+    if routes.iter().any(|route| route.id == id) {
+        return Err(anyhow!("A render is already pushed to as \"{id}\""));
+    }
+
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::Relaxed);
+    routes.push(Route {
+        id: id.to_owned(),
+        token,
+        handler,
+    });
+
+    Ok(PushRoute {
+        id: id.to_owned(),
+        token,
+    })
+}
+
+/// Hands `body` to the route registered as `id`.
+///
+/// Returns `None` if no route is registered as `id`, otherwise whether the
+/// route accepted the body.
+pub fn push(id: &str, body: &[u8]) -> Option<Result<(), String>> {
+    // Don't hold the lock while the handler runs, so it can't block
+    // registering or dropping other routes
+    let handler = ROUTES
+        .lock()
+        .iter()
+        .find(|route| route.id == id)
+        .map(|route| route.handler.clone())?;
+
+    Some(handler(body))
+}
+
+/// A route registered with [`register_get_route`], removed when dropped.
+pub struct GetRoute {
+    path: String,
+    token: u64,
+}
+
+impl GetRoute {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Drop for GetRoute {
+    fn drop(&mut self) {
+        GET_ROUTES.lock().retain(|route| route.token != self.token);
+    }
+}
+
+/// Answers every `GET` of `path` with the body returned by `handler` until the
+/// returned [`GetRoute`] is dropped. `path` can't shadow a route of the HTTP
+/// API, those are matched first.
+///
+/// Returns an error if another route is already registered as `path`.
+pub fn register_get_route(path: &str, handler: GetHandler) -> Result<GetRoute> {
+    let mut routes = GET_ROUTES.lock();
+
+    if routes.iter().any(|route| route.id == path) {
+        return Err(anyhow!("A route is already served at \"{path}\""));
+    }
+
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::Relaxed);
+    routes.push(Route {
+        id: path.to_owned(),
+        token,
+        handler,
+    });
+
+    Ok(GetRoute {
+        path: path.to_owned(),
+        token,
+    })
+}
+
+/// Answers a `GET` of `path` with the route registered as `path`, looking up
+/// its query parameters with `param`.
+///
+/// Returns `None` if no route is registered as `path`.
+pub fn get(path: &str, param: &dyn Fn(&str) -> Option<String>) -> Option<Value> {
+    // Don't hold the lock while the handler runs, same as for pushes
+    let handler = GET_ROUTES
+        .lock()
+        .iter()
+        .find(|route| route.id == path)
+        .map(|route| route.handler.clone())?;
+
+    Some(handler(param))
+}
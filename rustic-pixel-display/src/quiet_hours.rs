@@ -0,0 +1,238 @@
+use crate::{
+    clock::{SharedClock, SystemClock},
+    layout_manager::LayoutRegionConfig,
+    render::{Render, RenderError, RenderFactories, RenderFactory, SyncRender},
+    schedule::TimeRange,
+};
+use anyhow::Result;
+use chrono::NaiveTime;
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, RgbColor},
+    primitives::Rectangle,
+};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::{convert::Infallible, io::Read, marker::PhantomData, time::Instant};
+
+/// Configuration for the [`QuietHoursFactory`].
+///
+/// The panel is blank while the local time is within any of the ranges, and
+/// shows the render otherwise. For example:
+///
+/// ```json
+/// {
+///   "ranges": [["01:00", "06:00"], ["13:00", "14:00"]],
+///   "factory": "Weather",
+///   "config": { ... }
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct QuietHoursConfig {
+    pub ranges: Vec<TimeRange>,
+
+    #[serde(flatten)]
+    pub render: LayoutRegionConfig,
+}
+
+/// Turns the panel fully off while the local time is within any of the quiet
+/// [`TimeRange`]s, and draws the inner render otherwise.
+///
+/// Ranges that end before they start wrap past midnight, i.e. 22:00 to 06:00.
+/// The inner render is deselected for the duration of the quiet hours so it
+/// can stop polling, and selected again once they are over. Wrap any other
+/// wrappers of the render so that nothing at all is drawn.
+pub struct QuietHours<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    render: Box<dyn SyncRender<D>>,
+    ranges: Vec<TimeRange>,
+
+    /// Whether it was quiet when last drawn, `None` while not displayed
+    active: Mutex<Option<bool>>,
+
+    clock: SharedClock,
+}
+
+impl<D> QuietHours<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    pub fn new(render: Box<dyn SyncRender<D>>, ranges: Vec<TimeRange>) -> Self {
+        Self {
+            render,
+            ranges,
+            active: Mutex::new(None),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Reads the time of day from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Whether `time` is within any of the quiet ranges
+    pub fn is_quiet_at(&self, time: NaiveTime) -> bool {
+        self.ranges.iter().any(|range| range.contains(time))
+    }
+
+    fn is_quiet(&self) -> bool {
+        self.is_quiet_at(self.clock.now().time())
+    }
+}
+
+impl<D> Render<D> for QuietHours<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let quiet = self.is_quiet();
+        let mut active = self.active.lock();
+
+        // Let the inner render stop polling while it is not shown
+        if *active != Some(quiet) {
+            match (*active, quiet) {
+                (Some(false), true) => self.render.on_deselect(),
+                (Some(true) | None, false) => self.render.on_select(),
+                _ => {}
+            }
+
+            *active = Some(quiet);
+        }
+
+        if quiet {
+            canvas.clear(Rgb888::BLACK)
+        } else {
+            self.render.render_at(canvas, now)
+        }
+    }
+
+    fn on_select(&self) {
+        let quiet = self.is_quiet();
+
+        if !quiet {
+            self.render.on_select();
+        }
+
+        *self.active.lock() = Some(quiet);
+    }
+
+    fn on_deselect(&self) {
+        if self.active.lock().take() == Some(false) {
+            self.render.on_deselect();
+        }
+    }
+
+    fn background_color(&self) -> Option<Rgb888> {
+        if self.is_quiet() {
+            Some(Rgb888::BLACK)
+        } else {
+            self.render.background_color()
+        }
+    }
+
+    fn dirty_region(&self) -> Option<Rectangle> {
+        let quiet = self.is_quiet();
+
+        // The whole canvas changes when the quiet hours start or end
+        if *self.active.lock() != Some(quiet) {
+            return None;
+        }
+
+        if quiet {
+            Some(Rectangle::zero())
+        } else {
+            self.render.dirty_region()
+        }
+    }
+
+    fn describe(&self) -> String {
+        if self.is_quiet() {
+            "Quiet hours".to_owned()
+        } else {
+            self.render.describe()
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        // A blank panel is the content during quiet hours, a fallback must not
+        // light the panel up with something else
+        self.is_quiet() || self.render.has_content()
+    }
+
+    fn reset(&self) {
+        self.render.reset();
+    }
+
+    fn reconfigure(&self, config: &mut dyn Read) -> Result<(), RenderError> {
+        self.render.reconfigure(config)
+    }
+}
+
+/// Constructs a [`QuietHours`] whose render is constructed by the factories in
+/// `F`, typically the same `#[derive(RenderFactories)]` enum that the registry
+/// was created with.
+pub struct QuietHoursFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<(D, F)>,
+}
+
+impl<D, F> Default for QuietHoursFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D, F> RenderFactory<D> for QuietHoursFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible> + 'static,
+    F: RenderFactories<D>,
+{
+    fn render_name(&self) -> &'static str {
+        "QuietHours"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Turns the panel off during the configured hours"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: QuietHoursConfig = serde_json::from_reader(reader)?;
+        let factories = F::factories();
+
+        if config.ranges.is_empty() {
+            return Err(RenderError::Config(
+                "Need to provide at least one range".to_owned(),
+            ));
+        }
+
+        let factory = factories
+            .iter()
+            .find(|factory| factory.render_name() == config.render.factory)
+            .ok_or_else(|| {
+                RenderError::Config(format!(
+                    "Factory \"{}\" was not found",
+                    config.render.factory
+                ))
+            })?;
+
+        let render_config = serde_json::to_vec(&config.render.config)?;
+        let render = factory.load_from_config(render_config.as_slice())?;
+
+        Ok(Box::new(QuietHours::new(render, config.ranges)))
+    }
+}
@@ -1,52 +1,135 @@
-use crate::render::{Render, RenderFactory};
+use crate::{
+    render::{BufferCanvas, Render, RenderError, RenderFactory, SubCanvas, SyncRender},
+    theme::{SharedTheme, Theme},
+};
 use anyhow::Result;
-use embedded_graphics::{pixelcolor::Rgb888, prelude::DrawTarget};
-use std::{collections::HashMap, convert::Infallible, error::Error, io::Read};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+    primitives::Rectangle,
+    transform::Transform,
+};
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    error::Error,
+    io::Read,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
 use uuid::Uuid;
 
+/// The largest config, in bytes, that is kept with a loaded render. Larger
+/// configs are still loaded, but can't be read back.
+pub const MAX_STORED_CONFIG_SIZE: usize = 64 * 1024;
+
 pub struct RenderEntry<D>
 where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
 {
-    pub render: Box<dyn Render<D>>,
+    pub render: Box<dyn SyncRender<D>>,
     pub factory_name: String,
+
+    /// The config the render was loaded with, `None` if it was larger than
+    /// [`MAX_STORED_CONFIG_SIZE`]
+    pub config: Option<Vec<u8>>,
 }
 
-pub struct Registry<F, D>
+/// A render that is always drawn on top of the selected render, i.e. a clock
+/// in the corner of the panel.
+pub struct PersistentOverlay<D>
 where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
-    F: RenderFactory<D>,
 {
-    factory_entries: HashMap<String, F>,
-    render_entries: HashMap<Uuid, RenderEntry<D>>,
-    selected: Option<Uuid>,
+    pub render: Box<dyn for<'a> SyncRender<SubCanvas<'a, D>>>,
+    pub offset: Point,
+    pub size: Size,
 }
 
-unsafe impl<F, D> Send for Registry<F, D>
+/// A render drawn instead of the selected render until it expires, see
+/// [`Registry::push_priority`].
+struct PriorityEntry<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    uuid: Uuid,
+    render: Box<dyn SyncRender<D>>,
+    priority: u8,
+    expires_at: Instant,
+}
+
+pub struct Registry<F, D>
 where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
     F: RenderFactory<D>,
 {
+    factory_entries: HashMap<String, F>,
+
+    /// The raw config each factory last successfully loaded a render with
+    last_configs: HashMap<String, Vec<u8>>,
+
+    render_entries: HashMap<Uuid, RenderEntry<D>>,
+    selected: Option<Uuid>,
+    overlay: Option<PersistentOverlay<D>>,
+
+    /// Renders that preempt the selected render, in the order they were pushed
+    priority_entries: Vec<PriorityEntry<D>>,
+
+    /// The priority render that was shown last, `None` while the selected
+    /// render is shown
+    preempted_by: Mutex<Option<Uuid>>,
+
+    /// Set when the selected render changes, so the next frame is redrawn in
+    /// full regardless of what the selected render reports as dirty
+    selection_changed: AtomicBool,
+
+    theme: SharedTheme,
 }
 
 #[derive(Debug)]
 pub enum RegistryError {
+    DuplicateFactory(String),
     FactoryNotFound(String),
     RenderNotFound(Uuid),
     RenderNotLoaded,
     RenderNotUnload,
+    NoRememberedConfig(String),
+    ConfigNotStored(Uuid),
+    LoadFailed(RenderError),
+    ReconfigureFailed(RenderError),
     FileIoError,
 }
 
-impl Error for RegistryError {}
+impl Error for RegistryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::LoadFailed(e) | Self::ReconfigureFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl std::fmt::Display for RegistryError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Self::DuplicateFactory(name) => {
+                write!(f, "Multiple factories are named \"{}\"", name)
+            }
             Self::FactoryNotFound(name) => write!(f, "Factory \"{}\" was not found", name),
             Self::RenderNotFound(uuid) => write!(f, "Render \"{}\" was not found", uuid),
             Self::RenderNotLoaded => write!(f, "Render was not loaded"),
             Self::RenderNotUnload => write!(f, "Render was not unloaded"),
+            Self::NoRememberedConfig(name) => {
+                write!(f, "Factory \"{}\" has not loaded a render yet", name)
+            }
+            Self::ConfigNotStored(uuid) => write!(
+                f,
+                "The config of render \"{}\" is larger than {} bytes and was not kept",
+                uuid, MAX_STORED_CONFIG_SIZE
+            ),
+            Self::LoadFailed(e) => write!(f, "Could not load the render: {}", e),
+            Self::ReconfigureFailed(e) => write!(f, "Could not reconfigure the render: {}", e),
             Self::FileIoError => write!(f, "File IO error"),
         }
     }
@@ -57,76 +140,412 @@ where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
     F: RenderFactory<D>,
 {
-    pub fn new(factories: Vec<F>) -> Self {
-        Self {
-            factory_entries: factories
-                .into_iter()
-                .map(|factory| (factory.render_name().to_owned(), factory))
-                .collect::<HashMap<_, _>>(),
+    /// Creates a registry from the factories.
+    ///
+    /// Factories are looked up by their [`RenderFactory::render_name`], so an
+    /// error is returned if two of them share the same name.
+    pub fn new(factories: Vec<F>) -> Result<Self, RegistryError> {
+        let mut factory_entries = HashMap::with_capacity(factories.len());
+
+        for factory in factories {
+            let factory_name = factory.render_name().to_owned();
+
+            if factory_entries.contains_key(&factory_name) {
+                return Err(RegistryError::DuplicateFactory(factory_name));
+            }
+
+            factory_entries.insert(factory_name, factory);
+        }
+
+        Ok(Self {
+            factory_entries,
+            last_configs: HashMap::new(),
             render_entries: HashMap::new(),
             selected: None,
-        }
+            overlay: None,
+            priority_entries: Vec::new(),
+            preempted_by: Mutex::new(None),
+            selection_changed: AtomicBool::new(true),
+            theme: SharedTheme::default(),
+        })
     }
 
-    pub fn load<R: Read>(&mut self, factory_name: &str, reader: R) -> Result<Uuid, RegistryError> {
+    /// Loads a render from the factory, remembering the config so the render
+    /// can later be loaded again with [`Registry::reload`].
+    pub fn load<R: Read>(
+        &mut self,
+        factory_name: &str,
+        mut reader: R,
+    ) -> Result<Uuid, RegistryError> {
         let Self {
             factory_entries,
+            last_configs,
             render_entries,
             ..
         } = self;
 
-        let render = match factory_entries.get(factory_name) {
-            Some(factory) => match factory.load_from_config(reader) {
-                Ok(render) => render,
-                Err(_) => return Err(RegistryError::FileIoError),
-            },
+        let factory = match factory_entries.get(factory_name) {
+            Some(factory) => factory,
             None => return Err(RegistryError::FactoryNotFound(factory_name.to_owned())),
         };
 
+        let mut config = Vec::new();
+        if reader.read_to_end(&mut config).is_err() {
+            return Err(RegistryError::FileIoError);
+        }
+
+        let render = match factory.load_from_config(config.as_slice()) {
+            Ok(render) => render,
+            Err(e) => return Err(RegistryError::LoadFailed(e)),
+        };
+
+        let stored_config = if config.len() <= MAX_STORED_CONFIG_SIZE {
+            Some(config.clone())
+        } else {
+            None
+        };
+
+        last_configs.insert(factory_name.to_owned(), config);
+
+        // A new render is never the selected one, so it shouldn't poll for data
+        // until it is selected
+        render.on_deselect();
+
         let uuid = Uuid::new_v4();
         render_entries.insert(
             uuid,
             RenderEntry {
                 render,
                 factory_name: factory_name.to_owned(),
+                config: stored_config,
             },
         );
 
         Ok(uuid)
     }
 
+    /// Draws a single frame of a render constructed from `reader` onto a canvas
+    /// of `size`, without adding the render to the registry.
+    ///
+    /// The frame is drawn as soon as the render is constructed and the render is
+    /// dropped right after, so renders that fetch their data in the background
+    /// are previewed in their loading state.
+    pub fn preview<R: Read>(
+        &self,
+        factory_name: &str,
+        reader: R,
+        size: Size,
+    ) -> Result<BufferCanvas, RegistryError> {
+        let factory = match self.factory_entries.get(factory_name) {
+            Some(factory) => factory,
+            None => return Err(RegistryError::FactoryNotFound(factory_name.to_owned())),
+        };
+
+        let render = match factory.load_preview(reader) {
+            Ok(render) => render,
+            Err(e) => return Err(RegistryError::LoadFailed(e)),
+        };
+
+        let mut canvas = BufferCanvas::new(size);
+        let drawn = canvas
+            .clear(render.background_color().unwrap_or(Rgb888::BLACK))
+            .and_then(|_| render.render(&mut canvas));
+
+        match drawn {
+            Ok(()) => Ok(canvas),
+            Err(e) => match e {},
+        }
+    }
+
+    /// Loads another render from the factory using the last config the factory
+    /// successfully loaded a render with.
+    pub fn reload(&mut self, factory_name: &str) -> Result<Uuid, RegistryError> {
+        if !self.factory_entries.contains_key(factory_name) {
+            return Err(RegistryError::FactoryNotFound(factory_name.to_owned()));
+        }
+
+        let config = match self.last_configs.get(factory_name) {
+            Some(config) => config.clone(),
+            None => return Err(RegistryError::NoRememberedConfig(factory_name.to_owned())),
+        };
+
+        self.load(factory_name, config.as_slice())
+    }
+
+    /// Returns the config the render was loaded with.
+    pub fn render_config(&self, uuid: Uuid) -> Result<&[u8], RegistryError> {
+        match self.render_entries.get(&uuid) {
+            Some(render_entry) => match &render_entry.config {
+                Some(config) => Ok(config),
+                None => Err(RegistryError::ConfigNotStored(uuid)),
+            },
+            None => Err(RegistryError::RenderNotFound(uuid)),
+        }
+    }
+
+    /// Applies a new config to a loaded render in place, see
+    /// [`Render::reconfigure`].
+    ///
+    /// The render keeps its id and whether it is selected. The config is only
+    /// kept, and remembered for [`Registry::reload`], if the render accepts it.
+    pub fn reconfigure<R: Read>(&mut self, uuid: Uuid, mut reader: R) -> Result<(), RegistryError> {
+        let Self {
+            last_configs,
+            render_entries,
+            selected,
+            selection_changed,
+            ..
+        } = self;
+
+        let render_entry = match render_entries.get_mut(&uuid) {
+            Some(render_entry) => render_entry,
+            None => return Err(RegistryError::RenderNotFound(uuid)),
+        };
+
+        let mut config = Vec::new();
+        if reader.read_to_end(&mut config).is_err() {
+            return Err(RegistryError::FileIoError);
+        }
+
+        if let Err(e) = render_entry.render.reconfigure(&mut config.as_slice()) {
+            return Err(RegistryError::ReconfigureFailed(e));
+        }
+
+        render_entry.config = if config.len() <= MAX_STORED_CONFIG_SIZE {
+            Some(config.clone())
+        } else {
+            None
+        };
+
+        last_configs.insert(render_entry.factory_name.clone(), config);
+
+        // Redraw the whole frame in case the render still reports an empty
+        // dirty region
+        if *selected == Some(uuid) {
+            selection_changed.store(true, Ordering::SeqCst);
+        }
+
+        Ok(())
+    }
+
+    /// Restarts the render from its initial state, see [`Render::reset`].
+    pub fn reset(&self, uuid: Uuid) -> Result<(), RegistryError> {
+        match self.render_entries.get(&uuid) {
+            Some(render_entry) => {
+                render_entry.render.reset();
+
+                // Redraw the whole frame in case the render still reports an
+                // empty dirty region
+                if self.selected == Some(uuid) {
+                    self.selection_changed.store(true, Ordering::SeqCst);
+                }
+
+                Ok(())
+            }
+            None => Err(RegistryError::RenderNotFound(uuid)),
+        }
+    }
+
     pub fn unload(&mut self, uuid: Uuid) -> Result<(), RegistryError> {
         let Self {
             render_entries,
             selected,
+            selection_changed,
             ..
         } = self;
 
+        let render_entry = match render_entries.remove(&uuid) {
+            Some(render_entry) => render_entry,
+            None => return Err(RegistryError::RenderNotFound(uuid)),
+        };
+
         if let Some(selected_uuid) = selected {
             if selected_uuid == &uuid {
+                render_entry.render.on_deselect();
                 *selected = None;
+                selection_changed.store(true, Ordering::SeqCst);
             }
         }
 
-        match render_entries.remove(&uuid) {
-            Some(_) => Ok(()),
-            None => Err(RegistryError::RenderNotFound(uuid)),
-        }
+        Ok(())
     }
 
     pub fn select(&mut self, uuid: Uuid) -> Result<(), RegistryError> {
         let Self {
             render_entries,
             selected,
+            selection_changed,
             ..
         } = self;
 
-        if !render_entries.contains_key(&uuid) {
-            Err(RegistryError::RenderNotFound(uuid))
-        } else {
-            *selected = Some(uuid);
-            Ok(())
+        let render_entry = match render_entries.get(&uuid) {
+            Some(render_entry) => render_entry,
+            None => return Err(RegistryError::RenderNotFound(uuid)),
+        };
+
+        // Let the previously selected render know it is no longer visible
+        if let Some(previous_uuid) = selected.replace(uuid) {
+            if previous_uuid != uuid {
+                if let Some(previous_entry) = render_entries.get(&previous_uuid) {
+                    previous_entry.render.on_deselect();
+                }
+            }
+        }
+
+        render_entry.render.on_select();
+        selection_changed.store(true, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Draws `render` on top of whichever render is selected, in the region of
+    /// `size` at `offset`. The overlay is drawn even if no render is selected.
+    pub fn set_persistent_overlay(
+        &mut self,
+        render: Box<dyn for<'a> SyncRender<SubCanvas<'a, D>>>,
+        offset: Point,
+        size: Size,
+    ) {
+        if let Some(previous) = self.overlay.take() {
+            previous.render.on_deselect();
+        }
+
+        render.on_select();
+        self.overlay = Some(PersistentOverlay {
+            render,
+            offset,
+            size,
+        });
+        self.selection_changed.store(true, Ordering::SeqCst);
+    }
+
+    /// Removes the persistent overlay, returning it if there was one.
+    pub fn clear_persistent_overlay(&mut self) -> Option<PersistentOverlay<D>> {
+        let overlay = self.overlay.take()?;
+
+        overlay.render.on_deselect();
+        self.selection_changed.store(true, Ordering::SeqCst);
+
+        Some(overlay)
+    }
+
+    /// Draws `render` instead of the selected render for `duration`, i.e. for
+    /// an alert, returning the id to end it early with
+    /// [`Registry::end_priority`].
+    ///
+    /// The render with the highest priority is drawn, the most recently pushed
+    /// one if several share it, so pushed renders stack and unwind as they
+    /// expire. Once none are left the selected render is drawn again. The
+    /// persistent overlay is still drawn on top.
+    pub fn push_priority(
+        &mut self,
+        render: Box<dyn SyncRender<D>>,
+        priority: u8,
+        duration: Duration,
+    ) -> Uuid {
+        let now = Instant::now();
+
+        // Drop the renders that expired since the last push
+        let expired: Vec<Uuid> = self
+            .priority_entries
+            .iter()
+            .filter(|entry| entry.expires_at <= now)
+            .map(|entry| entry.uuid)
+            .collect();
+        for uuid in expired {
+            self.remove_priority(uuid);
+        }
+
+        let uuid = Uuid::new_v4();
+        self.priority_entries.push(PriorityEntry {
+            uuid,
+            render,
+            priority,
+            expires_at: now + duration,
+        });
+
+        uuid
+    }
+
+    /// Removes a render pushed with [`Registry::push_priority`] before it
+    /// expires.
+    pub fn end_priority(&mut self, uuid: Uuid) -> Result<(), RegistryError> {
+        match self.remove_priority(uuid) {
+            Some(_) => Ok(()),
+            None => Err(RegistryError::RenderNotFound(uuid)),
+        }
+    }
+
+    fn remove_priority(&mut self, uuid: Uuid) -> Option<Box<dyn SyncRender<D>>> {
+        let index = self
+            .priority_entries
+            .iter()
+            .position(|entry| entry.uuid == uuid)?;
+        let entry = self.priority_entries.remove(index);
+
+        // The next frame finds it gone and selects whatever is shown next
+        if *self.preempted_by.lock() == Some(uuid) {
+            entry.render.on_deselect();
+        }
+
+        Some(entry.render)
+    }
+
+    /// The priority render that should be shown, if any has not expired yet
+    fn active_priority(&self) -> Option<&PriorityEntry<D>> {
+        let now = Instant::now();
+
+        // `max_by_key` returns the last of equal elements, the newest one
+        self.priority_entries
+            .iter()
+            .filter(|entry| entry.expires_at > now)
+            .max_by_key(|entry| entry.priority)
+    }
+
+    /// The render drawn instead of the selected render, if any
+    fn shown_render(&self) -> Option<&dyn SyncRender<D>> {
+        match self.active_priority() {
+            Some(entry) => Some(entry.render.as_ref()),
+            None => self
+                .selected
+                .and_then(|uuid| self.render_entries.get(&uuid))
+                .map(|render_entry| render_entry.render.as_ref()),
+        }
+    }
+
+    /// Lets the renders know when a priority render started or stopped
+    /// preempting what was shown, and redraws the next frame in full
+    fn update_preemption(&self) {
+        let active = self.active_priority().map(|entry| entry.uuid);
+        let mut preempted_by = self.preempted_by.lock();
+
+        if *preempted_by == active {
+            return;
+        }
+
+        let render_for = |uuid: Option<Uuid>| match uuid {
+            Some(uuid) => self
+                .priority_entries
+                .iter()
+                .find(|entry| entry.uuid == uuid)
+                .map(|entry| &entry.render),
+            None => self
+                .selected
+                .and_then(|uuid| self.render_entries.get(&uuid))
+                .map(|render_entry| &render_entry.render),
+        };
+
+        // A priority render that was removed was deselected already
+        if let Some(render) = render_for(*preempted_by) {
+            render.on_deselect();
+        }
+
+        if let Some(render) = render_for(active) {
+            render.on_select();
         }
+
+        *preempted_by = active;
+        self.selection_changed.store(true, Ordering::SeqCst);
     }
 
     pub fn factory_iter(&self) -> impl Iterator<Item = (&String, &F)> {
@@ -142,6 +561,49 @@ where
 
         render_entries.iter()
     }
+
+    /// Iterates over the names of the factories along with if at least one
+    /// render created by the factory is currently loaded.
+    pub fn loaded_iter(&self) -> impl Iterator<Item = (&str, bool)> {
+        let Self {
+            factory_entries,
+            render_entries,
+            ..
+        } = self;
+
+        factory_entries.keys().map(move |factory_name| {
+            let is_loaded = render_entries
+                .values()
+                .any(|render_entry| &render_entry.factory_name == factory_name);
+
+            (factory_name.as_str(), is_loaded)
+        })
+    }
+
+    /// Handle to the theme, which is shared with the HTTP API.
+    pub fn theme(&self) -> SharedTheme {
+        self.theme.clone()
+    }
+
+    /// Replaces the theme and redraws the next frame in full, since the
+    /// background of the selected render may have changed.
+    pub fn set_theme(&self, theme: Theme) {
+        *self.theme.write() = theme;
+        self.selection_changed.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns the name of the factory that created the selected render.
+    pub fn selected_name(&self) -> Option<&str> {
+        let Self {
+            render_entries,
+            selected,
+            ..
+        } = self;
+
+        selected
+            .and_then(|uuid| render_entries.get(&uuid))
+            .map(|render_entry| render_entry.factory_name.as_str())
+    }
 }
 
 impl<F, D> Render<D> for Registry<F, D>
@@ -150,18 +612,73 @@ where
     F: RenderFactory<D>,
 {
     fn render(&self, canvas: &mut D) -> Result<(), <D as DrawTarget>::Error> {
-        let Self {
-            render_entries,
-            selected,
-            ..
-        } = self;
+        self.render_at(canvas, Instant::now())
+    }
 
-        if let Some(selected) = selected {
-            if let Some(render_entry) = render_entries.get(selected) {
-                render_entry.render.render(canvas)?;
-            }
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), <D as DrawTarget>::Error> {
+        self.update_preemption();
+
+        if let Some(render) = self.shown_render() {
+            render.render_at(canvas, now)?;
+        }
+
+        if let Some(overlay) = &self.overlay {
+            overlay.render.render_at(
+                &mut SubCanvas::new(overlay.offset, overlay.size, canvas),
+                now,
+            )?;
         }
 
         Ok(())
     }
+
+    fn background_color(&self) -> Option<Rgb888> {
+        // Renders without a background of their own are drawn on the theme's
+        self.shown_render()
+            .and_then(|render| render.background_color())
+            .or_else(|| Some(self.theme.read().background))
+    }
+
+    fn dirty_region(&self) -> Option<Rectangle> {
+        self.update_preemption();
+
+        if self.selection_changed.swap(false, Ordering::SeqCst) {
+            return None;
+        }
+
+        let selected_region = match self.shown_render() {
+            Some(render) => render.dirty_region()?,
+            None => Rectangle::zero(),
+        };
+
+        let overlay_region = match &self.overlay {
+            Some(overlay) => overlay.render.dirty_region()?.translate(overlay.offset),
+            None => Rectangle::zero(),
+        };
+
+        // Bounding box of both regions, ignoring the ones that are empty
+        if overlay_region.is_zero_sized() {
+            Some(selected_region)
+        } else if selected_region.is_zero_sized() {
+            Some(overlay_region)
+        } else {
+            let top_left = selected_region
+                .top_left
+                .component_min(overlay_region.top_left);
+            let bottom_right = (selected_region.top_left + selected_region.size)
+                .component_max(overlay_region.top_left + overlay_region.size);
+
+            Some(Rectangle::with_corners(
+                top_left,
+                bottom_right - Point::new(1, 1),
+            ))
+        }
+    }
+
+    /// Whether the shown render has content, `false` while no render is
+    /// selected or preempting it.
+    fn has_content(&self) -> bool {
+        self.shown_render()
+            .is_some_and(|render| render.has_content())
+    }
 }
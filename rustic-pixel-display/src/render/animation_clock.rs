@@ -0,0 +1,16 @@
+use std::{
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+/// When the animation clock started, the first time it was read
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+/// The time on the clock animated renders derive their phase from, at the
+/// `now` of a frame passed to [`Render::render_at`](super::Render::render_at).
+///
+/// Every render reads the same clock, so animations of the same period stay in
+/// step with each other instead of with when each render was constructed.
+pub fn animation_time(now: Instant) -> Duration {
+    now.saturating_duration_since(*EPOCH.get_or_init(|| now))
+}
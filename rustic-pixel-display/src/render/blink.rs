@@ -0,0 +1,84 @@
+use super::{Render, RenderError};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::DrawTarget};
+use std::{
+    fmt,
+    io::Read,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Returns if something blinking every `interval` should currently be shown.
+///
+/// The phase is derived from the wall-clock time rather than a frame counter,
+/// so blinking is independent of the frame rate and everything blinking with
+/// the same interval does so in unison.
+pub fn is_blink_visible(interval: Duration) -> bool {
+    let interval_millis = interval.as_millis().max(1);
+    let elapsed_millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+
+    (elapsed_millis / interval_millis) % 2 == 0
+}
+
+/// Toggles an inner render on and off to grab attention.
+///
+/// The inner render is drawn for `interval`, then nothing is drawn for
+/// `interval`, and so on.
+pub struct Blink<R> {
+    render: R,
+    interval: Duration,
+}
+
+impl<R> Blink<R> {
+    pub fn new(render: R, interval: Duration) -> Self {
+        Self { render, interval }
+    }
+}
+
+impl<D, R> Render<D> for Blink<R>
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+    R: Render<D>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        if is_blink_visible(self.interval) {
+            self.render.render_at(canvas, now)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn on_select(&self) {
+        self.render.on_select();
+    }
+
+    fn on_deselect(&self) {
+        self.render.on_deselect();
+    }
+
+    fn background_color(&self) -> Option<Rgb888> {
+        self.render.background_color()
+    }
+
+    fn describe(&self) -> String {
+        self.render.describe()
+    }
+
+    fn has_content(&self) -> bool {
+        self.render.has_content()
+    }
+
+    fn reset(&self) {
+        self.render.reset();
+    }
+
+    fn reconfigure(&self, config: &mut dyn Read) -> Result<(), RenderError> {
+        self.render.reconfigure(config)
+    }
+}
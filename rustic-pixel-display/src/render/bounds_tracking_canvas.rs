@@ -0,0 +1,107 @@
+use embedded_graphics::{
+    prelude::{Dimensions, DrawTarget, PixelColor, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// Canvas that passes every drawing operation on to `canvas` while keeping
+/// track of the smallest rectangle enclosing everything that was drawn.
+///
+/// Only the parts of a drawing operation that land on the canvas are tracked.
+/// Clearing the canvas is not tracked, so a render that clears its background
+/// is still measured by its content.
+pub struct BoundsTrackingCanvas<D> {
+    canvas: D,
+    bounds: Option<Rectangle>,
+}
+
+impl<D> BoundsTrackingCanvas<D> {
+    pub fn new(canvas: D) -> Self {
+        Self {
+            canvas,
+            bounds: None,
+        }
+    }
+
+    /// Returns the area that was drawn on, or `None` if nothing was drawn.
+    pub fn bounds(&self) -> Option<Rectangle> {
+        self.bounds
+    }
+
+    pub fn into_inner(self) -> D {
+        self.canvas
+    }
+}
+
+/// Grows `bounds` to include the part of `area` that is within `canvas_box`.
+fn track(bounds: &mut Option<Rectangle>, area: &Rectangle, canvas_box: &Rectangle) {
+    let area = area.intersection(canvas_box);
+
+    // Only a non empty rectangle has a bottom right corner
+    let area_end = match area.bottom_right() {
+        Some(area_end) => area_end,
+        None => return,
+    };
+
+    *bounds = Some(
+        match bounds.and_then(|b| b.bottom_right().map(|end| (b, end))) {
+            Some((bounds, bounds_end)) => Rectangle::with_corners(
+                bounds.top_left.component_min(area.top_left),
+                bounds_end.component_max(area_end),
+            ),
+            None => area,
+        },
+    );
+}
+
+impl<D> Dimensions for BoundsTrackingCanvas<D>
+where
+    D: Dimensions,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.canvas.bounding_box()
+    }
+}
+
+impl<D, C> DrawTarget for BoundsTrackingCanvas<D>
+where
+    C: PixelColor,
+    D: DrawTarget<Color = C>,
+{
+    type Color = C;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let canvas_box = self.canvas.bounding_box();
+        let bounds = &mut self.bounds;
+
+        self.canvas
+            .draw_iter(pixels.into_iter().inspect(|Pixel(point, _)| {
+                track(
+                    bounds,
+                    &Rectangle::new(*point, Size::new(1, 1)),
+                    &canvas_box,
+                )
+            }))
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        track(&mut self.bounds, area, &self.canvas.bounding_box());
+        self.canvas.fill_contiguous(area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        track(&mut self.bounds, area, &self.canvas.bounding_box());
+        self.canvas.fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.canvas.clear(color)
+    }
+}
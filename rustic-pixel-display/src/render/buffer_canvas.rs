@@ -0,0 +1,100 @@
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, Point, RgbColor, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+use std::convert::Infallible;
+
+/// An in-memory canvas that owns its pixels.
+///
+/// Useful when a render can't draw directly onto the final canvas, for example
+/// when the render needs to be `'static` or its output has to be post
+/// processed before being displayed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BufferCanvas {
+    size: Size,
+    pixels: Vec<Rgb888>,
+}
+
+impl BufferCanvas {
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            pixels: vec![Rgb888::BLACK; (size.width * size.height) as usize],
+        }
+    }
+
+    /// Returns the color of the pixel at `point`, or `None` if it is outside
+    /// the canvas.
+    pub fn pixel(&self, point: Point) -> Option<Rgb888> {
+        self.index(point).map(|index| self.pixels[index])
+    }
+
+    /// Returns the pixels of the canvas in row-major order.
+    pub fn pixels(&self) -> &[Rgb888] {
+        &self.pixels
+    }
+
+    /// Replaces every pixel of the canvas with the result of `f`, for post
+    /// processing the output of a render before it is copied elsewhere.
+    pub fn map_pixels<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Rgb888) -> Rgb888,
+    {
+        for pixel in self.pixels.iter_mut() {
+            *pixel = f(*pixel);
+        }
+    }
+
+    /// Copies the contents of the canvas onto `target`, with the top left
+    /// corner placed at `offset`.
+    pub fn draw_to<D>(&self, target: &mut D, offset: Point) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        target.fill_contiguous(
+            &Rectangle::new(offset, self.size),
+            self.pixels.iter().copied(),
+        )
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        let (x, y) = (u32::try_from(point.x).ok()?, u32::try_from(point.y).ok()?);
+
+        if x < self.size.width && y < self.size.height {
+            Some((y * self.size.width + x) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl OriginDimensions for BufferCanvas {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for BufferCanvas {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some(index) = self.index(point) {
+                self.pixels[index] = color;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.pixels.fill(color);
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use super::{
+    Blink, Centered, FixedResolution, Outline, Outlined, PixelShift, Render, ScaleMode, SyncRender,
+    Throttle,
+};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Size},
+};
+use std::{fmt, time::Duration};
+
+/// Wraps a render in the wrapper renders one call at a time, instead of
+/// nesting their constructors by hand.
+///
+/// Every call wraps everything built so far, so the last call is the outermost
+/// wrapper. The wrappers are checked against the canvas they draw on when the
+/// result is rendered or boxed, not while it is built.
+///
+/// ```rust
+/// use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+/// use rustic_pixel_display::render::{BufferCanvas, Render, RenderBuilder, ScaleMode};
+/// use std::{convert::Infallible, time::Duration};
+///
+/// struct Dot;
+///
+/// impl<D: DrawTarget<Color = Rgb888, Error = Infallible>> Render<D> for Dot {
+///     fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+///         Pixel(Point::zero(), Rgb888::RED).draw(canvas)
+///     }
+/// }
+///
+/// // Drawn at 2x2, scaled up to the canvas and redrawn at most once a second
+/// let render = RenderBuilder::new(Dot)
+///     .scaled(Size::new(2, 2), ScaleMode::Nearest)
+///     .throttled(Duration::from_secs(1))
+///     .build();
+///
+/// let mut canvas = BufferCanvas::new(Size::new(4, 4));
+/// render.render(&mut canvas).unwrap();
+///
+/// assert_eq!(canvas.pixel(Point::new(1, 1)), Some(Rgb888::RED));
+/// assert_eq!(canvas.pixel(Point::new(2, 2)), Some(Rgb888::BLACK));
+/// ```
+pub struct RenderBuilder<R> {
+    render: R,
+}
+
+impl<R> RenderBuilder<R> {
+    pub fn new(render: R) -> Self {
+        Self { render }
+    }
+
+    /// Draws at `logical_size` and scales to the canvas, see
+    /// [`FixedResolution`].
+    pub fn scaled(
+        self,
+        logical_size: Size,
+        scale_mode: ScaleMode,
+    ) -> RenderBuilder<FixedResolution<R>> {
+        RenderBuilder::new(FixedResolution::new(self.render, logical_size, scale_mode))
+    }
+
+    /// Redraws at most once every `interval`, see [`Throttle`].
+    pub fn throttled(self, interval: Duration) -> RenderBuilder<Throttle<R>> {
+        RenderBuilder::new(Throttle::new(self.render, interval))
+    }
+
+    /// Centers what is drawn on the canvas, see [`Centered`].
+    pub fn centered(self) -> RenderBuilder<Centered<R>> {
+        RenderBuilder::new(Centered::new(self.render))
+    }
+
+    /// Toggles on and off every `interval`, see [`Blink`].
+    pub fn blinking(self, interval: Duration) -> RenderBuilder<Blink<R>> {
+        RenderBuilder::new(Blink::new(self.render, interval))
+    }
+
+    /// Draws an outline around what is drawn, see [`Outlined`].
+    pub fn outlined(self, outline: Outline) -> RenderBuilder<Outlined<R>> {
+        RenderBuilder::new(Outlined::new(self.render, outline))
+    }
+
+    /// Moves by 1 pixel every `interval`, up to `range` pixels in every
+    /// direction, see [`PixelShift`].
+    pub fn pixel_shifted(self, interval: Duration, range: u32) -> RenderBuilder<PixelShift<R>> {
+        RenderBuilder::new(PixelShift::new(self.render, interval, range))
+    }
+
+    /// Returns the stack of wrappers.
+    pub fn build(self) -> R {
+        self.render
+    }
+
+    /// Returns the stack of wrappers boxed, i.e. for a layout or a factory.
+    pub fn boxed<D>(self) -> Box<dyn SyncRender<D>>
+    where
+        D: DrawTarget<Color = Rgb888>,
+        D::Error: fmt::Debug,
+        R: Render<D> + Send + Sync + 'static,
+    {
+        Box::new(self.render)
+    }
+}
@@ -0,0 +1,100 @@
+use super::{BoundsTrackingCanvas, BufferCanvas, Render, RenderError};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    primitives::{PointsIter, Rectangle},
+};
+use std::{fmt, io::Read, time::Instant};
+
+/// Centers whatever the inner render draws on the canvas.
+///
+/// Renders draw relative to the top left corner of their canvas, which leaves
+/// a small render in the corner of a large panel. Every frame the inner render
+/// is drawn into a [`BufferCanvas`] wrapped by a [`BoundsTrackingCanvas`], and
+/// only the area that was drawn on is copied to the center of the canvas.
+pub struct Centered<R> {
+    render: R,
+}
+
+impl<R> Centered<R> {
+    pub fn new(render: R) -> Self {
+        Self { render }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.render
+    }
+}
+
+impl<D, R> Render<D> for Centered<R>
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+    R: Render<BoundsTrackingCanvas<BufferCanvas>>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let bounding_box = canvas.bounding_box();
+
+        let mut buffer = BufferCanvas::new(bounding_box.size);
+        if let Err(e) = buffer.clear(self.render.background_color().unwrap_or(Rgb888::BLACK)) {
+            match e {}
+        }
+
+        let mut tracking_canvas = BoundsTrackingCanvas::new(buffer);
+        if let Err(e) = self.render.render_at(&mut tracking_canvas, now) {
+            match e {}
+        }
+
+        let bounds = match tracking_canvas.bounds() {
+            Some(bounds) => bounds,
+            None => return Ok(()),
+        };
+        let buffer = tracking_canvas.into_inner();
+
+        // Move the center of what was drawn onto the center of the canvas
+        let top_left = bounding_box.top_left
+            + Point::new(
+                (bounding_box.size.width - bounds.size.width) as i32 / 2,
+                (bounding_box.size.height - bounds.size.height) as i32 / 2,
+            );
+
+        canvas.fill_contiguous(
+            &Rectangle::new(top_left, bounds.size),
+            bounds
+                .points()
+                .map(|point| buffer.pixel(point).unwrap_or(Rgb888::BLACK)),
+        )
+    }
+
+    fn on_select(&self) {
+        self.render.on_select();
+    }
+
+    fn on_deselect(&self) {
+        self.render.on_deselect();
+    }
+
+    fn background_color(&self) -> Option<Rgb888> {
+        self.render.background_color()
+    }
+
+    fn describe(&self) -> String {
+        self.render.describe()
+    }
+
+    fn has_content(&self) -> bool {
+        self.render.has_content()
+    }
+
+    fn reset(&self) {
+        self.render.reset();
+    }
+
+    fn reconfigure(&self, config: &mut dyn Read) -> Result<(), RenderError> {
+        self.render.reconfigure(config)
+    }
+}
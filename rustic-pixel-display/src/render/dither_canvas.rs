@@ -0,0 +1,134 @@
+use embedded_graphics::{
+    geometry::Dimensions,
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    primitives::Rectangle,
+    Pixel,
+};
+use serde::{Deserialize, Serialize};
+
+/// The size of the threshold matrix used by a [`DitherCanvas`]. Larger
+/// matrices can represent more intermediate shades, at the cost of a more
+/// visible pattern.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BayerMatrix {
+    #[serde(rename = "2x2")]
+    TwoByTwo,
+    #[default]
+    #[serde(rename = "4x4")]
+    FourByFour,
+    #[serde(rename = "8x8")]
+    EightByEight,
+}
+
+impl BayerMatrix {
+    /// log2 of the width of the matrix
+    fn order(&self) -> u32 {
+        match self {
+            BayerMatrix::TwoByTwo => 1,
+            BayerMatrix::FourByFour => 2,
+            BayerMatrix::EightByEight => 3,
+        }
+    }
+
+    /// The threshold of the matrix at `point`, between 0 and 1 (exclusive).
+    fn threshold(&self, point: Point) -> f32 {
+        let order = self.order();
+        let (x, y) = (point.x as u32, point.y as u32);
+
+        // The lowest bits of the coordinates pick the most significant digits
+        // of the index, which spreads consecutive thresholds across the matrix
+        let index = (0..order).fold(0, |index, bit| {
+            let x_bit = (x >> bit) & 1;
+            let y_bit = (y >> bit) & 1;
+
+            (index << 2) | ((x_bit ^ y_bit) << 1) | y_bit
+        });
+
+        (index as f32 + 0.5) / (1 << (2 * order)) as f32
+    }
+}
+
+/// Canvas that reduces every pixel drawn on it to `bits` bits per channel
+/// before passing it on, using ordered dithering to hide the banding this would
+/// otherwise cause in gradients.
+///
+/// The threshold a pixel is rounded with depends on its position on the
+/// canvas, so areas of a single color in between two representable shades are
+/// drawn as a fine pattern of both.
+pub struct DitherCanvas<'a, D> {
+    canvas: &'a mut D,
+    bits: u8,
+    matrix: BayerMatrix,
+}
+
+impl<'a, D> DitherCanvas<'a, D> {
+    /// `bits` is clamped between 1 and 8, with 8 leaving the colors unchanged.
+    pub fn new(canvas: &'a mut D, bits: u8, matrix: BayerMatrix) -> Self {
+        Self {
+            canvas,
+            bits: bits.clamp(1, 8),
+            matrix,
+        }
+    }
+}
+
+/// Reduces `color` to `bits` bits per channel, rounding with the threshold of
+/// `matrix` at `point`.
+fn dither(Pixel(point, color): Pixel<Rgb888>, bits: u8, matrix: BayerMatrix) -> Pixel<Rgb888> {
+    if bits >= 8 {
+        return Pixel(point, color);
+    }
+
+    let levels = ((1 << bits) - 1) as f32;
+    let threshold = matrix.threshold(point);
+
+    let quantize = |channel: u8| -> u8 {
+        let level = (channel as f32 * levels / 255.0 + threshold)
+            .floor()
+            .min(levels);
+
+        (level * 255.0 / levels).round() as u8
+    };
+
+    Pixel(
+        point,
+        Rgb888::new(
+            quantize(color.r()),
+            quantize(color.g()),
+            quantize(color.b()),
+        ),
+    )
+}
+
+impl<D> Dimensions for DitherCanvas<'_, D>
+where
+    D: Dimensions,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.canvas.bounding_box()
+    }
+}
+
+impl<D> DrawTarget for DitherCanvas<'_, D>
+where
+    D: DrawTarget<Color = Rgb888>,
+{
+    type Color = Rgb888;
+    type Error = D::Error;
+
+    // Fills are left to the default implementations, which draw pixel by pixel
+    // so every pixel gets its own threshold
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (bits, matrix) = (self.bits, self.matrix);
+
+        self.canvas.draw_iter(
+            pixels
+                .into_iter()
+                .map(move |pixel| dither(pixel, bits, matrix)),
+        )
+    }
+}
@@ -0,0 +1,72 @@
+use std::error::Error;
+
+/// The reasons a [`RenderFactory`](super::RenderFactory) can fail to construct a
+/// render, so callers embedding the library can react to the kind of failure.
+///
+/// Code that uses [`anyhow`] internally can still return its errors with `?`,
+/// they are converted to [`RenderError::Other`] unless they wrap a
+/// `RenderError` already.
+#[derive(Debug)]
+pub enum RenderError {
+    /// The configuration could not be parsed or is invalid
+    Config(String),
+
+    /// The configuration is not valid JSON, or its fields don't match the
+    /// configuration of the render. Keeps the location of the problem.
+    ConfigParse(serde_json::Error),
+
+    /// A request to an external service failed
+    Network(String),
+
+    /// Data, like an image or an API response, could not be decoded
+    Decode(String),
+
+    /// Drawing onto the canvas failed
+    Draw(String),
+
+    /// The factory does not support the operation, i.e. previews
+    Unsupported(String),
+
+    /// Any other error
+    Other(anyhow::Error),
+}
+
+impl Error for RenderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::ConfigParse(e) => Some(e),
+            Self::Other(e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Config(message) => write!(f, "Invalid configuration: {}", message),
+            Self::ConfigParse(e) => write!(f, "Invalid configuration: {}", e),
+            Self::Network(message) => write!(f, "Network error: {}", message),
+            Self::Decode(message) => write!(f, "Could not decode data: {}", message),
+            Self::Draw(message) => write!(f, "Could not draw: {}", message),
+            Self::Unsupported(message) => write!(f, "Not supported: {}", message),
+            Self::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for RenderError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::ConfigParse(value)
+    }
+}
+
+impl From<anyhow::Error> for RenderError {
+    fn from(value: anyhow::Error) -> Self {
+        // Keep the kind of errors that only passed through anyhow
+        match value.downcast::<RenderError>() {
+            Ok(e) => e,
+            Err(e) => Self::Other(e),
+        }
+    }
+}
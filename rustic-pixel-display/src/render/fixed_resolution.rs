@@ -0,0 +1,194 @@
+use super::{BufferCanvas, Render, RenderError};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, Point, RgbColor, Size},
+    primitives::{PointsIter, Rectangle},
+};
+use std::{fmt, io::Read, time::Instant};
+
+/// How [`FixedResolution`] maps the logical pixels onto the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScaleMode {
+    /// Every canvas pixel takes the color of the closest logical pixel, which
+    /// keeps text and lines sharp
+    #[default]
+    Nearest,
+
+    /// Every canvas pixel blends the four closest logical pixels, which
+    /// smooths images and gradients
+    Bilinear,
+}
+
+/// Draws the inner render at a fixed logical size and scales the result to
+/// the canvas.
+///
+/// This lets a render be designed for one panel size, i.e. 128x64, and shown
+/// on another, i.e. 64x32, without the render being aware of the size of the
+/// canvas. Every frame the inner render is drawn into a [`BufferCanvas`] of
+/// the logical size, which is then sampled for every pixel of the canvas.
+pub struct FixedResolution<R> {
+    render: R,
+    logical_size: Size,
+    scale_mode: ScaleMode,
+}
+
+impl<R> FixedResolution<R> {
+    pub fn new(render: R, logical_size: Size, scale_mode: ScaleMode) -> Self {
+        Self {
+            render,
+            logical_size,
+            scale_mode,
+        }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.render
+    }
+
+    pub fn logical_size(&self) -> Size {
+        self.logical_size
+    }
+}
+
+/// Returns a copy of `source` scaled to `size`.
+///
+/// Both modes sample at the center of every target pixel and clamp at the
+/// edges of `source`, so a bilinear downscale by two averages the four source
+/// pixels behind every target pixel.
+pub fn scale_buffer(source: &BufferCanvas, size: Size, scale_mode: ScaleMode) -> BufferCanvas {
+    let mut target = BufferCanvas::new(size);
+    let source_size = source.size();
+
+    if source_size.width == 0 || source_size.height == 0 {
+        return target;
+    }
+
+    let area = Rectangle::new(Point::zero(), size);
+    let pixels = area
+        .points()
+        .map(|point| match scale_mode {
+            ScaleMode::Nearest => sample_nearest(source, size, point),
+            ScaleMode::Bilinear => sample_bilinear(source, size, point),
+        })
+        .collect::<Vec<_>>();
+    target
+        .fill_contiguous(&area, pixels)
+        .unwrap_or_else(|e| match e {});
+
+    target
+}
+
+/// Returns the color of the source pixel closest to `point` on a target of
+/// `size`
+fn sample_nearest(source: &BufferCanvas, size: Size, point: Point) -> Rgb888 {
+    let source_size = source.size();
+    let x = (2 * point.x as u64 + 1) * source_size.width as u64 / (2 * size.width as u64);
+    let y = (2 * point.y as u64 + 1) * source_size.height as u64 / (2 * size.height as u64);
+
+    source
+        .pixel(Point::new(x as i32, y as i32))
+        .unwrap_or(Rgb888::BLACK)
+}
+
+/// Returns the blend of the four source pixels surrounding `point` on a target
+/// of `size`, weighted by their distance to the center of `point`
+fn sample_bilinear(source: &BufferCanvas, size: Size, point: Point) -> Rgb888 {
+    let source_size = source.size();
+
+    // Position of the center of the target pixel in source pixels
+    let position = |position: i32, target: u32, source: u32| {
+        let center = (position as f32 + 0.5) * source as f32 / target as f32 - 0.5;
+        let center = center.clamp(0.0, (source - 1) as f32);
+        let low = center.floor() as i32;
+        let high = (low + 1).min(source as i32 - 1);
+
+        (low, high, center - low as f32)
+    };
+
+    let (x0, x1, fx) = position(point.x, size.width, source_size.width);
+    let (y0, y1, fy) = position(point.y, size.height, source_size.height);
+
+    let pixel = |x, y| source.pixel(Point::new(x, y)).unwrap_or(Rgb888::BLACK);
+    let (top_left, top_right) = (pixel(x0, y0), pixel(x1, y0));
+    let (bottom_left, bottom_right) = (pixel(x0, y1), pixel(x1, y1));
+
+    let blend = |channel: fn(Rgb888) -> u8| {
+        let lerp = |a: Rgb888, b: Rgb888, t: f32| {
+            channel(a) as f32 + (channel(b) as f32 - channel(a) as f32) * t
+        };
+
+        let top = lerp(top_left, top_right, fx);
+        let bottom = lerp(bottom_left, bottom_right, fx);
+
+        (top + (bottom - top) * fy).round() as u8
+    };
+
+    Rgb888::new(blend(|c| c.r()), blend(|c| c.g()), blend(|c| c.b()))
+}
+
+impl<D, R> Render<D> for FixedResolution<R>
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+    R: Render<BufferCanvas>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let bounding_box = canvas.bounding_box();
+
+        if self.logical_size.width == 0
+            || self.logical_size.height == 0
+            || bounding_box.size.width == 0
+            || bounding_box.size.height == 0
+        {
+            return Ok(());
+        }
+
+        let mut buffer = BufferCanvas::new(self.logical_size);
+        let drawn = buffer
+            .clear(self.render.background_color().unwrap_or(Rgb888::BLACK))
+            .and_then(|_| self.render.render_at(&mut buffer, now));
+        if let Err(e) = drawn {
+            match e {}
+        }
+
+        // Same size, nothing to scale
+        if bounding_box.size == self.logical_size {
+            return buffer.draw_to(canvas, bounding_box.top_left);
+        }
+
+        scale_buffer(&buffer, bounding_box.size, self.scale_mode)
+            .draw_to(canvas, bounding_box.top_left)
+    }
+
+    fn on_select(&self) {
+        self.render.on_select();
+    }
+
+    fn on_deselect(&self) {
+        self.render.on_deselect();
+    }
+
+    fn background_color(&self) -> Option<Rgb888> {
+        self.render.background_color()
+    }
+
+    fn describe(&self) -> String {
+        self.render.describe()
+    }
+
+    fn has_content(&self) -> bool {
+        self.render.has_content()
+    }
+
+    fn reset(&self) {
+        self.render.reset();
+    }
+
+    fn reconfigure(&self, config: &mut dyn Read) -> Result<(), RenderError> {
+        self.render.reconfigure(config)
+    }
+}
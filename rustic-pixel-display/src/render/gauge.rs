@@ -0,0 +1,88 @@
+use embedded_graphics::{
+    geometry::Dimensions,
+    prelude::{DrawTarget, PixelColor, Point, Primitive, Size},
+    primitives::{PrimitiveStyle, Rectangle},
+    transform::Transform,
+    Drawable,
+};
+
+/// Horizontal bar filled from the left in proportion to a value between 0 and
+/// 1, i.e. a progress or level indicator.
+///
+/// Implements [`Dimensions`] and [`Transform`] so it can be placed in an
+/// embedded-layout layout next to text.
+#[derive(Debug, Clone, Copy)]
+pub struct Gauge<C: PixelColor> {
+    bounds: Rectangle,
+
+    /// How much of the gauge is filled, clamped between 0 and 1
+    fraction: f32,
+    color: C,
+
+    /// Color of the unfilled part of the gauge, left undrawn if `None`
+    background: Option<C>,
+}
+
+impl<C: PixelColor> Gauge<C> {
+    pub fn new(size: Size, fraction: f32, color: C) -> Self {
+        Self {
+            bounds: Rectangle::new(Point::zero(), size),
+            fraction: fraction.clamp(0.0, 1.0),
+            color,
+            background: None,
+        }
+    }
+
+    pub fn with_background(mut self, background: C) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    /// Width, in pixels, of the filled part of the gauge
+    pub fn filled_width(&self) -> u32 {
+        (self.bounds.size.width as f32 * self.fraction).round() as u32
+    }
+}
+
+impl<C: PixelColor> Drawable for Gauge<C> {
+    type Color = C;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        if let Some(background) = self.background {
+            self.bounds
+                .into_styled(PrimitiveStyle::with_fill(background))
+                .draw(target)?;
+        }
+
+        Rectangle::new(
+            self.bounds.top_left,
+            Size::new(self.filled_width(), self.bounds.size.height),
+        )
+        .into_styled(PrimitiveStyle::with_fill(self.color))
+        .draw(target)
+    }
+}
+
+impl<C: PixelColor> Dimensions for Gauge<C> {
+    fn bounding_box(&self) -> Rectangle {
+        self.bounds
+    }
+}
+
+impl<C: PixelColor> Transform for Gauge<C> {
+    fn translate(&self, by: Point) -> Self {
+        Self {
+            bounds: self.bounds.translate(by),
+            ..*self
+        }
+    }
+
+    fn translate_mut(&mut self, by: Point) -> &mut Self {
+        self.bounds.translate_mut(by);
+        self
+    }
+}
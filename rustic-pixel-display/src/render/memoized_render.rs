@@ -0,0 +1,129 @@
+use super::{BufferCanvas, Render, RenderError};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, Point},
+    primitives::Rectangle,
+};
+use parking_lot::Mutex;
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+    io::Read,
+    time::Instant,
+};
+
+/// Only redraws the inner render when its state changes.
+///
+/// Every frame the `key` closure derives a hashable key from the current state
+/// of the inner render. The inner render is only drawn (into a cached
+/// [`BufferCanvas`]) when the hash of the key changes or the canvas is resized,
+/// otherwise the cached frame is copied onto the canvas.
+pub struct MemoizedRender<R, K> {
+    render: R,
+    key: Box<dyn Fn(&R) -> K + Send + Sync>,
+    cache: Mutex<Option<(u64, BufferCanvas)>>,
+}
+
+impl<R, K> MemoizedRender<R, K>
+where
+    K: Hash,
+{
+    pub fn new<F>(render: R, key: F) -> Self
+    where
+        F: Fn(&R) -> K + Send + Sync + 'static,
+    {
+        Self {
+            render,
+            key: Box::new(key),
+            cache: Mutex::new(None),
+        }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.render
+    }
+
+    fn key_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (self.key)(&self.render).hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl<D, R, K> Render<D> for MemoizedRender<R, K>
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+    R: Render<BufferCanvas>,
+    K: Hash,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let key_hash = self.key_hash();
+        let canvas_size = canvas.bounding_box().size;
+        let mut cache = self.cache.lock();
+
+        let is_stale = match &*cache {
+            Some((cached_hash, buffer)) => *cached_hash != key_hash || buffer.size() != canvas_size,
+            None => true,
+        };
+
+        if is_stale {
+            let mut buffer = BufferCanvas::new(canvas_size);
+            if let Err(e) = self.render.render_at(&mut buffer, now) {
+                match e {}
+            }
+            *cache = Some((key_hash, buffer));
+        }
+
+        match &*cache {
+            Some((_, buffer)) => buffer.draw_to(canvas, Point::zero()),
+            None => Ok(()),
+        }
+    }
+
+    fn on_select(&self) {
+        self.render.on_select();
+    }
+
+    fn on_deselect(&self) {
+        self.render.on_deselect();
+    }
+
+    fn background_color(&self) -> Option<Rgb888> {
+        self.render.background_color()
+    }
+
+    fn dirty_region(&self) -> Option<Rectangle> {
+        match &*self.cache.lock() {
+            Some((cached_hash, _)) if *cached_hash == self.key_hash() => Some(Rectangle::zero()),
+            _ => None,
+        }
+    }
+
+    fn describe(&self) -> String {
+        self.render.describe()
+    }
+
+    fn has_content(&self) -> bool {
+        self.render.has_content()
+    }
+
+    fn reset(&self) {
+        // Drop the cached frame so the reset shows up right away
+        *self.cache.lock() = None;
+        self.render.reset();
+    }
+
+    fn reconfigure(&self, config: &mut dyn Read) -> Result<(), RenderError> {
+        self.render.reconfigure(config)?;
+
+        // Drop the cached frame so the new configuration shows up right away
+        *self.cache.lock() = None;
+        Ok(())
+    }
+}
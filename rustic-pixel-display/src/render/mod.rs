@@ -1,21 +1,182 @@
 use anyhow::Result;
-use embedded_graphics::{pixelcolor::Rgb888, prelude::DrawTarget};
-use std::{convert::Infallible, io::Read};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::DrawTarget, primitives::Rectangle};
+use serde::Serialize;
+use std::{convert::Infallible, fmt, io::Read, time::Instant};
 
+mod animation_clock;
+mod blink;
+mod bounds_tracking_canvas;
+mod buffer_canvas;
+mod builder;
+mod centered;
+mod dither_canvas;
+mod error;
+mod fixed_resolution;
+mod gauge;
+mod memoized_render;
+mod outlined;
+mod panel_canvas;
+mod pixel_shift;
+mod poll_gate;
+#[cfg(feature = "test-util")]
+mod recording_canvas;
+mod spinner;
+mod splash;
 mod sub_canvas;
+mod tee_canvas;
+mod throttle;
 
+pub use animation_clock::animation_time;
+pub use blink::{is_blink_visible, Blink};
+pub use bounds_tracking_canvas::BoundsTrackingCanvas;
+pub use buffer_canvas::BufferCanvas;
+pub use builder::RenderBuilder;
+pub use centered::Centered;
+pub use dither_canvas::{BayerMatrix, DitherCanvas};
+pub use error::RenderError;
+pub use fixed_resolution::{scale_buffer, FixedResolution, ScaleMode};
+pub use gauge::Gauge;
+pub use memoized_render::MemoizedRender;
+pub use outlined::{draw_outlined, Outline, OutlineShape, Outlined};
+pub use panel_canvas::{PanelCanvas, PanelMap};
+pub use pixel_shift::{pixel_shift_offset, PixelShift};
+pub use poll_gate::PollGate;
+#[cfg(feature = "test-util")]
+pub use recording_canvas::{DrawOperation, RecordingCanvas};
+pub use spinner::Spinner;
+pub use splash::Splash;
 pub use sub_canvas::SubCanvas;
+pub use tee_canvas::TeeCanvas;
+pub use throttle::Throttle;
 
 /// Performs drawing operations on a embedded-graphics target
 ///
 /// Encapsulates drawing operations into a
+///
+/// Any canvas whose error can be logged can be drawn on, so canvases that write
+/// straight to a socket or bus can report failures. Most renders only
+/// implement `Render` for infallible canvases (`Error = Infallible`), which is
+/// all the drivers provide, while the wrappers are implemented for any canvas
+/// and draw their inner render into an infallible [`BufferCanvas`].
 pub trait Render<D>
 where
-    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
 {
     fn render(&self, canvas: &mut D) -> Result<(), D::Error>;
+
+    /// Draws the frame that is displayed at `now`.
+    ///
+    /// Drivers call this instead of [`Render::render`], reading the clock once
+    /// per frame, so every render of the frame animates from the same instant.
+    /// Animated renders override this and implement `render` as
+    /// `self.render_at(canvas, Instant::now())`, and renders that contain other
+    /// renders pass `now` on to them. Calls [`Render::render`] by default.
+    fn render_at(&self, canvas: &mut D, _now: Instant) -> Result<(), D::Error> {
+        self.render(canvas)
+    }
+
+    /// Called when the render has been selected to be displayed.
+    ///
+    /// Renders that keep background tasks running can use this to resume any
+    /// work that was paused by [`Render::on_deselect`].
+    fn on_select(&self) {}
+
+    /// Called when the render is no longer being displayed.
+    ///
+    /// Renders that poll APIs in the background can use this to pause their
+    /// polling, saving API quota and CPU while they are not visible.
+    fn on_deselect(&self) {}
+
+    /// The color the canvas is cleared to before the render is drawn.
+    ///
+    /// Renders designed on a colored background can return it here instead of
+    /// filling the canvas themselves. `None` clears the canvas to black.
+    fn background_color(&self) -> Option<Rgb888> {
+        None
+    }
+
+    /// The region of the canvas that changed since the previous frame.
+    ///
+    /// Called right before [`Render::render`]. Drivers use this to avoid
+    /// clearing pixels that did not change, and to skip rendering entirely
+    /// when an empty region is returned. `None` (the default) means the whole
+    /// canvas has to be redrawn.
+    fn dirty_region(&self) -> Option<Rectangle> {
+        None
+    }
+
+    /// A short, human readable description of what the render is currently
+    /// showing, i.e. "Weather: Philadelphia 72 °F".
+    ///
+    /// Used to identify active renders in the HTTP API and logs. Defaults to
+    /// the name of the type.
+    fn describe(&self) -> String {
+        std::any::type_name::<Self>().to_owned()
+    }
+
+    /// Whether the render currently has something meaningful to show.
+    ///
+    /// Renders that display fetched data can return `false` before their first
+    /// successful fetch, so a [`Fallback`](crate::fallback::Fallback) can show
+    /// something else in the meantime. Defaults to `true`.
+    fn has_content(&self) -> bool {
+        true
+    }
+
+    /// Called to restart the render from its initial state.
+    ///
+    /// Animated renders can use this to restart their animation or reseed
+    /// their state. Renders that contain other renders pass it on to them. Does
+    /// nothing by default.
+    fn reset(&self) {}
+
+    /// Applies a new configuration to the render in place.
+    ///
+    /// Unlike unloading the render and loading another one, the render can
+    /// keep its background task and any data that still applies to the new
+    /// configuration. `config` is the same JSON its factory is loaded with.
+    /// Takes a `dyn Read` rather than a generic reader so renders can still be
+    /// used as trait objects. Renders that can't be reconfigured return
+    /// [`RenderError::Unsupported`], the default.
+    fn reconfigure(&self, _config: &mut dyn Read) -> Result<(), RenderError> {
+        Err(RenderError::Unsupported(
+            "reconfiguring without reloading".to_owned(),
+        ))
+    }
+}
+
+/// A [`Render`] that can be shared with the render thread of a driver.
+///
+/// Implemented for every render that is `Send + Sync`. Factories return this
+/// instead of a plain [`Render`] so that thread safety is checked when a render
+/// is constructed, rather than when it is handed to a driver.
+pub trait SyncRender<D>: Render<D> + Send + Sync
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+{
+}
+
+impl<D, T> SyncRender<D> for T
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+    T: Render<D> + Send + Sync,
+{
 }
 
+/// A pixel color format a render can be drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ColorFormat {
+    Rgb888,
+    Rgb565,
+    Rgb555,
+}
+
+/// An example configuration of a render, see [`RenderFactory::example_config`]
+pub type ExampleConfig = serde_json::Value;
+
 /// Constructs a [`Render`] from a configuration.
 ///
 /// The `RenderFactory` trait is responsible for advertising the name and a
@@ -36,6 +197,95 @@ where
     /// Returns a short description about what the render does.
     fn render_description(&self) -> &'static str;
 
+    /// Returns the color formats the render can be drawn in, so clients can
+    /// avoid loading it onto a pipeline it does not support.
+    ///
+    /// This is informational only, renders are always drawn in
+    /// [`Rgb888`](ColorFormat::Rgb888) which is the default.
+    fn supported_colors(&self) -> &'static [ColorFormat] {
+        &[ColorFormat::Rgb888]
+    }
+
+    /// Returns an example configuration to start writing a configuration
+    /// from, `None` (the default) if the factory doesn't provide one.
+    fn example_config(&self) -> Option<ExampleConfig> {
+        None
+    }
+
     /// Attempts to construct a render based on the provided configuration.
-    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn Render<D>>>;
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError>;
+
+    /// Attempts to construct a render that draws onto a [`BufferCanvas`], used
+    /// to preview a configuration without loading it onto the display.
+    ///
+    /// Factories that don't support previews return an error, which is the
+    /// default.
+    fn load_preview<R: Read>(
+        &self,
+        _reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        Err(RenderError::Unsupported(format!(
+            "\"{}\" does not support previews",
+            self.render_name()
+        )))
+    }
+}
+
+/// What a [`RenderFactory`] advertises about the render it constructs, as
+/// listed by the discovery endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct FactoryInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub supported_colors: &'static [ColorFormat],
+    pub example_config: Option<ExampleConfig>,
+}
+
+impl FactoryInfo {
+    pub fn new<D, F>(factory: &F) -> Self
+    where
+        D: DrawTarget<Color = Rgb888, Error = Infallible>,
+        F: RenderFactory<D>,
+    {
+        Self {
+            name: factory.render_name(),
+            description: factory.render_description(),
+            supported_colors: factory.supported_colors(),
+            example_config: factory.example_config(),
+        }
+    }
+}
+
+impl fmt::Display for FactoryInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.name)?;
+        writeln!(f, "  {}", self.description)?;
+
+        let colors = self
+            .supported_colors
+            .iter()
+            .map(|color| format!("{color:?}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "  Colors: {colors}")?;
+
+        if let Some(example_config) = &self.example_config {
+            write!(f, "\n  Example config: {example_config}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The complete set of [`RenderFactory`]s compiled into the program.
+///
+/// This is implemented by `#[derive(RenderFactories)]` and allows renders that
+/// are composed of other renders, like layouts, to construct their children
+/// from the same factories that are available to the registry.
+pub trait RenderFactories<D>: RenderFactory<D> + Sized
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    /// Returns an instance of every factory in the set.
+    fn factories() -> Vec<Self>;
 }
@@ -0,0 +1,184 @@
+use super::{BufferCanvas, Render, RenderError};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, Point, RgbColor},
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+use serde::Deserialize;
+use std::{convert::Infallible, fmt, io::Read, time::Instant};
+
+/// Which neighbors of every drawn pixel [`draw_outlined`] fills in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlineShape {
+    /// The pixels above, below, left and right, a thin outline that keeps
+    /// small fonts readable
+    #[default]
+    Cross,
+
+    /// All eight surrounding pixels, a heavier outline that also covers the
+    /// corners
+    Square,
+
+    /// Only the pixel below and to the right, a drop shadow
+    Shadow,
+}
+
+impl OutlineShape {
+    fn offsets(self) -> &'static [Point] {
+        const CROSS: [Point; 4] = [
+            Point::new(0, -1),
+            Point::new(-1, 0),
+            Point::new(1, 0),
+            Point::new(0, 1),
+        ];
+        const SQUARE: [Point; 8] = [
+            Point::new(-1, -1),
+            Point::new(0, -1),
+            Point::new(1, -1),
+            Point::new(-1, 0),
+            Point::new(1, 0),
+            Point::new(-1, 1),
+            Point::new(0, 1),
+            Point::new(1, 1),
+        ];
+        const SHADOW: [Point; 1] = [Point::new(1, 1)];
+
+        match self {
+            OutlineShape::Cross => &CROSS,
+            OutlineShape::Square => &SQUARE,
+            OutlineShape::Shadow => &SHADOW,
+        }
+    }
+}
+
+/// The color and shape of an outline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outline {
+    pub color: Rgb888,
+    pub shape: OutlineShape,
+}
+
+impl Default for Outline {
+    fn default() -> Self {
+        Self {
+            color: Rgb888::BLACK,
+            shape: OutlineShape::default(),
+        }
+    }
+}
+
+/// Draws whatever `draw` draws with an outline around it.
+///
+/// `draw` is given a [`BufferCanvas`] the size of `canvas` that starts out
+/// black. Every pixel it leaves black is treated as empty, since black is off
+/// on an LED panel, and every other pixel as content. The outline is drawn on
+/// the empty pixels next to the content, then the content on top. Empty pixels
+/// are not drawn, so whatever is already on `canvas` shows through.
+pub fn draw_outlined<D, F>(canvas: &mut D, outline: Outline, draw: F) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb888>,
+    F: FnOnce(&mut BufferCanvas) -> Result<(), Infallible>,
+{
+    let bounding_box = canvas.bounding_box();
+
+    let mut buffer = BufferCanvas::new(bounding_box.size);
+    if let Err(e) = draw(&mut buffer) {
+        match e {}
+    }
+
+    let is_content = |point: Point| {
+        buffer
+            .pixel(point)
+            .is_some_and(|color| color != Rgb888::BLACK)
+    };
+
+    let area = Rectangle::new(Point::zero(), buffer.size());
+    let outline_pixels = area
+        .points()
+        .filter(|&point| {
+            !is_content(point)
+                && outline
+                    .shape
+                    .offsets()
+                    .iter()
+                    // A pixel is outlined if the content is at the opposite offset
+                    .any(|&offset| is_content(point - offset))
+        })
+        .map(|point| Pixel(bounding_box.top_left + point, outline.color));
+    canvas.draw_iter(outline_pixels)?;
+
+    let content_pixels = area.points().filter_map(|point| {
+        buffer
+            .pixel(point)
+            .filter(|&color| color != Rgb888::BLACK)
+            .map(|color| Pixel(bounding_box.top_left + point, color))
+    });
+    canvas.draw_iter(content_pixels)
+}
+
+/// Draws the inner render with an outline, so light text stays legible over a
+/// busy background.
+///
+/// See [`draw_outlined`] for which pixels are outlined. Only the content and
+/// its outline are drawn, so the background of the inner render is dropped.
+pub struct Outlined<R> {
+    render: R,
+    outline: Outline,
+}
+
+impl<R> Outlined<R> {
+    pub fn new(render: R, outline: Outline) -> Self {
+        Self { render, outline }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.render
+    }
+
+    pub fn outline(&self) -> Outline {
+        self.outline
+    }
+}
+
+impl<D, R> Render<D> for Outlined<R>
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+    R: Render<BufferCanvas>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        draw_outlined(canvas, self.outline, |buffer| {
+            self.render.render_at(buffer, now)
+        })
+    }
+
+    fn on_select(&self) {
+        self.render.on_select();
+    }
+
+    fn on_deselect(&self) {
+        self.render.on_deselect();
+    }
+
+    fn describe(&self) -> String {
+        self.render.describe()
+    }
+
+    fn has_content(&self) -> bool {
+        self.render.has_content()
+    }
+
+    fn reset(&self) {
+        self.render.reset();
+    }
+
+    fn reconfigure(&self, config: &mut dyn Read) -> Result<(), RenderError> {
+        self.render.reconfigure(config)
+    }
+}
@@ -0,0 +1,171 @@
+use crate::config::PhysicalPanel;
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    geometry::Dimensions,
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// A physical panel, where it is on the logical canvas and where its pixels
+/// are on the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PanelMapping {
+    logical: Rectangle,
+    chain_offset: Point,
+}
+
+/// Maps a logical canvas made up of physical panels of different sizes onto
+/// the canvas of the chain they are connected to.
+///
+/// The driver addresses a chain as one canvas `cols * chain_length` wide and
+/// `rows` tall, every panel shifting its share of each row on to the next
+/// panel. A panel therefore takes up as many columns of the chain canvas as it
+/// is wide, starting where the panel before it in the chain ends, and the
+/// rows of the chain canvas below its own height are never shown. The panels
+/// are listed in the order they are chained, from the one connected to the
+/// controller, and each one is placed on the logical canvas at its own `x` and
+/// `y`, so a 32x32 panel can sit below the left half of a 64x64 panel
+/// even though it comes after it in the chain.
+///
+/// The logical canvas spans from the origin to the bottom right corner of the
+/// furthest panel. Pixels drawn where there is no panel are dropped, so
+/// `LayoutManager` regions should be placed within a panel to show up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanelMap {
+    panels: Vec<PanelMapping>,
+    size: Size,
+}
+
+impl PanelMap {
+    /// Returns an error if the panels overlap, are empty, or do not fit on a
+    /// chain canvas of `chain_size`.
+    pub fn new(panels: &[PhysicalPanel], chain_size: Size) -> Result<Self> {
+        if panels.is_empty() {
+            return Err(anyhow!("At least one panel is required"));
+        }
+
+        let mut mappings: Vec<PanelMapping> = Vec::with_capacity(panels.len());
+        let mut chain_x = 0;
+
+        for panel in panels {
+            if panel.w == 0 || panel.h == 0 {
+                return Err(anyhow!(
+                    "Panel at {}, {} must not be empty",
+                    panel.x,
+                    panel.y
+                ));
+            }
+
+            if panel.x < 0 || panel.y < 0 {
+                return Err(anyhow!(
+                    "Panel at {}, {} must not be placed at negative coordinates",
+                    panel.x,
+                    panel.y
+                ));
+            }
+
+            if panel.h > chain_size.height {
+                return Err(anyhow!(
+                    "Panel at {}, {} is {} rows tall, but the chain only has {}",
+                    panel.x,
+                    panel.y,
+                    panel.h,
+                    chain_size.height
+                ));
+            }
+
+            let logical = Rectangle::new(Point::new(panel.x, panel.y), Size::new(panel.w, panel.h));
+            if let Some(other) = mappings
+                .iter()
+                .find(|mapping| !mapping.logical.intersection(&logical).is_zero_sized())
+            {
+                return Err(anyhow!(
+                    "Panel at {}, {} overlaps the panel at {}, {}",
+                    panel.x,
+                    panel.y,
+                    other.logical.top_left.x,
+                    other.logical.top_left.y
+                ));
+            }
+
+            mappings.push(PanelMapping {
+                logical,
+                chain_offset: Point::new(chain_x as i32, 0),
+            });
+            chain_x += panel.w;
+        }
+
+        if chain_x > chain_size.width {
+            return Err(anyhow!(
+                "The panels are {chain_x} columns wide in total, but the chain only has {}",
+                chain_size.width
+            ));
+        }
+
+        let bottom_right = mappings
+            .iter()
+            .fold(Point::zero(), |bottom_right, mapping| {
+                bottom_right.component_max(mapping.logical.top_left + mapping.logical.size)
+            });
+
+        Ok(Self {
+            panels: mappings,
+            size: Size::new(bottom_right.x as u32, bottom_right.y as u32),
+        })
+    }
+
+    /// The size of the logical canvas
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Where the pixel at `point` on the logical canvas is on the chain
+    /// canvas, `None` if no panel covers it
+    pub fn chain_point(&self, point: Point) -> Option<Point> {
+        self.panels
+            .iter()
+            .find(|mapping| mapping.logical.contains(point))
+            .map(|mapping| point - mapping.logical.top_left + mapping.chain_offset)
+    }
+
+    /// Wraps the canvas of the chain in a canvas the size of the logical
+    /// canvas.
+    pub fn canvas<'a, D>(&'a self, chain: &'a mut D) -> PanelCanvas<'a, D> {
+        PanelCanvas { map: self, chain }
+    }
+}
+
+/// Canvas that routes every pixel drawn on the logical canvas of a
+/// [`PanelMap`] to where it is on the chain.
+pub struct PanelCanvas<'a, D> {
+    map: &'a PanelMap,
+    chain: &'a mut D,
+}
+
+impl<D> Dimensions for PanelCanvas<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), self.map.size)
+    }
+}
+
+impl<D> DrawTarget for PanelCanvas<'_, D>
+where
+    D: DrawTarget<Color = Rgb888>,
+{
+    type Color = Rgb888;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let map = self.map;
+
+        self.chain
+            .draw_iter(pixels.into_iter().filter_map(|Pixel(point, color)| {
+                map.chain_point(point).map(|point| Pixel(point, color))
+            }))
+    }
+}
@@ -0,0 +1,145 @@
+use super::{BufferCanvas, Render, RenderError};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    primitives::{PointsIter, Rectangle},
+};
+use std::{
+    fmt,
+    io::Read,
+    time::{Duration, Instant},
+};
+
+/// Position along a walk from 0 up to `range`, down to `-range` and back to 0
+/// that moves 1 every step
+fn triangle(step: u64, range: u32) -> i32 {
+    let range = i64::from(range);
+    let phase = (step % (4 * range as u64)) as i64;
+
+    let position = if phase <= range {
+        phase
+    } else if phase <= 3 * range {
+        2 * range - phase
+    } else {
+        phase - 4 * range
+    };
+
+    position as i32
+}
+
+/// Returns how far [`PixelShift`] moves its inner render after `elapsed`.
+///
+/// Every `interval` the offset moves by 1 pixel, sweeping horizontally between
+/// `-range` and `range`. After every full sweep it also moves 1 pixel
+/// vertically, so over time every offset within `range` in both directions is
+/// used. A `range` of 0 or an empty `interval` never moves.
+pub fn pixel_shift_offset(elapsed: Duration, interval: Duration, range: u32) -> Point {
+    if range == 0 || interval.is_zero() {
+        return Point::zero();
+    }
+
+    let step = (elapsed.as_millis() / interval.as_millis().max(1)) as u64;
+    let sweep = 4 * u64::from(range);
+
+    Point::new(triangle(step, range), triangle(step / sweep, range))
+}
+
+/// Slowly moves the inner render around to even out the wear of the pixels.
+///
+/// Static content, i.e. a clock, lights the same pixels for hours, which can
+/// burn in on some panels. The inner render is drawn into a [`BufferCanvas`]
+/// the size of the canvas, then copied onto the canvas moved by
+/// [`pixel_shift_offset`]. The margin uncovered by the move is drawn black, and
+/// whatever is moved past the edge of the canvas is cut off, so `range` should
+/// be kept small.
+pub struct PixelShift<R> {
+    render: R,
+    interval: Duration,
+    range: u32,
+    started: Instant,
+}
+
+impl<R> PixelShift<R> {
+    pub fn new(render: R, interval: Duration, range: u32) -> Self {
+        Self {
+            render,
+            interval,
+            range,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.render
+    }
+
+    /// How far the inner render is currently moved
+    pub fn offset(&self) -> Point {
+        self.offset_at(Instant::now())
+    }
+
+    /// How far the inner render is moved at `now`
+    pub fn offset_at(&self, now: Instant) -> Point {
+        pixel_shift_offset(
+            now.saturating_duration_since(self.started),
+            self.interval,
+            self.range,
+        )
+    }
+}
+
+impl<D, R> Render<D> for PixelShift<R>
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+    R: Render<BufferCanvas>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let bounding_box = canvas.bounding_box();
+
+        let mut buffer = BufferCanvas::new(bounding_box.size);
+        let drawn = buffer
+            .clear(self.render.background_color().unwrap_or(Rgb888::BLACK))
+            .and_then(|_| self.render.render_at(&mut buffer, now));
+        if let Err(e) = drawn {
+            match e {}
+        }
+
+        let offset = self.offset_at(now);
+        canvas.fill_contiguous(
+            &bounding_box,
+            Rectangle::new(Point::zero(), bounding_box.size)
+                .points()
+                .map(|point| buffer.pixel(point - offset).unwrap_or(Rgb888::BLACK)),
+        )
+    }
+
+    fn on_select(&self) {
+        self.render.on_select();
+    }
+
+    fn on_deselect(&self) {
+        self.render.on_deselect();
+    }
+
+    fn describe(&self) -> String {
+        self.render.describe()
+    }
+
+    fn has_content(&self) -> bool {
+        self.render.has_content()
+    }
+
+    fn reset(&self) {
+        // The offset keeps moving, restarting it would wear the same pixels
+        self.render.reset();
+    }
+
+    fn reconfigure(&self, config: &mut dyn Read) -> Result<(), RenderError> {
+        self.render.reconfigure(config)
+    }
+}
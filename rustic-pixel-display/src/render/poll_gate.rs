@@ -0,0 +1,65 @@
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Shared "active" flag used to pause a render's background polling.
+///
+/// A render holds on to a `PollGate` and flips it from its
+/// [`Render::on_select`](super::Render::on_select) and
+/// [`Render::on_deselect`](super::Render::on_deselect) hooks, while its update
+/// task waits on [`PollGate::active`] before issuing a fetch. This way renders
+/// that are not being displayed do not use up API quota.
+///
+/// A newly created gate is active, so renders that are never selected (for
+/// example when driven by `MatrixDriver::with_single_render`) poll as usual.
+/// Renders loaded into a `Registry` are deselected as they are loaded, and
+/// only poll once they are selected.
+#[derive(Clone)]
+pub struct PollGate {
+    active: Arc<watch::Sender<bool>>,
+}
+
+impl Default for PollGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PollGate {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(true);
+
+        Self {
+            active: Arc::new(sender),
+        }
+    }
+
+    /// Allows the update task to resume polling.
+    pub fn resume(&self) {
+        self.active.send_replace(true);
+    }
+
+    /// Stops the update task from polling until [`PollGate::resume`] is called.
+    pub fn pause(&self) {
+        self.active.send_replace(false);
+    }
+
+    pub fn is_active(&self) -> bool {
+        *self.active.borrow()
+    }
+
+    /// Completes once the gate is active, immediately if it already is.
+    pub async fn active(&self) {
+        let mut receiver = self.active.subscribe();
+        let _ = receiver.wait_for(|active| *active).await;
+    }
+
+    /// Completes once the gate is paused, immediately if it already is.
+    ///
+    /// Update tasks should race this against their refresh timer, so that once
+    /// the render is displayed again it fetches fresh data straight away
+    /// instead of waiting out the remainder of the refresh interval.
+    pub async fn paused(&self) {
+        let mut receiver = self.active.subscribe();
+        let _ = receiver.wait_for(|active| !*active).await;
+    }
+}
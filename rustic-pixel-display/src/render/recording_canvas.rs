@@ -0,0 +1,110 @@
+use super::BufferCanvas;
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, Point, RgbColor, Size},
+    primitives::Rectangle,
+    Pixel,
+};
+use std::convert::Infallible;
+
+/// A draw operation performed on a [`RecordingCanvas`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawOperation {
+    /// The canvas was cleared with the color
+    Clear(Rgb888),
+
+    /// A batch of pixels was drawn, covering the bounds. Batches that did not
+    /// contain any pixels have empty bounds.
+    DrawIter(Rectangle),
+}
+
+/// Canvas that keeps a log of every draw operation performed on it.
+///
+/// Intended for tests, where the log can be used to assert on the draw calls
+/// a render makes and [`RecordingCanvas::to_ascii_art`] can be compared
+/// against a golden snapshot of the expected output.
+#[derive(Clone, Debug)]
+pub struct RecordingCanvas {
+    buffer: BufferCanvas,
+    operations: Vec<DrawOperation>,
+}
+
+impl RecordingCanvas {
+    pub fn new(size: Size) -> Self {
+        Self {
+            buffer: BufferCanvas::new(size),
+            operations: Vec::new(),
+        }
+    }
+
+    pub fn buffer(&self) -> &BufferCanvas {
+        &self.buffer
+    }
+
+    /// Returns the draw operations in the order they were performed.
+    pub fn operations(&self) -> &[DrawOperation] {
+        &self.operations
+    }
+
+    /// Renders the canvas as text, one line per row, with black pixels drawn
+    /// as `.` and all other pixels drawn as `#`.
+    pub fn to_ascii_art(&self) -> String {
+        let size = self.buffer.size();
+
+        (0..size.height)
+            .map(|y| {
+                (0..size.width)
+                    .map(
+                        |x| match self.buffer.pixel(Point::new(x as i32, y as i32)) {
+                            Some(Rgb888::BLACK) | None => '.',
+                            Some(_) => '#',
+                        },
+                    )
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl OriginDimensions for RecordingCanvas {
+    fn size(&self) -> Size {
+        self.buffer.size()
+    }
+}
+
+impl DrawTarget for RecordingCanvas {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let pixels = pixels.into_iter().collect::<Vec<_>>();
+
+        let bounds = match pixels
+            .iter()
+            .map(|Pixel(point, _)| *point)
+            .reduce(|a, b| Point::new(a.x.min(b.x), a.y.min(b.y)))
+        {
+            Some(top_left) => {
+                let bottom_right = pixels
+                    .iter()
+                    .map(|Pixel(point, _)| *point)
+                    .fold(top_left, |a, b| Point::new(a.x.max(b.x), a.y.max(b.y)));
+
+                Rectangle::with_corners(top_left, bottom_right)
+            }
+            None => Rectangle::zero(),
+        };
+
+        self.operations.push(DrawOperation::DrawIter(bounds));
+        self.buffer.draw_iter(pixels)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.operations.push(DrawOperation::Clear(color));
+        self.buffer.clear(color)
+    }
+}
@@ -0,0 +1,109 @@
+use super::{animation_time, Render};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Primitive, RgbColor},
+    primitives::{Circle, PrimitiveStyle},
+    Drawable,
+};
+use std::{
+    convert::Infallible,
+    f32::consts::PI,
+    time::{Duration, Instant},
+};
+
+/// The number of dots arranged around the spinner
+const SPINNER_DOTS: usize = 8;
+
+/// Dots arranged in a circle with a bright head that travels around it,
+/// leaving a fading tail behind.
+///
+/// The position of the head is derived from the [`animation_time`] of the
+/// frame, so it spins at the same speed regardless of the frame rate and every
+/// spinner on the panel is in step.
+pub struct Spinner {
+    /// Diameter of the spinner in pixels
+    size: u32,
+    color: Rgb888,
+
+    /// How long the head stays on each dot
+    frame_duration: Duration,
+}
+
+impl Spinner {
+    pub fn new(size: u32, color: Rgb888, frame_duration: Duration) -> Self {
+        Self {
+            size,
+            color,
+            frame_duration,
+        }
+    }
+
+    /// Returns the index of the dot the head is on at `elapsed` on the
+    /// animation clock.
+    pub fn frame_at(&self, elapsed: Duration) -> usize {
+        let frame_millis = self.frame_duration.as_millis().max(1);
+
+        ((elapsed.as_millis() / frame_millis) % SPINNER_DOTS as u128) as usize
+    }
+
+    /// Returns the index of the dot the head is currently on.
+    pub fn frame(&self) -> usize {
+        self.frame_at(animation_time(Instant::now()))
+    }
+
+    /// Draws the spinner centered on `center`.
+    pub fn draw<D>(&self, canvas: &mut D, center: Point) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        self.draw_at(canvas, center, Instant::now())
+    }
+
+    /// Draws the spinner centered on `center` as it is at `now`.
+    pub fn draw_at<D>(&self, canvas: &mut D, center: Point, now: Instant) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        let head = self.frame_at(animation_time(now));
+        let dot_diameter = (self.size / 5).max(1);
+        let radius = self.size.saturating_sub(dot_diameter) as f32 / 2.0;
+
+        for dot in 0..SPINNER_DOTS {
+            // Dots further behind the head are drawn dimmer
+            let age = (head + SPINNER_DOTS - dot) % SPINNER_DOTS;
+            let brightness = (SPINNER_DOTS - age) as u32;
+            let scale = |channel: u8| (channel as u32 * brightness / SPINNER_DOTS as u32) as u8;
+
+            let angle = 2.0 * PI * dot as f32 / SPINNER_DOTS as f32;
+            let dot_center = center
+                + Point::new(
+                    (radius * angle.cos()).round() as i32,
+                    (radius * angle.sin()).round() as i32,
+                );
+
+            Circle::with_center(dot_center, dot_diameter)
+                .into_styled(PrimitiveStyle::with_fill(Rgb888::new(
+                    scale(self.color.r()),
+                    scale(self.color.g()),
+                    scale(self.color.b()),
+                )))
+                .draw(canvas)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<D> Render<D> for Spinner
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let center = canvas.bounding_box().center();
+        self.draw_at(canvas, center, now)
+    }
+}
@@ -0,0 +1,72 @@
+use super::Render;
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Primitive, RgbColor, Size},
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable,
+};
+use std::convert::Infallible;
+
+const TEXT: &str = "Starting...";
+
+/// Side, in pixels, of each of the squares of the logo
+const LOGO_CELL: u32 = 4;
+
+/// Colors of the squares of the logo, left to right and top to bottom
+const LOGO_COLORS: [Rgb888; 4] = [Rgb888::RED, Rgb888::GREEN, Rgb888::BLUE, Rgb888::WHITE];
+
+/// Space between the logo and the text
+const LOGO_MARGIN: i32 = 3;
+
+/// The splash screen a [`MatrixDriver`](crate::driver::MatrixDriver) draws
+/// while starting up if no other splash is provided: a logo of red, green,
+/// blue and white pixels above "Starting...".
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Splash;
+
+impl<D> Render<D> for Splash
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let center = canvas.bounding_box().center();
+        let logo_side = (LOGO_CELL * 2) as i32;
+        let text_height = FONT_6X10.character_size.height as i32;
+        let top = center.y - (logo_side + LOGO_MARGIN + text_height) / 2;
+        let logo_left = center.x - logo_side / 2;
+
+        for (index, color) in LOGO_COLORS.into_iter().enumerate() {
+            let column = (index % 2) as i32;
+            let row = (index / 2) as i32;
+
+            Rectangle::new(
+                Point::new(
+                    logo_left + column * LOGO_CELL as i32,
+                    top + row * LOGO_CELL as i32,
+                ),
+                Size::new_equal(LOGO_CELL),
+            )
+            .into_styled(PrimitiveStyle::with_fill(color))
+            .draw(canvas)?;
+        }
+
+        Text::with_text_style(
+            TEXT,
+            Point::new(center.x, top + logo_side + LOGO_MARGIN),
+            MonoTextStyle::new(&FONT_6X10, Rgb888::WHITE),
+            TextStyleBuilder::new()
+                .alignment(Alignment::Center)
+                .baseline(Baseline::Top)
+                .build(),
+        )
+        .draw(canvas)?;
+
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        "Splash".to_owned()
+    }
+}
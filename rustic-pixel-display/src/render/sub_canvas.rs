@@ -1,14 +1,29 @@
 use anyhow::Result;
 use embedded_graphics::{
+    geometry::Dimensions,
     prelude::{DrawTarget, OriginDimensions, PixelColor, Point, Size},
-    primitives::Rectangle,
+    primitives::{ContainsPoint, PointsIter, Rectangle},
     transform::Transform,
     Pixel,
 };
 
+/// A rectangular region of a parent canvas that can be drawn on as if it was a
+/// canvas of its own.
+///
+/// Drawing operations are translated by `offset`, and fills are clipped to the
+/// region. Clearing a sub canvas only fills its own region, so a render can
+/// clear its part of a layout without wiping the rest of the panel.
+///
+/// A sub canvas can additionally be restricted to a clip rectangle, in which
+/// case every pixel outside of it is discarded. This lets a render redraw only
+/// part of its region, i.e. the area it reported as dirty.
 pub struct SubCanvas<'a, D> {
     offset: Point,
     size: Size,
+
+    /// Region of the sub canvas, relative to `offset`, that pixels are drawn in
+    clip: Option<Rectangle>,
+
     canvas: &'a mut D,
 }
 
@@ -17,9 +32,52 @@ impl<'a, D> SubCanvas<'a, D> {
         SubCanvas {
             offset,
             size,
+            clip: None,
+            canvas,
+        }
+    }
+
+    /// Creates a sub canvas that only draws the pixels inside of `clip`. The
+    /// clip rectangle is relative to the sub canvas, so it is in the same
+    /// coordinates the render draws in.
+    pub fn with_clip(offset: Point, size: Size, clip: Rectangle, canvas: &'a mut D) -> Self {
+        SubCanvas {
+            offset,
+            size,
+            clip: Some(clip),
             canvas,
         }
     }
+
+    /// Creates a sub canvas of this sub canvas. `offset` is relative to this
+    /// sub canvas, so the region starts at `self.offset + offset` on the parent.
+    pub fn sub_canvas(&mut self, offset: Point, size: Size) -> SubCanvas<'_, Self> {
+        SubCanvas::new(offset, size, self)
+    }
+}
+
+impl<D> SubCanvas<'_, D>
+where
+    D: DrawTarget,
+{
+    /// Returns if the sub canvas covers the whole parent canvas, in which case
+    /// drawing operations can be forwarded without being translated.
+    fn covers_parent(&self) -> bool {
+        self.clip.is_none()
+            && self.offset == Point::zero()
+            && self.canvas.bounding_box() == Rectangle::new(Point::zero(), self.size)
+    }
+
+    /// Returns the region of the sub canvas that can be drawn in, relative to
+    /// `offset`.
+    fn drawable_area(&self) -> Rectangle {
+        let bounds = Rectangle::new(Point::zero(), self.size);
+
+        match self.clip {
+            Some(clip) => bounds.intersection(&clip),
+            None => bounds,
+        }
+    }
 }
 
 impl<D> OriginDimensions for SubCanvas<'_, D> {
@@ -40,11 +98,19 @@ where
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        let translated_pixels = pixels.into_iter().map(|pixel| {
-            let point = pixel.0;
-            let translated_point = self.offset + point;
-            Pixel(translated_point, pixel.1)
-        });
+        if self.covers_parent() {
+            return self.canvas.draw_iter(pixels);
+        }
+
+        let clip = self.clip.map(|_| self.drawable_area());
+        let translated_pixels = pixels
+            .into_iter()
+            .filter(|pixel| clip.map_or(true, |clip| clip.contains(pixel.0)))
+            .map(|pixel| {
+                let point = pixel.0;
+                let translated_point = self.offset + point;
+                Pixel(translated_point, pixel.1)
+            });
 
         self.canvas.draw_iter(translated_pixels)
     }
@@ -53,20 +119,50 @@ where
     where
         I: IntoIterator<Item = Self::Color>,
     {
+        let clipped_area = area.intersection(&self.drawable_area());
+
+        if clipped_area.is_zero_sized() {
+            return Ok(());
+        }
+
+        // Common case of the area being within the sub canvas, the colors can be
+        // forwarded as is
+        if clipped_area == *area {
+            return self
+                .canvas
+                .fill_contiguous(&area.translate(self.offset), colors);
+        }
+
+        // Drop the colors of the pixels that fall outside of the sub canvas so
+        // they don't spill onto the rest of the parent canvas
+        let clipped_colors = area
+            .points()
+            .zip(colors)
+            .filter(|(point, _)| clipped_area.contains(*point))
+            .map(|(_, color)| color);
+
         self.canvas
-            .fill_contiguous(&area.translate(self.offset), colors)
+            .fill_contiguous(&clipped_area.translate(self.offset), clipped_colors)
     }
 
     fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
-        self.canvas.fill_solid(&area.translate(self.offset), color)
+        let clipped_area = area.intersection(&self.drawable_area());
+
+        if clipped_area.is_zero_sized() {
+            return Ok(());
+        }
+
+        self.canvas
+            .fill_solid(&clipped_area.translate(self.offset), color)
     }
 
     fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
-        let translated_bounds = Rectangle {
-            top_left: self.offset,
-            size: self.size,
-        };
+        // Only a sub canvas spanning the whole parent may clear the parent
+        if self.covers_parent() {
+            return self.canvas.clear(color);
+        }
 
-        self.canvas.fill_solid(&translated_bounds, color)
+        self.canvas
+            .fill_solid(&self.drawable_area().translate(self.offset), color)
     }
 }
@@ -0,0 +1,88 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    prelude::{Dimensions, DrawTarget, PixelColor},
+    primitives::Rectangle,
+    Pixel,
+};
+
+/// Canvas that mirrors every drawing operation onto two canvases of the same
+/// size, for example two identical panels showing the same content.
+pub struct TeeCanvas<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> TeeCanvas<A, B>
+where
+    A: Dimensions,
+    B: Dimensions,
+{
+    /// Returns an error if the bounding boxes of the canvases differ.
+    pub fn new(first: A, second: B) -> Result<Self> {
+        let (first_box, second_box) = (first.bounding_box(), second.bounding_box());
+
+        if first_box != second_box {
+            return Err(anyhow!(
+                "Canvases must have the same bounding box, got {:?} and {:?}",
+                first_box,
+                second_box
+            ));
+        }
+
+        Ok(Self { first, second })
+    }
+
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A, B> Dimensions for TeeCanvas<A, B>
+where
+    A: Dimensions,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.first.bounding_box()
+    }
+}
+
+impl<A, B, C> DrawTarget for TeeCanvas<A, B>
+where
+    C: PixelColor,
+    A: DrawTarget<Color = C>,
+    B: DrawTarget<Color = C, Error = A::Error>,
+{
+    type Color = C;
+    type Error = A::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        // The iterator can only be consumed once
+        let pixels = pixels.into_iter().collect::<Vec<_>>();
+
+        self.first.draw_iter(pixels.iter().copied())?;
+        self.second.draw_iter(pixels)
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let colors = colors.into_iter().collect::<Vec<_>>();
+
+        self.first.fill_contiguous(area, colors.iter().copied())?;
+        self.second.fill_contiguous(area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.first.fill_solid(area, color)?;
+        self.second.fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.first.clear(color)?;
+        self.second.clear(color)
+    }
+}
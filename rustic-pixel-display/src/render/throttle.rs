@@ -0,0 +1,116 @@
+use super::{BufferCanvas, Render, RenderError};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, Point},
+    primitives::Rectangle,
+};
+use parking_lot::Mutex;
+use std::{
+    fmt,
+    io::Read,
+    time::{Duration, Instant},
+};
+
+/// Redraws the inner render at most once every `interval`.
+///
+/// The inner render is drawn into a cached [`BufferCanvas`] and the cached
+/// frame is copied onto the canvas until `interval` has passed or the canvas is
+/// resized. Useful to limit an expensive render within a layout while the rest
+/// of the layout keeps animating.
+pub struct Throttle<R> {
+    render: R,
+    interval: Duration,
+    cache: Mutex<Option<(Instant, BufferCanvas)>>,
+}
+
+impl<R> Throttle<R> {
+    pub fn new(render: R, interval: Duration) -> Self {
+        Self {
+            render,
+            interval,
+            cache: Mutex::new(None),
+        }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.render
+    }
+}
+
+impl<D, R> Render<D> for Throttle<R>
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+    R: Render<BufferCanvas>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let canvas_size = canvas.bounding_box().size;
+        let mut cache = self.cache.lock();
+
+        let is_stale = match &*cache {
+            Some((drawn_at, buffer)) => {
+                now.saturating_duration_since(*drawn_at) >= self.interval
+                    || buffer.size() != canvas_size
+            }
+            None => true,
+        };
+
+        if is_stale {
+            let mut buffer = BufferCanvas::new(canvas_size);
+            if let Err(e) = self.render.render_at(&mut buffer, now) {
+                match e {}
+            }
+            *cache = Some((now, buffer));
+        }
+
+        match &*cache {
+            Some((_, buffer)) => buffer.draw_to(canvas, Point::zero()),
+            None => Ok(()),
+        }
+    }
+
+    fn on_select(&self) {
+        self.render.on_select();
+    }
+
+    fn on_deselect(&self) {
+        self.render.on_deselect();
+    }
+
+    fn background_color(&self) -> Option<Rgb888> {
+        self.render.background_color()
+    }
+
+    fn dirty_region(&self) -> Option<Rectangle> {
+        match &*self.cache.lock() {
+            Some((drawn_at, _)) if drawn_at.elapsed() < self.interval => Some(Rectangle::zero()),
+            _ => None,
+        }
+    }
+
+    fn describe(&self) -> String {
+        self.render.describe()
+    }
+
+    fn has_content(&self) -> bool {
+        self.render.has_content()
+    }
+
+    fn reset(&self) {
+        // Drop the cached frame so the reset shows up right away
+        *self.cache.lock() = None;
+        self.render.reset();
+    }
+
+    fn reconfigure(&self, config: &mut dyn Read) -> Result<(), RenderError> {
+        self.render.reconfigure(config)?;
+
+        // Drop the cached frame so the new configuration shows up right away
+        *self.cache.lock() = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,341 @@
+use crate::{
+    clock::{SharedClock, SystemClock},
+    layout_manager::LayoutRegionConfig,
+    render::{Render, RenderError, RenderFactories, RenderFactory, SyncRender},
+};
+use anyhow::Result;
+use chrono::NaiveTime;
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, RgbColor},
+    primitives::Rectangle,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Deserializer};
+use std::{convert::Infallible, io::Read, marker::PhantomData, time::Instant};
+
+/// A time of day range, `from` inclusive and `to` exclusive.
+///
+/// Ranges where `to` is before `from` wrap past midnight, i.e. 22:00 to 06:00.
+/// A range where `from` and `to` are equal covers the whole day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeRange {
+    pub from: NaiveTime,
+    pub to: NaiveTime,
+}
+
+impl TimeRange {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.from < self.to {
+            self.from <= time && time < self.to
+        } else if self.from > self.to {
+            self.from <= time || time < self.to
+        } else {
+            true
+        }
+    }
+}
+
+/// Deserializes from a pair of times, i.e. `["22:00", "06:00"]`.
+impl<'de> Deserialize<'de> for TimeRange {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (from, to) = <(String, String)>::deserialize(deserializer)?;
+
+        Ok(Self {
+            from: parse_time(&from).map_err(serde::de::Error::custom)?,
+            to: parse_time(&to).map_err(serde::de::Error::custom)?,
+        })
+    }
+}
+
+/// Parses a time of day formatted as "HH:MM" or "HH:MM:SS".
+fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M:%S"))
+        .map_err(|e| format!("Invalid time \"{time}\": {e}"))
+}
+
+fn deserialize_time<'de, D>(deserializer: D) -> Result<NaiveTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let time = String::deserialize(deserializer)?;
+
+    parse_time(&time).map_err(serde::de::Error::custom)
+}
+
+/// A render that is displayed while the local time is within a range.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ScheduleRuleConfig {
+    #[serde(deserialize_with = "deserialize_time")]
+    pub from: NaiveTime,
+
+    #[serde(deserialize_with = "deserialize_time")]
+    pub to: NaiveTime,
+
+    #[serde(flatten)]
+    pub render: LayoutRegionConfig,
+}
+
+/// Configuration for the [`ScheduleFactory`].
+///
+/// The first rule whose range contains the current local time is displayed.
+/// The default render, if any, is displayed when no rule matches. For example:
+///
+/// ```json
+/// {
+///   "rules": [
+///     { "from": "07:00", "to": "09:30", "factory": "UpcomingArrivals", "config": { ... } },
+///     { "from": "22:00", "to": "06:00", "factory": "TestPattern", "config": { ... } }
+///   ],
+///   "default": { "factory": "Weather", "config": { ... } }
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct ScheduleConfig {
+    pub rules: Vec<ScheduleRuleConfig>,
+
+    #[serde(default)]
+    pub default: Option<LayoutRegionConfig>,
+}
+
+/// Delegates to the render whose [`TimeRange`] contains the current local
+/// time, falling back to a default render in the gaps between the ranges.
+pub struct ScheduledRender<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    rules: Vec<(TimeRange, Box<dyn SyncRender<D>>)>,
+    default: Option<Box<dyn SyncRender<D>>>,
+
+    /// Index of the render that was last drawn, `rules.len()` being the
+    /// default render. `None` while the schedule is not displayed.
+    active: Mutex<Option<usize>>,
+
+    clock: SharedClock,
+}
+
+impl<D> ScheduledRender<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    pub fn new(
+        rules: Vec<(TimeRange, Box<dyn SyncRender<D>>)>,
+        default: Option<Box<dyn SyncRender<D>>>,
+    ) -> Self {
+        Self {
+            rules,
+            default,
+            active: Mutex::new(None),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Reads the time of day from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the index of the render scheduled for the current time.
+    fn current_index(&self) -> usize {
+        self.scheduled_index(self.clock.now().time())
+    }
+
+    /// Returns the index of the render scheduled for `time`.
+    fn scheduled_index(&self, time: NaiveTime) -> usize {
+        self.rules
+            .iter()
+            .position(|(range, _)| range.contains(time))
+            .unwrap_or(self.rules.len())
+    }
+
+    fn render_for(&self, index: usize) -> Option<&dyn SyncRender<D>> {
+        match self.rules.get(index) {
+            Some((_, render)) => Some(render.as_ref()),
+            None => self.default.as_deref(),
+        }
+    }
+
+    fn scheduled_render(&self) -> Option<&dyn SyncRender<D>> {
+        self.render_for(self.current_index())
+    }
+}
+
+impl<D> Render<D> for ScheduledRender<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let index = self.current_index();
+        let mut active = self.active.lock();
+
+        // Hand the selection over when the schedule moves on to another render
+        if *active != Some(index) {
+            if let Some(previous) = active.and_then(|previous| self.render_for(previous)) {
+                previous.on_deselect();
+            }
+
+            if let Some(render) = self.render_for(index) {
+                render.on_select();
+            }
+
+            *active = Some(index);
+        }
+
+        match self.render_for(index) {
+            Some(render) => render.render_at(canvas, now),
+            None => canvas.clear(Rgb888::BLACK),
+        }
+    }
+
+    fn on_select(&self) {
+        let index = self.current_index();
+
+        if let Some(render) = self.render_for(index) {
+            render.on_select();
+        }
+
+        *self.active.lock() = Some(index);
+    }
+
+    fn on_deselect(&self) {
+        if let Some(render) = self
+            .active
+            .lock()
+            .take()
+            .and_then(|index| self.render_for(index))
+        {
+            render.on_deselect();
+        }
+    }
+
+    fn background_color(&self) -> Option<Rgb888> {
+        self.scheduled_render()
+            .and_then(|render| render.background_color())
+    }
+
+    fn dirty_region(&self) -> Option<Rectangle> {
+        let index = self.current_index();
+
+        // The whole canvas changes when switching to another render
+        if *self.active.lock() != Some(index) {
+            return None;
+        }
+
+        self.render_for(index)
+            .and_then(|render| render.dirty_region())
+    }
+
+    fn describe(&self) -> String {
+        match self.scheduled_render() {
+            Some(render) => render.describe(),
+            None => "Nothing scheduled".to_owned(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        self.scheduled_render()
+            .map(|render| render.has_content())
+            .unwrap_or(false)
+    }
+
+    fn reset(&self) {
+        for (_, render) in &self.rules {
+            render.reset();
+        }
+
+        if let Some(render) = &self.default {
+            render.reset();
+        }
+    }
+}
+
+/// Constructs a [`ScheduledRender`] whose renders are constructed by the
+/// factories in `F`, typically the same `#[derive(RenderFactories)]` enum that
+/// the registry was created with.
+pub struct ScheduleFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<(D, F)>,
+}
+
+impl<D, F> Default for ScheduleFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D, F> ScheduleFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible> + 'static,
+    F: RenderFactories<D>,
+{
+    fn load_render(
+        factories: &[F],
+        render: LayoutRegionConfig,
+    ) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let factory = factories
+            .iter()
+            .find(|factory| factory.render_name() == render.factory)
+            .ok_or_else(|| {
+                RenderError::Config(format!("Factory \"{}\" was not found", render.factory))
+            })?;
+
+        let config = serde_json::to_vec(&render.config)?;
+        factory.load_from_config(config.as_slice())
+    }
+}
+
+impl<D, F> RenderFactory<D> for ScheduleFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible> + 'static,
+    F: RenderFactories<D>,
+{
+    fn render_name(&self) -> &'static str {
+        "Schedule"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Displays a different render depending on the time of day"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: ScheduleConfig = serde_json::from_reader(reader)?;
+        let factories = F::factories();
+
+        let rules = config
+            .rules
+            .into_iter()
+            .map(|rule| {
+                Ok((
+                    TimeRange {
+                        from: rule.from,
+                        to: rule.to,
+                    },
+                    Self::load_render(&factories, rule.render)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, RenderError>>()?;
+
+        let default = config
+            .default
+            .map(|default| Self::load_render(&factories, default))
+            .transpose()?;
+
+        Ok(Box::new(ScheduledRender::new(rules, default)))
+    }
+}
@@ -0,0 +1,293 @@
+use crate::{
+    layout_manager::LayoutRegionConfig,
+    render::{BufferCanvas, Render, RenderError, RenderFactories, RenderFactory, SyncRender},
+};
+use anyhow::Result;
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+    primitives::{PointsIter, Rectangle},
+};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
+
+/// A render of a [`ScrollingFeed`] and how many rows of the feed it takes up.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ScrollingFeedItemConfig {
+    pub height: u32,
+
+    #[serde(flatten)]
+    pub render: LayoutRegionConfig,
+}
+
+/// Configuration for the [`ScrollingFeedFactory`].
+///
+/// The renders are stacked top to bottom in the order they are listed. For
+/// example:
+///
+/// ```json
+/// {
+///   "scroll_speed": 8.0,
+///   "gap": 4,
+///   "renders": [
+///     { "height": 64, "factory": "Weather", "config": { ... } },
+///     { "height": 96, "factory": "UpcomingArrivals", "config": { ... } }
+///   ]
+/// }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct ScrollingFeedConfig {
+    /// How many rows the feed scrolls per second, 0 keeps it still
+    pub scroll_speed: f32,
+
+    /// Blank rows between the renders, and between the last render and the
+    /// first one when the feed wraps around
+    #[serde(default)]
+    pub gap: u32,
+
+    pub renders: Vec<ScrollingFeedItemConfig>,
+}
+
+struct FeedItem {
+    render: Box<dyn SyncRender<BufferCanvas>>,
+    height: u32,
+}
+
+/// Stacks several renders vertically and scrolls them upwards through the
+/// canvas on a loop, for tall narrow panels.
+///
+/// Every frame each render is drawn into a tall off-screen buffer the width of
+/// the canvas, which is then copied onto the canvas starting at the current
+/// scroll position, wrapping around to the top of the feed. Every render keeps
+/// drawing its own live data. A feed that is not taller than the canvas is
+/// drawn from the top without scrolling.
+pub struct ScrollingFeed {
+    items: Vec<FeedItem>,
+    scroll_speed: f32,
+    gap: u32,
+    started: Mutex<Instant>,
+    buffer: Mutex<BufferCanvas>,
+}
+
+impl ScrollingFeed {
+    /// Creates a feed of `renders`, each given as the render and the number of
+    /// rows it takes up. `scroll_speed` is in rows per second.
+    pub fn new(
+        renders: Vec<(Box<dyn SyncRender<BufferCanvas>>, u32)>,
+        scroll_speed: f32,
+        gap: u32,
+    ) -> Self {
+        Self {
+            items: renders
+                .into_iter()
+                .map(|(render, height)| FeedItem { render, height })
+                .collect(),
+            scroll_speed,
+            gap,
+            started: Mutex::new(Instant::now()),
+            buffer: Mutex::new(BufferCanvas::new(Size::zero())),
+        }
+    }
+
+    /// The height of one loop of the feed, including the gap after every
+    /// render
+    fn feed_height(&self) -> u32 {
+        self.items.iter().map(|item| item.height + self.gap).sum()
+    }
+
+    /// The first row of the feed shown at the top of a canvas `canvas_height`
+    /// rows tall after scrolling for `elapsed`
+    fn scroll_offset(&self, elapsed: Duration, canvas_height: u32) -> u32 {
+        let feed_height = self.feed_height();
+
+        // Everything fits, so there is nothing to scroll to
+        if feed_height <= canvas_height || self.scroll_speed <= 0.0 {
+            return 0;
+        }
+
+        let scrolled = (elapsed.as_secs_f64() * f64::from(self.scroll_speed)) as u64;
+        (scrolled % u64::from(feed_height)) as u32
+    }
+}
+
+impl<D> Render<D> for ScrollingFeed
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let bounding_box = canvas.bounding_box();
+        let feed_size = Size::new(bounding_box.size.width, self.feed_height());
+        let mut buffer = self.buffer.lock();
+
+        if buffer.size() != feed_size {
+            *buffer = BufferCanvas::new(feed_size);
+        }
+        buffer.clear(Rgb888::BLACK)?;
+
+        let mut top = 0;
+        for item in &self.items {
+            let mut item_buffer = BufferCanvas::new(Size::new(feed_size.width, item.height));
+            item_buffer.clear(item.render.background_color().unwrap_or(Rgb888::BLACK))?;
+            item.render.render_at(&mut item_buffer, now)?;
+            item_buffer.draw_to(&mut *buffer, Point::new(0, top as i32))?;
+
+            top += item.height + self.gap;
+        }
+
+        let elapsed = now.saturating_duration_since(*self.started.lock());
+        let offset = self.scroll_offset(elapsed, bounding_box.size.height);
+        let wraps = feed_size.height > bounding_box.size.height;
+
+        canvas.fill_contiguous(
+            &bounding_box,
+            Rectangle::new(Point::zero(), bounding_box.size)
+                .points()
+                .map(|point| {
+                    let row = if wraps {
+                        (offset + point.y as u32) % feed_size.height
+                    } else {
+                        point.y as u32
+                    };
+
+                    // Rows past the end of a short feed are left blank
+                    buffer
+                        .pixel(Point::new(point.x, row as i32))
+                        .unwrap_or(Rgb888::BLACK)
+                }),
+        )
+    }
+
+    fn on_select(&self) {
+        for item in &self.items {
+            item.render.on_select();
+        }
+    }
+
+    fn on_deselect(&self) {
+        for item in &self.items {
+            item.render.on_deselect();
+        }
+    }
+
+    fn dirty_region(&self) -> Option<Rectangle> {
+        // The feed moves every frame
+        None
+    }
+
+    fn describe(&self) -> String {
+        let descriptions: Vec<String> = self
+            .items
+            .iter()
+            .map(|item| item.render.describe())
+            .collect();
+
+        format!("Scrolling feed: {}", descriptions.join(", "))
+    }
+
+    fn has_content(&self) -> bool {
+        self.items.iter().any(|item| item.render.has_content())
+    }
+
+    fn reset(&self) {
+        *self.started.lock() = Instant::now();
+
+        for item in &self.items {
+            item.render.reset();
+        }
+    }
+}
+
+/// Constructs a [`ScrollingFeed`] whose renders are constructed by the
+/// factories in `F`, typically the same `#[derive(RenderFactories)]` enum that
+/// the registry was created with, instantiated for a [`BufferCanvas`].
+pub struct ScrollingFeedFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<(D, F)>,
+}
+
+impl<D, F> Default for ScrollingFeedFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D, F> RenderFactory<D> for ScrollingFeedFactory<D, F>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible> + 'static,
+    F: RenderFactories<BufferCanvas>,
+{
+    fn render_name(&self) -> &'static str {
+        "ScrollingFeed"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Stacks several renders and scrolls them vertically on a loop"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: ScrollingFeedConfig = serde_json::from_reader(reader)?;
+        let factories = F::factories();
+
+        if config.renders.is_empty() {
+            return Err(RenderError::Config(
+                "Need to provide at least one render".to_owned(),
+            ));
+        }
+
+        if !config.scroll_speed.is_finite() || config.scroll_speed < 0.0 {
+            return Err(RenderError::Config(format!(
+                "scroll_speed must be 0 or more, got {}",
+                config.scroll_speed
+            )));
+        }
+
+        let renders = config
+            .renders
+            .into_iter()
+            .map(|item| {
+                if item.height == 0 {
+                    return Err(RenderError::Config(format!(
+                        "The height of \"{}\" must be greater than 0",
+                        item.render.factory
+                    )));
+                }
+
+                let factory = factories
+                    .iter()
+                    .find(|factory| factory.render_name() == item.render.factory)
+                    .ok_or_else(|| {
+                        RenderError::Config(format!(
+                            "Factory \"{}\" was not found",
+                            item.render.factory
+                        ))
+                    })?;
+
+                let config = serde_json::to_vec(&item.render.config)?;
+                Ok((factory.load_from_config(config.as_slice())?, item.height))
+            })
+            .collect::<Result<Vec<_>, RenderError>>()?;
+
+        Ok(Box::new(ScrollingFeed::new(
+            renders,
+            config.scroll_speed,
+            config.gap,
+        )))
+    }
+}
@@ -0,0 +1,65 @@
+use embedded_graphics::{pixelcolor::Rgb888, prelude::RgbColor};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// The theme shared between the registry and the HTTP API. Changes are picked
+/// up on the next frame.
+pub type SharedTheme = Arc<RwLock<Theme>>;
+
+/// The colors the display is drawn with, so its look can be changed in one
+/// place.
+///
+/// Colors are written as `#RRGGBB`. Colors left out of a theme keep their
+/// default, and unknown colors are rejected so a typo isn't silently ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// Drawn behind renders that don't have a background color of their own
+    #[serde(with = "hex_color")]
+    pub background: Rgb888,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background: Rgb888::BLACK,
+        }
+    }
+}
+
+/// Parses a color written as `#RRGGBB`.
+pub fn parse_hex_color(value: &str) -> Result<Rgb888, String> {
+    let hex = value
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6 && hex.is_ascii())
+        .ok_or_else(|| format!("\"{value}\" is not formatted as #RRGGBB"))?;
+
+    let channel = |index: usize| {
+        u8::from_str_radix(&hex[index..index + 2], 16)
+            .map_err(|_| format!("\"{value}\" is not formatted as #RRGGBB"))
+    };
+
+    Ok(Rgb888::new(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Formats `color` as `#RRGGBB`.
+pub fn format_hex_color(color: Rgb888) -> String {
+    format!("#{:02X}{:02X}{:02X}", color.r(), color.g(), color.b())
+}
+
+/// (De)serializes an [`Rgb888`] as `#RRGGBB`.
+mod hex_color {
+    use super::{format_hex_color, parse_hex_color};
+    use embedded_graphics::pixelcolor::Rgb888;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Rgb888, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_hex_color(*color))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rgb888, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        parse_hex_color(&value).map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,93 @@
+//! Tests that animated renders derive their phase from the `now` they are
+//! drawn at, rather than from when they were constructed.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+};
+use rustic_pixel_display::{
+    render::{animation_time, BufferCanvas, Render, Spinner, SyncRender},
+    scrolling_feed::ScrollingFeed,
+};
+use std::{
+    convert::Infallible,
+    time::{Duration, Instant},
+};
+
+const SPINNER_FRAME: Duration = Duration::from_millis(100);
+
+/// Fills the canvas with its color
+struct Fill(Rgb888);
+
+impl<D> Render<D> for Fill
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        canvas.clear(self.0)
+    }
+}
+
+fn draw_at<R>(render: &R, size: Size, now: Instant) -> BufferCanvas
+where
+    R: Render<BufferCanvas>,
+{
+    let mut canvas = BufferCanvas::new(size);
+    render.render_at(&mut canvas, now).unwrap();
+
+    canvas
+}
+
+#[test]
+fn animation_time_follows_now() {
+    let now = Instant::now();
+    let later = now + Duration::from_millis(1500);
+
+    assert_eq!(
+        animation_time(later) - animation_time(now),
+        Duration::from_millis(1500)
+    );
+}
+
+#[test]
+fn spinner_frame_at_elapsed() {
+    let spinner = Spinner::new(10, Rgb888::WHITE, SPINNER_FRAME);
+
+    assert_eq!(spinner.frame_at(Duration::ZERO), 0);
+    assert_eq!(spinner.frame_at(Duration::from_millis(250)), 2);
+
+    // Wraps around after every one of the eight dots
+    assert_eq!(spinner.frame_at(Duration::from_millis(850)), 0);
+}
+
+#[test]
+fn spinner_draws_phase_of_now() {
+    let spinner = Spinner::new(10, Rgb888::WHITE, SPINNER_FRAME);
+    let size = Size::new(12, 12);
+    let now = Instant::now();
+
+    let first = draw_at(&spinner, size, now);
+    let next = draw_at(&spinner, size, now + SPINNER_FRAME);
+    let full_turn = draw_at(&spinner, size, now + SPINNER_FRAME * 8);
+
+    assert_ne!(first.pixels(), next.pixels());
+    assert_eq!(first.pixels(), full_turn.pixels());
+}
+
+#[test]
+fn scrolling_feed_scrolls_with_now() {
+    let renders: Vec<(Box<dyn SyncRender<BufferCanvas>>, u32)> = vec![
+        (Box::new(Fill(Rgb888::RED)), 4),
+        (Box::new(Fill(Rgb888::BLUE)), 4),
+    ];
+    // One row per second through a canvas half as tall as the feed
+    let feed = ScrollingFeed::new(renders, 1.0, 0);
+    let size = Size::new(2, 4);
+    let started = Instant::now();
+
+    let top_color = |now| draw_at(&feed, size, now).pixel(Point::zero()).unwrap();
+
+    assert_eq!(top_color(started), Rgb888::RED);
+    assert_eq!(top_color(started + Duration::from_secs(4)), Rgb888::BLUE);
+    assert_eq!(top_color(started + Duration::from_secs(8)), Rgb888::RED);
+}
@@ -0,0 +1,146 @@
+//! Tests that the canvas of a driver reports the composed size of the chains
+//! of panels, the same size renders see through `bounding_box`.
+
+use anyhow::Result;
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, Point, Size},
+    primitives::Rectangle,
+};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    config::{DriverBackend, HardwareConfig, HardwareMapping, LedSequence, RowAddressSetterType},
+    driver::HardwareDriver,
+    render::{BufferCanvas, Render},
+};
+use std::convert::Infallible;
+
+/// Sized like the panels of the configuration, the way the drivers size their
+/// canvases
+struct MockConfig {
+    size: Size,
+}
+
+impl TryFrom<HardwareConfig> for MockConfig {
+    type Error = Infallible;
+
+    fn try_from(config: HardwareConfig) -> Result<Self, Self::Error> {
+        Ok(Self {
+            size: config.chain_size(),
+        })
+    }
+}
+
+struct MockHardwareDriver {
+    size: Size,
+}
+
+impl HardwareDriver for MockHardwareDriver {
+    type Config = MockConfig;
+    type Canvas = BufferCanvas;
+
+    fn new(config: Self::Config) -> Result<Self> {
+        Ok(Self { size: config.size })
+    }
+
+    fn create_canvas(&mut self) -> Box<Self::Canvas> {
+        Box::new(BufferCanvas::new(self.size))
+    }
+
+    fn display_canvas(&mut self, canvas: Box<Self::Canvas>) -> Box<Self::Canvas> {
+        canvas
+    }
+}
+
+/// Records the size and bounding box of the canvas it is drawn on
+#[derive(Default)]
+struct SizeRecorder {
+    seen: Mutex<Option<(Size, Rectangle)>>,
+}
+
+impl<D> Render<D> for SizeRecorder
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible> + OriginDimensions,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        *self.seen.lock() = Some((canvas.size(), canvas.bounding_box()));
+        Ok(())
+    }
+}
+
+fn hardware_config(
+    rows: usize,
+    cols: usize,
+    chain_length: usize,
+    parallel: usize,
+) -> HardwareConfig {
+    HardwareConfig {
+        hardware_mapping: HardwareMapping::Regular,
+        rows,
+        cols,
+        refresh_rate: 120,
+        pi_chip: None,
+        pwm_bits: 11,
+        pwm_lsb_nanoseconds: 130,
+        slowdown: None,
+        interlaced: false,
+        dither_bits: 0,
+        chain_length,
+        parallel,
+        panel_type: None,
+        multiplexing: None,
+        row_setter: RowAddressSetterType::Direct,
+        led_sequence: LedSequence::Rgb,
+        spi_bus: None,
+        spi_speed_hz: None,
+        file_output_dir: None,
+        file_write_interval_ms: None,
+        tcp_address: None,
+        max_fps: None,
+        driver_backend: DriverBackend::Rust,
+        pixel_mapper: None,
+        software_dither: None,
+        simulator_scale: None,
+        panels: Vec::new(),
+    }
+}
+
+/// The size and bounding box a render sees on a canvas of the driver `H`
+fn seen_by_render<H>(config: HardwareConfig) -> (Size, Rectangle)
+where
+    H: HardwareDriver,
+    <H::Config as TryFrom<HardwareConfig>>::Error: std::fmt::Debug,
+{
+    let mut driver = H::new(config.try_into().unwrap()).unwrap();
+    let mut canvas = driver.create_canvas();
+    let recorder = SizeRecorder::default();
+
+    recorder.render(canvas.as_mut()).unwrap();
+    driver.display_canvas(canvas);
+
+    let seen = recorder.seen.lock().take();
+    seen.unwrap()
+}
+
+#[test]
+fn single_panel_size() {
+    let (size, bounding_box) = seen_by_render::<MockHardwareDriver>(hardware_config(32, 64, 1, 1));
+
+    assert_eq!(size, Size::new(64, 32));
+    assert_eq!(
+        bounding_box,
+        Rectangle::new(Point::zero(), Size::new(64, 32))
+    );
+}
+
+#[test]
+fn chained_and_parallel_panels_size() {
+    // Two chains of two 32x16 panels
+    let (size, bounding_box) = seen_by_render::<MockHardwareDriver>(hardware_config(16, 32, 2, 2));
+
+    assert_eq!(size, Size::new(64, 32));
+    assert_eq!(
+        bounding_box,
+        Rectangle::new(Point::zero(), Size::new(64, 32))
+    );
+}
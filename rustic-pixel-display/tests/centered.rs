@@ -0,0 +1,83 @@
+//! Tests that `Centered` moves what its render draws to the center of the
+//! canvas, using the bounds measured by `BoundsTrackingCanvas`.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+    primitives::{PointsIter, Rectangle},
+    Drawable, Pixel,
+};
+use rustic_pixel_display::render::{BoundsTrackingCanvas, BufferCanvas, Centered, Render};
+use std::fmt;
+
+/// Fills `area` with red, leaving the rest of the canvas alone
+struct Square {
+    area: Rectangle,
+}
+
+impl<D> Render<D> for Square
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        canvas.fill_solid(&self.area, Rgb888::RED)
+    }
+}
+
+/// Returns every pixel of `canvas` that is not black
+fn drawn_points(canvas: &BufferCanvas) -> Vec<Point> {
+    canvas
+        .bounding_box()
+        .points()
+        .filter(|&point| canvas.pixel(point) != Some(Rgb888::BLACK))
+        .collect()
+}
+
+#[test]
+fn bounds_cover_every_drawn_pixel() {
+    let mut canvas = BoundsTrackingCanvas::new(BufferCanvas::new(Size::new(10, 8)));
+    assert_eq!(canvas.bounds(), None);
+
+    Pixel(Point::new(2, 5), Rgb888::RED)
+        .draw(&mut canvas)
+        .unwrap();
+    Pixel(Point::new(6, 1), Rgb888::RED)
+        .draw(&mut canvas)
+        .unwrap();
+
+    assert_eq!(
+        canvas.bounds(),
+        Some(Rectangle::with_corners(Point::new(2, 1), Point::new(6, 5)))
+    );
+}
+
+#[test]
+fn small_render_is_centered_on_a_large_canvas() {
+    // Drawn away from the origin so the offset of the content is measured too
+    let render = Centered::new(Square {
+        area: Rectangle::new(Point::new(1, 1), Size::new(2, 2)),
+    });
+
+    let mut canvas = BufferCanvas::new(Size::new(10, 8));
+    render.render(&mut canvas).unwrap();
+
+    let expected: Vec<Point> = Rectangle::new(Point::new(4, 3), Size::new(2, 2))
+        .points()
+        .collect();
+    assert_eq!(drawn_points(&canvas), expected);
+}
+
+#[test]
+fn render_filling_the_canvas_is_left_in_place() {
+    let size = Size::new(6, 4);
+    let render = Centered::new(Square {
+        area: Rectangle::new(Point::zero(), size),
+    });
+
+    let mut canvas = BufferCanvas::new(size);
+    render.render(&mut canvas).unwrap();
+
+    let expected: Vec<Point> = Rectangle::new(Point::zero(), size).points().collect();
+    assert_eq!(drawn_points(&canvas), expected);
+}
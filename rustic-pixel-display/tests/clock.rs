@@ -0,0 +1,102 @@
+//! Tests that renders reading the time of day follow the clock they are given.
+
+use chrono::{DateTime, FixedOffset, NaiveTime, TimeZone};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+};
+use rustic_pixel_display::{
+    clock::{Clock, FixedClock},
+    quiet_hours::QuietHours,
+    render::{BufferCanvas, Render, SyncRender},
+    schedule::{ScheduledRender, TimeRange},
+};
+use std::{convert::Infallible, sync::Arc, time::Duration};
+
+const HOUR: Duration = Duration::from_secs(60 * 60);
+
+/// Fills the canvas with its color
+struct Fill(Rgb888);
+
+impl<D> Render<D> for Fill
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        canvas.clear(self.0)
+    }
+}
+
+fn time(hour: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, 0, 0).unwrap()
+}
+
+fn at(hour: u32) -> DateTime<FixedOffset> {
+    FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 3, 16, hour, 0, 0)
+        .unwrap()
+}
+
+fn drawn_color<R: Render<BufferCanvas>>(render: &R) -> Rgb888 {
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+    render.render(&mut canvas).unwrap();
+
+    canvas.pixel(Point::zero()).unwrap()
+}
+
+#[test]
+fn fixed_clock_advances_together() {
+    let clock = FixedClock::new(at(8));
+    let instant = clock.instant();
+
+    clock.advance(HOUR);
+
+    assert_eq!(clock.now(), at(9));
+    assert_eq!(clock.instant() - instant, HOUR);
+}
+
+#[test]
+fn quiet_hours_follow_clock() {
+    let clock = Arc::new(FixedClock::new(at(0)));
+    let quiet_hours = QuietHours::new(
+        Box::new(Fill(Rgb888::WHITE)),
+        vec![TimeRange {
+            from: time(1),
+            to: time(6),
+        }],
+    )
+    .with_clock(clock.clone());
+
+    assert_eq!(drawn_color(&quiet_hours), Rgb888::WHITE);
+
+    clock.advance(HOUR * 3);
+    assert_eq!(drawn_color(&quiet_hours), Rgb888::BLACK);
+
+    clock.advance(HOUR * 3);
+    assert_eq!(drawn_color(&quiet_hours), Rgb888::WHITE);
+}
+
+#[test]
+fn schedule_follows_clock() {
+    let clock = Arc::new(FixedClock::new(at(7)));
+    let schedule = ScheduledRender::new(
+        vec![(
+            TimeRange {
+                from: time(8),
+                to: time(17),
+            },
+            Box::new(Fill(Rgb888::RED)) as Box<dyn SyncRender<BufferCanvas>>,
+        )],
+        Some(Box::new(Fill(Rgb888::BLUE))),
+    )
+    .with_clock(clock.clone());
+
+    assert_eq!(drawn_color(&schedule), Rgb888::BLUE);
+
+    clock.advance(HOUR);
+    assert_eq!(drawn_color(&schedule), Rgb888::RED);
+
+    clock.set(at(17));
+    assert_eq!(drawn_color(&schedule), Rgb888::BLUE);
+}
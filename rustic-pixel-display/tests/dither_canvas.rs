@@ -0,0 +1,178 @@
+//! Tests the patterns a dither canvas rounds colors with and that it keeps the
+//! average shade of what is drawn on it.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+    primitives::Rectangle,
+};
+use rustic_pixel_display::render::{BayerMatrix, BufferCanvas, DitherCanvas};
+
+/// Halfway between off and fully on at 1 bit per channel
+const MID_GRAY: u8 = 128;
+
+/// A quarter of the way between off and fully on at 1 bit per channel
+const QUARTER_GRAY: u8 = 64;
+
+/// Too dim for any pixel of a 2x2 matrix to round up at 1 bit per channel
+const DIM_GRAY: u8 = 16;
+
+/// Difference between two shades a channel can take at 2 bits per channel
+const TWO_BIT_STEP: u8 = 85;
+
+fn gray(value: u8) -> Rgb888 {
+    Rgb888::new(value, value, value)
+}
+
+/// Fills a canvas of `size` with `colors`, row by row, through a dither canvas
+fn dithered<I>(size: Size, bits: u8, matrix: BayerMatrix, colors: I) -> BufferCanvas
+where
+    I: IntoIterator<Item = Rgb888>,
+{
+    let mut canvas = BufferCanvas::new(size);
+    DitherCanvas::new(&mut canvas, bits, matrix)
+        .fill_contiguous(&Rectangle::new(Point::zero(), size), colors)
+        .unwrap();
+
+    canvas
+}
+
+fn filled(size: Size, bits: u8, matrix: BayerMatrix, color: Rgb888) -> BufferCanvas {
+    dithered(
+        size,
+        bits,
+        matrix,
+        std::iter::repeat(color).take(size.width as usize * size.height as usize),
+    )
+}
+
+/// Points of a canvas of `size`, row by row
+fn points(size: Size) -> impl Iterator<Item = Point> {
+    (0..size.height as i32).flat_map(move |y| (0..size.width as i32).map(move |x| Point::new(x, y)))
+}
+
+/// A horizontal gradient of 16 grays, each 4 pixels wide, so every shade
+/// covers a whole 4x4 matrix
+fn gradient(size: Size) -> impl Iterator<Item = Rgb888> {
+    points(size).map(|point| gray((point.x / 4 * 17) as u8))
+}
+
+#[test]
+fn mid_gray_is_a_checkerboard() {
+    let size = Size::new(8, 8);
+
+    for matrix in [
+        BayerMatrix::TwoByTwo,
+        BayerMatrix::FourByFour,
+        BayerMatrix::EightByEight,
+    ] {
+        let canvas = filled(size, 1, matrix, gray(MID_GRAY));
+
+        for point in points(size) {
+            let expected = if (point.x + point.y) % 2 == 1 {
+                gray(255)
+            } else {
+                gray(0)
+            };
+            assert_eq!(
+                canvas.pixel(point),
+                Some(expected),
+                "{matrix:?} at {point:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn two_by_two_matrix_pattern() {
+    let size = Size::new(8, 8);
+    let canvas = filled(size, 1, BayerMatrix::TwoByTwo, gray(QUARTER_GRAY));
+
+    // A quarter gray only rounds up at the largest threshold of the matrix,
+    // the bottom left of each 2x2 tile
+    for point in points(size) {
+        let expected = if point.x % 2 == 0 && point.y % 2 == 1 {
+            gray(255)
+        } else {
+            gray(0)
+        };
+        assert_eq!(canvas.pixel(point), Some(expected), "{point:?}");
+    }
+}
+
+#[test]
+fn larger_matrices_keep_dimmer_shades() {
+    let size = Size::new(8, 8);
+    let lit = |matrix| {
+        let canvas = filled(size, 1, matrix, gray(DIM_GRAY));
+        canvas.pixels().iter().filter(|&&p| p == gray(255)).count()
+    };
+
+    // Below the smallest shade of the 2x2 matrix, but not of the larger ones
+    assert_eq!(lit(BayerMatrix::TwoByTwo), 0);
+    assert!(lit(BayerMatrix::FourByFour) > 0);
+    assert!(lit(BayerMatrix::EightByEight) > 0);
+}
+
+#[test]
+fn channels_are_quantized_independently() {
+    let size = Size::new(4, 4);
+    let canvas = filled(
+        size,
+        1,
+        BayerMatrix::FourByFour,
+        Rgb888::new(255, MID_GRAY, 0),
+    );
+
+    for point in points(size) {
+        let green = if (point.x + point.y) % 2 == 1 { 255 } else { 0 };
+        assert_eq!(
+            canvas.pixel(point),
+            Some(Rgb888::new(255, green, 0)),
+            "{point:?}"
+        );
+    }
+}
+
+#[test]
+fn gradient_keeps_average_shade() {
+    let size = Size::new(64, 4);
+    let canvas = dithered(size, 2, BayerMatrix::FourByFour, gradient(size));
+
+    // Every channel is one of the 4 shades of 2 bits
+    for pixel in canvas.pixels() {
+        for channel in [pixel.r(), pixel.g(), pixel.b()] {
+            assert_eq!(channel % TWO_BIT_STEP, 0, "{pixel:?}");
+        }
+    }
+
+    // Each 4x4 block of the gradient averages out to its original shade
+    let mut last_average = 0.0;
+    for block in 0..16 {
+        let shade = (block * 17) as f32;
+        let sum: u32 = points(Size::new(4, 4))
+            .map(|point| canvas.pixel(point + Point::new(block * 4, 0)).unwrap().r() as u32)
+            .sum();
+        let average = sum as f32 / 16.0;
+
+        assert!(
+            (average - shade).abs() <= TWO_BIT_STEP as f32 / 16.0,
+            "block {block}: {average} instead of {shade}"
+        );
+        assert!(average >= last_average, "block {block}");
+        last_average = average;
+    }
+}
+
+#[test]
+fn eight_bits_leaves_colors_unchanged() {
+    let size = Size::new(64, 4);
+    let canvas = dithered(size, 8, BayerMatrix::FourByFour, gradient(size));
+
+    let mut expected = BufferCanvas::new(size);
+    expected
+        .fill_contiguous(&Rectangle::new(Point::zero(), size), gradient(size))
+        .unwrap();
+
+    assert_eq!(canvas.pixels(), expected.pixels());
+}
@@ -0,0 +1,95 @@
+//! Tests that a fallback draws the first of its renders that has content.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+};
+use rustic_pixel_display::{
+    fallback::Fallback,
+    render::{BufferCanvas, Render, SyncRender},
+};
+use std::{
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// Fills the canvas with its color, reporting content while `content` is set
+struct Source {
+    color: Rgb888,
+    content: AtomicBool,
+}
+
+/// Shares a [`Source`] with the test so its content can be changed while the
+/// fallback owns it
+struct Shared(Arc<Source>);
+
+impl<D> Render<D> for Shared
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        canvas.clear(self.0.color)
+    }
+
+    fn has_content(&self) -> bool {
+        self.0.content.load(Ordering::SeqCst)
+    }
+}
+
+fn source(color: Rgb888, content: bool) -> Arc<Source> {
+    Arc::new(Source {
+        color,
+        content: AtomicBool::new(content),
+    })
+}
+
+fn fallback(sources: &[&Arc<Source>]) -> Fallback<BufferCanvas> {
+    Fallback::new(
+        sources
+            .iter()
+            .map(|source| Box::new(Shared(Arc::clone(source))) as Box<dyn SyncRender<BufferCanvas>>)
+            .collect(),
+    )
+}
+
+fn draw(fallback: &Fallback<BufferCanvas>) -> Rgb888 {
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+    fallback.render(&mut canvas).unwrap();
+
+    canvas.pixel(Point::zero()).unwrap()
+}
+
+#[test]
+fn first_render_without_content_falls_through() {
+    let weather = source(Rgb888::BLUE, false);
+    let label = source(Rgb888::GREEN, true);
+    let fallback = fallback(&[&weather, &label]);
+
+    assert_eq!(draw(&fallback), Rgb888::GREEN);
+    assert!(Render::<BufferCanvas>::has_content(&fallback));
+}
+
+#[test]
+fn first_render_with_content_takes_over_again() {
+    let weather = source(Rgb888::BLUE, false);
+    let label = source(Rgb888::GREEN, true);
+    let fallback = fallback(&[&weather, &label]);
+
+    assert_eq!(draw(&fallback), Rgb888::GREEN);
+
+    weather.content.store(true, Ordering::SeqCst);
+    assert_eq!(draw(&fallback), Rgb888::BLUE);
+}
+
+#[test]
+fn last_render_is_drawn_when_none_have_content() {
+    let weather = source(Rgb888::BLUE, false);
+    let label = source(Rgb888::GREEN, false);
+    let fallback = fallback(&[&weather, &label]);
+
+    assert_eq!(draw(&fallback), Rgb888::GREEN);
+    assert!(!Render::<BufferCanvas>::has_content(&fallback));
+}
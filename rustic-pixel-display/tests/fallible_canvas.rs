@@ -0,0 +1,125 @@
+//! Tests that renders can be drawn onto a canvas that fails.
+//!
+//! The canvas stands in for one that writes straight to a socket or bus, and
+//! fails every draw once it is disconnected.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, Point, RgbColor, Size},
+    Drawable, Pixel,
+};
+use rustic_pixel_display::render::{BufferCanvas, Render, RenderBuilder, SyncRender};
+use std::{convert::Infallible, fmt, time::Duration};
+
+#[derive(Debug, PartialEq, Eq)]
+struct Disconnected;
+
+/// Counts the pixels drawn while connected
+struct FallibleCanvas {
+    connected: bool,
+    drawn: usize,
+}
+
+impl FallibleCanvas {
+    fn new(connected: bool) -> Self {
+        Self {
+            connected,
+            drawn: 0,
+        }
+    }
+}
+
+impl OriginDimensions for FallibleCanvas {
+    fn size(&self) -> Size {
+        Size::new(4, 4)
+    }
+}
+
+impl DrawTarget for FallibleCanvas {
+    type Color = Rgb888;
+    type Error = Disconnected;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        if !self.connected {
+            return Err(Disconnected);
+        }
+
+        self.drawn += pixels.into_iter().count();
+        Ok(())
+    }
+}
+
+/// Draws one pixel onto any canvas
+struct Dot;
+
+impl<D> Render<D> for Dot
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        Pixel(Point::zero(), Rgb888::RED).draw(canvas)
+    }
+}
+
+/// Draws one pixel, but only onto canvases that can't fail
+struct InfallibleDot;
+
+impl<D> Render<D> for InfallibleDot
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        Pixel(Point::zero(), Rgb888::RED).draw(canvas)
+    }
+}
+
+#[test]
+fn render_error_is_returned() {
+    let mut canvas = FallibleCanvas::new(false);
+
+    assert_eq!(Dot.render(&mut canvas), Err(Disconnected));
+}
+
+#[test]
+fn render_draws_while_connected() {
+    let mut canvas = FallibleCanvas::new(true);
+
+    assert_eq!(Dot.render(&mut canvas), Ok(()));
+    assert_eq!(canvas.drawn, 1);
+}
+
+#[test]
+fn infallible_render_is_replayed_onto_fallible_canvas() {
+    // The inner render only draws onto the buffer the wrappers draw into, so
+    // it stays limited to infallible canvases
+    let render = RenderBuilder::new(InfallibleDot)
+        .throttled(Duration::from_secs(60))
+        .build();
+
+    // The whole cached buffer is replayed
+    let mut connected = FallibleCanvas::new(true);
+    assert_eq!(render.render(&mut connected), Ok(()));
+    assert_eq!(connected.drawn, 16);
+
+    let mut disconnected = FallibleCanvas::new(false);
+    assert_eq!(render.render(&mut disconnected), Err(Disconnected));
+}
+
+#[test]
+fn fallible_render_can_be_boxed() {
+    let render: Box<dyn SyncRender<FallibleCanvas>> = RenderBuilder::new(Dot)
+        .scaled(Size::new(2, 2), Default::default())
+        .boxed();
+    let mut canvas = FallibleCanvas::new(false);
+
+    assert_eq!(render.render(&mut canvas), Err(Disconnected));
+
+    // Existing renders still draw onto in-memory canvases unchanged
+    let mut buffer = BufferCanvas::new(Size::new(4, 4));
+    InfallibleDot.render(&mut buffer).unwrap();
+    assert_eq!(buffer.pixel(Point::zero()), Some(Rgb888::RED));
+}
@@ -0,0 +1,194 @@
+//! Tests that `FixedResolution` scales what its render draws at the logical
+//! size onto smaller and larger canvases, and the sampling of `scale_buffer`
+//! it scales with.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+    primitives::{PointsIter, Rectangle},
+};
+use rustic_pixel_display::render::{
+    scale_buffer, BufferCanvas, FixedResolution, Render, ScaleMode,
+};
+use std::fmt;
+
+const LOGICAL_SIZE: Size = Size::new(4, 4);
+
+/// Colors of the top left, top right, bottom left and bottom right quadrants
+const QUADRANT_COLORS: [Rgb888; 4] = [Rgb888::RED, Rgb888::GREEN, Rgb888::BLUE, Rgb888::WHITE];
+
+/// Fills each quadrant of the canvas with its color from [`QUADRANT_COLORS`]
+struct Quadrants;
+
+impl<D> Render<D> for Quadrants
+where
+    D: DrawTarget<Color = Rgb888>,
+    D::Error: fmt::Debug,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let size = canvas.bounding_box().size / 2;
+
+        for (index, color) in QUADRANT_COLORS.into_iter().enumerate() {
+            let top_left = Point::new(
+                (index % 2) as i32 * size.width as i32,
+                (index / 2) as i32 * size.height as i32,
+            );
+            canvas.fill_solid(&Rectangle::new(top_left, size), color)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders [`Quadrants`] at the logical size onto a canvas of `size`
+fn draw_scaled(size: Size, scale_mode: ScaleMode) -> BufferCanvas {
+    let render = FixedResolution::new(Quadrants, LOGICAL_SIZE, scale_mode);
+
+    let mut canvas = BufferCanvas::new(size);
+    render.render(&mut canvas).unwrap();
+
+    canvas
+}
+
+/// Asserts that every quadrant of `canvas` is entirely its own color
+fn assert_quadrants(canvas: &BufferCanvas, size: Size) {
+    for point in Rectangle::new(Point::zero(), size).points() {
+        let index = usize::from(point.x as u32 >= size.width / 2)
+            + 2 * usize::from(point.y as u32 >= size.height / 2);
+
+        assert_eq!(
+            canvas.pixel(point),
+            Some(QUADRANT_COLORS[index]),
+            "{point:?}"
+        );
+    }
+}
+
+/// A buffer of `size` with the red channel of every pixel, row by row, set to
+/// `reds`
+fn red_buffer(size: Size, reds: &[u8]) -> BufferCanvas {
+    let mut buffer = BufferCanvas::new(size);
+    buffer
+        .fill_contiguous(
+            &Rectangle::new(Point::zero(), size),
+            reds.iter().map(|&red| Rgb888::new(red, 0, 0)),
+        )
+        .unwrap();
+
+    buffer
+}
+
+/// The red channel of every pixel of `buffer`, row by row
+fn reds(buffer: &BufferCanvas, size: Size) -> Vec<u8> {
+    Rectangle::new(Point::zero(), size)
+        .points()
+        .map(|point| buffer.pixel(point).unwrap().r())
+        .collect()
+}
+
+#[test]
+fn same_size_is_drawn_as_is() {
+    assert_quadrants(&draw_scaled(LOGICAL_SIZE, ScaleMode::Nearest), LOGICAL_SIZE);
+}
+
+#[test]
+fn smaller_canvas_keeps_the_quadrants() {
+    let size = Size::new(2, 2);
+    assert_quadrants(&draw_scaled(size, ScaleMode::Nearest), size);
+}
+
+#[test]
+fn larger_canvas_keeps_the_quadrants() {
+    let size = Size::new(8, 8);
+    assert_quadrants(&draw_scaled(size, ScaleMode::Nearest), size);
+}
+
+#[test]
+fn wider_canvas_stretches_the_quadrants() {
+    let size = Size::new(12, 4);
+    assert_quadrants(&draw_scaled(size, ScaleMode::Nearest), size);
+}
+
+#[test]
+fn empty_canvas_does_not_panic() {
+    draw_scaled(Size::zero(), ScaleMode::Nearest);
+    draw_scaled(Size::new(0, 4), ScaleMode::Bilinear);
+}
+
+#[test]
+fn nearest_upscale_repeats_every_pixel() {
+    let source = red_buffer(Size::new(2, 2), &[0, 100, 200, 40]);
+    let size = Size::new(4, 4);
+
+    #[rustfmt::skip]
+    let expected: [u8; 16] = [
+        0, 0, 100, 100,
+        0, 0, 100, 100,
+        200, 200, 40, 40,
+        200, 200, 40, 40,
+    ];
+    assert_eq!(
+        reds(&scale_buffer(&source, size, ScaleMode::Nearest), size),
+        expected
+    );
+}
+
+#[test]
+fn bilinear_upscale_blends_between_pixels() {
+    let source = red_buffer(Size::new(2, 2), &[0, 100, 200, 40]);
+    let size = Size::new(4, 4);
+
+    // The outer pixels are clamped to the edges of the source
+    #[rustfmt::skip]
+    let expected: [u8; 16] = [
+        0, 25, 75, 100,
+        50, 59, 76, 85,
+        150, 126, 79, 55,
+        200, 160, 80, 40,
+    ];
+    assert_eq!(
+        reds(&scale_buffer(&source, size, ScaleMode::Bilinear), size),
+        expected
+    );
+}
+
+#[test]
+fn bilinear_downscale_averages_four_pixels() {
+    #[rustfmt::skip]
+    let source = red_buffer(
+        Size::new(4, 4),
+        &[
+            10, 20, 0, 0,
+            30, 40, 0, 255,
+            100, 100, 200, 100,
+            100, 100, 50, 50,
+        ],
+    );
+    let size = Size::new(2, 2);
+
+    assert_eq!(
+        reds(&scale_buffer(&source, size, ScaleMode::Bilinear), size),
+        [25, 64, 100, 100]
+    );
+}
+
+#[test]
+fn nearest_downscale_picks_a_pixel() {
+    #[rustfmt::skip]
+    let source = red_buffer(
+        Size::new(4, 4),
+        &[
+            10, 20, 0, 0,
+            30, 40, 0, 255,
+            100, 100, 200, 100,
+            100, 100, 50, 50,
+        ],
+    );
+    let size = Size::new(2, 2);
+
+    // The bottom right pixel of each block is closest to its center
+    assert_eq!(
+        reds(&scale_buffer(&source, size, ScaleMode::Nearest), size),
+        [40, 255, 100, 50]
+    );
+}
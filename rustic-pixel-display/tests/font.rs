@@ -0,0 +1,32 @@
+//! Tests that text is prepared for the ISO 8859-1 fonts without losing
+//! characters silently.
+
+use rustic_pixel_display::font::{latin1_lossy, FALLBACK_GLYPH};
+use std::borrow::Cow;
+
+#[test]
+fn latin1_text_is_borrowed() {
+    for text in ["Malvern/Parkesburg", "Genève", "Zürich", "72°F"] {
+        assert!(matches!(latin1_lossy(text), Cow::Borrowed(borrowed) if borrowed == text));
+    }
+}
+
+#[test]
+fn typographic_punctuation_is_replaced_by_plain_equivalents() {
+    assert_eq!(latin1_lossy("St. Mary\u{2019}s"), "St. Mary's");
+    assert_eq!(latin1_lossy("\u{201C}Express\u{201D}"), "\"Express\"");
+    assert_eq!(latin1_lossy("Trenton \u{2013} Newark"), "Trenton - Newark");
+    assert_eq!(
+        latin1_lossy("Track 1 \u{2022} On Time"),
+        "Track 1 \u{B7} On Time"
+    );
+}
+
+#[test]
+fn characters_without_a_glyph_are_replaced_by_the_fallback() {
+    assert_eq!(
+        latin1_lossy("Kraków \u{2603}"),
+        format!("Krak\u{F3}w {FALLBACK_GLYPH}")
+    );
+    assert_eq!(latin1_lossy("\u{0141}\u{F3}d\u{017A}").chars().count(), 4);
+}
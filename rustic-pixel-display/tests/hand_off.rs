@@ -0,0 +1,82 @@
+//! Tests that handing a second canvas to a thread that already has one
+//! waiting drops it and logs why, rather than blocking the thread handing it
+//! off.
+
+use log::{LevelFilter, Log, Metadata, Record};
+use rustic_pixel_display::driver::{hand_off, CANVASES_IN_FLIGHT};
+use std::{
+    sync::{mpsc, Mutex, Once},
+    thread,
+    time::Duration,
+};
+
+/// How long handing off may take before it is treated as blocked
+const BLOCKED_AFTER: Duration = Duration::from_secs(1);
+
+/// Keeps every message logged, so the tests can check what was logged
+struct CapturingLogger;
+
+static LOGGER: CapturingLogger = CapturingLogger;
+static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        MESSAGES.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+fn capture_logs() {
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+    });
+}
+
+#[test]
+fn second_canvas_in_flight_is_dropped() {
+    capture_logs();
+    let (sender, receiver) = mpsc::sync_channel(CANVASES_IN_FLIGHT);
+    let (done_sender, done) = mpsc::channel();
+
+    // Both canvases are handed off from another thread, so a blocking hand
+    // off fails the test instead of hanging it
+    thread::spawn(move || {
+        let results = [
+            hand_off(&sender, "first", "render").is_ok(),
+            hand_off(&sender, "second", "render").is_ok(),
+        ];
+        done_sender.send(results).unwrap();
+    });
+
+    let results = done
+        .recv_timeout(BLOCKED_AFTER)
+        .expect("Handing off the second canvas blocked");
+    assert_eq!(results, [true, true]);
+
+    // Only the first canvas made it to the other thread
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), ["first"]);
+
+    assert!(MESSAGES
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|message| message.contains("More than one canvas in flight to the render thread")));
+}
+
+#[test]
+fn exited_thread_is_an_error() {
+    let (sender, receiver) = mpsc::sync_channel(CANVASES_IN_FLIGHT);
+    drop(receiver);
+
+    let error = hand_off(&sender, "canvas", "driver").unwrap_err();
+
+    assert_eq!(error.to_string(), "The driver thread has exited");
+}
@@ -0,0 +1,612 @@
+//! End to end tests of the HTTP API.
+//!
+//! Every test serves the API on an ephemeral port, backed by a registry with a
+//! single trivial factory, and talks to it over a plain TCP connection so the
+//! request routing and the mapping of registry errors to status codes are
+//! exercised as a client would see them.
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::DrawTarget};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    http_server::{build_api_server, DEFAULT_MAX_CONFIG_BYTES},
+    push_routes::{register_get_route, register_push_route},
+    registry::Registry,
+    render::{BufferCanvas, ExampleConfig, Render, RenderError, RenderFactory, SyncRender},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    convert::Infallible,
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream},
+    sync::{mpsc::Sender, Arc},
+    thread::JoinHandle,
+};
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+const FACTORY_NAME: &str = "TestLabel";
+
+#[derive(Deserialize)]
+struct TestLabelConfig {
+    text: String,
+}
+
+/// Draws nothing, the tests only look at how it is described
+struct TestLabel {
+    text: Mutex<String>,
+}
+
+impl<D> Render<D> for TestLabel
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, _canvas: &mut D) -> Result<(), D::Error> {
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!("{FACTORY_NAME}: {}", self.text.lock())
+    }
+
+    fn reconfigure(&self, config: &mut dyn Read) -> Result<(), RenderError> {
+        let config: TestLabelConfig = serde_json::from_reader(config)?;
+        *self.text.lock() = config.text;
+        Ok(())
+    }
+}
+
+struct TestLabelFactory;
+
+impl RenderFactory<BufferCanvas> for TestLabelFactory {
+    fn render_name(&self) -> &'static str {
+        FACTORY_NAME
+    }
+
+    fn render_description(&self) -> &'static str {
+        "A label for testing"
+    }
+
+    fn example_config(&self) -> Option<ExampleConfig> {
+        Some(json!({ "text": "Hello" }))
+    }
+
+    fn load_from_config<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: TestLabelConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(TestLabel {
+            text: Mutex::new(config.text),
+        }))
+    }
+}
+
+type TestRegistry = Registry<TestLabelFactory, BufferCanvas>;
+
+/// The API served on an ephemeral port, stopped when dropped
+struct TestServer {
+    addr: SocketAddr,
+    registry: Arc<Mutex<TestRegistry>>,
+    stop: Option<Sender<()>>,
+    server_handle: Option<JoinHandle<()>>,
+    _runtime: Runtime,
+}
+
+impl TestServer {
+    fn start() -> Self {
+        let runtime = Runtime::new().unwrap();
+        let registry = Arc::new(Mutex::new(Registry::new(vec![TestLabelFactory]).unwrap()));
+
+        let server = build_api_server("127.0.0.1:0", runtime.handle().clone(), registry.clone());
+        let addr = server.server_addr();
+        let (server_handle, stop) = server.stoppable();
+
+        Self {
+            addr,
+            registry,
+            stop: Some(stop),
+            server_handle: Some(server_handle),
+            _runtime: runtime,
+        }
+    }
+
+    /// Sends a request with an optional JSON `body`, returning the status code
+    /// and the body of the response
+    fn request(&self, method: &str, path: &str, body: Option<&str>) -> (u16, String) {
+        let mut request =
+            format!("{method} {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n");
+        match body {
+            Some(body) => request.push_str(&format!(
+                "Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            )),
+            None => request.push_str("Content-Length: 0\r\n\r\n"),
+        }
+
+        self.send(&request)
+    }
+
+    fn send(&self, request: impl AsRef<[u8]>) -> (u16, String) {
+        let mut stream = TcpStream::connect(self.addr).unwrap();
+        stream.write_all(request.as_ref()).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status = response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .unwrap_or_else(|| panic!("Malformed response {response:?}"));
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_owned())
+            .unwrap_or_default();
+
+        (status, body)
+    }
+
+    fn json(&self, method: &str, path: &str, body: Option<&str>) -> (u16, Value) {
+        let (status, body) = self.request(method, path, body);
+        (status, serde_json::from_str(&body).unwrap_or(Value::Null))
+    }
+
+    /// Loads a label showing `text`, returning its id
+    fn load(&self, text: &str) -> Uuid {
+        let (status, body) = self.json(
+            "POST",
+            &format!("/factory/load/{FACTORY_NAME}"),
+            Some(&json!({ "text": text }).to_string()),
+        );
+        assert_eq!(status, 200);
+
+        body["id"].as_str().unwrap().parse().unwrap()
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+
+        if let Some(server_handle) = self.server_handle.take() {
+            let _ = server_handle.join();
+        }
+    }
+}
+
+#[test]
+fn discovery_lists_the_factories() {
+    let server = TestServer::start();
+
+    let (status, body) = server.json("GET", "/factory/discovery", None);
+
+    assert_eq!(status, 200);
+    assert_eq!(
+        body,
+        json!([{
+            "name": FACTORY_NAME,
+            "description": "A label for testing",
+            "supported_colors": ["Rgb888"],
+            "example_config": { "text": "Hello" },
+        }])
+    );
+}
+
+#[test]
+fn openapi_document_lists_the_routes() {
+    let server = TestServer::start();
+
+    let (status, body) = server.request("GET", "/openapi.json", None);
+    assert_eq!(status, 200);
+
+    let document: Value = serde_json::from_str(&body).unwrap();
+    assert!(document["openapi"].as_str().unwrap().starts_with("3."));
+
+    for (method, path) in [
+        ("get", "/openapi.json"),
+        ("get", "/render/active"),
+        ("get", "/render/{uuid}/config"),
+        ("post", "/render/{uuid}/config"),
+        ("post", "/render/{uuid}/reset"),
+        ("delete", "/render/{uuid}"),
+        ("get", "/factory/discovery"),
+        ("get", "/factory/status"),
+        ("post", "/factory/load/{render_name}"),
+        ("post", "/factory/preview/{render_name}"),
+        ("post", "/factory/reload/{render_name}"),
+        ("get", "/fonts"),
+        ("get", "/theme"),
+        ("post", "/theme"),
+        ("post", "/push/{render_id}"),
+        ("post", "/layout_manager/select/{uuid}"),
+    ] {
+        assert!(
+            document["paths"][path][method].is_object(),
+            "{method} {path} is not described"
+        );
+    }
+
+    // Every schema that is referred to is described
+    let schemas = &document["components"]["schemas"];
+    for reference in body.match_indices("#/components/schemas/").map(|(i, _)| {
+        body[i..]
+            .trim_start_matches("#/components/schemas/")
+            .split('"')
+            .next()
+            .unwrap()
+    }) {
+        assert!(
+            schemas[reference].is_object(),
+            "{reference} is not described"
+        );
+    }
+}
+
+#[test]
+fn load_adds_the_render_to_the_registry() {
+    let server = TestServer::start();
+
+    let uuid = server.load("Hello");
+
+    {
+        let registry = server.registry.lock();
+        let renders = registry.render_iter().collect::<Vec<_>>();
+        assert_eq!(renders.len(), 1);
+        assert_eq!(*renders[0].0, uuid);
+        assert_eq!(renders[0].1.factory_name, FACTORY_NAME);
+    }
+
+    let (status, body) = server.json("GET", "/render/active", None);
+    assert_eq!(status, 200);
+    assert_eq!(
+        body,
+        json!([{
+            "id": uuid.to_string(),
+            "factory_name": FACTORY_NAME,
+            "description": "TestLabel: Hello",
+        }])
+    );
+
+    let (status, body) = server.request("GET", &format!("/render/{uuid}/config"), None);
+    assert_eq!(status, 200);
+    assert_eq!(
+        serde_json::from_str::<Value>(&body).unwrap(),
+        json!({ "text": "Hello" })
+    );
+}
+
+#[test]
+fn load_from_an_unknown_factory_is_not_found() {
+    let server = TestServer::start();
+
+    let (status, _) = server.request("POST", "/factory/load/Unknown", Some("{}"));
+
+    assert_eq!(status, 404);
+    assert_eq!(server.registry.lock().render_iter().count(), 0);
+}
+
+#[test]
+fn load_with_an_invalid_config_is_a_bad_request() {
+    let server = TestServer::start();
+
+    let (status, body) = server.json(
+        "POST",
+        &format!("/factory/load/{FACTORY_NAME}"),
+        Some(r#"{ "txt": "Hello" }"#),
+    );
+
+    assert_eq!(status, 400);
+    assert!(body["description"].is_string());
+    assert_eq!(server.registry.lock().render_iter().count(), 0);
+}
+
+#[test]
+fn load_with_malformed_json_reports_where() {
+    let server = TestServer::start();
+
+    let (status, body) = server.json(
+        "POST",
+        &format!("/factory/load/{FACTORY_NAME}"),
+        Some("{\n  \"text\": \"Hello\",\n}"),
+    );
+
+    assert_eq!(status, 400);
+    assert_eq!(body["kind"], "syntax");
+    assert_eq!(body["line"], 3);
+    assert_eq!(body["column"], 1);
+    assert_eq!(server.registry.lock().render_iter().count(), 0);
+}
+
+#[test]
+fn load_with_a_field_of_the_wrong_type_reports_where() {
+    let server = TestServer::start();
+
+    let (status, body) = server.json(
+        "POST",
+        &format!("/factory/load/{FACTORY_NAME}"),
+        Some(r#"{ "text": 5 }"#),
+    );
+
+    assert_eq!(status, 400);
+    assert_eq!(body["kind"], "type");
+    assert_eq!(body["line"], 1);
+    assert_eq!(body["column"], 11);
+    assert_eq!(server.registry.lock().render_iter().count(), 0);
+}
+
+#[test]
+fn load_with_a_config_that_is_not_an_object_is_a_bad_request() {
+    let server = TestServer::start();
+
+    let (status, body) = server.json(
+        "POST",
+        &format!("/factory/load/{FACTORY_NAME}"),
+        Some(r#"["Hello"]"#),
+    );
+
+    assert_eq!(status, 400);
+    assert_eq!(body["kind"], "type");
+    assert_eq!(server.registry.lock().render_iter().count(), 0);
+}
+
+#[test]
+fn load_with_a_config_that_is_not_utf8_reports_where() {
+    let server = TestServer::start();
+    let config = b"{ \"text\": \"\xff\" }";
+
+    let mut request = format!(
+        "POST /factory/load/{FACTORY_NAME} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+         Content-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+        config.len()
+    )
+    .into_bytes();
+    request.extend_from_slice(config);
+
+    let (status, body) = server.send(request);
+    let body: Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(status, 400);
+    assert_eq!(body["kind"], "encoding");
+    assert_eq!(body["line"], 1);
+    assert_eq!(body["column"], 12);
+    assert_eq!(server.registry.lock().render_iter().count(), 0);
+}
+
+#[test]
+fn load_without_a_json_body_is_a_bad_request() {
+    let server = TestServer::start();
+
+    let (status, _) = server.send(&format!(
+        "POST /factory/load/{FACTORY_NAME} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+         Content-Type: text/plain\r\nContent-Length: 5\r\n\r\nHello"
+    ));
+
+    assert_eq!(status, 400);
+    assert_eq!(server.registry.lock().render_iter().count(), 0);
+}
+
+#[test]
+fn load_with_an_oversized_body_is_rejected() {
+    let server = TestServer::start();
+    let text = "A".repeat(DEFAULT_MAX_CONFIG_BYTES);
+
+    let (status, _) = server.request(
+        "POST",
+        &format!("/factory/load/{FACTORY_NAME}"),
+        Some(&json!({ "text": text }).to_string()),
+    );
+
+    assert_eq!(status, 413);
+    assert_eq!(server.registry.lock().render_iter().count(), 0);
+}
+
+#[test]
+fn reconfigure_keeps_the_render() {
+    let server = TestServer::start();
+    let uuid = server.load("Hello");
+
+    let (status, _) = server.request(
+        "POST",
+        &format!("/render/{uuid}/config"),
+        Some(r#"{ "text": "World" }"#),
+    );
+    assert_eq!(status, 204);
+
+    let (status, body) = server.json("GET", "/render/active", None);
+    assert_eq!(status, 200);
+    assert_eq!(
+        body,
+        json!([{
+            "id": uuid.to_string(),
+            "factory_name": FACTORY_NAME,
+            "description": "TestLabel: World",
+        }])
+    );
+
+    let (status, body) = server.json("GET", &format!("/render/{uuid}/config"), None);
+    assert_eq!(status, 200);
+    assert_eq!(body, json!({ "text": "World" }));
+}
+
+#[test]
+fn reconfigure_with_an_invalid_config_keeps_the_previous_config() {
+    let server = TestServer::start();
+    let uuid = server.load("Hello");
+
+    let (status, body) = server.json(
+        "POST",
+        &format!("/render/{uuid}/config"),
+        Some(r#"{ "text": 5 }"#),
+    );
+    assert_eq!(status, 400);
+    assert_eq!(body["kind"], "type");
+
+    let (status, body) = server.json("GET", &format!("/render/{uuid}/config"), None);
+    assert_eq!(status, 200);
+    assert_eq!(body, json!({ "text": "Hello" }));
+}
+
+#[test]
+fn reconfigure_of_an_unknown_render_is_not_found() {
+    let server = TestServer::start();
+
+    let (status, _) = server.request(
+        "POST",
+        &format!("/render/{}/config", Uuid::new_v4()),
+        Some(r#"{ "text": "World" }"#),
+    );
+
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn select_changes_the_selected_render() {
+    let server = TestServer::start();
+    let uuid = server.load("Hello");
+
+    let (status, _) = server.request("POST", &format!("/layout_manager/select/{uuid}"), None);
+    assert_eq!(status, 204);
+    assert_eq!(server.registry.lock().selected_name(), Some(FACTORY_NAME));
+
+    let (status, body) = server.json("GET", "/factory/status", None);
+    assert_eq!(status, 200);
+    assert_eq!(
+        body,
+        json!([{ "name": FACTORY_NAME, "loaded": true, "selected": true }])
+    );
+}
+
+#[test]
+fn select_of_an_unknown_render_is_not_found() {
+    let server = TestServer::start();
+    server.load("Hello");
+
+    let (status, _) = server.request(
+        "POST",
+        &format!("/layout_manager/select/{}", Uuid::new_v4()),
+        None,
+    );
+
+    assert_eq!(status, 404);
+    assert_eq!(server.registry.lock().selected_name(), None);
+}
+
+#[test]
+fn delete_unloads_the_render() {
+    let server = TestServer::start();
+    let uuid = server.load("Hello");
+    server.request("POST", &format!("/layout_manager/select/{uuid}"), None);
+
+    let (status, _) = server.request("DELETE", &format!("/render/{uuid}"), None);
+    assert_eq!(status, 204);
+
+    {
+        let registry = server.registry.lock();
+        assert_eq!(registry.render_iter().count(), 0);
+        assert_eq!(registry.selected_name(), None);
+    }
+
+    let (status, _) = server.request("DELETE", &format!("/render/{uuid}"), None);
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn unknown_routes_are_not_found() {
+    let server = TestServer::start();
+
+    let (status, _) = server.request("GET", "/factory/unknown", None);
+
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn push_to_an_unknown_route_is_not_found() {
+    let server = TestServer::start();
+
+    let (status, _) = server.request("POST", "/push/unknown", Some("{}"));
+
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn push_is_handed_to_the_registered_route() {
+    let server = TestServer::start();
+    let pushed = Arc::new(Mutex::new(Vec::new()));
+
+    let route_pushed = pushed.clone();
+    let route = register_push_route(
+        "handed_over",
+        Arc::new(move |body: &[u8]| -> Result<(), String> {
+            route_pushed.lock().push(body.to_vec());
+            Ok(())
+        }),
+    )
+    .unwrap();
+
+    let (status, _) = server.request("POST", "/push/handed_over", Some(r#"{ "Door": "Open" }"#));
+    assert_eq!(status, 204);
+    assert_eq!(*pushed.lock(), vec![br#"{ "Door": "Open" }"#.to_vec()]);
+
+    // The route is gone along with whatever registered it
+    drop(route);
+    let (status, _) = server.request("POST", "/push/handed_over", Some("{}"));
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn push_rejected_by_the_route_is_a_bad_request() {
+    let server = TestServer::start();
+    let _route = register_push_route(
+        "rejecting",
+        Arc::new(|_: &[u8]| -> Result<(), String> { Err("Not accepted".to_owned()) }),
+    )
+    .unwrap();
+
+    let (status, body) = server.request("POST", "/push/rejecting", Some("{}"));
+
+    assert_eq!(status, 400);
+    assert_eq!(body, "Not accepted");
+}
+
+#[test]
+fn routes_with_the_same_id_are_rejected() {
+    let _route = register_push_route(
+        "duplicate",
+        Arc::new(|_: &[u8]| -> Result<(), String> { Ok(()) }),
+    )
+    .unwrap();
+
+    assert!(register_push_route(
+        "duplicate",
+        Arc::new(|_: &[u8]| -> Result<(), String> { Ok(()) })
+    )
+    .is_err());
+}
+
+#[test]
+fn get_is_answered_by_the_registered_route() {
+    let server = TestServer::start();
+    let route = register_get_route(
+        "/registered/search",
+        Arc::new(|param: &dyn Fn(&str) -> Option<String>| json!({ "q": param("q") })),
+    )
+    .unwrap();
+
+    let (status, body) = server.request("GET", "/registered/search?q=subur", None);
+    assert_eq!(status, 200);
+    assert_eq!(
+        serde_json::from_str::<Value>(&body).unwrap(),
+        json!({ "q": "subur" })
+    );
+
+    // Only GET requests are handed to the route
+    let (status, _) = server.request("POST", "/registered/search", Some("{}"));
+    assert_eq!(status, 404);
+
+    drop(route);
+    let (status, _) = server.request("GET", "/registered/search?q=subur", None);
+    assert_eq!(status, 404);
+}
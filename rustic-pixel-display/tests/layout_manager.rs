@@ -0,0 +1,289 @@
+//! Tests of where the layout manager places the renders of its regions.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+};
+use rustic_pixel_display::{
+    layout_manager::{CommonLayout, LayoutManager, StatusBarPosition},
+    render::{BufferCanvas, Render, SubCanvas, SyncRender},
+};
+use std::convert::Infallible;
+
+const CANVAS_SIZE: Size = Size::new(32, 24);
+
+type SubRender = Box<dyn for<'a> SyncRender<SubCanvas<'a, BufferCanvas>>>;
+
+/// Fills its region with its color
+struct Fill(Rgb888);
+
+impl<D> Render<D> for Fill
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        canvas.clear(self.0)
+    }
+}
+
+fn draw(layout_manager: &LayoutManager<BufferCanvas>) -> BufferCanvas {
+    let mut canvas = BufferCanvas::new(CANVAS_SIZE);
+    layout_manager.render(&mut canvas).unwrap();
+
+    canvas
+}
+
+/// The color of each row of the canvas, which is the same across the row
+fn row_colors(canvas: &BufferCanvas) -> Vec<Rgb888> {
+    (0..CANVAS_SIZE.height as i32)
+        .map(|y| {
+            let color = canvas.pixel(Point::new(0, y)).unwrap();
+            assert!((0..CANVAS_SIZE.width as i32)
+                .all(|x| canvas.pixel(Point::new(x, y)) == Some(color)));
+
+            color
+        })
+        .collect()
+}
+
+fn status_bar(bar_height: u32, position: StatusBarPosition) -> LayoutManager<BufferCanvas> {
+    LayoutManager::from_common_layout(
+        CommonLayout::WithStatusBar {
+            bar_height,
+            position,
+            bar: Some(Box::new(Fill(Rgb888::RED))),
+            body: Some(Box::new(Fill(Rgb888::BLUE))),
+        },
+        CANVAS_SIZE,
+    )
+}
+
+#[test]
+fn body_starts_below_top_bar() {
+    let colors = row_colors(&draw(&status_bar(10, StatusBarPosition::Top)));
+
+    assert!(colors[..10].iter().all(|&color| color == Rgb888::RED));
+    assert!(colors[10..].iter().all(|&color| color == Rgb888::BLUE));
+}
+
+#[test]
+fn body_ends_above_bottom_bar() {
+    let colors = row_colors(&draw(&status_bar(10, StatusBarPosition::Bottom)));
+
+    assert!(colors[..14].iter().all(|&color| color == Rgb888::BLUE));
+    assert!(colors[14..].iter().all(|&color| color == Rgb888::RED));
+}
+
+#[test]
+fn bar_taller_than_canvas_leaves_no_body() {
+    let colors = row_colors(&draw(&status_bar(100, StatusBarPosition::Top)));
+
+    assert!(colors.iter().all(|&color| color == Rgb888::RED));
+}
+
+#[test]
+fn resize_moves_body_below_bar() {
+    let mut layout_manager = status_bar(10, StatusBarPosition::Bottom);
+    layout_manager.resize(Size::new(32, 16));
+
+    let mut canvas = BufferCanvas::new(Size::new(32, 16));
+    layout_manager.render(&mut canvas).unwrap();
+
+    assert_eq!(canvas.pixel(Point::new(0, 5)), Some(Rgb888::BLUE));
+    assert_eq!(canvas.pixel(Point::new(0, 6)), Some(Rgb888::RED));
+}
+
+fn fill(color: Rgb888) -> Option<SubRender> {
+    Some(Box::new(Fill(color)))
+}
+
+/// A render of every layout, filling each region with its own color
+fn every_layout() -> Vec<CommonLayout<BufferCanvas>> {
+    vec![
+        CommonLayout::Single(fill(Rgb888::RED)),
+        CommonLayout::SplitWidth {
+            left: fill(Rgb888::RED),
+            right: fill(Rgb888::BLUE),
+        },
+        CommonLayout::SplitHeight {
+            top: fill(Rgb888::RED),
+            bottom: fill(Rgb888::BLUE),
+        },
+        CommonLayout::Split4 {
+            top_left: fill(Rgb888::RED),
+            top_right: fill(Rgb888::GREEN),
+            bottom_left: fill(Rgb888::YELLOW),
+            bottom_right: fill(Rgb888::BLUE),
+        },
+        CommonLayout::WithStatusBar {
+            bar_height: 1,
+            position: StatusBarPosition::Top,
+            bar: fill(Rgb888::RED),
+            body: fill(Rgb888::BLUE),
+        },
+    ]
+}
+
+#[test]
+fn tiny_canvases_do_not_panic() {
+    for size in [
+        Size::zero(),
+        Size::new(1, 0),
+        Size::new(0, 1),
+        Size::new(1, 1),
+        Size::new(1, 2),
+        Size::new(2, 1),
+    ] {
+        for common_layout in every_layout() {
+            let layout_manager = LayoutManager::from_common_layout(common_layout, size);
+
+            let mut canvas = BufferCanvas::new(size);
+            layout_manager.render(&mut canvas).unwrap();
+        }
+    }
+}
+
+#[test]
+fn one_pixel_canvas_is_drawn_by_the_last_region() {
+    let size = Size::new(1, 1);
+    // The first half of a side that can't be split is zero sized and skipped
+    let expected = [
+        Rgb888::RED,
+        Rgb888::BLUE,
+        Rgb888::BLUE,
+        Rgb888::BLUE,
+        Rgb888::RED,
+    ];
+
+    for (common_layout, expected) in every_layout().into_iter().zip(expected) {
+        let layout_manager = LayoutManager::from_common_layout(common_layout, size);
+
+        let mut canvas = BufferCanvas::new(size);
+        layout_manager.render(&mut canvas).unwrap();
+
+        assert_eq!(canvas.pixel(Point::zero()), Some(expected));
+    }
+}
+
+#[test]
+fn odd_width_gives_the_extra_column_to_the_right() {
+    let size = Size::new(3, 1);
+    let layout_manager = LayoutManager::from_common_layout(
+        CommonLayout::SplitWidth {
+            left: fill(Rgb888::RED),
+            right: fill(Rgb888::BLUE),
+        },
+        size,
+    );
+
+    let mut canvas = BufferCanvas::new(size);
+    layout_manager.render(&mut canvas).unwrap();
+
+    assert_eq!(canvas.pixels(), [Rgb888::RED, Rgb888::BLUE, Rgb888::BLUE]);
+}
+
+/// A region of the common layout filled with green, to add regions on top of
+fn single_green() -> LayoutManager<BufferCanvas> {
+    LayoutManager::from_common_layout(CommonLayout::Single(fill(Rgb888::GREEN)), CANVAS_SIZE)
+}
+
+#[test]
+fn higher_z_is_drawn_on_top() {
+    let mut layout_manager = single_green();
+    layout_manager.add_render_z(
+        Size::new(8, 8),
+        Point::new(4, 4),
+        2,
+        Box::new(Fill(Rgb888::RED)),
+    );
+    // Added last, but drawn below the red region it overlaps
+    layout_manager.add_render_z(
+        Size::new(8, 8),
+        Point::new(8, 8),
+        1,
+        Box::new(Fill(Rgb888::BLUE)),
+    );
+
+    let canvas = draw(&layout_manager);
+
+    assert_eq!(canvas.pixel(Point::new(0, 0)), Some(Rgb888::GREEN));
+    assert_eq!(canvas.pixel(Point::new(5, 5)), Some(Rgb888::RED));
+    assert_eq!(canvas.pixel(Point::new(10, 10)), Some(Rgb888::RED));
+    assert_eq!(canvas.pixel(Point::new(14, 14)), Some(Rgb888::BLUE));
+}
+
+#[test]
+fn same_z_is_drawn_in_the_order_added() {
+    let mut layout_manager = single_green();
+    layout_manager.add_render(
+        Size::new(8, 8),
+        Point::new(4, 4),
+        Box::new(Fill(Rgb888::RED)),
+    );
+    layout_manager.add_render(
+        Size::new(8, 8),
+        Point::new(8, 8),
+        Box::new(Fill(Rgb888::BLUE)),
+    );
+
+    let canvas = draw(&layout_manager);
+
+    // Both are drawn on top of the common layout, which also has a z of zero
+    assert_eq!(canvas.pixel(Point::new(5, 5)), Some(Rgb888::RED));
+    assert_eq!(canvas.pixel(Point::new(10, 10)), Some(Rgb888::BLUE));
+}
+
+#[test]
+fn negative_z_is_drawn_below_the_common_layout() {
+    let mut layout_manager = single_green();
+    layout_manager.add_render_z(
+        Size::new(8, 8),
+        Point::new(4, 4),
+        -1,
+        Box::new(Fill(Rgb888::RED)),
+    );
+
+    let canvas = draw(&layout_manager);
+
+    assert_eq!(canvas.pixel(Point::new(5, 5)), Some(Rgb888::GREEN));
+}
+
+#[test]
+fn resize_only_moves_the_common_regions() {
+    let mut layout_manager = LayoutManager::from_common_layout(
+        CommonLayout::SplitWidth {
+            left: fill(Rgb888::RED),
+            right: fill(Rgb888::BLUE),
+        },
+        CANVAS_SIZE,
+    );
+    // Drawn below the common regions, so it is kept ahead of them
+    layout_manager.add_render_z(
+        Size::new(4, 4),
+        Point::new(2, 2),
+        -1,
+        Box::new(Fill(Rgb888::WHITE)),
+    );
+    layout_manager.add_render_z(
+        Size::new(4, 4),
+        Point::new(2, 2),
+        1,
+        Box::new(Fill(Rgb888::YELLOW)),
+    );
+
+    let size = Size::new(48, 24);
+    layout_manager.resize(size);
+
+    let mut canvas = BufferCanvas::new(size);
+    layout_manager.render(&mut canvas).unwrap();
+
+    assert_eq!(canvas.pixel(Point::new(0, 0)), Some(Rgb888::RED));
+    assert_eq!(canvas.pixel(Point::new(23, 0)), Some(Rgb888::RED));
+    assert_eq!(canvas.pixel(Point::new(24, 0)), Some(Rgb888::BLUE));
+    assert_eq!(canvas.pixel(Point::new(47, 23)), Some(Rgb888::BLUE));
+
+    // The added region on top keeps its placement
+    assert_eq!(canvas.pixel(Point::new(2, 2)), Some(Rgb888::YELLOW));
+    assert_eq!(canvas.pixel(Point::new(5, 5)), Some(Rgb888::YELLOW));
+    assert_eq!(canvas.pixel(Point::new(6, 6)), Some(Rgb888::RED));
+}
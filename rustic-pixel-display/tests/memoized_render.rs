@@ -0,0 +1,96 @@
+//! Tests that a memoized render only draws its inner render when the key of
+//! its state changes.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+};
+use rustic_pixel_display::render::{BufferCanvas, MemoizedRender, Render};
+use std::{
+    convert::Infallible,
+    sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+};
+
+/// Fills the canvas with a shade of its value, counting how often it is drawn
+#[derive(Default)]
+struct Counter {
+    value: AtomicU32,
+    draws: AtomicUsize,
+}
+
+impl Counter {
+    fn draws(&self) -> usize {
+        self.draws.load(Ordering::SeqCst)
+    }
+}
+
+impl<D> Render<D> for Counter
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.draws.fetch_add(1, Ordering::SeqCst);
+        canvas.clear(Rgb888::new(self.value.load(Ordering::SeqCst) as u8, 0, 0))
+    }
+}
+
+fn memoized_counter() -> MemoizedRender<Counter, u32> {
+    MemoizedRender::new(Counter::default(), |counter: &Counter| {
+        counter.value.load(Ordering::SeqCst)
+    })
+}
+
+fn draw(render: &MemoizedRender<Counter, u32>, canvas: &mut BufferCanvas) -> Rgb888 {
+    canvas.clear(Rgb888::BLACK).unwrap();
+    render.render(canvas).unwrap();
+
+    canvas.pixel(Point::zero()).unwrap()
+}
+
+#[test]
+fn unchanged_key_draws_once() {
+    let render = memoized_counter();
+    render.inner().value.store(200, Ordering::SeqCst);
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+
+    for _ in 0..5 {
+        // The cached frame is still copied onto the canvas
+        assert_eq!(draw(&render, &mut canvas), Rgb888::new(200, 0, 0));
+    }
+
+    assert_eq!(render.inner().draws(), 1);
+}
+
+#[test]
+fn changed_key_draws_again() {
+    let render = memoized_counter();
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+
+    draw(&render, &mut canvas);
+    render.inner().value.store(100, Ordering::SeqCst);
+
+    assert_eq!(draw(&render, &mut canvas), Rgb888::new(100, 0, 0));
+    assert_eq!(render.inner().draws(), 2);
+}
+
+#[test]
+fn resized_canvas_draws_again() {
+    let render = memoized_counter();
+
+    draw(&render, &mut BufferCanvas::new(Size::new(4, 4)));
+    draw(&render, &mut BufferCanvas::new(Size::new(8, 4)));
+
+    assert_eq!(render.inner().draws(), 2);
+}
+
+#[test]
+fn reset_draws_again() {
+    let render = memoized_counter();
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+
+    draw(&render, &mut canvas);
+    Render::<BufferCanvas>::reset(&render);
+    draw(&render, &mut canvas);
+
+    assert_eq!(render.inner().draws(), 2);
+}
@@ -0,0 +1,118 @@
+//! Tests which pixels around a single drawn pixel each outline shape fills in.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Drawable, Point, RgbColor, Size},
+    Pixel,
+};
+use rustic_pixel_display::render::{
+    draw_outlined, BufferCanvas, Outline, OutlineShape, Outlined, Render,
+};
+use std::convert::Infallible;
+
+const SIZE: Size = Size::new(9, 9);
+const CENTER: Point = Point::new(4, 4);
+const BACKGROUND: Rgb888 = Rgb888::BLUE;
+const CONTENT: Rgb888 = Rgb888::WHITE;
+
+fn outline(shape: OutlineShape) -> Outline {
+    Outline {
+        color: Rgb888::RED,
+        shape,
+    }
+}
+
+/// Draws a single pixel at `point` with `shape` around it, over the background
+fn outlined_pixel(point: Point, shape: OutlineShape) -> BufferCanvas {
+    let mut canvas = BufferCanvas::new(SIZE);
+    canvas.clear(BACKGROUND).unwrap();
+
+    draw_outlined(&mut canvas, outline(shape), |buffer| {
+        Pixel(point, CONTENT).draw(buffer)
+    })
+    .unwrap();
+
+    canvas
+}
+
+/// The points of `canvas` drawn in `color`
+fn points_of(canvas: &BufferCanvas, color: Rgb888) -> Vec<Point> {
+    (0..SIZE.height as i32)
+        .flat_map(|y| (0..SIZE.width as i32).map(move |x| Point::new(x, y)))
+        .filter(|&point| canvas.pixel(point) == Some(color))
+        .collect()
+}
+
+#[test]
+fn cross_outlines_four_neighbors() {
+    let canvas = outlined_pixel(CENTER, OutlineShape::Cross);
+
+    assert_eq!(
+        points_of(&canvas, Rgb888::RED),
+        [
+            Point::new(4, 3),
+            Point::new(3, 4),
+            Point::new(5, 4),
+            Point::new(4, 5)
+        ]
+    );
+    assert_eq!(points_of(&canvas, CONTENT), [CENTER]);
+}
+
+#[test]
+fn square_outlines_eight_neighbors() {
+    let canvas = outlined_pixel(CENTER, OutlineShape::Square);
+    let outline = points_of(&canvas, Rgb888::RED);
+
+    assert_eq!(outline.len(), 8);
+    assert!(outline
+        .iter()
+        .all(|point| (*point - CENTER).x.abs() <= 1 && (*point - CENTER).y.abs() <= 1));
+    assert_eq!(points_of(&canvas, CONTENT), [CENTER]);
+}
+
+#[test]
+fn shadow_is_below_and_right() {
+    let canvas = outlined_pixel(CENTER, OutlineShape::Shadow);
+
+    assert_eq!(points_of(&canvas, Rgb888::RED), [CENTER + Point::new(1, 1)]);
+    assert_eq!(points_of(&canvas, CONTENT), [CENTER]);
+}
+
+#[test]
+fn outline_is_clipped_to_the_canvas() {
+    let canvas = outlined_pixel(Point::zero(), OutlineShape::Square);
+
+    assert_eq!(
+        points_of(&canvas, Rgb888::RED),
+        [Point::new(1, 0), Point::new(0, 1), Point::new(1, 1)]
+    );
+}
+
+/// Draws a single pixel in the center of the canvas
+struct SinglePixel;
+
+impl<D> Render<D> for SinglePixel
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        Pixel(CENTER, CONTENT).draw(canvas)
+    }
+}
+
+#[test]
+fn outlined_render_keeps_the_background() {
+    let render = Outlined::new(SinglePixel, outline(OutlineShape::Cross));
+
+    let mut canvas = BufferCanvas::new(SIZE);
+    canvas.clear(BACKGROUND).unwrap();
+    render.render(&mut canvas).unwrap();
+
+    assert_eq!(points_of(&canvas, Rgb888::RED).len(), 4);
+    assert_eq!(points_of(&canvas, CONTENT), [CENTER]);
+    assert_eq!(
+        points_of(&canvas, BACKGROUND).len(),
+        (SIZE.width * SIZE.height) as usize - 5
+    );
+}
@@ -0,0 +1,135 @@
+//! Tests of composing physical panels of different sizes into one logical
+//! canvas.
+//!
+//! Every test uses a 64x64 panel followed by a 32x32 panel on a chain driven
+//! as two 64x64 panels, with the small panel placed below the left half of the
+//! large one.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+    primitives::{PointsIter, Rectangle},
+    Drawable, Pixel,
+};
+use rustic_pixel_display::{
+    config::PhysicalPanel,
+    render::{BufferCanvas, PanelMap},
+};
+
+const CHAIN_SIZE: Size = Size::new(128, 64);
+
+const PANELS: [PhysicalPanel; 2] = [
+    PhysicalPanel {
+        x: 0,
+        y: 0,
+        w: 64,
+        h: 64,
+    },
+    PhysicalPanel {
+        x: 0,
+        y: 64,
+        w: 32,
+        h: 32,
+    },
+];
+
+/// Returns every pixel of the chain that is not black
+fn drawn_points(chain: &BufferCanvas) -> Vec<Point> {
+    Rectangle::new(Point::zero(), CHAIN_SIZE)
+        .points()
+        .filter(|&point| chain.pixel(point) != Some(Rgb888::BLACK))
+        .collect()
+}
+
+/// Draws a single pixel at `logical` and returns where it ended up on the
+/// chain
+fn draw_logical_pixel(logical: Point) -> Vec<Point> {
+    let map = PanelMap::new(&PANELS, CHAIN_SIZE).unwrap();
+    let mut chain = BufferCanvas::new(CHAIN_SIZE);
+
+    Pixel(logical, Rgb888::RED)
+        .draw(&mut map.canvas(&mut chain))
+        .unwrap();
+
+    drawn_points(&chain)
+}
+
+#[test]
+fn logical_canvas_spans_every_panel() {
+    let map = PanelMap::new(&PANELS, CHAIN_SIZE).unwrap();
+
+    assert_eq!(map.size(), Size::new(64, 96));
+    assert_eq!(
+        map.canvas(&mut BufferCanvas::new(CHAIN_SIZE))
+            .bounding_box(),
+        Rectangle::new(Point::zero(), Size::new(64, 96))
+    );
+}
+
+#[test]
+fn first_panel_is_at_the_start_of_the_chain() {
+    assert_eq!(
+        draw_logical_pixel(Point::new(10, 20)),
+        vec![Point::new(10, 20)]
+    );
+    assert_eq!(
+        draw_logical_pixel(Point::new(63, 63)),
+        vec![Point::new(63, 63)]
+    );
+}
+
+#[test]
+fn second_panel_follows_the_first_on_the_chain() {
+    // The small panel starts right after the 64 columns of the large one
+    assert_eq!(
+        draw_logical_pixel(Point::new(0, 64)),
+        vec![Point::new(64, 0)]
+    );
+    assert_eq!(
+        draw_logical_pixel(Point::new(5, 70)),
+        vec![Point::new(69, 6)]
+    );
+    assert_eq!(
+        draw_logical_pixel(Point::new(31, 95)),
+        vec![Point::new(95, 31)]
+    );
+}
+
+#[test]
+fn pixels_without_a_panel_are_dropped() {
+    // Right of the small panel, below the large one
+    assert!(draw_logical_pixel(Point::new(40, 70)).is_empty());
+    assert!(draw_logical_pixel(Point::new(0, 96)).is_empty());
+}
+
+#[test]
+fn clear_fills_every_panel() {
+    let map = PanelMap::new(&PANELS, CHAIN_SIZE).unwrap();
+    let mut chain = BufferCanvas::new(CHAIN_SIZE);
+
+    map.canvas(&mut chain).clear(Rgb888::RED).unwrap();
+
+    let expected: Vec<Point> = Rectangle::new(Point::zero(), CHAIN_SIZE)
+        .points()
+        .filter(|point| point.x < 64 || (point.x < 96 && point.y < 32))
+        .collect();
+    assert_eq!(drawn_points(&chain), expected);
+}
+
+#[test]
+fn overlapping_panels_are_rejected() {
+    let mut panels = PANELS;
+    panels[1].y = 32;
+
+    assert!(PanelMap::new(&panels, CHAIN_SIZE).is_err());
+}
+
+#[test]
+fn panels_wider_than_the_chain_are_rejected() {
+    assert!(PanelMap::new(&PANELS, Size::new(64, 64)).is_err());
+}
+
+#[test]
+fn panels_taller_than_the_chain_are_rejected() {
+    assert!(PanelMap::new(&PANELS, Size::new(128, 32)).is_err());
+}
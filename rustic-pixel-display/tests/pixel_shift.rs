@@ -0,0 +1,62 @@
+//! Tests of the offsets the pixel shift walks through over time.
+
+use embedded_graphics::prelude::Point;
+use rustic_pixel_display::render::pixel_shift_offset;
+use std::{collections::HashSet, time::Duration};
+
+const INTERVAL: Duration = Duration::from_secs(180);
+
+fn offset_at_step(step: u32) -> Point {
+    pixel_shift_offset(INTERVAL * step, INTERVAL, 2)
+}
+
+#[test]
+fn offset_starts_unshifted() {
+    assert_eq!(offset_at_step(0), Point::zero());
+    assert_eq!(
+        pixel_shift_offset(INTERVAL - Duration::from_millis(1), INTERVAL, 2),
+        Point::zero()
+    );
+}
+
+#[test]
+fn offset_changes_every_interval() {
+    for step in 0..200 {
+        let (from, to) = (offset_at_step(step), offset_at_step(step + 1));
+        let moved = from - to;
+
+        assert_ne!(from, to, "Did not move after step {step}");
+        assert!(
+            moved.x.abs() <= 1 && moved.y.abs() <= 1,
+            "Moved from {from:?} to {to:?} after step {step}"
+        );
+    }
+}
+
+#[test]
+fn offset_stays_within_range() {
+    for step in 0..200 {
+        let offset = offset_at_step(step);
+        assert!(offset.x.abs() <= 2 && offset.y.abs() <= 2, "{offset:?}");
+    }
+}
+
+#[test]
+fn offset_covers_whole_range() {
+    let offsets: HashSet<(i32, i32)> = (0..64)
+        .map(offset_at_step)
+        .map(|offset| (offset.x, offset.y))
+        .collect();
+
+    assert_eq!(offsets.len(), 25);
+}
+
+#[test]
+fn zero_range_never_moves() {
+    for step in 0..20 {
+        assert_eq!(
+            pixel_shift_offset(INTERVAL * step, INTERVAL, 0),
+            Point::zero()
+        );
+    }
+}
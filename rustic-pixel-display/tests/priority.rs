@@ -0,0 +1,167 @@
+//! Tests that priority renders preempt the selected render and unwind back to
+//! it as they end.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size, WebColors},
+};
+use rustic_pixel_display::{
+    registry::Registry,
+    render::{BufferCanvas, Render, RenderError, RenderFactory, SyncRender},
+};
+use std::{
+    convert::Infallible,
+    io::Read,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Long enough to never expire while a test runs
+const FOREVER: Duration = Duration::from_secs(60 * 60);
+
+/// Fills the canvas with its color and keeps track of whether it is selected
+struct Fill {
+    color: Rgb888,
+    selected: Arc<AtomicBool>,
+}
+
+impl Fill {
+    fn new(color: Rgb888) -> (Box<dyn SyncRender<BufferCanvas>>, Arc<AtomicBool>) {
+        let selected = Arc::new(AtomicBool::new(false));
+        let render = Box::new(Fill {
+            color,
+            selected: selected.clone(),
+        });
+
+        (render, selected)
+    }
+}
+
+impl<D> Render<D> for Fill
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        canvas.clear(self.color)
+    }
+
+    fn on_select(&self) {
+        self.selected.store(true, Ordering::SeqCst);
+    }
+
+    fn on_deselect(&self) {
+        self.selected.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Loads a white [`Fill`]
+struct FillFactory;
+
+impl RenderFactory<BufferCanvas> for FillFactory {
+    fn render_name(&self) -> &'static str {
+        "Fill"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Fills the canvas"
+    }
+
+    fn load_from_config<R: Read>(
+        &self,
+        _reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        Ok(Fill::new(Rgb888::WHITE).0)
+    }
+}
+
+/// A registry with a white render selected
+fn registry() -> Registry<FillFactory, BufferCanvas> {
+    let mut registry = Registry::new(vec![FillFactory]).unwrap();
+    let uuid = registry.load("Fill", "{}".as_bytes()).unwrap();
+    registry.select(uuid).unwrap();
+
+    registry
+}
+
+/// The color the registry draws
+fn drawn_color(registry: &Registry<FillFactory, BufferCanvas>) -> Rgb888 {
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+    registry.render(&mut canvas).unwrap();
+
+    canvas.pixel(Point::zero()).unwrap()
+}
+
+#[test]
+fn priority_render_preempts_selected() {
+    let mut registry = registry();
+    let (alert, alert_selected) = Fill::new(Rgb888::RED);
+
+    let uuid = registry.push_priority(alert, 1, FOREVER);
+    assert_eq!(drawn_color(&registry), Rgb888::RED);
+    assert!(alert_selected.load(Ordering::SeqCst));
+
+    registry.end_priority(uuid).unwrap();
+    assert_eq!(drawn_color(&registry), Rgb888::WHITE);
+    assert!(!alert_selected.load(Ordering::SeqCst));
+}
+
+#[test]
+fn preemption_stacks_and_unwinds() {
+    let mut registry = registry();
+    let (low, low_selected) = Fill::new(Rgb888::BLUE);
+    let (high, high_selected) = Fill::new(Rgb888::RED);
+    let (newer_low, _) = Fill::new(Rgb888::GREEN);
+
+    let low = registry.push_priority(low, 1, FOREVER);
+    assert_eq!(drawn_color(&registry), Rgb888::BLUE);
+
+    let high = registry.push_priority(high, 5, FOREVER);
+    assert_eq!(drawn_color(&registry), Rgb888::RED);
+    assert!(high_selected.load(Ordering::SeqCst));
+    assert!(!low_selected.load(Ordering::SeqCst));
+
+    // A lower priority does not preempt, even when pushed later
+    let newer_low = registry.push_priority(newer_low, 1, FOREVER);
+    assert_eq!(drawn_color(&registry), Rgb888::RED);
+
+    // Of equal priorities the most recently pushed one is drawn
+    registry.end_priority(high).unwrap();
+    assert_eq!(drawn_color(&registry), Rgb888::GREEN);
+
+    registry.end_priority(newer_low).unwrap();
+    assert_eq!(drawn_color(&registry), Rgb888::BLUE);
+    assert!(low_selected.load(Ordering::SeqCst));
+
+    registry.end_priority(low).unwrap();
+    assert_eq!(drawn_color(&registry), Rgb888::WHITE);
+}
+
+#[test]
+fn expired_priority_render_returns_to_selected() {
+    let mut registry = registry();
+    let (long, _) = Fill::new(Rgb888::BLUE);
+    let (short, short_selected) = Fill::new(Rgb888::CSS_ORANGE);
+
+    registry.push_priority(long, 1, FOREVER);
+    registry.push_priority(short, 2, Duration::from_millis(20));
+    assert_eq!(drawn_color(&registry), Rgb888::CSS_ORANGE);
+
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(drawn_color(&registry), Rgb888::BLUE);
+    assert!(!short_selected.load(Ordering::SeqCst));
+}
+
+#[test]
+fn ending_unknown_priority_render_fails() {
+    let mut registry = registry();
+    let (alert, _) = Fill::new(Rgb888::RED);
+
+    let uuid = registry.push_priority(alert, 1, FOREVER);
+    registry.end_priority(uuid).unwrap();
+
+    assert!(registry.end_priority(uuid).is_err());
+}
@@ -0,0 +1,119 @@
+//! Tests that quiet hours blank the panel within their ranges, including ranges
+//! that wrap past midnight, and show the render outside of them.
+
+use chrono::NaiveTime;
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+};
+use rustic_pixel_display::{
+    quiet_hours::{QuietHours, QuietHoursConfig},
+    render::{BufferCanvas, Render},
+    schedule::TimeRange,
+};
+use std::convert::Infallible;
+
+/// Fills the canvas white
+struct White;
+
+impl<D> Render<D> for White
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        canvas.clear(Rgb888::WHITE)
+    }
+}
+
+fn time(hour: u32, min: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, min, 0).unwrap()
+}
+
+fn range(from: NaiveTime, to: NaiveTime) -> TimeRange {
+    TimeRange { from, to }
+}
+
+fn quiet_hours(ranges: Vec<TimeRange>) -> QuietHours<BufferCanvas> {
+    QuietHours::new(Box::new(White), ranges)
+}
+
+/// The color the quiet hours draw
+fn drawn_color(quiet_hours: &QuietHours<BufferCanvas>) -> Rgb888 {
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+    quiet_hours.render(&mut canvas).unwrap();
+
+    canvas.pixel(Point::zero()).unwrap()
+}
+
+#[test]
+fn quiet_within_range() {
+    let quiet_hours = quiet_hours(vec![range(time(1, 0), time(6, 0))]);
+
+    assert!(quiet_hours.is_quiet_at(time(1, 0)));
+    assert!(quiet_hours.is_quiet_at(time(3, 30)));
+    assert!(!quiet_hours.is_quiet_at(time(6, 0)));
+    assert!(!quiet_hours.is_quiet_at(time(0, 59)));
+    assert!(!quiet_hours.is_quiet_at(time(12, 0)));
+}
+
+#[test]
+fn quiet_across_midnight() {
+    let quiet_hours = quiet_hours(vec![range(time(22, 0), time(6, 0))]);
+
+    assert!(quiet_hours.is_quiet_at(time(23, 30)));
+    assert!(quiet_hours.is_quiet_at(time(0, 0)));
+    assert!(quiet_hours.is_quiet_at(time(0, 30)));
+    assert!(!quiet_hours.is_quiet_at(time(6, 30)));
+    assert!(!quiet_hours.is_quiet_at(time(21, 59)));
+}
+
+#[test]
+fn quiet_within_any_range() {
+    let quiet_hours = quiet_hours(vec![
+        range(time(1, 0), time(6, 0)),
+        range(time(13, 0), time(14, 0)),
+    ]);
+
+    assert!(quiet_hours.is_quiet_at(time(2, 0)));
+    assert!(quiet_hours.is_quiet_at(time(13, 30)));
+    assert!(!quiet_hours.is_quiet_at(time(10, 0)));
+    assert!(!quiet_hours.is_quiet_at(time(14, 0)));
+}
+
+#[test]
+fn ranges_parse_from_config() {
+    let config: QuietHoursConfig = serde_json::from_str(
+        r#"{ "ranges": [["22:00", "06:00"], ["13:00:30", "14:00"]], "factory": "Label", "config": {} }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.ranges,
+        vec![
+            range(time(22, 0), time(6, 0)),
+            range(NaiveTime::from_hms_opt(13, 0, 30).unwrap(), time(14, 0)),
+        ]
+    );
+    assert_eq!(config.render.factory, "Label");
+
+    assert!(serde_json::from_str::<QuietHoursConfig>(
+        r#"{ "ranges": [["25:00", "06:00"]], "factory": "Label", "config": {} }"#
+    )
+    .is_err());
+}
+
+#[test]
+fn blank_while_quiet() {
+    // Equal ends cover the whole day, so it is quiet whenever the test runs
+    let quiet_hours = quiet_hours(vec![range(time(0, 0), time(0, 0))]);
+
+    assert_eq!(drawn_color(&quiet_hours), Rgb888::BLACK);
+    assert!(Render::<BufferCanvas>::has_content(&quiet_hours));
+}
+
+#[test]
+fn delegates_outside_quiet_hours() {
+    let quiet_hours = quiet_hours(Vec::new());
+
+    assert_eq!(drawn_color(&quiet_hours), Rgb888::WHITE);
+}
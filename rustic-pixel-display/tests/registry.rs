@@ -0,0 +1,234 @@
+//! Tests that the registry looks factories up by a unique name, only lets the
+//! selected render poll for data, and resets renders through the wrappers that
+//! cache their frames.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    registry::{Registry, RegistryError},
+    render::{
+        BufferCanvas, MemoizedRender, PollGate, Render, RenderError, RenderFactory, SyncRender,
+        Throttle,
+    },
+};
+use std::{
+    convert::Infallible,
+    io::Read,
+    sync::atomic::{AtomicU8, Ordering},
+    time::Duration,
+};
+
+/// A factory that can't load anything, only its name matters
+struct NamedFactory(&'static str);
+
+impl RenderFactory<BufferCanvas> for NamedFactory {
+    fn render_name(&self) -> &'static str {
+        self.0
+    }
+
+    fn render_description(&self) -> &'static str {
+        "A factory for testing"
+    }
+
+    fn load_from_config<R: Read>(
+        &self,
+        _reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        Err(RenderError::Unsupported("Nothing to load".to_owned()))
+    }
+}
+
+fn registry(names: &[&'static str]) -> Result<Registry<NamedFactory, BufferCanvas>, RegistryError> {
+    Registry::new(names.iter().copied().map(NamedFactory).collect())
+}
+
+#[test]
+fn unique_names_are_accepted() {
+    let registry = registry(&["Clock", "Weather", "Label"]).unwrap();
+
+    let mut names = registry
+        .factory_iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+
+    assert_eq!(names, ["Clock", "Label", "Weather"]);
+}
+
+#[test]
+fn duplicate_names_are_rejected() {
+    let error = registry(&["Clock", "Weather", "Clock"]).err().unwrap();
+
+    assert!(matches!(&error, RegistryError::DuplicateFactory(name) if name == "Clock"));
+    assert!(error.to_string().contains("\"Clock\""));
+}
+
+/// Opens its poll gate while it is selected, the same as the renders that
+/// fetch their data do
+struct Polling(PollGate);
+
+impl<D> Render<D> for Polling
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, _canvas: &mut D) -> Result<(), D::Error> {
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.0.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.0.pause();
+    }
+}
+
+/// Loads [`Polling`] renders, keeping the gate of every render it loaded
+#[derive(Default)]
+struct PollingFactory {
+    gates: Mutex<Vec<PollGate>>,
+}
+
+impl RenderFactory<BufferCanvas> for PollingFactory {
+    fn render_name(&self) -> &'static str {
+        "Polling"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "A render that polls while it is selected"
+    }
+
+    fn load_from_config<R: Read>(
+        &self,
+        _reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let gate = PollGate::new();
+        self.gates.lock().push(gate.clone());
+
+        Ok(Box::new(Polling(gate)))
+    }
+}
+
+#[test]
+fn only_the_selected_render_polls() {
+    let mut registry: Registry<PollingFactory, BufferCanvas> =
+        Registry::new(vec![PollingFactory::default()]).unwrap();
+
+    let first = registry.load("Polling", "{}".as_bytes()).unwrap();
+    let second = registry.load("Polling", "{}".as_bytes()).unwrap();
+
+    let active = |registry: &Registry<PollingFactory, BufferCanvas>| {
+        let (_, factory) = registry.factory_iter().next().unwrap();
+        factory
+            .gates
+            .lock()
+            .iter()
+            .map(PollGate::is_active)
+            .collect::<Vec<_>>()
+    };
+
+    // Loaded renders wait to be selected before polling
+    assert_eq!(active(&registry), [false, false]);
+
+    registry.select(first).unwrap();
+    assert_eq!(active(&registry), [true, false]);
+
+    registry.select(second).unwrap();
+    assert_eq!(active(&registry), [false, true]);
+}
+
+/// Fills the canvas with a shade picked by its seed, which changes every time
+/// it is reset
+#[derive(Default)]
+struct Seeded {
+    seed: AtomicU8,
+}
+
+impl<D> Render<D> for Seeded
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        canvas.clear(Rgb888::new(self.seed.load(Ordering::SeqCst), 0, 0))
+    }
+
+    fn reset(&self) {
+        self.seed.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Loads [`Seeded`] renders behind a [`MemoizedRender`] that never sees its
+/// state change and a [`Throttle`] that doesn't redraw in the test, so only a
+/// reset makes either draw the render again
+struct CachedFactory;
+
+impl RenderFactory<BufferCanvas> for CachedFactory {
+    fn render_name(&self) -> &'static str {
+        "Cached"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "A render behind wrappers that cache its frame"
+    }
+
+    fn load_from_config<R: Read>(
+        &self,
+        _reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let memoized = MemoizedRender::new(Seeded::default(), |_| ());
+
+        Ok(Box::new(Throttle::new(memoized, Duration::from_secs(3600))))
+    }
+}
+
+/// Draws the registry the same way the driver does, returning the shade it
+/// was drawn in and whether the whole frame was redrawn
+fn draw(registry: &Registry<CachedFactory, BufferCanvas>) -> (u8, bool) {
+    let full_redraw = registry.dirty_region().is_none();
+
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+    registry.render(&mut canvas).unwrap();
+
+    (canvas.pixel(Point::zero()).unwrap().r(), full_redraw)
+}
+
+#[test]
+fn reset_reaches_through_cached_frames() {
+    let mut registry: Registry<CachedFactory, BufferCanvas> =
+        Registry::new(vec![CachedFactory]).unwrap();
+
+    let uuid = registry.load("Cached", "{}".as_bytes()).unwrap();
+    registry.select(uuid).unwrap();
+
+    assert_eq!(draw(&registry), (0, true));
+    assert_eq!(draw(&registry), (0, false));
+
+    // Both wrappers drop their cached frame, and the new seed is drawn over
+    // the whole frame
+    registry.reset(uuid).unwrap();
+    assert_eq!(draw(&registry), (1, true));
+    assert_eq!(draw(&registry), (1, false));
+}
+
+#[test]
+fn reset_of_unselected_render_keeps_frame() {
+    let mut registry: Registry<CachedFactory, BufferCanvas> =
+        Registry::new(vec![CachedFactory]).unwrap();
+
+    let selected = registry.load("Cached", "{}".as_bytes()).unwrap();
+    let other = registry.load("Cached", "{}".as_bytes()).unwrap();
+    registry.select(selected).unwrap();
+
+    assert_eq!(draw(&registry), (0, true));
+
+    registry.reset(other).unwrap();
+    assert_eq!(draw(&registry), (0, false));
+
+    // The reset render starts from its new seed once it is shown
+    registry.select(other).unwrap();
+    assert_eq!(draw(&registry), (1, true));
+}
@@ -0,0 +1,134 @@
+//! Tests that a render panicking doesn't take down the threads of the driver,
+//! which keep drawing frames once the render recovers.
+
+use anyhow::Result;
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, RgbColor, Size},
+};
+use rustic_pixel_display::{
+    config::{DriverBackend, HardwareConfig, HardwareMapping, LedSequence, RowAddressSetterType},
+    driver::{HardwareDriver, MatrixDriver},
+    render::{BufferCanvas, Render},
+};
+use std::{
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Number of frames the mock driver has displayed
+static DISPLAYED_FRAMES: AtomicUsize = AtomicUsize::new(0);
+
+struct MockHardwareDriver;
+
+impl HardwareDriver for MockHardwareDriver {
+    type Config = HardwareConfig;
+    type Canvas = BufferCanvas;
+
+    fn new(_config: Self::Config) -> Result<Self> {
+        Ok(Self)
+    }
+
+    fn create_canvas(&mut self) -> Box<Self::Canvas> {
+        Box::new(BufferCanvas::new(Size::new(8, 8)))
+    }
+
+    fn display_canvas(&mut self, canvas: Box<Self::Canvas>) -> Box<Self::Canvas> {
+        DISPLAYED_FRAMES.fetch_add(1, Ordering::SeqCst);
+        canvas
+    }
+}
+
+/// Panics the `panic_on`th time it is drawn, counting how often it is drawn
+struct Flaky {
+    calls: Arc<AtomicUsize>,
+    panic_on: usize,
+}
+
+impl<D> Render<D> for Flaky
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if call == self.panic_on {
+            panic!("Flaky render failed on call {call}");
+        }
+
+        canvas.clear(Rgb888::WHITE)
+    }
+}
+
+fn hardware_config() -> HardwareConfig {
+    HardwareConfig {
+        hardware_mapping: HardwareMapping::Regular,
+        rows: 8,
+        cols: 8,
+        refresh_rate: 120,
+        pi_chip: None,
+        pwm_bits: 11,
+        pwm_lsb_nanoseconds: 130,
+        slowdown: None,
+        interlaced: false,
+        dither_bits: 0,
+        chain_length: 1,
+        parallel: 1,
+        panel_type: None,
+        multiplexing: None,
+        row_setter: RowAddressSetterType::Direct,
+        led_sequence: LedSequence::Rgb,
+        spi_bus: None,
+        spi_speed_hz: None,
+        file_output_dir: None,
+        file_write_interval_ms: None,
+        tcp_address: None,
+        max_fps: None,
+        driver_backend: DriverBackend::Rust,
+        pixel_mapper: None,
+        software_dither: None,
+        simulator_scale: None,
+        panels: Vec::new(),
+    }
+}
+
+/// Waits until `counter` reaches `expected`, failing the test if it takes too
+/// long
+fn wait_for(counter: &AtomicUsize, expected: usize) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while counter.load(Ordering::SeqCst) < expected {
+        assert!(
+            Instant::now() < deadline,
+            "Only reached {} of {expected}",
+            counter.load(Ordering::SeqCst)
+        );
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn driver_survives_a_panicking_render() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let render = Flaky {
+        calls: calls.clone(),
+        panic_on: 3,
+    };
+
+    let driver =
+        MatrixDriver::with_single_render::<MockHardwareDriver, _>(render, None, hardware_config())
+            .unwrap();
+
+    // Well past the call that panicked
+    wait_for(&calls, 10);
+    let displayed = DISPLAYED_FRAMES.load(Ordering::SeqCst);
+    wait_for(&DISPLAYED_FRAMES, displayed + 5);
+
+    // Dropping the driver fails the test if either of its threads returned an
+    // error
+    drop(driver);
+}
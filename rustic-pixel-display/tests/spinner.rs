@@ -0,0 +1,68 @@
+//! Tests that the spinner advances with the time it is drawn at, whatever the
+//! frame rate.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, RgbColor, Size},
+};
+use rustic_pixel_display::render::{BufferCanvas, Render, Spinner};
+use std::time::{Duration, Instant};
+
+const FRAME_DURATION: Duration = Duration::from_millis(50);
+
+fn spinner() -> Spinner {
+    Spinner::new(10, Rgb888::WHITE, FRAME_DURATION)
+}
+
+#[test]
+fn frame_advances_with_elapsed_time() {
+    let spinner = spinner();
+
+    let frames = (0..10)
+        .map(|step| spinner.frame_at(FRAME_DURATION * step))
+        .collect::<Vec<_>>();
+
+    assert_eq!(frames, [0, 1, 2, 3, 4, 5, 6, 7, 0, 1]);
+}
+
+#[test]
+fn frame_is_independent_of_frame_rate() {
+    let started = Instant::now();
+    let shown_at = started + Duration::from_millis(730);
+    let size = Size::new(12, 12);
+
+    // One spinner drawn at 60 frames per second, the other only once
+    let fast = spinner();
+    let mut fast_canvas = BufferCanvas::new(size);
+    for frame in 0..44 {
+        fast.render_at(
+            &mut fast_canvas,
+            started + Duration::from_micros(16_667 * frame),
+        )
+        .unwrap();
+    }
+    fast_canvas.clear(Rgb888::BLACK).unwrap();
+    fast.render_at(&mut fast_canvas, shown_at).unwrap();
+
+    let mut slow_canvas = BufferCanvas::new(size);
+    spinner().render_at(&mut slow_canvas, shown_at).unwrap();
+
+    assert_eq!(fast_canvas.pixels(), slow_canvas.pixels());
+}
+
+#[test]
+fn draws_in_its_color() {
+    let mut canvas = BufferCanvas::new(Size::new(12, 12));
+    let color = Rgb888::new(0, 200, 0);
+
+    Spinner::new(10, color, FRAME_DURATION)
+        .render_at(&mut canvas, Instant::now())
+        .unwrap();
+
+    // The head is drawn at full brightness, the rest of its dots dimmer
+    assert!(canvas.pixels().contains(&color));
+    assert!(canvas
+        .pixels()
+        .iter()
+        .all(|pixel| pixel.r() == 0 && pixel.b() == 0 && pixel.g() <= color.g()));
+}
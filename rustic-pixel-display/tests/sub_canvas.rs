@@ -0,0 +1,170 @@
+//! Tests that a `SubCanvas` draws its pixels where the layout math expects.
+//!
+//! Every test draws into a sub canvas placed at a non-zero offset within a
+//! `BufferCanvas` and checks which pixels of the parent changed.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+    primitives::{PointsIter, Rectangle},
+    Drawable, Pixel,
+};
+use rustic_pixel_display::render::{BufferCanvas, SubCanvas};
+
+const PARENT_SIZE: Size = Size::new(16, 12);
+const OFFSET: Point = Point::new(3, 5);
+const REGION_SIZE: Size = Size::new(6, 4);
+
+/// Returns every pixel of `canvas` that is not black
+fn drawn_points(canvas: &BufferCanvas) -> Vec<Point> {
+    Rectangle::new(Point::zero(), PARENT_SIZE)
+        .points()
+        .filter(|&point| canvas.pixel(point) != Some(Rgb888::BLACK))
+        .collect()
+}
+
+/// Draws a single pixel at `local` within the region and returns where it
+/// ended up on the parent
+fn draw_local_pixel(local: Point) -> Vec<Point> {
+    let mut parent = BufferCanvas::new(PARENT_SIZE);
+
+    let mut sub_canvas = SubCanvas::new(OFFSET, REGION_SIZE, &mut parent);
+    Pixel(local, Rgb888::RED).draw(&mut sub_canvas).unwrap();
+
+    drawn_points(&parent)
+}
+
+#[test]
+fn origin_is_translated_by_offset() {
+    assert_eq!(draw_local_pixel(Point::zero()), vec![OFFSET]);
+}
+
+#[test]
+fn interior_pixel_is_translated_by_offset() {
+    let local = Point::new(2, 1);
+
+    assert_eq!(draw_local_pixel(local), vec![OFFSET + local]);
+}
+
+#[test]
+fn edge_pixels_are_translated_by_offset() {
+    let last_column = REGION_SIZE.width as i32 - 1;
+    let last_row = REGION_SIZE.height as i32 - 1;
+
+    for local in [
+        Point::new(last_column, 0),
+        Point::new(0, last_row),
+        Point::new(last_column, 1),
+        Point::new(1, last_row),
+    ] {
+        assert_eq!(draw_local_pixel(local), vec![OFFSET + local], "{local:?}");
+    }
+}
+
+#[test]
+fn far_corner_is_not_off_by_one() {
+    let far_corner = Point::new(REGION_SIZE.width as i32 - 1, REGION_SIZE.height as i32 - 1);
+
+    // The last pixel of the region, not the first pixel past it
+    assert_eq!(
+        draw_local_pixel(far_corner),
+        vec![Point::new(8, 8)],
+        "Region {OFFSET:?} {REGION_SIZE:?}"
+    );
+}
+
+#[test]
+fn every_local_pixel_maps_to_a_distinct_parent_pixel() {
+    for local in Rectangle::new(Point::zero(), REGION_SIZE).points() {
+        assert_eq!(draw_local_pixel(local), vec![OFFSET + local], "{local:?}");
+    }
+}
+
+#[test]
+fn clear_fills_exactly_the_region() {
+    let mut parent = BufferCanvas::new(PARENT_SIZE);
+
+    SubCanvas::new(OFFSET, REGION_SIZE, &mut parent)
+        .clear(Rgb888::RED)
+        .unwrap();
+
+    let expected: Vec<Point> = Rectangle::new(OFFSET, REGION_SIZE).points().collect();
+    assert_eq!(drawn_points(&parent), expected);
+}
+
+#[test]
+fn fill_past_the_edges_is_clipped_to_the_region() {
+    let mut parent = BufferCanvas::new(PARENT_SIZE);
+
+    // Starts before the region and ends past its far corner
+    SubCanvas::new(OFFSET, REGION_SIZE, &mut parent)
+        .fill_solid(
+            &Rectangle::new(Point::new(-1, -1), REGION_SIZE + Size::new(2, 2)),
+            Rgb888::RED,
+        )
+        .unwrap();
+
+    let expected: Vec<Point> = Rectangle::new(OFFSET, REGION_SIZE).points().collect();
+    assert_eq!(drawn_points(&parent), expected);
+}
+
+#[test]
+fn nested_offsets_add_up() {
+    let mut parent = BufferCanvas::new(PARENT_SIZE);
+    let inner_offset = Point::new(1, 2);
+    let local = Point::new(1, 1);
+
+    let mut sub_canvas = SubCanvas::new(OFFSET, REGION_SIZE, &mut parent);
+    let mut nested = sub_canvas.sub_canvas(inner_offset, Size::new(3, 2));
+    Pixel(local, Rgb888::RED).draw(&mut nested).unwrap();
+
+    assert_eq!(drawn_points(&parent), vec![OFFSET + inner_offset + local]);
+}
+
+#[test]
+fn nested_sub_canvas_of_a_sub_canvas() {
+    let mut parent = BufferCanvas::new(PARENT_SIZE);
+    let inner_offset = Point::new(2, 1);
+    let inner_size = Size::new(3, 2);
+
+    // Built directly on the outer sub canvas rather than through `sub_canvas`
+    let mut outer = SubCanvas::new(OFFSET, REGION_SIZE, &mut parent);
+    let mut inner = SubCanvas::new(inner_offset, inner_size, &mut outer);
+    inner.clear(Rgb888::RED).unwrap();
+
+    let expected: Vec<Point> = Rectangle::new(OFFSET + inner_offset, inner_size)
+        .points()
+        .collect();
+    assert_eq!(drawn_points(&parent), expected);
+}
+
+#[test]
+fn clear_with_a_clip_only_draws_the_clipped_pixels() {
+    let mut parent = BufferCanvas::new(PARENT_SIZE);
+    let clip = Rectangle::new(Point::new(1, 1), Size::new(2, 2));
+
+    SubCanvas::with_clip(OFFSET, REGION_SIZE, clip, &mut parent)
+        .clear(Rgb888::RED)
+        .unwrap();
+
+    let expected: Vec<Point> = Rectangle::new(OFFSET + clip.top_left, clip.size)
+        .points()
+        .collect();
+    assert_eq!(drawn_points(&parent), expected);
+}
+
+#[test]
+fn fill_with_a_clip_past_the_region_stops_at_the_region() {
+    let mut parent = BufferCanvas::new(PARENT_SIZE);
+    // Hangs off the bottom right corner of the region
+    let clip = Rectangle::new(Point::new(4, 2), Size::new(5, 5));
+
+    SubCanvas::with_clip(OFFSET, REGION_SIZE, clip, &mut parent)
+        .fill_solid(&Rectangle::new(Point::zero(), REGION_SIZE), Rgb888::RED)
+        .unwrap();
+
+    let expected: Vec<Point> = Rectangle::new(OFFSET + Point::new(4, 2), Size::new(2, 2))
+        .points()
+        .collect();
+    assert_eq!(drawn_points(&parent), expected);
+}
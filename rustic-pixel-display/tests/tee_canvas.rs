@@ -0,0 +1,71 @@
+//! Tests that a tee canvas draws the same pixels onto both of its canvases.
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_5X7, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Primitive, RgbColor, Size},
+    primitives::{Circle, PrimitiveStyle, Rectangle},
+    text::Text,
+    Drawable,
+};
+use rustic_pixel_display::render::{BufferCanvas, TeeCanvas};
+
+const SIZE: Size = Size::new(32, 16);
+
+fn tee() -> TeeCanvas<BufferCanvas, BufferCanvas> {
+    TeeCanvas::new(BufferCanvas::new(SIZE), BufferCanvas::new(SIZE)).unwrap()
+}
+
+#[test]
+fn both_canvases_receive_identical_pixels() {
+    let mut canvas = tee();
+
+    canvas.clear(Rgb888::BLUE).unwrap();
+    Circle::new(Point::new(2, 2), 10)
+        .into_styled(PrimitiveStyle::with_fill(Rgb888::RED))
+        .draw(&mut canvas)
+        .unwrap();
+    Text::new(
+        "Hi",
+        Point::new(14, 10),
+        MonoTextStyle::new(&FONT_5X7, Rgb888::WHITE),
+    )
+    .draw(&mut canvas)
+    .unwrap();
+    canvas
+        .fill_contiguous(
+            &Rectangle::new(Point::new(28, 0), Size::new(4, 4)),
+            (0..16).map(|i| Rgb888::new(i * 16, 0, 0)),
+        )
+        .unwrap();
+    canvas
+        .fill_solid(
+            &Rectangle::new(Point::new(0, 14), Size::new(32, 2)),
+            Rgb888::GREEN,
+        )
+        .unwrap();
+
+    let (first, second) = canvas.into_inner();
+    assert_eq!(first.pixels(), second.pixels());
+
+    // Every operation made it onto the canvases
+    for (point, color) in [
+        (Point::new(7, 7), Rgb888::RED),
+        (Point::new(31, 3), Rgb888::new(240, 0, 0)),
+        (Point::new(0, 15), Rgb888::GREEN),
+        (Point::new(20, 0), Rgb888::BLUE),
+    ] {
+        assert_eq!(first.pixel(point), Some(color));
+    }
+    assert!(first.pixels().contains(&Rgb888::WHITE));
+}
+
+#[test]
+fn canvases_of_different_sizes_are_rejected() {
+    let tee = TeeCanvas::new(
+        BufferCanvas::new(SIZE),
+        BufferCanvas::new(Size::new(16, 16)),
+    );
+
+    assert!(tee.is_err());
+}
@@ -0,0 +1,37 @@
+//! Tests how a `Theme` is read from and written to JSON, as it is for
+//! `/theme`.
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::RgbColor};
+use rustic_pixel_display::theme::Theme;
+
+#[test]
+fn background_is_parsed_as_hex() {
+    let theme: Theme = serde_json::from_str(r##"{ "background": "#10a0FF" }"##).unwrap();
+
+    assert_eq!(theme.background, Rgb888::new(0x10, 0xA0, 0xFF));
+    assert_eq!(
+        serde_json::to_string(&theme).unwrap(),
+        r##"{"background":"#10A0FF"}"##
+    );
+}
+
+#[test]
+fn left_out_background_keeps_its_default() {
+    let theme: Theme = serde_json::from_str("{}").unwrap();
+
+    assert_eq!(theme, Theme::default());
+    assert_eq!(theme.background, Rgb888::BLACK);
+}
+
+#[test]
+fn unknown_colors_are_rejected() {
+    assert!(serde_json::from_str::<Theme>(r##"{ "foreground": "#FFFFFF" }"##).is_err());
+}
+
+#[test]
+fn malformed_colors_are_rejected() {
+    for color in ["10A0FF", "#10A0F", "#10A0FFF", "#GGGGGG"] {
+        let json = format!(r#"{{ "background": "{color}" }}"#);
+        assert!(serde_json::from_str::<Theme>(&json).is_err(), "{color}");
+    }
+}
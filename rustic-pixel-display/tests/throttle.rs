@@ -0,0 +1,105 @@
+//! Tests that a throttled render only draws its inner render once per
+//! interval, copying its last frame onto the canvas in between.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+};
+use rustic_pixel_display::render::{BufferCanvas, Render, Throttle};
+use std::{
+    convert::Infallible,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+const INTERVAL: Duration = Duration::from_millis(100);
+
+/// Fills the canvas with a brighter shade every time it is drawn, counting how
+/// often it is drawn
+#[derive(Default)]
+struct Counter {
+    draws: AtomicUsize,
+}
+
+impl Counter {
+    fn draws(&self) -> usize {
+        self.draws.load(Ordering::SeqCst)
+    }
+}
+
+impl<D> Render<D> for Counter
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let draws = self.draws.fetch_add(1, Ordering::SeqCst) + 1;
+        canvas.clear(Rgb888::new(draws as u8, 0, 0))
+    }
+}
+
+/// Draws `render` at `now` onto `canvas`, returning the color it was drawn in
+fn draw_at(render: &Throttle<Counter>, canvas: &mut BufferCanvas, now: Instant) -> Rgb888 {
+    canvas.clear(Rgb888::BLACK).unwrap();
+    render.render_at(canvas, now).unwrap();
+
+    canvas.pixel(Point::zero()).unwrap()
+}
+
+#[test]
+fn inner_render_is_drawn_once_per_interval() {
+    let render = Throttle::new(Counter::default(), INTERVAL);
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+    let started = Instant::now();
+
+    // A second of frames at 50 frames per second
+    for frame in 0..50 {
+        draw_at(
+            &render,
+            &mut canvas,
+            started + Duration::from_millis(20) * frame,
+        );
+    }
+
+    assert_eq!(render.inner().draws(), 10);
+}
+
+#[test]
+fn cached_frame_is_drawn_in_between() {
+    let render = Throttle::new(Counter::default(), INTERVAL);
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+    let started = Instant::now();
+
+    assert_eq!(draw_at(&render, &mut canvas, started), Rgb888::new(1, 0, 0));
+    assert_eq!(
+        draw_at(&render, &mut canvas, started + INTERVAL / 2),
+        Rgb888::new(1, 0, 0)
+    );
+    assert_eq!(
+        draw_at(&render, &mut canvas, started + INTERVAL),
+        Rgb888::new(2, 0, 0)
+    );
+}
+
+#[test]
+fn resized_canvas_is_drawn_right_away() {
+    let render = Throttle::new(Counter::default(), INTERVAL);
+    let started = Instant::now();
+
+    draw_at(&render, &mut BufferCanvas::new(Size::new(4, 4)), started);
+    draw_at(&render, &mut BufferCanvas::new(Size::new(8, 4)), started);
+
+    assert_eq!(render.inner().draws(), 2);
+}
+
+#[test]
+fn reset_is_drawn_right_away() {
+    let render = Throttle::new(Counter::default(), INTERVAL);
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+    let started = Instant::now();
+
+    draw_at(&render, &mut canvas, started);
+    Render::<BufferCanvas>::reset(&render);
+    draw_at(&render, &mut canvas, started);
+
+    assert_eq!(render.inner().draws(), 2);
+}
@@ -0,0 +1,244 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    geometry::{Dimensions, OriginDimensions},
+    image::ImageDrawable,
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+    primitives::{PointsIter, Rectangle},
+    transform::Transform,
+    Drawable, Pixel,
+};
+use image::{imageops::FilterType, RgbImage, RgbaImage};
+use log::error;
+use tinybmp::Bmp;
+
+/// Decodes a bundled BMP asset, naming the asset in the error if it is corrupt
+/// or in an unsupported format.
+pub fn load_bmp(name: &str, bytes: &'static [u8]) -> Result<Bmp<'static, Rgb888>> {
+    Bmp::<Rgb888>::from_slice(bytes).map_err(|e| {
+        anyhow!(
+            "Could not decode asset \"{name}\" ({} bytes), expected an uncompressed 16, 24 or 32 \
+             bit BMP: {e:?}",
+            bytes.len()
+        )
+    })
+}
+
+/// Same as [`load_bmp`] but logs the error instead, so renders can skip drawing
+/// the asset rather than panic.
+pub fn load_bmp_or_log(name: &str, bytes: &'static [u8]) -> Option<Bmp<'static, Rgb888>> {
+    load_bmp(name, bytes).map_err(|e| error!("{e}")).ok()
+}
+
+/// The encodings [`load_image`] can decode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Bmp,
+    Png,
+}
+
+impl From<ImageFormat> for image::ImageFormat {
+    fn from(value: ImageFormat) -> Self {
+        match value {
+            ImageFormat::Bmp => image::ImageFormat::Bmp,
+            ImageFormat::Png => image::ImageFormat::Png,
+        }
+    }
+}
+
+/// Decodes an image asset into an RGBA buffer. Images without an alpha channel
+/// are fully opaque.
+pub fn load_image(bytes: &[u8], format: ImageFormat) -> Result<RgbaImage> {
+    Ok(image::load_from_memory_with_format(bytes, format.into())
+        .map_err(|e| {
+            anyhow!(
+                "Could not decode {format:?} image ({} bytes): {e}",
+                bytes.len()
+            )
+        })?
+        .into_rgba8())
+}
+
+/// Same as [`load_image`] but logs the error instead, so renders can skip
+/// drawing the asset rather than panic.
+pub fn load_image_or_log(name: &str, bytes: &[u8], format: ImageFormat) -> Option<RgbaImage> {
+    load_image(bytes, format)
+        .map_err(|e| error!("Could not load asset \"{name}\": {e}"))
+        .ok()
+}
+
+/// Downscales `image` to fit within `size`, keeping its aspect ratio. Images
+/// that already fit are returned as they are.
+pub fn fit_image(image: &RgbImage, size: Size) -> RgbImage {
+    if image.width() <= size.width && image.height() <= size.height {
+        return image.clone();
+    }
+
+    let scale = f32::min(
+        size.width as f32 / image.width() as f32,
+        size.height as f32 / image.height() as f32,
+    );
+
+    image::imageops::resize(
+        image,
+        ((image.width() as f32 * scale) as u32).max(1),
+        ((image.height() as f32 * scale) as u32).max(1),
+        FilterType::Triangle,
+    )
+}
+
+/// An RGBA image that can be drawn and arranged in a layout like an
+/// `Image<Bmp>`.
+///
+/// Fully transparent pixels are skipped so whatever is already on the canvas
+/// shows through. Partially transparent pixels are blended over `background`,
+/// black by default since that is what an unlit pixel looks like.
+#[derive(Debug, Clone, Copy)]
+pub struct AlphaImage<'a> {
+    image: &'a RgbaImage,
+    position: Point,
+    background: Rgb888,
+}
+
+impl<'a> AlphaImage<'a> {
+    pub fn new(image: &'a RgbaImage, position: Point) -> Self {
+        Self {
+            image,
+            position,
+            background: Rgb888::new(0, 0, 0),
+        }
+    }
+
+    /// Sets the color partially transparent pixels are blended over
+    pub fn with_background(mut self, background: Rgb888) -> Self {
+        self.background = background;
+        self
+    }
+}
+
+/// Blends `foreground` over `background` with `alpha` out of 255.
+fn blend(foreground: u8, background: u8, alpha: u8) -> u8 {
+    let alpha = alpha as u32;
+    ((foreground as u32 * alpha + background as u32 * (255 - alpha)) / 255) as u8
+}
+
+impl Drawable for AlphaImage<'_> {
+    type Color = Rgb888;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<Self::Output, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let background = self.background;
+
+        target.draw_iter(
+            self.image
+                .enumerate_pixels()
+                .filter(|(_, _, pixel)| pixel[3] != 0)
+                .map(|(x, y, pixel)| {
+                    let [r, g, b, a] = pixel.0;
+                    let color = Rgb888::new(
+                        blend(r, background.r(), a),
+                        blend(g, background.g(), a),
+                        blend(b, background.b(), a),
+                    );
+
+                    Pixel(self.position + Point::new(x as i32, y as i32), color)
+                }),
+        )
+    }
+}
+
+impl Dimensions for AlphaImage<'_> {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(
+            self.position,
+            Size::new(self.image.width(), self.image.height()),
+        )
+    }
+}
+
+impl Transform for AlphaImage<'_> {
+    fn translate(&self, by: Point) -> Self {
+        Self {
+            position: self.position + by,
+            ..*self
+        }
+    }
+
+    fn translate_mut(&mut self, by: Point) -> &mut Self {
+        self.position += by;
+        self
+    }
+}
+
+/// Wraps a decoded RGB image so it can be placed with
+/// `embedded_graphics::image::Image`, the same way as a `Bmp`.
+#[derive(Debug, Clone)]
+pub struct RgbImageDrawable {
+    image: RgbImage,
+}
+
+impl RgbImageDrawable {
+    pub fn new(image: RgbImage) -> Self {
+        Self { image }
+    }
+
+    /// Decodes `bytes` with [`load_image`], dropping the alpha channel.
+    pub fn from_bytes(bytes: &[u8], format: ImageFormat) -> Result<Self> {
+        Ok(Self::new(
+            image::DynamicImage::ImageRgba8(load_image(bytes, format)?).into_rgb8(),
+        ))
+    }
+
+    pub fn into_inner(self) -> RgbImage {
+        self.image
+    }
+
+    fn color_at(&self, point: Point) -> Rgb888 {
+        let [r, g, b] = self.image.get_pixel(point.x as u32, point.y as u32).0;
+        Rgb888::new(r, g, b)
+    }
+}
+
+impl From<RgbImage> for RgbImageDrawable {
+    fn from(value: RgbImage) -> Self {
+        Self::new(value)
+    }
+}
+
+impl OriginDimensions for RgbImageDrawable {
+    fn size(&self) -> Size {
+        Size::new(self.image.width(), self.image.height())
+    }
+}
+
+impl ImageDrawable for RgbImageDrawable {
+    type Color = Rgb888;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        target.fill_contiguous(
+            &self.bounding_box(),
+            self.image
+                .pixels()
+                .map(|pixel| Rgb888::new(pixel[0], pixel[1], pixel[2])),
+        )
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        // Parts of the area outside of the image are left untouched
+        let visible = area.intersection(&self.bounding_box());
+
+        target.fill_contiguous(
+            &Rectangle::new(visible.top_left - area.top_left, visible.size),
+            visible.points().map(|point| self.color_at(point)),
+        )
+    }
+}
@@ -1,41 +1,512 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use log::warn;
 use rustic_pixel_display::{
-    config::{HardwareConfig, HardwareMapping, LedSequence, RowAddressSetterType},
-    driver::{self, RustHardwareDriver},
+    config::{DriverBackend, HardwareConfig, HardwareMapping, LedSequence, RowAddressSetterType},
+    driver::{MatrixDriver, RustHardwareDriver},
 };
 
-use rustic_pixel_examples::renders::upcoming_arrivals::{UpcomingArrivals, UpcomingArrivalsConfig};
-use septa_api::types::RegionalRailStop;
+use rustic_pixel_examples::config_paths::{app_config_paths, init_config_path};
+use rustic_pixel_examples::renders::{
+    crypto::{Crypto, CryptoConfig},
+    septa_alerts::{SeptaAlerts, SeptaAlertsConfig},
+    upcoming_arrivals::{UpcomingArrivals, UpcomingArrivalsConfig},
+    weather::{Configuration, Weather},
+    wifi::{Wifi, WifiConfig},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io::{self, ErrorKind, IsTerminal, Write},
+    path::PathBuf,
+    str::FromStr,
+};
+
+/// The startup configuration used when there is none at any of the searched
+/// paths
+const DEFAULT_APP_CONFIG: &str = r#"{
+  "render": "UpcomingArrivals",
+  "config": { "septa_station": "SuburbanStation", "results": 20 }
+}"#;
+
+/// The renders `--init-config` can start with, along with the configuration
+/// they are written with
+const INIT_RENDERS: [(&str, &str); 4] = [
+    (
+        "UpcomingArrivals",
+        r#"{ "septa_station": "SuburbanStation", "results": 20 }"#,
+    ),
+    (
+        "Crypto",
+        r#"{ "coins": ["bitcoin", "ethereum"], "vs_currency": "usd" }"#,
+    ),
+    ("SeptaAlerts", r#"{ "lines": ["Paoli/Thorndale"] }"#),
+    ("Wifi", r#"{ "interface": "wlan0" }"#),
+];
+
+/// The panel `--init-config` suggests, a single 64x64 panel on an Adafruit HAT
+const INIT_PANEL: PanelConfig = PanelConfig {
+    hardware_mapping: HardwareMapping::AdafruitHat,
+    rows: 64,
+    cols: 64,
+    chain_length: 1,
+    parallel: 1,
+};
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path to the app configuration, searched for in the working directory,
+    /// the user's and the system wide configuration directories if not given
+    config: Option<PathBuf>,
+
+    /// Print the hardware and app configuration that would be used, then exit
+    /// without starting the driver
+    #[arg(long)]
+    print_config: bool,
+
+    /// Write a new app configuration to the configuration path, then exit.
+    /// Anything not given by the flags below is asked for when run from a
+    /// terminal, and defaults to a 64x64 panel on an Adafruit HAT otherwise
+    #[arg(long, conflicts_with = "print_config")]
+    init_config: bool,
+
+    /// Overwrite an existing app configuration with --init-config
+    #[arg(long, requires = "init_config")]
+    force: bool,
+
+    /// Hardware mapping written by --init-config, i.e. AdafruitHat or Regular
+    #[arg(long, requires = "init_config")]
+    hardware_mapping: Option<String>,
+
+    /// Rows of a single panel written by --init-config
+    #[arg(long, requires = "init_config")]
+    rows: Option<usize>,
+
+    /// Columns of a single panel written by --init-config
+    #[arg(long, requires = "init_config")]
+    cols: Option<usize>,
+
+    /// Number of daisy chained panels written by --init-config
+    #[arg(long, requires = "init_config")]
+    chain_length: Option<usize>,
+
+    /// Number of parallel chains written by --init-config
+    #[arg(long, requires = "init_config")]
+    parallel: Option<usize>,
+
+    /// Render displayed on startup written by --init-config
+    #[arg(long, requires = "init_config")]
+    render: Option<String>,
+}
+
+/// The panel the app is displayed on, overriding the hardware defaults, for
+/// example:
+///
+/// ```json
+/// {
+///   "render": "Wifi",
+///   "config": { "interface": "wlan0" },
+///   "panel": { "hardware_mapping": "AdafruitHat", "rows": 64, "cols": 64 }
+/// }
+/// ```
+#[derive(Deserialize, Serialize)]
+struct PanelConfig {
+    hardware_mapping: HardwareMapping,
+    rows: usize,
+    cols: usize,
+    #[serde(default = "default_panel_count")]
+    chain_length: usize,
+    #[serde(default = "default_panel_count")]
+    parallel: usize,
+}
+
+fn default_panel_count() -> usize {
+    1
+}
+
+impl PanelConfig {
+    fn apply(&self, hardware_config: &mut HardwareConfig) {
+        hardware_config.hardware_mapping = self.hardware_mapping.clone();
+        hardware_config.rows = self.rows;
+        hardware_config.cols = self.cols;
+        hardware_config.chain_length = self.chain_length;
+        hardware_config.parallel = self.parallel;
+    }
+}
+
+/// The render displayed on startup along with its configuration, for example:
+///
+/// ```json
+/// {
+///   "render": "UpcomingArrivals",
+///   "config": { "septa_station": "SuburbanStation", "amtrak_station": null, "results": 20 }
+/// }
+/// ```
+#[derive(Deserialize)]
+#[serde(tag = "render", content = "config")]
+enum StartupRender {
+    Crypto(CryptoConfig),
+    SeptaAlerts(SeptaAlertsConfig),
+    UpcomingArrivals(UpcomingArrivalsConfig),
+    Weather(Configuration),
+    Wifi(WifiConfig),
+}
+
+impl StartupRender {
+    fn validate(&self) -> Result<()> {
+        match self {
+            StartupRender::Weather(config) => config.validate(),
+            StartupRender::Crypto(_)
+            | StartupRender::SeptaAlerts(_)
+            | StartupRender::UpcomingArrivals(_)
+            | StartupRender::Wifi(_) => Ok(()),
+        }
+    }
+}
+
+/// The startup configuration along with where it was read from
+struct AppConfig {
+    /// `None` if the default configuration is used
+    path: Option<PathBuf>,
+
+    /// The configuration as it was read
+    raw: Value,
+
+    startup_render: StartupRender,
+
+    /// `None` if the hardware defaults are used
+    panel: Option<PanelConfig>,
+}
+
+impl AppConfig {
+    /// Parses and validates `raw`, naming `path` in any error.
+    fn parse(raw: Value, path: Option<PathBuf>) -> Result<Self> {
+        let source = match &path {
+            Some(path) => path.display().to_string(),
+            None => "default".to_owned(),
+        };
+
+        let startup_render = serde_json::from_value::<StartupRender>(raw.clone())
+            .map_err(|e| anyhow!("Could not parse the app configuration {source}: {e}"))?;
+
+        startup_render
+            .validate()
+            .map_err(|e| anyhow!("Invalid app configuration {source}: {e}"))?;
+
+        let panel = raw
+            .get("panel")
+            .cloned()
+            .map(serde_json::from_value::<PanelConfig>)
+            .transpose()
+            .map_err(|e| {
+                anyhow!("Could not parse the panel of the app configuration {source}: {e}")
+            })?;
+
+        Ok(Self {
+            path,
+            raw,
+            startup_render,
+            panel,
+        })
+    }
+}
+
+/// Reads the startup configuration from the first of `paths` that exists,
+/// falling back to [`DEFAULT_APP_CONFIG`] if there is no configuration at any
+/// of them.
+fn load_app_config(paths: &[PathBuf]) -> Result<AppConfig> {
+    for path in paths {
+        match File::open(path) {
+            Ok(file) => {
+                let raw = serde_json::from_reader(file).map_err(|e| {
+                    anyhow!(
+                        "Could not parse the app configuration {}: {e}",
+                        path.display()
+                    )
+                })?;
+
+                return AppConfig::parse(raw, Some(path.clone()));
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(anyhow!(
+                    "Could not open the app configuration {}: {e}",
+                    path.display()
+                ))
+            }
+        }
+    }
+
+    let tried = paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    warn!("No app configuration found at any of {tried}, using the default render");
+
+    AppConfig::parse(serde_json::from_str(DEFAULT_APP_CONFIG)?, None)
+}
+
+/// The hardware configuration used unless the app configuration has a panel
+fn default_hardware_config() -> HardwareConfig {
+    HardwareConfig {
+        hardware_mapping: HardwareMapping::Regular,
+        rows: 64,
+        cols: 128,
+        refresh_rate: 120,
+        pi_chip: None,
+        pwm_bits: 4,
+        pwm_lsb_nanoseconds: 130,
+        slowdown: Some(2),
+        interlaced: false,
+        dither_bits: 0,
+        chain_length: 2,
+        parallel: 1,
+        panel_type: None,
+        multiplexing: None,
+        row_setter: RowAddressSetterType::Direct,
+        led_sequence: LedSequence::Bgr,
+        spi_bus: None,
+        spi_speed_hz: None,
+        file_output_dir: None,
+        file_write_interval_ms: None,
+        tcp_address: None,
+        max_fps: None,
+        driver_backend: DriverBackend::Rust,
+        pixel_mapper: None,
+        software_dither: None,
+        simulator_scale: None,
+        panels: Vec::new(),
+    }
+}
+
+/// Loads and validates the hardware configuration and the app configuration,
+/// read from the first of `app_config_paths` that exists.
+fn load_config(app_config_paths: &[PathBuf]) -> Result<(HardwareConfig, AppConfig)> {
+    let app_config = load_app_config(app_config_paths)?;
+
+    let mut hardware_config = default_hardware_config();
+    if let Some(panel) = &app_config.panel {
+        panel.apply(&mut hardware_config);
+    }
+
+    hardware_config
+        .validate()
+        .map_err(|e| anyhow!("Invalid hardware configuration: {e}"))?;
+
+    Ok((hardware_config, app_config))
+}
+
+/// Returns the value given as a flag, otherwise asks for it on the terminal
+/// until a valid one is entered, using `default` when nothing is entered.
+/// Without a terminal `default` is used as is.
+fn init_value<T, F>(flag: Option<&str>, question: &str, default: &str, parse: F) -> Result<T>
+where
+    F: Fn(&str) -> Result<T>,
+{
+    if let Some(value) = flag {
+        return parse(value).map_err(|e| anyhow!("Invalid {question}: {e}"));
+    }
+
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        return parse(default);
+    }
+
+    loop {
+        print!("{question} [{default}]: ");
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        let value = match stdin.read_line(&mut line)? {
+            // Treat the end of input like an empty answer
+            0 => default,
+            _ if line.trim().is_empty() => default,
+            _ => line.trim(),
+        };
+
+        match parse(value) {
+            Ok(value) => return Ok(value),
+            Err(e) => println!("Invalid {question}: {e}"),
+        }
+    }
+}
+
+/// Parses a number of panels or pixels, which has to be at least 1
+fn parse_count<T>(value: &str) -> Result<T>
+where
+    T: FromStr + PartialOrd + From<u8>,
+    T::Err: Display,
+{
+    let count = value.parse::<T>().map_err(|e| anyhow!("{e}"))?;
+
+    if count < T::from(1) {
+        return Err(anyhow!("must be at least 1"));
+    }
+
+    Ok(count)
+}
+
+/// Writes a new app configuration for `args`, see [`Args::init_config`].
+fn init_config(args: &Args) -> Result<()> {
+    let path = args.config.clone().unwrap_or_else(init_config_path);
+
+    if path.exists() && !args.force {
+        return Err(anyhow!(
+            "{} already exists, pass --force to overwrite it",
+            path.display()
+        ));
+    }
+
+    let render_names = INIT_RENDERS.map(|(name, _)| name).join(", ");
+    let render_config = init_value(
+        args.render.as_deref(),
+        &format!("render ({render_names})"),
+        INIT_RENDERS[0].0,
+        |value| {
+            INIT_RENDERS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(value))
+                .copied()
+                .ok_or_else(|| anyhow!("\"{value}\" is not one of {render_names}"))
+        },
+    )?;
+
+    let panel = PanelConfig {
+        hardware_mapping: init_value(
+            args.hardware_mapping.as_deref(),
+            "hardware mapping",
+            INIT_PANEL.hardware_mapping.as_ref(),
+            |value| HardwareMapping::from_str(value).map_err(|e| anyhow!("{e}")),
+        )?,
+        rows: init_value(
+            args.rows.map(|rows| rows.to_string()).as_deref(),
+            "panel rows",
+            &INIT_PANEL.rows.to_string(),
+            parse_count,
+        )?,
+        cols: init_value(
+            args.cols.map(|cols| cols.to_string()).as_deref(),
+            "panel columns",
+            &INIT_PANEL.cols.to_string(),
+            parse_count,
+        )?,
+        chain_length: init_value(
+            args.chain_length.map(|count| count.to_string()).as_deref(),
+            "chain length",
+            &INIT_PANEL.chain_length.to_string(),
+            parse_count,
+        )?,
+        parallel: init_value(
+            args.parallel.map(|count| count.to_string()).as_deref(),
+            "parallel chains",
+            &INIT_PANEL.parallel.to_string(),
+            parse_count,
+        )?,
+    };
+
+    let (render, config) = render_config;
+    let raw = json!({
+        "render": render,
+        "config": serde_json::from_str::<Value>(config)?,
+        "panel": panel,
+    });
+
+    // Checked the same way as when it is loaded, so the daemon can start with it
+    let app_config = AppConfig::parse(raw.clone(), Some(path.clone()))?;
+    let mut hardware_config = default_hardware_config();
+    if let Some(panel) = &app_config.panel {
+        panel.apply(&mut hardware_config);
+    }
+    hardware_config
+        .validate()
+        .map_err(|e| anyhow!("Invalid hardware configuration: {e}"))?;
+
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)
+            .map_err(|e| anyhow!("Could not create {}: {e}", parent.display()))?;
+    }
+
+    let mut file = if args.force {
+        File::create(&path)
+    } else {
+        // Fails if the file was created since it was checked above
+        OpenOptions::new().write(true).create_new(true).open(&path)
+    }
+    .map_err(|e| anyhow!("Could not write {}: {e}", path.display()))?;
+
+    writeln!(file, "{}", serde_json::to_string_pretty(&raw)?)?;
+    println!("Wrote the app configuration to {}", path.display());
+
+    Ok(())
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
 
-    let _led_driver = driver::MatrixDriver::with_single_render::<RustHardwareDriver, _>(
-        UpcomingArrivals::new(UpcomingArrivalsConfig {
-            septa_station: Some(RegionalRailStop::SuburbanStation),
-            amtrak_station: None,
-            results: Some(20),
-        })?,
-        HardwareConfig {
-            hardware_mapping: HardwareMapping::Regular,
-            rows: 64,
-            cols: 128,
-            refresh_rate: 120,
-            pi_chip: None,
-            pwm_bits: 4,
-            pwm_lsb_nanoseconds: 130,
-            slowdown: Some(2),
-            interlaced: false,
-            dither_bits: 0,
-            chain_length: 2,
-            parallel: 1,
-            panel_type: None,
-            multiplexing: None,
-            row_setter: RowAddressSetterType::Direct,
-            led_sequence: LedSequence::Bgr,
-        },
-    )?;
+    let args = Args::parse();
+
+    if args.init_config {
+        return init_config(&args);
+    }
+
+    let app_config_paths = match args.config {
+        Some(path) => vec![path],
+        None => app_config_paths(),
+    };
+    let (hardware_config, app_config) = load_config(&app_config_paths)?;
+
+    if args.print_config {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "app_config_path": app_config.path,
+                "hardware": hardware_config,
+                "app": app_config.raw,
+            }))?
+        );
+
+        return Ok(());
+    }
+
+    type DriverType = RustHardwareDriver;
+
+    let _led_driver = match app_config.startup_render {
+        StartupRender::Crypto(config) => MatrixDriver::with_single_render::<DriverType, _>(
+            Crypto::new(config),
+            None,
+            hardware_config,
+        ),
+        StartupRender::SeptaAlerts(config) => MatrixDriver::with_single_render::<DriverType, _>(
+            SeptaAlerts::new(config),
+            None,
+            hardware_config,
+        ),
+        StartupRender::UpcomingArrivals(config) => {
+            MatrixDriver::with_single_render::<DriverType, _>(
+                UpcomingArrivals::new(config)?,
+                None,
+                hardware_config,
+            )
+        }
+        StartupRender::Weather(config) => MatrixDriver::with_single_render::<DriverType, _>(
+            Weather::new(config),
+            None,
+            hardware_config,
+        ),
+        StartupRender::Wifi(config) => MatrixDriver::with_single_render::<DriverType, _>(
+            Wifi::new(config),
+            None,
+            hardware_config,
+        ),
+    }?;
 
     tokio::select! {
         _ = tokio::signal::ctrl_c() => {
@@ -0,0 +1,106 @@
+//! Display agent that receives frames from a `TcpHardwareDriver` running on
+//! another machine and displays them on the panel connected to this one.
+
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{Dimensions, DrawTarget},
+};
+use log::{error, info};
+use rustic_pixel_display::{
+    config::{DriverBackend, HardwareConfig, HardwareMapping, LedSequence, RowAddressSetterType},
+    driver::{
+        tcp_driver::{accept_handshake, read_frame},
+        HardwareDriver, RustHardwareDriver,
+    },
+};
+use std::{env, net::TcpListener};
+
+/// Where the agent listens for a driver, unless an address is given as the
+/// first argument
+const DEFAULT_LISTEN_ADDRESS: &str = "0.0.0.0:7777";
+
+fn main() -> Result<()> {
+    env_logger::init();
+
+    let listen_address = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_LISTEN_ADDRESS.to_owned());
+
+    let hardware_config = HardwareConfig {
+        hardware_mapping: HardwareMapping::Regular,
+        rows: 64,
+        cols: 128,
+        refresh_rate: 120,
+        pi_chip: None,
+        pwm_bits: 4,
+        pwm_lsb_nanoseconds: 130,
+        slowdown: Some(2),
+        interlaced: false,
+        dither_bits: 0,
+        chain_length: 2,
+        parallel: 1,
+        panel_type: None,
+        multiplexing: None,
+        row_setter: RowAddressSetterType::Direct,
+        led_sequence: LedSequence::Bgr,
+        spi_bus: None,
+        spi_speed_hz: None,
+        file_output_dir: None,
+        file_write_interval_ms: None,
+        tcp_address: None,
+        max_fps: None,
+        driver_backend: DriverBackend::Rust,
+        pixel_mapper: None,
+        software_dither: None,
+        simulator_scale: None,
+        panels: Vec::new(),
+    };
+
+    let mut hardware_driver = RustHardwareDriver::new(
+        hardware_config
+            .try_into()
+            .map_err(|_e| anyhow!("Can't convert to RGBMatrixConfig"))?,
+    )?;
+    let mut canvas = hardware_driver.create_canvas();
+    let panel_bounds = canvas.bounding_box();
+    let mut frame = Vec::new();
+
+    let listener = TcpListener::bind(&listen_address)?;
+    info!("Waiting for a driver on {listen_address}");
+
+    // Only one driver is served at a time, the next one is accepted once the
+    // current one disconnects
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                error!("Could not accept driver connection: {e}");
+                continue;
+            }
+        };
+
+        info!("Driver connected from {:?}", stream.peer_addr());
+
+        if let Err(e) = accept_handshake(&mut stream, panel_bounds.size) {
+            error!("Driver handshake failed: {e}");
+            continue;
+        }
+
+        loop {
+            if let Err(e) = read_frame(&mut stream, panel_bounds.size, &mut frame) {
+                error!("Driver disconnected: {e}");
+                break;
+            }
+
+            let pixels = frame
+                .chunks_exact(3)
+                .map(|pixel| Rgb888::new(pixel[0], pixel[1], pixel[2]));
+
+            canvas.fill_contiguous(&panel_bounds, pixels)?;
+            canvas = hardware_driver.display_canvas(canvas);
+        }
+    }
+
+    Ok(())
+}
@@ -1,44 +1,141 @@
 use anyhow::Result;
+use clap::Parser;
 use embedded_graphics::{pixelcolor::Rgb888, prelude::DrawTarget};
 use parking_lot::Mutex;
 use rustic_pixel_display::{
-    config::{HardwareConfig, HardwareMapping, LedSequence, RowAddressSetterType},
-    driver::{self, HardwareDriver, RustHardwareDriver},
+    config::{DriverBackend, HardwareConfig, HardwareMapping, LedSequence, RowAddressSetterType},
+    driver::{self, BackendHardwareDriver, HardwareDriver},
+};
+use rustic_pixel_display::{
+    fallback::FallbackFactory,
+    layout_manager::LayoutFactory,
+    quiet_hours::QuietHoursFactory,
+    registry::Registry,
+    render::{BufferCanvas, FactoryInfo, Render, RenderFactories},
+    schedule::ScheduleFactory,
+    scrolling_feed::ScrollingFeedFactory,
 };
-use rustic_pixel_display::{registry::Registry, render::Render};
 use rustic_pixel_display_macros::RenderFactories;
 use rustic_pixel_examples::renders::{
-    person_tracker::TransitTrackerFactory, upcoming_arrivals::UpcomingArrivalsFactory,
+    bus_arrivals::BusArrivalsFactory,
+    ci_status::CiStatusFactory,
+    commute::CommuteFactory,
+    crypto::CryptoFactory,
+    date_tile::DateTileFactory,
+    flip_board::FlipBoardFactory,
+    gtfs_rt::GtfsRealtimeFactory,
+    ha_sensors::HaSensorsFactory,
+    iss::IssPassFactory,
+    label::LabelFactory,
+    log_tail::{self, LogTailFactory},
+    now_playing::NowPlayingFactory,
+    person_tracker::TransitTrackerFactory,
+    ping::PingMonitorFactory,
+    radar::RadarFactory,
+    septa_alerts::SeptaAlertsFactory,
+    sparkline::SparklineFactory,
+    test_pattern::TestPatternFactory,
+    train_map::TrainMapFactory,
+    upcoming_arrivals::UpcomingArrivalsFactory,
     weather::WeatherFactory,
+    webhook::WebhookFactory,
+    wifi::WifiFactory,
 };
+use rustic_pixel_examples::stations;
 use std::{convert::Infallible, sync::Arc, vec};
 
 #[derive(RenderFactories)]
-enum RenderFactoryEntries<D: DrawTarget<Color = Rgb888, Error = Infallible>> {
+enum RenderFactoryEntries<D: DrawTarget<Color = Rgb888, Error = Infallible> + 'static> {
+    Layout(LayoutFactory<D, RenderFactoryEntries<BufferCanvas>>),
+    Schedule(ScheduleFactory<D, RenderFactoryEntries<D>>),
+    QuietHours(QuietHoursFactory<D, RenderFactoryEntries<D>>),
+    Fallback(FallbackFactory<D, RenderFactoryEntries<D>>),
+    ScrollingFeed(ScrollingFeedFactory<D, RenderFactoryEntries<BufferCanvas>>),
+    BusArrivals(BusArrivalsFactory<D>),
+    CiStatus(CiStatusFactory<D>),
+    Commute(CommuteFactory<D>),
+    Crypto(CryptoFactory<D>),
+    DateTile(DateTileFactory<D>),
+    FlipBoard(FlipBoardFactory<D>),
+    GtfsRealtime(GtfsRealtimeFactory<D>),
+    HaSensors(HaSensorsFactory<D>),
+    IssPass(IssPassFactory<D>),
+    Label(LabelFactory<D>),
+    LogTail(LogTailFactory<D>),
+    NowPlaying(NowPlayingFactory<D>),
     TransitTracker(TransitTrackerFactory<D>),
+    PingMonitor(PingMonitorFactory<D>),
+    Radar(RadarFactory<D>),
+    SeptaAlerts(SeptaAlertsFactory<D>),
+    Sparkline(SparklineFactory<D>),
+    TestPattern(TestPatternFactory<D>),
+    TrainMap(TrainMapFactory<D>),
     UpcomingArrivals(UpcomingArrivalsFactory<D>),
     Weather(WeatherFactory<D>),
+    Webhook(WebhookFactory<D>),
+    Wifi(WifiFactory<D>),
+}
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Print the name, description and an example configuration of every
+    /// compiled in render factory, then exit
+    #[arg(long)]
+    list_renders: bool,
+
+    /// Print the factory list as JSON instead of text
+    #[arg(long, requires = "list_renders")]
+    json: bool,
+}
+
+/// Prints every render factory the binary was compiled with
+fn list_renders(json: bool) -> Result<()> {
+    let factories = RenderFactoryEntries::<BufferCanvas>::factories()
+        .iter()
+        .map(FactoryInfo::new::<BufferCanvas, _>)
+        .collect::<Vec<_>>();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&factories)?);
+    } else {
+        for factory in &factories {
+            println!("{factory}\n");
+        }
+    }
+
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    env_logger::init();
+    // Keep the recent log records around so they can be displayed on the panel
+    log_tail::init_logger(env_logger::Builder::from_default_env().build())?;
 
-    // Use the Rust Driver
-    type DriverType = RustHardwareDriver;
-    type CanvasType = <RustHardwareDriver as HardwareDriver>::Canvas;
+    let args = Args::parse();
+    if args.list_renders {
+        return list_renders(args.json);
+    }
+
+    // Use the driver backend selected in the HardwareConfig
+    type DriverType = BackendHardwareDriver;
+    type CanvasType = <BackendHardwareDriver as HardwareDriver>::Canvas;
 
     // Create the factory registry. This will house all the registered
     // RenderFactories that can be used to construct renders.
     let factory_registry = {
         let factory_registry: Registry<RenderFactoryEntries<CanvasType>, _> =
-            Registry::new(RenderFactoryEntries::factories());
+            Registry::new(RenderFactoryEntries::factories())?;
         Arc::new(Mutex::new(factory_registry))
     };
 
+    // Lets a station picker look up the stations to configure renders with
+    let _station_search = stations::register_station_search()?;
+
     let _led_driver = driver::MatrixDriver::with_register::<DriverType, _, _>(
         "0.0.0.0:8080",
         factory_registry,
+        None,
         HardwareConfig {
             hardware_mapping: HardwareMapping::Regular,
             rows: 64,
@@ -56,6 +153,17 @@ async fn main() -> Result<()> {
             multiplexing: None,
             row_setter: RowAddressSetterType::Direct,
             led_sequence: LedSequence::Bgr,
+            spi_bus: None,
+            spi_speed_hz: None,
+            file_output_dir: None,
+            file_write_interval_ms: None,
+            tcp_address: None,
+            max_fps: None,
+            driver_backend: DriverBackend::Rust,
+            pixel_mapper: None,
+            software_dither: None,
+            simulator_scale: None,
+            panels: Vec::new(),
         },
     )?;
 
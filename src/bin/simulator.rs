@@ -8,28 +8,48 @@ use embedded_graphics::{
 use embedded_graphics_simulator::{
     OutputSettingsBuilder, SimulatorDisplay, SimulatorEvent, Window,
 };
+use log::info;
 use rustic_pixel_display::render::Render;
-use rustic_pixel_examples::renders::{
-    person_tracker::{
-        HomeAssistantTracker, HomeTrackerConfig, PersonTracker, StateProvider, TransitTracker,
-        TransitTrackerConfig,
+use rustic_pixel_examples::{
+    format::TimeFormatConfig,
+    recording::Recorder,
+    renders::{
+        label::{Label, LabelConfig, LabelLine},
+        person_tracker::{
+            HomeAssistantTracker, HomeTrackerConfig, PersonTracker, StateProvider, TransitTracker,
+            TransitTrackerConfig,
+        },
+        presence_gate::{PresenceGate, PresenceGateConfig},
+        upcoming_arrivals::{ArrivalSort, UpcomingArrivals, UpcomingArrivalsConfig},
+        weather::{Configuration, Weather},
     },
-    upcoming_arrivals::{UpcomingArrivals, UpcomingArrivalsConfig},
-    weather::{Configuration, Weather},
 };
-use std::{collections::HashMap, env::var, vec};
+use std::{collections::HashMap, env::var, path::PathBuf, vec};
 
 const DISPLAY_SIZE: Size = Size {
     width: 256,
     height: 256,
 };
 
+/// The frame rate the window is paced at, and so the frame rate of recordings
+const FPS: u32 = 60;
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
 struct Args {
     #[command(subcommand)]
     command: Commands,
+
+    /// Record the frames to this path, an animated GIF if it ends in .gif
+    /// and a directory of PNGs otherwise
+    #[arg(long, global = true)]
+    record: Option<PathBuf>,
+
+    /// Stop recording and exit after this many seconds, records until the
+    /// window is closed if not set
+    #[arg(long, global = true, requires = "record")]
+    duration: Option<u64>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -37,13 +57,18 @@ enum Commands {
     Weather,
     UpcomingArrivals,
     PersonTracker,
+    Label {
+        /// The lines of text to display
+        #[arg(required = true)]
+        lines: Vec<String>,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
 
-    let output_settings = OutputSettingsBuilder::new().scale(4).max_fps(60).build();
+    let output_settings = OutputSettingsBuilder::new().scale(4).max_fps(FPS).build();
     let mut window = Window::new("Simulator", &output_settings);
     let mut canvas = SimulatorDisplay::<Rgb888>::new(DISPLAY_SIZE);
 
@@ -55,11 +80,34 @@ async fn main() -> Result<()> {
             location: rustic_pixel_examples::renders::weather::Location::City(
                 "Philadelphia".to_owned(),
             ),
+            spacing: None,
+            title_font: None,
+            body_font: None,
+            outline: None,
+            temp_colors: None,
+            request_timeout_secs: None,
+            refresh_secs: None,
+            error_retry_secs: None,
         })),
         Commands::UpcomingArrivals => Box::new(UpcomingArrivals::new(UpcomingArrivalsConfig {
-            septa_station: Some(septa_api::types::RegionalRailStop::SuburbanStation),
+            septa_station: Some(septa_api::types::RegionalRailStop::SuburbanStation.into()),
             amtrak_station: None,
             results: Some(20),
+            blink_late_mins: None,
+            late_thresholds: None,
+            merge_duplicates: false,
+            time_format: TimeFormatConfig::default(),
+            spacing: None,
+            title_font: None,
+            body_font: None,
+            request_timeout_secs: None,
+            refresh_secs: None,
+            error_retry_secs: None,
+            line_colors: None,
+            show_countdown_bar: false,
+            group_by_destination: false,
+            max_rows: None,
+            sort: ArrivalSort::default(),
         })?),
         Commands::PersonTracker => {
             let hass_url: String = var("HASS_URL")
@@ -68,6 +116,22 @@ async fn main() -> Result<()> {
                 "Please set BEARER_TOKEN environment variable to a long lived access token",
             );
 
+            let stefan_home = HomeAssistantTracker::new(HomeTrackerConfig {
+                home_assistant_url: hass_url.clone(),
+                home_assistant_bearer_token: bearer_token.clone(),
+                person_entity_id: "person.stefan".to_string(),
+                request_timeout_secs: None,
+                websocket: false,
+            })?;
+            let abby_home = HomeAssistantTracker::new(HomeTrackerConfig {
+                home_assistant_url: hass_url.clone(),
+                home_assistant_bearer_token: bearer_token.clone(),
+                person_entity_id: "person.abby".to_string(),
+                request_timeout_secs: None,
+                websocket: false,
+            })?;
+            let presence = vec![stefan_home.presence(), abby_home.presence()];
+
             let mut person_map: HashMap<String, Vec<Box<dyn StateProvider<_>>>> = HashMap::new();
 
             person_map.insert(
@@ -77,12 +141,11 @@ async fn main() -> Result<()> {
                         home_assistant_url: hass_url.clone(),
                         home_assistant_bearer_token: bearer_token.clone(),
                         person_entity_id: "person.stefan".to_string(),
+                        request_timeout_secs: None,
+                        line_colors: None,
+                        late_thresholds: None,
                     })?),
-                    Box::new(HomeAssistantTracker::new(HomeTrackerConfig {
-                        home_assistant_url: hass_url.clone(),
-                        home_assistant_bearer_token: bearer_token.clone(),
-                        person_entity_id: "person.stefan".to_string(),
-                    })?),
+                    Box::new(stefan_home),
                 ],
             );
 
@@ -93,27 +156,62 @@ async fn main() -> Result<()> {
                         home_assistant_url: hass_url.clone(),
                         home_assistant_bearer_token: bearer_token.clone(),
                         person_entity_id: "person.abby".to_string(),
+                        request_timeout_secs: None,
+                        line_colors: None,
+                        late_thresholds: None,
                     })?),
-                    Box::new(HomeAssistantTracker::new(HomeTrackerConfig {
-                        home_assistant_url: hass_url.clone(),
-                        home_assistant_bearer_token: bearer_token.clone(),
-                        person_entity_id: "person.abby".to_string(),
-                    })?),
+                    Box::new(abby_home),
                 ],
             );
 
-            Box::new(PersonTracker::new(person_map))
+            // Blank the panel once everyone has left
+            Box::new(PresenceGate::new(
+                PersonTracker::new(person_map),
+                presence,
+                PresenceGateConfig::default(),
+            ))
         }
+        Commands::Label { lines } => Box::new(Label::new(LabelConfig {
+            lines: lines
+                .into_iter()
+                .map(|text| LabelLine {
+                    text,
+                    font: Default::default(),
+                    color: None,
+                })
+                .collect(),
+            align: Default::default(),
+        })?),
     };
 
+    let mut recorder = args
+        .record
+        .as_deref()
+        .map(|path| Recorder::new(path, DISPLAY_SIZE, FPS))
+        .transpose()?;
+    // Counted in frames rather than time, so the recording is exactly as long
+    // as asked no matter how long capturing takes
+    let record_frames = args.duration.map(|secs| secs * u64::from(FPS));
+
     'render_loop: loop {
         canvas
-            .fill_solid(&Rectangle::new(Point::zero(), DISPLAY_SIZE), Rgb888::BLACK)
+            .fill_solid(
+                &Rectangle::new(Point::zero(), DISPLAY_SIZE),
+                render.background_color().unwrap_or(Rgb888::BLACK),
+            )
             .unwrap();
 
         render.render(&mut canvas).unwrap();
         window.update(&canvas);
 
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.capture(&canvas)?;
+
+            if record_frames.is_some_and(|frames| recorder.frames() >= frames) {
+                break 'render_loop;
+            }
+        }
+
         for event in window.events() {
             if event == SimulatorEvent::Quit {
                 break 'render_loop;
@@ -121,5 +219,10 @@ async fn main() -> Result<()> {
         }
     }
 
+    if let Some(recorder) = recorder {
+        info!("Recorded {} frames", recorder.frames());
+        recorder.finish()?;
+    }
+
     Ok(())
 }
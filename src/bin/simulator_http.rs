@@ -1,19 +1,45 @@
 use anyhow::Result;
-use embedded_graphics::{
-    pixelcolor::Rgb888,
-    prelude::{DrawTarget, Point, RgbColor, Size},
-    primitives::Rectangle,
-};
-use embedded_graphics_simulator::{
-    OutputSettingsBuilder, SimulatorDisplay, SimulatorEvent, Window,
-};
+use clap::Parser;
+use embedded_graphics::{pixelcolor::Rgb888, prelude::DrawTarget};
 use parking_lot::Mutex;
-use rustic_pixel_display::{http_server::build_api_server, registry::Registry, render::Render};
+use rustic_pixel_display::{
+    config::{DriverBackend, HardwareConfig, HardwareMapping, LedSequence, RowAddressSetterType},
+    driver::{self, HardwareDriver, SimulatorHardwareDriver, SimulatorWindow},
+    fallback::FallbackFactory,
+    layout_manager::LayoutFactory,
+    quiet_hours::QuietHoursFactory,
+    registry::Registry,
+    render::{BufferCanvas, FactoryInfo, Render, RenderFactories},
+    schedule::ScheduleFactory,
+    scrolling_feed::ScrollingFeedFactory,
+};
 use rustic_pixel_display_macros::RenderFactories;
 use rustic_pixel_examples::renders::{
-    person_tracker::TransitTrackerFactory, upcoming_arrivals::UpcomingArrivalsFactory,
+    bus_arrivals::BusArrivalsFactory,
+    ci_status::CiStatusFactory,
+    commute::CommuteFactory,
+    crypto::CryptoFactory,
+    date_tile::DateTileFactory,
+    flip_board::FlipBoardFactory,
+    gtfs_rt::GtfsRealtimeFactory,
+    ha_sensors::HaSensorsFactory,
+    iss::IssPassFactory,
+    label::LabelFactory,
+    log_tail::{self, LogTailFactory},
+    now_playing::NowPlayingFactory,
+    person_tracker::TransitTrackerFactory,
+    ping::PingMonitorFactory,
+    radar::RadarFactory,
+    septa_alerts::SeptaAlertsFactory,
+    sparkline::SparklineFactory,
+    test_pattern::TestPatternFactory,
+    train_map::TrainMapFactory,
+    upcoming_arrivals::UpcomingArrivalsFactory,
     weather::WeatherFactory,
+    webhook::WebhookFactory,
+    wifi::WifiFactory,
 };
+use rustic_pixel_examples::stations;
 use std::{
     convert::Infallible,
     sync::{
@@ -22,83 +48,160 @@ use std::{
     },
     vec,
 };
-use tokio::{runtime::Handle, task};
-
-const DISPLAY_SIZE: Size = Size {
-    width: 256,
-    height: 256,
-};
+use tokio::runtime::Runtime;
 
 #[derive(RenderFactories)]
-enum RenderFactoryEntries<D: DrawTarget<Color = Rgb888, Error = Infallible>> {
+enum RenderFactoryEntries<D: DrawTarget<Color = Rgb888, Error = Infallible> + 'static> {
+    Layout(LayoutFactory<D, RenderFactoryEntries<BufferCanvas>>),
+    Schedule(ScheduleFactory<D, RenderFactoryEntries<D>>),
+    QuietHours(QuietHoursFactory<D, RenderFactoryEntries<D>>),
+    Fallback(FallbackFactory<D, RenderFactoryEntries<D>>),
+    ScrollingFeed(ScrollingFeedFactory<D, RenderFactoryEntries<BufferCanvas>>),
+    BusArrivals(BusArrivalsFactory<D>),
+    CiStatus(CiStatusFactory<D>),
+    Commute(CommuteFactory<D>),
+    Crypto(CryptoFactory<D>),
+    DateTile(DateTileFactory<D>),
+    FlipBoard(FlipBoardFactory<D>),
+    GtfsRealtime(GtfsRealtimeFactory<D>),
+    HaSensors(HaSensorsFactory<D>),
+    IssPass(IssPassFactory<D>),
+    Label(LabelFactory<D>),
+    LogTail(LogTailFactory<D>),
+    NowPlaying(NowPlayingFactory<D>),
     TransitTracker(TransitTrackerFactory<D>),
+    PingMonitor(PingMonitorFactory<D>),
+    Radar(RadarFactory<D>),
+    SeptaAlerts(SeptaAlertsFactory<D>),
+    Sparkline(SparklineFactory<D>),
+    TestPattern(TestPatternFactory<D>),
+    TrainMap(TrainMapFactory<D>),
     UpcomingArrivals(UpcomingArrivalsFactory<D>),
     Weather(WeatherFactory<D>),
+    Webhook(WebhookFactory<D>),
+    Wifi(WifiFactory<D>),
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    env_logger::init();
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Print the name, description and an example configuration of every
+    /// compiled in render factory, then exit
+    #[arg(long)]
+    list_renders: bool,
+
+    /// Print the factory list as JSON instead of text
+    #[arg(long, requires = "list_renders")]
+    json: bool,
+}
 
-    // Get the handle to the created Tokio Runtime
-    let handle = Handle::current();
+/// Prints every render factory the binary was compiled with
+fn list_renders(json: bool) -> Result<()> {
+    let factories = RenderFactoryEntries::<BufferCanvas>::factories()
+        .iter()
+        .map(FactoryInfo::new::<BufferCanvas, _>)
+        .collect::<Vec<_>>();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&factories)?);
+    } else {
+        for factory in &factories {
+            println!("{factory}\n");
+        }
+    }
 
-    // Create the factory registry. This will house all the registered RenderFactories that can
-    // be used to construct renders.
-    let factory_registry = {
-        let factory_registry: Registry<RenderFactoryEntries<SimulatorDisplay<_>>, _> =
-            Registry::new(RenderFactoryEntries::factories());
-        Arc::new(Mutex::new(factory_registry))
-    };
+    Ok(())
+}
 
-    // Since we will be sharing the registry between the HTTP thread and the render thread, we
-    // need to clone since they will be moved into the lambda expression.
-    let http_registry = factory_registry.clone();
-    let render_registry = factory_registry;
+/// Serves the HTTP API and drives the simulator window like `rpi_http` drives
+/// the panel.
+///
+/// Threading model: the window is owned by the main thread, which SDL
+/// requires on some platforms, and blocks it while pumping the window events.
+/// The tokio runtime the renders and the HTTP API rely on runs on its own
+/// worker threads, and the `MatrixDriver` adds its usual render, driver and
+/// HTTP threads. The driver thread hands every frame to the main thread
+/// through the `SimulatorHardwareDriver`.
+fn main() -> Result<()> {
+    // Keep the recent log records around so they can be displayed on the panel
+    log_tail::init_logger(env_logger::Builder::from_default_env().build())?;
+
+    let args = Args::parse();
+    if args.list_renders {
+        return list_renders(args.json);
+    }
 
-    // Same with the alive variable
-    let alive = Arc::new(AtomicBool::new(true));
-    let http_alive = alive.clone();
-    let render_alive = alive;
+    // The main thread is kept for the window, so the runtime is entered rather
+    // than blocked on
+    let runtime = Runtime::new()?;
+    let _runtime_guard = runtime.enter();
 
-    let http_task = task::spawn(async move {
-        let server = build_api_server("localhost:8080", handle, http_registry);
+    // Drive the simulator window the same way the hardware is driven
+    type DriverType = SimulatorHardwareDriver;
+    type CanvasType = <SimulatorHardwareDriver as HardwareDriver>::Canvas;
 
-        while http_alive.load(Ordering::SeqCst) {
-            server.poll();
-        }
-    });
+    // Create the factory registry. This will house all the registered
+    // RenderFactories that can be used to construct renders.
+    let factory_registry = {
+        let factory_registry: Registry<RenderFactoryEntries<CanvasType>, _> =
+            Registry::new(RenderFactoryEntries::factories())?;
+        Arc::new(Mutex::new(factory_registry))
+    };
 
-    let render_task = task::spawn(async move {
-        let output_settings = OutputSettingsBuilder::new().scale(4).max_fps(60).build();
-        let mut window = Window::new("Simulator", &output_settings);
-        let mut canvas: SimulatorDisplay<Rgb888> = SimulatorDisplay::<Rgb888>::new(DISPLAY_SIZE);
+    // Same panel layout as rpi_http, so renders are laid out like on the panel
+    let hardware_config = HardwareConfig {
+        hardware_mapping: HardwareMapping::Regular,
+        rows: 64,
+        cols: 128,
+        refresh_rate: 120,
+        pi_chip: None,
+        pwm_bits: 4,
+        pwm_lsb_nanoseconds: 130,
+        slowdown: Some(2),
+        interlaced: false,
+        dither_bits: 0,
+        chain_length: 2,
+        parallel: 2,
+        panel_type: None,
+        multiplexing: None,
+        row_setter: RowAddressSetterType::Direct,
+        led_sequence: LedSequence::Bgr,
+        spi_bus: None,
+        spi_speed_hz: None,
+        file_output_dir: None,
+        file_write_interval_ms: None,
+        tcp_address: None,
+        max_fps: Some(60),
+        driver_backend: DriverBackend::Rust,
+        pixel_mapper: None,
+        software_dither: None,
+        simulator_scale: Some(2),
+        panels: Vec::new(),
+    };
 
-        while render_alive.load(Ordering::SeqCst) {
-            canvas
-                .fill_solid(&Rectangle::new(Point::zero(), DISPLAY_SIZE), Rgb888::BLACK)
-                .unwrap();
+    let window = SimulatorWindow::new(&hardware_config);
 
-            render_registry.lock().render(&mut canvas).unwrap();
-            window.update(&canvas);
+    // Lets a station picker look up the stations to configure renders with
+    let _station_search = stations::register_station_search()?;
 
-            for event in window.events() {
-                if event == SimulatorEvent::Quit {
-                    render_alive.store(false, Ordering::SeqCst);
-                }
-            }
-        }
-    });
+    let _simulator_driver = driver::MatrixDriver::with_register::<DriverType, _, _>(
+        "localhost:8080",
+        factory_registry,
+        None,
+        hardware_config,
+    )?;
 
-    tokio::select! {
-        _ = tokio::signal::ctrl_c() => {
+    let alive = Arc::new(AtomicBool::new(true));
+    let signal_alive = alive.clone();
+    runtime.spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
             println!("Ctrl+C received!");
+            signal_alive.store(false, Ordering::SeqCst);
         }
-    }
+    });
 
-    //
-    http_task.abort();
-    render_task.abort();
+    // Returns once the window is closed or Ctrl+C is pressed
+    window.run(|| alive.load(Ordering::SeqCst));
 
     Ok(())
 }
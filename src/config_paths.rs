@@ -0,0 +1,47 @@
+use log::warn;
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+/// Name of the startup configuration searched for when no path is given as the
+/// first argument
+pub const APP_CONFIG_FILE_NAME: &str = "app.json";
+
+/// System wide configuration directory, searched last so it also works for
+/// services that run without a `$HOME`
+pub const SYSTEM_CONFIG_DIR: &str = "/etc/rustic-pixel-display";
+
+/// The user's configuration directory, relative to `$HOME`
+const USER_CONFIG_DIR: &str = ".config/rustic-pixel-display";
+
+/// The paths the startup configuration is searched at, in order: the working
+/// directory, the user's configuration directory if `$HOME` is set and the
+/// system wide configuration directory.
+pub fn app_config_paths() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(APP_CONFIG_FILE_NAME)];
+
+    match env::var_os("HOME") {
+        Some(home) => paths.push(
+            Path::new(&home)
+                .join(USER_CONFIG_DIR)
+                .join(APP_CONFIG_FILE_NAME),
+        ),
+        None => warn!("$HOME is not set, skipping the user configuration directory"),
+    }
+
+    paths.push(Path::new(SYSTEM_CONFIG_DIR).join(APP_CONFIG_FILE_NAME));
+    paths
+}
+
+/// Where `--init-config` writes the app configuration when no path is given:
+/// the user's configuration directory, or the working directory if `$HOME` is
+/// not set.
+pub fn init_config_path() -> PathBuf {
+    match env::var_os("HOME") {
+        Some(home) => Path::new(&home)
+            .join(USER_CONFIG_DIR)
+            .join(APP_CONFIG_FILE_NAME),
+        None => PathBuf::from(APP_CONFIG_FILE_NAME),
+    }
+}
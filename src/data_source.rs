@@ -0,0 +1,227 @@
+use anyhow::Result;
+use log::{error, warn};
+use rustic_pixel_display::render::PollGate;
+use std::{fmt, future::Future, sync::Arc, time::Duration};
+use tokio::{select, sync::watch, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use crate::supervisor::{supervise, RESTART_DELAY};
+
+/// Where a render gets its data from, whether it is polled or pushed, so the
+/// render only has to watch for new values.
+pub trait DataSource<T>: Send + Sync {
+    /// Watches the latest value, `None` until the first one arrives
+    fn subscribe(&self) -> watch::Receiver<Option<T>>;
+
+    /// Called when the render is displayed, so sources that poll can start
+    /// again
+    fn resume(&self) {}
+
+    /// Called when the render is no longer displayed, so sources that poll can
+    /// stop using up API quota
+    fn pause(&self) {}
+}
+
+/// Returned by the fetch of a [`PollingSource`] when the API asked to not be
+/// called again until `retry_after` has passed
+#[derive(Debug)]
+pub struct RateLimited {
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Rate limited, retrying in {}s",
+            self.retry_after.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Fetches a value every `refresh`, or every `error_retry` after a failed
+/// fetch, while resumed.
+///
+/// A fetch that fails with [`RateLimited`] is tried again once its
+/// `retry_after` has passed instead. The fetch task is restarted if it panics,
+/// see [`supervise`].
+pub struct PollingSource<T> {
+    receiver: watch::Receiver<Option<T>>,
+
+    /// Used to pause the update task while the render is not displayed
+    poll_gate: PollGate,
+
+    /// Flag used to gracefully terminate the update task
+    cancel_token: CancellationToken,
+
+    /// Handle to the task used to fetch the value
+    update_task_handle: Option<JoinHandle<()>>,
+}
+
+impl<T> PollingSource<T>
+where
+    T: Send + Sync + 'static,
+{
+    /// Starts polling `fetch`, naming the source `name` in the log when a fetch
+    /// fails.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn new<F, Fut>(
+        name: &'static str,
+        refresh: Duration,
+        error_retry: Duration,
+        fetch: F,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T>> + Send + 'static,
+    {
+        Self::with_backoff(name, refresh, error_retry, error_retry, fetch)
+    }
+
+    /// Same as [`PollingSource::new`], but doubles the wait after every failed
+    /// fetch in a row, up to `max_backoff`, so a flaky API is not hammered.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn with_backoff<F, Fut>(
+        name: &'static str,
+        refresh: Duration,
+        error_retry: Duration,
+        max_backoff: Duration,
+        fetch: F,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T>> + Send + 'static,
+    {
+        let (sender, receiver) = watch::channel(None);
+        let poll_gate = PollGate::new();
+        let cancel_token = CancellationToken::new();
+
+        // Shared so a restarted task still publishes to the same receiver
+        let sender = Arc::new(sender);
+        let fetch = Arc::new(fetch);
+        let task_cancel_token = cancel_token.clone();
+        let task_poll_gate = poll_gate.clone();
+
+        let update_task_handle = supervise(name, cancel_token.clone(), RESTART_DELAY, move || {
+            let sender = sender.clone();
+            let fetch = fetch.clone();
+            let task_cancel_token = task_cancel_token.clone();
+            let task_poll_gate = task_poll_gate.clone();
+
+            async move {
+                let mut retry_delay = error_retry;
+
+                loop {
+                    select! {
+                        _ = task_poll_gate.active() => {},
+                        _ = task_cancel_token.cancelled() => break,
+                    }
+
+                    let start_time = tokio::time::Instant::now();
+
+                    let fetch_result = select! {
+                        fetch_result = (*fetch)() => fetch_result,
+                        _ = task_cancel_token.cancelled() => break,
+                    };
+
+                    let refresh_time = match fetch_result {
+                        Ok(value) => {
+                            sender.send_replace(Some(value));
+                            retry_delay = error_retry;
+                            start_time + refresh
+                        }
+                        Err(e) => match e.downcast_ref::<RateLimited>() {
+                            Some(rate_limited) => {
+                                warn!("Could not update {name} {rate_limited}");
+                                start_time + rate_limited.retry_after.max(error_retry)
+                            }
+                            None => {
+                                error!("Could not update {name} {e}");
+
+                                let delay = retry_delay;
+                                retry_delay = (retry_delay * 2).min(max_backoff);
+                                start_time + delay
+                            }
+                        },
+                    };
+
+                    select! {
+                        _ = tokio::time::sleep_until(refresh_time) => {},
+                        _ = task_poll_gate.paused() => {},
+                        _ = task_cancel_token.cancelled() => break,
+                    }
+                }
+
+                Ok(())
+            }
+        });
+
+        Self {
+            receiver,
+            poll_gate,
+            cancel_token,
+            update_task_handle: Some(update_task_handle),
+        }
+    }
+}
+
+impl<T> DataSource<T> for PollingSource<T>
+where
+    T: Send + Sync,
+{
+    fn subscribe(&self) -> watch::Receiver<Option<T>> {
+        self.receiver.clone()
+    }
+
+    fn resume(&self) {
+        self.poll_gate.resume();
+    }
+
+    fn pause(&self) {
+        self.poll_gate.pause();
+    }
+}
+
+impl<T> Drop for PollingSource<T> {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+
+        if let Some(task_handle) = self.update_task_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
+/// Hands out values published by whatever receives them, i.e. an MQTT or
+/// WebSocket subscription.
+pub struct PushSource<T> {
+    sender: watch::Sender<Option<T>>,
+}
+
+impl<T> Default for PushSource<T> {
+    fn default() -> Self {
+        Self {
+            sender: watch::channel(None).0,
+        }
+    }
+}
+
+impl<T> PushSource<T> {
+    /// Replaces the latest value and notifies every subscriber
+    pub fn publish(&self, value: T) {
+        self.sender.send_replace(Some(value));
+    }
+}
+
+impl<T> DataSource<T> for PushSource<T>
+where
+    T: Send + Sync,
+{
+    fn subscribe(&self) -> watch::Receiver<Option<T>> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::{DateTime, Datelike, Duration, Utc};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, RgbColor},
+};
+use rustic_pixel_display::render::{BufferCanvas, Render};
+use serde::Deserialize;
+use std::{convert::Infallible, time::Instant};
+
+/// Seconds the sun takes to move one degree of longitude
+const SECONDS_PER_DEGREE: f64 = 240.0;
+
+fn default_night_brightness() -> u8 {
+    25
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct DaylightConfig {
+    pub lat: f64,
+    pub lon: f64,
+
+    /// Brightness, in percent, renders are drawn at between sunset and sunrise
+    #[serde(default = "default_night_brightness")]
+    pub night_brightness: u8,
+}
+
+/// Determines if the sun is up at a location
+#[derive(Debug, Clone, Copy)]
+pub struct Daylight {
+    lat: f64,
+    lon: f64,
+}
+
+impl Daylight {
+    pub fn new(lat: f64, lon: f64) -> Self {
+        Self { lat, lon }
+    }
+
+    /// Returns if `now` is between sunrise and sunset at the location.
+    pub fn is_daytime(&self, now: DateTime<Utc>) -> bool {
+        // Use the date at the location rather than in UTC, otherwise the evening
+        // of a location far from the prime meridian falls on the next day
+        let local_now = now + Duration::seconds((self.lon * SECONDS_PER_DEGREE) as i64);
+
+        let (sunrise, sunset) = sunrise::sunrise_sunset(
+            self.lat,
+            self.lon,
+            local_now.year(),
+            local_now.month(),
+            local_now.day(),
+        );
+
+        (sunrise..sunset).contains(&now.timestamp())
+    }
+}
+
+/// Dims the inner render between sunset and sunrise.
+pub struct DayNight<R> {
+    render: R,
+    daylight: Daylight,
+
+    /// Brightness, in percent, the inner render is drawn at during the night
+    night_brightness: u8,
+}
+
+impl<R> DayNight<R> {
+    pub fn new(render: R, config: DaylightConfig) -> Self {
+        Self {
+            render,
+            daylight: Daylight::new(config.lat, config.lon),
+            night_brightness: config.night_brightness.min(100),
+        }
+    }
+}
+
+impl<R> DayNight<R> {
+    /// Returns the brightness, in percent, to draw at right now.
+    fn brightness(&self) -> u8 {
+        if self.daylight.is_daytime(Utc::now()) {
+            100
+        } else {
+            self.night_brightness
+        }
+    }
+}
+
+/// Scales each channel of `color` to `brightness` percent.
+fn dim(color: Rgb888, brightness: u8) -> Rgb888 {
+    let scale = |channel: u8| (u32::from(channel) * u32::from(brightness) / 100) as u8;
+    Rgb888::new(scale(color.r()), scale(color.g()), scale(color.b()))
+}
+
+impl<D, R> Render<D> for DayNight<R>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    R: Render<BufferCanvas>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let bounding_box = canvas.bounding_box();
+        let brightness = self.brightness();
+
+        let mut buffer = BufferCanvas::new(bounding_box.size);
+        buffer.clear(self.render.background_color().unwrap_or(Rgb888::BLACK))?;
+        self.render.render_at(&mut buffer, now)?;
+
+        buffer.map_pixels(|pixel| dim(pixel, brightness));
+        buffer.draw_to(canvas, bounding_box.top_left)
+    }
+
+    fn on_select(&self) {
+        self.render.on_select();
+    }
+
+    fn on_deselect(&self) {
+        self.render.on_deselect();
+    }
+
+    fn background_color(&self) -> Option<Rgb888> {
+        self.render
+            .background_color()
+            .map(|color| dim(color, self.brightness()))
+    }
+
+    fn describe(&self) -> String {
+        self.render.describe()
+    }
+
+    fn has_content(&self) -> bool {
+        self.render.has_content()
+    }
+
+    fn reset(&self) {
+        self.render.reset();
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, TimeZone, Timelike};
+use serde::Deserialize;
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum HourFormat {
+    /// 00:00 through 23:59
+    #[default]
+    TwentyFourHour,
+
+    /// 12:00 AM through 11:59 PM
+    TwelveHour,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimeFormatConfig {
+    #[serde(default)]
+    pub hour_format: HourFormat,
+
+    /// Designator appended to times before noon in the 12 hour format
+    #[serde(default = "default_am")]
+    pub am: String,
+
+    /// Designator appended to times after noon in the 12 hour format
+    #[serde(default = "default_pm")]
+    pub pm: String,
+}
+
+fn default_am() -> String {
+    "AM".to_owned()
+}
+
+fn default_pm() -> String {
+    "PM".to_owned()
+}
+
+impl Default for TimeFormatConfig {
+    fn default() -> Self {
+        Self {
+            hour_format: HourFormat::default(),
+            am: default_am(),
+            pm: default_pm(),
+        }
+    }
+}
+
+/// Formats the time of day of `dt` according to `config`.
+///
+/// Hours are padded with a space rather than a zero so that times line up when
+/// displayed in a column.
+pub fn format_time<Tz>(dt: &DateTime<Tz>, config: &TimeFormatConfig) -> String
+where
+    Tz: TimeZone,
+    Tz::Offset: Display,
+{
+    match config.hour_format {
+        HourFormat::TwentyFourHour => dt.format("%_H:%M").to_string(),
+        HourFormat::TwelveHour => {
+            let (is_pm, hour) = dt.hour12();
+            let designator = if is_pm { &config.pm } else { &config.am };
+
+            format!("{:>2}:{:02} {}", hour, dt.minute(), designator)
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::pixelcolor::Rgb888;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read, Seek},
+    sync::Arc,
+    time::Duration,
+};
+use zip::ZipArchive;
+
+use crate::line_colors::parse_hex_color;
+
+/// How long downloading a schedule may take, schedules are often several
+/// megabytes
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Parsed schedules, keyed by where they were loaded from, so renders sharing a
+// schedule only parse it once.
+lazy_static! {
+    static ref SCHEDULE_CACHE: Mutex<HashMap<String, Arc<GtfsSchedule>>> =
+        Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct StopRecord {
+    stop_id: String,
+
+    #[serde(default)]
+    stop_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RouteRecord {
+    route_id: String,
+
+    #[serde(default)]
+    route_short_name: Option<String>,
+
+    #[serde(default)]
+    route_long_name: Option<String>,
+
+    #[serde(default)]
+    route_color: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TripRecord {
+    trip_id: String,
+    route_id: String,
+
+    #[serde(default)]
+    trip_headsign: Option<String>,
+}
+
+/// A route of a [`GtfsSchedule`]
+#[derive(Debug, Clone)]
+pub struct GtfsRoute {
+    /// The short name if the agency provides one, otherwise the long name
+    pub name: String,
+
+    pub color: Option<Rgb888>,
+}
+
+/// A trip of a [`GtfsSchedule`]
+#[derive(Debug, Clone)]
+pub struct GtfsTrip {
+    pub route_id: String,
+
+    /// The destination shown on the vehicle
+    pub headsign: Option<String>,
+}
+
+/// The names of the stops, routes and trips of a static GTFS feed, used to
+/// show riders names rather than the ids of a GTFS-realtime feed.
+#[derive(Debug, Default)]
+pub struct GtfsSchedule {
+    stops: HashMap<String, String>,
+    routes: HashMap<String, GtfsRoute>,
+    trips: HashMap<String, GtfsTrip>,
+}
+
+/// Deserializes every row of the CSV file `name` in `archive`.
+fn read_records<R, T>(archive: &mut ZipArchive<R>, name: &str) -> Result<Vec<T>>
+where
+    R: Read + Seek,
+    T: DeserializeOwned,
+{
+    let file = archive
+        .by_name(name)
+        .map_err(|e| anyhow!("GTFS schedule has no {name} {e}"))?;
+
+    csv::Reader::from_reader(file)
+        .into_deserialize()
+        .collect::<Result<Vec<T>, _>>()
+        .map_err(|e| anyhow!("Could not parse {name} of the GTFS schedule {e}"))
+}
+
+impl GtfsSchedule {
+    /// Parses the `stops.txt`, `routes.txt` and `trips.txt` of a GTFS zip.
+    pub fn from_zip<R: Read + Seek>(reader: R) -> Result<Self> {
+        let mut archive =
+            ZipArchive::new(reader).map_err(|e| anyhow!("GTFS schedule is not a zip {e}"))?;
+
+        let stops = read_records::<_, StopRecord>(&mut archive, "stops.txt")?
+            .into_iter()
+            .filter_map(|stop| Some((stop.stop_id, stop.stop_name?)))
+            .collect();
+
+        let routes = read_records::<_, RouteRecord>(&mut archive, "routes.txt")?
+            .into_iter()
+            .filter_map(|route| {
+                let name = route
+                    .route_short_name
+                    .filter(|name| !name.is_empty())
+                    .or(route.route_long_name)?;
+
+                // An invalid color is only cosmetic, the route is still named
+                let color = route
+                    .route_color
+                    .filter(|color| !color.is_empty())
+                    .and_then(|color| match parse_hex_color(&format!("#{color}")) {
+                        Ok(color) => Some(color),
+                        Err(e) => {
+                            warn!("Ignoring color of GTFS route {} {e}", route.route_id);
+                            None
+                        }
+                    });
+
+                Some((route.route_id, GtfsRoute { name, color }))
+            })
+            .collect();
+
+        let trips = read_records::<_, TripRecord>(&mut archive, "trips.txt")?
+            .into_iter()
+            .map(|trip| {
+                (
+                    trip.trip_id,
+                    GtfsTrip {
+                        route_id: trip.route_id,
+                        headsign: trip.trip_headsign.filter(|headsign| !headsign.is_empty()),
+                    },
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            stops,
+            routes,
+            trips,
+        })
+    }
+
+    /// Loads the GTFS zip at `source`, either a URL or a local path.
+    ///
+    /// Schedules are cached for the life of the process, loading the same
+    /// `source` again returns the already parsed schedule.
+    pub async fn load(source: &str) -> Result<Arc<Self>> {
+        if let Some(schedule) = SCHEDULE_CACHE.lock().get(source) {
+            return Ok(schedule.clone());
+        }
+
+        let bytes = if source.starts_with("http://") || source.starts_with("https://") {
+            reqwest::Client::builder()
+                .timeout(DOWNLOAD_TIMEOUT)
+                .build()?
+                .get(source)
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?
+                .to_vec()
+        } else {
+            tokio::fs::read(source)
+                .await
+                .map_err(|e| anyhow!("Could not read GTFS schedule {source} {e}"))?
+        };
+
+        // Large feeds take a while to parse, so keep them off the async workers
+        let schedule = Arc::new(
+            tokio::task::spawn_blocking(move || Self::from_zip(Cursor::new(bytes))).await??,
+        );
+
+        info!(
+            "Loaded GTFS schedule {source} ({} stops, {} routes, {} trips)",
+            schedule.stops.len(),
+            schedule.routes.len(),
+            schedule.trips.len()
+        );
+
+        SCHEDULE_CACHE
+            .lock()
+            .insert(source.to_owned(), schedule.clone());
+
+        Ok(schedule)
+    }
+
+    pub fn stop_name(&self, stop_id: &str) -> Option<&str> {
+        self.stops.get(stop_id).map(String::as_str)
+    }
+
+    pub fn route(&self, route_id: &str) -> Option<&GtfsRoute> {
+        self.routes.get(route_id)
+    }
+
+    pub fn trip(&self, trip_id: &str) -> Option<&GtfsTrip> {
+        self.trips.get(trip_id)
+    }
+}
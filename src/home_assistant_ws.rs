@@ -0,0 +1,340 @@
+use anyhow::{anyhow, Result};
+use futures_util::{SinkExt, StreamExt};
+use home_assistant_rest::get::StateEnum;
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    select,
+    sync::{broadcast, watch},
+};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// How long to wait before reconnecting after the connection dropped
+const RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// How many state changes a subscriber can fall behind before it misses some
+const UPDATE_CAPACITY: usize = 64;
+
+const SUBSCRIBE_ID: u64 = 1;
+const GET_STATES_ID: u64 = 2;
+
+// Clients, keyed by the URL of the Home Assistant instance, so renders talking
+// to the same instance share one connection.
+lazy_static! {
+    static ref CLIENT_CACHE: Mutex<HashMap<String, Arc<HomeAssistantWsClient>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// The state of an entity as pushed over the WebSocket API
+#[derive(Debug, Clone, Deserialize)]
+pub struct WsEntityState {
+    pub entity_id: String,
+    pub state: String,
+
+    #[serde(default)]
+    pub attributes: Map<String, Value>,
+}
+
+impl WsEntityState {
+    /// The state in the form the REST client returns it, so renders can handle
+    /// both the same way
+    pub fn state_enum(&self) -> Option<StateEnum> {
+        Some(StateEnum::String(self.state.clone()))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    AuthRequired,
+    AuthOk,
+    AuthInvalid {
+        #[serde(default)]
+        message: Option<String>,
+    },
+    Result {
+        id: u64,
+        success: bool,
+
+        #[serde(default)]
+        result: Option<Value>,
+    },
+    Event {
+        event: Event,
+    },
+
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct Event {
+    data: StateChanged,
+}
+
+#[derive(Debug, Deserialize)]
+struct StateChanged {
+    entity_id: String,
+
+    /// `None` when the entity was removed
+    new_state: Option<WsEntityState>,
+}
+
+/// Converts the base URL of a Home Assistant instance into the URL of its
+/// WebSocket API.
+fn websocket_url(url: &str) -> Result<String> {
+    let url = url.trim_end_matches('/');
+
+    if let Some(rest) = url.strip_prefix("https://") {
+        Ok(format!("wss://{rest}/api/websocket"))
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        Ok(format!("ws://{rest}/api/websocket"))
+    } else {
+        Err(anyhow!(
+            "Home Assistant URL {url} is not http:// or https://"
+        ))
+    }
+}
+
+/// A connection to the WebSocket API of Home Assistant that keeps the latest
+/// state of every entity and pushes state changes as they happen.
+///
+/// The connection is re-established when it drops. While it is down
+/// [`HomeAssistantWsClient::state`] returns `None`, so renders fall back to
+/// polling the REST API.
+pub struct HomeAssistantWsClient {
+    /// The latest state of every entity, cleared while disconnected
+    states: Arc<Mutex<HashMap<String, WsEntityState>>>,
+
+    /// Every state change, for renders waiting on their entities
+    updates: broadcast::Sender<WsEntityState>,
+
+    connected: watch::Receiver<bool>,
+}
+
+impl HomeAssistantWsClient {
+    /// Returns the client connected to the Home Assistant at `url`, connecting
+    /// if no render has yet.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn shared(url: &str, token: &str) -> Result<Arc<Self>> {
+        let mut cache = CLIENT_CACHE.lock();
+
+        if let Some(client) = cache.get(url) {
+            return Ok(client.clone());
+        }
+
+        let client = Arc::new(Self::connect(url, token)?);
+        cache.insert(url.to_owned(), client.clone());
+
+        Ok(client)
+    }
+
+    fn connect(url: &str, token: &str) -> Result<Self> {
+        let websocket_url = websocket_url(url)?;
+        let states = Arc::new(Mutex::new(HashMap::new()));
+        let (updates, _) = broadcast::channel(UPDATE_CAPACITY);
+        let (connected_sender, connected) = watch::channel(false);
+
+        let task_states = states.clone();
+        let task_updates = updates.clone();
+        let token = token.to_owned();
+
+        // Clients are shared for the life of the process, so the task is never
+        // stopped
+        tokio::task::spawn(async move {
+            loop {
+                let result = Self::run(
+                    &websocket_url,
+                    &token,
+                    &task_states,
+                    &task_updates,
+                    &connected_sender,
+                )
+                .await;
+
+                connected_sender.send_replace(false);
+                task_states.lock().clear();
+
+                match result {
+                    Ok(()) => warn!("Home Assistant closed the WebSocket connection"),
+                    Err(e) => warn!("Home Assistant WebSocket connection failed {e}"),
+                }
+
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+
+        Ok(Self {
+            states,
+            updates,
+            connected,
+        })
+    }
+
+    /// Authenticates, subscribes to state changes and handles messages until
+    /// the connection drops.
+    async fn run(
+        websocket_url: &str,
+        token: &str,
+        states: &Mutex<HashMap<String, WsEntityState>>,
+        updates: &broadcast::Sender<WsEntityState>,
+        connected: &watch::Sender<bool>,
+    ) -> Result<()> {
+        let (mut socket, _) = connect_async(websocket_url).await?;
+
+        while let Some(message) = socket.next().await {
+            let text = match message? {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+
+            match serde_json::from_str::<ServerMessage>(&text)? {
+                ServerMessage::AuthRequired => {
+                    socket
+                        .send(Message::Text(
+                            json!({ "type": "auth", "access_token": token }).to_string(),
+                        ))
+                        .await?;
+                }
+                ServerMessage::AuthOk => {
+                    socket
+                        .send(Message::Text(
+                            json!({
+                                "id": SUBSCRIBE_ID,
+                                "type": "subscribe_events",
+                                "event_type": "state_changed"
+                            })
+                            .to_string(),
+                        ))
+                        .await?;
+
+                    // Fetched after subscribing so no change can slip in between
+                    socket
+                        .send(Message::Text(
+                            json!({ "id": GET_STATES_ID, "type": "get_states" }).to_string(),
+                        ))
+                        .await?;
+                }
+                ServerMessage::AuthInvalid { message } => {
+                    return Err(anyhow!(
+                        "Home Assistant rejected the token {}",
+                        message.unwrap_or_default()
+                    ));
+                }
+                ServerMessage::Result {
+                    id, success: false, ..
+                } => {
+                    return Err(anyhow!("Home Assistant rejected request {id}"));
+                }
+                ServerMessage::Result {
+                    id: GET_STATES_ID,
+                    result: Some(result),
+                    ..
+                } => {
+                    let entity_states = serde_json::from_value::<Vec<WsEntityState>>(result)?;
+
+                    *states.lock() = entity_states
+                        .into_iter()
+                        .map(|entity_state| (entity_state.entity_id.clone(), entity_state))
+                        .collect();
+
+                    info!("Connected to the Home Assistant WebSocket API");
+                    connected.send_replace(true);
+                }
+                ServerMessage::Event { event } => match event.data.new_state {
+                    Some(new_state) => {
+                        states
+                            .lock()
+                            .insert(new_state.entity_id.clone(), new_state.clone());
+
+                        // Nobody listening isn't an error
+                        let _ = updates.send(new_state);
+                    }
+                    None => {
+                        states.lock().remove(&event.data.entity_id);
+                    }
+                },
+                ServerMessage::Result { .. } | ServerMessage::Other => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The latest state of `entity_id`, `None` if it doesn't exist or the
+    /// connection is down and it needs to be fetched over the REST API.
+    pub fn state(&self, entity_id: &str) -> Option<WsEntityState> {
+        if !*self.connected.borrow() {
+            return None;
+        }
+
+        self.states.lock().get(entity_id).cloned()
+    }
+
+    /// Starts watching `entity_ids` for changes.
+    pub fn subscribe<I, S>(&self, entity_ids: I) -> HomeAssistantWsSubscription
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        HomeAssistantWsSubscription {
+            entity_ids: entity_ids.into_iter().map(Into::into).collect(),
+            updates: self.updates.subscribe(),
+            connected: self.connected.clone(),
+        }
+    }
+}
+
+/// Waits for changes of a set of entities, see
+/// [`HomeAssistantWsClient::subscribe`]
+pub struct HomeAssistantWsSubscription {
+    entity_ids: HashSet<String>,
+    updates: broadcast::Receiver<WsEntityState>,
+    connected: watch::Receiver<bool>,
+}
+
+impl HomeAssistantWsSubscription {
+    /// Whether state changes are currently being pushed
+    pub fn is_connected(&self) -> bool {
+        *self.connected.borrow()
+    }
+
+    /// Completes when one of the entities changes, or the connection is
+    /// established or dropped.
+    pub async fn changed(&mut self) {
+        loop {
+            select! {
+                update = self.updates.recv() => match update {
+                    Ok(update) if self.entity_ids.contains(&update.entity_id) => return,
+                    Ok(_) => {},
+
+                    // Some changes were missed, one of them may have been ours
+                    Err(broadcast::error::RecvError::Lagged(_)) => return,
+                    Err(broadcast::error::RecvError::Closed) => std::future::pending().await,
+                },
+                changed = self.connected.changed() => match changed {
+                    Ok(()) => return,
+                    Err(_) => std::future::pending().await,
+                },
+            }
+        }
+    }
+}
+
+/// Completes when `subscription` sees a change, never if there is no
+/// subscription, for update loops where pushed changes are optional.
+pub async fn changed(subscription: Option<&mut HomeAssistantWsSubscription>) {
+    match subscription {
+        Some(subscription) => subscription.changed().await,
+        None => std::future::pending().await,
+    }
+}
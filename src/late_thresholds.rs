@@ -0,0 +1,46 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{RgbColor, WebColors},
+};
+use serde::Deserialize;
+
+pub const DEFAULT_LATE_THRESHOLDS: LateThresholdsConfig = LateThresholdsConfig {
+    slightly_late_mins: 5,
+    late_mins: 10,
+};
+
+/// How late, in minutes, a train has to be for its status to change color.
+///
+/// Trains that are on time or early are green, up to `slightly_late_mins` late
+/// yellow, up to `late_mins` late orange and any later red.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct LateThresholdsConfig {
+    pub slightly_late_mins: u32,
+    pub late_mins: u32,
+}
+
+impl LateThresholdsConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.slightly_late_mins > self.late_mins {
+            return Err(anyhow!(
+                "slightly_late_mins of {} is larger than late_mins of {}",
+                self.slightly_late_mins,
+                self.late_mins
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The color of the status of a train that is `late_mins` minutes late,
+    /// negative if it is early.
+    pub fn color(&self, late_mins: i32) -> Rgb888 {
+        match u32::try_from(late_mins) {
+            Ok(0) | Err(_) => Rgb888::GREEN,
+            Ok(mins) if mins <= self.slightly_late_mins => Rgb888::YELLOW,
+            Ok(mins) if mins <= self.late_mins => Rgb888::CSS_ORANGE,
+            Ok(_) => Rgb888::RED,
+        }
+    }
+}
@@ -1,4 +1,20 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod assets;
+pub mod config_paths;
+pub mod data_source;
+pub mod daylight;
+pub mod format;
+pub mod gtfs;
+pub mod home_assistant_ws;
+pub mod late_thresholds;
+pub mod line_colors;
+pub mod loading;
+pub mod recording;
 pub mod renders;
+pub mod request;
+pub mod scroll;
+pub mod spacing;
+pub mod stations;
+pub mod supervisor;
@@ -0,0 +1,45 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::RgbColor};
+use rustic_pixel_display::theme;
+use std::collections::HashMap;
+
+/// The color of trains whose destination has no configured color
+pub const DEFAULT_LINE_COLOR: Rgb888 = Rgb888::WHITE;
+
+/// Colors of the lines trains run on, looked up by their destination so riders
+/// can recognize a train at a glance
+#[derive(Debug, Clone, Default)]
+pub struct LineColors {
+    /// Destinations are stored in lowercase so lookups ignore case
+    colors: HashMap<String, Rgb888>,
+}
+
+impl LineColors {
+    /// Parses a map from destination to a color written as `#RRGGBB`.
+    pub fn new(config: &HashMap<String, String>) -> Result<Self> {
+        let colors = config
+            .iter()
+            .map(|(destination, color)| {
+                parse_hex_color(color)
+                    .map(|color| (destination.to_lowercase(), color))
+                    .map_err(|e| anyhow!("Invalid line color for \"{destination}\": {e}"))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { colors })
+    }
+
+    /// The color of trains heading to `destination`, falling back to
+    /// [`DEFAULT_LINE_COLOR`] if none is configured.
+    pub fn color_for(&self, destination: &str) -> Rgb888 {
+        self.colors
+            .get(&destination.to_lowercase())
+            .copied()
+            .unwrap_or(DEFAULT_LINE_COLOR)
+    }
+}
+
+/// Parses a color written as `#RRGGBB`.
+pub fn parse_hex_color(value: &str) -> Result<Rgb888> {
+    theme::parse_hex_color(value).map_err(|e| anyhow!(e))
+}
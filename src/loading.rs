@@ -0,0 +1,52 @@
+use embedded_graphics::{
+    mono_font::{self, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    text::{Baseline, Text},
+    Drawable,
+};
+use rustic_pixel_display::render::Spinner;
+use std::time::Duration;
+
+const FONT: &mono_font::MonoFont = &mono_font::ascii::FONT_6X9;
+const TEXT: &str = "Loading";
+
+/// Space between the spinner and the text
+const SPINNER_MARGIN: i32 = 3;
+
+/// Creates the spinner drawn next to the text by [`draw_loading`]
+pub fn loading_spinner() -> Spinner {
+    Spinner::new(
+        FONT.character_size.height,
+        Rgb888::WHITE,
+        Duration::from_millis(100),
+    )
+}
+
+/// Draws `spinner` followed by "Loading" centered on the canvas.
+///
+/// Used by renders that fetch their data asynchronously until the first fetch
+/// completes, so an empty state is not mistaken for an error.
+pub fn draw_loading<D>(canvas: &mut D, spinner: &Spinner) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb888>,
+{
+    let center = canvas.bounding_box().center();
+    let spinner_size = FONT.character_size.height as i32;
+    let text_width =
+        TEXT.len() as i32 * (FONT.character_size.width + FONT.character_spacing) as i32;
+
+    let left = center.x - (spinner_size + SPINNER_MARGIN + text_width) / 2;
+
+    spinner.draw(canvas, Point::new(left + spinner_size / 2, center.y))?;
+
+    Text::with_baseline(
+        TEXT,
+        Point::new(left + spinner_size + SPINNER_MARGIN, center.y),
+        MonoTextStyle::new(FONT, Rgb888::WHITE),
+        Baseline::Middle,
+    )
+    .draw(canvas)?;
+
+    Ok(())
+}
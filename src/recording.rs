@@ -0,0 +1,180 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{OriginDimensions, Point, RgbColor, Size},
+};
+use embedded_graphics_simulator::SimulatorDisplay;
+use gif::{Encoder, Frame, Repeat};
+use image::{Rgb, RgbImage};
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+/// Most viewers play GIF frames shorter than this many hundredths of a second
+/// far slower than asked, so faster frames are dropped instead
+const MIN_GIF_DELAY: u64 = 2;
+
+/// Trades GIF encoding speed for palette quality, from 1 (best) to 30
+const GIF_QUANTIZE_SPEED: i32 = 10;
+
+enum Output {
+    /// Frames are encoded as they are captured. The frame waiting to be
+    /// written is kept with its start time in hundredths of a second, since
+    /// its delay is only known once a later frame replaces it.
+    Gif {
+        encoder: Encoder<BufWriter<File>>,
+        pending: Option<(Vec<u8>, u64)>,
+    },
+
+    /// Every frame is written to its own numbered PNG in the directory
+    Png { dir: PathBuf },
+}
+
+/// Records what the simulator shows, one frame per rendered frame.
+///
+/// Frames are written to disk as they are captured rather than kept in memory,
+/// so long recordings are fine. Frame times are derived from the frame count
+/// and the fps the simulator is paced at, not from the clock, so a recording
+/// plays back at the speed the renders were drawn even if capturing a frame
+/// was slow.
+///
+/// A path ending in `.gif` is recorded as an animated GIF, anything else is
+/// created as a directory of numbered PNGs.
+pub struct Recorder {
+    output: Output,
+    size: Size,
+    fps: u32,
+    frames: u64,
+}
+
+impl Recorder {
+    pub fn new(path: &Path, size: Size, fps: u32) -> Result<Self> {
+        if fps == 0 {
+            return Err(anyhow!("Recording fps must be greater than 0"));
+        }
+
+        let is_gif = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+
+        let output = if is_gif {
+            let (width, height) = match (u16::try_from(size.width), u16::try_from(size.height)) {
+                (Ok(width), Ok(height)) => (width, height),
+                _ => return Err(anyhow!("{size:?} is too large to record as a GIF")),
+            };
+
+            let file = File::create(path)
+                .map_err(|e| anyhow!("Could not create recording {}: {e}", path.display()))?;
+            let mut encoder = Encoder::new(BufWriter::new(file), width, height, &[])?;
+            encoder.set_repeat(Repeat::Infinite)?;
+
+            Output::Gif {
+                encoder,
+                pending: None,
+            }
+        } else {
+            fs::create_dir_all(path).map_err(|e| {
+                anyhow!(
+                    "Could not create recording directory {}: {e}",
+                    path.display()
+                )
+            })?;
+
+            Output::Png {
+                dir: path.to_path_buf(),
+            }
+        };
+
+        Ok(Self {
+            output,
+            size,
+            fps,
+            frames: 0,
+        })
+    }
+
+    /// The number of frames captured so far
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Captures the frame currently shown on `canvas`.
+    pub fn capture(&mut self, canvas: &SimulatorDisplay<Rgb888>) -> Result<()> {
+        if canvas.size() != self.size {
+            return Err(anyhow!(
+                "Captured a {:?} frame but the recording is {:?}",
+                canvas.size(),
+                self.size
+            ));
+        }
+
+        let image = RgbImage::from_fn(self.size.width, self.size.height, |x, y| {
+            let color = canvas.get_pixel(Point::new(x as i32, y as i32));
+            Rgb([color.r(), color.g(), color.b()])
+        });
+
+        let start = self.frame_time(self.frames);
+        match &mut self.output {
+            Output::Gif { encoder, pending } => match pending {
+                Some((_, pending_start)) if start - *pending_start < MIN_GIF_DELAY => {
+                    // Too soon after the frame still waiting, which stays shown
+                }
+                _ => {
+                    if let Some((pixels, pending_start)) =
+                        pending.replace((image.into_raw(), start))
+                    {
+                        write_gif_frame(encoder, self.size, &pixels, start - pending_start)?;
+                    }
+                }
+            },
+            Output::Png { dir } => {
+                let path = dir.join(format!("frame_{:05}.png", self.frames));
+                image
+                    .save(&path)
+                    .map_err(|e| anyhow!("Could not write frame {}: {e}", path.display()))?;
+            }
+        }
+
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Writes out the last frame and closes the recording.
+    pub fn finish(mut self) -> Result<()> {
+        let end = self.frame_time(self.frames);
+        if let Output::Gif { encoder, pending } = &mut self.output {
+            if let Some((pixels, pending_start)) = pending.take() {
+                write_gif_frame(encoder, self.size, &pixels, end - pending_start)?;
+            }
+        }
+
+        // Dropping the encoder writes the GIF trailer
+        Ok(())
+    }
+
+    /// When frame `index` starts, in hundredths of a second, rounded to the
+    /// nearest so rounding errors do not add up over the recording
+    fn frame_time(&self, index: u64) -> u64 {
+        let fps = u64::from(self.fps);
+        (index * 100 + fps / 2) / fps
+    }
+}
+
+fn write_gif_frame(
+    encoder: &mut Encoder<BufWriter<File>>,
+    size: Size,
+    pixels: &[u8],
+    delay: u64,
+) -> Result<()> {
+    let mut frame = Frame::from_rgb_speed(
+        size.width as u16,
+        size.height as u16,
+        pixels,
+        GIF_QUANTIZE_SPEED,
+    );
+    frame.delay = delay.clamp(1, u64::from(u16::MAX)) as u16;
+    encoder.write_frame(&frame)?;
+    Ok(())
+}
@@ -0,0 +1,502 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use embedded_graphics::{
+    mono_font::{self, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    text::{Baseline, Text},
+    Drawable,
+};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    font::{latin1_lossy, Font},
+    render::{
+        BufferCanvas, ExampleConfig, Render, RenderError, RenderFactory, Spinner, SyncRender,
+    },
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+
+use crate::{
+    data_source::{DataSource, PollingSource},
+    line_colors::{parse_hex_color, DEFAULT_LINE_COLOR},
+    loading::{draw_loading, loading_spinner},
+    request::{
+        refresh_interval, request_timeout, traced, validate_refresh_interval, STALE_AFTER_REFRESHES,
+    },
+};
+
+const BUS_SCHEDULES_URL: &str = "https://www3.septa.org/api/BusSchedules/index.php";
+
+/// Format of the `DateCalender` field of a scheduled stop, i.e.
+/// "10/16/26 02:56 pm"
+const DATE_CALENDER_FORMAT: &str = "%m/%d/%y %I:%M %p";
+
+const DEFAULT_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_5X7;
+
+/// How many arrivals are shown if no limit is configured
+const DEFAULT_RESULTS: usize = 4;
+
+/// How long to wait between refreshes if no refresh interval is configured
+const DEFAULT_REFRESH: Duration = Duration::from_secs(60);
+
+/// How long to wait after a failed request if no retry interval is
+/// configured
+const DEFAULT_ERROR_RETRY: Duration = Duration::from_secs(60);
+
+/// Space between the columns of an arrival
+const COLUMN_SPACING: &str = " ";
+
+/// Configuration for the [`BusArrivalsFactory`], for example:
+///
+/// ```json
+/// {
+///   "route": "44",
+///   "stop_id": "31400"
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct BusArrivalsConfig {
+    /// The SEPTA bus or trolley route, i.e. "44" or "10"
+    pub route: String,
+
+    /// The SEPTA stop id of the stop to show the arrivals of
+    pub stop_id: String,
+
+    /// How many arrivals are shown. Defaults to 4
+    pub results: Option<u8>,
+
+    /// Color of the route, written as `#RRGGBB`. Defaults to white
+    pub route_color: Option<String>,
+
+    /// Font of the arrivals, defaults to 5x7 if not provided
+    pub font: Option<Font>,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How long, in seconds, to wait between refreshes of the arrivals.
+    /// Defaults to [`DEFAULT_REFRESH`] if not provided
+    pub refresh_secs: Option<u64>,
+
+    /// How long, in seconds, to wait before trying again when the arrivals
+    /// could not be fetched. Defaults to [`DEFAULT_ERROR_RETRY`] if not provided
+    pub error_retry_secs: Option<u64>,
+}
+
+impl BusArrivalsConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.route.is_empty() {
+            return Err(anyhow!("route must not be empty"));
+        }
+
+        if self.stop_id.is_empty() {
+            return Err(anyhow!("stop_id must not be empty"));
+        }
+
+        if let Some(route_color) = &self.route_color {
+            parse_hex_color(route_color)?;
+        }
+
+        validate_refresh_interval("refresh_secs", self.refresh_secs)?;
+        validate_refresh_interval("error_retry_secs", self.error_retry_secs)
+    }
+}
+
+/// A scheduled stop returned by the SEPTA bus schedules endpoint
+#[derive(Debug, Deserialize)]
+struct ScheduledStop {
+    #[serde(rename = "StopName")]
+    stop_name: String,
+
+    #[serde(rename = "Route")]
+    route: String,
+
+    /// The date and time of the stop, see [`DATE_CALENDER_FORMAT`]
+    #[serde(rename = "DateCalender")]
+    date_calender: String,
+
+    /// Where the trip is heading, i.e. "5th-Market"
+    #[serde(rename = "DirectionDesc")]
+    direction_desc: String,
+}
+
+/// Unlike the regional rail endpoints, the bus schedules endpoint returns an
+/// object keyed by route, or an object with an `error` if the stop or route is
+/// unknown
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BusSchedulesResponse {
+    Schedules(HashMap<String, Vec<ScheduledStop>>),
+    Error { error: String },
+}
+
+/// An upcoming arrival at the configured stop
+#[derive(Debug, Clone)]
+struct BusArrival {
+    route: String,
+
+    /// Where the trip is heading
+    destination: String,
+
+    /// When the trip is scheduled to arrive at the stop
+    time: DateTime<Local>,
+}
+
+impl BusArrival {
+    /// The time left until the arrival, i.e. "Due" or "12 min"
+    fn countdown(&self, now: DateTime<Local>) -> String {
+        match self.time.signed_duration_since(now).num_minutes() {
+            mins if mins < 1 => "Due".to_owned(),
+            mins => format!("{mins} min"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct BusArrivalsState {
+    arrivals: Vec<BusArrival>,
+
+    /// The name of the stop as reported by SEPTA
+    stop_name: Option<String>,
+
+    /// When the arrivals were last fetched
+    refreshed_at: Option<Instant>,
+}
+
+/// The name of a stop, if SEPTA reported it, along with its upcoming arrivals
+type StopArrivals = (Option<String>, Vec<BusArrival>);
+
+/// Fetches the upcoming arrivals of `route` at `stop_id`, soonest first, along
+/// with the name of the stop.
+async fn fetch_arrivals(
+    client: &reqwest::Client,
+    route: &str,
+    stop_id: &str,
+    results: usize,
+) -> Result<StopArrivals> {
+    let results = results.to_string();
+    let response: BusSchedulesResponse = client
+        .get(BUS_SCHEDULES_URL)
+        .query(&[
+            ("stop_id", stop_id),
+            ("req2", route),
+            ("req6", results.as_str()),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let stops = match response {
+        BusSchedulesResponse::Schedules(mut schedules) => {
+            schedules.remove(route).unwrap_or_default()
+        }
+        BusSchedulesResponse::Error { error } => return Err(anyhow!("{error}")),
+    };
+
+    let stop_name = stops.first().map(|stop| stop.stop_name.clone());
+
+    let mut arrivals = stops
+        .into_iter()
+        .filter_map(|stop| {
+            let time =
+                NaiveDateTime::parse_from_str(&stop.date_calender, DATE_CALENDER_FORMAT).ok()?;
+
+            Some(BusArrival {
+                route: stop.route,
+                destination: stop.direction_desc,
+                time: Local.from_local_datetime(&time).single()?,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    arrivals.sort_by_key(|arrival| arrival.time);
+
+    Ok((stop_name, arrivals))
+}
+
+/// Upcoming SEPTA bus or trolley arrivals at a single stop.
+///
+/// Each row shows the route, a countdown to the scheduled arrival and where the
+/// trip is heading, below the name of the stop.
+pub struct BusArrivals {
+    state: Mutex<BusArrivalsState>,
+
+    /// Where the arrivals come from
+    source: Box<dyn DataSource<StopArrivals>>,
+
+    /// Watches the source for new arrivals
+    arrivals: Mutex<watch::Receiver<Option<StopArrivals>>>,
+
+    results: usize,
+    route_color: Rgb888,
+    font: &'static MonoFont<'static>,
+
+    /// How long the arrivals can go without a refresh before the render
+    /// reports it has no content
+    stale_after: Duration,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+}
+
+impl BusArrivals {
+    pub fn new(config: BusArrivalsConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(request_timeout(config.request_timeout_secs))
+            .build()?;
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let error_retry = refresh_interval(config.error_retry_secs, DEFAULT_ERROR_RETRY);
+        let results = config.results.map(usize::from).unwrap_or(DEFAULT_RESULTS);
+        let route_color = match &config.route_color {
+            Some(route_color) => parse_hex_color(route_color)?,
+            None => DEFAULT_LINE_COLOR,
+        };
+
+        let route = Arc::new(config.route.clone());
+        let stop_id = Arc::new(config.stop_id.clone());
+
+        let source = PollingSource::new("BusArrivals", refresh, error_retry, move || {
+            let client = client.clone();
+            let route = route.clone();
+            let stop_id = stop_id.clone();
+
+            async move {
+                traced(
+                    "BusArrivals",
+                    format!("septa/bus_schedules/{route}/{stop_id}"),
+                    fetch_arrivals(&client, &route, &stop_id, results),
+                )
+                .await
+                .map_err(|e| anyhow!("for route {route} at {stop_id} {e}"))
+            }
+        });
+        let source: Box<dyn DataSource<StopArrivals>> = Box::new(source);
+
+        Ok(Self {
+            state: Mutex::new(BusArrivalsState::default()),
+            arrivals: Mutex::new(source.subscribe()),
+            source,
+            results,
+            route_color,
+            font: config
+                .font
+                .map(|font| font.mono_font())
+                .unwrap_or(DEFAULT_FONT),
+            stale_after: refresh * STALE_AFTER_REFRESHES,
+            loading_spinner: loading_spinner(),
+        })
+    }
+
+    /// Takes the latest arrivals of the source, if they changed since they
+    /// were last taken
+    fn update_arrivals(&self) {
+        let mut arrivals = self.arrivals.lock();
+
+        if arrivals.has_changed().unwrap_or(false) {
+            if let Some((stop_name, arrivals)) = &*arrivals.borrow_and_update() {
+                let mut state = self.state.lock();
+                state.arrivals = arrivals.clone();
+                if stop_name.is_some() {
+                    state.stop_name = stop_name.clone();
+                }
+                state.refreshed_at = Some(Instant::now());
+            }
+        }
+    }
+}
+
+impl<D> Render<D> for BusArrivals
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.update_arrivals();
+        let state = self.state.lock();
+
+        if state.refreshed_at.is_none() {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let mut top_left = canvas.bounding_box().top_left;
+        let row_height = self.font.character_size.height as i32;
+
+        if let Some(stop_name) = &state.stop_name {
+            Text::with_baseline(
+                &latin1_lossy(stop_name),
+                top_left,
+                MonoTextStyle::new(self.font, Rgb888::YELLOW),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            top_left.y += row_height;
+        }
+
+        // Arrivals that passed since the last refresh are dropped
+        let now = Local::now();
+        let arrivals = state
+            .arrivals
+            .iter()
+            .filter(|arrival| arrival.time >= now)
+            .take(self.results)
+            .collect::<Vec<_>>();
+
+        if arrivals.is_empty() {
+            Text::with_baseline(
+                "No arrivals",
+                top_left,
+                MonoTextStyle::new(self.font, Rgb888::WHITE),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            return Ok(());
+        }
+
+        // Pad the routes and countdowns so the destinations line up
+        let route_width = arrivals
+            .iter()
+            .map(|arrival| arrival.route.chars().count())
+            .max()
+            .unwrap_or(0);
+        let countdowns = arrivals
+            .iter()
+            .map(|arrival| arrival.countdown(now))
+            .collect::<Vec<_>>();
+        let countdown_width = countdowns
+            .iter()
+            .map(|countdown| countdown.len())
+            .max()
+            .unwrap_or(0);
+
+        for (row, (arrival, countdown)) in arrivals.iter().zip(&countdowns).enumerate() {
+            let position = top_left + Point::new(0, row as i32 * row_height);
+
+            let position = Text::with_baseline(
+                &format!(
+                    "{:<route_width$}{COLUMN_SPACING}",
+                    latin1_lossy(&arrival.route)
+                ),
+                position,
+                MonoTextStyle::new(self.font, self.route_color),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            let position = Text::with_baseline(
+                &format!("{countdown:>countdown_width$}{COLUMN_SPACING}"),
+                position,
+                MonoTextStyle::new(self.font, Rgb888::GREEN),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            Text::with_baseline(
+                &latin1_lossy(&arrival.destination),
+                position,
+                MonoTextStyle::new(self.font, Rgb888::WHITE),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.source.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.source.pause();
+    }
+
+    fn describe(&self) -> String {
+        self.update_arrivals();
+        let state = self.state.lock();
+
+        match state.refreshed_at {
+            Some(_) => format!("BusArrivals: {} arrivals", state.arrivals.len()),
+            None => "BusArrivals: loading".to_owned(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        self.update_arrivals();
+        match self.state.lock().refreshed_at {
+            Some(refreshed_at) => refreshed_at.elapsed() < self.stale_after,
+            None => false,
+        }
+    }
+}
+
+pub struct BusArrivalsFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for BusArrivalsFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for BusArrivalsFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "BusArrivals"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Upcoming SEPTA bus or trolley arrivals at a stop"
+    }
+
+    fn example_config(&self) -> Option<ExampleConfig> {
+        Some(json!({
+            "route": "44",
+            "stop_id": "31400"
+        }))
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: BusArrivalsConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(BusArrivals::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: BusArrivalsConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(BusArrivals::new(config)?))
+    }
+}
@@ -0,0 +1,675 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    mono_font::{self, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, DrawTargetExt, Point, Primitive, RgbColor, Size},
+    primitives::{Circle, PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+    Drawable,
+};
+use log::error;
+use parking_lot::Mutex;
+use reqwest::{header::HeaderMap, StatusCode};
+use rustic_pixel_display::{
+    font::{latin1_lossy, Font},
+    render::{
+        animation_time, BufferCanvas, ExampleConfig, Render, RenderError, RenderFactory, Spinner,
+        SyncRender,
+    },
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::watch;
+
+use crate::{
+    data_source::{DataSource, PollingSource, RateLimited},
+    loading::{draw_loading, loading_spinner},
+    request::{
+        refresh_interval, request_timeout, traced, validate_refresh_interval, STALE_AFTER_REFRESHES,
+    },
+    scroll::{scroll_position, text_width},
+};
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// GitHub rejects requests without a user agent
+const USER_AGENT: &str = "rustic-pixel-display";
+
+const DEFAULT_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_5X7;
+
+/// How long to wait between refreshes if no refresh interval is configured
+const DEFAULT_REFRESH: Duration = Duration::from_secs(2 * 60);
+
+/// How long to wait after a failed request if no retry interval is
+/// configured
+const DEFAULT_ERROR_RETRY: Duration = Duration::from_secs(60);
+
+/// The longest the update task backs off for after repeated failures
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+/// How fast, in pixels per second, commit messages that do not fit on the
+/// canvas scroll
+const SCROLL_SPEED: u64 = 15;
+
+/// Color of the builds that could not be refreshed recently
+const STALE_COLOR: Rgb888 = Rgb888::new(128, 128, 128);
+
+/// Where the build statuses are read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CiProvider {
+    /// The latest GitHub Actions workflow run of every configured repository
+    Github,
+
+    /// A JSON file kept up to date by something else, i.e. a webhook receiver,
+    /// holding a list of `{ "name", "status", "message" }` entries
+    File,
+}
+
+/// A repository, and optionally a branch, to show the latest build of
+#[derive(Debug, Clone, Deserialize)]
+pub struct CiRepoConfig {
+    pub owner: String,
+    pub repo: String,
+
+    /// Only builds of this branch are shown, builds of any branch if not
+    /// provided
+    pub branch: Option<String>,
+}
+
+impl CiRepoConfig {
+    /// The name shown on the panel, i.e. "rustic-pixel-display@main"
+    fn display_name(&self) -> String {
+        match &self.branch {
+            Some(branch) => format!("{}@{branch}", self.repo),
+            None => self.repo.clone(),
+        }
+    }
+}
+
+/// Configuration for the [`CiStatusFactory`], for example:
+///
+/// ```json
+/// {
+///   "provider": "github",
+///   "token": "ghp_...",
+///   "repos": [{ "owner": "StefanBossbaly", "repo": "rustic-pixel-display", "branch": "main" }]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct CiStatusConfig {
+    pub provider: CiProvider,
+
+    /// GitHub access token, needed for private repositories and for a higher
+    /// rate limit
+    pub token: Option<String>,
+
+    /// The repositories to show, used by the `github` provider
+    #[serde(default)]
+    pub repos: Vec<CiRepoConfig>,
+
+    /// Path of the status file, used by the `file` provider
+    pub path: Option<String>,
+
+    /// Font of the builds, defaults to 5x7 if not provided
+    pub font: Option<Font>,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How long, in seconds, to wait between refreshes of the builds. Defaults
+    /// to [`DEFAULT_REFRESH`] if not provided
+    pub refresh_secs: Option<u64>,
+
+    /// How long, in seconds, to wait before trying again when the builds could
+    /// not be fetched. Doubles with every failure in a row, up to
+    /// [`MAX_BACKOFF`]. Defaults to [`DEFAULT_ERROR_RETRY`] if not provided
+    pub error_retry_secs: Option<u64>,
+}
+
+impl CiStatusConfig {
+    pub fn validate(&self) -> Result<()> {
+        match self.provider {
+            CiProvider::Github => {
+                if self.repos.is_empty() {
+                    return Err(anyhow!("repos must not be empty"));
+                }
+
+                if let Some(repo) = self
+                    .repos
+                    .iter()
+                    .find(|repo| repo.owner.is_empty() || repo.repo.is_empty())
+                {
+                    return Err(anyhow!(
+                        "owner and repo must not be empty, got \"{}/{}\"",
+                        repo.owner,
+                        repo.repo
+                    ));
+                }
+            }
+            CiProvider::File => {
+                if self.path.as_deref().map_or(true, str::is_empty) {
+                    return Err(anyhow!("path is required for the file provider"));
+                }
+            }
+        }
+
+        validate_refresh_interval("refresh_secs", self.refresh_secs)?;
+        validate_refresh_interval("error_retry_secs", self.error_retry_secs)
+    }
+}
+
+/// The outcome of a build
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildStatus {
+    Success,
+    Failure,
+    Running,
+
+    /// Cancelled, skipped or anything else that is neither passing nor failing
+    #[serde(other)]
+    Unknown,
+}
+
+impl BuildStatus {
+    fn color(&self) -> Rgb888 {
+        match self {
+            BuildStatus::Success => Rgb888::GREEN,
+            BuildStatus::Failure => Rgb888::RED,
+            BuildStatus::Running => Rgb888::YELLOW,
+            BuildStatus::Unknown => STALE_COLOR,
+        }
+    }
+}
+
+/// The latest build of a repository, also the format of the entries of the
+/// status file read by the `file` provider
+#[derive(Debug, Clone, Deserialize)]
+struct Build {
+    name: String,
+    status: BuildStatus,
+
+    /// The first line of the message of the commit that was built
+    #[serde(default)]
+    message: String,
+}
+
+/// A workflow run returned by the GitHub Actions API
+#[derive(Debug, Deserialize)]
+struct WorkflowRun {
+    /// "queued", "in_progress" or "completed"
+    status: String,
+
+    /// "success", "failure", "cancelled"... once the run completed
+    conclusion: Option<String>,
+
+    head_commit: Option<HeadCommit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HeadCommit {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkflowRuns {
+    workflow_runs: Vec<WorkflowRun>,
+}
+
+impl WorkflowRun {
+    fn build_status(&self) -> BuildStatus {
+        match (self.status.as_str(), self.conclusion.as_deref()) {
+            ("completed", Some("success")) => BuildStatus::Success,
+            ("completed", Some("failure" | "timed_out" | "startup_failure")) => {
+                BuildStatus::Failure
+            }
+            ("completed", _) => BuildStatus::Unknown,
+            _ => BuildStatus::Running,
+        }
+    }
+}
+
+/// Reads how long to wait from the `retry-after` header, or the
+/// `x-ratelimit-reset` header once the quota is used up
+fn rate_limit_delay(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+
+    if let Some(secs) = header("retry-after") {
+        return Some(Duration::from_secs(secs));
+    }
+
+    if header("x-ratelimit-remaining") == Some(0) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        return header("x-ratelimit-reset")
+            .map(|reset| Duration::from_secs(reset.saturating_sub(now)));
+    }
+
+    None
+}
+
+/// Fetches the latest workflow run of `repo`.
+async fn fetch_github_build(
+    client: &reqwest::Client,
+    token: Option<&str>,
+    repo: &CiRepoConfig,
+) -> Result<Build> {
+    let mut request = client
+        .get(format!(
+            "{GITHUB_API_URL}/repos/{}/{}/actions/runs",
+            repo.owner, repo.repo
+        ))
+        .header("Accept", "application/vnd.github+json")
+        .query(&[("per_page", "1")]);
+
+    if let Some(branch) = &repo.branch {
+        request = request.query(&[("branch", branch)]);
+    }
+
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request.send().await?;
+
+    // GitHub uses 403 for a used up quota and 429 for secondary rate limits
+    if matches!(
+        response.status(),
+        StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+    ) {
+        if let Some(retry_after) = rate_limit_delay(response.headers()) {
+            return Err(RateLimited { retry_after }.into());
+        }
+    }
+
+    let runs: WorkflowRuns = response.error_for_status()?.json().await?;
+    let run = runs
+        .workflow_runs
+        .first()
+        .ok_or_else(|| anyhow!("No workflow runs"))?;
+
+    Ok(Build {
+        name: repo.display_name(),
+        status: run.build_status(),
+        message: run
+            .head_commit
+            .as_ref()
+            .and_then(|commit| commit.message.lines().next())
+            .unwrap_or_default()
+            .to_owned(),
+    })
+}
+
+/// Reads the builds from the status file at `path`.
+async fn read_build_file(path: &str) -> Result<Vec<Build>> {
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| anyhow!("Could not read {path}: {e}"))?;
+
+    serde_json::from_str(&contents).map_err(|e| anyhow!("Could not parse {path}: {e}"))
+}
+
+/// A build along with when it was last refreshed
+#[derive(Debug, Clone)]
+struct BuildEntry {
+    build: Build,
+    refreshed_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct CiStatusState {
+    builds: Vec<BuildEntry>,
+
+    /// When any build was last refreshed
+    refreshed_at: Option<Instant>,
+}
+
+impl CiStatusState {
+    /// Replaces the build named like `build`, or adds it if it is new
+    fn update(&mut self, build: Build) {
+        let now = Instant::now();
+        let entry = BuildEntry {
+            build,
+            refreshed_at: now,
+        };
+
+        match self
+            .builds
+            .iter_mut()
+            .find(|existing| existing.build.name == entry.build.name)
+        {
+            Some(existing) => *existing = entry,
+            None => self.builds.push(entry),
+        }
+
+        self.refreshed_at = Some(now);
+    }
+}
+
+/// Fetches every build of `config`, keeping the builds that could not be
+/// fetched as they were. Fails if none could be fetched, with the rate limit
+/// if one was hit.
+async fn refresh_builds(
+    client: &reqwest::Client,
+    config: &CiStatusConfig,
+    state: &Mutex<CiStatusState>,
+) -> Result<()> {
+    match config.provider {
+        CiProvider::Github => {
+            let mut fetched = 0;
+            let mut last_error = None;
+
+            for repo in &config.repos {
+                let build = traced(
+                    "CiStatus",
+                    format!("github/{}/{}", repo.owner, repo.repo),
+                    fetch_github_build(client, config.token.as_deref(), repo),
+                )
+                .await;
+
+                match build {
+                    Ok(build) => {
+                        state.lock().update(build);
+                        fetched += 1;
+                    }
+                    // The rest of the repositories would be rate limited too
+                    Err(e) if e.is::<RateLimited>() => return Err(e),
+                    Err(e) => {
+                        error!(
+                            "Could not get the CI status of {}/{} {e}",
+                            repo.owner, repo.repo
+                        );
+                        last_error = Some(e);
+                    }
+                }
+            }
+
+            // Only back off if nothing could be fetched
+            match last_error {
+                Some(e) if fetched == 0 => Err(e),
+                _ => Ok(()),
+            }
+        }
+        CiProvider::File => {
+            let builds = read_build_file(config.path.as_deref().unwrap_or_default()).await?;
+
+            let mut state = state.lock();
+            state
+                .builds
+                .retain(|entry| builds.iter().any(|build| build.name == entry.build.name));
+            for build in builds {
+                state.update(build);
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// The latest CI build of a list of repositories.
+///
+/// Every build is shown as a dot colored by its outcome, green for passing,
+/// red for failing and yellow while running, followed by its name, with the
+/// commit message scrolling below. Builds that have not been refreshed for a
+/// while are grayed out and marked with a `*`.
+pub struct CiStatus {
+    state: Mutex<CiStatusState>,
+
+    /// Where the builds come from
+    source: Box<dyn DataSource<Vec<BuildEntry>>>,
+
+    /// Watches the source for new builds
+    builds: Mutex<watch::Receiver<Option<Vec<BuildEntry>>>>,
+
+    font: &'static MonoFont<'static>,
+
+    /// How long a build can go without a refresh before it is marked as stale
+    stale_after: Duration,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+}
+
+impl CiStatus {
+    pub fn new(config: CiStatusConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(request_timeout(config.request_timeout_secs))
+            .user_agent(USER_AGENT)
+            .build()?;
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let error_retry = refresh_interval(config.error_retry_secs, DEFAULT_ERROR_RETRY);
+        let font = config
+            .font
+            .map(|font| font.mono_font())
+            .unwrap_or(DEFAULT_FONT);
+
+        // Builds that could not be fetched are kept as they were, so every
+        // fetch starts from the builds of the last one
+        let fetched = Arc::new(Mutex::new(CiStatusState::default()));
+        let config = Arc::new(config);
+
+        let source =
+            PollingSource::with_backoff("CiStatus", refresh, error_retry, MAX_BACKOFF, move || {
+                let client = client.clone();
+                let config = config.clone();
+                let fetched = fetched.clone();
+
+                async move {
+                    refresh_builds(&client, &config, &fetched).await?;
+
+                    let builds = fetched.lock().builds.clone();
+                    Ok(builds)
+                }
+            });
+        let source: Box<dyn DataSource<Vec<BuildEntry>>> = Box::new(source);
+
+        Ok(Self {
+            state: Mutex::new(CiStatusState::default()),
+            builds: Mutex::new(source.subscribe()),
+            source,
+            font,
+            stale_after: refresh * STALE_AFTER_REFRESHES,
+            loading_spinner: loading_spinner(),
+        })
+    }
+
+    /// Takes the latest builds of the source, if they changed since they were
+    /// last taken
+    fn update_builds(&self) {
+        let mut builds = self.builds.lock();
+
+        if builds.has_changed().unwrap_or(false) {
+            if let Some(builds) = &*builds.borrow_and_update() {
+                let mut state = self.state.lock();
+                state.builds = builds.clone();
+                state.refreshed_at = Some(Instant::now());
+            }
+        }
+    }
+}
+
+impl<D> Render<D> for CiStatus
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        self.update_builds();
+        let state = self.state.lock();
+
+        if state.refreshed_at.is_none() {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let bounding_box = canvas.bounding_box();
+        let row_height = self.font.character_size.height as i32;
+        let dot_diameter = self.font.character_size.height.saturating_sub(2).max(1);
+        let mut top_left = bounding_box.top_left;
+
+        for entry in &state.builds {
+            let is_stale = entry.refreshed_at.elapsed() >= self.stale_after;
+
+            let (dot_color, name, name_color) = if is_stale {
+                (STALE_COLOR, format!("{}*", entry.build.name), STALE_COLOR)
+            } else {
+                (
+                    entry.build.status.color(),
+                    entry.build.name.clone(),
+                    Rgb888::WHITE,
+                )
+            };
+
+            Circle::new(top_left + Point::new(0, 1), dot_diameter)
+                .into_styled(PrimitiveStyle::with_fill(dot_color))
+                .draw(canvas)?;
+
+            Text::with_baseline(
+                &latin1_lossy(&name),
+                top_left + Point::new(dot_diameter as i32 + 2, 0),
+                MonoTextStyle::new(self.font, name_color),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+            top_left.y += row_height;
+
+            // The message scrolls within its own row
+            let message = latin1_lossy(&entry.build.message);
+            let message_row = Rectangle::new(
+                top_left,
+                Size::new(bounding_box.size.width, row_height as u32),
+            );
+            let x = scroll_position(
+                text_width(&message, self.font),
+                bounding_box.size.width,
+                SCROLL_SPEED,
+                animation_time(now),
+            );
+
+            Text::with_baseline(
+                &message,
+                top_left + Point::new(x, 0),
+                MonoTextStyle::new(self.font, STALE_COLOR),
+                Baseline::Top,
+            )
+            .draw(&mut canvas.clipped(&message_row))?;
+            top_left.y += row_height + 1;
+        }
+
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.source.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.source.pause();
+    }
+
+    fn describe(&self) -> String {
+        self.update_builds();
+        let state = self.state.lock();
+
+        if state.refreshed_at.is_none() {
+            return "CiStatus: loading".to_owned();
+        }
+
+        let failing = state
+            .builds
+            .iter()
+            .filter(|entry| entry.build.status == BuildStatus::Failure)
+            .count();
+        format!(
+            "CiStatus: {failing} of {} builds failing",
+            state.builds.len()
+        )
+    }
+
+    fn has_content(&self) -> bool {
+        self.update_builds();
+        match self.state.lock().refreshed_at {
+            Some(refreshed_at) => refreshed_at.elapsed() < self.stale_after,
+            None => false,
+        }
+    }
+}
+
+pub struct CiStatusFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for CiStatusFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for CiStatusFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "CiStatus"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Passing, failing and running CI builds of a list of repositories"
+    }
+
+    fn example_config(&self) -> Option<ExampleConfig> {
+        Some(json!({
+            "provider": "github",
+            "repos": [
+                { "owner": "StefanBossbaly", "repo": "rustic-pixel-display", "branch": "main" }
+            ]
+        }))
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: CiStatusConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(CiStatus::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: CiStatusConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(CiStatus::new(config)?))
+    }
+}
@@ -0,0 +1,470 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset, Local};
+use embedded_graphics::{
+    mono_font::{self, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, RgbColor},
+    text::{Baseline, Text},
+    Drawable,
+};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    font::latin1_lossy,
+    render::{
+        BufferCanvas, ExampleConfig, Render, RenderError, RenderFactory, Spinner, SyncRender,
+    },
+};
+use septa_api::types::RegionalRailStop;
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+
+use crate::{
+    data_source::{DataSource, PollingSource, PushSource},
+    format::{format_time, TimeFormatConfig},
+    loading::{draw_loading, loading_spinner},
+    renders::upcoming_arrivals::{
+        septa_provider::{self, SeptaProvider},
+        UpcomingTrain,
+    },
+    request::{
+        refresh_interval, request_timeout, traced, validate_refresh_interval, with_timeout,
+        STALE_AFTER_REFRESHES,
+    },
+};
+
+const TITLE_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_5X7;
+const COUNTDOWN_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_10X20;
+const DETAIL_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_5X7;
+
+/// Space between the lines of the render
+const LINE_SPACING: i32 = 2;
+
+const TITLE_COLOR: Rgb888 = Rgb888::YELLOW;
+const COUNTDOWN_COLOR: Rgb888 = Rgb888::GREEN;
+const DETAIL_COLOR: Rgb888 = Rgb888::WHITE;
+
+/// Trains that leave before you can walk to the station
+const MISSED_COLOR: Rgb888 = Rgb888::new(96, 96, 96);
+
+/// How long to wait between refreshes if no refresh interval is configured
+const DEFAULT_REFRESH: Duration = Duration::from_secs(60);
+
+/// How long to wait after a failed request if no retry interval is
+/// configured
+const DEFAULT_ERROR_RETRY: Duration = Duration::from_secs(60);
+
+/// Configuration for the [`CommuteFactory`], for example:
+///
+/// ```json
+/// {
+///   "station": "Suburban Station",
+///   "destination": "Paoli",
+///   "walk_minutes": 8
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommuteConfig {
+    /// The station the commute starts from
+    pub station: RegionalRailStop,
+
+    /// Only trains whose destination contains this, ignoring case, are shown
+    pub destination: String,
+
+    /// How long, in minutes, it takes to walk to the station. Trains leaving
+    /// sooner than this are shown as missed and the countdown is of the first
+    /// train that can still be caught. Defaults to 0
+    #[serde(default)]
+    pub walk_minutes: u32,
+
+    /// How the departure times are displayed
+    #[serde(default)]
+    pub time_format: TimeFormatConfig,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How long, in seconds, to wait between refreshes of the arrivals.
+    /// Defaults to [`DEFAULT_REFRESH`] if not provided
+    pub refresh_secs: Option<u64>,
+
+    /// How long, in seconds, to wait before trying again when the arrivals
+    /// could not be fetched. Defaults to [`DEFAULT_ERROR_RETRY`] if not
+    /// provided
+    pub error_retry_secs: Option<u64>,
+}
+
+impl CommuteConfig {
+    pub fn validate(&self) -> Result<()> {
+        septa_provider::validate_station(&self.station)?;
+
+        if self.destination.trim().is_empty() {
+            return Err(anyhow!("destination must not be empty"));
+        }
+
+        validate_refresh_interval("refresh_secs", self.refresh_secs)?;
+        validate_refresh_interval("error_retry_secs", self.error_retry_secs)
+    }
+}
+
+#[derive(Debug, Default)]
+struct CommuteState {
+    /// The arrivals heading to the destination, soonest first
+    arrivals: Vec<UpcomingTrain>,
+
+    /// When the arrivals were last fetched
+    refreshed_at: Option<Instant>,
+}
+
+/// Formats how long until `departure` as a countdown, i.e. "12 min".
+fn format_countdown(departure: &DateTime<FixedOffset>, now: &DateTime<FixedOffset>) -> String {
+    match (*departure - *now).num_minutes() {
+        mins if mins < 1 => "Now".to_owned(),
+        mins => format!("{mins} min"),
+    }
+}
+
+/// The arrivals heading to `destination`, which must be lowercase, soonest
+/// first.
+fn heading_to(arrivals: Vec<UpcomingTrain>, destination: &str) -> Vec<UpcomingTrain> {
+    let mut arrivals = arrivals
+        .into_iter()
+        .filter(|train| train.destination_name.to_lowercase().contains(destination))
+        .collect::<Vec<_>>();
+    arrivals.sort_by_key(UpcomingTrain::expected_arrival);
+
+    arrivals
+}
+
+/// Whether there is still time to walk to the station before `train` leaves.
+///
+/// Uses the expected departure, so a late train can still be caught after its
+/// scheduled time.
+fn can_make_it(
+    train: &UpcomingTrain,
+    now: &DateTime<FixedOffset>,
+    walk_time: chrono::Duration,
+) -> bool {
+    train.expected_arrival() - *now >= walk_time
+}
+
+/// The next SEPTA train to a destination that can be caught, counting down
+/// to when it leaves, along with the one after it and any trains leaving
+/// sooner than the walk to the station.
+pub struct Commute {
+    state: Mutex<CommuteState>,
+
+    /// Where the arrivals heading to the destination come from
+    source: Box<dyn DataSource<Vec<UpcomingTrain>>>,
+
+    /// Watches the source for new arrivals
+    arrivals: Mutex<watch::Receiver<Option<Vec<UpcomingTrain>>>>,
+
+    destination: String,
+
+    /// How long it takes to walk to the station
+    walk_time: chrono::Duration,
+
+    time_format: TimeFormatConfig,
+
+    /// How long the arrivals can go without a refresh before the render
+    /// reports it has no content
+    stale_after: Duration,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+}
+
+impl Commute {
+    pub fn new(config: CommuteConfig) -> Result<Self> {
+        let timeout = request_timeout(config.request_timeout_secs);
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let error_retry = refresh_interval(config.error_retry_secs, DEFAULT_ERROR_RETRY);
+
+        let septa_client = Arc::new(SeptaProvider::new(config.station.clone()));
+        let destination = config.destination.to_lowercase();
+
+        let source = PollingSource::new("Commute", refresh, error_retry, move || {
+            let septa_client = septa_client.clone();
+            let destination = destination.clone();
+
+            async move {
+                let arrivals = traced(
+                    "Commute",
+                    format!("septa/arrivals/{}", septa_client.station()),
+                    with_timeout(timeout, septa_client.arrivals()),
+                )
+                .await
+                .map_err(|e| anyhow!("for {} {e}", septa_client.station()))?;
+
+                Ok(heading_to(arrivals, &destination))
+            }
+        });
+
+        Ok(Self::with_source(config, Box::new(source), None))
+    }
+
+    /// Shows `arrivals` instead of fetching the arrivals of the configured
+    /// station, i.e. for tests
+    pub fn with_arrivals(config: CommuteConfig, arrivals: Vec<UpcomingTrain>) -> Self {
+        let arrivals = heading_to(arrivals, &config.destination.to_lowercase());
+
+        Self::with_source(config, Box::new(PushSource::default()), Some(arrivals))
+    }
+
+    /// Shows the arrivals of `source`, starting with `arrivals` if there are
+    /// any
+    fn with_source(
+        config: CommuteConfig,
+        source: Box<dyn DataSource<Vec<UpcomingTrain>>>,
+        arrivals: Option<Vec<UpcomingTrain>>,
+    ) -> Self {
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let state = match arrivals {
+            Some(arrivals) => CommuteState {
+                arrivals,
+                refreshed_at: Some(Instant::now()),
+            },
+            None => CommuteState::default(),
+        };
+
+        Self {
+            state: Mutex::new(state),
+            arrivals: Mutex::new(source.subscribe()),
+            source,
+            destination: config.destination,
+            walk_time: chrono::Duration::minutes(config.walk_minutes as i64),
+            time_format: config.time_format,
+            stale_after: refresh * STALE_AFTER_REFRESHES,
+            loading_spinner: loading_spinner(),
+        }
+    }
+
+    /// Takes the latest arrivals of the source, if they changed since they
+    /// were last taken
+    fn update_arrivals(&self) {
+        let mut arrivals = self.arrivals.lock();
+
+        if arrivals.has_changed().unwrap_or(false) {
+            if let Some(arrivals) = &*arrivals.borrow_and_update() {
+                let mut state = self.state.lock();
+                state.arrivals = arrivals.clone();
+                state.refreshed_at = Some(Instant::now());
+            }
+        }
+    }
+}
+
+impl<D> Render<D> for Commute
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.update_arrivals();
+        let state = self.state.lock();
+
+        if state.refreshed_at.is_none() {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let now = Local::now().fixed_offset();
+        let mut position = canvas.bounding_box().top_left;
+
+        // Trains that already left since the last refresh are dropped. Whether
+        // a train can still be caught changes by the minute, so it is worked
+        // out every frame rather than on refresh.
+        let (missed, catchable): (Vec<_>, Vec<_>) = state
+            .arrivals
+            .iter()
+            .filter(|train| train.expected_arrival() >= now)
+            .partition(|train| !can_make_it(train, &now, self.walk_time));
+
+        Text::with_baseline(
+            &latin1_lossy(&format!("Next to {}", self.destination)),
+            position,
+            MonoTextStyle::new(TITLE_FONT, TITLE_COLOR),
+            Baseline::Top,
+        )
+        .draw(canvas)?;
+        position.y += TITLE_FONT.character_size.height as i32 + LINE_SPACING;
+
+        // Fall back to the next train, grayed out, when none can be caught
+        let (next, countdown_color) = match (catchable.first(), missed.first()) {
+            (Some(next), _) => (next, COUNTDOWN_COLOR),
+            (None, Some(next)) => (next, MISSED_COLOR),
+            (None, None) => {
+                Text::with_baseline(
+                    "No trains",
+                    position,
+                    MonoTextStyle::new(DETAIL_FONT, DETAIL_COLOR),
+                    Baseline::Top,
+                )
+                .draw(canvas)?;
+
+                return Ok(());
+            }
+        };
+
+        let expected = next.expected_arrival();
+
+        Text::with_baseline(
+            &format_countdown(&expected, &now),
+            position,
+            MonoTextStyle::new(COUNTDOWN_FONT, countdown_color),
+            Baseline::Top,
+        )
+        .draw(canvas)?;
+        position.y += COUNTDOWN_FONT.character_size.height as i32 + LINE_SPACING;
+
+        let detail = if catchable.is_empty() {
+            "Can't make it".to_owned()
+        } else {
+            let leaves = format_time(&expected, &self.time_format);
+
+            match (expected - self.walk_time - now).num_minutes() {
+                mins if mins < 1 => format!("Leaves {leaves}, go now"),
+                mins => format!("Leaves {leaves}, go in {mins} min"),
+            }
+        };
+
+        Text::with_baseline(
+            &detail,
+            position,
+            MonoTextStyle::new(DETAIL_FONT, DETAIL_COLOR),
+            Baseline::Top,
+        )
+        .draw(canvas)?;
+        position.y += DETAIL_FONT.character_size.height as i32 + LINE_SPACING;
+
+        if let Some(then) = catchable.get(1) {
+            Text::with_baseline(
+                &format!(
+                    "then at {}",
+                    format_time(&then.expected_arrival(), &self.time_format)
+                ),
+                position,
+                MonoTextStyle::new(DETAIL_FONT, DETAIL_COLOR),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+            position.y += DETAIL_FONT.character_size.height as i32 + LINE_SPACING;
+        }
+
+        // Trains leaving before you could get there, so they don't come as a
+        // surprise on the platform
+        if !catchable.is_empty() {
+            for train in &missed {
+                Text::with_baseline(
+                    &format!(
+                        "{} missed",
+                        format_time(&train.expected_arrival(), &self.time_format)
+                    ),
+                    position,
+                    MonoTextStyle::new(DETAIL_FONT, MISSED_COLOR),
+                    Baseline::Top,
+                )
+                .draw(canvas)?;
+                position.y += DETAIL_FONT.character_size.height as i32 + LINE_SPACING;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.source.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.source.pause();
+    }
+
+    fn describe(&self) -> String {
+        self.update_arrivals();
+        let state = self.state.lock();
+
+        match state.refreshed_at {
+            Some(_) => format!(
+                "Commute: {} trains to {}",
+                state.arrivals.len(),
+                self.destination
+            ),
+            None => "Commute: loading".to_owned(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        self.update_arrivals();
+        match self.state.lock().refreshed_at {
+            Some(refreshed_at) => refreshed_at.elapsed() < self.stale_after,
+            None => false,
+        }
+    }
+}
+
+pub struct CommuteFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for CommuteFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for CommuteFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "Commute"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Countdown to the next SEPTA regional rail train to a destination"
+    }
+
+    fn example_config(&self) -> Option<ExampleConfig> {
+        Some(json!({
+            "station": "SuburbanStation",
+            "destination": "Paoli",
+            "walk_minutes": 10
+        }))
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: CommuteConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(Commute::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: CommuteConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(Commute::new(config)?))
+    }
+}
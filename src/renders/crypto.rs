@@ -0,0 +1,380 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    mono_font::{self, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Primitive, RgbColor},
+    primitives::{PrimitiveStyle, Triangle},
+    text::{Baseline, Text},
+    Drawable,
+};
+use log::error;
+use parking_lot::Mutex;
+use reqwest::StatusCode;
+use rustic_pixel_display::{
+    font::Font,
+    render::{BufferCanvas, PollGate, Render, RenderError, RenderFactory, Spinner, SyncRender},
+};
+use serde::Deserialize;
+use std::{convert::Infallible, io::Read, marker::PhantomData, sync::Arc, time::Duration};
+use tokio::{select, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    loading::{draw_loading, loading_spinner},
+    request::request_timeout,
+    supervisor::{supervise, RESTART_DELAY},
+};
+
+const MARKETS_URL: &str = "https://api.coingecko.com/api/v3/coins/markets";
+
+/// How often the prices are refreshed if no interval is configured
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+
+/// The longest the update task backs off for after repeated failures
+const MAX_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+const DEFAULT_FONT: &mono_font::MonoFont = &mono_font::ascii::FONT_6X9;
+const ROW_SPACING: i32 = 2;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CryptoConfig {
+    /// The symbols of the coins to display, i.e. "BTC"
+    pub coins: Vec<String>,
+
+    /// The currency the prices are displayed in, i.e. "usd"
+    pub vs_currency: String,
+
+    /// How often, in seconds, the prices are refreshed
+    pub interval_secs: Option<u64>,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// Defaults to 6x9 if not provided
+    pub font: Option<Font>,
+}
+
+/// A single coin returned by the markets endpoint
+#[derive(Debug, Deserialize)]
+struct MarketEntry {
+    symbol: String,
+    current_price: Option<f64>,
+    price_change_percentage_24h: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct DisplayCoin {
+    symbol: String,
+    price: String,
+    change_24h: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct CryptoState {
+    coins: Vec<DisplayCoin>,
+
+    /// Set when the last attempt to refresh the prices failed
+    is_stale: bool,
+
+    /// Set once the prices have been fetched at least once
+    has_loaded: bool,
+}
+
+async fn fetch_prices(
+    client: &reqwest::Client,
+    coins: &[String],
+    vs_currency: &str,
+) -> Result<Vec<DisplayCoin>> {
+    let symbols = coins
+        .iter()
+        .map(|coin| coin.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let response = client
+        .get(MARKETS_URL)
+        .query(&[("vs_currency", vs_currency), ("symbols", &symbols)])
+        .send()
+        .await?;
+
+    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+        return Err(anyhow!("Rate limited by the price API"));
+    }
+
+    let markets: Vec<MarketEntry> = response.error_for_status()?.json().await?;
+
+    // Keep the order the coins were configured in
+    Ok(coins
+        .iter()
+        .filter_map(|coin| {
+            markets
+                .iter()
+                .find(|market| market.symbol.eq_ignore_ascii_case(coin))
+                .map(|market| DisplayCoin {
+                    symbol: coin.to_uppercase(),
+                    price: market
+                        .current_price
+                        .map(|price| format!("{:.2}", price))
+                        .unwrap_or_else(|| "N/A".to_owned()),
+                    change_24h: market.price_change_percentage_24h,
+                })
+        })
+        .collect())
+}
+
+pub struct Crypto {
+    state: Arc<Mutex<CryptoState>>,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+
+    font: &'static mono_font::MonoFont<'static>,
+
+    /// Used to pause the update task while the render is not displayed
+    poll_gate: PollGate,
+
+    /// Flag used to gracefully terminate the render and driver threads
+    cancel_token: CancellationToken,
+
+    /// Handle to the task used to update the prices
+    update_prices_handle: Option<JoinHandle<()>>,
+}
+
+impl Crypto {
+    pub fn new(config: CryptoConfig) -> Self {
+        let font = config
+            .font
+            .map(|font| font.mono_font())
+            .unwrap_or(DEFAULT_FONT);
+        let state = Arc::new(Mutex::new(CryptoState::default()));
+        let poll_gate = PollGate::new();
+        let cancel_token = CancellationToken::new();
+
+        let task_cancel_token = cancel_token.clone();
+        let task_state = state.clone();
+        let task_poll_gate = poll_gate.clone();
+
+        let update_prices_handle =
+            supervise("Crypto", cancel_token.clone(), RESTART_DELAY, move || {
+                let config = config.clone();
+                let task_cancel_token = task_cancel_token.clone();
+                let task_state = task_state.clone();
+                let task_poll_gate = task_poll_gate.clone();
+
+                async move {
+                    let client = reqwest::Client::builder()
+                        .timeout(request_timeout(config.request_timeout_secs))
+                        .build()?;
+                    let interval =
+                        Duration::from_secs(config.interval_secs.unwrap_or(DEFAULT_INTERVAL_SECS));
+                    let mut refresh_duration = interval;
+
+                    loop {
+                        // Don't use up API quota while the render is not being displayed
+                        select! {
+                            _ = task_poll_gate.active() => {},
+                            _ = task_cancel_token.cancelled() => break,
+                        }
+
+                        let start_time = tokio::time::Instant::now();
+
+                        match fetch_prices(&client, &config.coins, &config.vs_currency).await {
+                            Ok(coins) => {
+                                *task_state.lock() = CryptoState {
+                                    coins,
+                                    is_stale: false,
+                                    has_loaded: true,
+                                };
+                                refresh_duration = interval;
+                            }
+                            Err(e) => {
+                                error!("Could not get updated prices {e}");
+                                task_state.lock().is_stale = true;
+
+                                // Back off so a rate limit has a chance to reset
+                                refresh_duration = (refresh_duration * 2).min(MAX_BACKOFF);
+                            }
+                        }
+
+                        select! {
+                            _ = tokio::time::sleep_until(start_time + refresh_duration) => {},
+                            _ = task_poll_gate.paused() => {},
+                            _ = task_cancel_token.cancelled() => break,
+                        }
+                    }
+
+                    Ok(())
+                }
+            });
+
+        Self {
+            state,
+            loading_spinner: loading_spinner(),
+            font,
+            poll_gate,
+            cancel_token,
+            update_prices_handle: Some(update_prices_handle),
+        }
+    }
+}
+
+impl<D> Render<D> for Crypto
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let state = self.state.lock();
+
+        if !state.has_loaded {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let char_width = (self.font.character_size.width + self.font.character_spacing) as i32;
+        let row_height = self.font.character_size.height as i32;
+
+        let mut position = Point::zero();
+
+        for coin in &state.coins {
+            let (change_str, change_color) = match coin.change_24h {
+                Some(change) if change >= 0.0 => (format!("{:+.1}%", change), Rgb888::GREEN),
+                Some(change) => (format!("{:+.1}%", change), Rgb888::RED),
+                None => ("N/A".to_owned(), Rgb888::WHITE),
+            };
+
+            // Prices that could not be refreshed are dimmed and marked
+            let (price_str, price_color) = if state.is_stale {
+                (format!("{}*", coin.price), Rgb888::new(128, 128, 128))
+            } else {
+                (coin.price.clone(), Rgb888::WHITE)
+            };
+
+            let mut column = position;
+
+            Text::with_baseline(
+                &format!("{:<5}", coin.symbol),
+                column,
+                MonoTextStyle::new(self.font, Rgb888::WHITE),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+            column.x += 6 * char_width;
+
+            Text::with_baseline(
+                &format!("{:>10}", price_str),
+                column,
+                MonoTextStyle::new(self.font, price_color),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+            column.x += 11 * char_width;
+
+            // Arrow pointing in the direction of the 24h change
+            if let Some(change) = coin.change_24h {
+                let (tip_y, base_y) = if change >= 0.0 {
+                    (column.y + 1, column.y + row_height - 2)
+                } else {
+                    (column.y + row_height - 2, column.y + 1)
+                };
+
+                Triangle::new(
+                    Point::new(column.x + 2, tip_y),
+                    Point::new(column.x, base_y),
+                    Point::new(column.x + 4, base_y),
+                )
+                .into_styled(PrimitiveStyle::with_fill(change_color))
+                .draw(canvas)?;
+            }
+            column.x += char_width;
+
+            Text::with_baseline(
+                &change_str,
+                column,
+                MonoTextStyle::new(self.font, change_color),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            position.y += row_height + ROW_SPACING;
+        }
+
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.poll_gate.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.poll_gate.pause();
+    }
+
+    fn describe(&self) -> String {
+        let state = self.state.lock();
+
+        match (state.has_loaded, state.is_stale) {
+            (false, _) => "Crypto: loading".to_owned(),
+            (true, false) => format!("Crypto: {} coins", state.coins.len()),
+            (true, true) => format!("Crypto: {} coins, stale", state.coins.len()),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        self.state.lock().has_loaded
+    }
+}
+
+impl Drop for Crypto {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+
+        if let Some(task_handle) = self.update_prices_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
+pub struct CryptoFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for CryptoFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for CryptoFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "Crypto"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Displays the price and 24h change of a list of crypto currencies"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: CryptoConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(Crypto::new(config)))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: CryptoConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(Crypto::new(config)))
+    }
+}
@@ -0,0 +1,221 @@
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Locale, Weekday};
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable,
+};
+use rustic_pixel_display::{
+    clock::{SharedClock, SystemClock},
+    font::{latin1_lossy, Font},
+    render::{BufferCanvas, ExampleConfig, Render, RenderError, RenderFactory, SyncRender},
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::{convert::Infallible, io::Read, marker::PhantomData};
+
+const DEFAULT_WEEKDAY_FONT: Font = Font::SixByTen;
+const DEFAULT_DAY_FONT: Font = Font::TenByTwenty;
+const DEFAULT_MONTH_FONT: Font = Font::SixByTen;
+
+/// Names the weekdays and months if no locale is configured
+const DEFAULT_LOCALE: Locale = Locale::en_US;
+
+const TEXT_COLOR: Rgb888 = Rgb888::WHITE;
+const DEFAULT_ACCENT_COLOR: Rgb888 = Rgb888::RED;
+
+/// Configuration for the [`DateTileFactory`], for example:
+///
+/// ```json
+/// {
+///   "day_font": "10x20",
+///   "accent_color": [255, 64, 0],
+///   "locale": "fr_FR"
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DateTileConfig {
+    /// Font of the weekday, defaults to 6x10 if not provided
+    pub weekday_font: Option<Font>,
+
+    /// Font of the day of the month, defaults to 10x20 if not provided
+    pub day_font: Option<Font>,
+
+    /// Font of the month, defaults to 6x10 if not provided
+    pub month_font: Option<Font>,
+
+    /// The color of the weekday and day on weekends as RGB, defaults to red
+    pub accent_color: Option<[u8; 3]>,
+
+    /// The locale the weekday and month are named in, i.e. `de_DE`. Defaults to
+    /// `en_US` if not provided
+    pub locale: Option<String>,
+}
+
+impl DateTileConfig {
+    fn locale(&self) -> Result<Locale> {
+        match &self.locale {
+            Some(locale) => Locale::try_from(locale.as_str())
+                .map_err(|_| anyhow!("Unknown locale \"{locale}\"")),
+            None => Ok(DEFAULT_LOCALE),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.locale().map(|_| ())
+    }
+}
+
+/// Today's date as a desk calendar page: the weekday at the top, the day of
+/// the month large in the middle and the month at the bottom.
+///
+/// The date is read on every render, so the tile turns over at midnight.
+pub struct DateTile {
+    weekday_font: Font,
+    day_font: Font,
+    month_font: Font,
+    accent_color: Rgb888,
+    locale: Locale,
+    clock: SharedClock,
+}
+
+impl DateTile {
+    pub fn new(config: DateTileConfig) -> Result<Self> {
+        Ok(Self {
+            locale: config.locale()?,
+            weekday_font: config.weekday_font.unwrap_or(DEFAULT_WEEKDAY_FONT),
+            day_font: config.day_font.unwrap_or(DEFAULT_DAY_FONT),
+            month_font: config.month_font.unwrap_or(DEFAULT_MONTH_FONT),
+            accent_color: config
+                .accent_color
+                .map(|[r, g, b]| Rgb888::new(r, g, b))
+                .unwrap_or(DEFAULT_ACCENT_COLOR),
+            clock: SystemClock::shared(),
+        })
+    }
+
+    /// Shows the date of `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+impl<D> Render<D> for DateTile
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let today = self.clock.now();
+        let bounding_box = canvas.bounding_box();
+        let center_x = bounding_box.center().x;
+
+        let color = match today.weekday() {
+            Weekday::Sat | Weekday::Sun => self.accent_color,
+            _ => TEXT_COLOR,
+        };
+
+        let style = |baseline| {
+            TextStyleBuilder::new()
+                .alignment(Alignment::Center)
+                .baseline(baseline)
+                .build()
+        };
+
+        Text::with_text_style(
+            &latin1_lossy(&today.format_localized("%A", self.locale).to_string()),
+            Point::new(center_x, bounding_box.top_left.y),
+            MonoTextStyle::new(self.weekday_font.mono_font(), color),
+            style(Baseline::Top),
+        )
+        .draw(canvas)?;
+
+        Text::with_text_style(
+            &today.day().to_string(),
+            bounding_box.center(),
+            MonoTextStyle::new(self.day_font.mono_font(), color),
+            style(Baseline::Middle),
+        )
+        .draw(canvas)?;
+
+        Text::with_text_style(
+            &latin1_lossy(&today.format_localized("%B", self.locale).to_string()),
+            Point::new(
+                center_x,
+                bounding_box.top_left.y + bounding_box.size.height as i32 - 1,
+            ),
+            MonoTextStyle::new(self.month_font.mono_font(), TEXT_COLOR),
+            style(Baseline::Bottom),
+        )
+        .draw(canvas)?;
+
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "DateTile: {}",
+            self.clock.now().format_localized("%A %-d %B", self.locale)
+        )
+    }
+}
+
+pub struct DateTileFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for DateTileFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for DateTileFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "DateTile"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Today's date as a desk calendar page"
+    }
+
+    fn example_config(&self) -> Option<ExampleConfig> {
+        Some(json!({
+            "day_font": "10x20",
+            "accent_color": [255, 64, 0],
+            "locale": "en_US"
+        }))
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: DateTileConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(DateTile::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: DateTileConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(DateTile::new(config)?))
+    }
+}
@@ -0,0 +1,489 @@
+use anyhow::Result;
+use embedded_graphics::{
+    draw_target::DrawTargetExt,
+    mono_font::{self, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Primitive, RgbColor, Size},
+    primitives::{Line, PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+    Drawable,
+};
+use log::error;
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    font::latin1_lossy,
+    render::{BufferCanvas, PollGate, Render, RenderError, RenderFactory, Spinner, SyncRender},
+};
+use septa_api::types::RegionalRailStop;
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{select, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    format::{format_time, TimeFormatConfig},
+    loading::{draw_loading, loading_spinner},
+    renders::upcoming_arrivals::{
+        septa_provider::{self, SeptaProvider},
+        UpcomingTrain, UpcomingTrainStatus,
+    },
+    request::{
+        refresh_interval, request_timeout, traced, validate_refresh_interval, with_timeout,
+        STALE_AFTER_REFRESHES,
+    },
+};
+
+const FONT: &MonoFont = &mono_font::iso_8859_1::FONT_6X10;
+
+/// Size of a single character cell, the glyph plus a one pixel border
+const CELL_SIZE: Size = Size::new(
+    FONT.character_size.width + 2,
+    FONT.character_size.height + 2,
+);
+
+/// Space between the cells of neighboring rows
+const ROW_SPACING: u32 = 1;
+
+/// How long a cell takes to flip over to its new character
+const FLIP_DURATION: Duration = Duration::from_millis(300);
+
+const CELL_COLOR: Rgb888 = Rgb888::new(32, 32, 32);
+const DIVIDER_COLOR: Rgb888 = Rgb888::BLACK;
+const TEXT_COLOR: Rgb888 = Rgb888::new(255, 200, 0);
+
+/// Width, in characters, of the status column
+const STATUS_WIDTH: usize = 3;
+
+/// How long to wait between refreshes if no refresh interval is configured
+const DEFAULT_REFRESH: Duration = Duration::from_secs(60);
+
+/// How long to wait after a failed request if no retry interval is
+/// configured
+const DEFAULT_ERROR_RETRY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FlipBoardConfig {
+    pub septa_station: RegionalRailStop,
+
+    /// How the scheduled times of the trains are displayed
+    #[serde(default)]
+    pub time_format: TimeFormatConfig,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How long, in seconds, to wait between refreshes of the arrivals.
+    /// Defaults to [`DEFAULT_REFRESH`] if not provided
+    pub refresh_secs: Option<u64>,
+
+    /// How long, in seconds, to wait before trying again when the arrivals
+    /// could not be fetched. Defaults to [`DEFAULT_ERROR_RETRY`] if not
+    /// provided
+    pub error_retry_secs: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct FlipBoardState {
+    arrivals: Vec<UpcomingTrain>,
+
+    /// When the arrivals were last fetched
+    refreshed_at: Option<Instant>,
+}
+
+/// A single character of the board
+#[derive(Debug, Clone, Copy)]
+struct FlipCell {
+    current: char,
+
+    /// The character the cell is flipping over from
+    previous: char,
+
+    /// When the cell started flipping to `current`, `None` if it never flipped
+    changed_at: Option<Instant>,
+}
+
+impl FlipCell {
+    fn new(current: char) -> Self {
+        Self {
+            current,
+            previous: current,
+            changed_at: None,
+        }
+    }
+
+    fn set(&mut self, next: char, now: Instant) {
+        if next != self.current {
+            self.previous = self.current;
+            self.current = next;
+            self.changed_at = Some(now);
+        }
+    }
+
+    /// Draws the cell with its top left corner at `position`.
+    ///
+    /// While flipping, the top half of the previous character folds down over
+    /// the top half of the new one, then the bottom half of the new character
+    /// folds down over the bottom half of the previous one.
+    fn draw<D>(&self, canvas: &mut D, position: Point, now: Instant) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    {
+        let cell = Rectangle::new(position, CELL_SIZE);
+        let half_height = CELL_SIZE.height / 2;
+        let top_half = Rectangle::new(position, Size::new(CELL_SIZE.width, half_height));
+        let bottom_half = Rectangle::new(
+            position + Point::new(0, half_height as i32),
+            Size::new(CELL_SIZE.width, CELL_SIZE.height - half_height),
+        );
+
+        let progress = match self.changed_at {
+            Some(changed_at) => (now.saturating_duration_since(changed_at).as_secs_f32()
+                / FLIP_DURATION.as_secs_f32())
+            .min(1.0),
+            None => 1.0,
+        };
+
+        if progress >= 1.0 {
+            draw_glyph(&mut canvas.clipped(&cell), self.current, position)?;
+        } else if progress < 0.5 {
+            // The top flap, still showing the previous character, folds down
+            // towards the divider
+            let folded = (top_half.size.height as f32 * progress * 2.0) as u32;
+
+            draw_glyph(&mut canvas.clipped(&top_half), self.current, position)?;
+            draw_glyph(
+                &mut canvas.clipped(&Rectangle::new(
+                    top_half.top_left + Point::new(0, folded as i32),
+                    Size::new(top_half.size.width, top_half.size.height - folded),
+                )),
+                self.previous,
+                position,
+            )?;
+            draw_glyph(&mut canvas.clipped(&bottom_half), self.previous, position)?;
+        } else {
+            // The flap, now showing the new character, unfolds over the bottom
+            let unfolded = (bottom_half.size.height as f32 * (progress - 0.5) * 2.0) as u32;
+
+            draw_glyph(&mut canvas.clipped(&top_half), self.current, position)?;
+            draw_glyph(&mut canvas.clipped(&bottom_half), self.previous, position)?;
+            draw_glyph(
+                &mut canvas.clipped(&Rectangle::new(
+                    bottom_half.top_left,
+                    Size::new(bottom_half.size.width, unfolded),
+                )),
+                self.current,
+                position,
+            )?;
+        }
+
+        Line::new(
+            Point::new(position.x, position.y + half_height as i32),
+            Point::new(
+                position.x + CELL_SIZE.width as i32 - 1,
+                position.y + half_height as i32,
+            ),
+        )
+        .into_styled(PrimitiveStyle::with_stroke(DIVIDER_COLOR, 1))
+        .draw(canvas)?;
+
+        Ok(())
+    }
+}
+
+/// Draws `glyph` centered in the cell whose top left corner is at `position`,
+/// covering whatever was drawn in the cell before.
+fn draw_glyph<D>(canvas: &mut D, glyph: char, position: Point) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    Rectangle::new(position, CELL_SIZE)
+        .into_styled(PrimitiveStyle::with_fill(CELL_COLOR))
+        .draw(canvas)?;
+
+    let mut buffer = [0; 4];
+    Text::with_baseline(
+        glyph.encode_utf8(&mut buffer),
+        position + Point::new(1, 1),
+        MonoTextStyle::new(FONT, TEXT_COLOR),
+        Baseline::Top,
+    )
+    .draw(canvas)?;
+
+    Ok(())
+}
+
+/// Formats `train` as a row of exactly `columns` characters: the scheduled
+/// time, the destination and how late the train is.
+fn format_row(train: &UpcomingTrain, time_format: &TimeFormatConfig, columns: usize) -> Vec<char> {
+    let time = format_time(&train.schedule_arrival, time_format);
+    let status = match train.status {
+        UpcomingTrainStatus::OnTime => "OT".to_owned(),
+        UpcomingTrainStatus::Early(mins) => format!("-{mins}"),
+        UpcomingTrainStatus::Late(mins) => format!("+{mins}"),
+        UpcomingTrainStatus::Unknown => "--".to_owned(),
+    };
+
+    let status_width = STATUS_WIDTH;
+    let destination_width = columns.saturating_sub(time.chars().count() + status_width + 2);
+    let destination = latin1_lossy(&train.destination_name.to_uppercase()).into_owned();
+
+    format!("{time} {destination:<destination_width$.destination_width$} {status:>status_width$}")
+        .chars()
+        .chain(std::iter::repeat(' '))
+        .take(columns)
+        .collect()
+}
+
+/// SEPTA arrivals drawn as a split-flap departure board.
+///
+/// Each character sits in its own cell with a divider across the middle, and
+/// flips over to its new character when the arrivals change.
+pub struct FlipBoard {
+    state: Arc<Mutex<FlipBoardState>>,
+
+    /// The characters currently on the board, one row per train
+    cells: Mutex<Vec<Vec<FlipCell>>>,
+
+    time_format: TimeFormatConfig,
+
+    /// How long the arrivals can go without a refresh before the render
+    /// reports it has no content
+    stale_after: Duration,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+
+    /// Used to pause the update task while the render is not displayed
+    poll_gate: PollGate,
+
+    /// Flag used to gracefully terminate the render and driver threads
+    cancel_token: CancellationToken,
+
+    /// Handle to the task used to update the arrivals
+    update_task_handle: Option<JoinHandle<Result<()>>>,
+}
+
+impl FlipBoard {
+    pub fn new(config: FlipBoardConfig) -> Result<Self> {
+        septa_provider::validate_station(&config.septa_station)?;
+        validate_refresh_interval("refresh_secs", config.refresh_secs)?;
+        validate_refresh_interval("error_retry_secs", config.error_retry_secs)?;
+
+        let timeout = request_timeout(config.request_timeout_secs);
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let error_retry = refresh_interval(config.error_retry_secs, DEFAULT_ERROR_RETRY);
+
+        let state = Arc::new(Mutex::new(FlipBoardState::default()));
+        let poll_gate = PollGate::new();
+        let cancel_token = CancellationToken::new();
+
+        let task_cancel_token = cancel_token.clone();
+        let task_state = state.clone();
+        let task_poll_gate = poll_gate.clone();
+        let septa_client = SeptaProvider::new(config.septa_station);
+
+        let update_task_handle = tokio::task::spawn(async move {
+            loop {
+                // Don't poll the SEPTA API while the render is not being displayed
+                select! {
+                    _ = task_poll_gate.active() => {},
+                    _ = task_cancel_token.cancelled() => break,
+                }
+
+                let start_time = tokio::time::Instant::now();
+
+                let arrivals_result = select! {
+                    arrivals_result = traced(
+                        "FlipBoard",
+                        format!("septa/arrivals/{}", septa_client.station()),
+                        with_timeout(timeout, septa_client.arrivals()),
+                    ) => arrivals_result,
+                    _ = task_cancel_token.cancelled() => break,
+                };
+
+                let refresh_time = match arrivals_result {
+                    Ok(arrivals) => {
+                        let mut state = task_state.lock();
+                        state.arrivals = arrivals;
+                        state.refreshed_at = Some(Instant::now());
+
+                        start_time + refresh
+                    }
+                    Err(e) => {
+                        error!(
+                            "Could not get updated SEPTA arrivals for {} {e}",
+                            septa_client.station()
+                        );
+
+                        start_time + error_retry
+                    }
+                };
+
+                select! {
+                    _ = tokio::time::sleep_until(refresh_time) => {},
+                    _ = task_poll_gate.paused() => {},
+                    _ = task_cancel_token.cancelled() => break,
+                }
+            }
+
+            Ok(())
+        });
+
+        Ok(Self {
+            state,
+            cells: Mutex::new(Vec::new()),
+            time_format: config.time_format,
+            stale_after: refresh * STALE_AFTER_REFRESHES,
+            loading_spinner: loading_spinner(),
+            poll_gate,
+            cancel_token,
+            update_task_handle: Some(update_task_handle),
+        })
+    }
+}
+
+impl<D> Render<D> for FlipBoard
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let state = self.state.lock();
+
+        if state.refreshed_at.is_none() {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let canvas_size = canvas.bounding_box().size;
+        let columns = (canvas_size.width / CELL_SIZE.width) as usize;
+        let rows = (canvas_size.height / (CELL_SIZE.height + ROW_SPACING)) as usize;
+
+        let text = (0..rows)
+            .map(|row| match state.arrivals.get(row) {
+                Some(train) => format_row(train, &self.time_format, columns),
+                None => vec![' '; columns],
+            })
+            .collect::<Vec<_>>();
+
+        drop(state);
+
+        let now = Instant::now();
+        let mut cells = self.cells.lock();
+
+        // A resized board starts over without animating
+        if cells.len() != rows || cells.first().map(Vec::len).unwrap_or(columns) != columns {
+            *cells = text
+                .iter()
+                .map(|row| row.iter().map(|&glyph| FlipCell::new(glyph)).collect())
+                .collect();
+        }
+
+        for (row, (cell_row, text_row)) in cells.iter_mut().zip(&text).enumerate() {
+            for (column, (cell, &glyph)) in cell_row.iter_mut().zip(text_row).enumerate() {
+                cell.set(glyph, now);
+                cell.draw(
+                    canvas,
+                    Point::new(
+                        (column as u32 * CELL_SIZE.width) as i32,
+                        (row as u32 * (CELL_SIZE.height + ROW_SPACING)) as i32,
+                    ),
+                    now,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.poll_gate.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.poll_gate.pause();
+    }
+
+    fn describe(&self) -> String {
+        let state = self.state.lock();
+
+        match state.refreshed_at {
+            Some(_) => format!("FlipBoard: {} trains", state.arrivals.len()),
+            None => "FlipBoard: loading".to_owned(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        match self.state.lock().refreshed_at {
+            Some(refreshed_at) => refreshed_at.elapsed() < self.stale_after,
+            None => false,
+        }
+    }
+
+    fn reset(&self) {
+        // Blank every cell so the whole board flips over to the arrivals again
+        for cell in self.cells.lock().iter_mut().flatten() {
+            *cell = FlipCell::new(' ');
+        }
+    }
+}
+
+impl Drop for FlipBoard {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+
+        if let Some(task_handle) = self.update_task_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
+pub struct FlipBoardFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for FlipBoardFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for FlipBoardFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "FlipBoard"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "SEPTA regional rail arrivals on a split-flap departure board"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: FlipBoardConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(FlipBoard::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: FlipBoardConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(FlipBoard::new(config)?))
+    }
+}
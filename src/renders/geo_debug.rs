@@ -0,0 +1,158 @@
+use embedded_graphics::{
+    mono_font::{self, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    primitives::{Circle, ContainsPoint, Primitive, PrimitiveStyle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable, Pixel,
+};
+use geoutils::Location;
+use std::convert::Infallible;
+
+use super::person_tracker::{TrackerLocation, AT_STATION_ENTER_RADIUS};
+use crate::stations::STATION_LOCATIONS;
+use rustic_pixel_display::render::Render;
+
+/// Distance from the person to the edge of the canvas if none is provided
+const DEFAULT_RANGE_METERS: f64 = 2000.0;
+
+/// Approximate length of a degree of latitude
+const METERS_PER_DEGREE_LAT: f64 = 110_540.0;
+
+/// Approximate length of a degree of longitude at the equator
+const METERS_PER_DEGREE_LON: f64 = 111_320.0;
+
+const PERSON_COLOR: Rgb888 = Rgb888::WHITE;
+const STATION_COLOR: Rgb888 = Rgb888::new(96, 96, 96);
+const STATION_RADIUS_COLOR: Rgb888 = Rgb888::new(32, 32, 32);
+const INSIDE_STATION_COLOR: Rgb888 = Rgb888::GREEN;
+
+/// Schematic map of the person followed by a
+/// [`TransitTracker`](super::person_tracker::TransitTracker) and the SEPTA
+/// stations around them, used to diagnose why the tracker did or did not
+/// change state.
+///
+/// The person is drawn at the center of the canvas. Stations are drawn as dots
+/// with their `AT_STATION_ENTER_RADIUS` around them, green if the person is
+/// inside that radius.
+pub struct GeoDebug {
+    location: TrackerLocation,
+
+    /// Distance, in meters, from the person to the closest edge of the canvas
+    range_meters: f64,
+
+    /// Latitude and longitude of every known station
+    stations: Vec<(f64, f64)>,
+}
+
+impl GeoDebug {
+    pub fn new(location: TrackerLocation, range_meters: Option<f64>) -> Self {
+        let stations = STATION_LOCATIONS
+            .values()
+            .map(|location| (location.latitude(), location.longitude()))
+            .collect();
+
+        Self {
+            location,
+            range_meters: range_meters.unwrap_or(DEFAULT_RANGE_METERS),
+            stations,
+        }
+    }
+}
+
+impl<D> Render<D> for GeoDebug
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let bounding_box = canvas.bounding_box();
+        let center = bounding_box.center();
+
+        let (lat, lon) = match self.location.get() {
+            Some(location) => location,
+            None => {
+                Text::with_text_style(
+                    "No location",
+                    center,
+                    MonoTextStyle::new(&mono_font::ascii::FONT_5X7, Rgb888::WHITE),
+                    TextStyleBuilder::new()
+                        .alignment(Alignment::Center)
+                        .baseline(Baseline::Middle)
+                        .build(),
+                )
+                .draw(canvas)?;
+
+                return Ok(());
+            }
+        };
+
+        let person = Location::new(lat, lon);
+        let half_size = bounding_box.size.width.min(bounding_box.size.height) / 2;
+        let pixels_per_meter = half_size as f64 / self.range_meters;
+
+        // Equirectangular projection, accurate enough over the few kilometers
+        // shown on the panel
+        let meters_per_degree_lon = METERS_PER_DEGREE_LON * lat.to_radians().cos();
+        let to_point = |(station_lat, station_lon): (f64, f64)| {
+            let east = (station_lon - lon) * meters_per_degree_lon;
+            let north = (station_lat - lat) * METERS_PER_DEGREE_LAT;
+
+            center
+                + Point::new(
+                    (east * pixels_per_meter).round() as i32,
+                    (-north * pixels_per_meter).round() as i32,
+                )
+        };
+
+        let radius_diameter =
+            (AT_STATION_ENTER_RADIUS.meters() * 2.0 * pixels_per_meter).round() as u32;
+
+        // Stations whose radius is partially visible are still drawn
+        let visible_area = bounding_box.offset(radius_diameter as i32 / 2);
+
+        for &station in &self.stations {
+            let point = to_point(station);
+
+            if !visible_area.contains(point) {
+                continue;
+            }
+
+            let inside = person
+                .is_in_circle(
+                    &Location::new(station.0, station.1),
+                    *AT_STATION_ENTER_RADIUS,
+                )
+                .unwrap_or(false);
+
+            if radius_diameter > 2 {
+                Circle::with_center(point, radius_diameter)
+                    .into_styled(PrimitiveStyle::with_stroke(
+                        if inside {
+                            INSIDE_STATION_COLOR
+                        } else {
+                            STATION_RADIUS_COLOR
+                        },
+                        1,
+                    ))
+                    .draw(canvas)?;
+            }
+
+            Pixel(
+                point,
+                if inside {
+                    INSIDE_STATION_COLOR
+                } else {
+                    STATION_COLOR
+                },
+            )
+            .draw(canvas)?;
+        }
+
+        // Draw the person last so it is never hidden by a station
+        Circle::with_center(center, 3)
+            .into_styled(PrimitiveStyle::with_fill(PERSON_COLOR))
+            .draw(canvas)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,556 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, TimeZone};
+use embedded_graphics::{
+    mono_font::{self, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    text::{Baseline, Text},
+    Drawable,
+};
+use gtfs_realtime::FeedMessage;
+use log::error;
+use parking_lot::Mutex;
+use prost::Message;
+use rustic_pixel_display::{
+    font::{latin1_lossy, Font},
+    render::{BufferCanvas, PollGate, Render, RenderError, RenderFactory, Spinner, SyncRender},
+};
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{select, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    format::{format_time, TimeFormatConfig},
+    gtfs::GtfsSchedule,
+    loading::{draw_loading, loading_spinner},
+    request::{
+        refresh_interval, request_timeout, validate_refresh_interval, STALE_AFTER_REFRESHES,
+    },
+};
+
+const DEFAULT_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_5X7;
+
+/// How many departures are shown if no limit is configured
+const DEFAULT_RESULTS: usize = 5;
+
+/// How long to wait between refreshes if no refresh interval is configured
+const DEFAULT_REFRESH: Duration = Duration::from_secs(30);
+
+/// How long to wait after a failed request if no retry interval is
+/// configured
+const DEFAULT_ERROR_RETRY: Duration = Duration::from_secs(60);
+
+/// Space between the columns of a departure
+const COLUMN_SPACING: &str = " ";
+
+/// Configuration for the [`GtfsRealtimeFactory`], for example:
+///
+/// ```json
+/// {
+///   "feed_url": "https://example.com/gtfs-rt/tripupdates",
+///   "stop_id": "90004",
+///   "route_filter": ["PAO", "CYN"],
+///   "schedule": "https://example.com/gtfs.zip"
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct GtfsRealtimeConfig {
+    /// URL of a GTFS-realtime `TripUpdates` feed, encoded as protobuf
+    pub feed_url: String,
+
+    /// The `stop_id` of the stop to show the departures of
+    pub stop_id: String,
+
+    /// Only show departures on these routes, identified by their `route_id`.
+    /// Departures on every route are shown if not provided
+    pub route_filter: Option<Vec<String>>,
+
+    /// URL or path of the static GTFS zip of the agency, used to show the
+    /// names of the stop and routes rather than their ids
+    pub schedule: Option<String>,
+
+    /// How many departures are shown. Defaults to 5
+    pub results: Option<u8>,
+
+    /// How the departure times are displayed
+    #[serde(default)]
+    pub time_format: TimeFormatConfig,
+
+    /// Font of the departures, defaults to 5x7 if not provided
+    pub font: Option<Font>,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How long, in seconds, to wait between refreshes of the feed.
+    /// Defaults to [`DEFAULT_REFRESH`] if not provided
+    pub refresh_secs: Option<u64>,
+
+    /// How long, in seconds, to wait before trying again when the feed could
+    /// not be fetched. Defaults to [`DEFAULT_ERROR_RETRY`] if not provided
+    pub error_retry_secs: Option<u64>,
+}
+
+impl GtfsRealtimeConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.stop_id.is_empty() {
+            return Err(anyhow!("stop_id must not be empty"));
+        }
+
+        validate_refresh_interval("refresh_secs", self.refresh_secs)?;
+        validate_refresh_interval("error_retry_secs", self.error_retry_secs)
+    }
+}
+
+/// A departure from the configured stop
+#[derive(Debug, Clone)]
+struct Departure {
+    /// The name of the route from the schedule, otherwise its `route_id`
+    route_name: String,
+
+    route_color: Rgb888,
+
+    /// Where the trip is heading, if known from the schedule
+    headsign: Option<String>,
+
+    /// When the trip is predicted to leave the stop
+    time: DateTime<Local>,
+
+    /// How many seconds behind schedule the trip is, negative if it is ahead
+    delay_secs: Option<i32>,
+}
+
+impl Departure {
+    fn status(&self) -> (String, Rgb888) {
+        match self.delay_secs.map(|delay_secs| delay_secs / 60) {
+            Some(0) => ("On Time".to_owned(), Rgb888::GREEN),
+            Some(mins) if mins > 0 => (format!("+{mins}m"), Rgb888::RED),
+            Some(mins) => (format!("{mins}m"), Rgb888::GREEN),
+            None => (String::new(), Rgb888::WHITE),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct GtfsRealtimeState {
+    departures: Vec<Departure>,
+
+    /// The name of the stop from the schedule
+    stop_name: Option<String>,
+
+    /// When the feed was last fetched
+    refreshed_at: Option<Instant>,
+}
+
+/// Fetches and decodes the feed at `url`.
+async fn fetch_feed(client: &reqwest::Client, url: &str) -> Result<FeedMessage> {
+    let body = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    FeedMessage::decode(body).map_err(|e| anyhow!("Could not decode GTFS-realtime feed {e}"))
+}
+
+/// Collects the departures from `stop_id` on the routes of `route_filter` that
+/// have not left yet, soonest first.
+///
+/// Trips that are deleted, or whose stop has no predicted time, are skipped.
+/// Routes and trips are named from `schedule` where possible.
+fn departures(
+    feed: &FeedMessage,
+    stop_id: &str,
+    route_filter: Option<&[String]>,
+    schedule: Option<&GtfsSchedule>,
+    now: DateTime<Local>,
+) -> Vec<Departure> {
+    let mut departures = feed
+        .entity
+        .iter()
+        .filter(|entity| !entity.is_deleted.unwrap_or(false))
+        .filter_map(|entity| entity.trip_update.as_ref())
+        .filter_map(|trip_update| {
+            let trip = trip_update
+                .trip
+                .trip_id
+                .as_deref()
+                .and_then(|trip_id| schedule?.trip(trip_id));
+
+            // Feeds may leave out the route of a trip that the schedule has
+            let route_id = trip_update
+                .trip
+                .route_id
+                .clone()
+                .or_else(|| trip.map(|trip| trip.route_id.clone()))
+                .unwrap_or_default();
+
+            if let Some(route_filter) = route_filter {
+                if !route_filter.contains(&route_id) {
+                    return None;
+                }
+            }
+
+            let stop_time_update = trip_update
+                .stop_time_update
+                .iter()
+                .find(|update| update.stop_id.as_deref() == Some(stop_id))?;
+
+            // Prefer when the trip leaves the stop, the last stop of a trip
+            // only has an arrival
+            let event = stop_time_update
+                .departure
+                .as_ref()
+                .or(stop_time_update.arrival.as_ref())?;
+
+            let time = Local.timestamp_opt(event.time?, 0).single()?;
+
+            let route = schedule.and_then(|schedule| schedule.route(&route_id));
+
+            Some(Departure {
+                route_name: route.map(|route| route.name.clone()).unwrap_or(route_id),
+                route_color: route.and_then(|route| route.color).unwrap_or(Rgb888::WHITE),
+                headsign: trip.and_then(|trip| trip.headsign.clone()),
+                time,
+                delay_secs: event.delay.or(trip_update.delay),
+            })
+        })
+        .filter(|departure| departure.time >= now)
+        .collect::<Vec<_>>();
+
+    departures.sort_by_key(|departure| departure.time);
+    departures
+}
+
+/// Upcoming departures from a single stop of any agency that publishes a
+/// GTFS-realtime `TripUpdates` feed.
+///
+/// Each row shows the route, the predicted departure time and how far the trip
+/// is behind or ahead of schedule. With a static GTFS schedule configured, the
+/// stop name is shown above the departures and each row also names the route
+/// and where the trip is heading.
+pub struct GtfsRealtime {
+    state: Arc<Mutex<GtfsRealtimeState>>,
+    results: usize,
+    time_format: TimeFormatConfig,
+    font: &'static MonoFont<'static>,
+
+    /// How long the departures can go without a refresh before the render
+    /// reports it has no content
+    stale_after: Duration,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+
+    /// Used to pause the update task while the render is not displayed
+    poll_gate: PollGate,
+
+    /// Flag used to gracefully terminate the render and driver threads
+    cancel_token: CancellationToken,
+
+    /// Handle to the task used to update the departures
+    update_task_handle: Option<JoinHandle<Result<()>>>,
+}
+
+impl GtfsRealtime {
+    pub fn new(config: GtfsRealtimeConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(request_timeout(config.request_timeout_secs))
+            .build()?;
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let error_retry = refresh_interval(config.error_retry_secs, DEFAULT_ERROR_RETRY);
+
+        let state = Arc::new(Mutex::new(GtfsRealtimeState::default()));
+        let poll_gate = PollGate::new();
+        let cancel_token = CancellationToken::new();
+
+        let task_cancel_token = cancel_token.clone();
+        let task_state = state.clone();
+        let task_poll_gate = poll_gate.clone();
+        let feed_url = config.feed_url.clone();
+        let stop_id = config.stop_id.clone();
+        let route_filter = config.route_filter.clone();
+        let schedule_source = config.schedule.clone();
+
+        let update_task_handle = tokio::task::spawn(async move {
+            let mut schedule: Option<Arc<GtfsSchedule>> = None;
+
+            loop {
+                // Don't poll the feed while the render is not being displayed
+                select! {
+                    _ = task_poll_gate.active() => {},
+                    _ = task_cancel_token.cancelled() => break,
+                }
+
+                // Keep trying to load the schedule, the departures are shown
+                // with ids until it loads
+                if let Some(source) = schedule_source.as_ref().filter(|_| schedule.is_none()) {
+                    let schedule_result = select! {
+                        schedule_result = GtfsSchedule::load(source) => schedule_result,
+                        _ = task_cancel_token.cancelled() => break,
+                    };
+
+                    match schedule_result {
+                        Ok(loaded) => {
+                            task_state.lock().stop_name =
+                                loaded.stop_name(&stop_id).map(str::to_owned);
+                            schedule = Some(loaded);
+                        }
+                        Err(e) => error!("Could not load GTFS schedule {source} {e}"),
+                    }
+                }
+
+                let start_time = tokio::time::Instant::now();
+
+                let feed_result = select! {
+                    feed_result = fetch_feed(&client, &feed_url) => feed_result,
+                    _ = task_cancel_token.cancelled() => break,
+                };
+
+                let refresh_time = match feed_result {
+                    Ok(feed) => {
+                        let departures = departures(
+                            &feed,
+                            &stop_id,
+                            route_filter.as_deref(),
+                            schedule.as_deref(),
+                            Local::now(),
+                        );
+
+                        let mut state = task_state.lock();
+                        state.departures = departures;
+                        state.refreshed_at = Some(Instant::now());
+
+                        start_time + refresh
+                    }
+                    Err(e) => {
+                        error!("Could not get GTFS-realtime feed {feed_url} {e}");
+
+                        start_time + error_retry
+                    }
+                };
+
+                select! {
+                    _ = tokio::time::sleep_until(refresh_time) => {},
+                    _ = task_poll_gate.paused() => {},
+                    _ = task_cancel_token.cancelled() => break,
+                }
+            }
+
+            Ok(())
+        });
+
+        Ok(Self {
+            state,
+            results: config.results.map(usize::from).unwrap_or(DEFAULT_RESULTS),
+            time_format: config.time_format,
+            font: config
+                .font
+                .map(|font| font.mono_font())
+                .unwrap_or(DEFAULT_FONT),
+            stale_after: refresh * STALE_AFTER_REFRESHES,
+            loading_spinner: loading_spinner(),
+            poll_gate,
+            cancel_token,
+            update_task_handle: Some(update_task_handle),
+        })
+    }
+}
+
+impl<D> Render<D> for GtfsRealtime
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let state = self.state.lock();
+
+        if state.refreshed_at.is_none() {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let mut top_left = canvas.bounding_box().top_left;
+        let row_height = self.font.character_size.height as i32;
+
+        if let Some(stop_name) = &state.stop_name {
+            Text::with_baseline(
+                &latin1_lossy(stop_name),
+                top_left,
+                MonoTextStyle::new(self.font, Rgb888::YELLOW),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            top_left.y += row_height;
+        }
+
+        // Departures that left since the last refresh are dropped
+        let now = Local::now();
+        let departures = state
+            .departures
+            .iter()
+            .filter(|departure| departure.time >= now)
+            .take(self.results)
+            .collect::<Vec<_>>();
+
+        if departures.is_empty() {
+            Text::with_baseline(
+                "No departures",
+                top_left,
+                MonoTextStyle::new(self.font, Rgb888::WHITE),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            return Ok(());
+        }
+
+        // Pad the routes so the times line up
+        let route_width = departures
+            .iter()
+            .map(|departure| departure.route_name.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        for (row, departure) in departures.iter().enumerate() {
+            let position = top_left + Point::new(0, row as i32 * row_height);
+            let (status, status_color) = departure.status();
+
+            let position = Text::with_baseline(
+                &format!(
+                    "{:<route_width$}{COLUMN_SPACING}",
+                    latin1_lossy(&departure.route_name)
+                ),
+                position,
+                MonoTextStyle::new(self.font, departure.route_color),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            let position = Text::with_baseline(
+                &format!(
+                    "{}{COLUMN_SPACING}",
+                    format_time(&departure.time, &self.time_format)
+                ),
+                position,
+                MonoTextStyle::new(self.font, Rgb888::WHITE),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            let position = Text::with_baseline(
+                &status,
+                position,
+                MonoTextStyle::new(self.font, status_color),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            if let Some(headsign) = &departure.headsign {
+                Text::with_baseline(
+                    &format!("{COLUMN_SPACING}{}", latin1_lossy(headsign)),
+                    position,
+                    MonoTextStyle::new(self.font, Rgb888::WHITE),
+                    Baseline::Top,
+                )
+                .draw(canvas)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.poll_gate.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.poll_gate.pause();
+    }
+
+    fn describe(&self) -> String {
+        let state = self.state.lock();
+
+        match state.refreshed_at {
+            Some(_) => format!("GtfsRealtime: {} departures", state.departures.len()),
+            None => "GtfsRealtime: loading".to_owned(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        match self.state.lock().refreshed_at {
+            Some(refreshed_at) => refreshed_at.elapsed() < self.stale_after,
+            None => false,
+        }
+    }
+}
+
+impl Drop for GtfsRealtime {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+
+        if let Some(task_handle) = self.update_task_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
+pub struct GtfsRealtimeFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for GtfsRealtimeFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for GtfsRealtimeFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "GtfsRealtime"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Upcoming departures from a stop of any agency with a GTFS-realtime feed"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: GtfsRealtimeConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(GtfsRealtime::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: GtfsRealtimeConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(GtfsRealtime::new(config)?))
+    }
+}
@@ -0,0 +1,837 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    mono_font::{self, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Primitive, RgbColor},
+    primitives::{Circle, PrimitiveStyle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable,
+};
+use home_assistant_rest::get::StateEnum;
+use image::{Rgba, RgbaImage};
+use log::{error, warn};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    font::{latin1_lossy, Font},
+    render::{BufferCanvas, PollGate, Render, RenderError, RenderFactory, Spinner, SyncRender},
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{select, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    assets::{load_image, load_image_or_log, AlphaImage, ImageFormat},
+    home_assistant_ws::{self, HomeAssistantWsClient},
+    loading::{draw_loading, loading_spinner},
+    request::{
+        refresh_interval, request_timeout, traced, validate_refresh_interval, with_timeout,
+        STALE_AFTER_REFRESHES,
+    },
+};
+
+const DEFAULT_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_6X10;
+
+/// How long to wait between refreshes if no refresh interval is configured
+const DEFAULT_REFRESH: Duration = Duration::from_secs(60);
+
+/// How long to wait after a failed request if no retry interval is
+/// configured
+const DEFAULT_ERROR_RETRY: Duration = Duration::from_secs(60);
+
+/// Space between the icon and the label of a sensor
+const ICON_SPACING: i32 = 2;
+
+/// Space between the rows of sensors
+const ROW_SPACING: i32 = 1;
+
+const LABEL_COLOR: Rgb888 = Rgb888::WHITE;
+const VALUE_COLOR: Rgb888 = Rgb888::GREEN;
+const WARN_COLOR: Rgb888 = Rgb888::YELLOW;
+const ALERT_COLOR: Rgb888 = Rgb888::RED;
+const UNAVAILABLE_COLOR: Rgb888 = Rgb888::new(128, 128, 128);
+
+/// Largest size of the circle drawn for binary sensors without icons
+const CIRCLE_DIAMETER: i32 = 8;
+
+/// The states Home Assistant reports for entities it can't read
+const UNAVAILABLE_STATES: [&str; 2] = ["unavailable", "unknown"];
+
+/// The states of binary sensors that are shown as on
+const ON_STATES: [&str; 3] = ["on", "open", "detected"];
+
+/// The states of binary sensors that are shown as off
+const OFF_STATES: [&str; 3] = ["off", "closed", "clear"];
+
+const DOOR_CLOSED_BYTES: &[u8] = include_bytes!("icons/door_closed_10.bmp");
+const DOOR_OPEN_BYTES: &[u8] = include_bytes!("icons/door_open_10.bmp");
+const MOTION_BYTES: &[u8] = include_bytes!("icons/motion_10.bmp");
+const MOTION_CLEAR_BYTES: &[u8] = include_bytes!("icons/motion_clear_10.bmp");
+const WINDOW_CLOSED_BYTES: &[u8] = include_bytes!("icons/window_closed_10.bmp");
+const WINDOW_OPEN_BYTES: &[u8] = include_bytes!("icons/window_open_10.bmp");
+
+lazy_static! {
+    static ref DOOR_CLOSED_ICON: Option<RgbaImage> =
+        load_image_or_log("door_closed_10.bmp", DOOR_CLOSED_BYTES, ImageFormat::Bmp);
+    static ref DOOR_OPEN_ICON: Option<RgbaImage> =
+        load_image_or_log("door_open_10.bmp", DOOR_OPEN_BYTES, ImageFormat::Bmp);
+    static ref MOTION_ICON: Option<RgbaImage> =
+        load_image_or_log("motion_10.bmp", MOTION_BYTES, ImageFormat::Bmp);
+    static ref MOTION_CLEAR_ICON: Option<RgbaImage> =
+        load_image_or_log("motion_clear_10.bmp", MOTION_CLEAR_BYTES, ImageFormat::Bmp);
+    static ref WINDOW_CLOSED_ICON: Option<RgbaImage> = load_image_or_log(
+        "window_closed_10.bmp",
+        WINDOW_CLOSED_BYTES,
+        ImageFormat::Bmp
+    );
+    static ref WINDOW_OPEN_ICON: Option<RgbaImage> =
+        load_image_or_log("window_open_10.bmp", WINDOW_OPEN_BYTES, ImageFormat::Bmp);
+}
+
+/// How the state of a sensor is shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorDisplay {
+    /// The state as text, along with its unit
+    #[default]
+    Text,
+
+    /// A glyph for whether the sensor is on or off, i.e. a door is open or
+    /// closed. States that aren't on or off are still shown as text
+    Binary,
+}
+
+/// An icon bundled with the render, or a PNG or BMP on disk, for example
+/// `"door_open"` or `{ "file": "icons/plant.png" }`.
+///
+/// The bundled icons are drawn in the color of the state they are shown for.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorIcon {
+    DoorClosed,
+    DoorOpen,
+    Motion,
+    MotionClear,
+    WindowClosed,
+    WindowOpen,
+
+    /// Path to a PNG or BMP, drawn in its own colors
+    File(String),
+}
+
+impl SensorIcon {
+    /// Loads the icon, tinting the bundled icons with `color`. Bundled icons
+    /// that could not be decoded are left out.
+    fn load(&self, color: Rgb888) -> Result<Option<RgbaImage>> {
+        let bundled = match self {
+            SensorIcon::DoorClosed => &*DOOR_CLOSED_ICON,
+            SensorIcon::DoorOpen => &*DOOR_OPEN_ICON,
+            SensorIcon::Motion => &*MOTION_ICON,
+            SensorIcon::MotionClear => &*MOTION_CLEAR_ICON,
+            SensorIcon::WindowClosed => &*WINDOW_CLOSED_ICON,
+            SensorIcon::WindowOpen => &*WINDOW_OPEN_ICON,
+            SensorIcon::File(path) => return load_icon(path).map(Some),
+        };
+
+        Ok(bundled.as_ref().map(|icon| tint(icon, color)))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SensorConfig {
+    /// The Home Assistant entity to show, i.e. `sensor.living_room_temperature`
+    pub entity_id: String,
+
+    pub label: String,
+
+    /// Shown after the value. Defaults to the `unit_of_measurement` of the
+    /// entity if not provided
+    pub unit: Option<String>,
+
+    /// Drawn before the label
+    pub icon: Option<SensorIcon>,
+
+    /// How the state is shown, defaults to text
+    #[serde(default)]
+    pub display: SensorDisplay,
+
+    /// Shown instead of the state of a binary sensor that is on. A filled
+    /// circle is shown if not provided
+    pub on_icon: Option<SensorIcon>,
+
+    /// Shown instead of the state of a binary sensor that is off. An empty
+    /// circle is shown if not provided
+    pub off_icon: Option<SensorIcon>,
+
+    /// The color of a binary sensor that is on as RGB, defaults to yellow
+    pub on_color: Option<[u8; 3]>,
+
+    /// The color of a binary sensor that is off as RGB, defaults to green
+    pub off_color: Option<[u8; 3]>,
+
+    /// The value is drawn in yellow when above this
+    pub warn_above: Option<f64>,
+
+    /// The value is drawn in red when above this
+    pub alert_above: Option<f64>,
+}
+
+/// Configuration for the [`HaSensorsFactory`], for example:
+///
+/// ```json
+/// {
+///   "url": "http://homeassistant.local:8123",
+///   "token": "...",
+///   "sensors": [
+///     { "entity_id": "sensor.office_temperature", "label": "Office", "warn_above": 80 },
+///     { "entity_id": "sensor.office_co2", "label": "CO2", "unit": "ppm" },
+///     {
+///       "entity_id": "binary_sensor.front_door",
+///       "label": "Door",
+///       "display": "binary",
+///       "on_icon": "door_open",
+///       "off_icon": "door_closed"
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct HaSensorsConfig {
+    /// URL of the Home Assistant instance
+    pub url: String,
+
+    /// A long-lived access token of Home Assistant
+    pub token: String,
+
+    /// The sensors to show, one per row
+    pub sensors: Vec<SensorConfig>,
+
+    /// Whether to have Home Assistant push state changes over its WebSocket
+    /// API, so they are shown right away. The sensors are still polled while
+    /// the connection is down. Defaults to false
+    #[serde(default)]
+    pub websocket: bool,
+
+    /// Font of the sensors, defaults to 6x10 if not provided
+    pub font: Option<Font>,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How long, in seconds, to wait between refreshes of the sensors.
+    /// Defaults to [`DEFAULT_REFRESH`] if not provided
+    pub refresh_secs: Option<u64>,
+
+    /// How long, in seconds, to wait before trying again when none of the
+    /// sensors could be fetched. Defaults to [`DEFAULT_ERROR_RETRY`] if not
+    /// provided
+    pub error_retry_secs: Option<u64>,
+}
+
+impl HaSensorsConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.sensors.is_empty() {
+            return Err(anyhow!("At least one sensor must be configured"));
+        }
+
+        for sensor in &self.sensors {
+            if let (Some(warn_above), Some(alert_above)) = (sensor.warn_above, sensor.alert_above) {
+                if warn_above > alert_above {
+                    return Err(anyhow!(
+                        "warn_above of {warn_above} is larger than alert_above of {alert_above} \
+                         for {}",
+                        sensor.entity_id
+                    ));
+                }
+            }
+        }
+
+        validate_refresh_interval("refresh_secs", self.refresh_secs)?;
+        validate_refresh_interval("error_retry_secs", self.error_retry_secs)
+    }
+}
+
+/// The last known state of a sensor
+#[derive(Debug, Clone, PartialEq)]
+enum SensorValue {
+    /// The state could not be fetched or Home Assistant can't read the entity
+    Unavailable,
+
+    Number(f64),
+
+    /// A state that isn't a number, i.e. `on` or `off`
+    Text(String),
+
+    /// Whether a sensor shown as [`SensorDisplay::Binary`] is on
+    Binary(bool),
+}
+
+impl SensorValue {
+    fn from_state(state: Option<StateEnum>, display: SensorDisplay) -> Self {
+        match state {
+            Some(StateEnum::Integer(value)) => SensorValue::Number(value as f64),
+            Some(StateEnum::Decimal(value)) => SensorValue::Number(value),
+            Some(StateEnum::Boolean(value)) => match display {
+                SensorDisplay::Binary => SensorValue::Binary(value),
+                SensorDisplay::Text => {
+                    SensorValue::Text(if value { "on" } else { "off" }.to_owned())
+                }
+            },
+            Some(StateEnum::String(value)) => {
+                if UNAVAILABLE_STATES.contains(&value.as_str()) {
+                    SensorValue::Unavailable
+                } else if display == SensorDisplay::Binary && ON_STATES.contains(&value.as_str()) {
+                    SensorValue::Binary(true)
+                } else if display == SensorDisplay::Binary && OFF_STATES.contains(&value.as_str()) {
+                    SensorValue::Binary(false)
+                } else {
+                    // Home Assistant reports most numeric sensors as strings
+                    match value.parse::<f64>() {
+                        Ok(number) => SensorValue::Number(number),
+                        Err(_) => SensorValue::Text(value),
+                    }
+                }
+            }
+            None => SensorValue::Unavailable,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SensorReading {
+    value: SensorValue,
+
+    /// The configured unit, otherwise the one reported by Home Assistant
+    unit: Option<String>,
+}
+
+impl Default for SensorReading {
+    fn default() -> Self {
+        Self {
+            value: SensorValue::Unavailable,
+            unit: None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct HaSensorsState {
+    /// One reading per configured sensor, in the same order
+    readings: Vec<SensorReading>,
+
+    /// When the sensors were last fetched
+    refreshed_at: Option<Instant>,
+}
+
+/// A configured sensor along with what is needed to draw it
+struct Sensor {
+    label: String,
+    icon: Option<RgbaImage>,
+    warn_above: Option<f64>,
+    alert_above: Option<f64>,
+
+    /// The glyphs of a binary sensor that is on and off, `None` to draw a
+    /// circle instead
+    on_icon: Option<RgbaImage>,
+    off_icon: Option<RgbaImage>,
+
+    on_color: Rgb888,
+    off_color: Rgb888,
+}
+
+impl Sensor {
+    fn color(&self, value: &SensorValue) -> Rgb888 {
+        match value {
+            SensorValue::Unavailable => UNAVAILABLE_COLOR,
+            SensorValue::Number(number) => {
+                if self
+                    .alert_above
+                    .is_some_and(|alert_above| *number > alert_above)
+                {
+                    ALERT_COLOR
+                } else if self
+                    .warn_above
+                    .is_some_and(|warn_above| *number > warn_above)
+                {
+                    WARN_COLOR
+                } else {
+                    VALUE_COLOR
+                }
+            }
+            SensorValue::Text(_) => VALUE_COLOR,
+            SensorValue::Binary(true) => self.on_color,
+            SensorValue::Binary(false) => self.off_color,
+        }
+    }
+
+    /// The height of the tallest icon of the sensor
+    fn icon_height(&self) -> i32 {
+        [&self.icon, &self.on_icon, &self.off_icon]
+            .into_iter()
+            .flatten()
+            .map(|icon| icon.height() as i32)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Draws the glyph of a binary sensor, right aligned to `top_right` and
+    /// centered in a row of `row_height`.
+    fn draw_glyph<D>(
+        &self,
+        canvas: &mut D,
+        on: bool,
+        top_right: Point,
+        row_height: i32,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    {
+        let glyph = if on { &self.on_icon } else { &self.off_icon };
+
+        match glyph {
+            Some(glyph) => {
+                AlphaImage::new(
+                    glyph,
+                    Point::new(
+                        top_right.x - glyph.width() as i32 + 1,
+                        top_right.y + (row_height - glyph.height() as i32) / 2,
+                    ),
+                )
+                .draw(canvas)?;
+            }
+            None => {
+                let diameter = (row_height - 2).clamp(1, CIRCLE_DIAMETER);
+                let color = self.color(&SensorValue::Binary(on));
+                let style = if on {
+                    PrimitiveStyle::with_fill(color)
+                } else {
+                    PrimitiveStyle::with_stroke(color, 1)
+                };
+
+                Circle::new(
+                    Point::new(
+                        top_right.x - diameter + 1,
+                        top_right.y + (row_height - diameter) / 2,
+                    ),
+                    diameter as u32,
+                )
+                .into_styled(style)
+                .draw(canvas)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Multiplies every pixel of `icon` by `color`, so a white glyph is drawn in
+/// `color`.
+fn tint(icon: &RgbaImage, color: Rgb888) -> RgbaImage {
+    let channel = |value: u8, tint: u8| ((value as u32 * tint as u32) / 255) as u8;
+
+    let mut tinted = icon.clone();
+    for pixel in tinted.pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        *pixel = Rgba([
+            channel(r, color.r()),
+            channel(g, color.g()),
+            channel(b, color.b()),
+            a,
+        ]);
+    }
+
+    tinted
+}
+
+/// Loads the icon at `path`, picking the decoder from its extension.
+fn load_icon(path: &str) -> Result<RgbaImage> {
+    let format = match Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("png") => ImageFormat::Png,
+        Some("bmp") => ImageFormat::Bmp,
+        _ => return Err(anyhow!("Icon {path} is not a PNG or BMP")),
+    };
+
+    let bytes = std::fs::read(path).map_err(|e| anyhow!("Could not read icon {path} {e}"))?;
+    load_image(&bytes, format)
+}
+
+impl SensorReading {
+    /// The reading of `sensor` in `state`, falling back to the
+    /// `unit_of_measurement` Home Assistant reports for it.
+    fn new(
+        sensor: &SensorConfig,
+        state: Option<StateEnum>,
+        unit_of_measurement: Option<&Value>,
+    ) -> Self {
+        let unit = sensor.unit.clone().or_else(|| {
+            unit_of_measurement
+                .and_then(|unit| unit.as_str())
+                .map(str::to_owned)
+        });
+
+        Self {
+            value: SensorValue::from_state(state, sensor.display),
+            unit,
+        }
+    }
+}
+
+/// Gets the state of `sensor`, from the WebSocket connection if it is up and
+/// otherwise over the REST API.
+async fn fetch_reading(
+    client: &home_assistant_rest::Client,
+    ws_client: Option<&HomeAssistantWsClient>,
+    sensor: &SensorConfig,
+    timeout: Duration,
+) -> Result<SensorReading> {
+    if let Some(entity_state) = ws_client.and_then(|ws_client| ws_client.state(&sensor.entity_id)) {
+        return Ok(SensorReading::new(
+            sensor,
+            entity_state.state_enum(),
+            entity_state.attributes.get("unit_of_measurement"),
+        ));
+    }
+
+    let entity_state = traced(
+        "HaSensors",
+        format!("home_assistant/states/{}", sensor.entity_id),
+        with_timeout(timeout, client.get_states_of_entity(&sensor.entity_id)),
+    )
+    .await?;
+
+    Ok(SensorReading::new(
+        sensor,
+        entity_state.state,
+        entity_state.attributes.get("unit_of_measurement"),
+    ))
+}
+
+/// Home Assistant sensor values, one labeled row per sensor, colored by how
+/// they compare against their thresholds.
+pub struct HaSensors {
+    state: Arc<Mutex<HaSensorsState>>,
+
+    sensors: Vec<Sensor>,
+
+    font: &'static MonoFont<'static>,
+
+    /// How long the sensors can go without a refresh before the render
+    /// reports it has no content
+    stale_after: Duration,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+
+    /// Used to pause the update task while the render is not displayed
+    poll_gate: PollGate,
+
+    /// Flag used to gracefully terminate the render and driver threads
+    cancel_token: CancellationToken,
+
+    /// Handle to the task used to update the sensors
+    update_task_handle: Option<JoinHandle<Result<()>>>,
+}
+
+impl HaSensors {
+    pub fn new(config: HaSensorsConfig) -> Result<Self> {
+        let home_assistant_client = home_assistant_rest::Client::new(&config.url, &config.token)?;
+        let ws_client = config
+            .websocket
+            .then(|| HomeAssistantWsClient::shared(&config.url, &config.token))
+            .transpose()?;
+
+        let sensors = config
+            .sensors
+            .iter()
+            .map(|sensor| {
+                let on_color = sensor
+                    .on_color
+                    .map(|[r, g, b]| Rgb888::new(r, g, b))
+                    .unwrap_or(WARN_COLOR);
+                let off_color = sensor
+                    .off_color
+                    .map(|[r, g, b]| Rgb888::new(r, g, b))
+                    .unwrap_or(VALUE_COLOR);
+
+                let load = |icon: &Option<SensorIcon>, color: Rgb888| {
+                    icon.as_ref()
+                        .map(|icon| icon.load(color))
+                        .transpose()
+                        .map(Option::flatten)
+                };
+
+                Ok(Sensor {
+                    label: sensor.label.clone(),
+                    icon: load(&sensor.icon, LABEL_COLOR)?,
+                    warn_above: sensor.warn_above,
+                    alert_above: sensor.alert_above,
+                    on_icon: load(&sensor.on_icon, on_color)?,
+                    off_icon: load(&sensor.off_icon, off_color)?,
+                    on_color,
+                    off_color,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let timeout = request_timeout(config.request_timeout_secs);
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let error_retry = refresh_interval(config.error_retry_secs, DEFAULT_ERROR_RETRY);
+
+        let state = Arc::new(Mutex::new(HaSensorsState::default()));
+        let poll_gate = PollGate::new();
+        let cancel_token = CancellationToken::new();
+
+        let task_cancel_token = cancel_token.clone();
+        let task_state = state.clone();
+        let task_poll_gate = poll_gate.clone();
+        let sensor_configs = config.sensors;
+        let mut subscription = ws_client.as_ref().map(|ws_client| {
+            ws_client.subscribe(sensor_configs.iter().map(|sensor| sensor.entity_id.clone()))
+        });
+
+        let update_task_handle = tokio::task::spawn(async move {
+            loop {
+                // Don't poll Home Assistant while the render is not being displayed
+                select! {
+                    _ = task_poll_gate.active() => {},
+                    _ = task_cancel_token.cancelled() => break,
+                }
+
+                let start_time = tokio::time::Instant::now();
+                let mut readings = Vec::with_capacity(sensor_configs.len());
+
+                for sensor in &sensor_configs {
+                    let reading_result = select! {
+                        reading_result = fetch_reading(&home_assistant_client, ws_client.as_deref(), sensor, timeout) => reading_result,
+                        _ = task_cancel_token.cancelled() => return Ok(()),
+                    };
+
+                    // A single failed sensor is shown as unavailable rather than
+                    // hiding the others
+                    readings.push(reading_result.map_err(|e| {
+                        warn!("Could not get state of {} {e}", sensor.entity_id);
+                    }));
+                }
+
+                let refresh_time = if readings.iter().any(Result::is_ok) {
+                    let mut state = task_state.lock();
+                    state.readings = readings
+                        .into_iter()
+                        .map(Result::unwrap_or_default)
+                        .collect();
+                    state.refreshed_at = Some(Instant::now());
+
+                    start_time + refresh
+                } else {
+                    error!("Could not get the state of any Home Assistant sensor");
+
+                    start_time + error_retry
+                };
+
+                select! {
+                    _ = tokio::time::sleep_until(refresh_time) => {},
+                    _ = home_assistant_ws::changed(subscription.as_mut()) => {},
+                    _ = task_poll_gate.paused() => {},
+                    _ = task_cancel_token.cancelled() => break,
+                }
+            }
+
+            Ok(())
+        });
+
+        Ok(Self {
+            state,
+            sensors,
+            font: config
+                .font
+                .map(|font| font.mono_font())
+                .unwrap_or(DEFAULT_FONT),
+            stale_after: refresh * STALE_AFTER_REFRESHES,
+            loading_spinner: loading_spinner(),
+            poll_gate,
+            cancel_token,
+            update_task_handle: Some(update_task_handle),
+        })
+    }
+}
+
+impl<D> Render<D> for HaSensors
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let state = self.state.lock();
+
+        if state.refreshed_at.is_none() {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let bounding_box = canvas.bounding_box();
+        let right = bounding_box.top_left.x + bounding_box.size.width as i32 - 1;
+        let text_height = self.font.character_size.height as i32;
+        let mut y = bounding_box.top_left.y;
+
+        let value_style = TextStyleBuilder::new()
+            .alignment(Alignment::Right)
+            .baseline(Baseline::Top)
+            .build();
+
+        for (sensor, reading) in self.sensors.iter().zip(&state.readings) {
+            let row_height = sensor.icon_height().max(text_height);
+
+            // Center the text against the icon
+            let text_y = y + (row_height - text_height) / 2;
+            let mut x = bounding_box.top_left.x;
+
+            if let Some(icon) = &sensor.icon {
+                AlphaImage::new(icon, Point::new(x, y)).draw(canvas)?;
+                x += icon.width() as i32 + ICON_SPACING;
+            }
+
+            Text::with_baseline(
+                &latin1_lossy(&sensor.label),
+                Point::new(x, text_y),
+                MonoTextStyle::new(self.font, LABEL_COLOR),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            let value = match &reading.value {
+                SensorValue::Unavailable => "N/A".to_owned(),
+                SensorValue::Number(number) => match &reading.unit {
+                    Some(unit) => format!("{number}{unit}"),
+                    None => number.to_string(),
+                },
+                SensorValue::Text(text) => text.clone(),
+                SensorValue::Binary(on) => {
+                    sensor.draw_glyph(canvas, *on, Point::new(right, y), row_height)?;
+
+                    y += row_height + ROW_SPACING;
+                    continue;
+                }
+            };
+
+            Text::with_text_style(
+                &latin1_lossy(&value),
+                Point::new(right, text_y),
+                MonoTextStyle::new(self.font, sensor.color(&reading.value)),
+                value_style,
+            )
+            .draw(canvas)?;
+
+            y += row_height + ROW_SPACING;
+        }
+
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.poll_gate.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.poll_gate.pause();
+    }
+
+    fn describe(&self) -> String {
+        let state = self.state.lock();
+
+        match state.refreshed_at {
+            Some(_) => {
+                let unavailable = state
+                    .readings
+                    .iter()
+                    .filter(|reading| reading.value == SensorValue::Unavailable)
+                    .count();
+
+                format!(
+                    "HaSensors: {} sensors, {unavailable} unavailable",
+                    state.readings.len()
+                )
+            }
+            None => "HaSensors: loading".to_owned(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        match self.state.lock().refreshed_at {
+            Some(refreshed_at) => refreshed_at.elapsed() < self.stale_after,
+            None => false,
+        }
+    }
+}
+
+impl Drop for HaSensors {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+
+        if let Some(task_handle) = self.update_task_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
+pub struct HaSensorsFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for HaSensorsFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for HaSensorsFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "HaSensors"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Home Assistant sensor values with thresholds"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: HaSensorsConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(HaSensors::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: HaSensorsConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(HaSensors::new(config)?))
+    }
+}
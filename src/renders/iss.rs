@@ -0,0 +1,468 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    mono_font::{self, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    text::{Baseline, Text},
+    Drawable,
+};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    clock::{SharedClock, SystemClock},
+    font::{latin1_lossy, Font},
+    render::{
+        BufferCanvas, ExampleConfig, Render, RenderError, RenderFactory, Spinner, SyncRender,
+    },
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+
+use crate::{
+    data_source::{DataSource, PollingSource},
+    loading::{draw_loading, loading_spinner},
+    request::{
+        refresh_interval, request_timeout, traced, validate_refresh_interval, STALE_AFTER_REFRESHES,
+    },
+};
+
+const N2YO_API_URL: &str = "https://api.n2yo.com/rest/v1/satellite";
+
+/// NORAD catalog number of the International Space Station
+const ISS_SATELLITE_ID: u32 = 25544;
+
+/// How many days ahead the passes are predicted, the most N2YO allows
+const PREDICTION_DAYS: u32 = 10;
+
+const DEFAULT_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_5X7;
+
+/// How long to wait between refreshes if no refresh interval is configured
+const DEFAULT_REFRESH: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long to wait after a failed request if no retry interval is
+/// configured
+const DEFAULT_ERROR_RETRY: Duration = Duration::from_secs(10 * 60);
+
+/// Color of the countdown while the satellite is overhead
+const OVERHEAD_COLOR: Rgb888 = Rgb888::GREEN;
+
+/// Color of the details of the pass
+const DETAIL_COLOR: Rgb888 = Rgb888::new(128, 128, 128);
+
+/// Configuration for the [`IssPassFactory`], for example:
+///
+/// ```json
+/// {
+///   "lat": 39.9526,
+///   "lon": -75.1652,
+///   "min_elevation": 20,
+///   "api_key": "..."
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssPassConfig {
+    pub lat: f64,
+    pub lon: f64,
+
+    /// Passes that do not reach this many degrees above the horizon are
+    /// skipped, defaults to 10
+    pub min_elevation: Option<u8>,
+
+    /// Your N2YO API key, free after registering at https://www.n2yo.com
+    pub api_key: String,
+
+    /// NORAD catalog number of the satellite, defaults to the ISS
+    pub satellite_id: Option<u32>,
+
+    /// Shown above the countdown, defaults to "ISS"
+    pub name: Option<String>,
+
+    /// Your altitude in meters, defaults to sea level
+    pub altitude: Option<f64>,
+
+    /// Font of the pass, defaults to 5x7 if not provided
+    pub font: Option<Font>,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How long, in seconds, to wait between refreshes of the passes. Defaults
+    /// to [`DEFAULT_REFRESH`] if not provided
+    pub refresh_secs: Option<u64>,
+
+    /// How long, in seconds, to wait before trying again when the passes could
+    /// not be fetched. Defaults to [`DEFAULT_ERROR_RETRY`] if not provided
+    pub error_retry_secs: Option<u64>,
+}
+
+impl IssPassConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !(-90.0..=90.0).contains(&self.lat) || !(-180.0..=180.0).contains(&self.lon) {
+            return Err(anyhow!(
+                "{}, {} is not a valid location",
+                self.lat,
+                self.lon
+            ));
+        }
+
+        if let Some(min_elevation) = self.min_elevation {
+            if min_elevation > 90 {
+                return Err(anyhow!("min_elevation must be at most 90 degrees"));
+            }
+        }
+
+        if self.api_key.is_empty() {
+            return Err(anyhow!("api_key must not be empty"));
+        }
+
+        validate_refresh_interval("refresh_secs", self.refresh_secs)?;
+        validate_refresh_interval("error_retry_secs", self.error_retry_secs)
+    }
+}
+
+/// A pass over the configured location as returned by the N2YO API
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Pass {
+    /// Seconds since the epoch the satellite rises above the horizon
+    #[serde(rename = "startUTC")]
+    start_utc: u64,
+
+    /// Seconds since the epoch the satellite sets below the horizon
+    #[serde(rename = "endUTC")]
+    end_utc: u64,
+
+    /// Highest elevation of the pass in degrees
+    max_el: f32,
+
+    /// Direction of the highest point of the pass, i.e. "NNE"
+    max_az_compass: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PassesResponse {
+    /// Set instead of the passes when the request was rejected
+    error: Option<String>,
+
+    /// Left out when there are no upcoming passes
+    #[serde(default)]
+    passes: Vec<Pass>,
+}
+
+/// Fetches the upcoming passes of the satellite of `config`.
+async fn fetch_passes(client: &reqwest::Client, config: &IssPassConfig) -> Result<Vec<Pass>> {
+    let response: PassesResponse = client
+        .get(format!(
+            "{N2YO_API_URL}/radiopasses/{}/{}/{}/{}/{PREDICTION_DAYS}/{}/",
+            config.satellite_id.unwrap_or(ISS_SATELLITE_ID),
+            config.lat,
+            config.lon,
+            config.altitude.unwrap_or(0.0),
+            config.min_elevation.unwrap_or(10),
+        ))
+        .query(&[("apiKey", &config.api_key)])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    match response.error {
+        Some(e) => Err(anyhow!(e)),
+        None => Ok(response.passes),
+    }
+}
+
+/// Formats the time until a pass, i.e. "2d 3h", "3h 12m" or "12m 05s"
+fn format_countdown(secs: u64) -> String {
+    let (days, hours, minutes, secs) =
+        (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60, secs % 60);
+
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else {
+        format!("{minutes}m {secs:02}s")
+    }
+}
+
+#[derive(Debug, Default)]
+struct IssPassState {
+    /// Sorted by when they start
+    passes: Vec<Pass>,
+
+    /// When the passes were last refreshed
+    refreshed_at: Option<Instant>,
+}
+
+/// A countdown to the next pass of the ISS, or any other satellite, over a
+/// location.
+///
+/// Shows the time until the satellite rises, then the highest elevation it
+/// reaches and in which direction to look for it. While the satellite is
+/// above the horizon the countdown is replaced by "Overhead". The passes of
+/// the next days are fetched once a day, so the countdown moves on to the
+/// next pass without another request.
+pub struct IssPass {
+    state: Mutex<IssPassState>,
+
+    /// Where the passes come from
+    source: Box<dyn DataSource<Vec<Pass>>>,
+
+    /// Watches the source for new passes
+    passes: Mutex<watch::Receiver<Option<Vec<Pass>>>>,
+
+    name: String,
+    font: &'static MonoFont<'static>,
+
+    /// How long the passes can go without a refresh before they are no longer
+    /// trusted
+    stale_after: Duration,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+
+    /// The countdown is to the passes as of this clock
+    clock: SharedClock,
+}
+
+impl IssPass {
+    pub fn new(config: IssPassConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(request_timeout(config.request_timeout_secs))
+            .build()?;
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let error_retry = refresh_interval(config.error_retry_secs, DEFAULT_ERROR_RETRY);
+        let name = config.name.clone().unwrap_or_else(|| "ISS".to_owned());
+        let font = config
+            .font
+            .map(|font| font.mono_font())
+            .unwrap_or(DEFAULT_FONT);
+
+        let task_name = name.clone();
+        let config = Arc::new(config);
+
+        let source = PollingSource::new("IssPass", refresh, error_retry, move || {
+            let client = client.clone();
+            let config = config.clone();
+            let task_name = task_name.clone();
+
+            async move {
+                let mut passes = traced(
+                    "IssPass",
+                    "n2yo/radiopasses",
+                    fetch_passes(&client, &config),
+                )
+                .await
+                .map_err(|e| anyhow!("for {task_name} {e}"))?;
+                passes.sort_by_key(|pass| pass.start_utc);
+
+                Ok(passes)
+            }
+        });
+        let source: Box<dyn DataSource<Vec<Pass>>> = Box::new(source);
+
+        Ok(Self {
+            state: Mutex::new(IssPassState::default()),
+            passes: Mutex::new(source.subscribe()),
+            source,
+            name,
+            font,
+            stale_after: refresh * STALE_AFTER_REFRESHES,
+            loading_spinner: loading_spinner(),
+            clock: SystemClock::shared(),
+        })
+    }
+
+    /// Takes the latest passes of the source, if they changed since they were
+    /// last taken
+    fn update_passes(&self) {
+        let mut passes = self.passes.lock();
+
+        if passes.has_changed().unwrap_or(false) {
+            if let Some(passes) = &*passes.borrow_and_update() {
+                let mut state = self.state.lock();
+                state.passes = passes.clone();
+                state.refreshed_at = Some(Instant::now());
+            }
+        }
+    }
+
+    /// Counts down to the passes as of `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// The current time as seconds since the Unix epoch, like the times of
+    /// the passes
+    fn unix_now(&self) -> u64 {
+        self.clock.now().timestamp().max(0) as u64
+    }
+}
+
+impl<D> Render<D> for IssPass
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.update_passes();
+        let state = self.state.lock();
+
+        if state.refreshed_at.is_none() {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let now = self.unix_now();
+        let row_height = self.font.character_size.height as i32 + 1;
+        let mut top_left = canvas.bounding_box().top_left;
+
+        let mut draw_row = |text: &str, color: Rgb888, canvas: &mut D| -> Result<(), D::Error> {
+            Text::with_baseline(
+                &latin1_lossy(text),
+                top_left,
+                MonoTextStyle::new(self.font, color),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+            top_left += Point::new(0, row_height);
+
+            Ok(())
+        };
+
+        draw_row(&self.name, Rgb888::WHITE, canvas)?;
+
+        // Passes that already ended are skipped until the next refresh drops them
+        match state.passes.iter().find(|pass| pass.end_utc > now) {
+            Some(pass) => {
+                if pass.start_utc <= now {
+                    draw_row("Overhead", OVERHEAD_COLOR, canvas)?;
+                } else {
+                    draw_row(
+                        &format_countdown(pass.start_utc - now),
+                        Rgb888::WHITE,
+                        canvas,
+                    )?;
+                }
+
+                draw_row(
+                    &format!("{:.0}° {}", pass.max_el, pass.max_az_compass),
+                    DETAIL_COLOR,
+                    canvas,
+                )
+            }
+            None => draw_row("No passes", DETAIL_COLOR, canvas),
+        }
+    }
+
+    fn on_select(&self) {
+        self.source.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.source.pause();
+    }
+
+    fn describe(&self) -> String {
+        self.update_passes();
+        let state = self.state.lock();
+
+        if state.refreshed_at.is_none() {
+            return "IssPass: loading".to_owned();
+        }
+
+        let now = self.unix_now();
+        match state.passes.iter().find(|pass| pass.end_utc > now) {
+            Some(pass) if pass.start_utc <= now => format!("IssPass: {} overhead", self.name),
+            Some(pass) => format!(
+                "IssPass: {} in {}",
+                self.name,
+                format_countdown(pass.start_utc - now)
+            ),
+            None => format!("IssPass: no upcoming passes of {}", self.name),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        self.update_passes();
+        let state = self.state.lock();
+        let now = self.unix_now();
+
+        match state.refreshed_at {
+            Some(refreshed_at) => {
+                refreshed_at.elapsed() < self.stale_after
+                    && state.passes.iter().any(|pass| pass.end_utc > now)
+            }
+            None => false,
+        }
+    }
+}
+
+pub struct IssPassFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for IssPassFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for IssPassFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "IssPass"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Countdown to the next pass of the ISS over a location"
+    }
+
+    fn example_config(&self) -> Option<ExampleConfig> {
+        Some(json!({
+            "lat": 39.9526,
+            "lon": -75.1652,
+            "min_elevation": 20,
+            "api_key": "your-n2yo-api-key"
+        }))
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: IssPassConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(IssPass::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: IssPassConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(IssPass::new(config)?))
+    }
+}
@@ -0,0 +1,208 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable,
+};
+use rustic_pixel_display::{
+    font::Font,
+    render::{BufferCanvas, ExampleConfig, Render, RenderError, RenderFactory, SyncRender},
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::{convert::Infallible, io::Read, marker::PhantomData};
+
+/// Space between the lines of the label
+const LINE_SPACING: i32 = 2;
+
+const DEFAULT_FONT: Font = Font::SixByTen;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelAlign {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+impl From<LabelAlign> for Alignment {
+    fn from(value: LabelAlign) -> Self {
+        match value {
+            LabelAlign::Left => Alignment::Left,
+            LabelAlign::Center => Alignment::Center,
+            LabelAlign::Right => Alignment::Right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LabelLine {
+    pub text: String,
+
+    /// Defaults to 6x10 if not provided
+    pub font: Option<Font>,
+
+    /// The color of the text as RGB, defaults to white
+    pub color: Option<[u8; 3]>,
+}
+
+/// Configuration for the [`LabelFactory`], for example:
+///
+/// ```json
+/// {
+///   "lines": [
+///     { "text": "Welcome", "font": "10x20", "color": [255, 128, 0] },
+///     { "text": "Back in 5 minutes" }
+///   ],
+///   "align": "center"
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct LabelConfig {
+    pub lines: Vec<LabelLine>,
+
+    /// How each line is aligned horizontally, defaults to centered
+    #[serde(default)]
+    pub align: LabelAlign,
+}
+
+/// Draws a few lines of static text, stacked and centered vertically on the
+/// canvas.
+pub struct Label {
+    lines: Vec<(String, Font, Rgb888)>,
+    align: LabelAlign,
+}
+
+impl Label {
+    pub fn new(config: LabelConfig) -> Result<Self> {
+        if config.lines.is_empty() {
+            return Err(anyhow!("A label needs at least one line"));
+        }
+
+        let lines = config
+            .lines
+            .into_iter()
+            .map(|line| {
+                let color = line
+                    .color
+                    .map_or(Rgb888::WHITE, |[r, g, b]| Rgb888::new(r, g, b));
+
+                (line.text, line.font.unwrap_or(DEFAULT_FONT), color)
+            })
+            .collect();
+
+        Ok(Self {
+            lines,
+            align: config.align,
+        })
+    }
+}
+
+impl<D> Render<D> for Label
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let bounding_box = canvas.bounding_box();
+
+        let line_heights = self
+            .lines
+            .iter()
+            .map(|(_, font, _)| font.mono_font().character_size.height as i32);
+        let total_height =
+            line_heights.clone().sum::<i32>() + LINE_SPACING * (self.lines.len() as i32 - 1);
+
+        let x = match self.align {
+            LabelAlign::Left => bounding_box.top_left.x,
+            LabelAlign::Center => bounding_box.center().x,
+            LabelAlign::Right => bounding_box.top_left.x + bounding_box.size.width as i32 - 1,
+        };
+        let mut y = bounding_box.center().y - total_height / 2;
+
+        let text_style = TextStyleBuilder::new()
+            .alignment(self.align.into())
+            .baseline(Baseline::Top)
+            .build();
+
+        for ((text, font, color), line_height) in self.lines.iter().zip(line_heights) {
+            Text::with_text_style(
+                text,
+                Point::new(x, y),
+                MonoTextStyle::new(font.mono_font(), *color),
+                text_style,
+            )
+            .draw(canvas)?;
+            y += line_height + LINE_SPACING;
+        }
+
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        let text = self
+            .lines
+            .iter()
+            .map(|(text, _, _)| text.as_str())
+            .collect::<Vec<_>>()
+            .join(" / ");
+
+        format!("Label: {text}")
+    }
+}
+
+pub struct LabelFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for LabelFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for LabelFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "Label"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Displays a few lines of static text"
+    }
+
+    fn example_config(&self) -> Option<ExampleConfig> {
+        Some(json!({
+            "lines": [
+                { "text": "Welcome", "font": "10x20", "color": [255, 128, 0] },
+                { "text": "Back in 5 minutes" }
+            ],
+            "align": "center"
+        }))
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: LabelConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(Label::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: LabelConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(Label::new(config)?))
+    }
+}
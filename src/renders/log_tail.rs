@@ -0,0 +1,234 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    mono_font::{self, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    text::{Baseline, Text},
+    Drawable,
+};
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    font::Font,
+    render::{BufferCanvas, Render, RenderError, RenderFactory, SyncRender},
+};
+use serde::Deserialize;
+use std::{
+    collections::VecDeque, convert::Infallible, io::Read, marker::PhantomData, str::FromStr,
+    sync::Arc,
+};
+
+/// The most records the logger keeps, regardless of the capacity of the renders
+const MAX_RECORDS: usize = 100;
+
+const DEFAULT_CAPACITY: usize = 10;
+const DEFAULT_MIN_LEVEL: LevelFilter = LevelFilter::Warn;
+
+const DEFAULT_FONT: &mono_font::MonoFont = &mono_font::ascii::FONT_5X7;
+const LINE_SPACING: i32 = 1;
+
+#[derive(Debug, Clone)]
+struct LogLine {
+    level: Level,
+    message: String,
+}
+
+lazy_static! {
+    /// Records captured by [`TailLogger`], oldest first
+    static ref LOG_LINES: Arc<Mutex<VecDeque<LogLine>>> =
+        Arc::new(Mutex::new(VecDeque::with_capacity(MAX_RECORDS)));
+}
+
+/// A [`Log`] that keeps the most recent records for the [`LogTail`] render and
+/// forwards every record to another logger.
+pub struct TailLogger<L> {
+    inner: L,
+    lines: Arc<Mutex<VecDeque<LogLine>>>,
+}
+
+impl<L: Log> Log for TailLogger<L> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata) || metadata.level() <= Level::Info
+    }
+
+    fn log(&self, record: &Record) {
+        if record.level() <= Level::Info {
+            let mut lines = self.lines.lock();
+
+            if lines.len() == MAX_RECORDS {
+                lines.pop_front();
+            }
+
+            lines.push_back(LogLine {
+                level: record.level(),
+                message: record.args().to_string(),
+            });
+        }
+
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Installs a [`TailLogger`] wrapping `env_logger` as the global logger.
+///
+/// This is opt-in, binaries that don't call it (and call `env_logger::init()`
+/// instead) will only ever show an empty [`LogTail`]. Records up to `info` are
+/// captured even if `env_logger` is configured to filter them out.
+pub fn init_logger(env_logger: env_logger::Logger) -> Result<(), SetLoggerError> {
+    let max_level = env_logger.filter().max(LevelFilter::Info);
+
+    log::set_boxed_logger(Box::new(TailLogger {
+        inner: env_logger,
+        lines: LOG_LINES.clone(),
+    }))?;
+    log::set_max_level(max_level);
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogTailConfig {
+    /// The number of records displayed, defaults to 10
+    pub capacity: Option<usize>,
+
+    /// The least severe level displayed, i.e. "warn", defaults to "warn"
+    pub min_level: Option<String>,
+
+    /// Defaults to 5x7 if not provided
+    pub font: Option<Font>,
+}
+
+/// Displays the most recent log records, newest at the bottom, colored by
+/// their level.
+pub struct LogTail {
+    lines: Arc<Mutex<VecDeque<LogLine>>>,
+    capacity: usize,
+    min_level: LevelFilter,
+    font: &'static mono_font::MonoFont<'static>,
+}
+
+impl LogTail {
+    pub fn new(config: LogTailConfig) -> Result<Self> {
+        let min_level = match config.min_level {
+            Some(min_level) => LevelFilter::from_str(&min_level)
+                .map_err(|_| anyhow!("Unknown log level \"{min_level}\""))?,
+            None => DEFAULT_MIN_LEVEL,
+        };
+
+        Ok(Self {
+            lines: LOG_LINES.clone(),
+            capacity: config.capacity.unwrap_or(DEFAULT_CAPACITY),
+            min_level,
+            font: config
+                .font
+                .map(|font| font.mono_font())
+                .unwrap_or(DEFAULT_FONT),
+        })
+    }
+}
+
+fn level_color(level: Level) -> Rgb888 {
+    match level {
+        Level::Error => Rgb888::RED,
+        Level::Warn => Rgb888::YELLOW,
+        Level::Info => Rgb888::WHITE,
+        Level::Debug | Level::Trace => Rgb888::new(128, 128, 128),
+    }
+}
+
+impl<D> Render<D> for LogTail
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let lines = self.lines.lock();
+        let bounding_box = canvas.bounding_box();
+        let line_height = self.font.character_size.height as i32 + LINE_SPACING;
+
+        // Start at the bottom with the newest record and work up
+        let mut position = Point::new(
+            bounding_box.top_left.x,
+            bounding_box.top_left.y + bounding_box.size.height as i32,
+        );
+
+        for line in lines
+            .iter()
+            .rev()
+            .filter(|line| line.level <= self.min_level)
+            .take(self.capacity)
+        {
+            position.y -= line_height;
+
+            if position.y < bounding_box.top_left.y {
+                break;
+            }
+
+            Text::with_baseline(
+                &line.message,
+                position,
+                MonoTextStyle::new(self.font, level_color(line.level)),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+        }
+
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "LogTail: {} of the last {} records",
+            self.min_level, self.capacity
+        )
+    }
+}
+
+pub struct LogTailFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for LogTailFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for LogTailFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "LogTail"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Displays the most recent log messages"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: LogTailConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(LogTail::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: LogTailConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(LogTail::new(config)?))
+    }
+}
@@ -1,3 +1,25 @@
+pub mod bus_arrivals;
+pub mod ci_status;
+pub mod commute;
+pub mod crypto;
+pub mod date_tile;
+pub mod flip_board;
+pub mod geo_debug;
+pub mod gtfs_rt;
+pub mod ha_sensors;
+pub mod iss;
+pub mod label;
+pub mod log_tail;
+pub mod now_playing;
 pub mod person_tracker;
+pub mod ping;
+pub mod presence_gate;
+pub mod radar;
+pub mod septa_alerts;
+pub mod sparkline;
+pub mod test_pattern;
+pub mod train_map;
 pub mod upcoming_arrivals;
 pub mod weather;
+pub mod webhook;
+pub mod wifi;
@@ -0,0 +1,609 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    draw_target::DrawTargetExt,
+    image::Image,
+    mono_font::MonoTextStyle,
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, Point, Primitive, RgbColor, Size, WebColors},
+    primitives::{Circle, Line, PrimitiveStyle, Rectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    transform::Transform,
+    Drawable,
+};
+use image::RgbImage;
+use log::{debug, info, warn};
+use mpd::{idle::Subsystem, Client, Idle, Song, State};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    font::{latin1_lossy, Font},
+    render::{
+        animation_time, BufferCanvas, ExampleConfig, Gauge, Render, RenderError, RenderFactory,
+        Spinner, SyncRender,
+    },
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    net::{Shutdown, TcpStream},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    assets::{fit_image, RgbImageDrawable},
+    loading::{draw_loading, loading_spinner},
+    scroll::{scroll_position, text_width},
+};
+
+const DEFAULT_PORT: u16 = 6600;
+
+const DEFAULT_FONT: Font = Font::SixByTen;
+
+/// How long to wait before reconnecting after the connection to MPD dropped
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+/// How fast, in pixels per second, titles that do not fit on the canvas scroll
+const SCROLL_SPEED: u64 = 20;
+
+/// Space between the lines of text
+const LINE_SPACING: i32 = 2;
+
+const PROGRESS_HEIGHT: u32 = 3;
+
+/// Space between the album art and the text, and above the progress bar
+const ART_MARGIN: u32 = 2;
+
+/// Color of the music note drawn when a song has no album art
+const NOTE_COLOR: Rgb888 = Rgb888::CSS_DODGER_BLUE;
+
+const TITLE_COLOR: Rgb888 = Rgb888::WHITE;
+const ARTIST_COLOR: Rgb888 = Rgb888::CSS_LIGHT_GRAY;
+const PAUSED_COLOR: Rgb888 = Rgb888::CSS_GRAY;
+const PROGRESS_COLOR: Rgb888 = Rgb888::CSS_DODGER_BLUE;
+const PROGRESS_BACKGROUND: Rgb888 = Rgb888::new(32, 32, 32);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NowPlayingConfig {
+    /// Host name or address of the MPD server
+    pub host: String,
+
+    /// Port of the MPD server, defaults to 6600 if not provided
+    pub port: Option<u16>,
+
+    /// Font of the title and artist, defaults to 6x10 if not provided
+    pub font: Option<Font>,
+}
+
+impl NowPlayingConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.host.is_empty() {
+            return Err(anyhow!("host is empty"));
+        }
+
+        Ok(())
+    }
+}
+
+/// The song MPD is playing, or has paused
+#[derive(Debug, Clone)]
+struct Track {
+    /// Path of the song in the MPD library, identifies the song
+    file: String,
+
+    title: String,
+    artist: Option<String>,
+    paused: bool,
+
+    /// The position in the song when it was read
+    elapsed: Duration,
+    duration: Option<Duration>,
+
+    /// When the position was read, to advance it while playing
+    read_at: Instant,
+
+    /// The cover of the album, `None` if MPD has none
+    art: Option<Arc<RgbImage>>,
+}
+
+impl Track {
+    /// How far through the song playback is, between 0 and 1
+    fn progress(&self) -> Option<f32> {
+        let duration = self.duration.filter(|duration| !duration.is_zero())?;
+
+        let position = if self.paused {
+            self.elapsed
+        } else {
+            self.elapsed + self.read_at.elapsed()
+        };
+
+        Some((position.as_secs_f32() / duration.as_secs_f32()).min(1.0))
+    }
+}
+
+#[derive(Debug, Default)]
+struct NowPlayingState {
+    /// `None` while MPD is stopped
+    track: Option<Track>,
+
+    /// Whether the connection to MPD is up
+    connected: bool,
+
+    /// Set once MPD has been reached at least once
+    has_loaded: bool,
+}
+
+/// Album art fetched for a song, so it is only fetched once per song
+type ArtCache = Option<(String, Option<Arc<RgbImage>>)>;
+
+/// Fetches and decodes the album art of `song`, `None` if MPD has none.
+fn read_album_art<S>(client: &mut Client<S>, song: &Song) -> Option<RgbImage>
+where
+    S: std::io::Read + std::io::Write,
+{
+    let bytes = match client.albumart(song) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            debug!("No album art for {} {e}", song.file);
+            return None;
+        }
+    };
+
+    match image::load_from_memory(&bytes) {
+        Ok(image) => Some(image.into_rgb8()),
+        Err(e) => {
+            warn!("Could not decode the album art of {} {e}", song.file);
+            None
+        }
+    }
+}
+
+/// Reads the song MPD is currently playing, `None` if it is stopped.
+///
+/// The album art is only fetched when the song differs from the one in
+/// `art_cache`.
+fn read_track<S>(client: &mut Client<S>, art_cache: &mut ArtCache) -> Result<Option<Track>>
+where
+    S: std::io::Read + std::io::Write,
+{
+    let status = client.status()?;
+
+    if status.state == State::Stop {
+        return Ok(None);
+    }
+
+    let Some(song) = client.currentsong()? else {
+        return Ok(None);
+    };
+
+    let art = match art_cache {
+        Some((file, art)) if *file == song.file => art.clone(),
+        _ => {
+            let art = read_album_art(client, &song).map(Arc::new);
+            *art_cache = Some((song.file.clone(), art.clone()));
+            art
+        }
+    };
+
+    Ok(Some(Track {
+        file: song.file.clone(),
+        // Untagged files are named after their path
+        title: song.title.clone().unwrap_or_else(|| {
+            song.file
+                .rsplit('/')
+                .next()
+                .unwrap_or(&song.file)
+                .to_owned()
+        }),
+        artist: song.artist,
+        paused: status.state == State::Pause,
+        elapsed: status.elapsed.unwrap_or_default(),
+        duration: status.duration.or(song.duration),
+        read_at: Instant::now(),
+        art,
+    }))
+}
+
+/// Connects to MPD at `address` and keeps `state` up to date until the
+/// connection drops or `cancel_token` is cancelled.
+///
+/// The socket is handed to `socket` so it can be shut down to interrupt a
+/// blocking wait for changes.
+fn follow_player(
+    address: &str,
+    state: &Mutex<NowPlayingState>,
+    socket: &Mutex<Option<TcpStream>>,
+    cancel_token: &CancellationToken,
+) -> Result<()> {
+    let stream = TcpStream::connect(address)?;
+    *socket.lock() = Some(stream.try_clone()?);
+
+    let mut client = Client::new(stream)?;
+    info!("Connected to MPD at {address}");
+
+    let mut art_cache = None;
+
+    while !cancel_token.is_cancelled() {
+        let track = read_track(&mut client, &mut art_cache)?;
+
+        {
+            let mut state = state.lock();
+            state.track = track;
+            state.connected = true;
+            state.has_loaded = true;
+        }
+
+        // Blocks until a song starts, stops, pauses or is seeked
+        client.idle(&[Subsystem::Player])?.get()?;
+    }
+
+    Ok(())
+}
+
+/// Album art scaled to the region it is drawn in
+struct ScaledArt {
+    file: String,
+    size: Size,
+    image: RgbImageDrawable,
+}
+
+/// Draws a generic music note filling `area`, in place of missing album art
+fn draw_music_note<D>(canvas: &mut D, area: Rectangle) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    let side = area.size.width.min(area.size.height) as i32;
+    let head_diameter = (side / 2).max(2);
+    let stroke = (side / 8).max(1) as u32;
+
+    let head_top_left = area.top_left + Point::new(0, side - head_diameter);
+    let stem_x = head_top_left.x + head_diameter - stroke as i32 / 2 - 1;
+    let stem_bottom = head_top_left.y + head_diameter / 2;
+
+    Circle::new(head_top_left, head_diameter as u32)
+        .into_styled(PrimitiveStyle::with_fill(NOTE_COLOR))
+        .draw(canvas)?;
+
+    Line::new(
+        Point::new(stem_x, stem_bottom),
+        Point::new(stem_x, area.top_left.y),
+    )
+    .into_styled(PrimitiveStyle::with_stroke(NOTE_COLOR, stroke))
+    .draw(canvas)?;
+
+    Line::new(
+        Point::new(stem_x, area.top_left.y),
+        Point::new(area.top_left.x + side - 1, area.top_left.y + side / 4),
+    )
+    .into_styled(PrimitiveStyle::with_stroke(NOTE_COLOR, stroke))
+    .draw(canvas)?;
+
+    Ok(())
+}
+
+/// The song playing on an MPD server: the album art, and next to it the title
+/// and artist, scrolling if they do not fit, above a bar showing the position
+/// in the song.
+pub struct NowPlaying {
+    state: Arc<Mutex<NowPlayingState>>,
+
+    font: Font,
+
+    /// The album art of the current song scaled to the canvas, so it is only
+    /// scaled once per song
+    scaled_art: Mutex<Option<ScaledArt>>,
+
+    /// The connection to MPD, shut down to stop the update task while it waits
+    /// for changes
+    socket: Arc<Mutex<Option<TcpStream>>>,
+
+    /// Drawn until MPD is reached
+    loading_spinner: Spinner,
+
+    /// Flag used to gracefully terminate the update task
+    cancel_token: CancellationToken,
+
+    /// Handle to the task used to follow the player
+    update_task_handle: Option<JoinHandle<()>>,
+}
+
+impl NowPlaying {
+    pub fn new(config: NowPlayingConfig) -> Result<Self> {
+        let address = format!("{}:{}", config.host, config.port.unwrap_or(DEFAULT_PORT));
+        let state = Arc::new(Mutex::new(NowPlayingState::default()));
+        let socket = Arc::new(Mutex::new(None));
+        let cancel_token = CancellationToken::new();
+
+        let task_state = state.clone();
+        let task_socket = socket.clone();
+        let task_cancel_token = cancel_token.clone();
+
+        // The MPD client is blocking, and is woken by MPD instead of polling, so
+        // it follows the player even while the render is not displayed
+        let update_task_handle = tokio::task::spawn_blocking(move || {
+            while !task_cancel_token.is_cancelled() {
+                if let Err(e) =
+                    follow_player(&address, &task_state, &task_socket, &task_cancel_token)
+                {
+                    if !task_cancel_token.is_cancelled() {
+                        warn!("Lost connection to MPD at {address} {e}");
+                    }
+                }
+
+                task_state.lock().connected = false;
+                task_socket.lock().take();
+
+                if !task_cancel_token.is_cancelled() {
+                    std::thread::sleep(RECONNECT_DELAY);
+                }
+            }
+        });
+
+        Ok(Self {
+            state,
+            font: config.font.unwrap_or(DEFAULT_FONT),
+            scaled_art: Mutex::new(None),
+            socket,
+            loading_spinner: loading_spinner(),
+            cancel_token,
+            update_task_handle: Some(update_task_handle),
+        })
+    }
+
+    /// Draws the album art of `track` centered in the square `area`, or a music
+    /// note if it has none
+    fn draw_art<D>(&self, canvas: &mut D, track: &Track, area: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    {
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+
+        let Some(art) = &track.art else {
+            return draw_music_note(canvas, area);
+        };
+
+        let mut scaled_art = self.scaled_art.lock();
+
+        let is_current = scaled_art
+            .as_ref()
+            .is_some_and(|scaled| scaled.file == track.file && scaled.size == area.size);
+
+        if !is_current {
+            *scaled_art = Some(ScaledArt {
+                file: track.file.clone(),
+                size: area.size,
+                image: fit_image(art, area.size).into(),
+            });
+        }
+
+        let Some(scaled) = scaled_art.as_ref() else {
+            return Ok(());
+        };
+
+        let image_size = scaled.image.size();
+        let offset = Point::new(
+            (area.size.width - image_size.width) as i32 / 2,
+            (area.size.height - image_size.height) as i32 / 2,
+        );
+
+        Image::new(&scaled.image, area.top_left + offset).draw(canvas)?;
+
+        Ok(())
+    }
+
+    /// Draws `text` on the line whose top is at `y`, scrolling as of `now` if
+    /// it does not fit on the canvas
+    fn draw_line<D>(
+        &self,
+        canvas: &mut D,
+        text: &str,
+        y: i32,
+        color: Rgb888,
+        now: Instant,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    {
+        let font = self.font.mono_font();
+        let bounding_box = canvas.bounding_box();
+        let text = latin1_lossy(text);
+
+        let x = scroll_position(
+            text_width(&text, font),
+            bounding_box.size.width,
+            SCROLL_SPEED,
+            animation_time(now),
+        );
+
+        Text::with_baseline(
+            &text,
+            Point::new(bounding_box.top_left.x + x, y),
+            MonoTextStyle::new(font, color),
+            Baseline::Top,
+        )
+        .draw(canvas)?;
+
+        Ok(())
+    }
+}
+
+impl<D> Render<D> for NowPlaying
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let state = self.state.lock();
+
+        if !state.has_loaded {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let Some(track) = state.track.clone() else {
+            drop(state);
+
+            Text::with_text_style(
+                "Stopped",
+                canvas.bounding_box().center(),
+                MonoTextStyle::new(self.font.mono_font(), PAUSED_COLOR),
+                TextStyleBuilder::new()
+                    .alignment(Alignment::Center)
+                    .baseline(Baseline::Middle)
+                    .build(),
+            )
+            .draw(canvas)?;
+
+            return Ok(());
+        };
+
+        drop(state);
+
+        let bounding_box = canvas.bounding_box();
+        let line_height = self.font.mono_font().character_size.height as i32;
+
+        // The art is a square as tall as the space above the progress bar
+        let art_side = bounding_box
+            .size
+            .height
+            .saturating_sub(PROGRESS_HEIGHT + ART_MARGIN)
+            .min(bounding_box.size.width / 2);
+        let art_area = Rectangle::new(bounding_box.top_left, Size::new(art_side, art_side));
+
+        self.draw_art(canvas, &track, art_area)?;
+
+        let text_left = art_side + ART_MARGIN;
+        let text_area = Rectangle::new(
+            bounding_box.top_left + Point::new(text_left as i32, 0),
+            Size::new(bounding_box.size.width.saturating_sub(text_left), art_side),
+        );
+        let mut text_canvas = canvas.clipped(&text_area);
+        let mut y = text_area.top_left.y;
+
+        let (title_color, artist_color) = if track.paused {
+            (PAUSED_COLOR, PAUSED_COLOR)
+        } else {
+            (TITLE_COLOR, ARTIST_COLOR)
+        };
+
+        self.draw_line(&mut text_canvas, &track.title, y, title_color, now)?;
+        y += line_height + LINE_SPACING;
+
+        if let Some(artist) = &track.artist {
+            self.draw_line(&mut text_canvas, artist, y, artist_color, now)?;
+        }
+
+        if let Some(progress) = track.progress() {
+            Gauge::new(
+                Size::new(bounding_box.size.width, PROGRESS_HEIGHT),
+                progress,
+                PROGRESS_COLOR,
+            )
+            .with_background(PROGRESS_BACKGROUND)
+            .translate(Point::new(
+                bounding_box.top_left.x,
+                bounding_box.top_left.y + (bounding_box.size.height - PROGRESS_HEIGHT) as i32,
+            ))
+            .draw(canvas)?;
+        }
+
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        let state = self.state.lock();
+
+        if !state.has_loaded {
+            return "NowPlaying: loading".to_owned();
+        }
+
+        match &state.track {
+            Some(track) => match &track.artist {
+                Some(artist) => format!("NowPlaying: {} by {artist}", track.title),
+                None => format!("NowPlaying: {}", track.title),
+            },
+            None => "NowPlaying: stopped".to_owned(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        self.state.lock().connected
+    }
+}
+
+impl Drop for NowPlaying {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+
+        // Wakes the update task if it is waiting for MPD
+        if let Some(socket) = self.socket.lock().take() {
+            let _ = socket.shutdown(Shutdown::Both);
+        }
+
+        if let Some(task_handle) = self.update_task_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
+pub struct NowPlayingFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for NowPlayingFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for NowPlayingFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "NowPlaying"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "The song playing on an MPD server"
+    }
+
+    fn example_config(&self) -> Option<ExampleConfig> {
+        Some(json!({ "host": "localhost", "port": 6600 }))
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: NowPlayingConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(NowPlaying::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: NowPlayingConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(NowPlaying::new(config)?))
+    }
+}
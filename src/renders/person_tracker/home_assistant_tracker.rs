@@ -17,23 +17,38 @@ use log::warn;
 use parking_lot::Mutex;
 use rustic_pixel_display::render::SubCanvas;
 use serde::Deserialize;
-use std::{convert::Infallible, sync::Arc, time::Duration};
+use std::{
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 use tinybmp::Bmp;
 use tokio::{select, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
+use crate::{
+    assets::load_bmp_or_log,
+    home_assistant_ws::{self, HomeAssistantWsClient},
+    request::{request_timeout, traced, with_timeout},
+};
+
 const HOME_BYTES: &[u8] = include_bytes!("icons/home_48.bmp");
 const LOCATION_AWAY_BYTES: &[u8] = include_bytes!("icons/location_away_48.bmp");
 const UNKNOWN_BYTES: &[u8] = include_bytes!("icons/unknown_48.bmp");
 const WORK_BYTES: &[u8] = include_bytes!("icons/work_48.bmp");
 
 lazy_static! {
-    static ref HOME_BMP: Bmp::<'static, Rgb888> = Bmp::<Rgb888>::from_slice(HOME_BYTES).unwrap();
-    static ref LOCATION_AWAY_BMP: Bmp::<'static, Rgb888> =
-        Bmp::<Rgb888>::from_slice(LOCATION_AWAY_BYTES).unwrap();
-    static ref UNKNOWN_BMP: Bmp::<'static, Rgb888> =
-        Bmp::<Rgb888>::from_slice(UNKNOWN_BYTES).unwrap();
-    static ref WORK_BMP: Bmp::<'static, Rgb888> = Bmp::<Rgb888>::from_slice(WORK_BYTES).unwrap();
+    static ref HOME_BMP: Option<Bmp::<'static, Rgb888>> =
+        load_bmp_or_log("home_48.bmp", HOME_BYTES);
+    static ref LOCATION_AWAY_BMP: Option<Bmp::<'static, Rgb888>> =
+        load_bmp_or_log("location_away_48.bmp", LOCATION_AWAY_BYTES);
+    static ref UNKNOWN_BMP: Option<Bmp::<'static, Rgb888>> =
+        load_bmp_or_log("unknown_48.bmp", UNKNOWN_BYTES);
+    static ref WORK_BMP: Option<Bmp::<'static, Rgb888>> =
+        load_bmp_or_log("work_48.bmp", WORK_BYTES);
 }
 
 #[derive(Clone, Deserialize, Debug)]
@@ -41,9 +56,20 @@ pub struct HomeTrackerConfig {
     pub home_assistant_url: String,
     pub home_assistant_bearer_token: String,
     pub person_entity_id: String,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// Whether to have Home Assistant push changes of the person over its
+    /// WebSocket API, so they are shown right away. The person is still polled
+    /// while the connection is down. Defaults to false
+    #[serde(default)]
+    pub websocket: bool,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PersonState {
     Home,
     Away,
@@ -65,26 +91,38 @@ impl<D> SubRender<D> for PersonState
 where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
 {
-    fn sub_render(&self, sub_canvas: &mut SubCanvas<D>) -> Result<()> {
+    fn sub_render(&self, sub_canvas: &mut SubCanvas<D>) -> Result<(), D::Error> {
         let (state_str, state_icon) = match self {
-            PersonState::Home => ("At Home", *HOME_BMP),
-            PersonState::Away => ("Away", *LOCATION_AWAY_BMP),
-            PersonState::Work => ("At Work", *WORK_BMP),
-            PersonState::Unknown => ("Unknown", *UNKNOWN_BMP),
+            PersonState::Home => ("At Home", &*HOME_BMP),
+            PersonState::Away => ("Away", &*LOCATION_AWAY_BMP),
+            PersonState::Work => ("At Work", &*WORK_BMP),
+            PersonState::Unknown => ("Unknown", &*UNKNOWN_BMP),
         };
 
-        LinearLayout::horizontal(Chain::new(Image::new(&state_icon, Point::zero())).append(
-            Text::new(
-                state_str,
-                Point::zero(),
-                MonoTextStyle::new(&mono_font::ascii::FONT_10X20, Rgb888::WHITE),
-            ),
-        ))
-        .with_alignment(vertical::Center)
-        .with_spacing(spacing::FixedMargin(4))
-        .arrange()
-        .draw(sub_canvas)
-        .unwrap();
+        let state_text = Text::new(
+            state_str,
+            Point::zero(),
+            MonoTextStyle::new(&mono_font::iso_8859_1::FONT_10X20, Rgb888::WHITE),
+        );
+
+        // Icons that could not be decoded are left out
+        match state_icon {
+            Some(state_icon) => {
+                LinearLayout::horizontal(
+                    Chain::new(Image::new(state_icon, Point::zero())).append(state_text),
+                )
+                .with_alignment(vertical::Center)
+                .with_spacing(spacing::FixedMargin(4))
+                .arrange()
+                .draw(sub_canvas)?;
+            }
+            None => {
+                LinearLayout::horizontal(Chain::new(state_text))
+                    .with_alignment(vertical::Center)
+                    .arrange()
+                    .draw(sub_canvas)?;
+            }
+        }
 
         Ok(())
     }
@@ -92,56 +130,99 @@ where
 
 pub struct HomeAssistantTracker {
     state: Arc<Mutex<PersonState>>,
+
+    /// The state before the last change and when it changed
+    previous_state: Arc<Mutex<Option<(PersonState, Instant)>>>,
+
+    /// Set while the person is home
+    presence: Arc<AtomicBool>,
+
     cancel_token: CancellationToken,
     update_task_handle: Option<JoinHandle<Result<()>>>,
 }
 
 impl HomeAssistantTracker {
+    /// Maps the state of a person entity to where they are
+    fn person_state(entity_id: &str, state: Option<StateEnum>) -> PersonState {
+        // Attempt to get the person's state
+        let person_state_str = if let Some(state_value) = state {
+            if let StateEnum::String(value) = state_value {
+                Some(value)
+            } else {
+                warn!("Could not parse 'state' as str");
+                None
+            }
+        } else {
+            warn!("{}'s 'state' was not provided", entity_id);
+            None
+        };
+
+        match person_state_str {
+            Some(state) => match state.to_ascii_lowercase().as_str() {
+                "home" => PersonState::Home,
+                "work" => PersonState::Work,
+                "away" | "not_home" => PersonState::Away,
+                _ => PersonState::Unknown,
+            },
+            None => PersonState::Unknown,
+        }
+    }
+
     pub fn new(config: HomeTrackerConfig) -> Result<Self> {
         let home_assistant_client = home_assistant_rest::Client::new(
             &config.home_assistant_url,
             &config.home_assistant_bearer_token,
         )?;
+        let ws_client = config
+            .websocket
+            .then(|| {
+                HomeAssistantWsClient::shared(
+                    &config.home_assistant_url,
+                    &config.home_assistant_bearer_token,
+                )
+            })
+            .transpose()?;
+        let mut subscription = ws_client
+            .as_ref()
+            .map(|ws_client| ws_client.subscribe([config.person_entity_id.clone()]));
 
         let state_holder = Arc::new(Mutex::new(PersonState::Unknown));
+        let previous_state = Arc::new(Mutex::new(None));
+        let presence = Arc::new(AtomicBool::new(false));
         let cancel_token = CancellationToken::new();
 
         // Clone the shared data since it will be moved onto the task
         let task_state_holder = state_holder.clone();
+        let task_previous_state = previous_state.clone();
+        let task_presence = presence.clone();
         let task_cancel_token = cancel_token.clone();
 
+        let timeout = request_timeout(config.request_timeout_secs);
+
         let update_task_handle: JoinHandle<Result<()>> = tokio::task::spawn(async move {
             'update_loop: loop {
                 let refresh_time = tokio::time::Instant::now() + Duration::from_secs(60);
 
-                let person_state = match home_assistant_client
-                    .get_states_of_entity(&config.person_entity_id)
+                let pushed_state = ws_client
+                    .as_ref()
+                    .and_then(|ws_client| ws_client.state(&config.person_entity_id));
+
+                let entity_state = match pushed_state {
+                    Some(pushed_state) => Ok(pushed_state.state_enum()),
+                    None => traced(
+                        "HomeAssistantTracker",
+                        format!("home_assistant/states/{}", config.person_entity_id),
+                        with_timeout(
+                            timeout,
+                            home_assistant_client.get_states_of_entity(&config.person_entity_id),
+                        ),
+                    )
                     .await
-                {
-                    Ok(entity_state) => {
-                        // Attempt to get the person's state
-                        let person_state_str = if let Some(state_value) = entity_state.state {
-                            if let StateEnum::String(value) = state_value {
-                                Some(value)
-                            } else {
-                                warn!("Could not parse 'state' as str");
-                                None
-                            }
-                        } else {
-                            warn!("{}'s 'state' was not provided", config.person_entity_id);
-                            None
-                        };
-
-                        match person_state_str {
-                            Some(state) => match state.to_ascii_lowercase().as_str() {
-                                "home" => PersonState::Home,
-                                "work" => PersonState::Work,
-                                "away" | "not_home" => PersonState::Away,
-                                _ => PersonState::Unknown,
-                            },
-                            None => PersonState::Unknown,
-                        }
-                    }
+                    .map(|entity_state| entity_state.state),
+                };
+
+                let person_state = match entity_state {
+                    Ok(state) => Self::person_state(&config.person_entity_id, state),
                     Err(e) => {
                         warn!(
                             "Could not acquire home assistant status for '{}' because of {}",
@@ -152,10 +233,16 @@ impl HomeAssistantTracker {
                     }
                 };
 
-                *task_state_holder.lock() = person_state;
+                task_presence.store(person_state == PersonState::Home, Ordering::Relaxed);
+
+                let last_state = std::mem::replace(&mut *task_state_holder.lock(), person_state);
+                if last_state != person_state {
+                    *task_previous_state.lock() = Some((last_state, Instant::now()));
+                }
 
                 select! {
                     _ = tokio::time::sleep_until(refresh_time) => {},
+                    _ = home_assistant_ws::changed(subscription.as_mut()) => {},
                     _ = task_cancel_token.cancelled() => break 'update_loop,
                 }
             }
@@ -165,12 +252,22 @@ impl HomeAssistantTracker {
 
         Ok(Self {
             state: state_holder,
+            previous_state,
+            presence,
             cancel_token,
             update_task_handle: Some(update_task_handle),
         })
     }
 }
 
+impl HomeAssistantTracker {
+    /// A flag that is set while the person is home, i.e. to feed a
+    /// [`PresenceGate`](crate::renders::presence_gate::PresenceGate)
+    pub fn presence(&self) -> Arc<AtomicBool> {
+        self.presence.clone()
+    }
+}
+
 impl<D> StateProvider<D> for HomeAssistantTracker
 where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
@@ -179,6 +276,12 @@ where
         let state: Box<dyn State<_>> = Box::new(*self.state.lock());
         state
     }
+
+    fn provide_previous_state(&self) -> Option<(Box<dyn State<D>>, Instant)> {
+        let (previous_state, changed_at) = (*self.previous_state.lock())?;
+        let state: Box<dyn State<_>> = Box::new(previous_state);
+        Some((state, changed_at))
+    }
 }
 
 impl Drop for HomeAssistantTracker {
@@ -13,13 +13,21 @@ use embedded_layout::{
 };
 use log::warn;
 use rustic_pixel_display::render::{Render, SubCanvas};
-use std::{collections::HashMap, convert::Infallible};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    time::{Duration, Instant},
+};
 
 mod home_assistant_tracker;
 mod septa_tracker;
 
 pub use home_assistant_tracker::{HomeAssistantTracker, HomeTrackerConfig};
-pub use septa_tracker::{TransitTracker, TransitTrackerConfig, TransitTrackerFactory};
+pub(crate) use septa_tracker::AT_STATION_ENTER_RADIUS;
+pub use septa_tracker::{
+    DisplayTransitState, TrackerLocation, TrainStatus, TransitTracker, TransitTrackerConfig,
+    TransitTrackerFactory,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub enum UsefulnessVal {
@@ -39,7 +47,7 @@ pub trait SubRender<D>
 where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
 {
-    fn sub_render(&self, canvas: &mut SubCanvas<D>) -> Result<()>;
+    fn sub_render(&self, canvas: &mut SubCanvas<D>) -> Result<(), D::Error>;
 }
 
 pub trait State<D>: Usefulness + SubRender<D>
@@ -53,6 +61,12 @@ where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
 {
     fn provide_state(&self) -> Box<dyn State<D>>;
+
+    /// The state that was provided before the last change, along with when the
+    /// change happened. `None` if the state has not changed yet.
+    fn provide_previous_state(&self) -> Option<(Box<dyn State<D>>, Instant)> {
+        None
+    }
 }
 
 // Create a blanket impl for State<D> if struct implements both Usefulness + SubRender<D>
@@ -68,6 +82,10 @@ where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
 {
     person_to_trackers: HashMap<String, Vec<Box<dyn StateProvider<D>>>>,
+
+    /// How long the previous state takes to slide out when the state of a
+    /// person changes, `None` to switch instantly
+    transition: Option<Duration>,
 }
 
 impl<D> PersonTracker<D>
@@ -75,7 +93,35 @@ where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
 {
     pub const fn new(person_to_trackers: HashMap<String, Vec<Box<dyn StateProvider<D>>>>) -> Self {
-        Self { person_to_trackers }
+        Self {
+            person_to_trackers,
+            transition: None,
+        }
+    }
+
+    /// Slides the previous state out and the new state in over `duration`
+    /// whenever the state of a person changes.
+    pub fn with_transition(mut self, duration: Duration) -> Self {
+        self.transition = Some(duration);
+        self
+    }
+
+    /// Returns the previous state of `tracker` and how far, from 0 to 1, the
+    /// transition away from it has progressed. `None` once the transition is
+    /// over or if transitions are disabled.
+    fn transition_from(&self, tracker: &dyn StateProvider<D>) -> Option<(Box<dyn State<D>>, f32)> {
+        let duration = self.transition?;
+        let (previous_state, changed_at) = tracker.provide_previous_state()?;
+        let elapsed = changed_at.elapsed();
+
+        if elapsed >= duration {
+            return None;
+        }
+
+        Some((
+            previous_state,
+            elapsed.as_secs_f32() / duration.as_secs_f32(),
+        ))
     }
 }
 
@@ -88,29 +134,31 @@ where
         let canvas_bounds = canvas.bounding_box();
 
         for (person_name, trackers) in &self.person_to_trackers {
-            let render_states = trackers.iter().map(|tracker| tracker.provide_state());
+            let render_states = trackers
+                .iter()
+                .map(|tracker| (tracker.provide_state(), tracker.as_ref()));
 
-            let mut most_useful_render: Option<Box<dyn State<D>>> = None;
+            let mut most_useful_render: Option<(Box<dyn State<D>>, &dyn StateProvider<D>)> = None;
 
-            for render_state in render_states {
+            for (render_state, tracker) in render_states {
                 match &most_useful_render {
-                    Some(most_useful) => {
+                    Some((most_useful, _)) => {
                         if most_useful.usefulness() < render_state.usefulness() {
-                            most_useful_render = Some(render_state);
+                            most_useful_render = Some((render_state, tracker));
                         }
                     }
                     None => {
-                        most_useful_render = Some(render_state);
+                        most_useful_render = Some((render_state, tracker));
                     }
                 }
             }
 
             match most_useful_render {
-                Some(most_useful) => {
+                Some((most_useful, tracker)) => {
                     let person_layout = LinearLayout::horizontal(Chain::new(Text::new(
                         person_name,
                         Point::zero(),
-                        MonoTextStyle::new(&mono_font::ascii::FONT_6X10, Rgb888::WHITE),
+                        MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X10, Rgb888::WHITE),
                     )))
                     .with_alignment(vertical::Center)
                     .with_spacing(spacing::FixedMargin(6))
@@ -131,13 +179,31 @@ where
                         height: 50,
                     };
 
-                    most_useful
-                        .sub_render(&mut SubCanvas::new(
-                            sub_canvas_offset,
-                            sub_canvas_size,
-                            canvas,
-                        ))
-                        .unwrap();
+                    match self.transition_from(tracker) {
+                        Some((previous_state, progress)) => {
+                            let shift = (sub_canvas_size.width as f32 * progress).round() as i32;
+
+                            previous_state.sub_render(&mut SubCanvas::new(
+                                sub_canvas_offset - Point::new(shift, 0),
+                                sub_canvas_size,
+                                canvas,
+                            ))?;
+
+                            most_useful.sub_render(&mut SubCanvas::new(
+                                sub_canvas_offset
+                                    + Point::new(sub_canvas_size.width as i32 - shift, 0),
+                                sub_canvas_size,
+                                canvas,
+                            ))?;
+                        }
+                        None => {
+                            most_useful.sub_render(&mut SubCanvas::new(
+                                sub_canvas_offset,
+                                sub_canvas_size,
+                                canvas,
+                            ))?;
+                        }
+                    }
 
                     offset = sub_canvas_offset
                         + Size {
@@ -16,7 +16,10 @@ use embedded_layout_macros::ViewGroup;
 use geoutils::{Distance, Location};
 use log::{debug, error};
 use parking_lot::Mutex;
-use rustic_pixel_display::render::{Render, RenderFactory, SubCanvas};
+use rustic_pixel_display::{
+    font::latin1_lossy,
+    render::{Render, RenderError, RenderFactory, SubCanvas, SyncRender},
+};
 use septa_api::{responses::Train, types::RegionalRailStop};
 use serde::Deserialize;
 use std::{
@@ -27,12 +30,18 @@ use std::{
     sync::Arc,
     time::{Duration, Instant},
 };
-use strum::IntoEnumIterator;
 use tinybmp::Bmp;
 use tokio::{join, select, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
 use super::{State, StateProvider, SubRender, Usefulness};
+use crate::{
+    assets::load_bmp_or_log,
+    late_thresholds::{LateThresholdsConfig, DEFAULT_LATE_THRESHOLDS},
+    line_colors::{LineColors, DEFAULT_LINE_COLOR},
+    request::{request_timeout, traced, with_timeout},
+    stations::{is_near_station, station_location, STATION_LOCATIONS},
+};
 
 /// The amount of time the user has to be within the radius of a station to be considered at the station.
 const NO_STATUS_TO_AT_STATION: Duration = Duration::from_secs(30);
@@ -40,7 +49,7 @@ const NO_STATUS_TO_AT_STATION: Duration = Duration::from_secs(30);
 // Have to wrap in lazy_static since from_meters is not a const function.
 lazy_static! {
     /// The radius around a station that a user must be within to be considered at the station.
-    static ref AT_STATION_ENTER_RADIUS: Distance = Distance::from_meters(200.0);
+    pub(crate) static ref AT_STATION_ENTER_RADIUS: Distance = Distance::from_meters(200.0);
 }
 
 /// The amount of time that a user would need to be outside a station's radius to
@@ -63,7 +72,8 @@ lazy_static! {
 const SEPTA_IMAGE: &[u8] = include_bytes!("../../../assets/SEPTA_16.bmp");
 
 lazy_static! {
-    static ref SEPTA_BMP: Bmp::<'static, Rgb888> = Bmp::<Rgb888>::from_slice(SEPTA_IMAGE).unwrap();
+    static ref SEPTA_BMP: Option<Bmp::<'static, Rgb888>> =
+        load_bmp_or_log("SEPTA_16.bmp", SEPTA_IMAGE);
 }
 
 #[derive(Debug, Default, Clone)]
@@ -115,6 +125,19 @@ pub struct TransitTrackerConfig {
     pub home_assistant_url: String,
     pub home_assistant_bearer_token: String,
     pub person_entity_id: String,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// Color of the destination of trains heading to it, written as `#RRGGBB`.
+    /// Trains heading anywhere else are drawn in white.
+    pub line_colors: Option<HashMap<String, String>>,
+
+    /// How late the train has to be for its status to change color, defaults
+    /// to [`DEFAULT_LATE_THRESHOLDS`] if not provided
+    pub late_thresholds: Option<LateThresholdsConfig>,
 }
 
 impl TransitState {
@@ -122,9 +145,7 @@ impl TransitState {
         Self::default()
     }
 
-    fn update_state(self, lat_lon: (f64, f64), trains: Vec<Train>) -> Result<Self> {
-        // Get the monotonic time
-        let now = Instant::now();
+    fn update_state(self, now: Instant, lat_lon: (f64, f64), trains: Vec<Train>) -> Result<Self> {
         let person_location = Location::new(lat_lon.0, lat_lon.1);
 
         Ok(match self {
@@ -134,20 +155,16 @@ impl TransitState {
                 let mut eligible_stations = Vec::new();
 
                 // See if we are currently in any station's radius
-                for station in
-                    RegionalRailStop::iter().filter(|p| !matches!(p, RegionalRailStop::Unknown(_)))
-                {
-                    let station_lat_lon = station.lat_lon()?;
-                    let station_location = Location::new(station_lat_lon.0, station_lat_lon.1);
-
-                    if person_location
-                        .is_in_circle(&station_location, *AT_STATION_ENTER_RADIUS)
-                        .expect("is_in_circle failed")
-                    {
-                        match station_to_first_encounter.get(&station) {
+                for (station, station_location) in STATION_LOCATIONS.iter() {
+                    if is_near_station(
+                        &person_location,
+                        station_location,
+                        &AT_STATION_ENTER_RADIUS,
+                    )? {
+                        match station_to_first_encounter.get(station) {
                             Some(first_encounter) => {
                                 if now - *first_encounter > NO_STATUS_TO_AT_STATION {
-                                    eligible_stations.push(station);
+                                    eligible_stations.push(station.clone());
                                 }
                             }
                             None => {
@@ -156,7 +173,7 @@ impl TransitState {
                         }
                     } else {
                         // We are not in the radius of the station, so remove it from the map
-                        station_to_first_encounter.remove(&station);
+                        station_to_first_encounter.remove(station);
                     }
                 }
 
@@ -180,15 +197,13 @@ impl TransitState {
                     }
                     _ => {
                         let mut closest_station = eligible_stations[0].clone();
-                        let closest_lat_lon = closest_station.lat_lon()?;
                         let mut closest_distance: Distance = person_location
-                            .distance_to(&Location::new(closest_lat_lon.0, closest_lat_lon.1))
+                            .distance_to(&station_location(&closest_station)?)
                             .map_err(|e| anyhow!("distance_to failed: {}", e))?;
 
                         for station in eligible_stations {
-                            let station_lat_lon = station.lat_lon()?;
                             let distance = person_location
-                                .distance_to(&Location::new(station_lat_lon.0, station_lat_lon.1))
+                                .distance_to(&station_location(&station)?)
                                 .map_err(|e| anyhow!("distance_to failed: {}", e))?;
 
                             if distance.meters() < closest_distance.meters() {
@@ -214,10 +229,7 @@ impl TransitState {
                 mut train_id_to_first_encounter,
                 mut time_outside_station,
             } => {
-                let station_location = {
-                    let station_lat_lon = station.lat_lon()?;
-                    Location::new(station_lat_lon.0, station_lat_lon.1)
-                };
+                let station_location = station_location(&station)?;
 
                 // See if we are still at the current location
                 let mut is_outside_location = false;
@@ -362,16 +374,13 @@ impl TransitState {
                         } else if last_train_encounter - now > ON_TRAIN_TO_NO_STATUS_TIMEOUT {
                             let station: Option<RegionalRailStop> = {
                                 let mut regional_rail_stop = None;
-                                for station in RegionalRailStop::iter() {
-                                    let station_location = {
-                                        let (lat, lon) = station.lat_lon()?;
-                                        Location::new(lat, lon)
-                                    };
-                                    if person_location
-                                        .is_in_circle(&station_location, *AT_STATION_ENTER_RADIUS)
-                                        .map_err(|e| anyhow!("distance_to failed: {}", e))?
-                                    {
-                                        regional_rail_stop = Some(station);
+                                for (station, station_location) in STATION_LOCATIONS.iter() {
+                                    if is_near_station(
+                                        &person_location,
+                                        station_location,
+                                        &AT_STATION_ENTER_RADIUS,
+                                    )? {
+                                        regional_rail_stop = Some(station.clone());
                                         break;
                                     }
                                 }
@@ -430,10 +439,36 @@ pub enum DisplayTransitState {
         train_number: String,
         status: TrainStatus,
         status_text: String,
+        status_color: Rgb888,
         destination: String,
+        destination_color: Rgb888,
     },
 }
 
+impl DisplayTransitState {
+    /// If both states show the person at the same station or on the same
+    /// train, ignoring updates to how late the train is.
+    fn is_same_status(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::NoStatus, Self::NoStatus) => true,
+            (
+                Self::AtStation { station_name },
+                Self::AtStation {
+                    station_name: other_station_name,
+                },
+            ) => station_name == other_station_name,
+            (
+                Self::OnTrain { train_number, .. },
+                Self::OnTrain {
+                    train_number: other_train_number,
+                    ..
+                },
+            ) => train_number == other_train_number,
+            _ => false,
+        }
+    }
+}
+
 impl Usefulness for DisplayTransitState {
     fn usefulness(&self) -> super::UsefulnessVal {
         match self {
@@ -448,14 +483,14 @@ impl<D> SubRender<D> for DisplayTransitState
 where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
 {
-    fn sub_render(&self, sub_canvas: &mut SubCanvas<D>) -> Result<()> {
+    fn sub_render(&self, sub_canvas: &mut SubCanvas<D>) -> Result<(), D::Error> {
         // Attempt to figure out the transit state
         let status_view = match self {
             DisplayTransitState::NoStatus => {
                 let chain = Chain::new(Text::new(
                     "No Status",
                     Point::zero(),
-                    MonoTextStyle::new(&mono_font::ascii::FONT_6X10, Rgb888::WHITE),
+                    MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X10, Rgb888::WHITE),
                 ));
 
                 PersonStatusView::NoStatus(
@@ -469,7 +504,7 @@ where
                 let chain = Chain::new(Text::new(
                     station_name,
                     Point::zero(),
-                    MonoTextStyle::new(&mono_font::ascii::FONT_6X10, Rgb888::WHITE),
+                    MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X10, Rgb888::WHITE),
                 ));
 
                 PersonStatusView::AtStation(
@@ -481,29 +516,26 @@ where
             }
             DisplayTransitState::OnTrain {
                 train_number,
-                status,
                 status_text,
+                status_color,
                 destination,
+                destination_color,
+                ..
             } => {
-                let status_color = match status {
-                    TrainStatus::Early(_) | TrainStatus::OnTime => Rgb888::GREEN,
-                    TrainStatus::Late(_) => Rgb888::RED,
-                };
-
                 let chain = Chain::new(Text::new(
                     train_number,
                     Point::zero(),
-                    MonoTextStyle::new(&mono_font::ascii::FONT_6X10, Rgb888::WHITE),
+                    MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X10, Rgb888::WHITE),
                 ))
                 .append(Text::new(
                     status_text,
                     Point::zero(),
-                    MonoTextStyle::new(&mono_font::ascii::FONT_6X10, status_color),
+                    MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X10, status_color),
                 ))
                 .append(Text::new(
                     destination,
                     Point::zero(),
-                    MonoTextStyle::new(&mono_font::ascii::FONT_6X10, Rgb888::WHITE),
+                    MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X10, *destination_color),
                 ));
 
                 PersonStatusView::OnTrain(
@@ -519,8 +551,7 @@ where
             .with_alignment(horizontal::Left)
             .with_spacing(spacing::FixedMargin(4))
             .arrange()
-            .draw(sub_canvas)
-            .unwrap();
+            .draw(sub_canvas)?;
 
         Ok(())
     }
@@ -531,29 +562,56 @@ impl From<&TransitState> for DisplayTransitState {
         match value {
             TransitState::NoStatus { .. } => Self::NoStatus,
             TransitState::AtStation { station, .. } => Self::AtStation {
-                station_name: station.to_string(),
+                station_name: latin1_lossy(&station.to_string()).into_owned(),
             },
             TransitState::OnTrain { train, .. } => Self::OnTrain {
                 train_number: train.train_number.clone(),
                 status: match train.late.cmp(&0) {
-                    std::cmp::Ordering::Less => TrainStatus::Early(train.late),
+                    std::cmp::Ordering::Less => TrainStatus::Early(-train.late),
                     std::cmp::Ordering::Equal => TrainStatus::OnTime,
-                    std::cmp::Ordering::Greater => TrainStatus::Late(-train.late),
+                    std::cmp::Ordering::Greater => TrainStatus::Late(train.late),
                 },
                 status_text: match train.late.cmp(&0) {
-                    std::cmp::Ordering::Less => format!("{} Mins Early", train.late),
+                    std::cmp::Ordering::Less => format!("{} Mins Early", -train.late),
                     std::cmp::Ordering::Equal => "On Time".to_string(),
-                    std::cmp::Ordering::Greater => format!("{} Mins Late", -train.late),
+                    std::cmp::Ordering::Greater => format!("{} Mins Late", train.late),
                 },
-                destination: train.dest.to_string(),
+                status_color: DEFAULT_LATE_THRESHOLDS.color(train.late),
+                destination: latin1_lossy(&train.dest.to_string()).into_owned(),
+                destination_color: DEFAULT_LINE_COLOR,
             },
         }
     }
 }
 
+/// Shared handle to the last location reported for the person followed by a
+/// [`TransitTracker`]
+#[derive(Clone, Default)]
+pub struct TrackerLocation(Arc<Mutex<Option<(f64, f64)>>>);
+
+impl TrackerLocation {
+    /// The last reported location as latitude and longitude, `None` if no
+    /// location has been received yet
+    pub fn get(&self) -> Option<(f64, f64)> {
+        *self.0.lock()
+    }
+}
+
 pub struct TransitTracker {
     state: Arc<Mutex<TransitState>>,
 
+    /// Last location of the person, kept so the geofencing can be inspected
+    location: TrackerLocation,
+
+    /// Color of the destination by destination name
+    line_colors: LineColors,
+
+    /// How late the train has to be for its status to change color
+    late_thresholds: LateThresholdsConfig,
+
+    /// The displayed state before the last change and when it changed
+    previous_state: Arc<Mutex<Option<(DisplayTransitState, Instant)>>>,
+
     /// Used to signal that all async tasks should be cancelled immediately
     cancel_token: CancellationToken,
 
@@ -566,9 +624,15 @@ impl TransitTracker {
         home_assistant_client: &home_assistant_rest::Client,
         config: &TransitTrackerConfig,
     ) -> Result<(f64, f64)> {
-        let entity_state = home_assistant_client
-            .get_states_of_entity(&config.person_entity_id)
-            .await?;
+        let entity_state = traced(
+            "TransitTracker",
+            format!("home_assistant/states/{}", config.person_entity_id),
+            with_timeout(
+                request_timeout(config.request_timeout_secs),
+                home_assistant_client.get_states_of_entity(&config.person_entity_id),
+            ),
+        )
+        .await?;
 
         if let (Some(lat), Some(lon)) = (
             entity_state.attributes.get("latitude"),
@@ -591,34 +655,55 @@ impl TransitTracker {
             &config.home_assistant_bearer_token,
         )?;
 
+        let line_colors = match &config.line_colors {
+            Some(line_colors) => LineColors::new(line_colors)?,
+            None => LineColors::default(),
+        };
+
+        let late_thresholds = config.late_thresholds.unwrap_or(DEFAULT_LATE_THRESHOLDS);
+        late_thresholds.validate()?;
+
         let state_holder = Arc::new(Mutex::new(TransitState::new()));
+        let location = TrackerLocation::default();
+        let previous_state = Arc::new(Mutex::new(None));
         let cancel_token = CancellationToken::new();
 
         // Clone the shared data since it will be moved onto the task
         let task_state_holder = state_holder.clone();
+        let task_location = location.clone();
+        let task_previous_state = previous_state.clone();
         let task_cancel_token = cancel_token.clone();
 
         let update_task_handle: JoinHandle<Result<()>> = tokio::task::spawn(async move {
             'update_loop: loop {
                 let refresh_time = tokio::time::Instant::now() + Duration::from_secs(15);
 
-                let trains_request = septa_client.train_view();
+                let trains_request = traced(
+                    "TransitTracker",
+                    "septa/train_view",
+                    with_timeout(
+                        request_timeout(config.request_timeout_secs),
+                        septa_client.train_view(),
+                    ),
+                );
                 let user_location_request = Self::get_location(&home_assistant_client, &config);
 
-                let (trains_result, user_location_result) =
-                    join!(trains_request, user_location_request);
+                // Drop any in flight requests as soon as the render is dropped
+                let (trains_result, user_location_result) = select! {
+                    results = async { join!(trains_request, user_location_request) } => results,
+                    _ = task_cancel_token.cancelled() => break 'update_loop,
+                };
 
                 match (user_location_result, trains_result) {
-                    (Ok((user_loc_lat, user_loc_lon)), Ok(trains)) => {
-                        let mut holder_unlocked = task_state_holder.lock();
-
-                        let transit_state = std::mem::take(&mut *holder_unlocked);
-                        let new_state =
-                            transit_state.update_state((user_loc_lat, user_loc_lon), trains)?;
-
-                        debug!("Updated state: {:?}", new_state);
-
-                        let _ = std::mem::replace(&mut *holder_unlocked, new_state);
+                    (Ok(user_location), Ok(trains)) => {
+                        Self::apply_update(
+                            &task_state_holder,
+                            &task_location,
+                            &task_previous_state,
+                            Instant::now(),
+                            user_location,
+                            trains,
+                        )?;
                     }
                     (Err(location_error), Err(train_error)) => {
                         error!("Error in both location and SEPTA calls (location_error: {location_error}, train_error: {train_error})");
@@ -642,10 +727,93 @@ impl TransitTracker {
 
         Ok(Self {
             state: state_holder,
+            location,
+            line_colors,
+            late_thresholds,
+            previous_state,
             cancel_token,
             update_task_handle: Some(update_task_handle),
         })
     }
+
+    /// Moves the state of the tracker on with the location of the person and
+    /// the trains running at `now`, the same as each time the tracker polls
+    /// SEPTA and Home Assistant.
+    pub fn update(&self, now: Instant, lat_lon: (f64, f64), trains: Vec<Train>) -> Result<()> {
+        Self::apply_update(
+            &self.state,
+            &self.location,
+            &self.previous_state,
+            now,
+            lat_lon,
+            trains,
+        )
+    }
+
+    fn apply_update(
+        state_holder: &Mutex<TransitState>,
+        location: &TrackerLocation,
+        previous_state: &Mutex<Option<(DisplayTransitState, Instant)>>,
+        now: Instant,
+        lat_lon: (f64, f64),
+        trains: Vec<Train>,
+    ) -> Result<()> {
+        *location.0.lock() = Some(lat_lon);
+
+        let mut holder_unlocked = state_holder.lock();
+
+        let transit_state = std::mem::take(&mut *holder_unlocked);
+        let last_display_state = DisplayTransitState::from(&transit_state);
+        let new_state = transit_state.update_state(now, lat_lon, trains)?;
+
+        debug!("Updated state: {:?}", new_state);
+
+        if !last_display_state.is_same_status(&DisplayTransitState::from(&new_state)) {
+            *previous_state.lock() = Some((last_display_state, now));
+        }
+
+        let _ = std::mem::replace(&mut *holder_unlocked, new_state);
+
+        Ok(())
+    }
+
+    /// Handle to the last location of the person, updated each time the state
+    /// of the tracker is
+    pub fn location(&self) -> TrackerLocation {
+        self.location.clone()
+    }
+
+    /// The state of the person as it would currently be displayed
+    pub fn current_state(&self) -> DisplayTransitState {
+        self.with_colors((&*self.state.lock()).into())
+    }
+
+    /// Colors the destination of the state with its line color and the status
+    /// by how late the train is
+    fn with_colors(&self, mut display_state: DisplayTransitState) -> DisplayTransitState {
+        if let DisplayTransitState::OnTrain {
+            status,
+            status_color,
+            destination,
+            destination_color,
+            ..
+        } = &mut display_state
+        {
+            *status_color = self.late_thresholds.color(match status {
+                TrainStatus::Late(mins) => *mins,
+                TrainStatus::Early(_) | TrainStatus::OnTime => 0,
+            });
+            *destination_color = self.line_colors.color_for(destination);
+        }
+
+        display_state
+    }
+
+    /// The last reported location of the person as latitude and longitude,
+    /// `None` if no location has been received yet
+    pub fn current_location(&self) -> Option<(f64, f64)> {
+        self.location.get()
+    }
 }
 
 impl<D> StateProvider<D> for TransitTracker
@@ -653,10 +821,15 @@ where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
 {
     fn provide_state(&self) -> Box<dyn super::State<D>> {
-        let display_state: DisplayTransitState = (&*self.state.lock()).into();
-        let state: Box<dyn State<D>> = Box::new(display_state);
+        let state: Box<dyn State<D>> = Box::new(self.current_state());
         state
     }
+
+    fn provide_previous_state(&self) -> Option<(Box<dyn State<D>>, Instant)> {
+        let (previous_state, changed_at) = self.previous_state.lock().clone()?;
+        let state: Box<dyn State<D>> = Box::new(self.with_colors(previous_state));
+        Some((state, changed_at))
+    }
 }
 
 type NoStatusViews<'a, C> = chain! {
@@ -714,7 +887,7 @@ where
         "Tracks a person based on the SEPTA transit information"
     }
 
-    fn load_from_config<R: Read>(&self, _reader: R) -> Result<Box<dyn Render<D>>> {
+    fn load_from_config<R: Read>(&self, _reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
         todo!()
     }
 }
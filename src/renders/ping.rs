@@ -0,0 +1,391 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    mono_font::{self, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Primitive, RgbColor, Size},
+    primitives::{Circle, PrimitiveStyle, Rectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable,
+};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    font::Font,
+    render::{BufferCanvas, Render, RenderError, RenderFactory, SyncRender},
+};
+use serde::Deserialize;
+use std::{
+    collections::VecDeque,
+    convert::Infallible,
+    io::{ErrorKind, Read},
+    marker::PhantomData,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{net::TcpStream, select, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use super::sparkline::{draw_sparkline, SparklineStyle};
+use crate::request::{refresh_interval, request_timeout, validate_refresh_interval};
+
+/// How often the hosts are pinged if no interval is configured
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The port connected to for hosts that are configured without one
+const DEFAULT_PORT: u16 = 80;
+
+/// How many round trip times are kept for the sparkline of each host
+const MAX_SAMPLES: usize = 64;
+
+const DEFAULT_FONT: &mono_font::MonoFont = &mono_font::ascii::FONT_5X7;
+
+const UP_COLOR: Rgb888 = Rgb888::GREEN;
+const DOWN_COLOR: Rgb888 = Rgb888::RED;
+const UNKNOWN_COLOR: Rgb888 = Rgb888::new(96, 96, 96);
+const SPARKLINE_COLOR: Rgb888 = Rgb888::new(0, 128, 192);
+
+/// Space between the columns of a row
+const COLUMN_SPACING: i32 = 2;
+const ROW_SPACING: i32 = 1;
+
+/// Width, in characters, of the round trip time column, enough for "9999ms"
+const RTT_CHARS: i32 = 6;
+
+/// Configuration for the [`PingMonitorFactory`], for example:
+///
+/// ```json
+/// {
+///   "hosts": ["192.168.1.1", "nas.local:445", "8.8.8.8:53"],
+///   "interval_secs": 30
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct PingConfig {
+    /// The hosts to monitor, optionally followed by the port to connect to.
+    /// IPv6 addresses need to be enclosed in brackets when a port is given,
+    /// i.e. "[::1]:22"
+    pub hosts: Vec<String>,
+
+    /// How often, in seconds, every host is pinged
+    pub interval_secs: Option<u64>,
+
+    /// The port connected to for hosts that are configured without one,
+    /// defaults to 80
+    pub port: Option<u16>,
+
+    /// How long, in seconds, a host has to respond before it is treated as
+    /// down. Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT)
+    /// if not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// Defaults to 5x7 if not provided
+    pub font: Option<Font>,
+}
+
+impl PingConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.hosts.is_empty() {
+            return Err(anyhow!("Need to provide at least one host"));
+        }
+
+        validate_refresh_interval("interval_secs", self.interval_secs)
+    }
+}
+
+/// Splits a configured host into the address that is connected to and its
+/// port.
+fn parse_host(host: &str, default_port: u16) -> Result<(String, u16)> {
+    match host.rsplit_once(':') {
+        // A bare IPv6 address also contains colons, so only treat the last
+        // segment as a port if the address is unambiguous
+        Some((address, port)) if !address.contains(':') || address.starts_with('[') => {
+            let port = port
+                .parse()
+                .map_err(|e| anyhow!("Invalid port in host \"{host}\": {e}"))?;
+
+            Ok((
+                address
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_owned(),
+                port,
+            ))
+        }
+        _ => Ok((host.to_owned(), default_port)),
+    }
+}
+
+/// Measures how long it takes to open a TCP connection to `address`. Returns
+/// `None` if the host did not respond within `timeout`.
+///
+/// A refused connection still means the host answered, so it is counted as
+/// reachable.
+async fn tcp_ping(address: &str, port: u16, timeout: Duration) -> Option<Duration> {
+    let start_time = tokio::time::Instant::now();
+
+    match tokio::time::timeout(timeout, TcpStream::connect((address, port))).await {
+        Ok(Ok(_)) => Some(start_time.elapsed()),
+        Ok(Err(e)) if e.kind() == ErrorKind::ConnectionRefused => Some(start_time.elapsed()),
+        Ok(Err(_)) | Err(_) => None,
+    }
+}
+
+#[derive(Debug, Default)]
+struct HostState {
+    /// Set once the host has been pinged at least once
+    has_pinged: bool,
+
+    /// Round trip time of the last ping, `None` if the host did not respond
+    rtt: Option<Duration>,
+
+    /// Round trip times, in milliseconds, of the recent pings that were
+    /// answered
+    samples: VecDeque<f32>,
+}
+
+/// Monitors whether a list of hosts are reachable, drawing a row for every host
+/// with a green or red dot, its name, a sparkline of the recent round trip
+/// times and the last round trip time.
+///
+/// Hosts are pinged by timing how long a TCP connection to them takes to open,
+/// rather than with ICMP echo requests which need raw sockets and with that
+/// elevated privileges. A host therefore needs to either accept or refuse
+/// connections on the configured port; hosts behind a firewall that silently
+/// drops the connection are reported as down.
+pub struct PingMonitor {
+    names: Vec<String>,
+    hosts: Arc<Mutex<Vec<HostState>>>,
+    font: &'static mono_font::MonoFont<'static>,
+
+    /// Flag used to gracefully terminate the render and driver threads
+    cancel_token: CancellationToken,
+
+    /// Handles to the tasks pinging each host
+    ping_handles: Vec<JoinHandle<()>>,
+}
+
+impl PingMonitor {
+    pub fn new(config: PingConfig) -> Result<Self> {
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+
+        let default_port = config.port.unwrap_or(DEFAULT_PORT);
+        let addresses = config
+            .hosts
+            .iter()
+            .map(|host| parse_host(host, default_port))
+            .collect::<Result<Vec<_>>>()?;
+
+        let font = config
+            .font
+            .map(|font| font.mono_font())
+            .unwrap_or(DEFAULT_FONT);
+        let interval = refresh_interval(config.interval_secs, DEFAULT_INTERVAL);
+        let timeout = request_timeout(config.request_timeout_secs);
+        let hosts = Arc::new(Mutex::new(
+            addresses
+                .iter()
+                .map(|_| HostState::default())
+                .collect::<Vec<_>>(),
+        ));
+        let cancel_token = CancellationToken::new();
+
+        // Every host is pinged by its own task so an unreachable host waiting
+        // out its timeout doesn't delay the others. The pings keep going while
+        // the render is not displayed so the sparklines have no gaps.
+        let ping_handles = addresses
+            .into_iter()
+            .enumerate()
+            .map(|(index, (address, port))| {
+                let task_cancel_token = cancel_token.clone();
+                let task_hosts = hosts.clone();
+
+                tokio::task::spawn(async move {
+                    loop {
+                        let start_time = tokio::time::Instant::now();
+                        let rtt = tcp_ping(&address, port, timeout).await;
+
+                        {
+                            let mut hosts = task_hosts.lock();
+                            let host = &mut hosts[index];
+
+                            host.has_pinged = true;
+                            host.rtt = rtt;
+
+                            if let Some(rtt) = rtt {
+                                if host.samples.len() >= MAX_SAMPLES {
+                                    host.samples.pop_front();
+                                }
+
+                                host.samples.push_back(rtt.as_secs_f32() * 1000.0);
+                            }
+                        }
+
+                        select! {
+                            _ = tokio::time::sleep_until(start_time + interval) => {},
+                            _ = task_cancel_token.cancelled() => break,
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            names: config.hosts,
+            hosts,
+            font,
+            cancel_token,
+            ping_handles,
+        })
+    }
+}
+
+impl<D> Render<D> for PingMonitor
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let bounding_box = canvas.bounding_box();
+        let hosts = self.hosts.lock();
+
+        let char_width = (self.font.character_size.width + self.font.character_spacing) as i32;
+        let row_height = self.font.character_size.height as i32;
+
+        let name_width = self
+            .names
+            .iter()
+            .map(|name| name.chars().count() as i32)
+            .max()
+            .unwrap_or(0)
+            * char_width;
+
+        let name_left = bounding_box.top_left.x + row_height + COLUMN_SPACING;
+        let rtt_right = bounding_box.top_left.x + bounding_box.size.width as i32 - 1;
+        let sparkline_left = name_left + name_width + COLUMN_SPACING;
+        let sparkline_width = rtt_right - RTT_CHARS * char_width - COLUMN_SPACING - sparkline_left;
+
+        let mut top = bounding_box.top_left.y;
+
+        for (name, host) in self.names.iter().zip(hosts.iter()) {
+            let dot_color = match (host.has_pinged, host.rtt) {
+                (false, _) => UNKNOWN_COLOR,
+                (true, Some(_)) => UP_COLOR,
+                (true, None) => DOWN_COLOR,
+            };
+
+            Circle::new(
+                Point::new(bounding_box.top_left.x + 1, top + 1),
+                (row_height - 2).max(1) as u32,
+            )
+            .into_styled(PrimitiveStyle::with_fill(dot_color))
+            .draw(canvas)?;
+
+            Text::with_baseline(
+                name,
+                Point::new(name_left, top),
+                MonoTextStyle::new(self.font, Rgb888::WHITE),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            // Leave the sparkline out if the names take up all the room
+            if sparkline_width > 0 {
+                draw_sparkline(
+                    canvas,
+                    Rectangle::new(
+                        Point::new(sparkline_left, top),
+                        Size::new(sparkline_width as u32, row_height as u32),
+                    ),
+                    &host.samples,
+                    SparklineStyle::Line,
+                    SPARKLINE_COLOR,
+                )?;
+            }
+
+            let rtt = match host.rtt {
+                Some(rtt) => format!("{}ms", rtt.as_millis()),
+                None => "--".to_owned(),
+            };
+
+            Text::with_text_style(
+                &rtt,
+                Point::new(rtt_right, top),
+                MonoTextStyle::new(self.font, dot_color),
+                TextStyleBuilder::new()
+                    .alignment(Alignment::Right)
+                    .baseline(Baseline::Top)
+                    .build(),
+            )
+            .draw(canvas)?;
+
+            top += row_height + ROW_SPACING;
+        }
+
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        let hosts = self.hosts.lock();
+        let up = hosts.iter().filter(|host| host.rtt.is_some()).count();
+
+        format!("Ping: {up}/{} hosts up", hosts.len())
+    }
+
+    fn has_content(&self) -> bool {
+        self.hosts.lock().iter().any(|host| host.has_pinged)
+    }
+}
+
+impl Drop for PingMonitor {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+
+        for task_handle in self.ping_handles.drain(..) {
+            task_handle.abort();
+        }
+    }
+}
+
+pub struct PingMonitorFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for PingMonitorFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for PingMonitorFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "PingMonitor"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Displays whether a list of hosts are reachable along with their latency"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: PingConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(PingMonitor::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: PingConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(PingMonitor::new(config)?))
+    }
+}
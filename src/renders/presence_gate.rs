@@ -0,0 +1,222 @@
+use anyhow::Result;
+use chrono::Local;
+use embedded_graphics::{
+    mono_font::{self, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    primitives::Rectangle,
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable,
+};
+use parking_lot::Mutex;
+use rustic_pixel_display::render::Render;
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::format::{format_time, TimeFormatConfig};
+
+/// How long everyone has to be away before blanking if no grace period is
+/// configured
+const DEFAULT_AWAY_GRACE: Duration = Duration::from_secs(10 * 60);
+
+const CLOCK_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_6X10;
+
+/// Dim enough to not light up the room
+const CLOCK_COLOR: Rgb888 = Rgb888::new(48, 48, 48);
+
+/// What is drawn while nobody is present
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AwayDisplay {
+    /// Every pixel is off
+    #[default]
+    Blank,
+
+    /// A dim clock in the center of the panel
+    Clock,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PresenceGateConfig {
+    /// How long, in seconds, everyone has to be away before the panel is
+    /// blanked, so stepping out briefly doesn't turn it off. Defaults to
+    /// [`DEFAULT_AWAY_GRACE`] if not provided
+    pub away_grace_secs: Option<u64>,
+
+    /// What is drawn while nobody is present, defaults to blank
+    #[serde(default)]
+    pub away_display: AwayDisplay,
+
+    /// How the clock is displayed
+    #[serde(default)]
+    pub time_format: TimeFormatConfig,
+}
+
+#[derive(Debug, Default)]
+struct GateState {
+    /// When the last person left, `None` while someone is present
+    away_since: Option<Instant>,
+
+    /// Whether the away display was last drawn instead of the render
+    away: bool,
+}
+
+/// Blanks the panel while nobody is present, to save power and spare OLED
+/// panels, and otherwise draws the wrapped render.
+///
+/// Presence is fed by flags such as [`HomeAssistantTracker::presence`](crate::renders::person_tracker::HomeAssistantTracker::presence),
+/// someone is present while any of them is set. The wrapped render is
+/// deselected while the panel is blanked so it stops fetching.
+pub struct PresenceGate<R> {
+    render: R,
+    presence: Vec<Arc<AtomicBool>>,
+    away_grace: Duration,
+    away_display: AwayDisplay,
+    time_format: TimeFormatConfig,
+    state: Mutex<GateState>,
+}
+
+impl<R> PresenceGate<R> {
+    pub fn new(render: R, presence: Vec<Arc<AtomicBool>>, config: PresenceGateConfig) -> Self {
+        Self {
+            render,
+            presence,
+            away_grace: config
+                .away_grace_secs
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_AWAY_GRACE),
+            away_display: config.away_display,
+            time_format: config.time_format,
+            state: Mutex::new(GateState::default()),
+        }
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.render
+    }
+
+    fn is_present(&self) -> bool {
+        self.presence
+            .iter()
+            .any(|presence| presence.load(Ordering::Relaxed))
+    }
+
+    /// Whether the away display should be drawn, starting the grace period if
+    /// everyone just left
+    fn should_blank(&self, state: &mut GateState) -> bool {
+        if self.is_present() {
+            state.away_since = None;
+            return false;
+        }
+
+        state.away_since.get_or_insert_with(Instant::now).elapsed() >= self.away_grace
+    }
+
+    fn draw_away<D>(&self, canvas: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    {
+        canvas.clear(Rgb888::BLACK)?;
+
+        if self.away_display == AwayDisplay::Clock {
+            Text::with_text_style(
+                &format_time(&Local::now(), &self.time_format),
+                canvas.bounding_box().center(),
+                MonoTextStyle::new(CLOCK_FONT, CLOCK_COLOR),
+                TextStyleBuilder::new()
+                    .alignment(Alignment::Center)
+                    .baseline(Baseline::Middle)
+                    .build(),
+            )
+            .draw(canvas)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<D, R> Render<D> for PresenceGate<R>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    R: Render<D>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let mut state = self.state.lock();
+        let away = self.should_blank(&mut state);
+
+        // Hand the selection over when blanking the panel, or waking it
+        if away != state.away {
+            if away {
+                self.render.on_deselect();
+            } else {
+                self.render.on_select();
+            }
+
+            state.away = away;
+        }
+
+        drop(state);
+
+        if away {
+            self.draw_away(canvas)
+        } else {
+            self.render.render_at(canvas, now)
+        }
+    }
+
+    fn on_select(&self) {
+        self.render.on_select();
+        self.state.lock().away = false;
+    }
+
+    fn on_deselect(&self) {
+        self.render.on_deselect();
+    }
+
+    fn background_color(&self) -> Option<Rgb888> {
+        if self.state.lock().away {
+            Some(Rgb888::BLACK)
+        } else {
+            self.render.background_color()
+        }
+    }
+
+    fn dirty_region(&self) -> Option<Rectangle> {
+        let mut state = self.state.lock();
+
+        // The whole canvas changes when blanking or waking, and the clock
+        // changes every minute
+        if state.away || self.should_blank(&mut state) != state.away {
+            return None;
+        }
+
+        self.render.dirty_region()
+    }
+
+    fn describe(&self) -> String {
+        if self.state.lock().away {
+            "PresenceGate: nobody present".to_owned()
+        } else {
+            self.render.describe()
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        self.render.has_content()
+    }
+
+    fn reset(&self) {
+        self.render.reset();
+    }
+}
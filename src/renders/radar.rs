@@ -0,0 +1,606 @@
+use anyhow::{anyhow, Result};
+use chrono::{Local, TimeZone};
+use embedded_graphics::{
+    image::Image,
+    mono_font::{self, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, OriginDimensions, Point, RgbColor, Size},
+    text::{Baseline, Text},
+    Drawable,
+};
+use image::{imageops, DynamicImage, RgbImage, Rgba, RgbaImage};
+use log::warn;
+use parking_lot::Mutex;
+use rustic_pixel_display::render::{
+    BufferCanvas, ExampleConfig, Render, RenderError, RenderFactory, Spinner, SyncRender,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    f64::consts::PI,
+    io::Read,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+
+use crate::{
+    assets::{fit_image, load_image, ImageFormat, RgbImageDrawable},
+    data_source::{DataSource, PollingSource},
+    format::{format_time, TimeFormatConfig},
+    loading::{draw_loading, loading_spinner},
+    request::{
+        refresh_interval, request_timeout, traced, validate_refresh_interval, STALE_AFTER_REFRESHES,
+    },
+};
+
+/// Lists the radar frames RainViewer has available
+const WEATHER_MAPS_URL: &str = "https://api.rainviewer.com/public/weather-maps.json";
+
+/// Width and height of a map tile
+const TILE_SIZE: u32 = 256;
+
+/// The most detailed zoom level RainViewer serves radar tiles at
+const MAX_ZOOM: u8 = 7;
+
+/// RainViewer keeps two hours of radar in ten minute steps
+const MAX_FRAMES: u8 = 13;
+
+/// How many of the most recent frames are looped if not configured
+const DEFAULT_FRAMES: u8 = 6;
+
+/// How long each frame is shown if not configured
+const DEFAULT_FRAME_DURATION: Duration = Duration::from_millis(500);
+
+/// The RainViewer color scheme used if not configured, "Universal Blue"
+const DEFAULT_COLOR_SCHEME: u8 = 2;
+
+/// How long to wait between refreshes if no refresh interval is configured,
+/// RainViewer publishes a new frame every ten minutes
+const DEFAULT_REFRESH: Duration = Duration::from_secs(10 * 60);
+
+/// How long to wait after a failed request if no retry interval is
+/// configured
+const DEFAULT_ERROR_RETRY: Duration = Duration::from_secs(60);
+
+/// Font of the time of the frame being shown
+const TIME_FONT: &MonoFont = &mono_font::ascii::FONT_5X7;
+
+/// Configuration for the [`RadarFactory`], for example:
+///
+/// ```json
+/// {
+///   "lat": 39.9526,
+///   "lon": -75.1652,
+///   "zoom": 6,
+///   "frames": 6,
+///   "base_map_url": "https://tile.openstreetmap.org/{z}/{x}/{y}.png"
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct RadarConfig {
+    pub lat: f64,
+    pub lon: f64,
+
+    /// The zoom level of the map tile, from 0 (the whole world) to 7
+    pub zoom: u8,
+
+    /// How many of the most recent frames are looped, from 1 to 13. Defaults
+    /// to 6, the last hour
+    pub frames: Option<u8>,
+
+    /// How long, in milliseconds, each frame is shown. Defaults to 500
+    pub frame_ms: Option<u64>,
+
+    /// The RainViewer color scheme of the precipitation, defaults to 2
+    pub color_scheme: Option<u8>,
+
+    /// URL of a map tile drawn under the radar, with `{z}`, `{x}` and `{y}`
+    /// in place of the tile coordinates. The radar is drawn on black if not
+    /// provided
+    pub base_map_url: Option<String>,
+
+    /// How the time of each frame is displayed
+    #[serde(default)]
+    pub time_format: TimeFormatConfig,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How long, in seconds, to wait between refreshes of the frames.
+    /// Defaults to [`DEFAULT_REFRESH`] if not provided
+    pub refresh_secs: Option<u64>,
+
+    /// How long, in seconds, to wait before trying again when no frames could
+    /// be fetched. Defaults to [`DEFAULT_ERROR_RETRY`] if not provided
+    pub error_retry_secs: Option<u64>,
+}
+
+impl RadarConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !(-85.0..=85.0).contains(&self.lat) || !(-180.0..=180.0).contains(&self.lon) {
+            return Err(anyhow!(
+                "{}, {} is not a location that can be shown on a map tile",
+                self.lat,
+                self.lon
+            ));
+        }
+
+        if self.zoom > MAX_ZOOM {
+            return Err(anyhow!("zoom must be at most {MAX_ZOOM}"));
+        }
+
+        if let Some(frames) = self.frames {
+            if !(1..=MAX_FRAMES).contains(&frames) {
+                return Err(anyhow!("frames must be between 1 and {MAX_FRAMES}"));
+            }
+        }
+
+        if let Some(base_map_url) = &self.base_map_url {
+            if ["{z}", "{x}", "{y}"]
+                .iter()
+                .any(|placeholder| !base_map_url.contains(placeholder))
+            {
+                return Err(anyhow!("base_map_url must contain {{z}}, {{x}} and {{y}}"));
+            }
+        }
+
+        validate_refresh_interval("refresh_secs", self.refresh_secs)?;
+        validate_refresh_interval("error_retry_secs", self.error_retry_secs)
+    }
+}
+
+/// The frames listed by the RainViewer weather maps endpoint
+#[derive(Debug, Deserialize)]
+struct WeatherMaps {
+    host: String,
+    radar: RadarMaps,
+}
+
+#[derive(Debug, Deserialize)]
+struct RadarMaps {
+    /// Frames of past radar, oldest first
+    past: Vec<RadarMap>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RadarMap {
+    /// When the radar was captured, as a unix timestamp
+    time: i64,
+
+    /// Path of the frame's tiles on the host
+    path: String,
+}
+
+/// The coordinates of the map tile at `zoom` that contains `lat`, `lon`
+fn tile_coordinates(lat: f64, lon: f64, zoom: u8) -> (u32, u32) {
+    let tiles = (1u32 << zoom) as f64;
+    let lat = lat.to_radians();
+
+    let x = (lon + 180.0) / 360.0 * tiles;
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * tiles;
+    let max = tiles as u32 - 1;
+
+    ((x as u32).min(max), (y as u32).min(max))
+}
+
+/// The tile shown by the render
+#[derive(Debug, Clone)]
+struct Tile {
+    zoom: u8,
+    x: u32,
+    y: u32,
+    color_scheme: u8,
+    base_map_url: Option<String>,
+}
+
+impl Tile {
+    fn radar_url(&self, host: &str, map: &RadarMap) -> String {
+        let Self {
+            zoom,
+            x,
+            y,
+            color_scheme,
+            ..
+        } = self;
+
+        format!(
+            "{host}{}/{TILE_SIZE}/{zoom}/{x}/{y}/{color_scheme}/1_1.png",
+            map.path
+        )
+    }
+
+    fn base_map_url(&self) -> Option<String> {
+        self.base_map_url.as_ref().map(|url| {
+            url.replace("{z}", &self.zoom.to_string())
+                .replace("{x}", &self.x.to_string())
+                .replace("{y}", &self.y.to_string())
+        })
+    }
+}
+
+/// A radar frame, composited over the base map
+#[derive(Debug, Clone)]
+struct RadarFrame {
+    /// When the radar was captured, as a unix timestamp
+    time: i64,
+    image: Arc<RgbImage>,
+}
+
+#[derive(Debug, Default)]
+struct RadarState {
+    /// Frames of the loop, oldest first
+    frames: Vec<RadarFrame>,
+
+    /// When the frames were last fetched
+    refreshed_at: Option<Instant>,
+}
+
+/// What the fetches of the frames keep from one fetch to the next
+#[derive(Debug, Default)]
+struct FetchCache {
+    /// The base map never changes, so it is only fetched until it loads
+    base_map: Option<Arc<RgbImage>>,
+
+    /// The frames of the last fetch, reused rather than fetched again
+    frames: Vec<RadarFrame>,
+}
+
+/// The frames scaled to the canvas, kept until the size of the canvas changes
+#[derive(Debug, Default)]
+struct ScaledFrames {
+    size: Size,
+    frames: HashMap<i64, RgbImageDrawable>,
+}
+
+async fn fetch_png(client: &reqwest::Client, url: &str) -> Result<RgbaImage> {
+    let body = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    load_image(&body, ImageFormat::Png)
+}
+
+/// Draws the transparent `radar` tile over `base_map`, or over black if there
+/// is no base map.
+fn composite(radar: &RgbaImage, base_map: Option<&RgbImage>) -> RgbImage {
+    let mut image = match base_map {
+        Some(base_map) => DynamicImage::ImageRgb8(base_map.clone()).into_rgba8(),
+        None => RgbaImage::from_pixel(radar.width(), radar.height(), Rgba([0, 0, 0, 255])),
+    };
+
+    imageops::overlay(&mut image, radar, 0, 0);
+    DynamicImage::ImageRgba8(image).into_rgb8()
+}
+
+/// Fetches the `count` most recent radar frames of `tile`.
+///
+/// Frames that are in `previous` are reused rather than fetched again, and
+/// frames that fail to fetch are left out of the loop.
+async fn fetch_frames(
+    client: &reqwest::Client,
+    tile: &Tile,
+    count: usize,
+    base_map: Option<&RgbImage>,
+    previous: &[RadarFrame],
+) -> Result<Vec<RadarFrame>> {
+    let maps: WeatherMaps = client
+        .get(WEATHER_MAPS_URL)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let past = &maps.radar.past;
+    let mut frames = Vec::with_capacity(count);
+
+    for map in &past[past.len().saturating_sub(count)..] {
+        if let Some(frame) = previous.iter().find(|frame| frame.time == map.time) {
+            frames.push(frame.clone());
+            continue;
+        }
+
+        match fetch_png(client, &tile.radar_url(&maps.host, map)).await {
+            Ok(radar) => frames.push(RadarFrame {
+                time: map.time,
+                image: Arc::new(composite(&radar, base_map)),
+            }),
+            Err(e) => warn!("Could not get the radar frame of {} {e}", map.time),
+        }
+    }
+
+    Ok(frames)
+}
+
+/// A loop of the most recent precipitation radar around a location, from
+/// RainViewer, optionally over a base map.
+///
+/// The tile is scaled down to fit the canvas, and the time of the frame being
+/// shown is drawn in the bottom left corner.
+pub struct Radar {
+    state: Mutex<RadarState>,
+
+    /// Where the frames come from
+    source: Box<dyn DataSource<Vec<RadarFrame>>>,
+
+    /// Watches the source for new frames
+    frames: Mutex<watch::Receiver<Option<Vec<RadarFrame>>>>,
+
+    scaled_frames: Mutex<ScaledFrames>,
+    frame_duration: Duration,
+    time_format: TimeFormatConfig,
+
+    /// When the loop started, the frame shown is derived from it
+    started: Mutex<Instant>,
+
+    /// How long the frames can go without a refresh before the render
+    /// reports it has no content
+    stale_after: Duration,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+}
+
+impl Radar {
+    pub fn new(config: RadarConfig) -> Result<Self> {
+        // Tile servers like OpenStreetMap's refuse requests without a user agent
+        let client = reqwest::Client::builder()
+            .timeout(request_timeout(config.request_timeout_secs))
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .build()?;
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let error_retry = refresh_interval(config.error_retry_secs, DEFAULT_ERROR_RETRY);
+        let count = config.frames.unwrap_or(DEFAULT_FRAMES) as usize;
+
+        let (x, y) = tile_coordinates(config.lat, config.lon, config.zoom);
+        let tile = Tile {
+            zoom: config.zoom,
+            x,
+            y,
+            color_scheme: config.color_scheme.unwrap_or(DEFAULT_COLOR_SCHEME),
+            base_map_url: config.base_map_url.clone(),
+        };
+
+        let cache = Arc::new(Mutex::new(FetchCache::default()));
+
+        let source = PollingSource::new("Radar", refresh, error_retry, move || {
+            let client = client.clone();
+            let tile = tile.clone();
+            let cache = cache.clone();
+
+            async move {
+                let (base_map, previous) = {
+                    let cache = cache.lock();
+                    (cache.base_map.clone(), cache.frames.clone())
+                };
+
+                let base_map = match (base_map, tile.base_map_url()) {
+                    (None, Some(base_map_url)) => {
+                        match traced("Radar", "base_map", fetch_png(&client, &base_map_url)).await {
+                            Ok(image) => {
+                                let image = Arc::new(DynamicImage::ImageRgba8(image).into_rgb8());
+                                cache.lock().base_map = Some(image.clone());
+                                Some(image)
+                            }
+                            Err(e) => {
+                                warn!("Could not get the base map {base_map_url} {e}");
+                                None
+                            }
+                        }
+                    }
+                    (base_map, _) => base_map,
+                };
+
+                let frames = traced(
+                    "Radar",
+                    "rainviewer",
+                    fetch_frames(&client, &tile, count, base_map.as_deref(), &previous),
+                )
+                .await?;
+
+                if frames.is_empty() {
+                    return Err(anyhow!("none of the radar frames could be fetched"));
+                }
+
+                cache.lock().frames = frames.clone();
+                Ok(frames)
+            }
+        });
+        let source: Box<dyn DataSource<Vec<RadarFrame>>> = Box::new(source);
+
+        Ok(Self {
+            state: Mutex::new(RadarState::default()),
+            frames: Mutex::new(source.subscribe()),
+            source,
+            scaled_frames: Mutex::new(ScaledFrames::default()),
+            frame_duration: config
+                .frame_ms
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_FRAME_DURATION),
+            time_format: config.time_format,
+            started: Mutex::new(Instant::now()),
+            stale_after: refresh * STALE_AFTER_REFRESHES,
+            loading_spinner: loading_spinner(),
+        })
+    }
+
+    /// Takes the latest frames of the source, if they changed since they were
+    /// last taken
+    fn update_frames(&self) {
+        let mut frames = self.frames.lock();
+
+        if frames.has_changed().unwrap_or(false) {
+            if let Some(frames) = &*frames.borrow_and_update() {
+                let mut state = self.state.lock();
+                state.frames = frames.clone();
+                state.refreshed_at = Some(Instant::now());
+            }
+        }
+    }
+}
+
+impl<D> Render<D> for Radar
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.update_frames();
+        let state = self.state.lock();
+
+        if state.frames.is_empty() {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let frame_millis = self.frame_duration.as_millis().max(1);
+        let frame_index = (self.started.lock().elapsed().as_millis() / frame_millis) as usize
+            % state.frames.len();
+        let frame = &state.frames[frame_index];
+
+        let bounding_box = canvas.bounding_box();
+        let mut scaled_frames = self.scaled_frames.lock();
+
+        if scaled_frames.size != bounding_box.size {
+            *scaled_frames = ScaledFrames {
+                size: bounding_box.size,
+                frames: HashMap::new(),
+            };
+        }
+
+        // Forget the frames that dropped out of the loop
+        scaled_frames
+            .frames
+            .retain(|time, _| state.frames.iter().any(|frame| frame.time == *time));
+
+        let image = scaled_frames
+            .frames
+            .entry(frame.time)
+            .or_insert_with(|| RgbImageDrawable::new(fit_image(&frame.image, bounding_box.size)));
+
+        let image_size = image.size();
+        let position = bounding_box.top_left
+            + Point::new(
+                (bounding_box.size.width as i32 - image_size.width as i32) / 2,
+                (bounding_box.size.height as i32 - image_size.height as i32) / 2,
+            );
+        Image::new(&*image, position).draw(canvas)?;
+
+        if let Some(time) = Local.timestamp_opt(frame.time, 0).single() {
+            Text::with_baseline(
+                format_time(&time, &self.time_format).trim_start(),
+                Point::new(
+                    bounding_box.top_left.x,
+                    bounding_box.top_left.y + bounding_box.size.height as i32 - 1,
+                ),
+                MonoTextStyle::new(TIME_FONT, Rgb888::WHITE),
+                Baseline::Bottom,
+            )
+            .draw(canvas)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.source.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.source.pause();
+    }
+
+    fn describe(&self) -> String {
+        self.update_frames();
+        let state = self.state.lock();
+
+        match state.refreshed_at {
+            Some(_) => format!("Radar: {} frames", state.frames.len()),
+            None => "Radar: loading".to_owned(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        self.update_frames();
+        match self.state.lock().refreshed_at {
+            Some(refreshed_at) => refreshed_at.elapsed() < self.stale_after,
+            None => false,
+        }
+    }
+
+    fn reset(&self) {
+        // Start the loop over from the oldest frame
+        *self.started.lock() = Instant::now();
+    }
+}
+
+pub struct RadarFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for RadarFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for RadarFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "Radar"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "An animated loop of the most recent precipitation radar around a location"
+    }
+
+    fn example_config(&self) -> Option<ExampleConfig> {
+        Some(json!({
+            "lat": 39.9526,
+            "lon": -75.1652,
+            "zoom": 6,
+            "frames": 6
+        }))
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: RadarConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(Radar::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: RadarConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(Radar::new(config)?))
+    }
+}
@@ -0,0 +1,406 @@
+use anyhow::Result;
+use embedded_graphics::{
+    mono_font::{self, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, WebColors},
+    text::{Baseline, Text},
+    Drawable,
+};
+use log::error;
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    font::Font,
+    render::{
+        animation_time, BufferCanvas, PollGate, Render, RenderError, RenderFactory, Spinner,
+        SyncRender,
+    },
+};
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{select, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    loading::{draw_loading, loading_spinner},
+    request::request_timeout,
+    scroll::{scroll_position, text_width},
+    supervisor::{supervise, RESTART_DELAY},
+};
+
+const ALERTS_URL: &str = "https://www3.septa.org/api/Alerts/get_alert_data.php?req1=all";
+
+/// How often the alerts are refreshed if no interval is configured
+const DEFAULT_INTERVAL_SECS: u64 = 5 * 60;
+
+/// How fast, in pixels per second, alerts that do not fit on the canvas scroll
+const SCROLL_SPEED: u64 = 20;
+
+const DEFAULT_TITLE_FONT: &mono_font::MonoFont = &mono_font::ascii::FONT_7X13;
+const DEFAULT_ALERT_FONT: &mono_font::MonoFont = &mono_font::ascii::FONT_6X9;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeptaAlertsConfig {
+    /// The names of the lines to display alerts for, i.e. "Paoli/Thorndale"
+    pub lines: Vec<String>,
+
+    /// How often, in seconds, the alerts are refreshed
+    pub interval_secs: Option<u64>,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// Font of the title, defaults to 7x13 if not provided
+    pub title_font: Option<Font>,
+
+    /// Font of the alerts, defaults to 6x9 if not provided
+    pub body_font: Option<Font>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum AlertSeverity {
+    Advisory,
+    Detour,
+    Alert,
+}
+
+impl AlertSeverity {
+    fn color(&self) -> Rgb888 {
+        match self {
+            AlertSeverity::Advisory => Rgb888::YELLOW,
+            AlertSeverity::Detour => Rgb888::CSS_ORANGE,
+            AlertSeverity::Alert => Rgb888::RED,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DisplayAlert {
+    severity: AlertSeverity,
+    message: String,
+}
+
+/// A single route entry returned by the SEPTA alerts endpoint
+#[derive(Debug, Deserialize)]
+struct RouteAlerts {
+    route_name: String,
+    current_message: Option<String>,
+    detour_message: Option<String>,
+    advisory_message: Option<String>,
+}
+
+impl RouteAlerts {
+    fn into_display_alerts(self) -> Vec<DisplayAlert> {
+        [
+            (AlertSeverity::Alert, self.current_message),
+            (AlertSeverity::Detour, self.detour_message),
+            (AlertSeverity::Advisory, self.advisory_message),
+        ]
+        .into_iter()
+        .filter_map(|(severity, message)| {
+            let message = strip_html(&message?);
+
+            if message.is_empty() {
+                None
+            } else {
+                Some(DisplayAlert {
+                    severity,
+                    message: format!("{}: {}", self.route_name, message),
+                })
+            }
+        })
+        .collect()
+    }
+}
+
+/// The alert messages are HTML fragments, remove the markup so they can be
+/// displayed as plain text.
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+async fn fetch_alerts(client: &reqwest::Client, lines: &[String]) -> Result<Vec<DisplayAlert>> {
+    let routes: Vec<RouteAlerts> = client.get(ALERTS_URL).send().await?.json().await?;
+
+    let mut alerts = routes
+        .into_iter()
+        .filter(|route| lines.iter().any(|line| line == &route.route_name))
+        .flat_map(RouteAlerts::into_display_alerts)
+        .collect::<Vec<_>>();
+
+    // Most severe alerts first
+    alerts.sort_by(|a, b| b.severity.cmp(&a.severity));
+
+    Ok(alerts)
+}
+
+#[derive(Debug, Default)]
+struct SeptaAlertsState {
+    alerts: Vec<DisplayAlert>,
+
+    /// Set once the alerts have been fetched at least once
+    has_loaded: bool,
+}
+
+pub struct SeptaAlerts {
+    state: Arc<Mutex<SeptaAlertsState>>,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+
+    title_font: &'static mono_font::MonoFont<'static>,
+    alert_font: &'static mono_font::MonoFont<'static>,
+
+    /// Used to pause the update task while the render is not displayed
+    poll_gate: PollGate,
+
+    /// Flag used to gracefully terminate the render and driver threads
+    cancel_token: CancellationToken,
+
+    /// Handle to the task used to update the alerts
+    update_alerts_handle: Option<JoinHandle<()>>,
+}
+
+impl SeptaAlerts {
+    pub fn new(config: SeptaAlertsConfig) -> Self {
+        let title_font = config
+            .title_font
+            .map(|font| font.mono_font())
+            .unwrap_or(DEFAULT_TITLE_FONT);
+        let alert_font = config
+            .body_font
+            .map(|font| font.mono_font())
+            .unwrap_or(DEFAULT_ALERT_FONT);
+
+        let state = Arc::new(Mutex::new(SeptaAlertsState::default()));
+        let poll_gate = PollGate::new();
+        let cancel_token = CancellationToken::new();
+
+        let task_cancel_token = cancel_token.clone();
+        let task_state = state.clone();
+        let task_poll_gate = poll_gate.clone();
+
+        let update_alerts_handle = supervise(
+            "SeptaAlerts",
+            cancel_token.clone(),
+            RESTART_DELAY,
+            move || {
+                let config = config.clone();
+                let task_cancel_token = task_cancel_token.clone();
+                let task_state = task_state.clone();
+                let task_poll_gate = task_poll_gate.clone();
+
+                async move {
+                    let client = reqwest::Client::builder()
+                        .timeout(request_timeout(config.request_timeout_secs))
+                        .build()?;
+                    let interval =
+                        Duration::from_secs(config.interval_secs.unwrap_or(DEFAULT_INTERVAL_SECS));
+
+                    loop {
+                        // Don't poll the alerts API while the render is not being displayed
+                        select! {
+                            _ = task_poll_gate.active() => {},
+                            _ = task_cancel_token.cancelled() => break,
+                        }
+
+                        let refresh_time = tokio::time::Instant::now() + interval;
+
+                        match fetch_alerts(&client, &config.lines).await {
+                            Ok(alerts) => {
+                                *task_state.lock() = SeptaAlertsState {
+                                    alerts,
+                                    has_loaded: true,
+                                }
+                            }
+                            Err(e) => error!("Could not get updated SEPTA alerts {e}"),
+                        }
+
+                        select! {
+                            _ = tokio::time::sleep_until(refresh_time) => {},
+                            _ = task_poll_gate.paused() => {},
+                            _ = task_cancel_token.cancelled() => break,
+                        }
+                    }
+
+                    Ok(())
+                }
+            },
+        );
+
+        Self {
+            state,
+            loading_spinner: loading_spinner(),
+            title_font,
+            alert_font,
+            poll_gate,
+            cancel_token,
+            update_alerts_handle: Some(update_alerts_handle),
+        }
+    }
+}
+
+impl<D> Render<D> for SeptaAlerts
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        let canvas_width = canvas.bounding_box().size.width;
+        let state = self.state.lock();
+
+        if !state.has_loaded {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let alerts = &state.alerts;
+
+        Text::with_baseline(
+            "SEPTA Alerts",
+            Point::zero(),
+            MonoTextStyle::new(self.title_font, Rgb888::WHITE),
+            Baseline::Top,
+        )
+        .draw(canvas)?;
+
+        let mut position = Point::new(0, self.title_font.character_size.height as i32 + 2);
+
+        if alerts.is_empty() {
+            Text::with_baseline(
+                "No Alerts",
+                position,
+                MonoTextStyle::new(self.alert_font, Rgb888::GREEN),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            return Ok(());
+        }
+
+        for alert in alerts.iter() {
+            position.x = scroll_position(
+                text_width(&alert.message, self.alert_font),
+                canvas_width,
+                SCROLL_SPEED,
+                animation_time(now),
+            );
+
+            Text::with_baseline(
+                &alert.message,
+                position,
+                MonoTextStyle::new(self.alert_font, alert.severity.color()),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            position.y += self.alert_font.character_size.height as i32 + 2;
+        }
+
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.poll_gate.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.poll_gate.pause();
+    }
+
+    fn describe(&self) -> String {
+        let state = self.state.lock();
+
+        if state.has_loaded {
+            format!("SeptaAlerts: {} alerts", state.alerts.len())
+        } else {
+            "SeptaAlerts: loading".to_owned()
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        self.state.lock().has_loaded
+    }
+}
+
+impl Drop for SeptaAlerts {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+
+        if let Some(task_handle) = self.update_alerts_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
+pub struct SeptaAlertsFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for SeptaAlertsFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for SeptaAlertsFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "SeptaAlerts"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Scrolls active SEPTA service alerts for a set of lines"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: SeptaAlertsConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(SeptaAlerts::new(config)))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: SeptaAlertsConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(SeptaAlerts::new(config)))
+    }
+}
@@ -0,0 +1,395 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Primitive, RgbColor},
+    primitives::{Line, PrimitiveStyle, Rectangle},
+    Drawable, Pixel,
+};
+use log::error;
+use parking_lot::Mutex;
+use rustic_pixel_display::render::{BufferCanvas, Render, RenderError, RenderFactory, SyncRender};
+use serde::Deserialize;
+use std::{
+    collections::VecDeque, convert::Infallible, io::Read, marker::PhantomData, sync::Arc,
+    time::Duration,
+};
+use tokio::{select, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use crate::request::{refresh_interval, request_timeout, validate_refresh_interval};
+
+const LOAD_AVERAGE_PATH: &str = "/proc/loadavg";
+const CPU_TEMPERATURE_PATH: &str = "/sys/class/thermal/thermal_zone0/temp";
+
+/// How often a sample is taken if no interval is configured
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How many samples are kept if no limit is configured, enough to fill the
+/// width of a chain of two panels
+const DEFAULT_MAX_SAMPLES: usize = 128;
+
+/// Range below which the samples are treated as all being equal
+const FLAT_RANGE: f32 = f32::EPSILON;
+
+const DEFAULT_COLOR: Rgb888 = Rgb888::new(0, 192, 255);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SparklineStyle {
+    /// Consecutive samples are joined by a line
+    #[default]
+    Line,
+
+    /// Every sample is drawn as a bar from the bottom of the canvas
+    Bars,
+}
+
+/// Where the samples of a [`Sparkline`] come from.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SparklineSource {
+    /// A number in the JSON returned by `url`, located by the JSON pointer
+    /// `pointer`, i.e. "/current/temp_c"
+    Rest { url: String, pointer: String },
+
+    /// The one minute load average of the system
+    LoadAverage,
+
+    /// The temperature of the CPU in degrees Celsius
+    CpuTemperature,
+}
+
+/// Configuration for the [`SparklineFactory`], for example:
+///
+/// ```json
+/// {
+///   "source": { "type": "rest", "url": "http://localhost:8080/status", "pointer": "/ping_ms" },
+///   "interval_secs": 30,
+///   "style": "bars"
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct SparklineConfig {
+    /// Where the samples are taken from. The render only displays the values
+    /// given to [`Sparkline::push`] if not provided
+    pub source: Option<SparklineSource>,
+
+    /// How often, in seconds, a sample is taken from the source
+    pub interval_secs: Option<u64>,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How many of the most recent samples are kept. Defaults to 128
+    pub max_samples: Option<usize>,
+
+    #[serde(default)]
+    pub style: SparklineStyle,
+
+    /// The color of the chart as RGB
+    pub color: Option<[u8; 3]>,
+}
+
+impl SparklineConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_samples == Some(0) {
+            return Err(anyhow!("max_samples must be at least 1"));
+        }
+
+        validate_refresh_interval("interval_secs", self.interval_secs)
+    }
+}
+
+/// Reads a single sample from `source`.
+async fn read_sample(client: &reqwest::Client, source: &SparklineSource) -> Result<f32> {
+    match source {
+        SparklineSource::Rest { url, pointer } => {
+            let response: serde_json::Value = client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            response
+                .pointer(pointer)
+                .and_then(|value| value.as_f64())
+                .map(|value| value as f32)
+                .ok_or_else(|| anyhow!("No number at \"{pointer}\" in the response of {url}"))
+        }
+        SparklineSource::LoadAverage => {
+            let load_average = tokio::fs::read_to_string(LOAD_AVERAGE_PATH)
+                .await
+                .map_err(|e| anyhow!("Could not read {LOAD_AVERAGE_PATH} {e}"))?;
+
+            load_average
+                .split_whitespace()
+                .next()
+                .and_then(|load| load.parse().ok())
+                .ok_or_else(|| anyhow!("Could not parse {LOAD_AVERAGE_PATH}"))
+        }
+        SparklineSource::CpuTemperature => {
+            let temperature = tokio::fs::read_to_string(CPU_TEMPERATURE_PATH)
+                .await
+                .map_err(|e| anyhow!("Could not read {CPU_TEMPERATURE_PATH} {e}"))?;
+
+            // Reported in thousandths of a degree
+            temperature
+                .trim()
+                .parse::<f32>()
+                .map(|millidegrees| millidegrees / 1000.0)
+                .map_err(|e| anyhow!("Could not parse {CPU_TEMPERATURE_PATH} {e}"))
+        }
+    }
+}
+
+/// Plots the most recent samples of a value as a line or bar chart across the
+/// whole canvas, see [`draw_sparkline`].
+pub struct Sparkline {
+    samples: Arc<Mutex<VecDeque<f32>>>,
+    max_samples: usize,
+    style: SparklineStyle,
+    color: Rgb888,
+
+    /// Flag used to gracefully terminate the render and driver threads
+    cancel_token: CancellationToken,
+
+    /// Handle to the task used to sample the source
+    update_samples_handle: Option<JoinHandle<Result<()>>>,
+}
+
+impl Sparkline {
+    pub fn new(config: SparklineConfig) -> Self {
+        let max_samples = config.max_samples.unwrap_or(DEFAULT_MAX_SAMPLES).max(1);
+        let samples = Arc::new(Mutex::new(VecDeque::with_capacity(max_samples)));
+        let cancel_token = CancellationToken::new();
+
+        // Keep sampling while the render is not displayed, otherwise the chart
+        // would have a gap every time it is selected again
+        let update_samples_handle = config.source.map(|source| {
+            let task_cancel_token = cancel_token.clone();
+            let task_samples = samples.clone();
+            let interval = refresh_interval(config.interval_secs, DEFAULT_INTERVAL);
+            let timeout = request_timeout(config.request_timeout_secs);
+
+            tokio::task::spawn(async move {
+                let client = reqwest::Client::builder().timeout(timeout).build()?;
+
+                loop {
+                    let start_time = tokio::time::Instant::now();
+
+                    match read_sample(&client, &source).await {
+                        Ok(value) => push_sample(&task_samples, max_samples, value),
+                        Err(e) => error!("Could not read sparkline sample {e}"),
+                    }
+
+                    select! {
+                        _ = tokio::time::sleep_until(start_time + interval) => {},
+                        _ = task_cancel_token.cancelled() => break,
+                    }
+                }
+
+                Ok(())
+            })
+        });
+
+        Self {
+            samples,
+            max_samples,
+            style: config.style,
+            color: config
+                .color
+                .map(|[r, g, b]| Rgb888::new(r, g, b))
+                .unwrap_or(DEFAULT_COLOR),
+            cancel_token,
+            update_samples_handle,
+        }
+    }
+
+    /// Appends `value` as the newest sample, dropping the oldest sample once
+    /// `max_samples` are held.
+    pub fn push(&self, value: f32) {
+        push_sample(&self.samples, self.max_samples, value);
+    }
+}
+
+fn push_sample(samples: &Mutex<VecDeque<f32>>, max_samples: usize, value: f32) {
+    // A NaN would make the whole chart unscalable
+    if !value.is_finite() {
+        return;
+    }
+
+    let mut samples = samples.lock();
+
+    while samples.len() >= max_samples {
+        samples.pop_front();
+    }
+
+    samples.push_back(value);
+}
+
+/// Draws the most recent of `samples` as a chart filling `area`, one sample per
+/// column with the newest sample on the right edge.
+///
+/// The chart is scaled between the smallest and largest of the drawn samples. If
+/// there are fewer samples than columns the chart starts part way across
+/// `area`, and if all the samples are equal it is drawn as a flat line through
+/// the middle of `area`.
+pub fn draw_sparkline<D>(
+    canvas: &mut D,
+    area: Rectangle,
+    samples: &VecDeque<f32>,
+    style: SparklineStyle,
+    color: Rgb888,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    let (width, height) = (area.size.width, area.size.height);
+
+    if width == 0 || height == 0 {
+        return Ok(());
+    }
+
+    let visible = samples.len().min(width as usize);
+    let samples = samples.iter().skip(samples.len() - visible).copied();
+
+    let (min, max) = samples
+        .clone()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    let range = max - min;
+
+    let bottom = area.top_left.y + height as i32 - 1;
+    let first_column = area.top_left.x + (width as usize - visible) as i32;
+
+    let points = samples.enumerate().map(|(index, value)| {
+        let scaled = if range > FLAT_RANGE {
+            (value - min) / range
+        } else {
+            0.5
+        };
+
+        Point::new(
+            first_column + index as i32,
+            bottom - (scaled * (height - 1) as f32).round() as i32,
+        )
+    });
+
+    let stroke = PrimitiveStyle::with_stroke(color, 1);
+
+    match style {
+        SparklineStyle::Line => {
+            let mut previous: Option<Point> = None;
+
+            for point in points {
+                match previous {
+                    Some(previous) => Line::new(previous, point)
+                        .into_styled(stroke)
+                        .draw(canvas)?,
+                    None => Pixel(point, color).draw(canvas)?,
+                }
+
+                previous = Some(point);
+            }
+        }
+        SparklineStyle::Bars => {
+            for point in points {
+                Line::new(Point::new(point.x, bottom), point)
+                    .into_styled(stroke)
+                    .draw(canvas)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl<D> Render<D> for Sparkline
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let bounding_box = canvas.bounding_box();
+        let samples = self.samples.lock();
+
+        draw_sparkline(canvas, bounding_box, &samples, self.style, self.color)
+    }
+
+    fn describe(&self) -> String {
+        let samples = self.samples.lock();
+
+        match samples.back() {
+            Some(latest) => format!("Sparkline: {} samples, latest {latest}", samples.len()),
+            None => "Sparkline: no samples".to_owned(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        !self.samples.lock().is_empty()
+    }
+}
+
+impl Drop for Sparkline {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+
+        if let Some(task_handle) = self.update_samples_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
+pub struct SparklineFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for SparklineFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for SparklineFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "Sparkline"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Plots the recent values of a REST endpoint or system reading as a chart"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: SparklineConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(Sparkline::new(config)))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: SparklineConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(Sparkline::new(config)))
+    }
+}
@@ -0,0 +1,175 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, RgbColor},
+};
+use rustic_pixel_display::render::{BufferCanvas, Render, RenderError, RenderFactory, SyncRender};
+use serde::Deserialize;
+use std::{convert::Infallible, io::Read, marker::PhantomData};
+
+/// Size of the checkerboard squares and grid cells if none is configured
+const DEFAULT_SIZE: u32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pattern {
+    /// The whole panel filled with the configured color
+    Solid,
+
+    /// Red increasing from left to right, green from top to bottom and blue
+    /// from right to left
+    Gradient,
+
+    /// Black on the left fading to white on the right
+    Grayscale,
+
+    /// Alternating white and black squares
+    Checkerboard,
+
+    /// The whole panel filled with a single channel, useful to check that the
+    /// `led_sequence` is correct
+    Red,
+    Green,
+    Blue,
+    White,
+
+    /// White 1px lines on a black background
+    Grid,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TestPatternConfig {
+    pub pattern: Pattern,
+
+    /// Size, in pixels, of the checkerboard squares and grid cells
+    pub size: Option<u32>,
+
+    /// The color of the solid pattern as RGB, defaults to white
+    pub color: Option<[u8; 3]>,
+}
+
+/// Draws a static pattern used to calibrate panels, i.e. finding dead pixels,
+/// swapped color channels or uneven brightness.
+pub struct TestPattern {
+    pattern: Pattern,
+    size: u32,
+    color: Rgb888,
+}
+
+impl TestPattern {
+    pub fn new(config: TestPatternConfig) -> Result<Self> {
+        let size = config.size.unwrap_or(DEFAULT_SIZE);
+
+        if size == 0 {
+            return Err(anyhow!("The size of the pattern must be at least 1"));
+        }
+
+        Ok(Self {
+            pattern: config.pattern,
+            size,
+            color: config
+                .color
+                .map_or(Rgb888::WHITE, |[r, g, b]| Rgb888::new(r, g, b)),
+        })
+    }
+
+    /// Returns the color of the pixel at `x`, `y` on a canvas that is `width`
+    /// by `height` pixels.
+    fn color_at(&self, x: u32, y: u32, width: u32, height: u32) -> Rgb888 {
+        // Scales a position along a side of the canvas to a color channel
+        let ramp = |position: u32, length: u32| (position * 255 / (length.max(2) - 1)) as u8;
+
+        match self.pattern {
+            Pattern::Solid => self.color,
+            Pattern::Gradient => {
+                let red = ramp(x, width);
+                Rgb888::new(red, ramp(y, height), 255 - red)
+            }
+            Pattern::Grayscale => {
+                let level = ramp(x, width);
+                Rgb888::new(level, level, level)
+            }
+            Pattern::Checkerboard => {
+                if (x / self.size + y / self.size) % 2 == 0 {
+                    Rgb888::WHITE
+                } else {
+                    Rgb888::BLACK
+                }
+            }
+            Pattern::Red => Rgb888::RED,
+            Pattern::Green => Rgb888::GREEN,
+            Pattern::Blue => Rgb888::BLUE,
+            Pattern::White => Rgb888::WHITE,
+            Pattern::Grid => {
+                if x % self.size == 0 || y % self.size == 0 {
+                    Rgb888::WHITE
+                } else {
+                    Rgb888::BLACK
+                }
+            }
+        }
+    }
+}
+
+impl<D> Render<D> for TestPattern
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let bounding_box = canvas.bounding_box();
+        let (width, height) = (bounding_box.size.width, bounding_box.size.height);
+
+        canvas.fill_contiguous(
+            &bounding_box,
+            (0..height).flat_map(|y| (0..width).map(move |x| self.color_at(x, y, width, height))),
+        )
+    }
+
+    fn describe(&self) -> String {
+        format!("TestPattern: {:?}", self.pattern)
+    }
+}
+
+pub struct TestPatternFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for TestPatternFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for TestPatternFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "TestPattern"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Displays a test pattern used to calibrate panels"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: TestPatternConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(TestPattern::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: TestPatternConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(TestPattern::new(config)?))
+    }
+}
@@ -0,0 +1,527 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    mono_font::{self, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Primitive, RgbColor},
+    primitives::{Circle, Line, PrimitiveStyle},
+    text::{Baseline, Text},
+    Drawable,
+};
+use log::error;
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    font::latin1_lossy,
+    render::{BufferCanvas, PollGate, Render, RenderError, RenderFactory, Spinner, SyncRender},
+};
+use septa_api::{responses::Train, types::RegionalRailStop};
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{select, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    loading::{draw_loading, loading_spinner},
+    renders::upcoming_arrivals::septa_provider,
+    request::{
+        refresh_interval, request_timeout, traced, validate_refresh_interval, with_timeout,
+        STALE_AFTER_REFRESHES,
+    },
+    stations::station_location,
+};
+
+const FONT: &MonoFont = &mono_font::ascii::FONT_5X7;
+
+/// Approximate length of a degree of latitude
+const METERS_PER_DEGREE_LAT: f64 = 110_540.0;
+
+/// Approximate length of a degree of longitude at the equator
+const METERS_PER_DEGREE_LON: f64 = 111_320.0;
+
+/// Trains further than this from every segment of the line are running on a
+/// different line
+const OFF_LINE_METERS: f64 = 1_000.0;
+
+/// Space between the ends of the line and the edges of the canvas
+const LINE_MARGIN: i32 = 3;
+
+/// How far the station ticks reach above and below the line
+const TICK_LENGTH: i32 = 2;
+
+const MARKER_DIAMETER: u32 = 5;
+
+const DEFAULT_LINE_COLOR: Rgb888 = Rgb888::new(96, 96, 96);
+const ON_TIME_COLOR: Rgb888 = Rgb888::GREEN;
+const LATE_COLOR: Rgb888 = Rgb888::RED;
+
+/// How long to wait between refreshes if no refresh interval is configured
+const DEFAULT_REFRESH: Duration = Duration::from_secs(15);
+
+/// How long to wait after a failed request if no retry interval is
+/// configured
+const DEFAULT_ERROR_RETRY: Duration = Duration::from_secs(30);
+
+/// Which trains running along the line are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapDirection {
+    /// Trains heading from the first towards the last of the stations
+    Forward,
+
+    /// Trains heading from the last towards the first of the stations
+    Reverse,
+}
+
+/// Configuration for the [`TrainMapFactory`], for example:
+///
+/// ```json
+/// {
+///   "line": "Paoli/Thorndale",
+///   "stations": ["Suburban Station", "30th Street Station", "Overbrook", "Ardmore", "Paoli"],
+///   "direction": "forward"
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrainMapConfig {
+    /// Name of the line, drawn above the diagram
+    pub line: String,
+
+    /// The stations of the line in the order they are drawn, from left to right
+    pub stations: Vec<RegionalRailStop>,
+
+    /// Only show trains heading in this direction. Every train on the line is
+    /// shown if not provided
+    pub direction: Option<MapDirection>,
+
+    /// The color of the line as RGB
+    pub color: Option<[u8; 3]>,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How long, in seconds, to wait between refreshes of the train positions.
+    /// Defaults to [`DEFAULT_REFRESH`] if not provided
+    pub refresh_secs: Option<u64>,
+
+    /// How long, in seconds, to wait before trying again when the train
+    /// positions could not be fetched. Defaults to [`DEFAULT_ERROR_RETRY`] if
+    /// not provided
+    pub error_retry_secs: Option<u64>,
+}
+
+impl TrainMapConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.stations.len() < 2 {
+            return Err(anyhow!("stations must list at least two stations"));
+        }
+
+        for station in &self.stations {
+            septa_provider::validate_station(station)?;
+        }
+
+        validate_refresh_interval("refresh_secs", self.refresh_secs)?;
+        validate_refresh_interval("error_retry_secs", self.error_retry_secs)
+    }
+}
+
+/// A train placed along the line
+#[derive(Debug, Clone)]
+struct MappedTrain {
+    train_number: String,
+
+    /// Position along the line, in stations from the first station, i.e. 1.5
+    /// is halfway between the second and third station
+    position: f64,
+
+    /// How many minutes late the train is, negative if it is early
+    late: i32,
+}
+
+#[derive(Debug, Default)]
+struct TrainMapState {
+    trains: Vec<MappedTrain>,
+
+    /// When the train positions were last fetched
+    refreshed_at: Option<Instant>,
+}
+
+/// Places the location `(lat, lon)` along the line through `stations`.
+///
+/// The location is projected onto the segments to either side of the closest
+/// station and placed on whichever segment it is nearest to. Returns the
+/// position in stations from the first station, or `None` if the location is
+/// more than [`OFF_LINE_METERS`] away from the line.
+fn position_on_line(stations: &[(f64, f64)], (lat, lon): (f64, f64)) -> Option<f64> {
+    // Equirectangular projection around the location, accurate enough over
+    // the distance between two stations
+    let meters_per_degree_lon = METERS_PER_DEGREE_LON * lat.to_radians().cos();
+    let to_meters = |(station_lat, station_lon): (f64, f64)| {
+        (
+            (station_lon - lon) * meters_per_degree_lon,
+            (station_lat - lat) * METERS_PER_DEGREE_LAT,
+        )
+    };
+
+    let closest = stations
+        .iter()
+        .map(|&station| {
+            let (east, north) = to_meters(station);
+            east.hypot(north)
+        })
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)?;
+
+    // Segments are identified by the index of the station they start at
+    let segments = [closest.checked_sub(1), Some(closest)]
+        .into_iter()
+        .flatten()
+        .filter(|&start| start + 1 < stations.len());
+
+    segments
+        .map(|start| {
+            let (start_east, start_north) = to_meters(stations[start]);
+            let (end_east, end_north) = to_meters(stations[start + 1]);
+            let (segment_east, segment_north) = (end_east - start_east, end_north - start_north);
+            let length_squared = segment_east.powi(2) + segment_north.powi(2);
+
+            // The location is at the origin, so this is how far along the
+            // segment its projection lands
+            let fraction = if length_squared > 0.0 {
+                (-(start_east * segment_east + start_north * segment_north) / length_squared)
+                    .clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+
+            let distance = (start_east + segment_east * fraction)
+                .hypot(start_north + segment_north * fraction);
+
+            (start as f64 + fraction, distance)
+        })
+        .filter(|&(_, distance)| distance <= OFF_LINE_METERS)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(position, _)| position)
+}
+
+/// Returns the direction `train` is heading along the line, if its
+/// destination is one of `stations`.
+fn train_direction(
+    train: &Train,
+    stations: &[RegionalRailStop],
+    position: f64,
+) -> Option<MapDirection> {
+    let destination = train.dest.to_string();
+    let destination_index = stations
+        .iter()
+        .position(|station| station.to_string() == destination)?;
+
+    if destination_index as f64 >= position {
+        Some(MapDirection::Forward)
+    } else {
+        Some(MapDirection::Reverse)
+    }
+}
+
+/// Schematic of a single line with the live position of its trains.
+///
+/// The stations are drawn as ticks along a horizontal line, and each train as a
+/// marker placed between the two stations it is travelling between. Trains
+/// that are not close to the line are left out.
+pub struct TrainMap {
+    line: String,
+    station_count: usize,
+    color: Rgb888,
+
+    state: Arc<Mutex<TrainMapState>>,
+
+    /// How long the train positions can go without a refresh before the
+    /// render reports it has no content
+    stale_after: Duration,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+
+    /// Used to pause the update task while the render is not displayed
+    poll_gate: PollGate,
+
+    /// Flag used to gracefully terminate the render and driver threads
+    cancel_token: CancellationToken,
+
+    /// Handle to the task used to update the train positions
+    update_task_handle: Option<JoinHandle<Result<()>>>,
+}
+
+impl TrainMap {
+    pub fn new(config: TrainMapConfig) -> Result<Self> {
+        let station_locations = config
+            .stations
+            .iter()
+            .map(|station| {
+                station_location(station)
+                    .map(|location| (location.latitude(), location.longitude()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let timeout = request_timeout(config.request_timeout_secs);
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let error_retry = refresh_interval(config.error_retry_secs, DEFAULT_ERROR_RETRY);
+
+        let state = Arc::new(Mutex::new(TrainMapState::default()));
+        let poll_gate = PollGate::new();
+        let cancel_token = CancellationToken::new();
+
+        let task_cancel_token = cancel_token.clone();
+        let task_state = state.clone();
+        let task_poll_gate = poll_gate.clone();
+        let stations = config.stations.clone();
+        let direction = config.direction;
+        let septa_client = septa_api::Client::new();
+
+        let update_task_handle = tokio::task::spawn(async move {
+            loop {
+                // Don't poll the SEPTA API while the render is not being displayed
+                select! {
+                    _ = task_poll_gate.active() => {},
+                    _ = task_cancel_token.cancelled() => break,
+                }
+
+                let start_time = tokio::time::Instant::now();
+
+                let trains_result = select! {
+                    trains_result = traced(
+                        "TrainMap",
+                        "septa/train_view",
+                        with_timeout(timeout, septa_client.train_view()),
+                    ) => trains_result,
+                    _ = task_cancel_token.cancelled() => break,
+                };
+
+                let refresh_time = match trains_result {
+                    Ok(trains) => {
+                        let mapped_trains = trains
+                            .iter()
+                            .filter_map(|train| {
+                                let position =
+                                    position_on_line(&station_locations, (train.lat, train.lon))?;
+
+                                // Trains whose direction can't be told apart are
+                                // only shown when every direction is
+                                if direction.is_some()
+                                    && train_direction(train, &stations, position) != direction
+                                {
+                                    return None;
+                                }
+
+                                Some(MappedTrain {
+                                    train_number: train.train_number.to_string(),
+                                    position,
+                                    late: train.late,
+                                })
+                            })
+                            .collect();
+
+                        let mut state = task_state.lock();
+                        state.trains = mapped_trains;
+                        state.refreshed_at = Some(Instant::now());
+
+                        start_time + refresh
+                    }
+                    Err(e) => {
+                        error!("Could not get SEPTA train positions {e}");
+
+                        start_time + error_retry
+                    }
+                };
+
+                select! {
+                    _ = tokio::time::sleep_until(refresh_time) => {},
+                    _ = task_poll_gate.paused() => {},
+                    _ = task_cancel_token.cancelled() => break,
+                }
+            }
+
+            Ok(())
+        });
+
+        Ok(Self {
+            line: config.line,
+            station_count: config.stations.len(),
+            color: config
+                .color
+                .map(|[r, g, b]| Rgb888::new(r, g, b))
+                .unwrap_or(DEFAULT_LINE_COLOR),
+            state,
+            stale_after: refresh * STALE_AFTER_REFRESHES,
+            loading_spinner: loading_spinner(),
+            poll_gate,
+            cancel_token,
+            update_task_handle: Some(update_task_handle),
+        })
+    }
+}
+
+impl<D> Render<D> for TrainMap
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let state = self.state.lock();
+
+        if state.refreshed_at.is_none() {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        let bounding_box = canvas.bounding_box();
+
+        Text::with_baseline(
+            &latin1_lossy(&self.line),
+            bounding_box.top_left,
+            MonoTextStyle::new(FONT, Rgb888::WHITE),
+            Baseline::Top,
+        )
+        .draw(canvas)?;
+
+        // The line runs through the middle of the space below the name
+        let top = bounding_box.top_left.y + FONT.character_size.height as i32;
+        let line_y = top + (bounding_box.size.height as i32 - (top - bounding_box.top_left.y)) / 2;
+        let left = bounding_box.top_left.x + LINE_MARGIN;
+        let length = (bounding_box.size.width as i32 - LINE_MARGIN * 2).max(0);
+
+        // A single station is drawn at the start of the line
+        let stations = self.station_count.saturating_sub(1).max(1) as f64;
+        let to_x = |position: f64| left + (position / stations * length as f64).round() as i32;
+
+        let line_style = PrimitiveStyle::with_stroke(self.color, 1);
+
+        Line::new(Point::new(left, line_y), Point::new(left + length, line_y))
+            .into_styled(line_style)
+            .draw(canvas)?;
+
+        for station in 0..self.station_count {
+            let x = to_x(station as f64);
+
+            Line::new(
+                Point::new(x, line_y - TICK_LENGTH),
+                Point::new(x, line_y + TICK_LENGTH),
+            )
+            .into_styled(line_style)
+            .draw(canvas)?;
+        }
+
+        for train in &state.trains {
+            let color = if train.late > 0 {
+                LATE_COLOR
+            } else {
+                ON_TIME_COLOR
+            };
+
+            Circle::with_center(Point::new(to_x(train.position), line_y), MARKER_DIAMETER)
+                .into_styled(PrimitiveStyle::with_fill(color))
+                .draw(canvas)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.poll_gate.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.poll_gate.pause();
+    }
+
+    fn describe(&self) -> String {
+        let state = self.state.lock();
+
+        match state.refreshed_at {
+            Some(_) => format!(
+                "TrainMap: {} trains on {} ({})",
+                state.trains.len(),
+                self.line,
+                state
+                    .trains
+                    .iter()
+                    .map(|train| train.train_number.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => "TrainMap: loading".to_owned(),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        match self.state.lock().refreshed_at {
+            Some(refreshed_at) => refreshed_at.elapsed() < self.stale_after,
+            None => false,
+        }
+    }
+}
+
+impl Drop for TrainMap {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+
+        if let Some(task_handle) = self.update_task_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
+pub struct TrainMapFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for TrainMapFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for TrainMapFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "TrainMap"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Diagram of a SEPTA regional rail line with the live position of its trains"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: TrainMapConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(TrainMap::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: TrainMapConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(TrainMap::new(config)?))
+    }
+}
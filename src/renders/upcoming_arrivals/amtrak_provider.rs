@@ -4,6 +4,7 @@ use amtrak_api::{
     responses::{TrainState, TrainStatus},
     Client,
 };
+use chrono::{DateTime, FixedOffset};
 
 use super::{UpcomingTrain, UpcomingTrainDirection, UpcomingTrainStatus};
 
@@ -61,27 +62,15 @@ impl AmtrakProvider {
                             UpcomingTrainDirection::Departure
                         },
                         train_id: train.train_id,
-                        status: match station.arrival {
-                            None => super::UpcomingTrainStatus::Unknown,
-                            Some(est_arrival) => {
-                                let mins_early = station
-                                    .schedule_arrival
-                                    .signed_duration_since(est_arrival)
-                                    .num_minutes();
-
-                                match mins_early.cmp(&0) {
-                                    Ordering::Equal => super::UpcomingTrainStatus::OnTime,
-                                    Ordering::Less => match mins_early.abs().try_into() {
-                                        Ok(num) => UpcomingTrainStatus::Late(num),
-                                        Err(_) => UpcomingTrainStatus::Unknown,
-                                    },
-                                    Ordering::Greater => match mins_early.abs().try_into() {
-                                        Ok(num) => UpcomingTrainStatus::Early(num),
-                                        Err(_) => UpcomingTrainStatus::Unknown,
-                                    },
-                                }
-                            }
-                        },
+                        status: station_status(
+                            station.schedule_arrival,
+                            station.arrival,
+                            station.schedule_departure,
+                            station.departure,
+                        ),
+                        track: Some(station.platform).filter(|platform| !platform.is_empty()),
+                        shared_with_amtrak: false,
+                        station: None,
                     })
                 } else {
                     None
@@ -92,3 +81,41 @@ impl AmtrakProvider {
         Ok(arrivals)
     }
 }
+
+/// The status of an Amtrak train at a station, from its estimated arrival.
+///
+/// The origin station has no arrival estimate, so the departure estimate is
+/// used instead to still know the status.
+pub fn station_status(
+    schedule_arrival: DateTime<FixedOffset>,
+    est_arrival: Option<DateTime<FixedOffset>>,
+    schedule_departure: DateTime<FixedOffset>,
+    est_departure: Option<DateTime<FixedOffset>>,
+) -> UpcomingTrainStatus {
+    match (est_arrival, est_departure) {
+        (Some(est_arrival), _) => delay_status(schedule_arrival, est_arrival),
+        (None, Some(est_departure)) => delay_status(schedule_departure, est_departure),
+        (None, None) => UpcomingTrainStatus::Unknown,
+    }
+}
+
+/// Converts the difference between the scheduled and estimated time of a train
+/// into its status.
+fn delay_status(
+    scheduled: DateTime<FixedOffset>,
+    estimated: DateTime<FixedOffset>,
+) -> UpcomingTrainStatus {
+    let mins_early = scheduled.signed_duration_since(estimated).num_minutes();
+
+    match mins_early.cmp(&0) {
+        Ordering::Equal => UpcomingTrainStatus::OnTime,
+        Ordering::Less => match mins_early.abs().try_into() {
+            Ok(num) => UpcomingTrainStatus::Late(num),
+            Err(_) => UpcomingTrainStatus::Unknown,
+        },
+        Ordering::Greater => match mins_early.abs().try_into() {
+            Ok(num) => UpcomingTrainStatus::Early(num),
+            Err(_) => UpcomingTrainStatus::Unknown,
+        },
+    }
+}
@@ -1,39 +1,67 @@
 use anyhow::{anyhow, Result};
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, Utc};
 use embedded_graphics::{
     image::Image,
-    mono_font::{self, MonoTextStyle},
+    mono_font::{self, MonoFont, MonoTextStyle},
     pixelcolor::Rgb888,
-    prelude::{DrawTarget, ImageDrawable, PixelColor, Point, RgbColor},
-    text::Text,
+    prelude::{DrawTarget, ImageDrawable, PixelColor, Point, RgbColor, Size},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
     Drawable,
 };
 use embedded_layout::{
-    chain,
-    layout::linear::{Horizontal, LinearLayout},
-    prelude::{vertical, Chain},
+    layout::linear::{spacing, LinearLayout},
+    prelude::vertical,
     view_group::Views,
     View,
 };
-use embedded_layout::{layout::linear::spacing, prelude::Link};
 use embedded_layout_macros::ViewGroup;
 use log::error;
-use parking_lot::Mutex;
-use rustic_pixel_display::render::{Render, RenderFactory};
+use parking_lot::{Mutex, RwLock};
+use rustic_pixel_display::{
+    font::{latin1_lossy, Font},
+    render::{
+        is_blink_visible, BufferCanvas, Gauge, PollGate, Render, RenderError, RenderFactory,
+        Spinner, SyncRender,
+    },
+};
 use septa_api::types::RegionalRailStop;
 use serde::Deserialize;
-use std::{convert::Infallible, io::Read, marker::PhantomData, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tinybmp::Bmp;
 use tokio::{select, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
+use crate::{
+    assets::load_bmp_or_log,
+    format::{format_time, TimeFormatConfig},
+    late_thresholds::{LateThresholdsConfig, DEFAULT_LATE_THRESHOLDS},
+    line_colors::LineColors,
+    loading::{draw_loading, loading_spinner},
+    request::{
+        refresh_interval, request_timeout, traced, validate_refresh_interval, with_timeout,
+        STALE_AFTER_REFRESHES,
+    },
+    spacing::SpacingConfig,
+    supervisor::{supervise, RESTART_DELAY},
+};
+
 use self::{amtrak_provider::AmtrakProvider, septa_provider::SeptaProvider};
 
 mod amtrak_provider;
-mod septa_provider;
+pub(crate) mod septa_provider;
 
-#[derive(Debug, Clone, Copy)]
-enum UpcomingTrainStatus {
+pub use amtrak_provider::station_status;
+pub use septa_provider::parse_status;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpcomingTrainStatus {
     OnTime,
     Early(u32),
     Late(u32),
@@ -47,21 +75,79 @@ enum UpcomingTrainDirection {
 }
 
 #[derive(Debug, Clone)]
-struct UpcomingTrain {
+pub struct UpcomingTrain {
     /// The time the train is scheduled to arrive in the station
-    schedule_arrival: DateTime<FixedOffset>,
+    pub(crate) schedule_arrival: DateTime<FixedOffset>,
 
     /// The final destination of the train
-    destination_name: String,
+    pub(crate) destination_name: String,
 
     direction: UpcomingTrainDirection,
 
     /// The unique identifier of the train
-    train_id: String,
+    pub(crate) train_id: String,
 
     /// The amount of time, in mins, that the train is late from its scheduled
     /// time. A negative value indicates the train is that many mins early.
-    status: UpcomingTrainStatus,
+    pub(crate) status: UpcomingTrainStatus,
+
+    /// The track the train is assigned to, if the provider reports one
+    track: Option<String>,
+
+    /// If this SEPTA train was merged with an Amtrak train serving the same trip
+    shared_with_amtrak: bool,
+
+    /// The SEPTA station the train was reported for, `None` for Amtrak trains
+    station: Option<RegionalRailStop>,
+}
+
+impl UpcomingTrain {
+    /// A train departing the station for `destination_name`, without a track
+    pub fn new(
+        train_id: impl Into<String>,
+        destination_name: impl Into<String>,
+        schedule_arrival: DateTime<FixedOffset>,
+        status: UpcomingTrainStatus,
+    ) -> Self {
+        Self {
+            schedule_arrival,
+            destination_name: destination_name.into(),
+            direction: UpcomingTrainDirection::Departure,
+            train_id: train_id.into(),
+            status,
+            track: None,
+            shared_with_amtrak: false,
+            station: None,
+        }
+    }
+
+    pub fn with_track(mut self, track: impl Into<String>) -> Self {
+        self.track = Some(track.into());
+        self
+    }
+
+    pub fn train_id(&self) -> &str {
+        &self.train_id
+    }
+
+    /// If this SEPTA train was merged with an Amtrak train serving the same trip
+    pub fn is_shared_with_amtrak(&self) -> bool {
+        self.shared_with_amtrak
+    }
+
+    /// When the train is expected to arrive, its scheduled time adjusted by how
+    /// late or early it is
+    pub(crate) fn expected_arrival(&self) -> DateTime<FixedOffset> {
+        match self.status {
+            UpcomingTrainStatus::Late(mins) => {
+                self.schedule_arrival + chrono::Duration::minutes(mins as i64)
+            }
+            UpcomingTrainStatus::Early(mins) => {
+                self.schedule_arrival - chrono::Duration::minutes(mins as i64)
+            }
+            UpcomingTrainStatus::OnTime | UpcomingTrainStatus::Unknown => self.schedule_arrival,
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -71,189 +157,812 @@ struct UpcomingTrainsState {
     amtrak_arrivals: Vec<UpcomingTrain>,
 
     combined_arrivals: Vec<UpcomingTrain>,
+
+    /// Set once arrivals have been fetched from at least one provider
+    has_loaded: bool,
+
+    /// When arrivals were last fetched from at least one provider
+    refreshed_at: Option<Instant>,
+}
+
+impl UpcomingTrainsState {
+    /// Combines the SEPTA and Amtrak arrivals into the arrivals that are
+    /// displayed, in the order they are scheduled
+    fn combine(&mut self, merge_duplicates: bool) {
+        let mut arrivals = if merge_duplicates {
+            merge_duplicate_trains(&self.septa_arrivals, &self.amtrak_arrivals)
+        } else {
+            self.septa_arrivals
+                .iter()
+                .cloned()
+                .chain(self.amtrak_arrivals.iter().cloned())
+                .collect::<Vec<_>>()
+        };
+        arrivals.sort_by(|a, b| a.schedule_arrival.cmp(&b.schedule_arrival));
+
+        self.combined_arrivals = arrivals;
+    }
+}
+
+/// One or more SEPTA stations, deserialized from either a single station or a
+/// list of stations
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SeptaStations {
+    Single(RegionalRailStop),
+    Multiple(Vec<RegionalRailStop>),
+}
+
+impl SeptaStations {
+    pub fn stations(&self) -> &[RegionalRailStop] {
+        match self {
+            SeptaStations::Single(station) => std::slice::from_ref(station),
+            SeptaStations::Multiple(stations) => stations,
+        }
+    }
+}
+
+impl From<RegionalRailStop> for SeptaStations {
+    fn from(station: RegionalRailStop) -> Self {
+        SeptaStations::Single(station)
+    }
+}
+
+/// Shortens the name of a station to the first letter of each word, or the
+/// first three letters if the name is a single word, i.e. "Suburban Station"
+/// becomes "SS".
+fn station_abbreviation(station: &RegionalRailStop) -> String {
+    let name = station.to_string();
+    let words = name.split_whitespace().collect::<Vec<_>>();
+
+    if words.len() > 1 {
+        words
+            .iter()
+            .filter_map(|word| word.chars().next())
+            .collect::<String>()
+            .to_uppercase()
+    } else {
+        name.chars().take(3).collect::<String>().to_uppercase()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct UpcomingArrivalsConfig {
-    pub septa_station: Option<RegionalRailStop>,
+    pub septa_station: Option<SeptaStations>,
     pub amtrak_station: Option<String>,
     pub results: Option<u8>,
+
+    /// Blink the status of trains that are at least this many minutes late
+    pub blink_late_mins: Option<u32>,
+
+    /// How late trains have to be for their status to change color, defaults
+    /// to [`DEFAULT_LATE_THRESHOLDS`] if not provided
+    pub late_thresholds: Option<LateThresholdsConfig>,
+
+    /// Collapse SEPTA and Amtrak trains that are the same physical train into a
+    /// single entry
+    #[serde(default)]
+    pub merge_duplicates: bool,
+
+    /// How the scheduled times of the trains are displayed
+    #[serde(default)]
+    pub time_format: TimeFormatConfig,
+
+    /// Margins between the title, rows and columns, defaults to
+    /// [`DEFAULT_SPACING`] if not provided
+    pub spacing: Option<SpacingConfig>,
+
+    /// Font of the station name, defaults to 9x15 if not provided
+    pub title_font: Option<Font>,
+
+    /// Font of the arrivals, defaults to 5x7 if not provided
+    pub body_font: Option<Font>,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How long, in seconds, to wait between refreshes of the arrivals.
+    /// Defaults to [`DEFAULT_REFRESH`] if not provided
+    pub refresh_secs: Option<u64>,
+
+    /// How long, in seconds, to wait before trying again when no arrivals could
+    /// be fetched. Defaults to [`DEFAULT_ERROR_RETRY`] if not provided
+    pub error_retry_secs: Option<u64>,
+
+    /// Color of the train id of trains heading to a destination, written as
+    /// `#RRGGBB`. Trains heading anywhere else are drawn in white.
+    pub line_colors: Option<HashMap<String, String>>,
+
+    /// Show a bar next to each train that empties as its scheduled time
+    /// approaches
+    #[serde(default)]
+    pub show_countdown_bar: bool,
+
+    /// Show a row per destination with its next two scheduled times, rather
+    /// than a row per train
+    #[serde(default)]
+    pub group_by_destination: bool,
+
+    /// Show at most this many rows, even if more fit on the panel
+    pub max_rows: Option<usize>,
+
+    /// The order the rows are shown in, soonest first by default
+    #[serde(default)]
+    pub sort: ArrivalSort,
 }
 
-pub struct UpcomingArrivals {
+/// The order the rows of [`UpcomingArrivals`] are shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArrivalSort {
+    /// The train scheduled next at the top
+    #[default]
+    Soonest,
+
+    /// The train scheduled last at the top
+    Latest,
+}
+
+/// Orders `rows`, which are in the order they are scheduled, by `sort` and
+/// keeps the first `max_rows` of them.
+///
+/// Applied before the rows are cut off at the bottom of the panel, so the
+/// latest trains are shown even on a panel that can't fit every row.
+pub fn select_rows<T>(mut rows: Vec<T>, sort: ArrivalSort, max_rows: Option<usize>) -> Vec<T> {
+    if sort == ArrivalSort::Latest {
+        rows.reverse();
+    }
+
+    if let Some(max_rows) = max_rows {
+        rows.truncate(max_rows);
+    }
+
+    rows
+}
+
+const DEFAULT_SPACING: SpacingConfig = SpacingConfig {
+    title_spacing: 2,
+    row_spacing: 3,
+    column_spacing: 6,
+};
+
+const DEFAULT_TITLE_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_9X15;
+const DEFAULT_BODY_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_5X7;
+
+/// How long to wait between refreshes if no refresh interval is configured
+const DEFAULT_REFRESH: Duration = Duration::from_secs(60);
+
+/// How long to wait after every request failed if no retry interval is
+/// configured
+const DEFAULT_ERROR_RETRY: Duration = Duration::from_secs(60);
+
+/// How long the status of a late train is shown or hidden while blinking
+const LATE_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long before its scheduled time a train's countdown bar starts to empty
+const COUNTDOWN_FULL: Duration = Duration::from_secs(10 * 60);
+
+/// Size of the countdown bar of each train
+const COUNTDOWN_BAR_SIZE: Size = Size::new(20, 3);
+
+/// Number of text columns in an arrival row, not counting the countdown bar
+const ARRIVAL_COLUMNS: usize = 8;
+
+const DESTINATION_COLUMN: usize = 6;
+const STATUS_COLUMN: usize = 7;
+
+/// How each column of an arrival row is aligned: the time, station, train id,
+/// shared tag, direction, track, destination and status
+const COLUMN_ALIGNMENTS: [Alignment; ARRIVAL_COLUMNS] = [
+    Alignment::Right,
+    Alignment::Left,
+    Alignment::Left,
+    Alignment::Left,
+    Alignment::Left,
+    Alignment::Left,
+    Alignment::Left,
+    Alignment::Right,
+];
+
+/// How many scheduled times are shown per destination when grouping by
+/// destination
+const GROUPED_TIMES: usize = 2;
+
+/// A single column of an arrival row
+struct ArrivalCell {
+    text: String,
+    color: Rgb888,
+}
+
+impl ArrivalCell {
+    fn new(text: String, color: Rgb888) -> Self {
+        Self { text, color }
+    }
+
+    fn empty() -> Self {
+        Self::new(String::new(), Rgb888::WHITE)
+    }
+}
+
+/// The columns of an arrival row, followed by its countdown bar
+type ArrivalRow = ([ArrivalCell; ARRIVAL_COLUMNS], Option<Gauge<Rgb888>>);
+
+/// Returns how full the countdown bar of a train scheduled at
+/// `schedule_arrival` is at `now`, and the color of the bar.
+fn countdown(schedule_arrival: &DateTime<FixedOffset>, now: &DateTime<Utc>) -> (f32, Rgb888) {
+    let secs_left = schedule_arrival.timestamp() - now.timestamp();
+    let fraction = (secs_left as f32 / COUNTDOWN_FULL.as_secs() as f32).clamp(0.0, 1.0);
+
+    let color = if fraction > 0.5 {
+        Rgb888::GREEN
+    } else if fraction > 0.2 {
+        Rgb888::YELLOW
+    } else {
+        Rgb888::RED
+    };
+
+    (fraction, color)
+}
+
+/// How far apart, in mins, the scheduled times of a SEPTA and an Amtrak train
+/// can be while still being considered the same train
+const MERGE_WINDOW_MINS: i64 = 3;
+
+/// Removes the Amtrak trains that are also reported by SEPTA, marking the
+/// matching SEPTA trains as shared instead.
+///
+/// Trains are considered the same if they are heading to the same destination
+/// and are scheduled within [`MERGE_WINDOW_MINS`] of each other.
+pub fn merge_duplicate_trains(
+    septa_arrivals: &[UpcomingTrain],
+    amtrak_arrivals: &[UpcomingTrain],
+) -> Vec<UpcomingTrain> {
+    let mut septa_arrivals = septa_arrivals.to_vec();
+    let mut unmatched_amtrak_arrivals = Vec::new();
+
+    for amtrak_arrival in amtrak_arrivals {
+        let duplicate = septa_arrivals.iter_mut().find(|septa_arrival| {
+            !septa_arrival.shared_with_amtrak
+                && septa_arrival
+                    .destination_name
+                    .eq_ignore_ascii_case(&amtrak_arrival.destination_name)
+                && septa_arrival
+                    .schedule_arrival
+                    .signed_duration_since(amtrak_arrival.schedule_arrival)
+                    .num_minutes()
+                    .abs()
+                    <= MERGE_WINDOW_MINS
+        });
+
+        match duplicate {
+            Some(septa_arrival) => septa_arrival.shared_with_amtrak = true,
+            None => unmatched_amtrak_arrivals.push(amtrak_arrival.clone()),
+        }
+    }
+
+    septa_arrivals.extend(unmatched_amtrak_arrivals);
+    septa_arrivals
+}
+
+/// The arrivals of one configuration of [`UpcomingArrivals`], along with the
+/// task that fetches them
+struct ArrivalsBoard {
     /// The name of the train stop
     station_name: String,
 
+    /// The SEPTA stations the arrivals are fetched for
+    septa_stations: Vec<RegionalRailStop>,
+
+    /// The Amtrak station the arrivals are fetched for
+    amtrak_station: Option<String>,
+
+    title_font: &'static MonoFont<'static>,
+    body_font: &'static MonoFont<'static>,
+
+    /// How long the arrivals can go without a refresh before the render
+    /// reports it has no content
+    stale_after: Duration,
+
     /// If the station has SEPTA transit information
     is_septa_stop: bool,
 
     /// If the station has Amtrak transit information
     is_amtrak_stop: bool,
 
+    /// Show the station each train was reported for, set when more than one
+    /// SEPTA station is configured
+    show_station: bool,
+
+    /// Blink the status of trains that are at least this many minutes late
+    blink_late_mins: Option<u32>,
+
+    /// How late trains have to be for their status to change color
+    late_thresholds: LateThresholdsConfig,
+
+    /// How the scheduled times of the trains are displayed
+    time_format: TimeFormatConfig,
+
+    /// Margins between the title, rows and columns
+    spacing: SpacingConfig,
+
+    /// Color of the train id by destination
+    line_colors: LineColors,
+
+    /// Show a bar next to each train that empties as its scheduled time
+    /// approaches
+    show_countdown_bar: bool,
+
+    /// Show a row per destination rather than a row per train
+    group_by_destination: bool,
+
+    /// Show at most this many rows
+    max_rows: Option<usize>,
+
+    /// The order the rows are shown in
+    sort: ArrivalSort,
+
     /// Flag used to gracefully terminate the render and driver threads
     cancel_token: CancellationToken,
 
+    /// Used to pause the update task while the render is not displayed
+    poll_gate: PollGate,
+
     /// Shared state between the render and the async task
     state: Arc<Mutex<UpcomingTrainsState>>,
 
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+
     /// Handle to the task used to update the SEPTA information
-    update_task_handle: Option<JoinHandle<Result<()>>>,
+    update_task_handle: Option<JoinHandle<()>>,
 }
 
-impl UpcomingArrivals {
-    pub fn new(config: UpcomingArrivalsConfig) -> Result<Self> {
+impl ArrivalsBoard {
+    /// Starts fetching the arrivals of `config` if `fetch` is set, showing the
+    /// arrivals of `state` until the first fetch completes
+    fn new(
+        config: UpcomingArrivalsConfig,
+        state: UpcomingTrainsState,
+        fetch: bool,
+    ) -> Result<Self> {
         // Derive the station name from either the SEPTA or Amtrak location, giving
         // preference to SEPTA.
-        let station_name = match (&config.septa_station, &config.amtrak_station) {
-            (None, Some(amtrak_station)) => amtrak_station.clone(),
-            (Some(septa_station), None) | (Some(septa_station), Some(_)) => {
-                septa_station.to_string()
-            }
-            (None, None) => return Err(anyhow!("Need to provide at least one Station")),
+        let septa_stations = config
+            .septa_station
+            .as_ref()
+            .map(|septa_station| septa_station.stations().to_vec())
+            .unwrap_or_default();
+
+        let station_name = match (septa_stations.as_slice(), &config.amtrak_station) {
+            ([], Some(amtrak_station)) => amtrak_station.clone(),
+            ([septa_station], _) => septa_station.to_string(),
+            ([], None) => return Err(anyhow!("Need to provide at least one Station")),
+            (septa_stations, _) => septa_stations
+                .iter()
+                .map(station_abbreviation)
+                .collect::<Vec<_>>()
+                .join(" / "),
+        };
+        let station_name = latin1_lossy(&station_name).into_owned();
+
+        for septa_station in &septa_stations {
+            septa_provider::validate_station(septa_station)?;
+        }
+
+        let spacing = config.spacing.unwrap_or(DEFAULT_SPACING);
+        spacing.validate()?;
+
+        if config.max_rows == Some(0) {
+            return Err(anyhow!("max_rows must be at least 1"));
+        }
+
+        let late_thresholds = config.late_thresholds.unwrap_or(DEFAULT_LATE_THRESHOLDS);
+        late_thresholds.validate()?;
+
+        validate_refresh_interval("refresh_secs", config.refresh_secs)?;
+        validate_refresh_interval("error_retry_secs", config.error_retry_secs)?;
+
+        let line_colors = match &config.line_colors {
+            Some(line_colors) => LineColors::new(line_colors)?,
+            None => LineColors::default(),
         };
 
-        let state = Arc::new(Mutex::new(UpcomingTrainsState::default()));
+        let state = Arc::new(Mutex::new(state));
         let cancel_token = CancellationToken::new();
+        let poll_gate = PollGate::new();
 
-        let is_septa_stop = config.septa_station.is_some();
+        let is_septa_stop = !septa_stations.is_empty();
+        let show_station = septa_stations.len() > 1;
         let is_amtrak_stop = config.amtrak_station.is_some();
+        let amtrak_station = config.amtrak_station.clone();
+        let blink_late_mins = config.blink_late_mins;
+        let merge_duplicates = config.merge_duplicates;
+        let timeout = request_timeout(config.request_timeout_secs);
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let error_retry = refresh_interval(config.error_retry_secs, DEFAULT_ERROR_RETRY);
+        let time_format = config.time_format.clone();
+        let show_countdown_bar = config.show_countdown_bar;
+        let group_by_destination = config.group_by_destination;
+        let max_rows = config.max_rows;
+        let sort = config.sort;
+        let title_font = config
+            .title_font
+            .map(|font| font.mono_font())
+            .unwrap_or(DEFAULT_TITLE_FONT);
+        let body_font = config
+            .body_font
+            .map(|font| font.mono_font())
+            .unwrap_or(DEFAULT_BODY_FONT);
 
         let task_cancel_token = cancel_token.clone();
         let task_state = state.clone();
+        let task_poll_gate = poll_gate.clone();
+        let task_septa_stations = septa_stations.clone();
+        let task_amtrak_station = amtrak_station.clone();
 
-        let update_task_handle: JoinHandle<Result<()>> = tokio::task::spawn(async move {
-            let septa_client = config.septa_station.map(SeptaProvider::new);
-            let amtrak_client = config.amtrak_station.map(AmtrakProvider::new);
+        let update_task_handle = fetch.then(move || {
+            supervise(
+                "UpcomingArrivals",
+                task_cancel_token.clone(),
+                RESTART_DELAY,
+                move || {
+                    let task_cancel_token = task_cancel_token.clone();
+                    let task_state = task_state.clone();
+                    let task_poll_gate = task_poll_gate.clone();
+                    let task_septa_stations = task_septa_stations.clone();
+                    let task_amtrak_station = task_amtrak_station.clone();
 
-            loop {
-                let refresh_time = tokio::time::Instant::now() + Duration::from_secs(60);
+                    async move {
+                        let septa_clients = task_septa_stations
+                            .into_iter()
+                            .map(SeptaProvider::new)
+                            .collect::<Vec<_>>();
+                        let amtrak_client = task_amtrak_station.map(AmtrakProvider::new);
 
-                let septa_arrivals = if let Some(septa_client) = &septa_client {
-                    match septa_client.arrivals().await {
-                        Ok(response) => Some(response),
-                        Err(e) => {
-                            error!("Could not get updated SEPTA arrivals {e}");
-                            None
-                        }
-                    }
-                } else {
-                    None
-                };
+                        loop {
+                            // Don't poll the transit APIs while the render is not being displayed
+                            select! {
+                                _ = task_poll_gate.active() => {},
+                                _ = task_cancel_token.cancelled() => break,
+                            }
 
-                let amtrak_arrivals = if let Some(amtrak_client) = &amtrak_client {
-                    match amtrak_client.arrivals().await {
-                        Ok(response) => Some(response),
-                        Err(e) => {
-                            error!("Could not get updated Amtrak arrivals {e}");
-                            None
-                        }
-                    }
-                } else {
-                    None
-                };
+                            let start_time = tokio::time::Instant::now();
 
-                {
-                    let mut state_unlocked = task_state.lock();
+                            let fetch_arrivals = async {
+                                // Stations that could not be fetched keep their previous arrivals
+                                let mut septa_arrivals = Vec::new();
+                                for septa_client in &septa_clients {
+                                    match traced(
+                                        "UpcomingArrivals",
+                                        format!("septa/arrivals/{}", septa_client.station()),
+                                        with_timeout(timeout, septa_client.arrivals()),
+                                    )
+                                    .await
+                                    {
+                                        Ok(response) => {
+                                            septa_arrivals.push((septa_client.station(), response))
+                                        }
+                                        Err(e) => error!(
+                                            "Could not get updated SEPTA arrivals for {} {e}",
+                                            septa_client.station()
+                                        ),
+                                    }
+                                }
 
-                    if let Some(septa_arrivals) = septa_arrivals {
-                        state_unlocked.septa_arrivals = septa_arrivals;
-                    }
+                                let amtrak_arrivals = if let Some(amtrak_client) = &amtrak_client {
+                                    match traced(
+                                        "UpcomingArrivals",
+                                        "amtrak/arrivals",
+                                        with_timeout(timeout, amtrak_client.arrivals()),
+                                    )
+                                    .await
+                                    {
+                                        Ok(response) => Some(response),
+                                        Err(e) => {
+                                            error!("Could not get updated Amtrak arrivals {e}");
+                                            None
+                                        }
+                                    }
+                                } else {
+                                    None
+                                };
 
-                    if let Some(amtrak_arrivals) = amtrak_arrivals {
-                        state_unlocked.amtrak_arrivals = amtrak_arrivals;
-                    }
+                                (septa_arrivals, amtrak_arrivals)
+                            };
 
-                    let mut arrivals = state_unlocked
-                        .septa_arrivals
-                        .iter()
-                        .cloned()
-                        .chain(state_unlocked.amtrak_arrivals.iter().cloned())
-                        .collect::<Vec<_>>();
-                    arrivals.sort_by(|a, b| a.schedule_arrival.cmp(&b.schedule_arrival));
+                            // Drop any in flight requests as soon as the render is dropped
+                            let (septa_arrivals, amtrak_arrivals) = select! {
+                                arrivals = fetch_arrivals => arrivals,
+                                _ = task_cancel_token.cancelled() => break,
+                            };
 
-                    state_unlocked.combined_arrivals = arrivals;
-                } // drop(state_unlocked)
+                            let refresh_time =
+                                if septa_arrivals.is_empty() && amtrak_arrivals.is_none() {
+                                    start_time + error_retry
+                                } else {
+                                    start_time + refresh
+                                };
 
-                select! {
-                    _ = tokio::time::sleep_until(refresh_time) => {},
-                    _ = task_cancel_token.cancelled() => break,
-                }
-            }
+                            {
+                                let mut state_unlocked = task_state.lock();
+
+                                if !septa_arrivals.is_empty() || amtrak_arrivals.is_some() {
+                                    state_unlocked.has_loaded = true;
+                                    state_unlocked.refreshed_at = Some(Instant::now());
+                                }
+
+                                for (station, arrivals) in septa_arrivals {
+                                    state_unlocked.septa_arrivals.retain(|arrival| {
+                                        arrival.station.as_ref() != Some(station)
+                                    });
+                                    state_unlocked.septa_arrivals.extend(arrivals);
+                                }
+
+                                if let Some(amtrak_arrivals) = amtrak_arrivals {
+                                    state_unlocked.amtrak_arrivals = amtrak_arrivals;
+                                }
+
+                                state_unlocked.combine(merge_duplicates);
+                            } // drop(state_unlocked)
 
-            Ok(())
+                            select! {
+                                _ = tokio::time::sleep_until(refresh_time) => {},
+                                _ = task_poll_gate.paused() => {},
+                                _ = task_cancel_token.cancelled() => break,
+                            }
+                        }
+
+                        Ok(())
+                    }
+                },
+            )
         });
 
         Ok(Self {
             state,
+            loading_spinner: loading_spinner(),
             station_name,
+            septa_stations,
+            amtrak_station,
+            title_font,
+            body_font,
+            stale_after: refresh * STALE_AFTER_REFRESHES,
             is_septa_stop,
             is_amtrak_stop,
+            show_station,
+            blink_late_mins,
+            late_thresholds,
+            time_format,
+            spacing,
+            line_colors,
+            show_countdown_bar,
+            group_by_destination,
+            max_rows,
+            sort,
             cancel_token,
-            update_task_handle: Some(update_task_handle),
+            poll_gate,
+            update_task_handle,
         })
     }
+
+    /// Returns the countdown bar of `arrival`, if countdown bars are shown.
+    fn countdown_bar(&self, arrival: &UpcomingTrain, now: &DateTime<Utc>) -> Option<Gauge<Rgb888>> {
+        self.show_countdown_bar.then(|| {
+            let (fraction, color) = countdown(&arrival.schedule_arrival, now);
+            Gauge::new(COUNTDOWN_BAR_SIZE, fraction, color)
+        })
+    }
+
+    /// Builds the row of a single train.
+    fn train_row(
+        &self,
+        arrival: &UpcomingTrain,
+        now: &DateTime<Utc>,
+        late_status_visible: bool,
+    ) -> ArrivalRow {
+        let status_color = match arrival.status {
+            UpcomingTrainStatus::OnTime | UpcomingTrainStatus::Early(_) => Rgb888::GREEN,
+            UpcomingTrainStatus::Late(mins) => {
+                let should_blink = self
+                    .blink_late_mins
+                    .is_some_and(|blink_late_mins| mins >= blink_late_mins);
+
+                if should_blink && !late_status_visible {
+                    Rgb888::BLACK
+                } else {
+                    self.late_thresholds.color(mins as i32)
+                }
+            }
+            UpcomingTrainStatus::Unknown => Rgb888::WHITE,
+        };
+
+        // Columns that are empty for every train take up no room
+        let cells = [
+            ArrivalCell::new(
+                format_time(&arrival.schedule_arrival, &self.time_format),
+                Rgb888::WHITE,
+            ),
+            ArrivalCell::new(
+                match (&arrival.station, self.show_station) {
+                    (Some(station), true) => station_abbreviation(station),
+                    _ => String::new(),
+                },
+                Rgb888::MAGENTA,
+            ),
+            ArrivalCell::new(
+                arrival.train_id.clone(),
+                self.line_colors.color_for(&arrival.destination_name),
+            ),
+            ArrivalCell::new(
+                if arrival.shared_with_amtrak {
+                    "S+A".to_owned()
+                } else {
+                    String::new()
+                },
+                Rgb888::YELLOW,
+            ),
+            ArrivalCell::new(
+                match arrival.direction {
+                    UpcomingTrainDirection::Arrival => "A".to_owned(),
+                    UpcomingTrainDirection::Departure => "D".to_owned(),
+                },
+                Rgb888::WHITE,
+            ),
+            ArrivalCell::new(
+                match &arrival.track {
+                    Some(track) => format!("Trk {}", track),
+                    None => String::new(),
+                },
+                Rgb888::CYAN,
+            ),
+            ArrivalCell::new(
+                latin1_lossy(&arrival.destination_name).into_owned(),
+                Rgb888::WHITE,
+            ),
+            ArrivalCell::new(
+                match arrival.status {
+                    UpcomingTrainStatus::OnTime => "On Time".to_string(),
+                    UpcomingTrainStatus::Early(mins) => format!("{} mins early", mins),
+                    UpcomingTrainStatus::Late(mins) => format!("{} mins late", mins),
+                    UpcomingTrainStatus::Unknown => "N/A".to_string(),
+                },
+                status_color,
+            ),
+        ];
+
+        (cells, self.countdown_bar(arrival, now))
+    }
+
+    /// Builds a row per destination, in the order of each destination's next
+    /// train, listing the scheduled times of up to [`GROUPED_TIMES`] trains.
+    fn destination_rows(&self, arrivals: &[UpcomingTrain], now: &DateTime<Utc>) -> Vec<ArrivalRow> {
+        let mut destinations: Vec<(&UpcomingTrain, Vec<String>)> = Vec::new();
+
+        for arrival in arrivals {
+            let time = format_time(&arrival.schedule_arrival, &self.time_format);
+
+            match destinations
+                .iter_mut()
+                .find(|(next, _)| next.destination_name == arrival.destination_name)
+            {
+                Some((_, times)) => {
+                    if times.len() < GROUPED_TIMES {
+                        times.push(time);
+                    }
+                }
+                None => destinations.push((arrival, vec![time])),
+            }
+        }
+
+        destinations
+            .into_iter()
+            .map(|(next, times)| {
+                let mut cells: [ArrivalCell; ARRIVAL_COLUMNS] =
+                    std::array::from_fn(|_| ArrivalCell::empty());
+
+                // The destination and times take the place of the destination
+                // and status columns, so the same alignment applies
+                cells[DESTINATION_COLUMN] = ArrivalCell::new(
+                    latin1_lossy(&next.destination_name).into_owned(),
+                    self.line_colors.color_for(&next.destination_name),
+                );
+                cells[STATUS_COLUMN] = ArrivalCell::new(times.join(", "), Rgb888::WHITE);
+
+                (cells, self.countdown_bar(next, now))
+            })
+            .collect()
+    }
+
+    /// The arrivals of the stations `config` keeps, so they are shown straight
+    /// away rather than after the first fetch of the new configuration
+    fn carried_over_state(&self, config: &UpcomingArrivalsConfig) -> UpcomingTrainsState {
+        let septa_stations = config
+            .septa_station
+            .as_ref()
+            .map(|septa_station| septa_station.stations())
+            .unwrap_or_default();
+        let keeps_amtrak_station =
+            self.amtrak_station.is_some() && self.amtrak_station == config.amtrak_station;
+        let keeps_station = keeps_amtrak_station
+            || self
+                .septa_stations
+                .iter()
+                .any(|station| septa_stations.contains(station));
+
+        let previous = self.state.lock();
+        let mut state = UpcomingTrainsState {
+            septa_arrivals: previous
+                .septa_arrivals
+                .iter()
+                .filter(|arrival| {
+                    arrival
+                        .station
+                        .as_ref()
+                        .is_some_and(|station| septa_stations.contains(station))
+                })
+                .cloned()
+                .collect(),
+            amtrak_arrivals: if keeps_amtrak_station {
+                previous.amtrak_arrivals.clone()
+            } else {
+                Vec::new()
+            },
+            ..UpcomingTrainsState::default()
+        };
+
+        if keeps_station {
+            state.has_loaded = previous.has_loaded;
+            state.refreshed_at = previous.refreshed_at;
+        }
+
+        state.combine(config.merge_duplicates);
+        state
+    }
 }
 
 const SEPTA_IMAGE: &[u8] = include_bytes!("../../../assets/SEPTA_16.bmp");
 const AMTRAK_IMAGE: &[u8] = include_bytes!("../../../assets/AMTRAK_16.bmp");
 
 lazy_static! {
-    static ref SEPTA_BMP: Bmp::<'static, Rgb888> = Bmp::<Rgb888>::from_slice(SEPTA_IMAGE).unwrap();
-    static ref AMTRAK_BMP: Bmp::<'static, Rgb888> =
-        Bmp::<Rgb888>::from_slice(AMTRAK_IMAGE).unwrap();
+    static ref SEPTA_BMP: Option<Bmp::<'static, Rgb888>> =
+        load_bmp_or_log("SEPTA_16.bmp", SEPTA_IMAGE);
+    static ref AMTRAK_BMP: Option<Bmp::<'static, Rgb888>> =
+        load_bmp_or_log("AMTRAK_16.bmp", AMTRAK_IMAGE);
 }
 
-type UpcomingArrivalViews<'a, C> = chain! {
-    Text<'a, MonoTextStyle<'static, C>>,
-    Text<'a, MonoTextStyle<'static, C>>,
-    Text<'a, MonoTextStyle<'static, C>>,
-    Text<'a, MonoTextStyle<'static, C>>,
-    Text<'a, MonoTextStyle<'static, C>>
-};
-
 #[derive(ViewGroup)]
 enum TitleView<'a, C: PixelColor, T: ImageDrawable<Color = C>> {
     LogoView(Image<'a, T>),
     TextView(Text<'a, MonoTextStyle<'static, C>>),
 }
 
-#[derive(ViewGroup)]
-enum LayoutView<'a, C: PixelColor> {
-    UpcomingArrival(
-        LinearLayout<
-            Horizontal<vertical::Center, spacing::FixedMargin>,
-            UpcomingArrivalViews<'a, C>,
-        >,
-    ),
-    NoArrival(
-        LinearLayout<
-            Horizontal<vertical::Center, spacing::FixedMargin>,
-            chain! { Text<'a, MonoTextStyle<'static, C>> },
-        >,
-    ),
-}
-
-impl<D> Render<D> for UpcomingArrivals
+impl<D> Render<D> for ArrivalsBoard
 where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
 {
     fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        if !self.state.lock().has_loaded {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
         let canvas_bounding_box = canvas.bounding_box();
         let mut remaining_height = canvas_bounding_box.size.height;
 
         // Figure out which logos to display
         let mut title_views = Vec::new();
-        if self.is_septa_stop {
-            title_views.push(TitleView::LogoView(Image::new(&*SEPTA_BMP, Point::zero())));
+        if let (true, Some(septa_bmp)) = (self.is_septa_stop, &*SEPTA_BMP) {
+            title_views.push(TitleView::LogoView(Image::new(septa_bmp, Point::zero())));
         }
-        if self.is_amtrak_stop {
-            title_views.push(TitleView::LogoView(Image::new(&*AMTRAK_BMP, Point::zero())));
+        if let (true, Some(amtrak_bmp)) = (self.is_amtrak_stop, &*AMTRAK_BMP) {
+            title_views.push(TitleView::LogoView(Image::new(amtrak_bmp, Point::zero())));
         }
 
         title_views.push(TitleView::TextView(Text::new(
             &self.station_name,
             Point::zero(),
-            MonoTextStyle::new(&mono_font::ascii::FONT_9X15, Rgb888::WHITE),
+            MonoTextStyle::new(self.title_font, Rgb888::WHITE),
         )));
 
         // Generate the title layout
@@ -262,116 +971,156 @@ where
             .with_spacing(spacing::FixedMargin(2))
             .arrange();
 
-        remaining_height -= title_layout.bounds().size.height;
+        // A title taller than the panel leaves no room for arrivals rather than
+        // underflowing
+        remaining_height = remaining_height.saturating_sub(
+            title_layout
+                .bounds()
+                .size
+                .height
+                .saturating_add(self.spacing.title_spacing),
+        );
 
-        let mut arrival_layouts = Vec::new();
+        let column_spacing = self.spacing.column_spacing as i32;
+        let late_status_visible = is_blink_visible(LATE_BLINK_INTERVAL);
 
-        let display_items = self
-            .state
-            .lock()
-            .combined_arrivals
-            .iter()
-            .map(|arrival| {
-                (
-                    arrival.schedule_arrival.format("%_H:%M").to_string(),
-                    format!("{:<7}", arrival.train_id),
-                    match arrival.direction {
-                        UpcomingTrainDirection::Arrival => "A".to_owned(),
-                        UpcomingTrainDirection::Departure => "D".to_owned(),
-                    },
-                    format!("{:<20}", arrival.destination_name),
-                    match arrival.status {
-                        UpcomingTrainStatus::OnTime => "On Time".to_string(),
-                        UpcomingTrainStatus::Early(mins) => format!("{} mins early", mins),
-                        UpcomingTrainStatus::Late(mins) => format!("{} mins late", mins),
-                        UpcomingTrainStatus::Unknown => "N/A".to_string(),
-                    },
-                    match arrival.status {
-                        UpcomingTrainStatus::OnTime | UpcomingTrainStatus::Early(_) => {
-                            Rgb888::GREEN
-                        }
-                        UpcomingTrainStatus::Late(_) => Rgb888::RED,
-                        UpcomingTrainStatus::Unknown => Rgb888::WHITE,
-                    },
-                )
-            })
-            .collect::<Vec<_>>();
-
-        if display_items.is_empty() {
-            arrival_layouts.push(LayoutView::NoArrival(
-                LinearLayout::horizontal(Chain::new(Text::new(
-                    "No upcoming arrivals",
-                    Point::zero(),
-                    MonoTextStyle::new(&mono_font::ascii::FONT_6X9, Rgb888::WHITE),
-                )))
-                .with_alignment(vertical::Center)
-                .with_spacing(spacing::FixedMargin(6))
-                .arrange(),
-            ));
+        // The countdown bars change with the time, so they are computed on every frame
+        let now = Utc::now();
+
+        let state = self.state.lock();
+
+        let rows = if self.group_by_destination {
+            self.destination_rows(&state.combined_arrivals, &now)
+        } else {
+            state
+                .combined_arrivals
+                .iter()
+                .map(|arrival| self.train_row(arrival, &now, late_status_visible))
+                .collect()
+        };
+
+        drop(state);
+
+        let rows = select_rows(rows, self.sort, self.max_rows);
+
+        title_layout.draw(canvas)?;
+
+        let mut top = title_layout.bounds().size.height as i32 + self.spacing.title_spacing as i32;
+
+        if rows.is_empty() {
+            Text::with_baseline(
+                "No upcoming arrivals",
+                Point::new(0, top),
+                MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X9, Rgb888::WHITE),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+
+            return Ok(());
+        }
+
+        let char_width =
+            (self.body_font.character_size.width + self.body_font.character_spacing) as i32;
+
+        // Every column is as wide as its widest entry
+        let column_widths: [i32; ARRIVAL_COLUMNS] = std::array::from_fn(|column| {
+            rows.iter()
+                .map(|(cells, _)| cells[column].text.chars().count() as i32 * char_width)
+                .max()
+                .unwrap_or(0)
+        });
+
+        let row_height = if self.show_countdown_bar {
+            self.body_font
+                .character_size
+                .height
+                .max(COUNTDOWN_BAR_SIZE.height)
         } else {
-            for display_item in &display_items {
-                let (time, train_id, direction, destination_name, status, status_color) =
-                    display_item;
-
-                let chain = Chain::new(Text::new(
-                    time,
-                    Point::zero(),
-                    MonoTextStyle::new(&mono_font::ascii::FONT_5X7, Rgb888::WHITE),
-                ))
-                .append(Text::new(
-                    train_id,
-                    Point::zero(),
-                    MonoTextStyle::new(&mono_font::ascii::FONT_5X7, Rgb888::WHITE),
-                ))
-                .append(Text::new(
-                    direction,
-                    Point::zero(),
-                    MonoTextStyle::new(&mono_font::ascii::FONT_5X7, Rgb888::WHITE),
-                ))
-                .append(Text::new(
-                    destination_name,
-                    Point::zero(),
-                    MonoTextStyle::new(&mono_font::ascii::FONT_5X7, Rgb888::WHITE),
-                ))
-                .append(Text::new(
-                    status,
-                    Point::zero(),
-                    MonoTextStyle::new(&mono_font::ascii::FONT_5X7, *status_color),
-                ));
-
-                let chain_height = chain.bounds().size.height;
-
-                if remaining_height < chain_height {
-                    break;
+            self.body_font.character_size.height
+        };
+
+        for (cells, countdown_bar) in &rows {
+            if remaining_height < row_height {
+                break;
+            }
+
+            remaining_height = remaining_height
+                .saturating_sub(row_height.saturating_add(self.spacing.row_spacing));
+
+            let middle = top + row_height as i32 / 2;
+            let mut left = 0;
+
+            for ((cell, width), alignment) in cells.iter().zip(column_widths).zip(COLUMN_ALIGNMENTS)
+            {
+                if width == 0 {
+                    continue;
                 }
 
-                remaining_height -= chain.bounds().size.height;
+                let x = match alignment {
+                    Alignment::Right => left + width,
+                    _ => left,
+                };
 
-                arrival_layouts.push(LayoutView::UpcomingArrival(
-                    LinearLayout::horizontal(chain)
-                        .with_alignment(vertical::Center)
-                        .with_spacing(spacing::FixedMargin(6))
-                        .arrange(),
-                ));
+                Text::with_text_style(
+                    &cell.text,
+                    Point::new(x, middle),
+                    MonoTextStyle::new(self.body_font, cell.color),
+                    TextStyleBuilder::new()
+                        .alignment(alignment)
+                        .baseline(Baseline::Middle)
+                        .build(),
+                )
+                .draw(canvas)?;
+
+                left += width + column_spacing;
             }
-        }
 
-        LinearLayout::vertical(
-            Chain::new(title_layout).append(
-                LinearLayout::vertical(Views::new(arrival_layouts.as_mut_slice()))
-                    .with_spacing(spacing::FixedMargin(3))
-                    .arrange(),
-            ),
-        )
-        .with_spacing(spacing::FixedMargin(2))
-        .arrange()
-        .draw(canvas)?;
+            if let Some(countdown_bar) = countdown_bar {
+                countdown_bar
+                    .translate(Point::new(
+                        left,
+                        middle - COUNTDOWN_BAR_SIZE.height as i32 / 2,
+                    ))
+                    .draw(canvas)?;
+            }
+
+            top += (row_height + self.spacing.row_spacing) as i32;
+        }
 
         Ok(())
     }
+
+    fn on_select(&self) {
+        self.poll_gate.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.poll_gate.pause();
+    }
+
+    fn describe(&self) -> String {
+        let state = self.state.lock();
+
+        if state.has_loaded {
+            format!(
+                "UpcomingArrivals @ {}, {} trains",
+                self.station_name,
+                state.combined_arrivals.len()
+            )
+        } else {
+            format!("UpcomingArrivals @ {}, loading", self.station_name)
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        match self.state.lock().refreshed_at {
+            Some(refreshed_at) => refreshed_at.elapsed() < self.stale_after,
+            None => false,
+        }
+    }
 }
 
-impl Drop for UpcomingArrivals {
+impl Drop for ArrivalsBoard {
     fn drop(&mut self) {
         self.cancel_token.cancel();
 
@@ -381,6 +1130,94 @@ impl Drop for UpcomingArrivals {
     }
 }
 
+/// Upcoming SEPTA regional rail and Amtrak trains at one or more stations.
+///
+/// Can be reconfigured in place, in which case the arrivals of the stations
+/// that are still configured are kept until the new configuration fetches
+/// its own.
+pub struct UpcomingArrivals {
+    board: RwLock<ArrivalsBoard>,
+}
+
+impl UpcomingArrivals {
+    pub fn new(config: UpcomingArrivalsConfig) -> Result<Self> {
+        Ok(Self {
+            board: RwLock::new(ArrivalsBoard::new(
+                config,
+                UpcomingTrainsState::default(),
+                true,
+            )?),
+        })
+    }
+
+    /// Shows `septa_arrivals` and `amtrak_arrivals` instead of fetching the
+    /// arrivals of the configured stations, i.e. for golden tests
+    pub fn with_arrivals(
+        config: UpcomingArrivalsConfig,
+        septa_arrivals: Vec<UpcomingTrain>,
+        amtrak_arrivals: Vec<UpcomingTrain>,
+    ) -> Result<Self> {
+        let mut state = UpcomingTrainsState {
+            septa_arrivals,
+            amtrak_arrivals,
+            has_loaded: true,
+            refreshed_at: Some(Instant::now()),
+            ..UpcomingTrainsState::default()
+        };
+        state.combine(config.merge_duplicates);
+
+        Ok(Self {
+            board: RwLock::new(ArrivalsBoard::new(config, state, false)?),
+        })
+    }
+}
+
+impl<D> Render<D> for UpcomingArrivals
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.render_at(canvas, Instant::now())
+    }
+
+    fn render_at(&self, canvas: &mut D, now: Instant) -> Result<(), D::Error> {
+        self.board.read().render_at(canvas, now)
+    }
+
+    fn on_select(&self) {
+        Render::<D>::on_select(&*self.board.read());
+    }
+
+    fn on_deselect(&self) {
+        Render::<D>::on_deselect(&*self.board.read());
+    }
+
+    fn describe(&self) -> String {
+        Render::<D>::describe(&*self.board.read())
+    }
+
+    fn has_content(&self) -> bool {
+        Render::<D>::has_content(&*self.board.read())
+    }
+
+    fn reconfigure(&self, config: &mut dyn Read) -> Result<(), RenderError> {
+        let config: UpcomingArrivalsConfig = serde_json::from_reader(config)?;
+        let mut board = self.board.write();
+
+        let state = board.carried_over_state(&config);
+        let new_board = ArrivalsBoard::new(config, state, true)?;
+
+        // Keep polling paused if the render is not being displayed
+        if !board.poll_gate.is_active() {
+            new_board.poll_gate.pause();
+        }
+
+        // Dropping the previous board stops its update task
+        *board = new_board;
+        Ok(())
+    }
+}
+
 pub struct UpcomingArrivalsFactory<D>
 where
     D: DrawTarget<Color = Rgb888, Error = Infallible>,
@@ -411,7 +1248,15 @@ where
         "Upcoming train arrivals for SEPTA regional rail and Amtrak"
     }
 
-    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn Render<D>>> {
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: UpcomingArrivalsConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(UpcomingArrivals::new(config)?))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
         let config: UpcomingArrivalsConfig = serde_json::from_reader(reader)?;
         Ok(Box::new(UpcomingArrivals::new(config)?))
     }
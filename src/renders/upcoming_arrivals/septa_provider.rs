@@ -1,24 +1,74 @@
-use std::error::Error;
+use std::{collections::HashSet, error::Error};
 
+use anyhow::{anyhow, Result};
 use chrono::FixedOffset;
 use log::warn;
+use parking_lot::Mutex;
 use septa_api::{requests::ArrivalsRequest, responses::Arrivals, types::RegionalRailStop, Client};
 
 use super::{UpcomingTrain, UpcomingTrainStatus};
+use crate::stations::known_stations;
 
-pub(super) struct SeptaProvider {
+// Anomalies in SEPTA responses that were already logged, so one that is
+// repeated in every response doesn't flood the log
+lazy_static! {
+    static ref LOGGED_ANOMALIES: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// Logs `anomaly` the first time it is seen
+fn warn_once(anomaly: String) {
+    if LOGGED_ANOMALIES.lock().insert(anomaly.clone()) {
+        warn!("{anomaly}");
+    }
+}
+
+/// Parses the status of a SEPTA arrival, i.e. "On Time" or "5 min".
+///
+/// SEPTA sometimes leaves the status empty or null, those trains are shown
+/// with an unknown status rather than dropped.
+pub fn parse_status(status: &str) -> UpcomingTrainStatus {
+    let status = status.trim();
+
+    if status.eq_ignore_ascii_case("On Time") {
+        return UpcomingTrainStatus::OnTime;
+    }
+
+    if status.is_empty() || status == "N/A" {
+        return UpcomingTrainStatus::Unknown;
+    }
+
+    match status
+        .trim_end_matches("mins")
+        .trim_end_matches("min")
+        .trim()
+        .parse::<u32>()
+    {
+        Ok(0) => UpcomingTrainStatus::OnTime,
+        Ok(mins) => UpcomingTrainStatus::Late(mins),
+        Err(_) => {
+            warn_once(format!("Unknown SEPTA train status \"{status}\""));
+            UpcomingTrainStatus::Unknown
+        }
+    }
+}
+
+pub(crate) struct SeptaProvider {
     station: RegionalRailStop,
     client: Client,
 }
 
 impl SeptaProvider {
-    pub(super) fn new(station: RegionalRailStop) -> Self {
+    pub(crate) fn new(station: RegionalRailStop) -> Self {
         let client = Client::new();
 
         Self { station, client }
     }
 
-    pub(super) async fn arrivals(&self) -> Result<Vec<UpcomingTrain>, Box<dyn Error>> {
+    pub(crate) fn station(&self) -> &RegionalRailStop {
+        &self.station
+    }
+
+    pub(crate) async fn arrivals(&self) -> Result<Vec<UpcomingTrain>, Box<dyn Error>> {
         let Self {
             station, client, ..
         } = self;
@@ -37,32 +87,86 @@ impl SeptaProvider {
         arrivals.extend(response.southbound.into_iter());
         arrivals.sort_by(|a, b| a.sched_time.cmp(&b.sched_time));
 
-        arrivals.into_iter().map(|train| train.try_into()).collect()
+        // A malformed train is left out rather than failing the whole response
+        Ok(arrivals
+            .into_iter()
+            .filter_map(|train| {
+                let train_id = train.train_id.clone();
+
+                match UpcomingTrain::try_from(train) {
+                    Ok(mut arrival) => {
+                        arrival.station = Some(station.clone());
+                        Some(arrival)
+                    }
+                    Err(e) => {
+                        warn_once(format!("Skipping SEPTA train {train_id} {e}"));
+                        None
+                    }
+                }
+            })
+            .collect())
+    }
+}
+
+/// Rejects stations that did not match any known stop, suggesting the known
+/// stop with the closest name.
+pub(crate) fn validate_station(station: &RegionalRailStop) -> Result<()> {
+    let name = match station {
+        RegionalRailStop::Unknown(name) => name,
+        _ => return Ok(()),
+    };
+
+    let closest = known_stations()
+        .map(|stop| stop.to_string())
+        .min_by_key(|stop_name| edit_distance(&name.to_lowercase(), &stop_name.to_lowercase()));
+
+    match closest {
+        Some(closest) => Err(anyhow!(
+            "Unknown SEPTA station \"{name}\", did you mean \"{closest}\"?"
+        )),
+        None => Err(anyhow!("Unknown SEPTA station \"{name}\"")),
     }
 }
 
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous_row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current_row = vec![i + 1; b.len() + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution_cost = usize::from(a_char != *b_char);
+
+            current_row[j + 1] = (previous_row[j] + substitution_cost)
+                .min(previous_row[j + 1] + 1)
+                .min(current_row[j] + 1);
+        }
+
+        previous_row = current_row;
+    }
+
+    previous_row[b.len()]
+}
+
 impl TryFrom<Arrivals> for UpcomingTrain {
     type Error = Box<dyn Error>;
 
     fn try_from(value: Arrivals) -> Result<Self, Self::Error> {
+        let schedule_arrival = FixedOffset::east_opt(-4 * 3600)
+            .and_then(|offset| value.sched_time.and_local_timezone(offset).single())
+            .ok_or_else(|| anyhow!("has no valid scheduled time {}", value.sched_time))?;
+
         Ok(UpcomingTrain {
-            schedule_arrival: value
-                .sched_time
-                .and_local_timezone(FixedOffset::east_opt(-4 * 3600).unwrap())
-                .unwrap(),
+            schedule_arrival,
             destination_name: value.destination.to_string(),
             direction: super::UpcomingTrainDirection::Arrival,
             train_id: value.train_id,
-            status: if value.status == "On Time" {
-                UpcomingTrainStatus::OnTime
-            } else if value.status == "N/A" {
-                UpcomingTrainStatus::Unknown
-            } else if let Ok(mins) = value.status.trim_end_matches(" min").parse::<u32>() {
-                UpcomingTrainStatus::Late(mins)
-            } else {
-                warn!("Unknown SEPTA train status {}", value.status);
-                UpcomingTrainStatus::Unknown
-            },
+            status: parse_status(&value.status),
+            track: value.track.filter(|track| !track.is_empty()),
+            shared_with_amtrak: false,
+            station: None,
         })
     }
 }
@@ -1,8 +1,8 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use embedded_graphics::{
-    mono_font::{self, MonoTextStyle},
+    mono_font::{self, MonoFont, MonoTextStyle},
     pixelcolor::Rgb888,
-    prelude::{DrawTarget, Point, RgbColor, WebColors},
+    prelude::{DrawTarget, Point, RgbColor, Size, WebColors},
     text::Text,
     Drawable,
 };
@@ -11,24 +11,91 @@ use embedded_layout::{
     prelude::Chain,
     view_group::Views,
 };
-use log::error;
 use parking_lot::Mutex;
-use rustic_pixel_display::render::{Render, RenderFactory};
+use rustic_pixel_display::{
+    font::Font,
+    render::{
+        draw_outlined, BufferCanvas, Outline, OutlineShape, Render, RenderError, RenderFactory,
+        Spinner, SyncRender,
+    },
+};
 use serde::Deserialize;
 use std::{
-    convert::Infallible, io::Read, marker::PhantomData, net::IpAddr, sync::Arc, time::Duration,
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+use weer_api::{chrono::Utc, BaseApi, Client, Forecast};
+
+use crate::{
+    data_source::{DataSource, PollingSource},
+    line_colors::parse_hex_color,
+    loading::{draw_loading, loading_spinner},
+    request::{
+        refresh_interval, request_timeout, traced, validate_refresh_interval, with_timeout,
+        STALE_AFTER_REFRESHES,
+    },
+    spacing::SpacingConfig,
 };
-use tokio::{select, task::JoinHandle};
-use tokio_util::sync::CancellationToken;
-use weer_api::{chrono::Utc, BaseApi, Client};
 
+/// Where the forecast is retrieved for.
+///
+/// Deserialized from an object tagged with its `type`:
+///
+/// ```json
+/// { "type": "ip" }
+/// { "type": "ip", "value": "8.8.8.8" }
+/// { "type": "city", "value": "Philadelphia" }
+/// { "type": "coords", "lat": 39.9, "lon": -75.1 }
+/// ```
+///
+/// Defaults to looking up the location of the IP address the request is made
+/// from.
 #[derive(Clone, Debug, Deserialize)]
+#[serde(from = "LocationConfig")]
 pub enum Location {
     LatLon(f32, f32),
     City(String),
     Ip(Option<IpAddr>),
 }
 
+impl Default for Location {
+    fn default() -> Self {
+        Location::Ip(None)
+    }
+}
+
+/// The JSON representation of a [`Location`]
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum LocationConfig {
+    Ip {
+        #[serde(default)]
+        value: Option<IpAddr>,
+    },
+    City {
+        value: String,
+    },
+    Coords {
+        lat: f32,
+        lon: f32,
+    },
+}
+
+impl From<LocationConfig> for Location {
+    fn from(value: LocationConfig) -> Self {
+        match value {
+            LocationConfig::Ip { value } => Location::Ip(value),
+            LocationConfig::City { value } => Location::City(value),
+            LocationConfig::Coords { lat, lon } => Location::LatLon(lat, lon),
+        }
+    }
+}
+
 impl From<Location> for weer_api::Query {
     fn from(value: Location) -> Self {
         match value {
@@ -41,25 +108,36 @@ impl From<Location> for weer_api::Query {
 
 #[derive(Debug, Default)]
 struct DisplayForecast {
+    /// Set once the first forecast has been received
+    has_loaded: bool,
+    /// When the forecast was received
+    received_at: Option<Instant>,
     location_name: String,
     temperature: f32,
     temperature_str: String,
+    /// Rounded temperature for panels too narrow for [`Self::temperature_str`]
+    temperature_short: String,
     feels_like: f32,
     feels_like_str: String,
     wind: String,
     humidity: String,
+    condition: String,
 }
 
-impl From<weer_api::Forecast> for DisplayForecast {
-    fn from(value: weer_api::Forecast) -> Self {
+impl From<&Forecast> for DisplayForecast {
+    fn from(value: &Forecast) -> Self {
         Self {
+            has_loaded: true,
+            received_at: Some(Instant::now()),
             location_name: value.location.name.clone(),
             temperature: value.current.temp_f,
             temperature_str: format!("{} °F", value.current.temp_f),
+            temperature_short: format!("{:.0}°F", value.current.temp_f),
             feels_like: value.current.feelslike_f,
             feels_like_str: format!("{} °F", value.current.feelslike_f),
             wind: format!("{} mph", value.current.wind_mph),
             humidity: format!("{} %", value.current.humidity),
+            condition: value.current.condition.text.clone(),
         }
     }
 }
@@ -67,172 +145,608 @@ impl From<weer_api::Forecast> for DisplayForecast {
 #[derive(Debug, Clone, Deserialize)]
 pub struct Configuration {
     pub api_key: String,
+
+    /// Defaults to the location of the IP address the request is made from
+    #[serde(default)]
     pub location: Location,
+
+    /// Margins between the title, rows and columns, defaults to
+    /// [`DEFAULT_SPACING`] if not provided
+    pub spacing: Option<SpacingConfig>,
+
+    /// Font of the location, defaults to a size that fits the layout picked
+    /// for the canvas if not provided
+    pub title_font: Option<Font>,
+
+    /// Font of the rows, defaults to a size that fits the layout picked for
+    /// the canvas if not provided
+    pub body_font: Option<Font>,
+
+    /// Outline drawn around the text so it stays legible over a busy
+    /// background, no outline is drawn if not provided
+    pub outline: Option<OutlineConfig>,
+
+    /// Colors the temperatures are drawn in, defaults to
+    /// [`TemperatureColors::default`] if not provided
+    pub temp_colors: Option<TemperatureColorsConfig>,
+
+    /// How long, in seconds, a request may take before it is treated as failed.
+    /// Defaults to [`DEFAULT_REQUEST_TIMEOUT`](crate::request::DEFAULT_REQUEST_TIMEOUT) if
+    /// not provided
+    pub request_timeout_secs: Option<u64>,
+
+    /// How long, in seconds, to wait after a forecast is received before
+    /// requesting the next one. Defaults to [`DEFAULT_REFRESH`] if not provided
+    pub refresh_secs: Option<u64>,
+
+    /// How long, in seconds, to wait after a failed request before trying
+    /// again. Defaults to [`DEFAULT_ERROR_RETRY`] if not provided
+    pub error_retry_secs: Option<u64>,
 }
 
-pub struct Weather {
-    state: Arc<Mutex<DisplayForecast>>,
+impl Configuration {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(spacing) = &self.spacing {
+            spacing.validate()?;
+        }
 
-    /// Flag used to gracefully terminate the render and driver threads
-    cancel_token: CancellationToken,
+        if let Some(color) = self
+            .outline
+            .as_ref()
+            .and_then(|outline| outline.color.as_ref())
+        {
+            parse_hex_color(color)?;
+        }
 
-    /// Handle to the task used to update the SEPTA information
-    update_forecast_handle: Option<JoinHandle<Result<()>>>,
+        if let Some(temp_colors) = &self.temp_colors {
+            TemperatureColors::try_from(temp_colors)?;
+        }
+
+        validate_refresh_interval("refresh_secs", self.refresh_secs)?;
+        validate_refresh_interval("error_retry_secs", self.error_retry_secs)?;
+
+        Ok(())
+    }
 }
 
-impl Weather {
-    pub fn new(config: Configuration) -> Self {
-        let client = Client::new(&config.api_key, true);
+/// The outline drawn around the text, for example:
+///
+/// ```json
+/// { "color": "#000000", "shape": "square" }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct OutlineConfig {
+    /// Color of the outline as `#rrggbb`, defaults to black
+    pub color: Option<String>,
+
+    /// One of `cross`, `square` or `shadow`, defaults to `cross`
+    #[serde(default)]
+    pub shape: OutlineShape,
+}
+
+impl From<&OutlineConfig> for Outline {
+    fn from(config: &OutlineConfig) -> Self {
+        Self {
+            color: config
+                .color
+                .as_deref()
+                .and_then(|color| parse_hex_color(color).ok())
+                .unwrap_or(Rgb888::BLACK),
+            shape: config.shape,
+        }
+    }
+}
+
+/// How long to wait between forecasts if no refresh interval is configured
+const DEFAULT_REFRESH: Duration = Duration::from_secs(30 * 60);
+
+/// How long to wait after a failed request if no retry interval is configured
+const DEFAULT_ERROR_RETRY: Duration = Duration::from_secs(30);
+
+const CONDITION_COLOR: Rgb888 = Rgb888::new(160, 160, 160);
+
+/// Gap between the location and the current condition in the full layout
+const CONDITION_SPACING: i32 = 4;
+
+const DEFAULT_SPACING: SpacingConfig = SpacingConfig {
+    title_spacing: 2,
+    row_spacing: 2,
+    column_spacing: 0,
+};
 
-        let display_state = Arc::new(Mutex::new(DisplayForecast::default()));
-        let cancel_token = CancellationToken::new();
+/// How much of the forecast is drawn, picked from the size of the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WeatherLayout {
+    /// Location and rounded temperature, for panels narrower than 64 or shorter
+    /// than 48 pixels (e.g. 32x32)
+    Compact,
 
-        let task_cancel_token = cancel_token.clone();
-        let task_display_state = display_state.clone();
+    /// Every field with short labels in a small font (e.g. 64x64)
+    Standard,
+
+    /// Every field with full labels and the current condition, for panels at
+    /// least 128 pixels wide (e.g. 128x64)
+    Full,
+}
+
+impl WeatherLayout {
+    fn for_size(size: Size) -> Self {
+        if size.width < 64 || size.height < 48 {
+            WeatherLayout::Compact
+        } else if size.width < 128 {
+            WeatherLayout::Standard
+        } else {
+            WeatherLayout::Full
+        }
+    }
+}
 
-        let update_forecast_handle = tokio::task::spawn(async move {
-            loop {
-                let start_time = tokio::time::Instant::now();
-                let refresh_duration;
+/// How the colors of the temperatures between two breakpoints are picked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemperatureScaleMode {
+    /// A temperature takes the color of the first breakpoint it is at or below
+    #[default]
+    Stepped,
 
-                match client
+    /// A temperature blends the colors of the breakpoints on either side of it
+    Interpolated,
+}
+
+/// A temperature, in Fahrenheit, and the color it is drawn in
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemperatureBreakpointConfig {
+    pub temp: f32,
+
+    /// Written as `#rrggbb`
+    pub color: String,
+}
+
+/// Configuration of the [`TemperatureColors`], for example to shift what is
+/// considered warm for a hot climate:
+///
+/// ```json
+/// {
+///   "mode": "interpolated",
+///   "breakpoints": [
+///     { "temp": 60, "color": "#0000ff" },
+///     { "temp": 80, "color": "#00ff00" },
+///     { "temp": 100, "color": "#ff0000" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemperatureColorsConfig {
+    #[serde(default)]
+    pub mode: TemperatureScaleMode,
+
+    /// Sorted from the coldest to the warmest temperature
+    pub breakpoints: Vec<TemperatureBreakpointConfig>,
+}
+
+/// Maps a temperature, in Fahrenheit, to the color it is drawn in.
+///
+/// Temperatures below the first breakpoint take its color, temperatures above
+/// the last breakpoint the color of the last one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemperatureColors {
+    mode: TemperatureScaleMode,
+
+    /// Sorted by temperature, never empty
+    breakpoints: Vec<(f32, Rgb888)>,
+}
+
+impl TemperatureColors {
+    pub fn color_for(&self, temp: f32) -> Rgb888 {
+        if temp.is_nan() {
+            return Rgb888::WHITE;
+        }
+
+        let upper = self
+            .breakpoints
+            .iter()
+            .position(|&(breakpoint, _)| temp <= breakpoint);
+
+        match (self.mode, upper) {
+            (_, None) => self.breakpoints[self.breakpoints.len() - 1].1,
+            (TemperatureScaleMode::Stepped, Some(upper)) | (_, Some(upper @ 0)) => {
+                self.breakpoints[upper].1
+            }
+            (TemperatureScaleMode::Interpolated, Some(upper)) => {
+                let (low_temp, low_color) = self.breakpoints[upper - 1];
+                let (high_temp, high_color) = self.breakpoints[upper];
+                let fraction = (temp - low_temp) / (high_temp - low_temp);
+
+                let lerp = |low: u8, high: u8| {
+                    (f32::from(low) + (f32::from(high) - f32::from(low)) * fraction).round() as u8
+                };
+
+                Rgb888::new(
+                    lerp(low_color.r(), high_color.r()),
+                    lerp(low_color.g(), high_color.g()),
+                    lerp(low_color.b(), high_color.b()),
+                )
+            }
+        }
+    }
+}
+
+impl Default for TemperatureColors {
+    /// Green for comfortable temperatures, through yellow and red to purple
+    /// and magenta the further from comfortable it gets in either direction
+    fn default() -> Self {
+        Self {
+            mode: TemperatureScaleMode::Stepped,
+            breakpoints: vec![
+                (20.0, Rgb888::CSS_MAGENTA),
+                (30.0, Rgb888::CSS_PURPLE),
+                (40.0, Rgb888::RED),
+                (50.0, Rgb888::YELLOW),
+                (70.0, Rgb888::GREEN),
+                (80.0, Rgb888::YELLOW),
+                (90.0, Rgb888::RED),
+                (100.0, Rgb888::CSS_PURPLE),
+                (f32::INFINITY, Rgb888::CSS_MAGENTA),
+            ],
+        }
+    }
+}
+
+impl TryFrom<&TemperatureColorsConfig> for TemperatureColors {
+    type Error = anyhow::Error;
+
+    fn try_from(config: &TemperatureColorsConfig) -> Result<Self> {
+        if config.breakpoints.is_empty() {
+            return Err(anyhow!("temp_colors needs at least one breakpoint"));
+        }
+
+        let breakpoints = config
+            .breakpoints
+            .iter()
+            .map(|breakpoint| {
+                if !breakpoint.temp.is_finite() {
+                    return Err(anyhow!(
+                        "Invalid breakpoint temperature {}",
+                        breakpoint.temp
+                    ));
+                }
+
+                Ok((breakpoint.temp, parse_hex_color(&breakpoint.color)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if let Some(unsorted) = breakpoints.windows(2).find(|pair| pair[0].0 >= pair[1].0) {
+            return Err(anyhow!(
+                "temp_colors breakpoints must be sorted from coldest to warmest, {} is not \
+                 below {}",
+                unsorted[0].0,
+                unsorted[1].0
+            ));
+        }
+
+        Ok(Self {
+            mode: config.mode,
+            breakpoints,
+        })
+    }
+}
+
+pub struct Weather {
+    state: Mutex<DisplayForecast>,
+
+    /// Where the forecast comes from
+    source: Box<dyn DataSource<Forecast>>,
+
+    /// Watches the source for new forecasts
+    forecast: Mutex<watch::Receiver<Option<Forecast>>>,
+
+    /// Drawn until the first data arrives
+    loading_spinner: Spinner,
+
+    /// Margins between the title, rows and columns
+    spacing: SpacingConfig,
+
+    /// Overrides the font of the location picked by the layout
+    title_font: Option<&'static MonoFont<'static>>,
+
+    /// Overrides the font of the rows picked by the layout
+    body_font: Option<&'static MonoFont<'static>>,
+
+    /// Drawn around the text if configured
+    outline: Option<Outline>,
+
+    /// Colors the temperatures are drawn in
+    temp_colors: TemperatureColors,
+
+    /// How old the forecast can get before the render reports it has no
+    /// content
+    stale_after: Duration,
+}
+
+impl Weather {
+    /// Polls the forecast of the configured location
+    pub fn new(config: Configuration) -> Self {
+        let client = Arc::new(Client::new(&config.api_key, true));
+        let timeout = request_timeout(config.request_timeout_secs);
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+        let error_retry = refresh_interval(config.error_retry_secs, DEFAULT_ERROR_RETRY);
+        let location = config.location.clone();
+
+        let source = PollingSource::new("forecast", refresh, error_retry, move || {
+            // The client blocks, so the request runs on the blocking pool. When the
+            // render is dropped the source stops waiting on it straight away.
+            let forecast_client = client.clone();
+            let location = location.clone();
+            let forecast_request = tokio::task::spawn_blocking(move || {
+                forecast_client
                     .forecast()
-                    .query(config.location.clone().into())
+                    .query(location.into())
                     .dt(Utc::now())
                     .call()
-                {
-                    Ok(result) => {
-                        *task_display_state.lock() = result.into();
-                        refresh_duration = Duration::from_secs(30 * 60);
-                    }
-                    Err(e) => {
-                        error!("Could not get updated information {e}");
-                        refresh_duration = Duration::from_secs(30);
-                    }
-                }
+            });
 
-                select! {
-                    _ = tokio::time::sleep_until(start_time + refresh_duration) => {},
-                    _ = task_cancel_token.cancelled() => break,
-                }
+            async move {
+                traced(
+                    "Weather",
+                    "weatherapi/forecast",
+                    with_timeout(timeout, forecast_request),
+                )
+                .await?
+                .map_err(|e| anyhow!("{e}"))
             }
-
-            Ok(())
         });
 
+        Self::with_source(config, Box::new(source))
+    }
+
+    /// Draws the forecasts of `source`. The location and request settings of
+    /// `config` are ignored, `refresh_secs` only sets how long a forecast is
+    /// shown before it is stale
+    pub fn with_source(config: Configuration, source: Box<dyn DataSource<Forecast>>) -> Self {
+        let refresh = refresh_interval(config.refresh_secs, DEFAULT_REFRESH);
+
         Self {
-            state: display_state,
-            cancel_token,
-            update_forecast_handle: Some(update_forecast_handle),
+            state: Mutex::new(DisplayForecast::default()),
+            forecast: Mutex::new(source.subscribe()),
+            source,
+            loading_spinner: loading_spinner(),
+            spacing: config.spacing.unwrap_or(DEFAULT_SPACING),
+            title_font: config.title_font.map(|font| font.mono_font()),
+            body_font: config.body_font.map(|font| font.mono_font()),
+            outline: config.outline.as_ref().map(Outline::from),
+            temp_colors: config
+                .temp_colors
+                .as_ref()
+                .and_then(|temp_colors| TemperatureColors::try_from(temp_colors).ok())
+                .unwrap_or_default(),
+            stale_after: refresh * STALE_AFTER_REFRESHES,
         }
     }
-}
 
-impl<D> Render<D> for Weather
-where
-    D: DrawTarget<Color = Rgb888, Error = Infallible>,
-{
-    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
-        let display_state = self.state.lock();
+    /// Takes the latest forecast of the source, if it changed since it was
+    /// last taken
+    fn update_forecast(&self) {
+        let mut forecast = self.forecast.lock();
 
-        let color_from_temp = |temp: f32| -> Rgb888 {
-            if temp > 50.0 && temp <= 70.0 {
-                Rgb888::GREEN
-            } else if temp > 70.0 && temp <= 80.0 {
-                Rgb888::YELLOW
-            } else if temp > 80.0 && temp <= 90.0 {
-                Rgb888::RED
-            } else if temp > 90.0 && temp <= 100.0 {
-                Rgb888::CSS_PURPLE
-            } else if temp > 100.0 {
-                Rgb888::CSS_MAGENTA
-            } else if temp > 40.0 && temp <= 50.0 {
-                Rgb888::YELLOW
-            } else if temp > 30.0 && temp <= 40.0 {
-                Rgb888::RED
-            } else if temp > 20.0 && temp <= 30.0 {
-                Rgb888::CSS_PURPLE
-            } else if temp <= 20.0 {
-                Rgb888::CSS_MAGENTA
-            } else {
-                Rgb888::WHITE
+        if forecast.has_changed().unwrap_or(false) {
+            if let Some(forecast) = &*forecast.borrow_and_update() {
+                *self.state.lock() = forecast.into();
             }
+        }
+    }
+
+    fn draw_layout<D>(
+        &self,
+        display_state: &DisplayForecast,
+        canvas: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    {
+        match WeatherLayout::for_size(canvas.bounding_box().size) {
+            WeatherLayout::Compact => self.draw_compact(display_state, canvas),
+            WeatherLayout::Standard => self.draw_standard(display_state, canvas),
+            WeatherLayout::Full => self.draw_full(display_state, canvas),
+        }
+    }
+
+    fn draw_compact<D>(
+        &self,
+        display_state: &DisplayForecast,
+        canvas: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    {
+        let title_font = self.title_font.unwrap_or(&mono_font::iso_8859_1::FONT_5X7);
+        let body_font = self.body_font.unwrap_or(&mono_font::iso_8859_1::FONT_6X10);
+
+        LinearLayout::vertical(
+            Chain::new(Text::new(
+                &display_state.location_name,
+                Point::zero(),
+                MonoTextStyle::new(title_font, Rgb888::WHITE),
+            ))
+            .append(Text::new(
+                &display_state.temperature_short,
+                Point::zero(),
+                MonoTextStyle::new(
+                    body_font,
+                    self.temp_colors.color_for(display_state.temperature),
+                ),
+            )),
+        )
+        .with_spacing(spacing::FixedMargin(self.spacing.title_spacing as i32))
+        .arrange()
+        .draw(canvas)?;
+
+        Ok(())
+    }
+
+    fn draw_standard<D>(
+        &self,
+        display_state: &DisplayForecast,
+        canvas: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    {
+        let title_font = self.title_font.unwrap_or(&mono_font::iso_8859_1::FONT_6X10);
+        let body_font = self.body_font.unwrap_or(&mono_font::iso_8859_1::FONT_5X7);
+        let label_style = MonoTextStyle::new(body_font, Rgb888::WHITE);
+        let column_spacing = self.spacing.column_spacing as i32;
+
+        let row = |label, value, color| {
+            LinearLayout::horizontal(
+                Chain::new(Text::new(label, Point::zero(), label_style)).append(Text::new(
+                    value,
+                    Point::zero(),
+                    MonoTextStyle::new(body_font, color),
+                )),
+            )
+            .with_spacing(spacing::FixedMargin(column_spacing))
+            .arrange()
         };
 
         LinearLayout::vertical(
             Chain::new(Text::new(
                 &display_state.location_name,
                 Point::zero(),
-                MonoTextStyle::new(&mono_font::iso_8859_1::FONT_7X13, Rgb888::WHITE),
+                MonoTextStyle::new(title_font, Rgb888::WHITE),
             ))
             .append(
-                LinearLayout::horizontal(Views::new(&mut [
-                    Text::new(
-                        "Temperature: ",
-                        Point::zero(),
-                        MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X9, Rgb888::WHITE),
-                    ),
-                    Text::new(
+                LinearLayout::vertical(
+                    Chain::new(row(
+                        "Temp ",
                         &display_state.temperature_str,
-                        Point::zero(),
-                        MonoTextStyle::new(
-                            &mono_font::iso_8859_1::FONT_6X9,
-                            color_from_temp(display_state.temperature),
-                        ),
-                    ),
-                ]))
-                .arrange(),
-            )
-            .append(
-                LinearLayout::horizontal(Views::new(&mut [
-                    Text::new(
-                        "Feels like: ",
-                        Point::zero(),
-                        MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X9, Rgb888::WHITE),
-                    ),
-                    Text::new(
+                        self.temp_colors.color_for(display_state.temperature),
+                    ))
+                    .append(row(
+                        "Feel ",
                         &display_state.feels_like_str,
-                        Point::zero(),
-                        MonoTextStyle::new(
-                            &mono_font::iso_8859_1::FONT_6X9,
-                            color_from_temp(display_state.feels_like),
-                        ),
-                    ),
-                ]))
+                        self.temp_colors.color_for(display_state.feels_like),
+                    ))
+                    .append(row("Wind ", &display_state.wind, Rgb888::WHITE))
+                    .append(row(
+                        "Hum  ",
+                        &display_state.humidity,
+                        Rgb888::WHITE,
+                    )),
+                )
+                .with_spacing(spacing::FixedMargin(self.spacing.row_spacing as i32))
                 .arrange(),
-            )
-            .append(
+            ),
+        )
+        .with_spacing(spacing::FixedMargin(self.spacing.title_spacing as i32))
+        .arrange()
+        .draw(canvas)?;
+
+        Ok(())
+    }
+
+    fn draw_full<D>(&self, display_state: &DisplayForecast, canvas: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Rgb888, Error = Infallible>,
+    {
+        let title_font = self.title_font.unwrap_or(&mono_font::iso_8859_1::FONT_7X13);
+        let body_font = self.body_font.unwrap_or(&mono_font::iso_8859_1::FONT_6X9);
+        let column_spacing = self.spacing.column_spacing as i32;
+
+        LinearLayout::vertical(
+            Chain::new(
                 LinearLayout::horizontal(Views::new(&mut [
                     Text::new(
-                        "Wind: ",
+                        &display_state.location_name,
                         Point::zero(),
-                        MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X9, Rgb888::WHITE),
+                        MonoTextStyle::new(title_font, Rgb888::WHITE),
                     ),
                     Text::new(
-                        &display_state.wind,
+                        &display_state.condition,
                         Point::zero(),
-                        MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X9, Rgb888::WHITE),
+                        MonoTextStyle::new(body_font, CONDITION_COLOR),
                     ),
                 ]))
+                .with_spacing(spacing::FixedMargin(CONDITION_SPACING))
                 .arrange(),
             )
             .append(
-                LinearLayout::horizontal(Views::new(&mut [
-                    Text::new(
-                        "Humidity: ",
-                        Point::zero(),
-                        MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X9, Rgb888::WHITE),
-                    ),
-                    Text::new(
-                        &display_state.humidity,
-                        Point::zero(),
-                        MonoTextStyle::new(&mono_font::iso_8859_1::FONT_6X9, Rgb888::WHITE),
+                LinearLayout::vertical(
+                    Chain::new(
+                        LinearLayout::horizontal(Views::new(&mut [
+                            Text::new(
+                                "Temperature: ",
+                                Point::zero(),
+                                MonoTextStyle::new(body_font, Rgb888::WHITE),
+                            ),
+                            Text::new(
+                                &display_state.temperature_str,
+                                Point::zero(),
+                                MonoTextStyle::new(
+                                    body_font,
+                                    self.temp_colors.color_for(display_state.temperature),
+                                ),
+                            ),
+                        ]))
+                        .with_spacing(spacing::FixedMargin(column_spacing))
+                        .arrange(),
+                    )
+                    .append(
+                        LinearLayout::horizontal(Views::new(&mut [
+                            Text::new(
+                                "Feels like: ",
+                                Point::zero(),
+                                MonoTextStyle::new(body_font, Rgb888::WHITE),
+                            ),
+                            Text::new(
+                                &display_state.feels_like_str,
+                                Point::zero(),
+                                MonoTextStyle::new(
+                                    body_font,
+                                    self.temp_colors.color_for(display_state.feels_like),
+                                ),
+                            ),
+                        ]))
+                        .with_spacing(spacing::FixedMargin(column_spacing))
+                        .arrange(),
+                    )
+                    .append(
+                        LinearLayout::horizontal(Views::new(&mut [
+                            Text::new(
+                                "Wind: ",
+                                Point::zero(),
+                                MonoTextStyle::new(body_font, Rgb888::WHITE),
+                            ),
+                            Text::new(
+                                &display_state.wind,
+                                Point::zero(),
+                                MonoTextStyle::new(body_font, Rgb888::WHITE),
+                            ),
+                        ]))
+                        .with_spacing(spacing::FixedMargin(column_spacing))
+                        .arrange(),
+                    )
+                    .append(
+                        LinearLayout::horizontal(Views::new(&mut [
+                            Text::new(
+                                "Humidity: ",
+                                Point::zero(),
+                                MonoTextStyle::new(body_font, Rgb888::WHITE),
+                            ),
+                            Text::new(
+                                &display_state.humidity,
+                                Point::zero(),
+                                MonoTextStyle::new(body_font, Rgb888::WHITE),
+                            ),
+                        ]))
+                        .with_spacing(spacing::FixedMargin(column_spacing))
+                        .arrange(),
                     ),
-                ]))
+                )
+                .with_spacing(spacing::FixedMargin(self.spacing.row_spacing as i32))
                 .arrange(),
             ),
         )
-        .with_spacing(spacing::FixedMargin(2))
+        .with_spacing(spacing::FixedMargin(self.spacing.title_spacing as i32))
         .arrange()
         .draw(canvas)?;
 
@@ -240,12 +754,50 @@ where
     }
 }
 
-impl Drop for Weather {
-    fn drop(&mut self) {
-        self.cancel_token.cancel();
+impl<D> Render<D> for Weather
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        self.update_forecast();
+        let display_state = self.state.lock();
+
+        if !display_state.has_loaded {
+            return draw_loading(canvas, &self.loading_spinner);
+        }
+
+        match self.outline {
+            Some(outline) => draw_outlined(canvas, outline, |buffer| {
+                self.draw_layout(&display_state, buffer)
+            }),
+            None => self.draw_layout(&display_state, canvas),
+        }
+    }
+
+    fn on_select(&self) {
+        self.source.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.source.pause();
+    }
+
+    fn describe(&self) -> String {
+        self.update_forecast();
+        let state = self.state.lock();
+
+        if state.has_loaded {
+            format!("Weather: {} {}", state.location_name, state.temperature_str)
+        } else {
+            "Weather: loading".to_owned()
+        }
+    }
 
-        if let Some(task_handle) = self.update_forecast_handle.take() {
-            task_handle.abort();
+    fn has_content(&self) -> bool {
+        self.update_forecast();
+        match self.state.lock().received_at {
+            Some(received_at) => received_at.elapsed() < self.stale_after,
+            None => false,
         }
     }
 }
@@ -280,8 +832,24 @@ where
         "Display weather information about a location"
     }
 
-    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn Render<D>>> {
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
         let config: Configuration = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+
+        Ok(Box::new(Weather::new(config)))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: Configuration = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+
         Ok(Box::new(Weather::new(config)))
     }
 }
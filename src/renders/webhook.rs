@@ -0,0 +1,302 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use embedded_graphics::{
+    mono_font::{self, MonoFont, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor},
+    text::{Baseline, Text},
+    Drawable,
+};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    font::{latin1_lossy, Font},
+    push_routes::{register_push_route, PushRoute},
+    render::{BufferCanvas, ExampleConfig, Render, RenderError, RenderFactory, SyncRender},
+};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::{
+    convert::Infallible,
+    io::Read,
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::format::{format_time, TimeFormatConfig};
+
+const DEFAULT_FONT: &MonoFont = &mono_font::iso_8859_1::FONT_5X7;
+
+/// How long a push is shown as current if no stale interval is configured
+const DEFAULT_STALE: Duration = Duration::from_secs(15 * 60);
+
+/// Color of the time of the push, and of the values once they went stale
+const DIM_COLOR: Rgb888 = Rgb888::new(128, 128, 128);
+
+/// Configuration for the [`WebhookFactory`], for example:
+///
+/// ```json
+/// {
+///   "id": "garage",
+///   "title": "Garage",
+///   "stale_secs": 600
+/// }
+/// ```
+///
+/// The render then shows the JSON object last posted to `/push/garage` on the
+/// HTTP API, i.e. `{ "Door": "Closed", "Temp": 54 }`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookConfig {
+    /// The `{id}` of `/push/{id}` the render is pushed to, unique among the
+    /// loaded renders
+    pub id: String,
+
+    /// Drawn above the values if provided
+    pub title: Option<String>,
+
+    /// How long, in seconds, after the last push the values are marked as
+    /// stale. Defaults to [`DEFAULT_STALE`] if not provided
+    pub stale_secs: Option<u64>,
+
+    /// Font of the values, defaults to 5x7 if not provided
+    pub font: Option<Font>,
+
+    /// How the time of the last push is displayed
+    #[serde(default)]
+    pub time_format: TimeFormatConfig,
+}
+
+impl WebhookConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.id.is_empty() || self.id.contains('/') {
+            return Err(anyhow!(
+                "id must not be empty or contain a '/', got \"{}\"",
+                self.id
+            ));
+        }
+
+        if self.stale_secs == Some(0) {
+            return Err(anyhow!("stale_secs must be at least 1"));
+        }
+
+        Ok(())
+    }
+}
+
+/// The label and value of every field of a pushed JSON object, strings without
+/// their quotes
+fn parse_push(body: &[u8]) -> Result<Vec<(String, String)>, String> {
+    let fields: Map<String, Value> =
+        serde_json::from_slice(body).map_err(|e| format!("The push must be a JSON object: {e}"))?;
+
+    Ok(fields
+        .into_iter()
+        .map(|(label, value)| {
+            let value = match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+
+            (label, value)
+        })
+        .collect())
+}
+
+#[derive(Debug, Default)]
+struct WebhookState {
+    fields: Vec<(String, String)>,
+
+    /// When the last push arrived, for the stale check and to display
+    pushed_at: Option<(Instant, DateTime<Local>)>,
+}
+
+/// Shows the labeled values last pushed to it through the HTTP API, the
+/// opposite of the renders that poll an API themselves.
+///
+/// Every field of the pushed JSON object is drawn as a row of its label and
+/// value, with the time of the push below. Once no push arrived for
+/// `stale_secs` everything is grayed out and the time is marked with a `*`.
+pub struct Webhook {
+    state: Arc<Mutex<WebhookState>>,
+    id: String,
+    title: Option<String>,
+    stale_after: Duration,
+    font: &'static MonoFont<'static>,
+    time_format: TimeFormatConfig,
+
+    /// Keeps the render reachable through `/push/{id}`, `None` for previews
+    _route: Option<PushRoute>,
+}
+
+impl Webhook {
+    /// Creates the render and registers its push route, failing if another
+    /// render already uses the id.
+    pub fn new(config: WebhookConfig) -> Result<Self> {
+        let mut webhook = Self::preview(config);
+
+        let state = webhook.state.clone();
+        let route = register_push_route(
+            &webhook.id,
+            Arc::new(move |body: &[u8]| -> Result<(), String> {
+                let fields = parse_push(body)?;
+
+                let mut state = state.lock();
+                state.fields = fields;
+                state.pushed_at = Some((Instant::now(), Local::now()));
+
+                Ok(())
+            }),
+        )?;
+        webhook._route = Some(route);
+
+        Ok(webhook)
+    }
+
+    /// Creates the render without registering its push route, so it never
+    /// receives a push
+    pub fn preview(config: WebhookConfig) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(WebhookState::default())),
+            id: config.id,
+            title: config.title,
+            stale_after: config
+                .stale_secs
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_STALE),
+            font: config
+                .font
+                .map(|font| font.mono_font())
+                .unwrap_or(DEFAULT_FONT),
+            time_format: config.time_format,
+            _route: None,
+        }
+    }
+}
+
+impl<D> Render<D> for Webhook
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let state = self.state.lock();
+        let row_height = self.font.character_size.height as i32 + 1;
+        let mut top_left = canvas.bounding_box().top_left;
+
+        let mut draw_row = |text: &str, color: Rgb888, canvas: &mut D| -> Result<(), D::Error> {
+            Text::with_baseline(
+                &latin1_lossy(text),
+                top_left,
+                MonoTextStyle::new(self.font, color),
+                Baseline::Top,
+            )
+            .draw(canvas)?;
+            top_left += Point::new(0, row_height);
+
+            Ok(())
+        };
+
+        if let Some(title) = &self.title {
+            draw_row(title, Rgb888::WHITE, canvas)?;
+        }
+
+        let (pushed_instant, pushed_time) = match state.pushed_at {
+            Some(pushed_at) => pushed_at,
+            None => return draw_row(&format!("Waiting for {}", self.id), DIM_COLOR, canvas),
+        };
+        let is_stale = pushed_instant.elapsed() >= self.stale_after;
+        let value_color = if is_stale { DIM_COLOR } else { Rgb888::WHITE };
+
+        for (label, value) in &state.fields {
+            draw_row(&format!("{label}: {value}"), value_color, canvas)?;
+        }
+
+        let time = format_time(&pushed_time, &self.time_format);
+        if is_stale {
+            draw_row(&format!("{time}*"), DIM_COLOR, canvas)
+        } else {
+            draw_row(&time, DIM_COLOR, canvas)
+        }
+    }
+
+    fn describe(&self) -> String {
+        let state = self.state.lock();
+
+        match state.pushed_at {
+            Some((pushed_instant, _)) => format!(
+                "Webhook {}: {} values pushed {}s ago",
+                self.id,
+                state.fields.len(),
+                pushed_instant.elapsed().as_secs()
+            ),
+            None => format!("Webhook {}: waiting for a push", self.id),
+        }
+    }
+
+    fn has_content(&self) -> bool {
+        match self.state.lock().pushed_at {
+            Some((pushed_instant, _)) => pushed_instant.elapsed() < self.stale_after,
+            None => false,
+        }
+    }
+}
+
+pub struct WebhookFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for WebhookFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for WebhookFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "Webhook"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Labeled values pushed by other systems to /push/{id}"
+    }
+
+    fn example_config(&self) -> Option<ExampleConfig> {
+        Some(json!({
+            "id": "garage",
+            "title": "Garage",
+            "stale_secs": 600
+        }))
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: WebhookConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+        let webhook = Webhook::new(config).map_err(|e| RenderError::Config(e.to_string()))?;
+        Ok(Box::new(webhook))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: WebhookConfig = serde_json::from_reader(reader)?;
+        config
+            .validate()
+            .map_err(|e| RenderError::Config(e.to_string()))?;
+
+        // A preview must not take over the route of a loaded render
+        Ok(Box::new(Webhook::preview(config)))
+    }
+}
@@ -0,0 +1,358 @@
+use anyhow::{anyhow, Result};
+use embedded_graphics::{
+    mono_font::{self, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, Primitive, RgbColor, Size},
+    primitives::{Line, PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+    Drawable,
+};
+use log::{error, warn};
+use parking_lot::Mutex;
+use rustic_pixel_display::{
+    font::Font,
+    render::{BufferCanvas, PollGate, Render, RenderError, RenderFactory, SyncRender},
+};
+use serde::Deserialize;
+use std::{convert::Infallible, io::Read, marker::PhantomData, sync::Arc, time::Duration};
+use tokio::{select, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+const WIRELESS_PATH: &str = "/proc/net/wireless";
+
+/// How often the connection quality is refreshed
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The maximum link quality reported by most drivers in `/proc/net/wireless`
+const MAX_LINK_QUALITY: f32 = 70.0;
+
+const DEFAULT_FONT: &mono_font::MonoFont = &mono_font::ascii::FONT_5X7;
+
+const SIGNAL_BARS: u32 = 4;
+const BAR_WIDTH: u32 = 2;
+const BAR_GAP: u32 = 1;
+const BAR_HEIGHT_STEP: u32 = 2;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WifiConfig {
+    /// The wireless interface to monitor, i.e. "wlan0"
+    pub interface: String,
+
+    /// Display the SSID of the connected network next to the signal icon
+    #[serde(default)]
+    pub show_ssid: bool,
+
+    /// Defaults to 5x7 if not provided
+    pub font: Option<Font>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum WifiState {
+    /// The interface is not a wireless interface, i.e. connected over ethernet
+    #[default]
+    NoWifi,
+
+    /// The wireless interface is not associated with a network
+    Disconnected,
+
+    /// Connected with a signal of 0 to [`SIGNAL_BARS`] bars
+    Connected { bars: u32, ssid: Option<String> },
+}
+
+/// Parses the link quality of `interface` out of the contents of
+/// `/proc/net/wireless`. Returns `None` if the interface is not listed.
+fn parse_link_quality(wireless: &str, interface: &str) -> Option<f32> {
+    wireless.lines().skip(2).find_map(|line| {
+        let (name, stats) = line.split_once(':')?;
+
+        if name.trim() != interface {
+            return None;
+        }
+
+        // The columns after the interface name are status, link, level and noise
+        stats
+            .split_whitespace()
+            .nth(1)?
+            .trim_end_matches('.')
+            .parse()
+            .ok()
+    })
+}
+
+async fn read_ssid(interface: &str) -> Option<String> {
+    let output = tokio::process::Command::new("iwgetid")
+        .arg(interface)
+        .arg("--raw")
+        .output()
+        .await
+        .map_err(|e| warn!("Could not run iwgetid {e}"))
+        .ok()?;
+
+    let ssid = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+
+    if ssid.is_empty() {
+        None
+    } else {
+        Some(ssid)
+    }
+}
+
+async fn read_wifi_state(interface: &str, show_ssid: bool) -> Result<WifiState> {
+    let wireless = tokio::fs::read_to_string(WIRELESS_PATH)
+        .await
+        .map_err(|e| anyhow!("Could not read {WIRELESS_PATH} {e}"))?;
+
+    let link_quality = match parse_link_quality(&wireless, interface) {
+        Some(link_quality) => link_quality,
+        None => return Ok(WifiState::NoWifi),
+    };
+
+    let operstate = tokio::fs::read_to_string(format!("/sys/class/net/{interface}/operstate"))
+        .await
+        .unwrap_or_default();
+
+    if operstate.trim() != "up" || link_quality <= 0.0 {
+        return Ok(WifiState::Disconnected);
+    }
+
+    let bars = ((link_quality / MAX_LINK_QUALITY) * SIGNAL_BARS as f32).ceil() as u32;
+    let ssid = if show_ssid {
+        read_ssid(interface).await
+    } else {
+        None
+    };
+
+    Ok(WifiState::Connected {
+        bars: bars.min(SIGNAL_BARS),
+        ssid,
+    })
+}
+
+/// Draws the signal icon with the first `bars` bars filled in with `color`.
+fn draw_signal_bars<D>(canvas: &mut D, bars: u32, color: Rgb888) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    let icon_height = SIGNAL_BARS * BAR_HEIGHT_STEP;
+
+    for bar in 0..SIGNAL_BARS {
+        let bar_height = (bar + 1) * BAR_HEIGHT_STEP;
+        let style = if bar < bars {
+            PrimitiveStyle::with_fill(color)
+        } else {
+            PrimitiveStyle::with_stroke(Rgb888::new(64, 64, 64), 1)
+        };
+
+        Rectangle::new(
+            Point::new(
+                (bar * (BAR_WIDTH + BAR_GAP)) as i32,
+                (icon_height - bar_height) as i32,
+            ),
+            Size::new(BAR_WIDTH, bar_height),
+        )
+        .into_styled(style)
+        .draw(canvas)?;
+    }
+
+    Ok(())
+}
+
+pub struct Wifi {
+    state: Arc<Mutex<WifiState>>,
+
+    font: &'static mono_font::MonoFont<'static>,
+
+    /// Used to pause the update task while the render is not displayed
+    poll_gate: PollGate,
+
+    /// Flag used to gracefully terminate the render and driver threads
+    cancel_token: CancellationToken,
+
+    /// Handle to the task used to update the connection state
+    update_state_handle: Option<JoinHandle<Result<()>>>,
+}
+
+impl Wifi {
+    pub fn new(config: WifiConfig) -> Self {
+        let font = config
+            .font
+            .map(|font| font.mono_font())
+            .unwrap_or(DEFAULT_FONT);
+        let state = Arc::new(Mutex::new(WifiState::default()));
+        let poll_gate = PollGate::new();
+        let cancel_token = CancellationToken::new();
+
+        let task_cancel_token = cancel_token.clone();
+        let task_state = state.clone();
+        let task_poll_gate = poll_gate.clone();
+
+        let update_state_handle = tokio::task::spawn(async move {
+            loop {
+                select! {
+                    _ = task_poll_gate.active() => {},
+                    _ = task_cancel_token.cancelled() => break,
+                }
+
+                let refresh_time = tokio::time::Instant::now() + REFRESH_INTERVAL;
+
+                match read_wifi_state(&config.interface, config.show_ssid).await {
+                    Ok(wifi_state) => *task_state.lock() = wifi_state,
+                    Err(e) => error!("Could not get updated WiFi state {e}"),
+                }
+
+                select! {
+                    _ = tokio::time::sleep_until(refresh_time) => {},
+                    _ = task_poll_gate.paused() => {},
+                    _ = task_cancel_token.cancelled() => break,
+                }
+            }
+
+            Ok(())
+        });
+
+        Self {
+            state,
+            font,
+            poll_gate,
+            cancel_token,
+            update_state_handle: Some(update_state_handle),
+        }
+    }
+}
+
+impl<D> Render<D> for Wifi
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        let state = self.state.lock();
+        let text_position = Point::new(
+            (SIGNAL_BARS * (BAR_WIDTH + BAR_GAP) + 2) as i32,
+            (SIGNAL_BARS * BAR_HEIGHT_STEP) as i32,
+        );
+
+        match &*state {
+            WifiState::NoWifi => {
+                Text::with_baseline(
+                    "No WiFi",
+                    Point::zero(),
+                    MonoTextStyle::new(self.font, Rgb888::new(128, 128, 128)),
+                    Baseline::Top,
+                )
+                .draw(canvas)?;
+            }
+            WifiState::Disconnected => {
+                draw_signal_bars(canvas, 0, Rgb888::RED)?;
+
+                // Cross out the empty bars
+                let icon_corner = Point::new(
+                    (SIGNAL_BARS * (BAR_WIDTH + BAR_GAP) - BAR_GAP - 1) as i32,
+                    (SIGNAL_BARS * BAR_HEIGHT_STEP - 1) as i32,
+                );
+                let style = PrimitiveStyle::with_stroke(Rgb888::RED, 1);
+
+                Line::new(Point::zero(), icon_corner)
+                    .into_styled(style)
+                    .draw(canvas)?;
+                Line::new(Point::new(0, icon_corner.y), Point::new(icon_corner.x, 0))
+                    .into_styled(style)
+                    .draw(canvas)?;
+            }
+            WifiState::Connected { bars, ssid } => {
+                let color = match bars {
+                    0 | 1 => Rgb888::RED,
+                    2 => Rgb888::YELLOW,
+                    _ => Rgb888::GREEN,
+                };
+
+                draw_signal_bars(canvas, *bars, color)?;
+
+                if let Some(ssid) = ssid {
+                    Text::with_baseline(
+                        ssid,
+                        text_position,
+                        MonoTextStyle::new(self.font, Rgb888::WHITE),
+                        Baseline::Bottom,
+                    )
+                    .draw(canvas)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_select(&self) {
+        self.poll_gate.resume();
+    }
+
+    fn on_deselect(&self) {
+        self.poll_gate.pause();
+    }
+
+    fn describe(&self) -> String {
+        match &*self.state.lock() {
+            WifiState::NoWifi => "Wifi: not a wireless interface".to_owned(),
+            WifiState::Disconnected => "Wifi: disconnected".to_owned(),
+            WifiState::Connected {
+                bars,
+                ssid: Some(ssid),
+            } => format!("Wifi: {ssid}, {bars} bars"),
+            WifiState::Connected { bars, ssid: None } => format!("Wifi: {bars} bars"),
+        }
+    }
+}
+
+impl Drop for Wifi {
+    fn drop(&mut self) {
+        self.cancel_token.cancel();
+
+        if let Some(task_handle) = self.update_state_handle.take() {
+            task_handle.abort();
+        }
+    }
+}
+
+pub struct WifiFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    _phantom: PhantomData<D>,
+}
+
+impl<D> Default for WifiFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<D> RenderFactory<D> for WifiFactory<D>
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render_name(&self) -> &'static str {
+        "Wifi"
+    }
+
+    fn render_description(&self) -> &'static str {
+        "Displays the signal strength of a wireless interface"
+    }
+
+    fn load_from_config<R: Read>(&self, reader: R) -> Result<Box<dyn SyncRender<D>>, RenderError> {
+        let config: WifiConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(Wifi::new(config)))
+    }
+
+    fn load_preview<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Box<dyn SyncRender<BufferCanvas>>, RenderError> {
+        let config: WifiConfig = serde_json::from_reader(reader)?;
+        Ok(Box::new(Wifi::new(config)))
+    }
+}
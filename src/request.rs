@@ -0,0 +1,78 @@
+use anyhow::{anyhow, Result};
+use std::{fmt::Display, future::Future, time::Duration};
+
+/// How long a request may take if no timeout is configured
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Converts the `request_timeout_secs` of a configuration into a timeout,
+/// falling back to [`DEFAULT_REQUEST_TIMEOUT`].
+pub fn request_timeout(request_timeout_secs: Option<u64>) -> Duration {
+    request_timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_REQUEST_TIMEOUT)
+}
+
+/// The shortest interval an update loop can be configured to refresh at, so a
+/// typo can't burn through an API quota
+pub const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How many refresh intervals may pass without new data before a render reports
+/// that it no longer has content
+pub const STALE_AFTER_REFRESHES: u32 = 3;
+
+/// Converts a `*_secs` refresh interval of a configuration into a duration,
+/// falling back to `default`.
+pub fn refresh_interval(refresh_secs: Option<u64>, default: Duration) -> Duration {
+    refresh_secs.map(Duration::from_secs).unwrap_or(default)
+}
+
+/// Checks that the refresh interval `name` of a configuration, if provided, is
+/// at least [`MIN_REFRESH_INTERVAL`].
+pub fn validate_refresh_interval(name: &str, refresh_secs: Option<u64>) -> Result<()> {
+    match refresh_secs {
+        Some(secs) if secs < MIN_REFRESH_INTERVAL.as_secs() => Err(anyhow!(
+            "{name} of {secs}s is shorter than the minimum of {}s",
+            MIN_REFRESH_INTERVAL.as_secs()
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Awaits `request` inside a `fetch` span naming the `render` that made it and
+/// the `endpoint` it went to. The span is only recorded when built with the
+/// `tracing` feature.
+pub async fn traced<F>(render: &'static str, endpoint: impl Display, request: F) -> F::Output
+where
+    F: Future,
+{
+    #[cfg(feature = "tracing")]
+    {
+        use tracing::Instrument;
+
+        request
+            .instrument(tracing::info_span!("fetch", render, %endpoint))
+            .await
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (render, endpoint);
+        request.await
+    }
+}
+
+/// Awaits `request`, failing if it does not complete within `timeout`.
+///
+/// Used for API clients that can't be constructed with a timeout of their own,
+/// so a stalled connection can't hold up an update loop past its interval.
+pub async fn with_timeout<F, T, E>(timeout: Duration, request: F) -> Result<T>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    match tokio::time::timeout(timeout, request).await {
+        Ok(Ok(response)) => Ok(response),
+        Ok(Err(e)) => Err(anyhow!("{e}")),
+        Err(_) => Err(anyhow!("Request timed out after {}s", timeout.as_secs())),
+    }
+}
@@ -0,0 +1,26 @@
+use embedded_graphics::mono_font::MonoFont;
+use std::time::Duration;
+
+/// Returns the width, in pixels, of `text` drawn in `font`
+pub fn text_width(text: &str, font: &MonoFont) -> u32 {
+    text.chars().count() as u32 * (font.character_size.width + font.character_spacing)
+}
+
+/// Returns the horizontal position of a line of text that is `text_width` wide
+/// scrolling from right to left, `speed` pixels per second, across a canvas
+/// that is `canvas_width` wide, at `elapsed` on the
+/// [`animation_time`](rustic_pixel_display::render::animation_time) clock.
+///
+/// Text that fits on the canvas does not scroll.
+pub fn scroll_position(text_width: u32, canvas_width: u32, speed: u64, elapsed: Duration) -> i32 {
+    if text_width <= canvas_width {
+        return 0;
+    }
+
+    let elapsed_millis = elapsed.as_millis() as u64;
+
+    let travel = u64::from(text_width + canvas_width);
+    let offset = (elapsed_millis * speed / 1000) % travel;
+
+    canvas_width as i32 - offset as i32
+}
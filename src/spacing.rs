@@ -0,0 +1,37 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+/// The largest margin, in pixels, that can be configured between the elements
+/// of a render. Anything larger would push most of the content off a panel.
+pub const MAX_SPACING: u32 = 32;
+
+/// Margins, in pixels, between the elements of a render
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct SpacingConfig {
+    /// Space between the title and the rest of the render
+    pub title_spacing: u32,
+
+    /// Space between consecutive rows
+    pub row_spacing: u32,
+
+    /// Space between the columns of a row
+    pub column_spacing: u32,
+}
+
+impl SpacingConfig {
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in [
+            ("title_spacing", self.title_spacing),
+            ("row_spacing", self.row_spacing),
+            ("column_spacing", self.column_spacing),
+        ] {
+            if value > MAX_SPACING {
+                return Err(anyhow!(
+                    "{name} of {value} is larger than the maximum of {MAX_SPACING}"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
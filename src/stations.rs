@@ -0,0 +1,122 @@
+use anyhow::{anyhow, Result};
+use geoutils::{Distance, Location};
+use log::error;
+use rustic_pixel_display::push_routes::{register_get_route, GetRoute};
+use septa_api::types::RegionalRailStop;
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+use strum::IntoEnumIterator;
+
+/// Every Regional Rail station, leaving out the `Unknown` placeholder that has
+/// no location.
+///
+/// Use this rather than iterating [`RegionalRailStop`] directly wherever
+/// stations are compared by their location.
+pub fn known_stations() -> impl Iterator<Item = RegionalRailStop> {
+    RegionalRailStop::iter().filter(|stop| !matches!(stop, RegionalRailStop::Unknown(_)))
+}
+
+// Computed once since the transit tracker looks up every station on every update
+lazy_static! {
+    /// The location of every known station
+    pub static ref STATION_LOCATIONS: HashMap<RegionalRailStop, Location> = known_stations()
+        .filter_map(|station| match station.lat_lon() {
+            Ok((lat, lon)) => Some((station, Location::new(lat, lon))),
+            Err(e) => {
+                error!("No location for station {station} {e}");
+                None
+            }
+        })
+        .collect();
+}
+
+/// Returns the location of `station`, naming the station if it has none.
+pub fn station_location(station: &RegionalRailStop) -> Result<Location> {
+    STATION_LOCATIONS
+        .get(station)
+        .cloned()
+        .ok_or_else(|| anyhow!("No location for station {station}"))
+}
+
+/// Approximate length of a degree of latitude
+const METERS_PER_DEGREE_LAT: f64 = 110_540.0;
+
+/// Approximate length of a degree of longitude at the equator
+const METERS_PER_DEGREE_LON: f64 = 111_320.0;
+
+/// How much larger than the radius the box around a station is, so the
+/// approximate lengths of a degree never reject a station that is in range
+const BOUNDING_BOX_MARGIN: f64 = 1.1;
+
+/// Checks whether `person_location` is within `radius` of `station_location`.
+///
+/// Stations whose latitude or longitude alone is further than `radius` away
+/// are rejected before computing the distance between the two, which rules out
+/// almost every station.
+pub fn is_near_station(
+    person_location: &Location,
+    station_location: &Location,
+    radius: &Distance,
+) -> Result<bool> {
+    let max_meters = radius.meters() * BOUNDING_BOX_MARGIN;
+    let lat_meters =
+        (person_location.latitude() - station_location.latitude()).abs() * METERS_PER_DEGREE_LAT;
+    let lon_meters = (person_location.longitude() - station_location.longitude()).abs()
+        * METERS_PER_DEGREE_LON
+        * person_location.latitude().to_radians().cos();
+
+    if lat_meters > max_meters || lon_meters > max_meters {
+        return Ok(false);
+    }
+
+    person_location
+        .is_in_circle(station_location, *radius)
+        .map_err(|e| anyhow!("distance_to failed: {}", e))
+}
+
+/// A known Regional Rail station, as returned by a station search
+#[derive(Serialize, Debug, Clone)]
+pub struct StationEntry {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Finds the Regional Rail stations whose display name contains `query`,
+/// ignoring case. An empty query matches every station.
+pub fn search_stations(query: &str) -> Vec<StationEntry> {
+    let query = query.to_lowercase();
+
+    known_stations()
+        .filter_map(|stop| {
+            let name = stop.to_string();
+            if !name.to_lowercase().contains(&query) {
+                return None;
+            }
+
+            // Stations without a known location can't be placed on a map
+            let location = STATION_LOCATIONS.get(&stop)?;
+            Some(StationEntry {
+                name,
+                lat: location.latitude(),
+                lon: location.longitude(),
+            })
+        })
+        .collect()
+}
+
+/// The path of the HTTP API that [`search_stations`] is served on, with the
+/// query in the `q` parameter
+pub const STATION_SEARCH_PATH: &str = "/septa/stations";
+
+/// Serves [`search_stations`] on `GET /septa/stations?q=` of the HTTP API until
+/// the returned route is dropped. A missing query matches every station.
+pub fn register_station_search() -> Result<GetRoute> {
+    register_get_route(
+        STATION_SEARCH_PATH,
+        Arc::new(|param: &dyn Fn(&str) -> Option<String>| {
+            let stations = search_stations(&param("q").unwrap_or_default());
+            serde_json::to_value(stations).unwrap_or_default()
+        }),
+    )
+}
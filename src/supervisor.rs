@@ -0,0 +1,61 @@
+use anyhow::Result;
+use log::error;
+use std::{future::Future, time::Duration};
+use tokio::{select, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+/// How long to wait before restarting an update task that stopped, so a task
+/// that fails right away does not spin
+pub const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+/// Runs the update task returned by `spawn_task`, and restarts it whenever it
+/// panics or returns before `cancel_token` is cancelled.
+///
+/// Without this, a render whose update task panicked keeps drawing the last
+/// data it got without any sign that it stopped updating. Every restart is
+/// logged with `name` and waits `restart_delay` first. `spawn_task` is called
+/// again for every restart, so it should clone whatever the task needs, i.e.
+/// the config and the shared state.
+///
+/// Must be called from within a tokio runtime.
+pub fn supervise<F, Fut>(
+    name: &'static str,
+    cancel_token: CancellationToken,
+    restart_delay: Duration,
+    spawn_task: F,
+) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    tokio::task::spawn(async move {
+        loop {
+            let mut task = tokio::task::spawn(spawn_task());
+
+            let task_result = select! {
+                task_result = &mut task => task_result,
+                _ = cancel_token.cancelled() => {
+                    task.abort();
+                    break;
+                }
+            };
+
+            // The task stopped because the render is being dropped
+            if cancel_token.is_cancelled() {
+                break;
+            }
+
+            match task_result {
+                Ok(Ok(())) => error!("The {name} update task stopped, restarting it"),
+                Ok(Err(e)) => error!("The {name} update task failed, restarting it {e}"),
+                Err(e) if e.is_panic() => error!("The {name} update task panicked, restarting it"),
+                Err(_) => break,
+            }
+
+            select! {
+                _ = tokio::time::sleep(restart_delay) => {},
+                _ = cancel_token.cancelled() => break,
+            }
+        }
+    })
+}
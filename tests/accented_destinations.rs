@@ -0,0 +1,54 @@
+//! Tests that the arrivals board draws accented destinations with their
+//! accents and other characters as a visible fallback glyph.
+
+use chrono::{FixedOffset, TimeZone};
+use embedded_graphics::prelude::Size;
+use rustic_pixel_display::render::{RecordingCanvas, Render};
+use rustic_pixel_examples::renders::upcoming_arrivals::{
+    UpcomingArrivals, UpcomingArrivalsConfig, UpcomingTrain, UpcomingTrainStatus,
+};
+
+/// The board drawn with a single train heading to `destination`
+fn board_to(destination: &str) -> String {
+    let departure = FixedOffset::west_opt(5 * 60 * 60)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 10, 9, 5, 0)
+        .unwrap();
+    let config: UpcomingArrivalsConfig =
+        serde_json::from_str(r#"{ "amtrak_station": "PHL" }"#).unwrap();
+    let board = UpcomingArrivals::with_arrivals(
+        config,
+        Vec::new(),
+        vec![UpcomingTrain::new(
+            "2150",
+            destination,
+            departure,
+            UpcomingTrainStatus::OnTime,
+        )],
+    )
+    .unwrap();
+
+    let mut canvas = RecordingCanvas::new(Size::new(256, 32));
+    board.render(&mut canvas).unwrap();
+
+    canvas.to_ascii_art()
+}
+
+#[test]
+fn slash_in_destination_is_drawn() {
+    assert_ne!(
+        board_to("Malvern/Parkesburg"),
+        board_to("Malvern Parkesburg")
+    );
+}
+
+#[test]
+fn accents_are_drawn() {
+    assert_ne!(board_to("Genève"), board_to("Geneve"));
+}
+
+#[test]
+fn characters_outside_the_font_are_drawn_as_the_fallback() {
+    assert_eq!(board_to("Snow \u{2603}"), board_to("Snow ?"));
+    assert_ne!(board_to("Snow \u{2603}"), board_to("Snow  "));
+}
@@ -0,0 +1,46 @@
+//! Tests that the status of Amtrak trains is derived from the estimates
+//! Amtrak reports for the station.
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+use rustic_pixel_examples::renders::upcoming_arrivals::{station_status, UpcomingTrainStatus};
+
+fn at(hour: u32, min: u32) -> DateTime<FixedOffset> {
+    FixedOffset::west_opt(5 * 60 * 60)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 10, hour, min, 0)
+        .unwrap()
+}
+
+#[test]
+fn status_from_arrival_estimate() {
+    let status = |est_arrival| station_status(at(9, 0), Some(est_arrival), at(9, 5), None);
+
+    assert_eq!(status(at(9, 0)), UpcomingTrainStatus::OnTime);
+    assert_eq!(status(at(9, 12)), UpcomingTrainStatus::Late(12));
+    assert_eq!(status(at(8, 57)), UpcomingTrainStatus::Early(3));
+}
+
+#[test]
+fn arrival_estimate_is_preferred() {
+    assert_eq!(
+        station_status(at(9, 0), Some(at(9, 4)), at(9, 5), Some(at(9, 20))),
+        UpcomingTrainStatus::Late(4)
+    );
+}
+
+#[test]
+fn origin_station_uses_departure_estimate() {
+    // The origin station has no arrival estimate
+    assert_eq!(
+        station_status(at(9, 0), None, at(9, 5), Some(at(9, 15))),
+        UpcomingTrainStatus::Late(10)
+    );
+}
+
+#[test]
+fn unknown_without_estimates() {
+    assert_eq!(
+        station_status(at(9, 0), None, at(9, 5), None),
+        UpcomingTrainStatus::Unknown
+    );
+}
@@ -0,0 +1,24 @@
+//! Tests that a bundled asset that can't be decoded is reported by name
+//! rather than panicking.
+
+use rustic_pixel_examples::assets::{load_bmp, load_bmp_or_log};
+
+const GARBAGE: &[u8] = b"definitely not a bitmap";
+
+#[test]
+fn corrupt_bmp_error_names_the_asset() {
+    let error = load_bmp("SEPTA_16.bmp", GARBAGE).unwrap_err().to_string();
+
+    assert!(error.contains("SEPTA_16.bmp"), "{error}");
+    assert!(error.contains("BMP"), "{error}");
+}
+
+#[test]
+fn empty_bmp_is_rejected() {
+    assert!(load_bmp("empty.bmp", &[]).is_err());
+}
+
+#[test]
+fn corrupt_bmp_is_skipped() {
+    assert!(load_bmp_or_log("SEPTA_16.bmp", GARBAGE).is_none());
+}
@@ -0,0 +1,88 @@
+//! Tests that dropping a render while its fetch is in flight cancels the
+//! fetch straight away, rather than holding on to it until it completes.
+
+use anyhow::Result;
+use rustic_pixel_examples::{
+    data_source::PollingSource,
+    renders::weather::{Configuration, Weather},
+};
+use std::{
+    future::pending,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// How long a dropped fetch may take to be cancelled
+const CANCEL_BOUND: Duration = Duration::from_millis(500);
+
+/// A source whose fetches never complete. Every fetch holds on to a clone of
+/// `in_flight` until it is dropped, and counts itself in `started`.
+fn stalled_source<T>(in_flight: &Arc<()>, started: &Arc<AtomicUsize>) -> PollingSource<T>
+where
+    T: Send + Sync + 'static,
+{
+    let in_flight = in_flight.clone();
+    let started = started.clone();
+
+    PollingSource::new(
+        "stalled",
+        Duration::from_secs(60),
+        Duration::from_secs(60),
+        move || {
+            let guard = in_flight.clone();
+            started.fetch_add(1, Ordering::SeqCst);
+
+            async move {
+                let _guard = guard;
+                pending::<Result<T>>().await
+            }
+        },
+    )
+}
+
+/// Waits until `condition` holds, failing the test if it takes longer than
+/// `bound`
+async fn wait_until(bound: Duration, condition: impl Fn() -> bool) {
+    tokio::time::timeout(bound, async {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for the condition");
+}
+
+#[tokio::test]
+async fn dropping_the_source_cancels_the_fetch() {
+    let in_flight = Arc::new(());
+    let started = Arc::new(AtomicUsize::new(0));
+    let source = stalled_source::<u32>(&in_flight, &started);
+
+    wait_until(Duration::from_secs(5), || {
+        started.load(Ordering::SeqCst) == 1
+    })
+    .await;
+    assert_eq!(Arc::strong_count(&in_flight), 2);
+
+    drop(source);
+    wait_until(CANCEL_BOUND, || Arc::strong_count(&in_flight) == 1).await;
+}
+
+#[tokio::test]
+async fn dropping_weather_cancels_the_fetch() {
+    let in_flight = Arc::new(());
+    let started = Arc::new(AtomicUsize::new(0));
+    let config: Configuration = serde_json::from_str(r#"{ "api_key": "" }"#).unwrap();
+    let weather = Weather::with_source(config, Box::new(stalled_source(&in_flight, &started)));
+
+    wait_until(Duration::from_secs(5), || {
+        started.load(Ordering::SeqCst) == 1
+    })
+    .await;
+
+    drop(weather);
+    wait_until(CANCEL_BOUND, || Arc::strong_count(&in_flight) == 1).await;
+}
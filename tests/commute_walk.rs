@@ -0,0 +1,91 @@
+//! Tests that the commute render counts down to the first train that can be
+//! reached in the walk to the station, graying out the ones that can't.
+
+use chrono::{Duration, Local};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{Dimensions, RgbColor, Size},
+    primitives::PointsIter,
+};
+use rustic_pixel_display::render::{BufferCanvas, Render};
+use rustic_pixel_examples::renders::{
+    commute::{Commute, CommuteConfig},
+    upcoming_arrivals::{UpcomingTrain, UpcomingTrainStatus},
+};
+
+const WALK_MINUTES: i64 = 8;
+
+/// Color of the countdown to a train that can be caught
+const COUNTDOWN_COLOR: Rgb888 = Rgb888::GREEN;
+
+/// Color of trains that leave before the walk to the station is over
+const MISSED_COLOR: Rgb888 = Rgb888::new(96, 96, 96);
+
+/// A train to Paoli leaving `offset` from now
+fn train_in(train_id: &str, offset: Duration) -> UpcomingTrain {
+    UpcomingTrain::new(
+        train_id,
+        "Paoli",
+        Local::now().fixed_offset() + offset,
+        UpcomingTrainStatus::OnTime,
+    )
+}
+
+/// A train leaving a minute after the walk to the station is over
+fn just_inside() -> UpcomingTrain {
+    train_in("9401", Duration::minutes(WALK_MINUTES + 1))
+}
+
+/// A train leaving a minute before the walk to the station is over
+fn just_outside() -> UpcomingTrain {
+    train_in("9301", Duration::minutes(WALK_MINUTES - 1))
+}
+
+fn draw_commute(arrivals: Vec<UpcomingTrain>) -> BufferCanvas {
+    let config: CommuteConfig = serde_json::from_value(serde_json::json!({
+        "station": "SuburbanStation",
+        "destination": "Paoli",
+        "walk_minutes": WALK_MINUTES,
+    }))
+    .unwrap();
+    let commute = Commute::with_arrivals(config, arrivals);
+
+    let mut canvas = BufferCanvas::new(Size::new(128, 64));
+    commute.render(&mut canvas).unwrap();
+
+    canvas
+}
+
+/// Whether any pixel of `canvas` is `color`
+fn has_color(canvas: &BufferCanvas, color: Rgb888) -> bool {
+    canvas
+        .bounding_box()
+        .points()
+        .any(|point| canvas.pixel(point) == Some(color))
+}
+
+#[test]
+fn train_just_inside_the_walk_can_be_caught() {
+    let canvas = draw_commute(vec![just_inside()]);
+
+    assert!(has_color(&canvas, COUNTDOWN_COLOR));
+    assert!(!has_color(&canvas, MISSED_COLOR));
+}
+
+#[test]
+fn train_just_outside_the_walk_is_missed() {
+    let canvas = draw_commute(vec![just_outside()]);
+
+    // Counted down to anyway, grayed out
+    assert!(!has_color(&canvas, COUNTDOWN_COLOR));
+    assert!(has_color(&canvas, MISSED_COLOR));
+}
+
+#[test]
+fn first_catchable_train_is_counted_down_to() {
+    // The missed train leaves first, and is listed grayed out
+    let canvas = draw_commute(vec![just_inside(), just_outside()]);
+
+    assert!(has_color(&canvas, COUNTDOWN_COLOR));
+    assert!(has_color(&canvas, MISSED_COLOR));
+}
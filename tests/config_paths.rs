@@ -0,0 +1,36 @@
+//! Tests the paths the startup configuration is searched at, with and without
+//! a `$HOME`.
+
+use rustic_pixel_examples::config_paths::{app_config_paths, init_config_path};
+use std::{env, path::PathBuf};
+
+// Both cases live in one test as they change the environment of the whole
+// process
+#[test]
+fn searched_paths_follow_home() {
+    env::remove_var("HOME");
+
+    assert_eq!(
+        app_config_paths(),
+        vec![
+            PathBuf::from("app.json"),
+            PathBuf::from("/etc/rustic-pixel-display/app.json"),
+        ]
+    );
+    assert_eq!(init_config_path(), PathBuf::from("app.json"));
+
+    env::set_var("HOME", "/home/pi");
+
+    assert_eq!(
+        app_config_paths(),
+        vec![
+            PathBuf::from("app.json"),
+            PathBuf::from("/home/pi/.config/rustic-pixel-display/app.json"),
+            PathBuf::from("/etc/rustic-pixel-display/app.json"),
+        ]
+    );
+    assert_eq!(
+        init_config_path(),
+        PathBuf::from("/home/pi/.config/rustic-pixel-display/app.json")
+    );
+}
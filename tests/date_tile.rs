@@ -0,0 +1,106 @@
+//! Golden tests of the date tile drawn at fixed times.
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable,
+};
+use rustic_pixel_display::{
+    clock::FixedClock,
+    font::Font,
+    render::{BufferCanvas, Render},
+};
+use rustic_pixel_examples::renders::date_tile::{DateTile, DateTileConfig};
+use std::{sync::Arc, time::Duration};
+
+const SIZE: Size = Size::new(64, 64);
+
+/// 11 PM on Saturday March 16th 2024 in New York, already Sunday in UTC
+fn saturday_night() -> DateTime<FixedOffset> {
+    FixedOffset::west_opt(4 * 60 * 60)
+        .unwrap()
+        .with_ymd_and_hms(2024, 3, 16, 23, 0, 0)
+        .unwrap()
+}
+
+fn draw_tile(clock: Arc<FixedClock>) -> BufferCanvas {
+    let tile = DateTile::new(DateTileConfig::default())
+        .unwrap()
+        .with_clock(clock);
+
+    let mut canvas = BufferCanvas::new(SIZE);
+    tile.render(&mut canvas).unwrap();
+
+    canvas
+}
+
+/// The page of a desk calendar as the tile lays it out with its default
+/// fonts, drawn independently of the tile
+fn golden_page(weekday: &str, day: &str, month: &str, color: Rgb888) -> BufferCanvas {
+    let mut canvas = BufferCanvas::new(SIZE);
+    let bounding_box = canvas.bounding_box();
+    let center_x = bounding_box.center().x;
+    let style = |baseline| {
+        TextStyleBuilder::new()
+            .alignment(Alignment::Center)
+            .baseline(baseline)
+            .build()
+    };
+
+    Text::with_text_style(
+        weekday,
+        Point::new(center_x, 0),
+        MonoTextStyle::new(Font::SixByTen.mono_font(), color),
+        style(Baseline::Top),
+    )
+    .draw(&mut canvas)
+    .unwrap();
+    Text::with_text_style(
+        day,
+        bounding_box.center(),
+        MonoTextStyle::new(Font::TenByTwenty.mono_font(), color),
+        style(Baseline::Middle),
+    )
+    .draw(&mut canvas)
+    .unwrap();
+    Text::with_text_style(
+        month,
+        Point::new(center_x, SIZE.height as i32 - 1),
+        MonoTextStyle::new(Font::SixByTen.mono_font(), Rgb888::WHITE),
+        style(Baseline::Bottom),
+    )
+    .draw(&mut canvas)
+    .unwrap();
+
+    canvas
+}
+
+#[test]
+fn weekend_page_at_fixed_time() {
+    let clock = Arc::new(FixedClock::new(saturday_night()));
+
+    assert_eq!(
+        draw_tile(clock).pixels(),
+        golden_page("Saturday", "16", "March", Rgb888::RED).pixels()
+    );
+}
+
+#[test]
+fn page_turns_over_at_midnight() {
+    let clock = Arc::new(FixedClock::new(saturday_night()));
+    let before = draw_tile(clock.clone());
+
+    clock.advance(Duration::from_secs(2 * 60 * 60));
+
+    assert_ne!(draw_tile(clock.clone()).pixels(), before.pixels());
+
+    // Back to a weekday the next day
+    clock.advance(Duration::from_secs(24 * 60 * 60));
+    assert_eq!(
+        draw_tile(clock).pixels(),
+        golden_page("Monday", "18", "March", Rgb888::WHITE).pixels()
+    );
+}
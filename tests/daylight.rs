@@ -0,0 +1,47 @@
+//! Tests whether the sun is up around the solstices, when the days are at
+//! their longest and shortest.
+
+use chrono::{DateTime, TimeZone, Utc};
+use rustic_pixel_examples::daylight::Daylight;
+
+const PHILADELPHIA: (f64, f64) = (39.9526, -75.1652);
+const REYKJAVIK: (f64, f64) = (64.1466, -21.9426);
+
+fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+        .unwrap()
+}
+
+fn is_daytime((lat, lon): (f64, f64), now: DateTime<Utc>) -> bool {
+    Daylight::new(lat, lon).is_daytime(now)
+}
+
+#[test]
+fn philadelphia_summer_solstice() {
+    // 6:30 and 20:15 EDT, after sunrise at 5:32 and before sunset at 20:33
+    assert!(is_daytime(PHILADELPHIA, utc(2024, 6, 21, 10, 30)));
+    assert!(is_daytime(PHILADELPHIA, utc(2024, 6, 22, 0, 15)));
+}
+
+#[test]
+fn philadelphia_winter_solstice() {
+    // 7:00 and 17:00 EST, before sunrise at 7:18 and after sunset at 16:38
+    assert!(!is_daytime(PHILADELPHIA, utc(2024, 12, 21, 12, 0)));
+    assert!(!is_daytime(PHILADELPHIA, utc(2024, 12, 21, 22, 0)));
+}
+
+#[test]
+fn reykjavik_summer_solstice() {
+    // The sun sets just after midnight and rises again before 3:00
+    assert!(!is_daytime(REYKJAVIK, utc(2024, 6, 21, 1, 30)));
+    assert!(is_daytime(REYKJAVIK, utc(2024, 6, 21, 3, 30)));
+    assert!(is_daytime(REYKJAVIK, utc(2024, 6, 21, 23, 0)));
+}
+
+#[test]
+fn reykjavik_winter_solstice() {
+    // The sun is only up from about 11:20 to 15:30
+    assert!(!is_daytime(REYKJAVIK, utc(2024, 12, 21, 10, 30)));
+    assert!(is_daytime(REYKJAVIK, utc(2024, 12, 21, 13, 0)));
+    assert!(!is_daytime(REYKJAVIK, utc(2024, 12, 21, 16, 30)));
+}
@@ -0,0 +1,53 @@
+//! Tests of how times of day are formatted in the 12 and 24 hour formats,
+//! around noon and midnight.
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+use rustic_pixel_examples::format::{format_time, HourFormat, TimeFormatConfig};
+
+fn at(hour: u32, min: u32) -> DateTime<FixedOffset> {
+    FixedOffset::west_opt(5 * 60 * 60)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 10, hour, min, 0)
+        .unwrap()
+}
+
+fn config(hour_format: HourFormat) -> TimeFormatConfig {
+    TimeFormatConfig {
+        hour_format,
+        ..TimeFormatConfig::default()
+    }
+}
+
+#[test]
+fn twenty_four_hour() {
+    let config = config(HourFormat::TwentyFourHour);
+
+    assert_eq!(format_time(&at(0, 0), &config), " 0:00");
+    assert_eq!(format_time(&at(0, 30), &config), " 0:30");
+    assert_eq!(format_time(&at(11, 59), &config), "11:59");
+    assert_eq!(format_time(&at(12, 0), &config), "12:00");
+    assert_eq!(format_time(&at(23, 59), &config), "23:59");
+}
+
+#[test]
+fn twelve_hour() {
+    let config = config(HourFormat::TwelveHour);
+
+    assert_eq!(format_time(&at(0, 0), &config), "12:00 AM");
+    assert_eq!(format_time(&at(0, 30), &config), "12:30 AM");
+    assert_eq!(format_time(&at(1, 5), &config), " 1:05 AM");
+    assert_eq!(format_time(&at(11, 59), &config), "11:59 AM");
+    assert_eq!(format_time(&at(12, 0), &config), "12:00 PM");
+    assert_eq!(format_time(&at(13, 5), &config), " 1:05 PM");
+    assert_eq!(format_time(&at(23, 59), &config), "11:59 PM");
+}
+
+#[test]
+fn twelve_hour_designators() {
+    let config: TimeFormatConfig =
+        serde_json::from_str(r#"{ "hour_format": "TwelveHour", "am": "vorm.", "pm": "nachm." }"#)
+            .unwrap();
+
+    assert_eq!(format_time(&at(0, 0), &config), "12:00 vorm.");
+    assert_eq!(format_time(&at(12, 0), &config), "12:00 nachm.");
+}
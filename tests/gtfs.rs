@@ -0,0 +1,115 @@
+//! Tests loading the names of a minimal static GTFS feed.
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::RgbColor};
+use rustic_pixel_examples::gtfs::GtfsSchedule;
+use std::{
+    env, fs,
+    io::{Cursor, Write},
+    sync::Arc,
+};
+use zip::{write::FileOptions, ZipWriter};
+
+const STOPS: &str = "\
+stop_id,stop_name,stop_lat,stop_lon
+90004,30th Street Station,39.9566,-75.1820
+90005,Suburban Station,39.9540,-75.1679
+90006,,39.9525,-75.1580
+";
+
+const ROUTES: &str = "\
+route_id,route_short_name,route_long_name,route_color
+AIR,AIR,Airport Line,91456C
+WAR,,Warminster Line,not-a-color
+";
+
+const TRIPS: &str = "\
+route_id,service_id,trip_id,trip_headsign
+AIR,M1,AIR_431,Airport Terminal E-F
+WAR,M1,WAR_6412,
+";
+
+/// Zips the given files into a GTFS feed
+fn gtfs_zip(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+
+    for (name, contents) in files {
+        writer.start_file(*name, FileOptions::default()).unwrap();
+        writer.write_all(contents.as_bytes()).unwrap();
+    }
+
+    writer.finish().unwrap().into_inner()
+}
+
+fn fixture() -> Vec<u8> {
+    gtfs_zip(&[
+        ("stops.txt", STOPS),
+        ("routes.txt", ROUTES),
+        ("trips.txt", TRIPS),
+    ])
+}
+
+#[test]
+fn stops_are_named() {
+    let schedule = GtfsSchedule::from_zip(Cursor::new(fixture())).unwrap();
+
+    assert_eq!(schedule.stop_name("90004"), Some("30th Street Station"));
+    assert_eq!(schedule.stop_name("90005"), Some("Suburban Station"));
+
+    // A stop without a name keeps showing its id
+    assert_eq!(schedule.stop_name("90006"), None);
+    assert_eq!(schedule.stop_name("12345"), None);
+}
+
+#[test]
+fn routes_prefer_their_short_name() {
+    let schedule = GtfsSchedule::from_zip(Cursor::new(fixture())).unwrap();
+
+    let airport = schedule.route("AIR").unwrap();
+    assert_eq!(airport.name, "AIR");
+    assert_eq!(airport.color, Some(Rgb888::new(0x91, 0x45, 0x6C)));
+
+    // Falls back to the long name, and an invalid color is dropped
+    let warminster = schedule.route("WAR").unwrap();
+    assert_eq!(warminster.name, "Warminster Line");
+    assert_eq!(warminster.color, None);
+}
+
+#[test]
+fn trips_have_their_route_and_headsign() {
+    let schedule = GtfsSchedule::from_zip(Cursor::new(fixture())).unwrap();
+
+    let trip = schedule.trip("AIR_431").unwrap();
+    assert_eq!(trip.route_id, "AIR");
+    assert_eq!(trip.headsign.as_deref(), Some("Airport Terminal E-F"));
+
+    assert_eq!(schedule.trip("WAR_6412").unwrap().headsign, None);
+    assert!(schedule.trip("AIR_999").is_none());
+}
+
+#[test]
+fn missing_file_is_named_in_the_error() {
+    let zip = gtfs_zip(&[("stops.txt", STOPS), ("routes.txt", ROUTES)]);
+
+    let error = GtfsSchedule::from_zip(Cursor::new(zip)).unwrap_err();
+    assert!(error.to_string().contains("trips.txt"), "{error}");
+}
+
+#[test]
+fn not_a_zip_is_an_error() {
+    assert!(GtfsSchedule::from_zip(Cursor::new(STOPS.as_bytes())).is_err());
+}
+
+#[tokio::test]
+async fn local_schedule_is_loaded_once() {
+    let path = env::temp_dir().join(format!("gtfs-fixture-{}.zip", std::process::id()));
+    fs::write(&path, fixture()).unwrap();
+    let source = path.to_str().unwrap();
+
+    let first = GtfsSchedule::load(source).await.unwrap();
+    assert_eq!(first.stop_name("90005"), Some("Suburban Station"));
+
+    // Served from the cache even once the file is gone
+    fs::remove_file(&path).unwrap();
+    let second = GtfsSchedule::load(source).await.unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+}
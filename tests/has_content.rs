@@ -0,0 +1,72 @@
+//! Tests that the data renders only report content once they received data.
+
+use anyhow::anyhow;
+use rustic_pixel_display::render::{BufferCanvas, Render};
+use rustic_pixel_examples::{
+    data_source::PollingSource,
+    renders::{
+        upcoming_arrivals::{UpcomingArrivals, UpcomingArrivalsConfig},
+        weather::{Configuration, Weather},
+    },
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+fn arrivals_config() -> UpcomingArrivalsConfig {
+    serde_json::from_str(r#"{ "amtrak_station": "PHL" }"#).unwrap()
+}
+
+#[tokio::test]
+async fn weather_without_a_forecast_has_no_content() {
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let source_fetches = fetches.clone();
+    let source = PollingSource::new(
+        "forecast",
+        Duration::from_millis(10),
+        Duration::from_millis(10),
+        move || {
+            let fetches = source_fetches.clone();
+
+            async move {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                Err(anyhow!("Offline"))
+            }
+        },
+    );
+
+    let config: Configuration = serde_json::from_str(r#"{ "api_key": "" }"#).unwrap();
+    let weather = Weather::with_source(config, Box::new(source));
+    assert!(!Render::<BufferCanvas>::has_content(&weather));
+
+    // Failed fetches don't count as content either
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while fetches.load(Ordering::SeqCst) < 3 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert!(!Render::<BufferCanvas>::has_content(&weather));
+}
+
+#[tokio::test]
+async fn arrivals_never_fetched_have_no_content() {
+    // The test runs on a single thread, so the fetch that was spawned can't
+    // complete before the render is asked
+    let arrivals = UpcomingArrivals::new(arrivals_config()).unwrap();
+
+    assert!(!Render::<BufferCanvas>::has_content(&arrivals));
+}
+
+#[test]
+fn arrivals_fetched_without_trains_have_content() {
+    let arrivals =
+        UpcomingArrivals::with_arrivals(arrivals_config(), Vec::new(), Vec::new()).unwrap();
+
+    assert!(Render::<BufferCanvas>::has_content(&arrivals));
+}
@@ -0,0 +1,93 @@
+//! Tests that trains are colored by their destination, falling back to white
+//! for destinations without a configured color.
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{Point, RgbColor, Size},
+};
+use rustic_pixel_display::render::{BufferCanvas, Render};
+use rustic_pixel_examples::{
+    line_colors::{LineColors, DEFAULT_LINE_COLOR},
+    renders::upcoming_arrivals::{
+        UpcomingArrivals, UpcomingArrivalsConfig, UpcomingTrain, UpcomingTrainStatus,
+    },
+};
+use std::collections::{BTreeSet, HashMap};
+
+fn line_colors(colors: &[(&str, &str)]) -> anyhow::Result<LineColors> {
+    LineColors::new(
+        &colors
+            .iter()
+            .map(|(destination, color)| (destination.to_string(), color.to_string()))
+            .collect::<HashMap<_, _>>(),
+    )
+}
+
+#[test]
+fn configured_destination_uses_its_color() {
+    let colors = line_colors(&[("Thorndale", "#F26122")]).unwrap();
+
+    assert_eq!(colors.color_for("Thorndale"), Rgb888::new(0xF2, 0x61, 0x22));
+    assert_eq!(colors.color_for("THORNDALE"), Rgb888::new(0xF2, 0x61, 0x22));
+}
+
+#[test]
+fn unknown_destination_uses_the_fallback() {
+    let colors = line_colors(&[("Thorndale", "#F26122")]).unwrap();
+
+    assert_eq!(colors.color_for("Doylestown"), DEFAULT_LINE_COLOR);
+    assert_eq!(LineColors::default().color_for("Thorndale"), Rgb888::WHITE);
+}
+
+#[test]
+fn invalid_color_names_the_destination() {
+    let error = line_colors(&[("Thorndale", "orange")]).unwrap_err();
+
+    assert!(error.to_string().contains("Thorndale"), "{error}");
+}
+
+fn at(hour: u32, min: u32) -> DateTime<FixedOffset> {
+    FixedOffset::west_opt(5 * 60 * 60)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 10, hour, min, 0)
+        .unwrap()
+}
+
+/// The colors drawn in the train id column of the rows starting at `top`
+fn train_id_colors(canvas: &BufferCanvas, top: i32) -> BTreeSet<(u8, u8, u8)> {
+    // Between the right aligned time and the direction
+    (28..56)
+        .flat_map(|x| (top..top + 10).map(move |y| Point::new(x, y)))
+        .filter_map(|point| canvas.pixel(point))
+        .filter(|&color| color != Rgb888::BLACK)
+        .map(|color| (color.r(), color.g(), color.b()))
+        .collect()
+}
+
+#[test]
+fn arrivals_board_draws_train_ids_in_line_colors() {
+    let config: UpcomingArrivalsConfig = serde_json::from_str(
+        r##"{ "amtrak_station": "PHL", "line_colors": { "New York": "#FF0000" } }"##,
+    )
+    .unwrap();
+    let board = UpcomingArrivals::with_arrivals(
+        config,
+        Vec::new(),
+        vec![
+            UpcomingTrain::new("2150", "New York", at(9, 5), UpcomingTrainStatus::OnTime),
+            UpcomingTrain::new("641", "Harrisburg", at(9, 20), UpcomingTrainStatus::OnTime),
+        ],
+    )
+    .unwrap();
+
+    let mut canvas = BufferCanvas::new(Size::new(256, 64));
+    board.render(&mut canvas).unwrap();
+
+    // Rows are 10 pixels apart below the title, which is 15 or 16 pixels tall
+    assert_eq!(train_id_colors(&canvas, 16), BTreeSet::from([(255, 0, 0)]));
+    assert_eq!(
+        train_id_colors(&canvas, 26),
+        BTreeSet::from([(255, 255, 255)])
+    );
+}
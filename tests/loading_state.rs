@@ -0,0 +1,72 @@
+//! Tests that newly constructed data renders draw their loading indicator and
+//! report no content until their first fetch completes.
+//!
+//! The tests run on a single threaded runtime, so the fetches spawned by the
+//! renders can't complete before the renders are drawn.
+
+use embedded_graphics::prelude::Size;
+use rustic_pixel_display::render::{BufferCanvas, Render};
+use rustic_pixel_examples::{
+    loading::{draw_loading, loading_spinner},
+    renders::{
+        crypto::{Crypto, CryptoConfig},
+        septa_alerts::{SeptaAlerts, SeptaAlertsConfig},
+        upcoming_arrivals::{UpcomingArrivals, UpcomingArrivalsConfig},
+        weather::{Configuration, Weather},
+    },
+};
+
+const SIZE: Size = Size::new(64, 32);
+
+/// Checks that `render` has no content and draws the same as the loading
+/// indicator on its own
+fn assert_loading<R: Render<BufferCanvas>>(render: &R) {
+    assert!(!render.has_content());
+
+    // The spinner may advance between the two drawings, so try again until
+    // both land on the same frame
+    for _ in 0..10 {
+        let mut canvas = BufferCanvas::new(SIZE);
+        render.render(&mut canvas).unwrap();
+
+        let mut loading = BufferCanvas::new(SIZE);
+        draw_loading(&mut loading, &loading_spinner()).unwrap();
+
+        if canvas.pixels() == loading.pixels() {
+            return;
+        }
+    }
+
+    panic!("The loading indicator was not drawn");
+}
+
+#[tokio::test]
+async fn upcoming_arrivals_is_loading() {
+    let config: UpcomingArrivalsConfig =
+        serde_json::from_str(r#"{ "amtrak_station": "PHL" }"#).unwrap();
+
+    assert_loading(&UpcomingArrivals::new(config).unwrap());
+}
+
+#[tokio::test]
+async fn weather_is_loading() {
+    let config: Configuration = serde_json::from_str(r#"{ "api_key": "" }"#).unwrap();
+
+    assert_loading(&Weather::new(config));
+}
+
+#[tokio::test]
+async fn crypto_is_loading() {
+    let config: CryptoConfig =
+        serde_json::from_str(r#"{ "coins": ["BTC"], "vs_currency": "usd" }"#).unwrap();
+
+    assert_loading(&Crypto::new(config));
+}
+
+#[tokio::test]
+async fn septa_alerts_is_loading() {
+    let config: SeptaAlertsConfig =
+        serde_json::from_str(r#"{ "lines": ["Paoli/Thorndale"] }"#).unwrap();
+
+    assert_loading(&SeptaAlerts::new(config));
+}
@@ -0,0 +1,86 @@
+//! Tests that SEPTA and Amtrak entries for the same train are merged into the
+//! SEPTA entry.
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+use rustic_pixel_examples::renders::upcoming_arrivals::{
+    merge_duplicate_trains, UpcomingTrain, UpcomingTrainStatus,
+};
+
+fn at(hour: u32, min: u32) -> DateTime<FixedOffset> {
+    FixedOffset::west_opt(5 * 60 * 60)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 10, hour, min, 0)
+        .unwrap()
+}
+
+fn train(
+    train_id: &str,
+    destination_name: &str,
+    schedule_arrival: DateTime<FixedOffset>,
+) -> UpcomingTrain {
+    UpcomingTrain::new(
+        train_id,
+        destination_name,
+        schedule_arrival,
+        UpcomingTrainStatus::OnTime,
+    )
+}
+
+/// The id of each merged train and whether it is shared with Amtrak
+fn merged(
+    septa_arrivals: &[UpcomingTrain],
+    amtrak_arrivals: &[UpcomingTrain],
+) -> Vec<(String, bool)> {
+    merge_duplicate_trains(septa_arrivals, amtrak_arrivals)
+        .iter()
+        .map(|train| (train.train_id().to_owned(), train.is_shared_with_amtrak()))
+        .collect()
+}
+
+#[test]
+fn overlapping_trains_keep_the_septa_entry() {
+    let septa_arrivals = [
+        train("9735", "Trenton", at(9, 0)),
+        train("1520", "Paoli", at(9, 10)),
+    ];
+    let amtrak_arrivals = [
+        train("641", "TRENTON", at(9, 2)),
+        train("43", "Pittsburgh", at(9, 15)),
+    ];
+
+    assert_eq!(
+        merged(&septa_arrivals, &amtrak_arrivals),
+        vec![
+            ("9735".to_owned(), true),
+            ("1520".to_owned(), false),
+            ("43".to_owned(), false),
+        ]
+    );
+}
+
+#[test]
+fn trains_further_apart_than_the_window_are_kept() {
+    let septa_arrivals = [train("9735", "Trenton", at(9, 0))];
+    let amtrak_arrivals = [train("641", "Trenton", at(9, 4))];
+
+    assert_eq!(
+        merged(&septa_arrivals, &amtrak_arrivals),
+        vec![("9735".to_owned(), false), ("641".to_owned(), false)]
+    );
+}
+
+#[test]
+fn septa_train_is_merged_once() {
+    // Two Amtrak trains close to the same SEPTA train, only one of them can be
+    // the same train
+    let septa_arrivals = [train("9735", "Trenton", at(9, 0))];
+    let amtrak_arrivals = [
+        train("641", "Trenton", at(9, 1)),
+        train("643", "Trenton", at(9, 3)),
+    ];
+
+    assert_eq!(
+        merged(&septa_arrivals, &amtrak_arrivals),
+        vec![("9735".to_owned(), true), ("643".to_owned(), false)]
+    );
+}
@@ -0,0 +1,76 @@
+//! Tests that renders stop polling while they are deselected and poll again
+//! as soon as they are selected.
+
+use anyhow::anyhow;
+use rustic_pixel_display::render::{BufferCanvas, Render};
+use rustic_pixel_examples::{
+    data_source::PollingSource,
+    renders::weather::{Configuration, Weather},
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+const SHORT_INTERVAL: Duration = Duration::from_millis(10);
+const LONG_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// A weather render whose forecast source counts its fetches, polling every
+/// `interval`
+fn counting_weather(fetches: Arc<AtomicUsize>, interval: Duration) -> Weather {
+    let source = PollingSource::new("forecast", interval, interval, move || {
+        let fetches = fetches.clone();
+
+        async move {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow!("Offline"))
+        }
+    });
+
+    let config: Configuration = serde_json::from_str(r#"{ "api_key": "" }"#).unwrap();
+    Weather::with_source(config, Box::new(source))
+}
+
+/// Waits until `fetches` reaches `expected`, failing the test if it takes too
+/// long
+async fn wait_for_fetches(fetches: &AtomicUsize, expected: usize) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while fetches.load(Ordering::SeqCst) < expected {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("Only fetched {} times", fetches.load(Ordering::SeqCst)));
+}
+
+#[tokio::test]
+async fn deselected_render_does_not_fetch() {
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let weather = counting_weather(fetches.clone(), SHORT_INTERVAL);
+
+    wait_for_fetches(&fetches, 2).await;
+    Render::<BufferCanvas>::on_deselect(&weather);
+
+    // Let a fetch that was already under way finish
+    tokio::time::sleep(SHORT_INTERVAL * 2).await;
+    let paused_fetches = fetches.load(Ordering::SeqCst);
+
+    tokio::time::sleep(SHORT_INTERVAL * 10).await;
+    assert_eq!(fetches.load(Ordering::SeqCst), paused_fetches);
+}
+
+#[tokio::test]
+async fn reselected_render_fetches_right_away() {
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let weather = counting_weather(fetches.clone(), LONG_INTERVAL);
+
+    wait_for_fetches(&fetches, 1).await;
+    Render::<BufferCanvas>::on_deselect(&weather);
+    Render::<BufferCanvas>::on_select(&weather);
+
+    // Well before the next refresh is due
+    wait_for_fetches(&fetches, 2).await;
+}
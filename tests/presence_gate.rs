@@ -0,0 +1,122 @@
+//! Tests that `PresenceGate` blanks the panel while nobody is present and
+//! draws the wrapped render once someone returns.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{DrawTarget, Point, RgbColor, Size},
+};
+use rustic_pixel_display::render::{BufferCanvas, Render};
+use rustic_pixel_examples::renders::presence_gate::{PresenceGate, PresenceGateConfig};
+use std::{
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+const SIZE: Size = Size::new(8, 4);
+
+/// Fills the canvas with white, counting how often it is selected and
+/// deselected
+#[derive(Default)]
+struct Fill {
+    selects: AtomicUsize,
+    deselects: AtomicUsize,
+}
+
+impl<D> Render<D> for Fill
+where
+    D: DrawTarget<Color = Rgb888, Error = Infallible>,
+{
+    fn render(&self, canvas: &mut D) -> Result<(), D::Error> {
+        canvas.clear(Rgb888::WHITE)
+    }
+
+    fn on_select(&self) {
+        self.selects.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn on_deselect(&self) {
+        self.deselects.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Gates a [`Fill`] on a single presence flag, blanking as soon as nobody is
+/// present unless `away_grace_secs` says otherwise
+fn gate(away_grace_secs: u64) -> (PresenceGate<Fill>, Arc<AtomicBool>) {
+    let presence = Arc::new(AtomicBool::new(true));
+    let config = PresenceGateConfig {
+        away_grace_secs: Some(away_grace_secs),
+        ..Default::default()
+    };
+
+    (
+        PresenceGate::new(Fill::default(), vec![presence.clone()], config),
+        presence,
+    )
+}
+
+/// Renders `gate` and returns the color of its top left pixel
+fn drawn_color(gate: &PresenceGate<Fill>) -> Rgb888 {
+    let mut canvas = BufferCanvas::new(SIZE);
+    gate.render(&mut canvas).unwrap();
+
+    canvas.pixel(Point::zero()).unwrap()
+}
+
+#[test]
+fn presence_toggles_the_output() {
+    let (gate, presence) = gate(0);
+
+    assert_eq!(drawn_color(&gate), Rgb888::WHITE);
+
+    presence.store(false, Ordering::SeqCst);
+    assert_eq!(drawn_color(&gate), Rgb888::BLACK);
+    assert_eq!(
+        Render::<BufferCanvas>::background_color(&gate),
+        Some(Rgb888::BLACK)
+    );
+
+    presence.store(true, Ordering::SeqCst);
+    assert_eq!(drawn_color(&gate), Rgb888::WHITE);
+}
+
+#[test]
+fn blanking_deselects_the_render() {
+    let (gate, presence) = gate(0);
+    drawn_color(&gate);
+
+    presence.store(false, Ordering::SeqCst);
+    drawn_color(&gate);
+    drawn_color(&gate);
+    assert_eq!(gate.inner().deselects.load(Ordering::SeqCst), 1);
+
+    presence.store(true, Ordering::SeqCst);
+    drawn_color(&gate);
+    assert_eq!(gate.inner().selects.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn panel_stays_on_during_the_grace_period() {
+    let (gate, presence) = gate(60 * 60);
+
+    presence.store(false, Ordering::SeqCst);
+    assert_eq!(drawn_color(&gate), Rgb888::WHITE);
+}
+
+#[test]
+fn anyone_present_keeps_the_panel_on() {
+    let home = Arc::new(AtomicBool::new(true));
+    let away = Arc::new(AtomicBool::new(false));
+    let config = PresenceGateConfig {
+        away_grace_secs: Some(0),
+        ..Default::default()
+    };
+    let gate = PresenceGate::new(Fill::default(), vec![home.clone(), away], config);
+
+    assert_eq!(drawn_color(&gate), Rgb888::WHITE);
+
+    home.store(false, Ordering::SeqCst);
+    assert_eq!(drawn_color(&gate), Rgb888::BLACK);
+}
@@ -0,0 +1,47 @@
+//! Tests that a request to a slow endpoint fails once its timeout passes,
+//! rather than holding up the update loop.
+
+use anyhow::Result;
+use rustic_pixel_examples::request::{request_timeout, with_timeout, DEFAULT_REQUEST_TIMEOUT};
+use std::{future::pending, time::Duration};
+
+#[tokio::test(start_paused = true)]
+async fn request_that_never_responds_times_out() {
+    let start = tokio::time::Instant::now();
+
+    let error = with_timeout(Duration::from_secs(3), pending::<Result<()>>())
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.to_string(), "Request timed out after 3s");
+    assert!(start.elapsed() >= Duration::from_secs(3));
+}
+
+#[tokio::test(start_paused = true)]
+async fn request_within_the_timeout_succeeds() {
+    let request = async {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        Ok::<_, anyhow::Error>(42)
+    };
+
+    assert_eq!(
+        with_timeout(Duration::from_secs(3), request).await.unwrap(),
+        42
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn failed_request_keeps_its_error() {
+    let request = async { Err::<(), _>("connection refused") };
+
+    let error = with_timeout(Duration::from_secs(3), request)
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "connection refused");
+}
+
+#[test]
+fn default_timeout_is_used_when_none_is_configured() {
+    assert_eq!(request_timeout(None), DEFAULT_REQUEST_TIMEOUT);
+    assert_eq!(request_timeout(Some(30)), Duration::from_secs(30));
+}
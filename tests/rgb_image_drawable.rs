@@ -0,0 +1,67 @@
+//! Tests that decoded image buffers are drawn like the BMP assets, through
+//! `embedded_graphics::image::Image`.
+
+use embedded_graphics::{
+    image::{Image, ImageDrawableExt},
+    pixelcolor::Rgb888,
+    prelude::{OriginDimensions, Point, RgbColor, Size},
+    primitives::Rectangle,
+    Drawable,
+};
+use image::{Rgb, RgbImage};
+use rustic_pixel_display::render::BufferCanvas;
+use rustic_pixel_examples::assets::RgbImageDrawable;
+
+/// A 3x2 image whose red channel is the x and green channel the y of the pixel
+fn gradient() -> RgbImageDrawable {
+    RgbImageDrawable::new(RgbImage::from_fn(3, 2, |x, y| {
+        Rgb([x as u8 * 100, y as u8 * 100, 0])
+    }))
+}
+
+#[test]
+fn reports_the_size_of_the_image() {
+    assert_eq!(gradient().size(), Size::new(3, 2));
+}
+
+#[test]
+fn draws_into_a_buffer_canvas() {
+    let drawable = gradient();
+    let mut canvas = BufferCanvas::new(Size::new(8, 8));
+
+    Image::new(&drawable, Point::new(2, 4))
+        .draw(&mut canvas)
+        .unwrap();
+
+    assert_eq!(canvas.pixel(Point::new(2, 4)), Some(Rgb888::BLACK));
+    assert_eq!(
+        canvas.pixel(Point::new(4, 5)),
+        Some(Rgb888::new(200, 100, 0))
+    );
+
+    // Nothing is drawn outside of the image
+    assert_eq!(canvas.pixel(Point::new(5, 4)), Some(Rgb888::BLACK));
+    assert_eq!(canvas.pixel(Point::new(1, 4)), Some(Rgb888::BLACK));
+    assert_eq!(canvas.pixel(Point::new(2, 6)), Some(Rgb888::BLACK));
+}
+
+#[test]
+fn draws_a_sub_image() {
+    let drawable = gradient();
+    let sub_image = drawable.sub_image(&Rectangle::new(Point::new(1, 1), Size::new(2, 1)));
+    let mut canvas = BufferCanvas::new(Size::new(4, 4));
+
+    Image::new(&sub_image, Point::zero())
+        .draw(&mut canvas)
+        .unwrap();
+
+    assert_eq!(
+        canvas.pixel(Point::new(0, 0)),
+        Some(Rgb888::new(100, 100, 0))
+    );
+    assert_eq!(
+        canvas.pixel(Point::new(1, 0)),
+        Some(Rgb888::new(200, 100, 0))
+    );
+    assert_eq!(canvas.pixel(Point::new(0, 1)), Some(Rgb888::BLACK));
+}
@@ -0,0 +1,32 @@
+//! Tests the position of scrolling text at injected times.
+
+use rustic_pixel_examples::scroll::scroll_position;
+use std::time::Duration;
+
+#[test]
+fn text_that_fits_does_not_scroll() {
+    assert_eq!(scroll_position(40, 64, 10, Duration::from_secs(3)), 0);
+}
+
+#[test]
+fn text_scrolls_at_speed() {
+    assert_eq!(scroll_position(100, 64, 10, Duration::ZERO), 64);
+    assert_eq!(scroll_position(100, 64, 10, Duration::from_secs(2)), 44);
+    assert_eq!(
+        scroll_position(100, 64, 10, Duration::from_millis(2500)),
+        39
+    );
+}
+
+#[test]
+fn text_wraps_after_crossing_the_canvas() {
+    // The text travels its own width plus the canvas width before repeating
+    assert_eq!(
+        scroll_position(100, 64, 10, Duration::from_millis(16400)),
+        64
+    );
+    assert_eq!(
+        scroll_position(100, 64, 10, Duration::from_millis(16500)),
+        63
+    );
+}
@@ -0,0 +1,40 @@
+//! Tests that partial or unexpected statuses in SEPTA arrivals are shown as an
+//! unknown status rather than failing the train.
+
+use rustic_pixel_examples::renders::upcoming_arrivals::{parse_status, UpcomingTrainStatus};
+
+#[test]
+fn on_time() {
+    assert_eq!(parse_status("On Time"), UpcomingTrainStatus::OnTime);
+    assert_eq!(parse_status(" on time "), UpcomingTrainStatus::OnTime);
+    assert_eq!(parse_status("0 min"), UpcomingTrainStatus::OnTime);
+}
+
+#[test]
+fn minutes_late() {
+    assert_eq!(parse_status("1 min"), UpcomingTrainStatus::Late(1));
+    assert_eq!(parse_status("12 mins"), UpcomingTrainStatus::Late(12));
+    assert_eq!(parse_status("7"), UpcomingTrainStatus::Late(7));
+}
+
+#[test]
+fn missing_status_is_unknown() {
+    for status in ["", "   ", "N/A"] {
+        assert_eq!(
+            parse_status(status),
+            UpcomingTrainStatus::Unknown,
+            "\"{status}\""
+        );
+    }
+}
+
+#[test]
+fn unrecognized_status_is_unknown() {
+    for status in ["Suspended", "-3 min", "late", "5 hours"] {
+        assert_eq!(
+            parse_status(status),
+            UpcomingTrainStatus::Unknown,
+            "\"{status}\""
+        );
+    }
+}
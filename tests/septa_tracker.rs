@@ -0,0 +1,115 @@
+//! Tests how the transit tracker moves the person between having no status and
+//! being at a station as their location is updated.
+//!
+//! The tests run on a single threaded runtime, so the polling task spawned by
+//! the tracker never runs and only the updates made by the tests are seen.
+
+use rustic_pixel_display::font::latin1_lossy;
+use rustic_pixel_examples::{
+    renders::person_tracker::{DisplayTransitState, TransitTracker, TransitTrackerConfig},
+    stations::known_stations,
+};
+use septa_api::types::RegionalRailStop;
+use std::time::{Duration, Instant};
+
+/// Far away from any regional rail station
+const NOWHERE: (f64, f64) = (0.0, 0.0);
+
+fn tracker() -> TransitTracker {
+    TransitTracker::new(TransitTrackerConfig {
+        home_assistant_url: "http://localhost:8123".to_owned(),
+        home_assistant_bearer_token: "token".to_owned(),
+        person_entity_id: "person.test".to_owned(),
+        request_timeout_secs: None,
+        line_colors: None,
+        late_thresholds: None,
+    })
+    .unwrap()
+}
+
+fn station() -> (RegionalRailStop, (f64, f64)) {
+    let station = known_stations().next().unwrap();
+    let lat_lon = station.lat_lon().unwrap();
+
+    (station, lat_lon)
+}
+
+fn assert_no_status(state: DisplayTransitState) {
+    assert!(matches!(state, DisplayTransitState::NoStatus), "{state:?}");
+}
+
+fn assert_at_station(state: DisplayTransitState, station: &RegionalRailStop) {
+    match state {
+        DisplayTransitState::AtStation { station_name } => {
+            assert_eq!(station_name, latin1_lossy(&station.to_string()))
+        }
+        state => panic!("Expected to be at {station}, was {state:?}"),
+    }
+}
+
+#[tokio::test]
+async fn new_tracker_has_no_status() {
+    let tracker = tracker();
+
+    assert_no_status(tracker.current_state());
+    assert_eq!(tracker.current_location(), None);
+}
+
+#[tokio::test]
+async fn arrives_at_station_after_staying_near_it() {
+    let tracker = tracker();
+    let (station, lat_lon) = station();
+    let start = Instant::now();
+
+    // Only passing by the station
+    tracker.update(start, lat_lon, Vec::new()).unwrap();
+    assert_no_status(tracker.current_state());
+    assert_eq!(tracker.current_location(), Some(lat_lon));
+
+    tracker
+        .update(start + Duration::from_secs(31), lat_lon, Vec::new())
+        .unwrap();
+    assert_at_station(tracker.current_state(), &station);
+}
+
+#[tokio::test]
+async fn leaving_station_early_resets_the_wait() {
+    let tracker = tracker();
+    let (_, lat_lon) = station();
+    let start = Instant::now();
+
+    tracker.update(start, lat_lon, Vec::new()).unwrap();
+    tracker
+        .update(start + Duration::from_secs(20), NOWHERE, Vec::new())
+        .unwrap();
+    tracker
+        .update(start + Duration::from_secs(40), lat_lon, Vec::new())
+        .unwrap();
+
+    // Back near the station for less than the time it takes to arrive
+    assert_no_status(tracker.current_state());
+}
+
+#[tokio::test]
+async fn leaves_station_after_staying_away_from_it() {
+    let tracker = tracker();
+    let (station, lat_lon) = station();
+    let start = Instant::now();
+
+    tracker.update(start, lat_lon, Vec::new()).unwrap();
+    tracker
+        .update(start + Duration::from_secs(31), lat_lon, Vec::new())
+        .unwrap();
+
+    // Stepping away from the station is not enough to leave it
+    tracker
+        .update(start + Duration::from_secs(40), NOWHERE, Vec::new())
+        .unwrap();
+    assert_at_station(tracker.current_state(), &station);
+    assert_eq!(tracker.current_location(), Some(NOWHERE));
+
+    tracker
+        .update(start + Duration::from_secs(101), NOWHERE, Vec::new())
+        .unwrap();
+    assert_no_status(tracker.current_state());
+}
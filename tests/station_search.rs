@@ -0,0 +1,62 @@
+//! Tests the station search served on `GET /septa/stations?q=`.
+
+use rustic_pixel_display::push_routes;
+use rustic_pixel_examples::stations::{
+    register_station_search, search_stations, STATION_LOCATIONS, STATION_SEARCH_PATH,
+};
+
+#[test]
+fn partial_match_ignores_case() {
+    let stations = search_stations("SUBUR");
+
+    assert!(
+        stations
+            .iter()
+            .any(|station| station.name == "Suburban Station"),
+        "{stations:?}"
+    );
+    assert!(stations
+        .iter()
+        .all(|station| station.name.to_lowercase().contains("subur")));
+}
+
+#[test]
+fn empty_query_returns_every_station() {
+    let stations = search_stations("");
+
+    // Every station with a location
+    assert_eq!(stations.len(), STATION_LOCATIONS.len());
+}
+
+#[test]
+fn unmatched_query_returns_nothing() {
+    assert!(search_stations("Not a station").is_empty());
+}
+
+#[test]
+fn search_is_served_until_the_route_is_dropped() {
+    let route = register_station_search().unwrap();
+
+    let query = |q: &'static str| {
+        push_routes::get(STATION_SEARCH_PATH, &move |name| {
+            (name == "q").then(|| q.to_owned())
+        })
+    };
+
+    let body = query("subur").unwrap();
+    assert!(body
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|station| station["name"] == "Suburban Station"));
+
+    // Without a query every station is listed
+    let every_station = push_routes::get(STATION_SEARCH_PATH, &|_| None).unwrap();
+    assert_eq!(
+        every_station.as_array().unwrap().len(),
+        STATION_LOCATIONS.len()
+    );
+
+    drop(route);
+    assert!(query("subur").is_none());
+}
@@ -0,0 +1,39 @@
+//! Tests the cached station locations the transit tracker picks the current
+//! station from, and that the `Unknown` placeholder is left out of them.
+
+use rustic_pixel_examples::stations::{known_stations, station_location, STATION_LOCATIONS};
+use septa_api::types::RegionalRailStop;
+
+/// The `Unknown` placeholder, as it is made for a station the API doesn't know
+fn unknown_station() -> RegionalRailStop {
+    RegionalRailStop::Unknown("Nowhere".to_owned())
+}
+
+#[test]
+fn unknown_station_is_never_known() {
+    assert!(known_stations().all(|station| !matches!(station, RegionalRailStop::Unknown(_))));
+    assert!(!STATION_LOCATIONS.contains_key(&unknown_station()));
+}
+
+#[test]
+fn unknown_station_has_no_location() {
+    let station = unknown_station();
+    let error = station_location(&station).unwrap_err();
+
+    assert!(error.to_string().contains(&station.to_string()), "{error}");
+}
+
+#[test]
+fn cached_locations_match_lat_lon() {
+    // A sample of the known stations
+    for station in known_stations().step_by(5) {
+        let (lat, lon) = station.lat_lon().unwrap();
+        let location = station_location(&station).unwrap();
+
+        assert_eq!(
+            (location.latitude(), location.longitude()),
+            (lat, lon),
+            "{station}"
+        );
+    }
+}
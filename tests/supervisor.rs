@@ -0,0 +1,101 @@
+//! Tests that supervised update tasks are restarted when they stop.
+
+use anyhow::anyhow;
+use rustic_pixel_examples::supervisor::supervise;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio_util::sync::CancellationToken;
+
+const RESTART_DELAY: Duration = Duration::from_millis(10);
+
+/// Waits until `runs` reaches `expected`, failing the test if it takes too
+/// long
+async fn wait_for_runs(runs: &AtomicUsize, expected: usize) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while runs.load(Ordering::SeqCst) < expected {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("Only ran {} times", runs.load(Ordering::SeqCst)));
+}
+
+#[tokio::test]
+async fn panicking_task_is_restarted() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let cancel_token = CancellationToken::new();
+
+    let task_runs = runs.clone();
+    let task_cancel_token = cancel_token.clone();
+    let handle = supervise("Test", cancel_token.clone(), RESTART_DELAY, move || {
+        let runs = task_runs.clone();
+        let cancel_token = task_cancel_token.clone();
+
+        async move {
+            if runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                panic!("First iteration");
+            }
+
+            cancel_token.cancelled().await;
+            Ok(())
+        }
+    });
+
+    wait_for_runs(&runs, 2).await;
+
+    cancel_token.cancel();
+    handle.await.unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn failing_task_is_restarted() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let cancel_token = CancellationToken::new();
+
+    let task_runs = runs.clone();
+    let handle = supervise("Test", cancel_token.clone(), RESTART_DELAY, move || {
+        let runs = task_runs.clone();
+
+        async move {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow!("Failed"))
+        }
+    });
+
+    wait_for_runs(&runs, 3).await;
+
+    cancel_token.cancel();
+    handle.await.unwrap();
+}
+
+#[tokio::test]
+async fn cancelled_task_is_not_restarted() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let cancel_token = CancellationToken::new();
+
+    let task_runs = runs.clone();
+    let task_cancel_token = cancel_token.clone();
+    let handle = supervise("Test", cancel_token.clone(), RESTART_DELAY, move || {
+        let runs = task_runs.clone();
+        let cancel_token = task_cancel_token.clone();
+
+        async move {
+            runs.fetch_add(1, Ordering::SeqCst);
+            cancel_token.cancelled().await;
+            Ok(())
+        }
+    });
+
+    wait_for_runs(&runs, 1).await;
+    cancel_token.cancel();
+    handle.await.unwrap();
+
+    tokio::time::sleep(RESTART_DELAY * 5).await;
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,83 @@
+//! Tests of the colors the weather render draws temperatures in.
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::{RgbColor, WebColors},
+};
+use rustic_pixel_examples::renders::weather::{
+    TemperatureBreakpointConfig, TemperatureColors, TemperatureColorsConfig, TemperatureScaleMode,
+};
+
+fn temperature_colors(mode: TemperatureScaleMode) -> TemperatureColors {
+    TemperatureColors::try_from(&TemperatureColorsConfig {
+        mode,
+        breakpoints: vec![
+            TemperatureBreakpointConfig {
+                temp: 40.0,
+                color: "#0000ff".to_owned(),
+            },
+            TemperatureBreakpointConfig {
+                temp: 80.0,
+                color: "#ff0000".to_owned(),
+            },
+        ],
+    })
+    .unwrap()
+}
+
+#[test]
+fn interpolated_blends_between_breakpoints() {
+    let colors = temperature_colors(TemperatureScaleMode::Interpolated);
+
+    assert_eq!(colors.color_for(40.0), Rgb888::BLUE);
+    assert_eq!(colors.color_for(50.0), Rgb888::new(64, 0, 191));
+    assert_eq!(colors.color_for(60.0), Rgb888::new(128, 0, 128));
+    assert_eq!(colors.color_for(80.0), Rgb888::RED);
+}
+
+#[test]
+fn interpolated_is_clamped_outside_breakpoints() {
+    let colors = temperature_colors(TemperatureScaleMode::Interpolated);
+
+    assert_eq!(colors.color_for(-10.0), Rgb888::BLUE);
+    assert_eq!(colors.color_for(120.0), Rgb888::RED);
+}
+
+#[test]
+fn stepped_uses_the_next_breakpoint() {
+    let colors = temperature_colors(TemperatureScaleMode::Stepped);
+
+    assert_eq!(colors.color_for(40.0), Rgb888::BLUE);
+    assert_eq!(colors.color_for(40.5), Rgb888::RED);
+    assert_eq!(colors.color_for(120.0), Rgb888::RED);
+}
+
+#[test]
+fn default_matches_comfort_scale() {
+    let colors = TemperatureColors::default();
+
+    assert_eq!(colors.color_for(10.0), Rgb888::CSS_MAGENTA);
+    assert_eq!(colors.color_for(60.0), Rgb888::GREEN);
+    assert_eq!(colors.color_for(85.0), Rgb888::RED);
+    assert_eq!(colors.color_for(120.0), Rgb888::CSS_MAGENTA);
+    assert_eq!(colors.color_for(f32::NAN), Rgb888::WHITE);
+}
+
+#[test]
+fn unsorted_breakpoints_are_rejected() {
+    let config = TemperatureColorsConfig {
+        mode: TemperatureScaleMode::Stepped,
+        breakpoints: vec![
+            TemperatureBreakpointConfig {
+                temp: 80.0,
+                color: "#ff0000".to_owned(),
+            },
+            TemperatureBreakpointConfig {
+                temp: 40.0,
+                color: "#0000ff".to_owned(),
+            },
+        ],
+    };
+
+    assert!(TemperatureColors::try_from(&config).is_err());
+}
@@ -0,0 +1,166 @@
+//! Golden test of the arrivals board drawn for a fixed set of trains.
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+use embedded_graphics::{
+    image::Image,
+    mono_font::{iso_8859_1::FONT_5X7, iso_8859_1::FONT_9X15, MonoTextStyle},
+    pixelcolor::Rgb888,
+    prelude::{Point, RgbColor, Size},
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+    Drawable,
+};
+use embedded_layout::{
+    layout::linear::{spacing, LinearLayout},
+    prelude::{vertical, Chain},
+    View,
+};
+use rustic_pixel_display::render::{RecordingCanvas, Render};
+use rustic_pixel_examples::renders::upcoming_arrivals::{
+    UpcomingArrivals, UpcomingArrivalsConfig, UpcomingTrain, UpcomingTrainStatus,
+};
+use tinybmp::Bmp;
+
+const SIZE: Size = Size::new(256, 64);
+
+/// The left or right edge of each column of the golden board, as wide as its
+/// widest entry with 6 pixels between columns: the time, train id, direction,
+/// track, destination and status
+const COLUMNS: [(i32, Alignment); 6] = [
+    (25, Alignment::Right),
+    (31, Alignment::Left),
+    (57, Alignment::Left),
+    (68, Alignment::Left),
+    (99, Alignment::Left),
+    (210, Alignment::Right),
+];
+
+fn at(hour: u32, min: u32) -> DateTime<FixedOffset> {
+    FixedOffset::west_opt(5 * 60 * 60)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 10, hour, min, 0)
+        .unwrap()
+}
+
+fn draw_board(trains: Vec<UpcomingTrain>) -> RecordingCanvas {
+    let config: UpcomingArrivalsConfig =
+        serde_json::from_str(r#"{ "amtrak_station": "PHL" }"#).unwrap();
+    let board = UpcomingArrivals::with_arrivals(config, Vec::new(), trains).unwrap();
+
+    let mut canvas = RecordingCanvas::new(SIZE);
+    board.render(&mut canvas).unwrap();
+
+    canvas
+}
+
+/// The board as it is laid out with its default fonts and spacing, drawn
+/// independently of the render with each column at its edge in `columns`
+fn golden_board<const N: usize>(
+    columns: [(i32, Alignment); N],
+    rows: &[[&str; N]],
+) -> RecordingCanvas {
+    let mut canvas = RecordingCanvas::new(SIZE);
+
+    // The logo is left out if it can't be loaded, the same as the render does
+    let logo = Bmp::<Rgb888>::from_slice(include_bytes!("../assets/AMTRAK_16.bmp")).ok();
+    let title = Text::new(
+        "PHL",
+        Point::zero(),
+        MonoTextStyle::new(&FONT_9X15, Rgb888::WHITE),
+    );
+    let title_height = match &logo {
+        Some(logo) => {
+            let layout =
+                LinearLayout::horizontal(Chain::new(Image::new(logo, Point::zero())).append(title))
+                    .with_alignment(vertical::Center)
+                    .with_spacing(spacing::FixedMargin(2))
+                    .arrange();
+            layout.draw(&mut canvas).unwrap();
+            layout.bounds().size.height
+        }
+        None => {
+            let layout = LinearLayout::horizontal(Chain::new(title))
+                .with_alignment(vertical::Center)
+                .with_spacing(spacing::FixedMargin(2))
+                .arrange();
+            layout.draw(&mut canvas).unwrap();
+            layout.bounds().size.height
+        }
+    };
+
+    // Rows are as tall as the body font and 3 pixels apart
+    let mut middle = title_height as i32 + 2 + 3;
+    for row in rows {
+        for (text, (x, alignment)) in row.iter().zip(columns) {
+            Text::with_text_style(
+                text,
+                Point::new(x, middle),
+                MonoTextStyle::new(&FONT_5X7, Rgb888::WHITE),
+                TextStyleBuilder::new()
+                    .alignment(alignment)
+                    .baseline(Baseline::Middle)
+                    .build(),
+            )
+            .draw(&mut canvas)
+            .unwrap();
+        }
+
+        middle += 7 + 3;
+    }
+
+    canvas
+}
+
+#[test]
+fn board_with_fixed_trains() {
+    let canvas = draw_board(vec![
+        UpcomingTrain::new("641", "Harrisburg", at(9, 20), UpcomingTrainStatus::Late(5)),
+        UpcomingTrain::new("2150", "New York", at(9, 5), UpcomingTrainStatus::OnTime)
+            .with_track("3"),
+    ]);
+
+    // In the order the trains are scheduled
+    assert_eq!(
+        canvas.to_ascii_art(),
+        golden_board(
+            COLUMNS,
+            &[
+                [" 9:05", "2150", "D", "Trk 3", "New York", "On Time"],
+                [" 9:20", "641", "D", "", "Harrisburg", "5 mins late"],
+            ],
+        )
+        .to_ascii_art()
+    );
+}
+
+#[test]
+fn columns_fit_their_widest_entry() {
+    let canvas = draw_board(vec![
+        UpcomingTrain::new(
+            "641",
+            "Harrisburg",
+            at(9, 20),
+            UpcomingTrainStatus::Late(12),
+        ),
+        UpcomingTrain::new("2150", "New York", at(9, 5), UpcomingTrainStatus::OnTime),
+    ]);
+
+    // Without a track for any train the destination moves up next to the
+    // direction, and the longer status moves the right edge of its column
+    assert_eq!(
+        canvas.to_ascii_art(),
+        golden_board(
+            [
+                (25, Alignment::Right),
+                (31, Alignment::Left),
+                (57, Alignment::Left),
+                (68, Alignment::Left),
+                (184, Alignment::Right),
+            ],
+            &[
+                [" 9:05", "2150", "D", "New York", "On Time"],
+                [" 9:20", "641", "D", "Harrisburg", "12 mins late"],
+            ],
+        )
+        .to_ascii_art()
+    );
+}
@@ -0,0 +1,52 @@
+//! Tests that the arrivals board clamps its layout on a panel too short for
+//! its title instead of underflowing.
+
+use chrono::{FixedOffset, TimeZone};
+use embedded_graphics::{prelude::Size, primitives::Rectangle};
+use rustic_pixel_display::render::{DrawOperation, RecordingCanvas, Render};
+use rustic_pixel_examples::renders::upcoming_arrivals::{
+    UpcomingArrivals, UpcomingArrivalsConfig, UpcomingTrain, UpcomingTrainStatus,
+};
+
+/// Below the title, which is 15 pixels tall or 16 with the logo, and the
+/// spacing under it
+const FIRST_ROW_TOP: i32 = 17;
+
+fn board() -> UpcomingArrivals {
+    let departure = FixedOffset::west_opt(5 * 60 * 60)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 10, 9, 5, 0)
+        .unwrap();
+    let config: UpcomingArrivalsConfig =
+        serde_json::from_str(r#"{ "amtrak_station": "PHL" }"#).unwrap();
+
+    UpcomingArrivals::with_arrivals(
+        config,
+        Vec::new(),
+        vec![UpcomingTrain::new(
+            "2150",
+            "New York",
+            departure,
+            UpcomingTrainStatus::OnTime,
+        )],
+    )
+    .unwrap()
+}
+
+#[test]
+fn panel_shorter_than_the_title_draws_no_rows() {
+    for height in [0, 1, 8, 15] {
+        let mut canvas = RecordingCanvas::new(Size::new(256, height));
+        board().render(&mut canvas).unwrap();
+
+        for operation in canvas.operations() {
+            if let DrawOperation::DrawIter(bounds) = operation {
+                assert!(
+                    *bounds == Rectangle::zero()
+                        || bounds.top_left.y + (bounds.size.height as i32) <= FIRST_ROW_TOP,
+                    "{bounds:?} drawn on a panel {height} pixels tall"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Tests of how many upcoming arrivals are shown and in which order.
+
+use rustic_pixel_examples::renders::upcoming_arrivals::{
+    select_rows, ArrivalSort, UpcomingArrivalsConfig,
+};
+
+/// Trains in the order they are scheduled
+fn trains() -> Vec<&'static str> {
+    vec!["8:05", "8:20", "8:35", "8:50", "9:05"]
+}
+
+#[test]
+fn soonest_keeps_scheduled_order() {
+    assert_eq!(select_rows(trains(), ArrivalSort::Soonest, None), trains());
+}
+
+#[test]
+fn more_trains_than_max_rows_truncates() {
+    assert_eq!(
+        select_rows(trains(), ArrivalSort::Soonest, Some(3)),
+        vec!["8:05", "8:20", "8:35"]
+    );
+    assert_eq!(
+        select_rows(trains(), ArrivalSort::Soonest, Some(10)),
+        trains()
+    );
+}
+
+#[test]
+fn latest_reverses_order() {
+    assert_eq!(
+        select_rows(trains(), ArrivalSort::Latest, None),
+        vec!["9:05", "8:50", "8:35", "8:20", "8:05"]
+    );
+    assert_eq!(
+        select_rows(trains(), ArrivalSort::Latest, Some(2)),
+        vec!["9:05", "8:50"]
+    );
+}
+
+#[test]
+fn sort_and_max_rows_parse_from_config() {
+    let config: UpcomingArrivalsConfig =
+        serde_json::from_str(r#"{ "amtrak_station": "PHL", "max_rows": 4, "sort": "latest" }"#)
+            .unwrap();
+    assert_eq!(config.max_rows, Some(4));
+    assert_eq!(config.sort, ArrivalSort::Latest);
+
+    let config: UpcomingArrivalsConfig =
+        serde_json::from_str(r#"{ "amtrak_station": "PHL" }"#).unwrap();
+    assert_eq!(config.max_rows, None);
+    assert_eq!(config.sort, ArrivalSort::Soonest);
+}
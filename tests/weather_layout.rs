@@ -0,0 +1,83 @@
+//! Tests that `Weather` is laid out within its own region of a split layout,
+//! the same as it is on a canvas of the region's size.
+
+use embedded_graphics::{
+    prelude::{Dimensions, Point, Size},
+    primitives::PointsIter,
+};
+use rustic_pixel_display::{
+    layout_manager::{CommonLayout, LayoutManager},
+    render::{BufferCanvas, Render, SubCanvas, SyncRender},
+};
+use rustic_pixel_examples::{
+    data_source::PushSource,
+    renders::weather::{Configuration, Weather},
+};
+
+type SubRender = Box<dyn for<'a> SyncRender<SubCanvas<'a, BufferCanvas>>>;
+
+/// A weather render that is still waiting for its first forecast, so it draws
+/// its loading indicator centered on its canvas
+fn weather() -> Weather {
+    let config: Configuration = serde_json::from_str(r#"{ "api_key": "" }"#).unwrap();
+    Weather::with_source(config, Box::new(PushSource::default()))
+}
+
+fn boxed_weather() -> Option<SubRender> {
+    Some(Box::new(weather()))
+}
+
+/// Checks that every region of `layout`, each of `region_size`, is drawn the
+/// same as a weather render on a canvas of `region_size`
+fn assert_regions_match(layout: CommonLayout<BufferCanvas>, canvas_size: Size, region_size: Size) {
+    let layout_manager = LayoutManager::from_common_layout(layout, canvas_size);
+    let alone_weather = weather();
+
+    // The loading spinner may advance between the two drawings, so try again
+    // until both land on the same frame
+    for _ in 0..10 {
+        let mut alone = BufferCanvas::new(region_size);
+        alone_weather.render(&mut alone).unwrap();
+
+        let mut split = BufferCanvas::new(canvas_size);
+        layout_manager.render(&mut split).unwrap();
+
+        let matches = split.bounding_box().points().all(|point| {
+            let local = Point::new(
+                point.x % region_size.width as i32,
+                point.y % region_size.height as i32,
+            );
+            split.pixel(point) == alone.pixel(local)
+        });
+
+        if matches {
+            return;
+        }
+    }
+
+    panic!("Weather is not drawn the same within its region");
+}
+
+#[test]
+fn weather_in_a_split_width_layout() {
+    assert_regions_match(
+        CommonLayout::SplitWidth {
+            left: boxed_weather(),
+            right: boxed_weather(),
+        },
+        Size::new(128, 32),
+        Size::new(64, 32),
+    );
+}
+
+#[test]
+fn weather_in_a_split_height_layout() {
+    assert_regions_match(
+        CommonLayout::SplitHeight {
+            top: boxed_weather(),
+            bottom: boxed_weather(),
+        },
+        Size::new(64, 64),
+        Size::new(64, 32),
+    );
+}
@@ -0,0 +1,66 @@
+//! Tests that weather locations deserialize from objects tagged with their
+//! `type` and are turned into the matching forecast query.
+
+use rustic_pixel_examples::renders::weather::{Configuration, Location};
+use std::net::{IpAddr, Ipv4Addr};
+
+fn location(json: &str) -> Location {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn ip_without_address() {
+    let location = location(r#"{ "type": "ip" }"#);
+
+    assert!(matches!(location, Location::Ip(None)));
+    assert!(matches!(
+        weer_api::Query::from(location),
+        weer_api::Query::Ip(None)
+    ));
+}
+
+#[test]
+fn ip_with_address() {
+    let address = IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8));
+    let location = location(r#"{ "type": "ip", "value": "8.8.8.8" }"#);
+
+    assert!(matches!(location, Location::Ip(Some(ip)) if ip == address));
+    assert!(matches!(
+        weer_api::Query::from(location),
+        weer_api::Query::Ip(Some(ip)) if ip == address
+    ));
+}
+
+#[test]
+fn city() {
+    let location = location(r#"{ "type": "city", "value": "Philadelphia" }"#);
+
+    assert!(matches!(&location, Location::City(city) if city == "Philadelphia"));
+    assert!(matches!(
+        weer_api::Query::from(location),
+        weer_api::Query::City(city) if city == "Philadelphia"
+    ));
+}
+
+#[test]
+fn coords() {
+    let location = location(r#"{ "type": "coords", "lat": 39.9, "lon": -75.1 }"#);
+
+    assert!(matches!(location, Location::LatLon(lat, lon) if lat == 39.9 && lon == -75.1));
+    assert!(matches!(
+        weer_api::Query::from(location),
+        weer_api::Query::Coords(lat, lon) if lat == 39.9 && lon == -75.1
+    ));
+}
+
+#[test]
+fn unknown_type_is_rejected() {
+    assert!(serde_json::from_str::<Location>(r#"{ "type": "zip", "value": "19103" }"#).is_err());
+}
+
+#[test]
+fn config_defaults_to_ip() {
+    let config: Configuration = serde_json::from_str(r#"{ "api_key": "" }"#).unwrap();
+
+    assert!(matches!(config.location, Location::Ip(None)));
+}